@@ -0,0 +1,120 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Lets another thread start fading out a [`FadeOut`] source while it's playing.
+#[derive(Clone)]
+pub struct FadeHandle(Arc<AtomicU64>);
+
+impl FadeHandle {
+    /// Stored in the handle until a fade is requested.
+    const NOT_FADING: u64 = u64::MAX;
+
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicU64::new(Self::NOT_FADING)))
+    }
+
+    /// Starts fading the source out over `duration`. The source ends once the fade is over.
+    pub fn fade_out(&self, duration: std::time::Duration) {
+        let millis = (duration.as_millis() as u64).min(Self::NOT_FADING - 1);
+        self.0.store(millis, Ordering::Relaxed);
+    }
+}
+
+/// A source that can be faded out through a [`FadeHandle`].
+pub struct FadeOut<S> {
+    inner: S,
+    handle: FadeHandle,
+    /// The number of samples left in the fade, and the number of samples in the whole fade, once
+    /// it has started
+    fade: Option<(u64, u64)>,
+}
+
+impl<S> FadeOut<S> {
+    pub fn new(inner: S, handle: FadeHandle) -> Self {
+        Self {
+            inner,
+            handle,
+            fade: None,
+        }
+    }
+}
+
+impl<S> Iterator for FadeOut<S>
+where
+    S: rodio::Source,
+    S::Item: rodio::Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fade.is_none() {
+            let millis = self.handle.0.load(Ordering::Relaxed);
+            if millis != FadeHandle::NOT_FADING {
+                let samples_per_second =
+                    self.inner.sample_rate() as u64 * self.inner.channels() as u64;
+                let total = (millis * samples_per_second / 1000).max(1);
+                self.fade = Some((total, total));
+            }
+        }
+
+        let sample = self.inner.next()?;
+        match &mut self.fade {
+            None => Some(sample),
+            Some((0, _)) => None,
+            Some((remaining, total)) => {
+                *remaining -= 1;
+                Some(rodio::Sample::amplify(
+                    sample,
+                    *remaining as f32 / *total as f32,
+                ))
+            }
+        }
+    }
+}
+
+impl<S> rodio::Source for FadeOut<S>
+where
+    S: rodio::Source,
+    S::Item: rodio::Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        self.inner.total_duration()
+    }
+}
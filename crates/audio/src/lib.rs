@@ -22,9 +22,14 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::io::{Read, Seek};
+
 mod error;
+mod fade;
 mod midi;
+mod probe;
 pub use error::{Error, Result};
+pub use midi::SAMPLE_RATE as MIDI_SAMPLE_RATE;
 
 pub use luminol_config::VolumeScale;
 
@@ -58,3 +63,28 @@ impl Source {
         })
     }
 }
+
+/// Computes how long an audio file plays for. Only the headers (and for Ogg files, the last
+/// page) are read, so this is cheap even for long tracks.
+pub fn duration(mut file: impl Read + Seek + Send + Sync + 'static) -> Result<std::time::Duration> {
+    let mut magic_header_buf = [0u8; 4];
+    file.read_exact(&mut magic_header_buf)?;
+    file.seek(std::io::SeekFrom::Current(-4))?;
+    if &magic_header_buf == b"MThd" {
+        let midi_file = rustysynth::MidiFile::new(&mut file).map_err(Error::from)?;
+        return Ok(std::time::Duration::from_secs_f64(midi_file.get_length()));
+    }
+    if &magic_header_buf == b"OggS" {
+        return probe::ogg_duration(&mut file);
+    }
+    if magic_header_buf.starts_with(b"ID3")
+        || (magic_header_buf[0] == 0xff && magic_header_buf[1] & 0xe0 == 0xe0)
+    {
+        return probe::mp3_duration(&mut file);
+    }
+
+    // WAV and FLAC store their length up front
+    let decoder = rodio::Decoder::new(file).map_err(Error::from)?;
+    rodio::Source::total_duration(&decoder)
+        .ok_or_else(|| color_eyre::eyre::eyre!("Could not determine the length of the track"))
+}
@@ -19,34 +19,236 @@ use std::{io::Cursor, sync::Arc};
 
 use crate::Result;
 
+/// The sample rate MIDI files are synthesized at.
+pub const SAMPLE_RATE: u32 = 44100;
+
+/// Playback settings of a MIDI file that can be changed while it plays.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MidiSettings {
+    /// How fast the song plays, without changing its pitch
+    pub speed: f64,
+    /// Where looping songs jump back to, in samples from the start of the song. If this is
+    /// `None`, loop markers in the file (control change 111, as used by RPG Maker) are respected.
+    pub loop_start: Option<u64>,
+}
+
+impl Default for MidiSettings {
+    fn default() -> Self {
+        Self {
+            speed: 1.,
+            loop_start: None,
+        }
+    }
+}
+
 pub struct MidiSource {
     // These are each 4410 long
     left: Vec<f32>,
     right: Vec<f32>,
     sample_read_count: usize,
     sequencer: rustysynth::MidiFileSequencer,
+
+    data: Arc<[u8]>,
+    looping: bool,
+    settings: Arc<parking_lot::Mutex<MidiSettings>>,
+    /// The settings the sequencer is currently playing with
+    applied_settings: MidiSettings,
 }
 
 impl MidiSource {
-    pub fn new(mut file: impl std::io::Read, looping: bool) -> Result<Self> {
-        let midi_file = Arc::new(rustysynth::MidiFile::new(&mut file)?);
+    pub fn new(
+        mut file: impl std::io::Read,
+        looping: bool,
+        settings: Arc<parking_lot::Mutex<MidiSettings>>,
+    ) -> Result<Self> {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let data: Arc<[u8]> = data.into();
+
+        let applied_settings = *settings.lock();
+        let midi_file = Self::load(&data, applied_settings.loop_start)?;
 
-        let settings = rustysynth::SynthesizerSettings::new(44100);
-        let synthesizer = rustysynth::Synthesizer::new(&SOUND_FONT, &settings)?;
+        let synth_settings = rustysynth::SynthesizerSettings::new(SAMPLE_RATE as i32);
+        let synthesizer = rustysynth::Synthesizer::new(&SOUND_FONT, &synth_settings)?;
         let mut sequencer = rustysynth::MidiFileSequencer::new(synthesizer);
 
         sequencer.play(&midi_file, looping);
+        sequencer.set_speed(applied_settings.speed.max(0.));
 
-        Ok(Self::new_sequencer(sequencer))
-    }
-
-    pub fn new_sequencer(sequencer: rustysynth::MidiFileSequencer) -> Self {
-        Self {
+        Ok(Self {
             left: vec![0.; 4410],
             right: vec![0.; 4410],
             sample_read_count: 0,
             sequencer,
+
+            data,
+            looping,
+            settings,
+            applied_settings,
+        })
+    }
+
+    fn load(data: &[u8], loop_start: Option<u64>) -> Result<Arc<rustysynth::MidiFile>> {
+        let loop_type = match loop_start
+            .and_then(|sample| loop_tick(data, sample as f64 / SAMPLE_RATE as f64))
+        {
+            Some(tick) => rustysynth::MidiFileLoopType::LoopPoint(tick),
+            None => rustysynth::MidiFileLoopType::RpgMaker,
+        };
+        let midi_file =
+            rustysynth::MidiFile::new_with_loop_type(&mut Cursor::new(data), loop_type)?;
+        Ok(Arc::new(midi_file))
+    }
+
+    /// Picks up any settings that were changed since the last block was rendered.
+    fn apply_settings(&mut self) {
+        let settings = *self.settings.lock();
+        if settings == self.applied_settings {
+            return;
         }
+
+        if settings.loop_start != self.applied_settings.loop_start && self.looping {
+            // The sequencer can only jump to loop points that were set when the file was loaded,
+            // so the song has to start over to use a new one. The file loaded fine before, so this
+            // can't fail
+            if let Ok(midi_file) = Self::load(&self.data, settings.loop_start) {
+                self.sequencer.play(&midi_file, true);
+            }
+        }
+        self.sequencer.set_speed(settings.speed.max(0.));
+
+        self.applied_settings = settings;
+    }
+}
+
+/// Finds the tick that is `seconds` into a standard MIDI file, going by its tempo changes.
+///
+/// Returns `None` if the file can't be read or the song is over by then.
+fn loop_tick(data: &[u8], seconds: f64) -> Option<usize> {
+    let mut reader = ByteReader(data);
+    if reader.bytes(4)? != b"MThd" {
+        return None;
+    }
+    let header_len = reader.u32()? as usize;
+    let header = reader.bytes(header_len)?;
+    let track_count = u16::from_be_bytes([*header.get(2)?, *header.get(3)?]);
+    let division = u16::from_be_bytes([*header.get(4)?, *header.get(5)?]);
+
+    let mut tempo_changes = Vec::new();
+    let mut end_tick = 0;
+    for _ in 0..track_count {
+        let id = reader.bytes(4)?;
+        let len = reader.u32()? as usize;
+        let mut track = ByteReader(reader.bytes(len)?);
+        if id != b"MTrk" {
+            continue;
+        }
+
+        let mut tick = 0u64;
+        let mut running_status = 0;
+        while !track.0.is_empty() {
+            tick += track.var_len()? as u64;
+            let status = match track.u8()? {
+                0xFF => {
+                    let kind = track.u8()?;
+                    let len = track.var_len()? as usize;
+                    let data = track.bytes(len)?;
+                    if kind == 0x51 && len == 3 {
+                        let tempo = u32::from_be_bytes([0, data[0], data[1], data[2]]);
+                        tempo_changes.push((tick, tempo));
+                    } else if kind == 0x2F {
+                        break;
+                    }
+                    continue;
+                }
+                0xF0 | 0xF7 => {
+                    let len = track.var_len()? as usize;
+                    track.bytes(len)?;
+                    continue;
+                }
+                status if status >= 0x80 => {
+                    running_status = status;
+                    track.bytes(data_len(status))?;
+                    continue;
+                }
+                _ if running_status != 0 => running_status,
+                _ => return None,
+            };
+            // Running status, where the byte we read was the first data byte
+            track.bytes(data_len(status) - 1)?;
+        }
+        end_tick = end_tick.max(tick);
+    }
+
+    let tick = if division & 0x8000 != 0 {
+        // SMPTE timing, where ticks have a fixed length
+        let frames_per_second = -((division >> 8) as i8) as f64;
+        let ticks_per_frame = (division & 0xFF) as f64;
+        seconds * frames_per_second * ticks_per_frame
+    } else {
+        let ticks_per_beat = division as f64;
+        tempo_changes.sort_by_key(|&(tick, _)| tick);
+
+        let mut time = 0.;
+        let mut last_tick = 0;
+        // Songs play at 120 bpm until the first tempo change
+        let mut tempo = 500_000.;
+        for (tick, new_tempo) in tempo_changes {
+            let length = (tick - last_tick) as f64 * tempo / 1_000_000. / ticks_per_beat;
+            if time + length > seconds {
+                break;
+            }
+            time += length;
+            last_tick = tick;
+            tempo = new_tempo as f64;
+        }
+        last_tick as f64 + (seconds - time) * ticks_per_beat * 1_000_000. / tempo
+    };
+
+    let tick = tick.round() as u64;
+    (tick < end_tick).then_some(tick as usize)
+}
+
+/// How many data bytes a channel message with this status byte has.
+fn data_len(status: u8) -> usize {
+    match status & 0xF0 {
+        0xC0 | 0xD0 => 1,
+        _ => 2,
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a variable-length quantity, which stores 7 bits per byte.
+    fn var_len(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
     }
 }
 
@@ -76,6 +278,7 @@ impl Iterator for MidiSource {
         self.sample_read_count += 1;
         if self.sample_read_count >= 4410 * 2 {
             self.sample_read_count = 0;
+            self.apply_settings();
             self.sequencer.render(&mut self.left, &mut self.right);
         }
 
@@ -93,7 +296,7 @@ impl rodio::Source for MidiSource {
     }
 
     fn sample_rate(&self) -> u32 {
-        44100
+        SAMPLE_RATE
     }
 
     fn total_duration(&self) -> Option<std::time::Duration> {
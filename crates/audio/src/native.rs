@@ -24,7 +24,11 @@
 
 use std::io::{Read, Seek};
 
-use crate::{midi, Result, Source, VolumeScale};
+use crate::{
+    fade::{FadeHandle, FadeOut},
+    midi::{self, MidiSettings},
+    Result, Source, VolumeScale,
+};
 
 /// A struct for playing Audio.
 pub struct Audio {
@@ -34,6 +38,11 @@ pub struct Audio {
 struct Inner {
     output_stream_handle: rodio::OutputStreamHandle,
     sinks: std::collections::HashMap<Source, rodio::Sink>,
+    /// Handles for fading out the sound playing on each source
+    fades: std::collections::HashMap<Source, FadeHandle>,
+    /// Settings of the MIDI file playing on each source
+    midi_settings:
+        std::collections::HashMap<Source, std::sync::Arc<parking_lot::Mutex<MidiSettings>>>,
 }
 
 impl Default for Audio {
@@ -49,6 +58,8 @@ impl Default for Audio {
             inner: parking_lot::Mutex::new(Inner {
                 output_stream_handle,
                 sinks: std::collections::HashMap::default(),
+                fades: std::collections::HashMap::default(),
+                midi_settings: std::collections::HashMap::default(),
             }),
         }
     }
@@ -96,6 +107,53 @@ impl Audio {
         self.play_from_file(file, volume, pitch, source, scale)
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Play a sound on a source once, without looping, even on sources that normally loop.
+    ///
+    /// If `crossfade` isn't zero, the new sound fades in over that long while the sound that was
+    /// playing on the source fades out. Use [`Self::is_playing`] to find out when it's over.
+    #[allow(clippy::too_many_arguments)]
+    pub fn play_track<T>(
+        &self,
+        path: impl AsRef<camino::Utf8Path>,
+        filesystem: &T,
+        volume: u8,
+        pitch: u8,
+        source: Source,
+        scale: VolumeScale,
+        crossfade: std::time::Duration,
+    ) -> Result<()>
+    where
+        T: luminol_filesystem::FileSystem,
+        T::File: 'static,
+    {
+        let path = path.as_ref();
+        let file = filesystem.open_file(path, luminol_filesystem::OpenFlags::Read)?;
+
+        self.play_from_file_with(file, volume, pitch, Some(source), scale, false, crossfade)
+    }
+
+    /// Like [`Self::play_track`], but from audio file data.
+    pub fn play_track_from_slice(
+        &self,
+        slice: impl AsRef<[u8]> + Send + Sync + 'static,
+        volume: u8,
+        pitch: u8,
+        source: Source,
+        scale: VolumeScale,
+        crossfade: std::time::Duration,
+    ) -> Result<()> {
+        self.play_from_file_with(
+            std::io::Cursor::new(slice),
+            volume,
+            pitch,
+            Some(source),
+            scale,
+            false,
+            crossfade,
+        )
+    }
+
     /// Play a sound on a source from audio file data.
     pub fn play_from_slice(
         &self,
@@ -109,12 +167,36 @@ impl Audio {
     }
 
     fn play_from_file(
+        &self,
+        file: impl Read + Seek + Send + Sync + 'static,
+        volume: u8,
+        pitch: u8,
+        source: Option<Source>,
+        scale: VolumeScale,
+    ) -> Result<()> {
+        // BGM and BGS loop, everything else plays once
+        let looping = matches!(source, Some(Source::BGM | Source::BGS));
+        self.play_from_file_with(
+            file,
+            volume,
+            pitch,
+            source,
+            scale,
+            looping,
+            std::time::Duration::ZERO,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn play_from_file_with(
         &self,
         mut file: impl Read + Seek + Send + Sync + 'static,
         volume: u8,
         pitch: u8,
         source: Option<Source>,
         scale: VolumeScale,
+        looping: bool,
+        crossfade: std::time::Duration,
     ) -> Result<()> {
         let mut magic_header_buf = [0u8; 4];
         file.read_exact(&mut magic_header_buf)?;
@@ -125,24 +207,28 @@ impl Audio {
         // Create a sink
         let sink = rodio::Sink::try_new(&inner.output_stream_handle)?;
 
-        // Select decoder type based on sound source
-        match source {
-            None | Some(Source::SE | Source::ME) => {
-                // Non looping
-                if is_midi {
-                    sink.append(midi::MidiSource::new(file, false)?);
-                } else {
-                    sink.append(rodio::Decoder::new(file)?);
-                }
-            }
-            _ => {
-                // Looping
-                if is_midi {
-                    sink.append(midi::MidiSource::new(file, true)?);
-                } else {
-                    sink.append(rodio::Decoder::new_looped(file)?);
-                }
-            }
+        let fade = FadeHandle::new();
+        let midi_settings = std::sync::Arc::new(parking_lot::Mutex::new(MidiSettings::default()));
+
+        // Select decoder type based on whether the sound loops
+        if is_midi {
+            let midi = midi::MidiSource::new(file, looping, midi_settings.clone())?;
+            sink.append(FadeOut::new(
+                rodio::Source::fade_in(midi, crossfade),
+                fade.clone(),
+            ));
+        } else if looping {
+            let decoder = rodio::Decoder::new_looped(file)?;
+            sink.append(FadeOut::new(
+                rodio::Source::fade_in(decoder, crossfade),
+                fade.clone(),
+            ));
+        } else {
+            let decoder = rodio::Decoder::new(file)?;
+            sink.append(FadeOut::new(
+                rodio::Source::fade_in(decoder, crossfade),
+                fade.clone(),
+            ));
         }
 
         // Set pitch and volume
@@ -152,11 +238,27 @@ impl Audio {
         sink.play();
 
         if let Some(source) = source {
+            if is_midi {
+                inner.midi_settings.insert(source, midi_settings);
+            } else {
+                inner.midi_settings.remove(&source);
+            }
+            let old_fade = inner.fades.insert(source, fade);
             // Add sink to hash, stop the current one if it's there.
             if let Some(s) = inner.sinks.insert(source, sink) {
-                s.stop();
-                #[cfg(not(target_arch = "wasm32"))]
-                s.sleep_until_end(); // wait for the sink to stop, there is a ~5ms delay where it will not
+                match old_fade {
+                    Some(old_fade) if !crossfade.is_zero() => {
+                        // Let the old sound fade out underneath the new one; the sink stops by
+                        // itself once the fade is over
+                        old_fade.fade_out(crossfade);
+                        s.detach();
+                    }
+                    _ => {
+                        s.stop();
+                        #[cfg(not(target_arch = "wasm32"))]
+                        s.sleep_until_end(); // wait for the sink to stop, there is a ~5ms delay where it will not
+                    }
+                }
             };
         } else {
             sink.detach();
@@ -173,6 +275,29 @@ impl Audio {
         }
     }
 
+    /// Set how fast the MIDI file playing on a source plays, as a multiple of its normal tempo.
+    /// Unlike the pitch, this doesn't change how high the notes sound.
+    ///
+    /// This does nothing if the source isn't playing a MIDI file.
+    pub fn set_midi_speed(&self, speed: f64, source: Source) {
+        let inner = self.inner.lock();
+        if let Some(settings) = inner.midi_settings.get(&source) {
+            settings.lock().speed = speed;
+        }
+    }
+
+    /// Set where the MIDI file playing on a source jumps back to when it loops, in samples at
+    /// [`crate::MIDI_SAMPLE_RATE`] from the start of the song, or `None` to use the loop marker in
+    /// the file. Changing the loop start makes the song start over.
+    ///
+    /// This does nothing if the source isn't playing a MIDI file.
+    pub fn set_midi_loop_start(&self, loop_start: Option<u64>, source: Source) {
+        let inner = self.inner.lock();
+        if let Some(settings) = inner.midi_settings.get(&source) {
+            settings.lock().loop_start = loop_start;
+        }
+    }
+
     /// Set the volume of a source.
     pub fn set_volume(&self, volume: u8, source: Source, scale: VolumeScale) {
         let mut inner = self.inner.lock();
@@ -190,6 +315,15 @@ impl Audio {
             sink.sleep_until_end();
         }
         inner.sinks.clear();
+        inner.fades.clear();
+        inner.midi_settings.clear();
+    }
+
+    /// Returns whether a sound is still playing on a source, i.e. a sound was played on it and
+    /// hasn't ended or been stopped.
+    pub fn is_playing(&self, source: Source) -> bool {
+        let inner = self.inner.lock();
+        inner.sinks.get(&source).is_some_and(|s| !s.empty())
     }
 
     /// Stop a source.
@@ -0,0 +1,181 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Finding out how long Ogg and MP3 files play for without decoding them.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use color_eyre::eyre::eyre;
+
+/// How much of the start or end of a file is read to look for headers.
+const PROBE_SIZE: u64 = 64 * 1024;
+
+/// Bitrates in kbit/s of MPEG-1 Layer III frames, by bitrate index.
+const MPEG1_LAYER3_BITRATES: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+/// Bitrates in kbit/s of MPEG-2 and MPEG-2.5 Layer III frames, by bitrate index.
+const MPEG2_LAYER3_BITRATES: [u32; 15] =
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+fn read_at(file: &mut (impl Read + Seek), position: SeekFrom) -> std::io::Result<Vec<u8>> {
+    file.seek(position)?;
+    let mut buf = Vec::new();
+    file.take(PROBE_SIZE).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn find_all<'a>(
+    haystack: &'a [u8],
+    needle: &'a [u8],
+) -> impl DoubleEndedIterator<Item = usize> + 'a {
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .filter(move |(_, window)| *window == needle)
+        .map(|(i, _)| i)
+}
+
+/// Computes the length of an Ogg Vorbis or Ogg Opus file from the sample rate in its first page and
+/// the granule position of its last page.
+pub fn ogg_duration(file: &mut (impl Read + Seek)) -> crate::Result<std::time::Duration> {
+    let head = read_at(file, SeekFrom::Start(0))?;
+    let (sample_rate, pre_skip) = if let Some(i) = find_all(&head, b"\x01vorbis").next() {
+        // The identification header has a 4 byte version and 1 byte channel count before the rate
+        let rate = head
+            .get(i + 12..i + 16)
+            .ok_or_else(|| eyre!("Truncated Vorbis identification header"))?;
+        (u32::from_le_bytes(rate.try_into().unwrap()), 0)
+    } else if let Some(i) = find_all(&head, b"OpusHead").next() {
+        // Opus granule positions always count samples at 48 kHz, minus the pre-skip
+        let pre_skip = head
+            .get(i + 10..i + 12)
+            .ok_or_else(|| eyre!("Truncated Opus identification header"))?;
+        (
+            48000,
+            u16::from_le_bytes(pre_skip.try_into().unwrap()) as u64,
+        )
+    } else {
+        return Err(eyre!("Ogg file is neither Vorbis nor Opus"));
+    };
+    if sample_rate == 0 {
+        return Err(eyre!("Ogg file has a sample rate of 0"));
+    }
+
+    let len = file.seek(SeekFrom::End(0))?;
+    let tail = read_at(file, SeekFrom::Start(len.saturating_sub(PROBE_SIZE)))?;
+    // A granule position of -1 means no packet ends on that page, so use the page before it
+    let granule_position = find_all(&tail, b"OggS")
+        .rev()
+        .filter_map(|i| tail.get(i + 6..i + 14))
+        .map(|granule| u64::from_le_bytes(granule.try_into().unwrap()))
+        .find(|&granule| granule != u64::MAX)
+        .ok_or_else(|| eyre!("No Ogg page with a granule position found"))?;
+
+    Ok(std::time::Duration::from_secs_f64(
+        granule_position.saturating_sub(pre_skip) as f64 / sample_rate as f64,
+    ))
+}
+
+/// Estimates the length of an MP3 file from the size of the file and the bitrate of its first
+/// frame. This is exact for constant bitrate files and an approximation otherwise.
+pub fn mp3_duration(file: &mut (impl Read + Seek)) -> crate::Result<std::time::Duration> {
+    let len = file.seek(SeekFrom::End(0))?;
+
+    // Skip the ID3v2 tag if there is one. Its size is stored as a 28 bit "syncsafe" integer.
+    let head = read_at(file, SeekFrom::Start(0))?;
+    let mut start = 0;
+    if head.starts_with(b"ID3") && head.len() >= 10 {
+        let size = head[6..10]
+            .iter()
+            .fold(0u64, |size, &byte| (size << 7) | (byte & 0x7f) as u64);
+        start = 10 + size;
+    }
+
+    let head = read_at(file, SeekFrom::Start(start))?;
+    let (offset, bitrate) = head
+        .windows(3)
+        .enumerate()
+        .find_map(|(i, header)| {
+            // Frame sync, then version and layer bits. Layer III is 0b01
+            if header[0] != 0xff || header[1] & 0xe0 != 0xe0 || (header[1] >> 1) & 0b11 != 0b01 {
+                return None;
+            }
+            let bitrates = match (header[1] >> 3) & 0b11 {
+                0b11 => &MPEG1_LAYER3_BITRATES,
+                0b10 | 0b00 => &MPEG2_LAYER3_BITRATES,
+                _ => return None,
+            };
+            let bitrate = *bitrates.get((header[2] >> 4) as usize)?;
+            (bitrate != 0).then_some((i as u64, bitrate))
+        })
+        .ok_or_else(|| eyre!("No MPEG Layer III frame found"))?;
+
+    let audio_bytes = len.saturating_sub(start + offset);
+    Ok(std::time::Duration::from_secs_f64(
+        audio_bytes as f64 * 8. / (bitrate as f64 * 1000.),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ogg_page(granule_position: u64, payload: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS\0\0".to_vec();
+        page.extend_from_slice(&granule_position.to_le_bytes());
+        page.extend_from_slice(&[0; 13]);
+        page.extend_from_slice(payload);
+        page
+    }
+
+    #[test]
+    fn ogg_vorbis_duration() {
+        let mut identification = b"\x01vorbis".to_vec();
+        identification.extend_from_slice(&0u32.to_le_bytes());
+        identification.push(2);
+        identification.extend_from_slice(&44100u32.to_le_bytes());
+
+        let mut file = ogg_page(0, &identification);
+        file.extend(ogg_page(44100, &[0; 32]));
+        file.extend(ogg_page(44100 * 3, &[0; 32]));
+        file.extend(ogg_page(u64::MAX, &[0; 32]));
+
+        let duration = ogg_duration(&mut std::io::Cursor::new(file)).unwrap();
+        assert_eq!(duration, std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    fn mp3_duration_skips_id3_tag() {
+        // A 10 byte ID3v2 header announcing a 100 byte tag
+        let mut file = b"ID3\x04\0\0\0\0\0\x64".to_vec();
+        file.extend_from_slice(&[0; 100]);
+        // MPEG-1 Layer III, 128 kbit/s
+        file.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+        file.resize(file.len() + 16000 - 4, 0);
+
+        let duration = mp3_duration(&mut std::io::Cursor::new(file)).unwrap();
+        assert_eq!(duration, std::time::Duration::from_secs(1));
+    }
+}
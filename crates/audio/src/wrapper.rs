@@ -38,11 +38,34 @@ enum Command {
         scale: VolumeScale,
         oneshot_tx: oneshot::Sender<Result<()>>,
     },
+    PlayTrack {
+        slice: std::sync::Arc<[u8]>,
+        volume: u8,
+        pitch: u8,
+        source: Source,
+        scale: VolumeScale,
+        crossfade: std::time::Duration,
+        oneshot_tx: oneshot::Sender<Result<()>>,
+    },
+    IsPlaying {
+        source: Source,
+        oneshot_tx: oneshot::Sender<bool>,
+    },
     SetPitch {
         pitch: u8,
         source: Source,
         oneshot_tx: oneshot::Sender<()>,
     },
+    SetMidiSpeed {
+        speed: f64,
+        source: Source,
+        oneshot_tx: oneshot::Sender<()>,
+    },
+    SetMidiLoopStart {
+        loop_start: Option<u64>,
+        source: Source,
+        oneshot_tx: oneshot::Sender<()>,
+    },
     SetVolume {
         volume: u8,
         source: Source,
@@ -106,6 +129,62 @@ impl Audio {
         oneshot_rx.recv().unwrap()
     }
 
+    /// Play a sound on a source once, without looping, even on sources that normally loop.
+    ///
+    /// If `crossfade` isn't zero, the new sound fades in over that long while the sound that was
+    /// playing on the source fades out. Use [`Self::is_playing`] to find out when it's over.
+    #[allow(clippy::too_many_arguments)]
+    pub fn play_track(
+        &self,
+        path: impl AsRef<camino::Utf8Path>,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        volume: u8,
+        pitch: u8,
+        source: Source,
+        scale: VolumeScale,
+        crossfade: std::time::Duration,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let slice: std::sync::Arc<[u8]> = filesystem.read(path)?.into();
+
+        self.play_track_from_slice(slice, volume, pitch, source, scale, crossfade)
+    }
+
+    /// Like [`Self::play_track`], but from audio file data.
+    pub fn play_track_from_slice(
+        &self,
+        slice: impl AsRef<[u8]> + Send + Sync + 'static,
+        volume: u8,
+        pitch: u8,
+        source: Source,
+        scale: VolumeScale,
+        crossfade: std::time::Duration,
+    ) -> Result<()> {
+        let (oneshot_tx, oneshot_rx) = oneshot::channel();
+        self.tx
+            .send(Command::PlayTrack {
+                slice: slice.as_ref().into(),
+                volume,
+                pitch,
+                source,
+                scale,
+                crossfade,
+                oneshot_tx,
+            })
+            .unwrap();
+        oneshot_rx.recv().unwrap()
+    }
+
+    /// Returns whether a sound is still playing on a source, i.e. a sound was played on it and
+    /// hasn't ended or been stopped.
+    pub fn is_playing(&self, source: Source) -> bool {
+        let (oneshot_tx, oneshot_rx) = oneshot::channel();
+        self.tx
+            .send(Command::IsPlaying { source, oneshot_tx })
+            .unwrap();
+        oneshot_rx.recv().unwrap()
+    }
+
     /// Set the pitch of a source.
     pub fn set_pitch(&self, pitch: u8, source: Source) {
         let (oneshot_tx, oneshot_rx) = oneshot::channel();
@@ -119,6 +198,39 @@ impl Audio {
         oneshot_rx.recv().unwrap()
     }
 
+    /// Set how fast the MIDI file playing on a source plays, as a multiple of its normal tempo.
+    /// Unlike the pitch, this doesn't change how high the notes sound.
+    ///
+    /// This does nothing if the source isn't playing a MIDI file.
+    pub fn set_midi_speed(&self, speed: f64, source: Source) {
+        let (oneshot_tx, oneshot_rx) = oneshot::channel();
+        self.tx
+            .send(Command::SetMidiSpeed {
+                speed,
+                source,
+                oneshot_tx,
+            })
+            .unwrap();
+        oneshot_rx.recv().unwrap()
+    }
+
+    /// Set where the MIDI file playing on a source jumps back to when it loops, in samples at
+    /// [`crate::MIDI_SAMPLE_RATE`] from the start of the song, or `None` to use the loop marker in
+    /// the file. Changing the loop start makes the song start over.
+    ///
+    /// This does nothing if the source isn't playing a MIDI file.
+    pub fn set_midi_loop_start(&self, loop_start: Option<u64>, source: Source) {
+        let (oneshot_tx, oneshot_rx) = oneshot::channel();
+        self.tx
+            .send(Command::SetMidiLoopStart {
+                loop_start,
+                source,
+                oneshot_tx,
+            })
+            .unwrap();
+        oneshot_rx.recv().unwrap()
+    }
+
     /// Set the volume of a source.
     pub fn set_volume(&self, volume: u8, source: Source, scale: VolumeScale) {
         let (oneshot_tx, oneshot_rx) = oneshot::channel();
@@ -184,6 +296,26 @@ impl Default for Audio {
                             .unwrap();
                     }
 
+                    Command::PlayTrack {
+                        slice,
+                        volume,
+                        pitch,
+                        source,
+                        scale,
+                        crossfade,
+                        oneshot_tx,
+                    } => {
+                        oneshot_tx
+                            .send(audio.play_track_from_slice(
+                                slice, volume, pitch, source, scale, crossfade,
+                            ))
+                            .unwrap();
+                    }
+
+                    Command::IsPlaying { source, oneshot_tx } => {
+                        oneshot_tx.send(audio.is_playing(source)).unwrap();
+                    }
+
                     Command::SetPitch {
                         pitch,
                         source,
@@ -193,6 +325,24 @@ impl Default for Audio {
                         oneshot_tx.send(()).unwrap();
                     }
 
+                    Command::SetMidiSpeed {
+                        speed,
+                        source,
+                        oneshot_tx,
+                    } => {
+                        audio.set_midi_speed(speed, source);
+                        oneshot_tx.send(()).unwrap();
+                    }
+
+                    Command::SetMidiLoopStart {
+                        loop_start,
+                        source,
+                        oneshot_tx,
+                    } => {
+                        audio.set_midi_loop_start(loop_start, source);
+                        oneshot_tx.send(()).unwrap();
+                    }
+
                     Command::SetVolume {
                         volume,
                         source,
@@ -0,0 +1,47 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Named map positions the user wants to be able to jump back to.
+///
+/// This is editor-only state, stored in `.luminol/bookmarks` rather than in the game data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Bookmarks {
+    /// Bookmarks for each map, keyed by map ID.
+    pub maps: BTreeMap<usize, Vec<Bookmark>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Bookmarks {
+    /// Path of the bookmarks file relative to the project root.
+    pub const PATH: &'static str = ".luminol/bookmarks";
+}
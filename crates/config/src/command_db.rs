@@ -24,6 +24,7 @@
 
 use luminol_data::commands::CommandDescription;
 use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -54,6 +55,50 @@ pub struct CommandDB {
     /// User defined commands
     // FIXME: visible to user?
     pub user: Vec<CommandDescription>,
+    /// The colors commands are tinted with in the command list, by category
+    #[serde(default = "default_category_colors")]
+    pub category_colors: BTreeMap<String, egui::Color32>,
+}
+
+/// Tints loosely based on the text colors RPG Maker XP uses in its command list.
+fn default_category_colors() -> BTreeMap<String, egui::Color32> {
+    [
+        (
+            "Message",
+            egui::Color32::from_rgba_unmultiplied(128, 128, 128, 24),
+        ),
+        (
+            "Flow Control",
+            egui::Color32::from_rgba_unmultiplied(64, 128, 255, 32),
+        ),
+        (
+            "Game Progression",
+            egui::Color32::from_rgba_unmultiplied(255, 64, 64, 32),
+        ),
+        (
+            "Movement",
+            egui::Color32::from_rgba_unmultiplied(192, 64, 255, 32),
+        ),
+        (
+            "Screen",
+            egui::Color32::from_rgba_unmultiplied(255, 160, 0, 32),
+        ),
+        (
+            "Audio",
+            egui::Color32::from_rgba_unmultiplied(0, 192, 192, 32),
+        ),
+        (
+            "Comment",
+            egui::Color32::from_rgba_unmultiplied(0, 192, 0, 32),
+        ),
+        (
+            "Script",
+            egui::Color32::from_rgba_unmultiplied(160, 160, 160, 32),
+        ),
+    ]
+    .into_iter()
+    .map(|(category, color)| (category.to_string(), color))
+    .collect()
 }
 
 impl CommandDB {
@@ -66,6 +111,7 @@ impl CommandDB {
             }
             .clone(),
             user: vec![],
+            category_colors: default_category_colors(),
         }
     }
 
@@ -76,6 +122,12 @@ impl CommandDB {
             .or_else(|| self.default.iter().find(|c| c.code == code))
     }
 
+    /// Returns the color the command with this code is tinted with in the command list, if any.
+    pub fn category_color(&self, code: u16) -> Option<egui::Color32> {
+        let category = self.get(code)?.category.as_ref()?;
+        self.category_colors.get(category).copied()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &CommandDescription> {
         self.default.iter().chain(self.user.iter())
     }
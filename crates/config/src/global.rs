@@ -25,7 +25,8 @@
 #[cfg(not(target_arch = "wasm32"))]
 use crate::terminal;
 use crate::CodeTheme;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
+use strum::IntoEnumIterator;
 
 /// The state saved by Luminol between sessions.
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
@@ -44,6 +45,205 @@ pub struct Config {
     pub theme: CodeTheme,
     #[cfg(not(target_arch = "wasm32"))]
     pub rtp_paths: indexmap::IndexMap<String, String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Programs to open files with from the filesystem view, keyed by lowercase file extension
+    /// without the dot.
+    pub external_programs: indexmap::IndexMap<String, String>,
+
+    /// Whether to ask for confirmation before performing destructive actions.
+    pub confirm_destructive_actions: bool,
+    /// Destructive actions the user chose not to be asked about again.
+    pub skipped_confirmations: BTreeSet<DestructiveAction>,
+
+    /// What is drawn behind the transparent parts of image previews.
+    pub preview_background: PreviewBackground,
+
+    /// The colors used for map overlays and selections.
+    pub overlay_palette: OverlayPalette,
+
+    /// How many changes to a map can be undone.
+    pub map_history_size: usize,
+    /// The gesture that creates an event on an empty tile of the map.
+    pub event_creation: EventCreation,
+    /// The most seconds between the two clicks of a double-click that creates an event.
+    pub event_double_click_time: f64,
+
+    /// How many seconds after the first unsaved change the project is saved automatically, or
+    /// `None` if it isn't.
+    pub autosave_interval_secs: Option<u32>,
+}
+
+/// The gesture that creates an event on an empty tile of the map's event layer. The right-click
+/// menu of the map can create events with any of them.
+///
+/// These are serialized by name in the global config, so variants shouldn't be renamed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum EventCreation {
+    /// Clicking the same tile twice, however much the mouse moves in between, or pressing Enter
+    #[default]
+    #[strum(to_string = "Double-click or Enter")]
+    DoubleClick,
+    #[strum(to_string = "Enter only")]
+    EnterOnly,
+    #[strum(to_string = "Right-click menu only")]
+    ContextMenu,
+}
+
+/// The background drawn behind image previews, so their transparency can be seen.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PreviewBackground {
+    #[default]
+    Checkerboard,
+    /// Black, which is what shows through the transparent parts of a map in game when it has no
+    /// panorama.
+    Map,
+    Custom(egui::Color32),
+}
+
+/// Sets of colors for map overlays and selections. The presets other than the default one avoid
+/// color pairs that are hard to tell apart with color vision deficiencies.
+///
+/// These are serialized by name in the global config, so variants shouldn't be renamed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum OverlayPalette {
+    #[default]
+    Default,
+    #[strum(to_string = "Deuteranopia-safe")]
+    DeuteranopiaSafe,
+    #[strum(to_string = "High contrast")]
+    HighContrast,
+}
+
+/// The colors of an [`OverlayPalette`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OverlayColors {
+    /// Fill of the collision overlay.
+    pub collision: egui::Color32,
+    /// The line in the middle of each grid line.
+    pub grid_inner: egui::Color32,
+    /// The border on either side of each grid line.
+    pub grid_outer: egui::Color32,
+    /// Outline of events that aren't selected.
+    pub event_outline: egui::Color32,
+    /// Outline of the selected event.
+    pub selected_event: egui::Color32,
+    /// Outline of events whose event editor is open.
+    pub editing_event: egui::Color32,
+    /// Outline of the tiles selected in the tilepicker.
+    pub tile_selection: egui::Color32,
+}
+
+impl OverlayPalette {
+    pub fn colors(self) -> OverlayColors {
+        use egui::Color32;
+
+        match self {
+            Self::Default => OverlayColors {
+                collision: Color32::from_rgba_unmultiplied(255, 0, 0, 102),
+                grid_inner: Color32::from_rgba_unmultiplied(26, 26, 26, 64),
+                grid_outer: Color32::from_rgba_unmultiplied(179, 179, 179, 64),
+                event_outline: Color32::WHITE,
+                selected_event: Color32::YELLOW,
+                editing_event: Color32::from_rgb(255, 0, 255),
+                tile_selection: Color32::WHITE,
+            },
+            // Colors from the Okabe-Ito palette, which stay distinct without red-green vision
+            Self::DeuteranopiaSafe => OverlayColors {
+                collision: Color32::from_rgba_unmultiplied(0, 114, 178, 128),
+                grid_inner: Color32::from_rgba_unmultiplied(26, 26, 26, 64),
+                grid_outer: Color32::from_rgba_unmultiplied(179, 179, 179, 64),
+                event_outline: Color32::WHITE,
+                selected_event: Color32::from_rgb(230, 159, 0),
+                editing_event: Color32::from_rgb(86, 180, 233),
+                tile_selection: Color32::from_rgb(230, 159, 0),
+            },
+            Self::HighContrast => OverlayColors {
+                collision: Color32::from_rgba_unmultiplied(255, 0, 255, 153),
+                grid_inner: Color32::from_rgba_unmultiplied(0, 0, 0, 160),
+                grid_outer: Color32::from_rgba_unmultiplied(255, 255, 255, 160),
+                event_outline: Color32::WHITE,
+                selected_event: Color32::from_rgb(0, 255, 255),
+                editing_event: Color32::from_rgb(255, 255, 0),
+                tile_selection: Color32::from_rgb(0, 255, 255),
+            },
+        }
+    }
+}
+
+/// The parts of the global config that can be restored to their defaults on their own. These are
+/// also what's compared when previewing an imported config.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum Section {
+    #[strum(to_string = "Recent projects")]
+    RecentProjects,
+    #[strum(to_string = "Code theme")]
+    CodeTheme,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[strum(to_string = "RTP paths")]
+    RtpPaths,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[strum(to_string = "External programs")]
+    ExternalPrograms,
+    Confirmations,
+    Previews,
+    #[strum(to_string = "Overlay colors")]
+    OverlayColors,
+    #[strum(to_string = "Map editor")]
+    MapEditor,
+    Saving,
+    #[cfg(not(target_arch = "wasm32"))]
+    Terminal,
+}
+
+impl Section {
+    /// Whether this section only makes sense on the machine it was made on, like paths to files.
+    pub fn is_machine_specific(self) -> bool {
+        match self {
+            Self::RecentProjects => true,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::RtpPaths | Self::ExternalPrograms => true,
+            _ => false,
+        }
+    }
+}
+
+/// Actions that ask for confirmation before they are performed, because they throw away data.
+///
+/// These are serialized by name in the global config, so variants shouldn't be renamed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum DestructiveAction {
+    #[strum(to_string = "Delete event")]
+    DeleteEvent,
+    #[strum(to_string = "Delete event page")]
+    DeleteEventPage,
+    #[strum(to_string = "Lower database maximum")]
+    ShrinkDatabase,
+    #[strum(to_string = "Revert unsaved changes")]
+    RevertData,
+}
+
+impl DestructiveAction {
+    /// The question shown to the user when asking for confirmation.
+    pub fn prompt(self) -> &'static str {
+        match self {
+            Self::DeleteEvent => "Are you sure you want to delete the selected event?",
+            Self::DeleteEventPage => "Are you sure you want to delete this event page?",
+            Self::ShrinkDatabase => {
+                "Lowering the maximum deletes every entry past the new maximum. Are you sure?"
+            }
+            Self::RevertData => {
+                "Reverting discards every unsaved change to this data and reloads it from disk. Are you sure?"
+            }
+        }
+    }
 }
 
 impl Default for Config {
@@ -53,6 +253,9 @@ impl Default for Config {
 }
 
 impl Config {
+    /// The autosave interval used when autosaving is turned on.
+    pub const DEFAULT_AUTOSAVE_INTERVAL_SECS: u32 = 300;
+
     pub fn new() -> Self {
         Self {
             recent_projects: VecDeque::new(),
@@ -60,7 +263,106 @@ impl Config {
             #[cfg(not(target_arch = "wasm32"))]
             rtp_paths: indexmap::IndexMap::new(),
             #[cfg(not(target_arch = "wasm32"))]
+            external_programs: indexmap::IndexMap::new(),
+            #[cfg(not(target_arch = "wasm32"))]
             terminal: terminal::Config::default(),
+            confirm_destructive_actions: true,
+            skipped_confirmations: BTreeSet::new(),
+            preview_background: PreviewBackground::default(),
+            overlay_palette: OverlayPalette::default(),
+            map_history_size: 50,
+            event_creation: EventCreation::default(),
+            event_double_click_time: 0.3,
+            autosave_interval_secs: None,
+        }
+    }
+
+    /// Returns whether the user should be asked before performing the given destructive action.
+    pub fn should_confirm(&self, action: DestructiveAction) -> bool {
+        self.confirm_destructive_actions && !self.skipped_confirmations.contains(&action)
+    }
+
+    /// Sets the settings in `section` back to their defaults.
+    pub fn restore_defaults(&mut self, section: Section) {
+        self.copy_section(Self::new(), section);
+    }
+
+    /// Replaces the settings in `section` with the ones from `other`.
+    pub fn copy_section(&mut self, other: Self, section: Section) {
+        match section {
+            Section::RecentProjects => self.recent_projects = other.recent_projects,
+            Section::CodeTheme => self.theme = other.theme,
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::RtpPaths => self.rtp_paths = other.rtp_paths,
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::ExternalPrograms => self.external_programs = other.external_programs,
+            Section::Confirmations => {
+                self.confirm_destructive_actions = other.confirm_destructive_actions;
+                self.skipped_confirmations = other.skipped_confirmations;
+            }
+            Section::Previews => self.preview_background = other.preview_background,
+            Section::OverlayColors => self.overlay_palette = other.overlay_palette,
+            Section::MapEditor => {
+                self.map_history_size = other.map_history_size;
+                self.event_creation = other.event_creation;
+                self.event_double_click_time = other.event_double_click_time;
+            }
+            Section::Saving => self.autosave_interval_secs = other.autosave_interval_secs,
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::Terminal => self.terminal = other.terminal,
+        }
+    }
+
+    /// Serializes the settings in `section` as RON, for showing and comparing them.
+    pub fn section_to_ron(&self, section: Section) -> ron::Result<String> {
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        match section {
+            Section::RecentProjects => {
+                ron::ser::to_string_pretty(&self.recent_projects, pretty_config)
+            }
+            Section::CodeTheme => ron::ser::to_string_pretty(&self.theme, pretty_config),
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::RtpPaths => ron::ser::to_string_pretty(&self.rtp_paths, pretty_config),
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::ExternalPrograms => {
+                ron::ser::to_string_pretty(&self.external_programs, pretty_config)
+            }
+            Section::Confirmations => ron::ser::to_string_pretty(
+                &(
+                    self.confirm_destructive_actions,
+                    &self.skipped_confirmations,
+                ),
+                pretty_config,
+            ),
+            Section::Previews => {
+                ron::ser::to_string_pretty(&self.preview_background, pretty_config)
+            }
+            Section::OverlayColors => {
+                ron::ser::to_string_pretty(&self.overlay_palette, pretty_config)
+            }
+            Section::MapEditor => ron::ser::to_string_pretty(
+                &(
+                    self.map_history_size,
+                    self.event_creation,
+                    self.event_double_click_time,
+                ),
+                pretty_config,
+            ),
+            Section::Saving => {
+                ron::ser::to_string_pretty(&self.autosave_interval_secs, pretty_config)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Section::Terminal => ron::ser::to_string_pretty(&self.terminal, pretty_config),
+        }
+    }
+
+    /// Returns a copy of this config with the machine-specific sections set to their defaults, for
+    /// exporting to another machine.
+    pub fn without_machine_specific(&self) -> Self {
+        let mut config = self.clone();
+        for section in Section::iter().filter(|s| s.is_machine_specific()) {
+            config.restore_defaults(section);
         }
+        config
     }
 }
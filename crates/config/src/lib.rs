@@ -15,11 +15,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
+pub mod bookmarks;
 pub mod command_db;
 pub mod global;
+pub mod map_loops;
+pub mod onion_skins;
+pub mod playlists;
 pub mod project;
+pub mod recent_edits;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod terminal;
+pub mod world_layout;
 
 #[derive(Clone, Copy, Hash, PartialEq, Debug, Default)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -80,6 +86,53 @@ pub enum RMVer {
     Ace = 3,
 }
 
+impl RMVer {
+    /// Extension of the project file that RPG Maker opens.
+    pub fn project_extension(self) -> &'static str {
+        match self {
+            Self::XP => "rxproj",
+            Self::VX => "rvproj",
+            Self::Ace => "rvproj2",
+        }
+    }
+
+    /// Extension of the Ruby Marshal data files in the Data folder.
+    pub fn data_extension(self) -> &'static str {
+        match self {
+            Self::XP => "rxdata",
+            Self::VX => "rvdata",
+            Self::Ace => "rvdata2",
+        }
+    }
+
+    /// Extension of the encrypted archive of a deployed game.
+    pub fn archive_extension(self) -> &'static str {
+        match self {
+            Self::XP => "rgssad",
+            Self::VX => "rgss2a",
+            Self::Ace => "rgss3a",
+        }
+    }
+
+    /// The features a project of this version needs that Luminol doesn't implement yet when its
+    /// data is in `data_format`. Projects can't be opened unless this is empty.
+    pub fn unsupported_features(self, data_format: DataFormat) -> &'static [&'static str] {
+        match (self, data_format) {
+            (Self::XP, _) | (_, DataFormat::Ron { .. } | DataFormat::Json { .. }) => &[],
+            (Self::VX, DataFormat::Marshal) => &[
+                "Reading and writing .rvdata files",
+                "The RPG Maker VX database layout (actors, classes, items and so on)",
+                "RPG Maker VX maps, which have no tileset priorities and a different layer layout",
+            ],
+            (Self::Ace, DataFormat::Marshal) => &[
+                "Reading and writing .rvdata2 files",
+                "The RPG Maker VX Ace database layout (features, traits and so on)",
+                "RPG Maker VX Ace scripts, which are stored in Scripts.rvdata2",
+            ],
+        }
+    }
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(strum::EnumIter, strum::Display)]
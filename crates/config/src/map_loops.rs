@@ -0,0 +1,159 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Which maps loop around at their edges, like the world maps of games that scroll endlessly.
+///
+/// RPG Maker XP maps have no setting for this, so it's stored in `.luminol/map_loops` rather than
+/// in the game data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct MapLoops {
+    /// How each looping map loops, keyed by map ID. Maps that don't loop are left out.
+    pub maps: BTreeMap<usize, MapLoop>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct MapLoop {
+    /// Whether the left and right edges of the map connect to each other.
+    pub horizontal: bool,
+    /// Whether the top and bottom edges of the map connect to each other.
+    pub vertical: bool,
+}
+
+impl MapLoops {
+    /// Path of the map loop file relative to the project root.
+    pub const PATH: &'static str = ".luminol/map_loops";
+}
+
+impl MapLoop {
+    pub fn is_looping(self) -> bool {
+        self.horizontal || self.vertical
+    }
+
+    /// Wraps a position around the looping edges of a map of the given size. Returns `None` if
+    /// the position is past an edge that doesn't loop.
+    pub fn wrap(self, x: i32, y: i32, width: usize, height: usize) -> Option<(usize, usize)> {
+        Some((
+            Self::wrap_axis(self.horizontal, x, width)?,
+            Self::wrap_axis(self.vertical, y, height)?,
+        ))
+    }
+
+    fn wrap_axis(looping: bool, position: i32, size: usize) -> Option<usize> {
+        if size == 0 {
+            None
+        } else if looping {
+            Some(position.rem_euclid(size as i32) as usize)
+        } else {
+            usize::try_from(position)
+                .ok()
+                .filter(|&position| position < size)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: MapLoop = MapLoop {
+        horizontal: false,
+        vertical: false,
+    };
+    const HORIZONTAL: MapLoop = MapLoop {
+        horizontal: true,
+        vertical: false,
+    };
+    const VERTICAL: MapLoop = MapLoop {
+        horizontal: false,
+        vertical: true,
+    };
+    const BOTH: MapLoop = MapLoop {
+        horizontal: true,
+        vertical: true,
+    };
+
+    #[test]
+    fn positions_inside_the_map_are_unchanged() {
+        for map_loop in [NONE, HORIZONTAL, VERTICAL, BOTH] {
+            assert_eq!(map_loop.wrap(0, 0, 20, 15), Some((0, 0)));
+            assert_eq!(map_loop.wrap(19, 14, 20, 15), Some((19, 14)));
+            assert_eq!(map_loop.wrap(7, 3, 20, 15), Some((7, 3)));
+        }
+    }
+
+    #[test]
+    fn no_loop() {
+        assert_eq!(NONE.wrap(-1, 0, 20, 15), None);
+        assert_eq!(NONE.wrap(0, -1, 20, 15), None);
+        assert_eq!(NONE.wrap(20, 0, 20, 15), None);
+        assert_eq!(NONE.wrap(0, 15, 20, 15), None);
+        assert!(!NONE.is_looping());
+    }
+
+    #[test]
+    fn horizontal_loop() {
+        assert_eq!(HORIZONTAL.wrap(-1, 5, 20, 15), Some((19, 5)));
+        assert_eq!(HORIZONTAL.wrap(20, 5, 20, 15), Some((0, 5)));
+        assert_eq!(HORIZONTAL.wrap(-41, 5, 20, 15), Some((19, 5)));
+        assert_eq!(HORIZONTAL.wrap(45, 5, 20, 15), Some((5, 5)));
+        // The top and bottom edges still end the map
+        assert_eq!(HORIZONTAL.wrap(-1, -1, 20, 15), None);
+        assert_eq!(HORIZONTAL.wrap(20, 15, 20, 15), None);
+        assert!(HORIZONTAL.is_looping());
+    }
+
+    #[test]
+    fn vertical_loop() {
+        assert_eq!(VERTICAL.wrap(5, -1, 20, 15), Some((5, 14)));
+        assert_eq!(VERTICAL.wrap(5, 15, 20, 15), Some((5, 0)));
+        assert_eq!(VERTICAL.wrap(5, -31, 20, 15), Some((5, 14)));
+        assert_eq!(VERTICAL.wrap(5, 32, 20, 15), Some((5, 2)));
+        // The left and right edges still end the map
+        assert_eq!(VERTICAL.wrap(-1, -1, 20, 15), None);
+        assert_eq!(VERTICAL.wrap(20, 15, 20, 15), None);
+        assert!(VERTICAL.is_looping());
+    }
+
+    #[test]
+    fn loop_in_both_directions() {
+        assert_eq!(BOTH.wrap(-1, -1, 20, 15), Some((19, 14)));
+        assert_eq!(BOTH.wrap(20, 15, 20, 15), Some((0, 0)));
+        assert_eq!(BOTH.wrap(-20, 30, 20, 15), Some((0, 0)));
+        assert_eq!(BOTH.wrap(i32::MIN, i32::MAX, 20, 15), Some((12, 7)));
+        assert!(BOTH.is_looping());
+    }
+
+    #[test]
+    fn empty_maps_have_no_positions() {
+        for map_loop in [NONE, HORIZONTAL, VERTICAL, BOTH] {
+            assert_eq!(map_loop.wrap(0, 0, 0, 15), None);
+            assert_eq!(map_loop.wrap(0, 0, 20, 0), None);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Which other map is drawn as a translucent overlay in each map editor, used to line up maps that
+/// connect to each other.
+///
+/// This is editor-only state, stored in `.luminol/onion_skins` rather than in the game data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct OnionSkins {
+    /// The ID of the map overlaid on each map, keyed by the ID of the map being edited.
+    pub maps: BTreeMap<usize, usize>,
+    /// How one map is overlaid on another, keyed by the IDs of the map being edited and the
+    /// overlaid map.
+    pub pairs: BTreeMap<(usize, usize), OnionSkin>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct OnionSkin {
+    /// Position of the top left corner of the overlaid map relative to the edited map, in tiles.
+    pub offset: [i32; 2],
+    pub opacity: u8,
+    /// Whether the overlaid map is drawn over the edited map instead of under it.
+    pub above: bool,
+}
+
+impl Default for OnionSkin {
+    fn default() -> Self {
+        Self {
+            offset: [0; 2],
+            opacity: 128,
+            above: false,
+        }
+    }
+}
+
+impl OnionSkins {
+    /// Path of the onion skin file relative to the project root.
+    pub const PATH: &'static str = ".luminol/onion_skins";
+}
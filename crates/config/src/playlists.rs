@@ -0,0 +1,57 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+use serde::{Deserialize, Serialize};
+
+/// Lists of sounds the sound test plays one after another, for auditioning a soundtrack in order.
+///
+/// This is editor-only state, stored in `.luminol/playlists` rather than in the game data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Playlists {
+    pub playlists: Vec<Playlist>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Playlist {
+    pub name: String,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaylistEntry {
+    /// The folder in `Audio` the sound is in, like `BGM`.
+    pub folder: String,
+    /// The file name of the sound, which may leave out the extension.
+    pub name: String,
+    pub volume: u8,
+    pub pitch: u8,
+    /// How many seconds this entry fades in for while the entry before it fades out.
+    pub crossfade: f32,
+}
+
+impl Playlists {
+    /// Path of the playlists file relative to the project root.
+    pub const PATH: &'static str = ".luminol/playlists";
+}
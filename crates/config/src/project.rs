@@ -47,6 +47,135 @@ pub struct Project {
     pub playtest_exe: String,
     pub prefer_rgssad: bool,
     pub persistence_id: u64,
+    /// A color treated as transparent in images without an alpha channel, for legacy assets
+    /// that use a color key instead. RPG Maker XP doesn't do this, so it's off by default.
+    pub transparent_color: Option<[u8; 3]>,
+    /// Brush shapes drawn by the user, selectable in the toolbar next to the built-in brushes.
+    pub brush_shapes: Vec<BrushShape>,
+    /// Blocks of regular tiles that the smart brush connects to each other like autotiles.
+    pub smart_tiles: Vec<SmartTile>,
+    /// Patterns of tiles saved from the map or the tilepicker, pasted with the select tool.
+    pub stamps: Vec<Stamp>,
+}
+
+/// A user-defined brush, stamped onto the map centered on the cursor.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BrushShape {
+    pub name: String,
+    /// Width and height of the mask, from 1 to [`BrushShape::MAX_SIZE`].
+    pub size: usize,
+    /// Which cells of the mask are drawn, in row-major order.
+    pub mask: Vec<bool>,
+}
+
+impl BrushShape {
+    pub const MAX_SIZE: usize = 9;
+
+    /// Creates a shape with every cell of a `size` by `size` mask drawn.
+    pub fn new(name: impl Into<String>, size: usize) -> Self {
+        let size = size.clamp(1, Self::MAX_SIZE);
+        Self {
+            name: name.into(),
+            size,
+            mask: vec![true; size * size],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.mask.get(x + y * self.size) == Some(&true)
+    }
+
+    /// Changes the size of the mask, keeping the cells that are still inside it.
+    pub fn resize(&mut self, size: usize) {
+        let size = size.clamp(1, Self::MAX_SIZE);
+        let mask = (0..size * size)
+            .map(|i| self.get(i % size, i / size))
+            .collect();
+        self.size = size;
+        self.mask = mask;
+    }
+
+    /// The offsets of the drawn cells from the center of the mask.
+    pub fn offsets(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let center = (self.size / 2) as i32;
+        (0..self.size * self.size)
+            .filter(|&i| self.mask.get(i) == Some(&true))
+            .map(move |i| {
+                (
+                    (i % self.size) as i32 - center,
+                    (i / self.size) as i32 - center,
+                )
+            })
+    }
+}
+
+/// A block of regular tiles in a tileset that's drawn like an autotile: each tile is replaced by
+/// the variant that connects to the neighboring tiles of the same block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SmartTile {
+    pub name: String,
+    pub tileset_id: usize,
+    pub layout: SmartTileLayout,
+    /// ID of the top-left tile of the block.
+    pub origin: i16,
+}
+
+/// How the variants of a [`SmartTile`] are laid out in the tileset.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartTileLayout {
+    /// A 3x3 block with the center tile in the middle and the edges and outer corners around it.
+    /// There are no inner corners, so diagonal neighbors are ignored.
+    Block3x3,
+    /// The 47 variants of an autotile, in the same order as autotile IDs, 8 tiles per row.
+    Full47,
+}
+
+impl SmartTileLayout {
+    /// The width and height of the block in the tilepicker.
+    pub fn size(self) -> (i16, i16) {
+        match self {
+            Self::Block3x3 => (3, 3),
+            Self::Full47 => (8, 6),
+        }
+    }
+
+    /// Returns the layout whose block has this size.
+    pub fn from_size(width: i16, height: i16) -> Option<Self> {
+        [Self::Block3x3, Self::Full47]
+            .into_iter()
+            .find(|layout| layout.size() == (width, height))
+    }
+}
+
+impl SmartTile {
+    /// The IDs of the tiles in the block.
+    pub fn tiles(&self) -> impl Iterator<Item = i16> + '_ {
+        let (width, height) = self.layout.size();
+        (0..height)
+            .flat_map(move |y| (0..width).map(move |x| self.origin + x + y * 8))
+            .take(match self.layout {
+                SmartTileLayout::Block3x3 => 9,
+                SmartTileLayout::Full47 => 47,
+            })
+    }
+
+    pub fn contains(&self, id: i16) -> bool {
+        self.tiles().any(|tile| tile == id)
+    }
+}
+
+/// A named rectangle of tiles that can be pasted onto any map with the same tileset.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Stamp {
+    pub name: String,
+    pub tileset_id: usize,
+    pub width: usize,
+    pub height: usize,
+    /// The tile IDs of each layer in row-major order.
+    pub layers: Vec<Vec<i16>>,
+    /// Whether `layers` holds every layer of the map, pasted into the same layers, or a single
+    /// layer that's pasted into the selected one.
+    pub all_layers: bool,
 }
 
 impl Default for Project {
@@ -61,6 +190,10 @@ impl Default for Project {
             playtest_exe: "game".to_string(),
             prefer_rgssad: false,
             persistence_id: 0,
+            transparent_color: None,
+            brush_shapes: Vec::new(),
+            smart_tiles: Vec::new(),
+            stamps: Vec::new(),
         }
     }
 }
@@ -0,0 +1,92 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The places the user most recently made changes in, most recent first, so they can switch
+/// back to them.
+///
+/// This is editor-only state, stored in `.luminol/recent_edits` rather than in the game data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct RecentEdits {
+    pub locations: VecDeque<EditLocation>,
+}
+
+/// Something the user can edit, along with enough information to open its editor again.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditLocation {
+    /// An event on a map.
+    Event { map_id: usize, event_id: usize },
+    /// An entry of one of the database windows.
+    Database { database: Database, id: usize },
+    /// A script, by its index in the script list.
+    Script { index: usize },
+}
+
+/// The parts of the database with their own editor window.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display
+)]
+pub enum Database {
+    Actors,
+    Classes,
+    Skills,
+    Items,
+    Weapons,
+    Armors,
+    Enemies,
+    Troops,
+    States,
+    Animations,
+}
+
+impl RecentEdits {
+    /// Path of the recent edits file relative to the project root.
+    pub const PATH: &'static str = ".luminol/recent_edits";
+
+    /// How many locations are remembered.
+    pub const MAX_LEN: usize = 20;
+
+    /// Moves a location to the front of the list, adding it if it isn't in there. Returns whether
+    /// the list changed.
+    pub fn record(&mut self, location: EditLocation) -> bool {
+        if self.locations.front() == Some(&location) {
+            return false;
+        }
+        self.locations.retain(|l| *l != location);
+        self.locations.push_front(location);
+        self.locations.truncate(Self::MAX_LEN);
+        true
+    }
+}
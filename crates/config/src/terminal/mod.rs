@@ -23,15 +23,20 @@
 // Program grant you additional permission to convey the resulting work.
 
 mod theme;
-pub use theme::Theme;
+pub use theme::{Preset as ThemePreset, Theme};
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Debug)]
 pub struct Config {
     pub font: egui::FontId,
     pub initial_size: (u16, u16),
     pub bell_enabled: bool,
+    /// Maximum number of lines kept in the scrollback buffer.
+    #[serde(default = "Config::default_scrollback")]
+    pub scrollback: usize,
 
     pub cursor_blinking: CursorBlinking,
+    #[serde(default)]
+    pub cursor_shape: CursorShape,
     pub theme: Theme,
 }
 
@@ -45,13 +50,27 @@ pub enum CursorBlinking {
     Never,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum CursorShape {
+    #[default]
+    #[strum(to_string = "Terminal defined")]
+    Terminal,
+    Block,
+    Underline,
+    Beam,
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             font: Self::default_font(),
             initial_size: (80, 24),
             bell_enabled: true,
+            scrollback: Self::default_scrollback(),
             cursor_blinking: CursorBlinking::Always,
+            cursor_shape: CursorShape::Terminal,
             theme: Theme::default(),
         }
     }
@@ -64,4 +83,8 @@ impl Config {
             family: egui::FontFamily::Name("Iosevka Term".into()),
         }
     }
+
+    pub fn default_scrollback() -> usize {
+        10_000
+    }
 }
@@ -29,11 +29,13 @@ use std::{
 
 use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct Theme {
     pub color_pallette: [egui::Color32; 16],
     pub background_color: egui::Color32,
+    #[serde(default = "Theme::default_foreground_color")]
+    pub foreground_color: egui::Color32,
     pub cursor_color: egui::Color32,
     ansi_colors: HashMap<u8, egui::Color32>,
 }
@@ -67,12 +69,130 @@ impl Default for Theme {
         Self {
             ansi_colors,
             background_color: egui::Color32::from_rgb(15, 15, 15),
+            foreground_color: Self::default_foreground_color(),
             cursor_color: egui::Color32::WHITE,
             color_pallette: Self::default_configurable_colors(),
         }
     }
 }
 
+/// Built-in color schemes that can be applied to a [`Theme`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum Preset {
+    Luminol,
+    #[strum(to_string = "Solarized Dark")]
+    SolarizedDark,
+    #[strum(to_string = "Solarized Light")]
+    SolarizedLight,
+    Dracula,
+    #[strum(to_string = "Gruvbox Dark")]
+    GruvboxDark,
+}
+
+const fn hex(color: u32) -> egui::Color32 {
+    egui::Color32::from_rgb((color >> 16) as u8, (color >> 8) as u8, color as u8)
+}
+
+const SOLARIZED_PALLETTE: [egui::Color32; 16] = [
+    hex(0x073642),
+    hex(0xdc322f),
+    hex(0x859900),
+    hex(0xb58900),
+    hex(0x268bd2),
+    hex(0xd33682),
+    hex(0x2aa198),
+    hex(0xeee8d5),
+    hex(0x002b36),
+    hex(0xcb4b16),
+    hex(0x586e75),
+    hex(0x657b83),
+    hex(0x839496),
+    hex(0x6c71c4),
+    hex(0x93a1a1),
+    hex(0xfdf6e3),
+];
+
+impl Preset {
+    /// Returns the 16 configurable colors, the background color, the foreground color and the
+    /// cursor color of this preset, in that order.
+    const fn colors(
+        self,
+    ) -> (
+        [egui::Color32; 16],
+        egui::Color32,
+        egui::Color32,
+        egui::Color32,
+    ) {
+        match self {
+            Self::Luminol => (
+                Theme::default_configurable_colors(),
+                egui::Color32::from_rgb(15, 15, 15),
+                Theme::default_foreground_color(),
+                egui::Color32::WHITE,
+            ),
+            Self::SolarizedDark => (
+                SOLARIZED_PALLETTE,
+                hex(0x002b36),
+                hex(0x839496),
+                hex(0x93a1a1),
+            ),
+            Self::SolarizedLight => (
+                SOLARIZED_PALLETTE,
+                hex(0xfdf6e3),
+                hex(0x657b83),
+                hex(0x586e75),
+            ),
+            Self::Dracula => (
+                [
+                    hex(0x21222c),
+                    hex(0xff5555),
+                    hex(0x50fa7b),
+                    hex(0xf1fa8c),
+                    hex(0xbd93f9),
+                    hex(0xff79c6),
+                    hex(0x8be9fd),
+                    hex(0xf8f8f2),
+                    hex(0x6272a4),
+                    hex(0xff6e6e),
+                    hex(0x69ff94),
+                    hex(0xffffa5),
+                    hex(0xd6acff),
+                    hex(0xff92df),
+                    hex(0xa4ffff),
+                    hex(0xffffff),
+                ],
+                hex(0x282a36),
+                hex(0xf8f8f2),
+                hex(0xf8f8f2),
+            ),
+            Self::GruvboxDark => (
+                [
+                    hex(0x282828),
+                    hex(0xcc241d),
+                    hex(0x98971a),
+                    hex(0xd79921),
+                    hex(0x458588),
+                    hex(0xb16286),
+                    hex(0x689d6a),
+                    hex(0xa89984),
+                    hex(0x928374),
+                    hex(0xfb4934),
+                    hex(0xb8bb26),
+                    hex(0xfabd2f),
+                    hex(0x83a598),
+                    hex(0xd3869b),
+                    hex(0x8ec07c),
+                    hex(0xebdbb2),
+                ],
+                hex(0x282828),
+                hex(0xebdbb2),
+                hex(0xebdbb2),
+            ),
+        }
+    }
+}
+
 impl Index<u8> for Theme {
     type Output = egui::Color32;
 
@@ -92,6 +212,10 @@ impl IndexMut<u8> for Theme {
 }
 
 impl Theme {
+    const fn default_foreground_color() -> egui::Color32 {
+        egui::Color32::from_rgb(235, 218, 177)
+    }
+
     const fn default_configurable_colors() -> [egui::Color32; 16] {
         [
             // Default terminal reserved colors
@@ -132,8 +256,8 @@ impl Theme {
             }
             AnsiColor::Named(c) => match c {
                 NamedColor::Background => self.background_color,
-                NamedColor::Foreground => egui::Color32::from_rgb(235, 218, 177),
-                NamedColor::BrightForeground => egui::Color32::from_rgb(235, 218, 177),
+                NamedColor::Foreground => self.foreground_color,
+                NamedColor::BrightForeground => self.foreground_color,
                 // Default terminal reserved colors
                 NamedColor::Black |
                 NamedColor::Red |
@@ -158,3 +282,84 @@ impl Theme {
         }
     }
 }
+
+impl Theme {
+    /// Replaces the configurable colors of this theme with the ones from a preset.
+    pub fn apply_preset(&mut self, preset: Preset) {
+        let (pallette, background, foreground, cursor) = preset.colors();
+        self.color_pallette = pallette;
+        self.background_color = background;
+        self.foreground_color = foreground;
+        self.cursor_color = cursor;
+    }
+
+    /// Applies the colors defined in an X resources file (the format used by `xrdb` and most
+    /// terminal theme collections) to this theme.
+    ///
+    /// Recognizes `color0` through `color15`, `background`, `foreground` and `cursorColor` with
+    /// any resource class prefix (e.g. `*.color0`, `URxvt.background`), with colors written as
+    /// `#rrggbb` or `rgb:rr/gg/bb`. Returns how many colors were applied.
+    pub fn apply_xresources(&mut self, source: &str) -> usize {
+        let mut applied = 0;
+
+        for line in source.lines() {
+            let line = line.trim();
+            // `!` starts a comment, `#` starts a preprocessor directive
+            if line.is_empty() || line.starts_with('!') || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let name = key.trim().rsplit(['.', '*']).next().unwrap_or_default();
+            let Some(color) = parse_xresources_color(value.trim()) else {
+                continue;
+            };
+
+            let slot = match name {
+                "background" => &mut self.background_color,
+                "foreground" => &mut self.foreground_color,
+                "cursorColor" => &mut self.cursor_color,
+                _ => match name
+                    .strip_prefix("color")
+                    .and_then(|index| index.parse::<usize>().ok())
+                {
+                    Some(index @ 0..=15) => &mut self.color_pallette[index],
+                    _ => continue,
+                },
+            };
+            *slot = color;
+            applied += 1;
+        }
+
+        applied
+    }
+}
+
+fn parse_xresources_color(value: &str) -> Option<egui::Color32> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        return u32::from_str_radix(hex, 16).ok().map(self::hex);
+    }
+
+    let mut channels = value.strip_prefix("rgb:")?.split('/').map(|channel| {
+        // X allows 1 to 4 hex digits per channel; only the most significant byte matters here
+        let value = u16::from_str_radix(channel, 16).ok()?;
+        match channel.len() {
+            1 => Some((value * 0x11) as u8),
+            2 => Some(value as u8),
+            3 => Some((value >> 4) as u8),
+            4 => Some((value >> 8) as u8),
+            _ => None,
+        }
+    });
+    let r = channels.next()??;
+    let g = channels.next()??;
+    let b = channels.next()??;
+    channels
+        .next()
+        .is_none()
+        .then(|| egui::Color32::from_rgb(r, g, b))
+}
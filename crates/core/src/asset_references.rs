@@ -0,0 +1,448 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Every field in the project data that names an asset file, so that tools which need to find or
+//! change references to assets all agree on where they are.
+
+use luminol_data::{rpg, ParameterType};
+
+use crate::Data;
+
+/// Every directory an asset reference can point into.
+pub const DIRECTORIES: &[&str] = &[
+    "Graphics/Animations",
+    "Graphics/Autotiles",
+    "Graphics/Battlebacks",
+    "Graphics/Battlers",
+    "Graphics/Characters",
+    "Graphics/Fogs",
+    "Graphics/Gameovers",
+    "Graphics/Icons",
+    "Graphics/Panoramas",
+    "Graphics/Pictures",
+    "Graphics/Tilesets",
+    "Graphics/Titles",
+    "Graphics/Transitions",
+    "Graphics/Windowskins",
+    "Audio/BGM",
+    "Audio/BGS",
+    "Audio/ME",
+    "Audio/SE",
+];
+
+/// The name of an asset, stored in one of the project's data structures.
+pub enum Name<'a> {
+    Path(&'a mut luminol_data::Path),
+    /// Names stored as plain strings, like autotiles and event command parameters.
+    String(&'a mut String),
+}
+
+/// A field in the project data that names an asset file.
+pub struct Reference<'a> {
+    /// Where the field is, e.g. `Map 001: MAP001, event 0003: EV003, page 1`.
+    pub location: &'a str,
+    /// The directory the asset is looked up in, e.g. `Graphics/Characters`.
+    pub directory: &'static str,
+    pub name: Name<'a>,
+}
+
+impl Name<'_> {
+    /// The asset's name, or `None` if the field is empty.
+    pub fn get(&self) -> Option<&str> {
+        match self {
+            Self::Path(path) => path.as_ref().map(|path| path.as_str()),
+            Self::String(string) => Some(string.as_str()),
+        }
+        .filter(|name| !name.is_empty())
+    }
+
+    pub fn set(&mut self, name: &str) {
+        match self {
+            Self::Path(path) => **path = Some(name.into()),
+            Self::String(string) => name.clone_into(string),
+        }
+    }
+}
+
+impl Reference<'_> {
+    /// Whether this references `file` (a path relative to `directory`), which may be given with or
+    /// without its extension. RGSS looks files up case-insensitively, so this does too.
+    pub fn refers_to(&self, directory: &str, file: &camino::Utf8Path) -> bool {
+        if !self.directory.eq_ignore_ascii_case(directory) {
+            return false;
+        }
+        let Some(name) = self.name.get() else {
+            return false;
+        };
+        name.eq_ignore_ascii_case(file.as_str())
+            || file
+                .extension()
+                .is_some_and(|_| name.eq_ignore_ascii_case(file.with_extension("").as_str()))
+    }
+}
+
+/// Calls `visit` for every asset reference in the database (everything but the maps). `visit`
+/// returns whether it changed the reference, and any structure it changed is marked as modified.
+///
+/// # Panics
+///
+/// Will panic if the data cache is not loaded.
+pub fn for_each_database_reference(data: &Data, mut visit: impl FnMut(Reference<'_>) -> bool) {
+    let mut visitor = Visitor {
+        visit: &mut visit,
+        modified: false,
+    };
+
+    let mut actors = data.actors();
+    for actor in actors.data.iter_mut() {
+        let location = format!("Actor {:0>4}: {}", actor.id + 1, actor.name);
+        visitor.path(&location, "Graphics/Characters", &mut actor.character_name);
+        visitor.path(&location, "Graphics/Battlers", &mut actor.battler_name);
+    }
+    actors.modified |= visitor.take_modified();
+    drop(actors);
+
+    let mut animations = data.animations();
+    for animation in animations.data.iter_mut() {
+        let location = format!("Animation {:0>4}: {}", animation.id + 1, animation.name);
+        visitor.path(
+            &location,
+            "Graphics/Animations",
+            &mut animation.animation_name,
+        );
+        for timing in animation.timings.iter_mut() {
+            visitor.path(&location, "Audio/SE", &mut timing.se.name);
+        }
+    }
+    animations.modified |= visitor.take_modified();
+    drop(animations);
+
+    let mut armors = data.armors();
+    for armor in armors.data.iter_mut() {
+        let location = format!("Armor {:0>4}: {}", armor.id + 1, armor.name);
+        visitor.path(&location, "Graphics/Icons", &mut armor.icon_name);
+    }
+    armors.modified |= visitor.take_modified();
+    drop(armors);
+
+    let mut common_events = data.common_events();
+    for common_event in common_events.data.iter_mut() {
+        let location = format!(
+            "Common event {:0>4}: {}",
+            common_event.id + 1,
+            common_event.name
+        );
+        visitor.commands(&location, &mut common_event.list);
+    }
+    common_events.modified |= visitor.take_modified();
+    drop(common_events);
+
+    let mut enemies = data.enemies();
+    for enemy in enemies.data.iter_mut() {
+        let location = format!("Enemy {:0>4}: {}", enemy.id + 1, enemy.name);
+        visitor.path(&location, "Graphics/Battlers", &mut enemy.battler_name);
+    }
+    enemies.modified |= visitor.take_modified();
+    drop(enemies);
+
+    let mut items = data.items();
+    for item in items.data.iter_mut() {
+        let location = format!("Item {:0>4}: {}", item.id + 1, item.name);
+        visitor.path(&location, "Graphics/Icons", &mut item.icon_name);
+        visitor.path(&location, "Audio/SE", &mut item.menu_se.name);
+    }
+    items.modified |= visitor.take_modified();
+    drop(items);
+
+    let mut skills = data.skills();
+    for skill in skills.data.iter_mut() {
+        let location = format!("Skill {:0>4}: {}", skill.id + 1, skill.name);
+        visitor.path(&location, "Graphics/Icons", &mut skill.icon_name);
+        visitor.path(&location, "Audio/SE", &mut skill.menu_se.name);
+    }
+    skills.modified |= visitor.take_modified();
+    drop(skills);
+
+    let mut system = data.system();
+    let system = &mut *system;
+    let location = "System";
+    visitor.path(
+        location,
+        "Graphics/Windowskins",
+        &mut system.windowskin_name,
+    );
+    visitor.path(location, "Graphics/Titles", &mut system.title_name);
+    visitor.path(location, "Graphics/Gameovers", &mut system.gameover_name);
+    visitor.path(
+        location,
+        "Graphics/Transitions",
+        &mut system.battle_transition,
+    );
+    visitor.path(
+        location,
+        "Graphics/Battlebacks",
+        &mut system.battleback_name,
+    );
+    visitor.path(location, "Graphics/Battlers", &mut system.battler_name);
+    visitor.path(location, "Audio/BGM", &mut system.title_bgm.name);
+    visitor.path(location, "Audio/BGM", &mut system.battle_bgm.name);
+    visitor.path(location, "Audio/ME", &mut system.battle_end_me.name);
+    visitor.path(location, "Audio/ME", &mut system.gameover_me.name);
+    for se in [
+        &mut system.cursor_se,
+        &mut system.decision_se,
+        &mut system.cancel_se,
+        &mut system.buzzer_se,
+        &mut system.equip_se,
+        &mut system.shop_se,
+        &mut system.save_se,
+        &mut system.load_se,
+        &mut system.battle_start_se,
+        &mut system.escape_se,
+        &mut system.actor_collapse_se,
+        &mut system.enemy_collapse_se,
+    ] {
+        visitor.path(location, "Audio/SE", &mut se.name);
+    }
+    system.modified |= visitor.take_modified();
+
+    let mut tilesets = data.tilesets();
+    for tileset in tilesets.data.iter_mut() {
+        let location = format!("Tileset {:0>3}: {}", tileset.id + 1, tileset.name);
+        visitor.path(&location, "Graphics/Tilesets", &mut tileset.tileset_name);
+        for autotile_name in tileset.autotile_names.iter_mut() {
+            visitor.string(&location, "Graphics/Autotiles", autotile_name);
+        }
+        visitor.path(&location, "Graphics/Panoramas", &mut tileset.panorama_name);
+        visitor.path(&location, "Graphics/Fogs", &mut tileset.fog_name);
+        visitor.path(
+            &location,
+            "Graphics/Battlebacks",
+            &mut tileset.battleback_name,
+        );
+    }
+    tilesets.modified |= visitor.take_modified();
+    drop(tilesets);
+
+    let mut troops = data.troops();
+    for troop in troops.data.iter_mut() {
+        for (i, page) in troop.pages.iter_mut().enumerate() {
+            let location = format!("Troop {:0>4}: {}, page {}", troop.id + 1, troop.name, i + 1);
+            visitor.commands(&location, &mut page.list);
+        }
+    }
+    troops.modified |= visitor.take_modified();
+    drop(troops);
+
+    let mut weapons = data.weapons();
+    for weapon in weapons.data.iter_mut() {
+        let location = format!("Weapon {:0>4}: {}", weapon.id + 1, weapon.name);
+        visitor.path(&location, "Graphics/Icons", &mut weapon.icon_name);
+    }
+    weapons.modified |= visitor.take_modified();
+}
+
+/// Calls `visit` for every asset reference in every map, loading the maps that aren't loaded yet.
+/// `visit` returns whether it changed the reference, and any map it changed is marked as modified.
+///
+/// # Panics
+///
+/// Will panic if the data cache is not loaded or a map fails to load.
+pub fn for_each_map_reference(
+    data: &Data,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    config: &luminol_config::project::Config,
+    mut visit: impl FnMut(Reference<'_>) -> bool,
+) {
+    let mut visitor = Visitor {
+        visit: &mut visit,
+        modified: false,
+    };
+
+    let mut map_infos: Vec<_> = (data.map_infos().data.iter())
+        .map(|(&id, info)| (id, info.name.clone()))
+        .collect();
+    map_infos.sort_unstable_by_key(|(id, _)| *id);
+
+    for (id, name) in map_infos {
+        let mut map = data.get_or_load_map(id, filesystem, config);
+        let location = format!("Map {id:0>3}: {name}");
+        visitor.path(&location, "Audio/BGM", &mut map.bgm.name);
+        visitor.path(&location, "Audio/BGS", &mut map.bgs.name);
+
+        for (event_id, event) in map.events.iter_mut() {
+            for (i, page) in event.pages.iter_mut().enumerate() {
+                let location = format!(
+                    "Map {id:0>3}: {name}, event {event_id:0>4}: {}, page {}",
+                    event.name,
+                    i + 1
+                );
+                visitor.path(
+                    &location,
+                    "Graphics/Characters",
+                    &mut page.graphic.character_name,
+                );
+                visitor.move_route(&location, &mut page.move_route);
+                visitor.commands(&location, &mut page.list);
+            }
+        }
+
+        if visitor.take_modified() {
+            map.modified = true;
+        }
+    }
+}
+
+/// Calls `visit` for every asset reference in the database and every map. See
+/// [`for_each_database_reference`] and [`for_each_map_reference`].
+pub fn for_each_reference(
+    data: &Data,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    config: &luminol_config::project::Config,
+    mut visit: impl FnMut(Reference<'_>) -> bool,
+) {
+    for_each_database_reference(data, &mut visit);
+    for_each_map_reference(data, filesystem, config, &mut visit);
+}
+
+struct Visitor<'v> {
+    visit: &'v mut dyn FnMut(Reference<'_>) -> bool,
+    /// Whether `visit` changed anything since the last call to `take_modified`.
+    modified: bool,
+}
+
+impl Visitor<'_> {
+    fn path(&mut self, location: &str, directory: &'static str, path: &mut luminol_data::Path) {
+        self.modified |= (self.visit)(Reference {
+            location,
+            directory,
+            name: Name::Path(path),
+        });
+    }
+
+    fn string(&mut self, location: &str, directory: &'static str, string: &mut String) {
+        self.modified |= (self.visit)(Reference {
+            location,
+            directory,
+            name: Name::String(string),
+        });
+    }
+
+    fn take_modified(&mut self) -> bool {
+        std::mem::take(&mut self.modified)
+    }
+
+    fn string_parameter(
+        &mut self,
+        location: &str,
+        directory: &'static str,
+        parameters: &mut [ParameterType],
+        index: usize,
+    ) {
+        if let Some(ParameterType::String(string)) = parameters.get_mut(index) {
+            self.string(location, directory, string);
+        }
+    }
+
+    fn audio_parameter(
+        &mut self,
+        location: &str,
+        directory: &'static str,
+        parameters: &mut [ParameterType],
+        index: usize,
+    ) {
+        if let Some(ParameterType::AudioFile(file)) = parameters.get_mut(index) {
+            self.path(location, directory, &mut file.name);
+        }
+    }
+
+    fn commands(&mut self, location: &str, list: &mut [rpg::EventCommand]) {
+        for command in list {
+            let parameters = &mut command.parameters;
+            match command.code {
+                // Change Windowskin
+                131 => self.string_parameter(location, "Graphics/Windowskins", parameters, 0),
+                // Change Battle BGM
+                132 => self.audio_parameter(location, "Audio/BGM", parameters, 0),
+                // Change Battle End ME
+                133 => self.audio_parameter(location, "Audio/ME", parameters, 0),
+                // Change Map Settings
+                204 => {
+                    let directory = match parameters.first() {
+                        Some(ParameterType::Integer(0)) => "Graphics/Panoramas",
+                        Some(ParameterType::Integer(1)) => "Graphics/Fogs",
+                        Some(ParameterType::Integer(2)) => "Graphics/Battlebacks",
+                        _ => continue,
+                    };
+                    self.string_parameter(location, directory, parameters, 1);
+                }
+                // Set Move Route
+                209 => {
+                    if let Some(ParameterType::MoveRoute(route)) = parameters.get_mut(1) {
+                        self.move_route(location, route);
+                    }
+                }
+                // Execute Transition
+                222 => self.string_parameter(location, "Graphics/Transitions", parameters, 0),
+                // Show Picture
+                231 => self.string_parameter(location, "Graphics/Pictures", parameters, 1),
+                // Play BGM, Play BGS, Play ME and Play SE
+                241 => self.audio_parameter(location, "Audio/BGM", parameters, 0),
+                245 => self.audio_parameter(location, "Audio/BGS", parameters, 0),
+                249 => self.audio_parameter(location, "Audio/ME", parameters, 0),
+                250 => self.audio_parameter(location, "Audio/SE", parameters, 0),
+                // Change Actor Graphic
+                322 => {
+                    self.string_parameter(location, "Graphics/Characters", parameters, 1);
+                    self.string_parameter(location, "Graphics/Battlers", parameters, 3);
+                }
+                // the copies of a Set Move Route's commands that RPG Maker keeps after it
+                509 => {
+                    if let Some(ParameterType::MoveCommand(command)) = parameters.first_mut() {
+                        self.move_command(location, command);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn move_route(&mut self, location: &str, route: &mut rpg::MoveRoute) {
+        for command in route.list.iter_mut() {
+            self.move_command(location, command);
+        }
+    }
+
+    fn move_command(&mut self, location: &str, command: &mut rpg::MoveCommand) {
+        match command.code {
+            // Change Graphic
+            41 => {
+                self.string_parameter(location, "Graphics/Characters", &mut command.parameters, 0)
+            }
+            // Play SE
+            44 => self.audio_parameter(location, "Audio/SE", &mut command.parameters, 0),
+            _ => {}
+        }
+    }
+}
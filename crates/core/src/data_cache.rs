@@ -26,10 +26,10 @@ use color_eyre::eyre::WrapErr;
 use luminol_data::rpg;
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
 };
 
-use crate::error;
+use crate::{error, info};
 
 pub mod data_formats;
 
@@ -57,6 +57,8 @@ pub enum Data {
         weapons: RefCell<rpg::Weapons>,
 
         maps: RefCell<HashMap<usize, rpg::Map>>,
+
+        timestamps: RefCell<HashMap<Structure, Timestamps>>,
     },
 }
 
@@ -79,18 +81,25 @@ macro_rules! from_defaults {
     };
 }
 
-macro_rules! save {
-    ($fs:ident, $type:ident, $field:ident, $format_handler:ident) => {{
+macro_rules! snapshot {
+    ($type:ident, $field:ident, $format_handler:ident, $force:ident) => {{
         let borrowed = $field.get_mut();
-        if borrowed.modified {
-            $format_handler
-                .write_nil_padded(&borrowed.data, $fs, format!("{}", stringify!($type)))
-                .wrap_err_with(|| format!("While saving {}", stringify!($type)))?;
+        if borrowed.modified || $force {
+            borrowed.modified = false;
+            let data = borrowed.data.clone();
+            Some(Snapshot::new(
+                $format_handler.path_for(stringify!($type)),
+                move |buffer| $format_handler.write_nil_padded_to(&data, buffer),
+            ))
+        } else {
+            None
         }
-        borrowed.modified
     }};
 }
 
+/// How many files can be serialized in the background at once while saving.
+const FILES_IN_FLIGHT: usize = 4;
+
 impl Data {
     /// Load all data required when opening a project.
     /// Does not load config. That is expected to have been loaded beforehand.
@@ -100,6 +109,7 @@ impl Data {
         toasts: &mut crate::Toasts,
         config: &mut luminol_config::project::Config,
     ) -> color_eyre::Result<()> {
+        Self::detect_data_format(filesystem, toasts, config);
         let handler = data_formats::Handler::new(config.project.data_format);
 
         let map_infos = RefCell::new(rpg::MapInfos {
@@ -124,7 +134,7 @@ impl Data {
         ];
 
         for script_path in scripts_paths {
-            match handler.read_data(filesystem, format!("{script_path}")) {
+            match handler.read_data(filesystem, &script_path) {
                 Ok(s) => {
                     config.project.scripts_path = script_path;
                     scripts = Some(rpg::Scripts {
@@ -170,7 +180,9 @@ impl Data {
             system,
             scripts,
             maps,
+            timestamps: Default::default(),
         };
+        self.record_loaded(Structure::ALL);
 
         Ok(())
     }
@@ -220,6 +232,46 @@ impl Data {
             system,
             scripts,
             maps,
+            timestamps: Default::default(),
+        }
+    }
+
+    /// Switches the project to the format its data is actually in, if it isn't in the configured
+    /// one. This happens when the data was converted by a copy of the project with its own config,
+    /// like when a team keeps its data in version control as JSON.
+    fn detect_data_format(
+        filesystem: &impl luminol_filesystem::FileSystem,
+        toasts: &mut crate::Toasts,
+        config: &mut luminol_config::project::Config,
+    ) {
+        use luminol_config::DataFormat;
+
+        let has_data_in = |format: DataFormat| {
+            filesystem
+                .exists(data_formats::Handler::new(format).path_for("MapInfos"))
+                .unwrap_or_default()
+        };
+        if has_data_in(config.project.data_format) {
+            return;
+        }
+
+        let detected = [
+            DataFormat::Marshal,
+            DataFormat::Json { pretty: true },
+            DataFormat::Ron { pretty: true },
+        ]
+        .into_iter()
+        .find(|&format| has_data_in(format));
+        if let Some(format) = detected {
+            info!(
+                *toasts,
+                format!(
+                    "This project's data is in the {format} format instead of {}, so it will be \
+                    read and saved as {format}",
+                    config.project.data_format
+                )
+            );
+            config.project.data_format = format;
         }
     }
 
@@ -227,15 +279,82 @@ impl Data {
         todo!()
     }
 
-    /// Save all cached data to disk.
+    /// Save cached data that has been modified since the last save to disk.
     // we take an &mut self to ensure no outsanding borrows of the cache exist.
     pub fn save(
         &mut self,
         filesystem: &impl luminol_filesystem::FileSystem,
         config: &luminol_config::project::Config,
     ) -> color_eyre::Result<()> {
-        let handler = data_formats::Handler::new(config.project.data_format);
+        let mut task = self.start_save(false);
+        self.continue_save(&mut task, filesystem, config, None)
+            .map(|_| ())
+    }
+
+    /// Save all cached data to disk, regardless of whether or not it has been modified.
+    ///
+    /// This is needed when the data on disk can't be trusted to match the cache,
+    /// like when creating a project or converting between data formats.
+    pub fn save_all(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let mut task = self.start_save(true);
+        self.continue_save(&mut task, filesystem, config, None)
+            .map(|_| ())
+    }
 
+    /// Writes only the maps that have been modified since the last save, skipping the rest of the
+    /// project.
+    ///
+    /// Returns how many maps were written, along with the errors of the maps that couldn't be.
+    /// A map that fails to save is left modified so that it's written by the next save.
+    pub fn save_modified_maps(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> (usize, Vec<color_eyre::Report>) {
+        let Self::Loaded { maps, .. } = self else {
+            panic!("project not loaded")
+        };
+        let mut map_ids = maps
+            .get_mut()
+            .iter()
+            .filter(|(_, map)| map.modified)
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        map_ids.sort_unstable();
+
+        let mut count = 0;
+        let mut errors = Vec::new();
+        for id in map_ids {
+            let step = SaveStep::Map(id);
+            let Some(snapshot) = self.snapshot(step, false, false, config) else {
+                continue;
+            };
+            let bytes = (snapshot.serialize)();
+            match self.write_file(step, &snapshot.path, bytes, filesystem) {
+                Ok(()) => count += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // System isn't written here, so its magic number has to be updated by the next save
+        if count > 0 {
+            if let Self::Loaded { system, .. } = self {
+                system.get_mut().modified = true;
+            }
+        }
+
+        (count, errors)
+    }
+
+    /// Plans a save of the project without writing anything yet.
+    /// Pass the returned task to [`Data::continue_save`] to actually write the files.
+    ///
+    /// If `force` is true, every file is written regardless of whether or not it has been modified.
+    pub fn start_save(&self, force: bool) -> SaveTask {
         let Self::Loaded {
             actors,
             animations,
@@ -251,71 +370,340 @@ impl Data {
             tilesets,
             troops,
             weapons,
-            system,
             maps,
+            ..
         } = self
         else {
             panic!("project not loaded")
         };
 
-        let mut modified = false;
-
-        modified |= save!(filesystem, Actors, actors, handler);
-        modified |= save!(filesystem, Animations, animations, handler);
-        modified |= save!(filesystem, Armors, armors, handler);
-        modified |= save!(filesystem, Classes, classes, handler);
-        modified |= save!(filesystem, CommonEvents, common_events, handler);
-        modified |= save!(filesystem, Enemies, enemies, handler);
-        modified |= save!(filesystem, Items, items, handler);
-        modified |= save!(filesystem, Skills, skills, handler);
-        modified |= save!(filesystem, States, states, handler);
-        modified |= save!(filesystem, Tilesets, tilesets, handler);
-        modified |= save!(filesystem, Troops, troops, handler);
-        modified |= save!(filesystem, Weapons, weapons, handler);
-
-        {
-            let map_infos = map_infos.get_mut();
-            if map_infos.modified {
-                modified = true;
-                handler
-                    .write_data(&map_infos.data, filesystem, "MapInfos")
-                    .wrap_err("While saving MapInfos")?;
+        let mut steps = VecDeque::new();
+        let mut queue = |step: SaveStep, modified: bool| {
+            if modified || force {
+                steps.push_back(step);
             }
+        };
+
+        queue(SaveStep::Actors, actors.borrow().modified);
+        queue(SaveStep::Animations, animations.borrow().modified);
+        queue(SaveStep::Armors, armors.borrow().modified);
+        queue(SaveStep::Classes, classes.borrow().modified);
+        queue(SaveStep::CommonEvents, common_events.borrow().modified);
+        queue(SaveStep::Enemies, enemies.borrow().modified);
+        queue(SaveStep::Items, items.borrow().modified);
+        queue(SaveStep::Skills, skills.borrow().modified);
+        queue(SaveStep::States, states.borrow().modified);
+        queue(SaveStep::Tilesets, tilesets.borrow().modified);
+        queue(SaveStep::Troops, troops.borrow().modified);
+        queue(SaveStep::Weapons, weapons.borrow().modified);
+        queue(SaveStep::MapInfos, map_infos.borrow().modified);
+        queue(SaveStep::Scripts, scripts.borrow().modified);
+
+        let mut map_ids = maps
+            .borrow()
+            .iter()
+            .filter(|(_, map)| map.modified || force)
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        map_ids.sort_unstable();
+        steps.extend(map_ids.into_iter().map(SaveStep::Map));
+
+        // System and the project config are always looked at last, since whether or not system
+        // needs to be written depends on everything else
+        steps.push_back(SaveStep::System);
+        steps.push_back(SaveStep::Config);
+
+        SaveTask {
+            total: steps.len(),
+            steps,
+            in_flight: VecDeque::new(),
+            force,
+            wrote_any: false,
+            cancelled: false,
         }
+    }
 
-        {
-            let scripts = scripts.get_mut();
-            if scripts.modified {
-                modified = true;
-                handler.write_data(&scripts.data, filesystem, &config.project.scripts_path)?;
+    /// Writes the remaining files of a save, stopping early once `budget` has passed.
+    ///
+    /// With a budget, files are serialized in the background from snapshots of their structures
+    /// taken when the save reaches them, so the cache can keep being edited in the meantime, and
+    /// this returns while they're being serialized. With no budget, every remaining file is
+    /// serialized and written right away.
+    ///
+    /// Returns `Ok(true)` once the save has finished or was cancelled.
+    /// Files written before an error or cancellation are left valid, and the structures that
+    /// weren't written stay marked as modified.
+    pub fn continue_save(
+        &mut self,
+        task: &mut SaveTask,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        budget: Option<std::time::Duration>,
+    ) -> color_eyre::Result<bool> {
+        let result = self.write_files(task, filesystem, config, budget);
+        if matches!(result, Ok(false)) {
+            return result;
+        }
+
+        // the files that were still being serialized won't be written
+        for file in std::mem::take(&mut task.in_flight) {
+            self.set_modified(file.step);
+        }
+        // the magic number hasn't been updated for what was written, so make sure the next save does it
+        if task.wrote_any && (result.is_err() || task.cancelled) {
+            self.set_modified(SaveStep::System);
+        }
+
+        result
+    }
+
+    fn write_files(
+        &mut self,
+        task: &mut SaveTask,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        budget: Option<std::time::Duration>,
+    ) -> color_eyre::Result<bool> {
+        let start = web_time::Instant::now();
+
+        loop {
+            // files are written in the order they were queued in, as soon as they're serialized
+            while task
+                .in_flight
+                .front()
+                .is_some_and(|file| file.bytes.ready().is_some())
+            {
+                let file = task
+                    .in_flight
+                    .pop_front()
+                    .expect("file should be in flight");
+                self.write_file(
+                    file.step,
+                    &file.path,
+                    file.bytes.block_and_take(),
+                    filesystem,
+                )?;
+                task.wrote_any = true;
+
+                if budget.is_some_and(|budget| start.elapsed() >= budget) {
+                    return Ok(false);
+                }
             }
+
+            if task.cancelled {
+                return Ok(true);
+            }
+            let Some(&step) = task.steps.front() else {
+                return Ok(task.in_flight.is_empty());
+            };
+            // System and the project config depend on everything else having been written
+            let must_wait =
+                matches!(step, SaveStep::System | SaveStep::Config) && !task.in_flight.is_empty();
+            if must_wait || task.in_flight.len() >= FILES_IN_FLIGHT {
+                return Ok(false);
+            }
+            task.steps.pop_front();
+
+            if step == SaveStep::Config {
+                Self::save_config(filesystem, config)?;
+                continue;
+            }
+            let Some(snapshot) = self.snapshot(step, task.force, task.wrote_any, config) else {
+                continue;
+            };
+            let bytes = match budget {
+                Some(_) => crate::spawn_future(async move { (snapshot.serialize)() }),
+                None => poll_promise::Promise::from_ready((snapshot.serialize)()),
+            };
+            task.in_flight.push_back(InFlight {
+                step,
+                path: snapshot.path,
+                bytes,
+            });
         }
+    }
 
-        {
-            let maps = maps.get_mut();
-            maps.iter().try_for_each(|(id, map)| {
-                if map.modified {
-                    modified = true;
-                    handler
-                        .write_data(map, filesystem, format!("Map{id:0>3}"))
-                        .wrap_err_with(|| format!("While saving map {id:0>3}"))
-                } else {
-                    Ok(())
+    /// Writes a serialized file, and marks its structure as matching it.
+    /// If serializing or writing it failed, the structure is marked as modified again instead.
+    fn write_file(
+        &mut self,
+        step: SaveStep,
+        path: &camino::Utf8Path,
+        bytes: color_eyre::Result<Vec<u8>>,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<()> {
+        let result = bytes
+            .and_then(|bytes| filesystem.write(path, bytes))
+            .wrap_err_with(|| format!("While saving {step}"));
+        match &result {
+            Ok(()) => {
+                if let Some(structure) = step.structure() {
+                    self.record_loaded([structure]);
                 }
-            })?
+            }
+            Err(_) => self.set_modified(step),
         }
+        result
+    }
 
-        {
-            let system = system.get_mut();
-            if system.modified || modified {
+    /// Takes a copy of the structure written by `step` if it needs to be written, and marks it as
+    /// saved. The copy can then be serialized without borrowing the cache.
+    fn snapshot(
+        &mut self,
+        step: SaveStep,
+        force: bool,
+        wrote_any: bool,
+        config: &luminol_config::project::Config,
+    ) -> Option<Snapshot> {
+        let handler = data_formats::Handler::new(config.project.data_format);
+
+        let Self::Loaded {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            tilesets,
+            troops,
+            weapons,
+            system,
+            maps,
+            ..
+        } = self
+        else {
+            panic!("project not loaded")
+        };
+
+        match step {
+            SaveStep::Actors => snapshot!(Actors, actors, handler, force),
+            SaveStep::Animations => snapshot!(Animations, animations, handler, force),
+            SaveStep::Armors => snapshot!(Armors, armors, handler, force),
+            SaveStep::Classes => snapshot!(Classes, classes, handler, force),
+            SaveStep::CommonEvents => snapshot!(CommonEvents, common_events, handler, force),
+            SaveStep::Enemies => snapshot!(Enemies, enemies, handler, force),
+            SaveStep::Items => snapshot!(Items, items, handler, force),
+            SaveStep::Skills => snapshot!(Skills, skills, handler, force),
+            SaveStep::States => snapshot!(States, states, handler, force),
+            SaveStep::Tilesets => snapshot!(Tilesets, tilesets, handler, force),
+            SaveStep::Troops => snapshot!(Troops, troops, handler, force),
+            SaveStep::Weapons => snapshot!(Weapons, weapons, handler, force),
+
+            SaveStep::MapInfos => {
+                let map_infos = map_infos.get_mut();
+                if !(map_infos.modified || force) {
+                    return None;
+                }
+                map_infos.modified = false;
+                let data = map_infos.data.clone();
+                Some(Snapshot::new(handler.path_for("MapInfos"), move |buffer| {
+                    handler.write_data_to(&luminol_data::helpers::SortedMap(&data), buffer)
+                }))
+            }
+
+            SaveStep::Scripts => {
+                let scripts = scripts.get_mut();
+                if !(scripts.modified || force) {
+                    return None;
+                }
+                scripts.modified = false;
+                let data = scripts.data.clone();
+                Some(Snapshot::new(
+                    handler.path_for(&config.project.scripts_path),
+                    move |buffer| handler.write_data_to(&data, buffer),
+                ))
+            }
+
+            SaveStep::Map(id) => {
+                let map = maps.get_mut().get_mut(&id)?;
+                if !(map.modified || force) {
+                    return None;
+                }
+                map.modified = false;
+                let data = map.clone();
+                Some(Snapshot::new(
+                    handler.path_for(format!("Map{id:0>3}")),
+                    move |buffer| handler.write_data_to(&data, buffer),
+                ))
+            }
+
+            SaveStep::System => {
+                let system = system.get_mut();
+                // the magic number changes on every save, so system has to be written whenever anything else is
+                if !(system.modified || wrote_any || force) {
+                    return None;
+                }
                 system.magic_number = rand::random();
-                handler
-                    .write_data(system, filesystem, "System")
-                    .wrap_err("While saving System")?;
                 system.modified = false;
+                let data = system.clone();
+                Some(Snapshot::new(handler.path_for("System"), move |buffer| {
+                    handler.write_data_to(&data, buffer)
+                }))
             }
+
+            SaveStep::Config => None,
         }
+    }
 
+    /// Marks the structure written by `step` as modified again, so the next save writes it.
+    fn set_modified(&mut self, step: SaveStep) {
+        let Self::Loaded {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            tilesets,
+            troops,
+            weapons,
+            system,
+            maps,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        match step {
+            SaveStep::Actors => actors.get_mut().modified = true,
+            SaveStep::Animations => animations.get_mut().modified = true,
+            SaveStep::Armors => armors.get_mut().modified = true,
+            SaveStep::Classes => classes.get_mut().modified = true,
+            SaveStep::CommonEvents => common_events.get_mut().modified = true,
+            SaveStep::Enemies => enemies.get_mut().modified = true,
+            SaveStep::Items => items.get_mut().modified = true,
+            SaveStep::Skills => skills.get_mut().modified = true,
+            SaveStep::States => states.get_mut().modified = true,
+            SaveStep::Tilesets => tilesets.get_mut().modified = true,
+            SaveStep::Troops => troops.get_mut().modified = true,
+            SaveStep::Weapons => weapons.get_mut().modified = true,
+            SaveStep::MapInfos => map_infos.get_mut().modified = true,
+            SaveStep::Scripts => scripts.get_mut().modified = true,
+            SaveStep::System => system.get_mut().modified = true,
+            SaveStep::Map(id) => {
+                if let Some(map) = maps.get_mut().get_mut(&id) {
+                    map.modified = true;
+                }
+            }
+            SaveStep::Config => {}
+        }
+    }
+
+    fn save_config(
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
         let pretty_config = ron::ser::PrettyConfig::new()
             .struct_names(true)
             .enumerate_arrays(true);
@@ -347,27 +735,200 @@ impl Data {
             .write_to(&mut ini_file)
             .wrap_err("While serializing Game.ini")?;
 
-        actors.borrow_mut().modified = false;
-        animations.borrow_mut().modified = false;
-        armors.borrow_mut().modified = false;
-        classes.borrow_mut().modified = false;
-        common_events.borrow_mut().modified = false;
-        enemies.borrow_mut().modified = false;
-        items.borrow_mut().modified = false;
-        skills.borrow_mut().modified = false;
-        states.borrow_mut().modified = false;
-        tilesets.borrow_mut().modified = false;
-        troops.borrow_mut().modified = false;
-        weapons.borrow_mut().modified = false;
-        map_infos.borrow_mut().modified = false;
-        scripts.borrow_mut().modified = false;
-        for (_, map) in maps.borrow_mut().iter_mut() {
-            map.modified = false;
-        }
         Ok(())
     }
 }
 
+/// A single file written while saving a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SaveStep {
+    Actors,
+    Animations,
+    Armors,
+    Classes,
+    CommonEvents,
+    Enemies,
+    Items,
+    Skills,
+    States,
+    Tilesets,
+    Troops,
+    Weapons,
+    MapInfos,
+    Scripts,
+    Map(usize),
+    System,
+    Config,
+}
+
+impl std::fmt::Display for SaveStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Map(id) => write!(f, "Map{id:0>3}"),
+            Self::Config => f.write_str("project config"),
+            step => write!(f, "{step:?}"),
+        }
+    }
+}
+
+impl SaveStep {
+    /// The structure written by this step, if any.
+    fn structure(self) -> Option<Structure> {
+        Some(match self {
+            Self::Actors => Structure::Actors,
+            Self::Animations => Structure::Animations,
+            Self::Armors => Structure::Armors,
+            Self::Classes => Structure::Classes,
+            Self::CommonEvents => Structure::CommonEvents,
+            Self::Enemies => Structure::Enemies,
+            Self::Items => Structure::Items,
+            Self::Skills => Structure::Skills,
+            Self::States => Structure::States,
+            Self::Tilesets => Structure::Tilesets,
+            Self::Troops => Structure::Troops,
+            Self::Weapons => Structure::Weapons,
+            Self::MapInfos => Structure::MapInfos,
+            Self::Scripts => Structure::Scripts,
+            Self::Map(id) => Structure::Map(id),
+            Self::System => Structure::System,
+            Self::Config => return None,
+        })
+    }
+}
+
+/// One of the data files held in the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Structure {
+    Actors,
+    Animations,
+    Armors,
+    Classes,
+    CommonEvents,
+    Enemies,
+    Items,
+    Skills,
+    States,
+    Tilesets,
+    Troops,
+    Weapons,
+    MapInfos,
+    Scripts,
+    System,
+    Map(usize),
+}
+
+impl Structure {
+    /// Every structure that isn't a map.
+    pub const ALL: [Self; 15] = [
+        Self::Actors,
+        Self::Animations,
+        Self::Armors,
+        Self::Classes,
+        Self::CommonEvents,
+        Self::Enemies,
+        Self::Items,
+        Self::Skills,
+        Self::States,
+        Self::Tilesets,
+        Self::Troops,
+        Self::Weapons,
+        Self::MapInfos,
+        Self::Scripts,
+        Self::System,
+    ];
+}
+
+impl std::fmt::Display for Structure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommonEvents => f.write_str("Common Events"),
+            Self::MapInfos => f.write_str("Map Tree"),
+            Self::Map(id) => write!(f, "Map {id:0>3}"),
+            structure => write!(f, "{structure:?}"),
+        }
+    }
+}
+
+/// When a structure in the cache last matched its file, and when it stopped matching.
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamps {
+    /// When the structure was last loaded from or saved to disk.
+    pub on_disk: web_time::Instant,
+    /// When the structure was first modified after that, if it has been.
+    pub modified_since: Option<web_time::Instant>,
+}
+
+/// A copy of a structure taken while saving, along with how to serialize it.
+struct Snapshot {
+    path: camino::Utf8PathBuf,
+    serialize: Box<dyn FnOnce() -> color_eyre::Result<Vec<u8>> + Send>,
+}
+
+impl Snapshot {
+    fn new(
+        path: camino::Utf8PathBuf,
+        write: impl FnOnce(&mut Vec<u8>) -> color_eyre::Result<()> + Send + 'static,
+    ) -> Self {
+        Self {
+            path,
+            serialize: Box::new(move || {
+                let mut buffer = Vec::new();
+                write(&mut buffer)?;
+                Ok(buffer)
+            }),
+        }
+    }
+}
+
+/// A file of a save that is being serialized in the background.
+struct InFlight {
+    step: SaveStep,
+    path: camino::Utf8PathBuf,
+    bytes: poll_promise::Promise<color_eyre::Result<Vec<u8>>>,
+}
+
+/// A save that is written a few files at a time by [`Data::continue_save`],
+/// so saving a large project doesn't freeze the editor.
+pub struct SaveTask {
+    steps: VecDeque<SaveStep>,
+    in_flight: VecDeque<InFlight>,
+    total: usize,
+    force: bool,
+    wrote_any: bool,
+    cancelled: bool,
+}
+
+impl SaveTask {
+    /// How far along the save is, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.
+        } else {
+            let remaining = self.steps.len() + self.in_flight.len();
+            (self.total - remaining) as f32 / self.total as f32
+        }
+    }
+
+    /// The name of the file that will be written next, if any.
+    pub fn next_file(&self) -> Option<String> {
+        self.in_flight
+            .front()
+            .map(|file| file.step)
+            .or_else(|| self.steps.front().copied())
+            .map(|step| step.to_string())
+    }
+
+    /// Stops the save before the next file is written.
+    /// Files that were already written are kept, and everything else stays marked as modified.
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
 macro_rules! nested_ref_getter {
     ($($typ:ty, $name:ident),* $(,)?) => {
         $(
@@ -402,6 +963,227 @@ impl Data {
         rpg::Weapons, weapons,
     }
 
+    /// Whether `structure` was modified since it was last loaded or saved.
+    /// `None` if it isn't loaded or is currently borrowed.
+    fn check_modified(&self, structure: Structure) -> Option<bool> {
+        let Self::Loaded {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            system,
+            tilesets,
+            troops,
+            weapons,
+            maps,
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        macro_rules! modified {
+            ($field:ident) => {
+                $field.try_borrow().ok().map(|data| data.modified)
+            };
+        }
+
+        match structure {
+            Structure::Actors => modified!(actors),
+            Structure::Animations => modified!(animations),
+            Structure::Armors => modified!(armors),
+            Structure::Classes => modified!(classes),
+            Structure::CommonEvents => modified!(common_events),
+            Structure::Enemies => modified!(enemies),
+            Structure::Items => modified!(items),
+            Structure::Skills => modified!(skills),
+            Structure::States => modified!(states),
+            Structure::Tilesets => modified!(tilesets),
+            Structure::Troops => modified!(troops),
+            Structure::Weapons => modified!(weapons),
+            Structure::MapInfos => modified!(map_infos),
+            Structure::Scripts => modified!(scripts),
+            Structure::System => modified!(system),
+            Structure::Map(id) => maps.try_borrow().ok()?.get(&id).map(|map| map.modified),
+        }
+    }
+
+    /// Marks `structures` as matching their files as of now.
+    fn record_loaded(&self, structures: impl IntoIterator<Item = Structure>) {
+        let Self::Loaded { timestamps, .. } = self else {
+            return;
+        };
+        let now = web_time::Instant::now();
+        let mut timestamps = timestamps.borrow_mut();
+        for structure in structures {
+            timestamps.insert(
+                structure,
+                Timestamps {
+                    on_disk: now,
+                    modified_since: None,
+                },
+            );
+        }
+    }
+
+    /// Notes which structures became modified or were saved since the last call.
+    /// Should be called once per frame, while nothing in the cache is borrowed.
+    pub fn track_modifications(&self) {
+        let Self::Loaded {
+            maps, timestamps, ..
+        } = self
+        else {
+            return;
+        };
+
+        let map_ids = maps
+            .try_borrow()
+            .map(|maps| maps.keys().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let now = web_time::Instant::now();
+        let mut timestamps = timestamps.borrow_mut();
+        for structure in Structure::ALL
+            .into_iter()
+            .chain(map_ids.into_iter().map(Structure::Map))
+        {
+            let Some(modified) = self.check_modified(structure) else {
+                continue;
+            };
+            let entry = timestamps.entry(structure).or_insert(Timestamps {
+                on_disk: now,
+                modified_since: None,
+            });
+            match (modified, entry.modified_since) {
+                (true, None) => entry.modified_since = Some(now),
+                (false, Some(_)) => entry.modified_since = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `structure` has unsaved changes, as of the last [`Data::track_modifications`].
+    pub fn is_modified(&self, structure: Structure) -> bool {
+        match self {
+            Self::Loaded { timestamps, .. } => timestamps
+                .borrow()
+                .get(&structure)
+                .is_some_and(|t| t.modified_since.is_some()),
+            Self::Unloaded => false,
+        }
+    }
+
+    /// Every structure with unsaved changes, in save order.
+    pub fn modified_structures(&self) -> Vec<(Structure, Timestamps)> {
+        let Self::Loaded { timestamps, .. } = self else {
+            return Vec::new();
+        };
+        let mut modified = timestamps
+            .borrow()
+            .iter()
+            .filter(|(_, t)| t.modified_since.is_some())
+            .map(|(&structure, &t)| (structure, t))
+            .collect::<Vec<_>>();
+        modified.sort_unstable_by_key(|&(structure, _)| structure);
+        modified
+    }
+
+    /// Reads `structure` from disk again, replacing what's in the cache.
+    /// Unsaved changes to it are discarded and its modified flag is cleared.
+    ///
+    /// This doesn't let open editors know. [`crate::UpdateState::reload_data`] does.
+    pub fn reload(
+        &self,
+        structure: Structure,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let handler = data_formats::Handler::new(config.project.data_format);
+
+        let Self::Loaded {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            system,
+            tilesets,
+            troops,
+            weapons,
+            maps,
+            ..
+        } = self
+        else {
+            panic!("project not loaded")
+        };
+
+        macro_rules! reload {
+            ($type:ident, $field:ident) => {
+                *$field.borrow_mut() = load!(filesystem, $type, handler).into_inner()
+            };
+        }
+
+        match structure {
+            Structure::Actors => reload!(Actors, actors),
+            Structure::Animations => reload!(Animations, animations),
+            Structure::Armors => reload!(Armors, armors),
+            Structure::Classes => reload!(Classes, classes),
+            Structure::CommonEvents => reload!(CommonEvents, common_events),
+            Structure::Enemies => reload!(Enemies, enemies),
+            Structure::Items => reload!(Items, items),
+            Structure::Skills => reload!(Skills, skills),
+            Structure::States => reload!(States, states),
+            Structure::Tilesets => reload!(Tilesets, tilesets),
+            Structure::Troops => reload!(Troops, troops),
+            Structure::Weapons => reload!(Weapons, weapons),
+            Structure::MapInfos => {
+                *map_infos.borrow_mut() = rpg::MapInfos {
+                    data: handler
+                        .read_data(filesystem, "MapInfos")
+                        .wrap_err("While reading MapInfos")?,
+                    ..Default::default()
+                }
+            }
+            Structure::Scripts => {
+                *scripts.borrow_mut() = rpg::Scripts {
+                    data: handler
+                        .read_data(filesystem, &config.project.scripts_path)
+                        .wrap_err("While reading scripts")?,
+                    ..Default::default()
+                }
+            }
+            Structure::System => {
+                let mut data = handler
+                    .read_data::<rpg::System>(filesystem, "System")
+                    .wrap_err("While reading System")?;
+                data.magic_number = rand::random();
+                *system.borrow_mut() = data;
+            }
+            Structure::Map(id) => {
+                let map = handler
+                    .read_data(filesystem, format!("Map{id:0>3}"))
+                    .wrap_err_with(|| format!("While reading map {id:0>3}"))?;
+                maps.borrow_mut().insert(id, map);
+            }
+        }
+
+        self.record_loaded([structure]);
+        Ok(())
+    }
+
     /// Load a map.
     #[allow(clippy::panic)]
     pub fn get_or_load_map(
@@ -414,6 +1196,9 @@ impl Data {
             Self::Loaded { maps, .. } => maps.borrow_mut(),
             Self::Unloaded => panic!("project not loaded"),
         };
+        if !maps_ref.contains_key(&id) {
+            self.record_loaded([Structure::Map(id)]);
+        }
         RefMut::map(maps_ref, |maps| {
             // FIXME
             maps.entry(id).or_insert_with(|| {
@@ -433,3 +1218,205 @@ impl Data {
         RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A project made of the default data, saved to a temporary directory.
+    fn saved_project() -> (
+        tempfile::TempDir,
+        luminol_filesystem::host::FileSystem,
+        luminol_config::project::Config,
+        Data,
+    ) {
+        saved_project_in(luminol_config::DataFormat::Marshal)
+    }
+
+    fn saved_project_in(
+        data_format: luminol_config::DataFormat,
+    ) -> (
+        tempfile::TempDir,
+        luminol_filesystem::host::FileSystem,
+        luminol_config::project::Config,
+        Data,
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("Data")).unwrap();
+        std::fs::create_dir(dir.path().join(".luminol")).unwrap();
+
+        let root = camino::Utf8Path::from_path(dir.path()).unwrap();
+        let filesystem = luminol_filesystem::host::FileSystem::new(root);
+        let config =
+            luminol_config::project::Config::from_project(luminol_config::project::Project {
+                project_name: "Reload".to_string(),
+                data_format,
+                ..Default::default()
+            });
+
+        let mut data = Data::from_defaults();
+        data.save_all(&filesystem, &config).unwrap();
+        data.track_modifications();
+
+        (dir, filesystem, config, data)
+    }
+
+    #[test]
+    fn reload_discards_unsaved_edits() {
+        let (_dir, filesystem, config, data) = saved_project();
+
+        {
+            let mut items = data.items();
+            items.data[0].name = "Unsaved potion".to_string();
+            items.modified = true;
+        }
+        data.track_modifications();
+        assert!(data.is_modified(Structure::Items));
+
+        data.reload(Structure::Items, &filesystem, &config).unwrap();
+        let items = data.items();
+        assert_eq!(items.data.len(), 1);
+        assert_eq!(items.data[0].name, rpg::Item::default().name);
+        assert!(!items.modified);
+        drop(items);
+
+        assert!(!data.is_modified(Structure::Items));
+        data.track_modifications();
+        assert!(!data.is_modified(Structure::Items));
+    }
+
+    #[test]
+    fn reload_leaves_other_structures_alone() {
+        let (_dir, filesystem, config, data) = saved_project();
+
+        data.items().data[0].name = "Unsaved potion".to_string();
+        data.items().modified = true;
+        data.weapons().data[0].name = "Unsaved sword".to_string();
+        data.weapons().modified = true;
+        data.track_modifications();
+
+        data.reload(Structure::Items, &filesystem, &config).unwrap();
+        data.track_modifications();
+
+        assert_eq!(data.weapons().data[0].name, "Unsaved sword");
+        assert!(data.weapons().modified);
+        assert!(data.is_modified(Structure::Weapons));
+        assert!(!data.is_modified(Structure::Items));
+    }
+
+    #[test]
+    fn reload_map_discards_unsaved_edits() {
+        let (_dir, filesystem, config, data) = saved_project();
+
+        {
+            let mut map = data.get_map(1);
+            map.autoplay_bgm = !map.autoplay_bgm;
+            map.modified = true;
+        }
+        data.track_modifications();
+        assert!(data.is_modified(Structure::Map(1)));
+
+        data.reload(Structure::Map(1), &filesystem, &config)
+            .unwrap();
+        let map = data.get_map(1);
+        assert_eq!(map.autoplay_bgm, rpg::Map::default().autoplay_bgm);
+        assert!(!map.modified);
+        drop(map);
+        assert!(!data.is_modified(Structure::Map(1)));
+    }
+
+    #[test]
+    fn failed_save_keeps_unwritten_structures_modified() {
+        let (dir, filesystem, config, mut data) = saved_project();
+
+        data.items().data[0].name = "Saved potion".to_string();
+        data.items().modified = true;
+        data.weapons().data[0].name = "Unsaved sword".to_string();
+        data.weapons().modified = true;
+
+        // Weapons are written after Items, and can't be written over a folder
+        let weapons = dir.path().join("Data/Weapons.rxdata");
+        std::fs::remove_file(&weapons).unwrap();
+        std::fs::create_dir(&weapons).unwrap();
+
+        assert!(data.save(&filesystem, &config).is_err());
+        assert!(!data.items().modified);
+        assert!(data.weapons().modified);
+        // Items was written, so System still has to be written with a new magic number
+        assert!(data.system().modified);
+    }
+
+    /// The contents of every file in the Data folder, by name.
+    fn data_files(dir: &tempfile::TempDir) -> std::collections::BTreeMap<String, Vec<u8>> {
+        std::fs::read_dir(dir.path().join("Data"))
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let name = entry.file_name().into_string().unwrap();
+                (name, std::fs::read(entry.path()).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn load_then_save_is_byte_identical() {
+        use luminol_config::DataFormat;
+
+        for format in [
+            DataFormat::Marshal,
+            DataFormat::Ron { pretty: false },
+            DataFormat::Ron { pretty: true },
+            DataFormat::Json { pretty: false },
+            DataFormat::Json { pretty: true },
+        ] {
+            let (dir, filesystem, mut config, _) = saved_project_in(format);
+            let saved = data_files(&dir);
+
+            let mut data = Data::default();
+            data.load(&filesystem, &mut crate::Toasts::default(), &mut config)
+                .unwrap();
+            assert_eq!(config.project.data_format, format);
+            data.save_all(&filesystem, &config).unwrap();
+            let resaved = data_files(&dir);
+
+            assert_eq!(
+                saved.keys().collect::<Vec<_>>(),
+                resaved.keys().collect::<Vec<_>>()
+            );
+            for (name, bytes) in &saved {
+                // System gets a new magic number every time it's saved, so it can't match
+                if name.starts_with("System.") {
+                    continue;
+                }
+                assert!(
+                    *bytes == resaved[name],
+                    "{name} changed after loading and saving it as {format:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn load_detects_the_format_the_data_is_in() {
+        use luminol_config::DataFormat;
+
+        let (dir, filesystem, mut config, _) = saved_project_in(DataFormat::Json { pretty: true });
+        let saved = data_files(&dir);
+
+        // The config says Marshal, like one from before the project was converted
+        config.project.data_format = DataFormat::Marshal;
+        let mut data = Data::default();
+        data.load(&filesystem, &mut crate::Toasts::default(), &mut config)
+            .unwrap();
+        assert_eq!(
+            config.project.data_format,
+            DataFormat::Json { pretty: true }
+        );
+
+        data.save_all(&filesystem, &config).unwrap();
+        let resaved = data_files(&dir);
+        assert!(resaved.keys().all(|name| saved.contains_key(name)));
+        assert_eq!(saved["Actors.json"], resaved["Actors.json"]);
+        assert_eq!(saved["MapInfos.json"], resaved["MapInfos.json"]);
+    }
+}
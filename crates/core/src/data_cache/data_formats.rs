@@ -401,3 +401,62 @@ pub fn format_traced_error(
     }
     error
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use luminol_data::{helpers::SortedMap, rpg};
+
+    fn map_infos(
+        ids: impl Iterator<Item = usize>,
+    ) -> std::collections::HashMap<usize, rpg::MapInfo> {
+        ids.map(|id| {
+            let info = rpg::MapInfo {
+                name: format!("MAP{id:03}"),
+                parent_id: id / 4,
+                order: id as i32,
+                expanded: id % 2 == 0,
+                scroll_x: id as i32 * 16,
+                scroll_y: -(id as i32),
+            };
+            (id, info)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn map_infos_save_is_byte_identical() {
+        for format in [
+            DataFormat::Marshal,
+            DataFormat::Ron { pretty: false },
+            DataFormat::Ron { pretty: true },
+            DataFormat::Json { pretty: false },
+            DataFormat::Json { pretty: true },
+        ] {
+            let handler = Handler::new(format);
+
+            // Inserting in a different order gives the hash maps different iteration orders
+            let mut first = Vec::new();
+            handler
+                .write_data_to(&SortedMap(&map_infos(1..=64)), &mut first)
+                .unwrap();
+            let mut second = Vec::new();
+            handler
+                .write_data_to(&SortedMap(&map_infos((1..=64).rev())), &mut second)
+                .unwrap();
+            assert_eq!(
+                first, second,
+                "{format:?} output depends on insertion order"
+            );
+
+            // Loading what was saved and saving it again must not change a single byte
+            let reloaded: std::collections::HashMap<usize, rpg::MapInfo> =
+                handler.read_data_from(&first).unwrap();
+            let mut resaved = Vec::new();
+            handler
+                .write_data_to(&SortedMap(&reloaded), &mut resaved)
+                .unwrap();
+            assert_eq!(first, resaved, "{format:?} output changed after a reload");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Support for adding windows and tabs to Luminol from other crates.
+//!
+//! Luminol doesn't load plugins at runtime, but forks and custom builds can link extra crates that
+//! implement [`LuminolExtension`] and register them with [`register_extension`] before the app
+//! starts (Luminol's launcher does this in `main`). Every registered extension gets a submenu in the
+//! "Extensions" menu of the top bar, listing its [`MenuEntry`]s.
+//!
+//! See the `luminol-extension-example` crate for a complete example.
+
+use std::sync::{RwLock, RwLockReadGuard};
+
+/// Creates a window when a menu entry is clicked.
+pub type WindowFactory = fn(&mut crate::UpdateState<'_>) -> Box<dyn crate::Window>;
+/// Creates a tab when a menu entry is clicked.
+pub type TabFactory = fn(&mut crate::UpdateState<'_>) -> Box<dyn crate::Tab>;
+
+static EXTENSIONS: RwLock<Vec<Box<dyn LuminolExtension>>> = RwLock::new(Vec::new());
+
+/// An extension that adds its own windows and tabs to Luminol.
+pub trait LuminolExtension: Send + Sync {
+    /// The name of this extension, shown as the title of its submenu.
+    fn name(&self) -> &str;
+
+    /// The entries this extension adds to its submenu.
+    fn menu_entries(&self) -> Vec<MenuEntry>;
+}
+
+/// A button in an extension's submenu.
+pub struct MenuEntry {
+    pub label: String,
+    /// If this is true, the entry is disabled while no project is open.
+    pub requires_project: bool,
+    pub open: Open,
+}
+
+/// What to open when a [`MenuEntry`] is clicked.
+pub enum Open {
+    Window(WindowFactory),
+    Tab(TabFactory),
+}
+
+impl MenuEntry {
+    pub fn window(label: impl Into<String>, factory: WindowFactory) -> Self {
+        Self {
+            label: label.into(),
+            requires_project: false,
+            open: Open::Window(factory),
+        }
+    }
+
+    pub fn tab(label: impl Into<String>, factory: TabFactory) -> Self {
+        Self {
+            label: label.into(),
+            requires_project: false,
+            open: Open::Tab(factory),
+        }
+    }
+
+    /// Disables this entry while no project is open.
+    pub fn requires_project(mut self) -> Self {
+        self.requires_project = true;
+        self
+    }
+
+    /// Creates the window or tab for this entry and adds it to the UI.
+    pub fn open(&self, update_state: &mut crate::UpdateState<'_>) {
+        match self.open {
+            Open::Window(factory) => {
+                let window = factory(update_state);
+                update_state.edit_windows.add_boxed_window(window);
+            }
+            Open::Tab(factory) => {
+                let tab = factory(update_state);
+                update_state.edit_tabs.add_boxed_tab(tab);
+            }
+        }
+    }
+}
+
+/// Registers an extension. This should be called before the app is started.
+pub fn register_extension(extension: impl LuminolExtension + 'static) {
+    EXTENSIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Box::new(extension));
+}
+
+/// Returns all registered extensions, in the order they were registered in.
+pub fn extensions() -> RwLockReadGuard<'static, Vec<Box<dyn LuminolExtension>>> {
+    EXTENSIONS.read().unwrap_or_else(|e| e.into_inner())
+}
@@ -22,20 +22,29 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use color_eyre::eyre::WrapErr;
+use luminol_filesystem::FileSystem;
 use std::sync::Arc;
 pub use tracing;
 
 mod tab;
 pub use tab::{EditTabs, Tab, Tabs};
 
-mod window;
+pub mod window;
 pub use window::{EditWindows, Window, Windows};
 
 pub mod modal;
-pub use modal::Modal;
+pub use modal::{Confirmation, Modal};
+
+pub mod extension;
+pub use extension::{register_extension, LuminolExtension};
 
 mod data_cache;
-pub use data_cache::Data;
+pub use data_cache::{Data, SaveTask, Structure, Timestamps};
+
+pub mod asset_references;
+
+pub mod validation;
 
 /// Toasts to be displayed for errors, information, etc.
 mod toasts;
@@ -45,6 +54,12 @@ pub mod project_manager;
 pub use project_manager::spawn_future;
 pub use project_manager::ProjectManager;
 
+pub mod tasks;
+pub use tasks::{Task, TaskManager};
+
+mod tile_clipboard;
+pub use tile_clipboard::TileClipboard;
+
 pub use alox_48;
 pub use data_cache::data_formats::{self, format_traced_error};
 
@@ -56,6 +71,9 @@ pub mod prelude {
     pub use luminol_graphics::*;
 }
 
+/// How long writing the files serialized by a save may block the UI for each frame.
+const SAVE_FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(12);
+
 static GIT_REVISION: once_cell::sync::OnceCell<&'static str> = once_cell::sync::OnceCell::new();
 
 pub fn set_git_revision(revision: &'static str) {
@@ -83,10 +101,13 @@ pub struct UpdateState<'res> {
     pub global_config: &'res mut luminol_config::global::Config,
 
     pub toolbar: &'res mut ToolbarState,
+    /// The places the user last made changes in, for the recent edits switcher
+    pub recent_edits: &'res mut luminol_config::recent_edits::RecentEdits,
 
     pub modified: ModifiedState,
     pub modified_during_prev_frame: &'res mut bool,
     pub project_manager: &'res mut ProjectManager,
+    pub tasks: &'res mut TaskManager,
 
     pub build_diagnostics: &'static BuildDiagnostics,
 }
@@ -172,6 +193,24 @@ pub struct ToolbarState {
     pub brush_density: f32,
     /// Whether or not brush tile ID randomization is active.
     pub brush_random: bool,
+    /// Width of the area drawn by the pen and smart tiles, in tiles, between 1 and 10 inclusive.
+    pub brush_size: usize,
+    /// Whether the pen and smart tiles draw a circle instead of a square when the brush size is
+    /// more than 1.
+    pub brush_round: bool,
+    /// Whether the rectangle and circle pencils only draw the border of their shape.
+    pub shape_hollow: bool,
+    /// Whether the fill pencil replaces every matching tile on the layer instead of only the
+    /// contiguous ones.
+    pub fill_global: bool,
+    /// Index of the project's brush shape used by [`Pencil::Custom`].
+    pub brush_shape: usize,
+    /// Index of the project's smart tile used by [`Pencil::Smart`].
+    pub smart_tile: usize,
+    /// Which axes the brushes mirror the tiles they draw across.
+    pub symmetry: Symmetry,
+    /// The tiles copied with the select tool, which can be pasted into any open map.
+    pub tile_clipboard: Option<Arc<TileClipboard>>,
 }
 
 #[derive(Default, strum::EnumIter, strum::Display, PartialEq, Eq, Clone, Copy)]
@@ -179,9 +218,52 @@ pub struct ToolbarState {
 pub enum Pencil {
     #[default]
     Pen,
+    /// Draws a straight line from where the mouse was pressed to where it is now
+    Line,
     Circle,
     Rectangle,
     Fill,
+    /// Toggles the quarter-tile shadows of RPG Maker VX Ace maps
+    Shadow,
+    /// Stamps one of the brush shapes stored in the project config
+    #[strum(to_string = "Custom shape")]
+    Custom,
+    /// Draws one of the smart tiles stored in the project config, connecting it to its neighbors
+    #[strum(to_string = "Smart tile")]
+    Smart,
+    /// Selects a rectangle of tiles to copy and paste
+    Select,
+}
+
+/// The mirror painting mode. The axes go through the center of the map unless the map tab has
+/// moved them.
+#[derive(Default, strum::EnumIter, strum::Display, PartialEq, Eq, Clone, Copy)]
+pub enum Symmetry {
+    /// Brushes only draw where they're used
+    #[default]
+    #[strum(to_string = "No mirroring")]
+    Off,
+    /// Mirrors the left and right halves of the map
+    #[strum(to_string = "Mirror left/right")]
+    Horizontal,
+    /// Mirrors the top and bottom halves of the map
+    #[strum(to_string = "Mirror top/bottom")]
+    Vertical,
+    /// Mirrors all four quarters of the map
+    #[strum(to_string = "Mirror both ways")]
+    Both,
+}
+
+impl Symmetry {
+    /// Whether tiles are mirrored across the vertical axis.
+    pub fn mirrors_x(self) -> bool {
+        matches!(self, Self::Horizontal | Self::Both)
+    }
+
+    /// Whether tiles are mirrored across the horizontal axis.
+    pub fn mirrors_y(self) -> bool {
+        matches!(self, Self::Vertical | Self::Both)
+    }
 }
 
 impl Default for ToolbarState {
@@ -190,11 +272,56 @@ impl Default for ToolbarState {
             pencil: Default::default(),
             brush_density: 1.,
             brush_random: false,
+            brush_size: 1,
+            brush_round: false,
+            shape_hollow: false,
+            fill_global: false,
+            brush_shape: 0,
+            smart_tile: 0,
+            symmetry: Symmetry::Off,
+            tile_clipboard: None,
         }
     }
 }
 
+impl ToolbarState {
+    /// The largest brush size the toolbar allows.
+    pub const MAX_BRUSH_SIZE: usize = 10;
+
+    /// Returns the offsets from the cursor of the tiles covered by the brush, row by row. The
+    /// cursor is at the center of the brush, or just above and to the left of it for even sizes.
+    pub fn brush_offsets(&self) -> impl Iterator<Item = (i32, i32)> {
+        let size = self.brush_size.clamp(1, Self::MAX_BRUSH_SIZE) as i32;
+        let start = -((size - 1) / 2);
+        let center = start as f32 + (size - 1) as f32 / 2.;
+        // Slightly less than the radius so that small circles don't come out as squares
+        let max_distance_squared = ((size as f32 / 2.).powi(2) - 0.5).max(0.);
+        let round = self.brush_round;
+
+        (start..start + size)
+            .flat_map(move |y| (start..start + size).map(move |x| (x, y)))
+            .filter(move |&(x, y)| {
+                !round
+                    || (x as f32 - center).powi(2) + (y as f32 - center).powi(2)
+                        <= max_distance_squared
+            })
+    }
+}
+
 impl<'res> UpdateState<'res> {
+    /// Asks to perform a destructive action through `confirmation`.
+    ///
+    /// Returns `true` if the action can be performed right away, which is the case if the user
+    /// chose not to be asked about it again. Otherwise, the confirmation modal is opened and
+    /// [`Confirmation::show`] will return the action once the user has confirmed it.
+    pub fn confirm_destructive(
+        &self,
+        confirmation: &mut Confirmation,
+        action: luminol_config::global::DestructiveAction,
+    ) -> bool {
+        confirmation.request(self.global_config, action)
+    }
+
     pub(crate) fn reborrow_with_edit_window<'this>(
         &'this mut self,
         edit_windows: &'this mut window::EditWindows,
@@ -212,9 +339,11 @@ impl<'res> UpdateState<'res> {
             project_config: self.project_config,
             global_config: self.global_config,
             toolbar: self.toolbar,
+            recent_edits: self.recent_edits,
             modified: self.modified.clone(),
             modified_during_prev_frame: self.modified_during_prev_frame,
             project_manager: self.project_manager,
+            tasks: self.tasks,
             build_diagnostics: self.build_diagnostics,
         }
     }
@@ -236,9 +365,11 @@ impl<'res> UpdateState<'res> {
             project_config: self.project_config,
             global_config: self.global_config,
             toolbar: self.toolbar,
+            recent_edits: self.recent_edits,
             modified: self.modified.clone(),
             modified_during_prev_frame: self.modified_during_prev_frame,
             project_manager: self.project_manager,
+            tasks: self.tasks,
             build_diagnostics: self.build_diagnostics,
         }
     }
@@ -249,7 +380,8 @@ impl<'res> UpdateState<'res> {
         let mut should_run_closure = false;
         let mut should_focus_save_button = false;
 
-        if self.project_manager.closure.is_some() {
+        // the closure will be run once the save started by the unsaved changes modal finishes
+        if self.project_manager.closure.is_some() && self.project_manager.save_task.is_none() {
             if !self.modified.get() {
                 should_close = true;
                 should_run_closure = true;
@@ -296,31 +428,347 @@ impl<'res> UpdateState<'res> {
 
         if should_close {
             if should_save {
-                match self
-                    .data
-                    .save(self.filesystem, self.project_config.as_ref().unwrap())
-                {
-                    Ok(_) => {
-                        self.modified.set(false);
-                        info!(self.toasts, "Saved project successfully!");
-                    }
-                    Err(e) => {
-                        should_run_closure = false;
-                        error!(self.toasts, e.wrap_err("Error saving project"));
+                if self.can_save() {
+                    // keep the closure around until the save has finished
+                    self.project_manager.save_task = Some(self.data.start_save(false));
+                    self.project_manager.run_closure_after_save = true;
+                } else {
+                    self.project_manager.closure = None;
+                }
+            } else {
+                if should_run_closure {
+                    if let Some(closure) = self.project_manager.closure.take() {
+                        closure(self);
                     }
                 }
+
+                self.project_manager.closure = None;
             }
+        }
 
-            if should_run_closure {
-                if let Some(closure) = self.project_manager.closure.take() {
-                    closure(self);
-                }
+        // the save progress window should only be shown once per frame
+        if show_modal {
+            self.handle_saving();
+            self.handle_project_lock();
+        }
+        self.handle_project_loading();
+    }
+
+    /// Starts saving the project in the background, unless a save is already in progress.
+    ///
+    /// If `force` is true, every file is written regardless of whether or not it has been modified.
+    pub fn save_project(&mut self, force: bool) {
+        if self.project_config.is_some()
+            && self.project_manager.save_task.is_none()
+            && self.can_save()
+        {
+            self.project_manager.save_task = Some(self.data.start_save(force));
+            self.project_manager.run_closure_after_save = false;
+        }
+    }
+
+    /// Saves the project once the autosave interval has passed since it was first modified, if
+    /// autosaving is turned on. Should be called every frame, after the windows and tabs.
+    ///
+    /// Autosaving waits while a modal, a file picker or another save is in progress, and while a
+    /// project is waiting to be opened or closed.
+    pub fn autosave(&mut self) {
+        let Some(interval) = self.global_config.autosave_interval_secs else {
+            self.project_manager.autosave_time = None;
+            return;
+        };
+        if self.project_config.is_none() || !self.modified.get() || self.project_manager.read_only {
+            self.project_manager.autosave_time = None;
+            return;
+        }
+
+        let now = self.ctx.input(|i| i.time);
+        let time = *self
+            .project_manager
+            .autosave_time
+            .get_or_insert(now + interval as f64);
+        if now < time {
+            self.ctx
+                .request_repaint_after(std::time::Duration::from_secs_f64(time - now));
+            return;
+        }
+
+        if self.project_manager.is_modal_open()
+            || self.project_manager.is_picker_open()
+            || self.project_manager.is_saving()
+            || self.project_manager.closure.is_some()
+        {
+            return;
+        }
+        self.project_manager.autosave_time = None;
+        if !self.can_save() {
+            return;
+        }
+
+        let config = self.project_config.as_ref().expect("project not loaded");
+        match self.data.save(self.filesystem, config) {
+            Ok(()) => {
+                self.modified.set(false);
+                info!(self.toasts, "Autosaved project");
             }
+            Err(e) => error!(self.toasts, e.wrap_err("Error autosaving project")),
+        }
+    }
 
-            self.project_manager.closure = None;
+    /// Writes only the modified maps right away, without the slower full save of the project.
+    pub fn save_modified_maps(&mut self) {
+        if self.project_config.is_none()
+            || self.project_manager.save_task.is_some()
+            || !self.can_save()
+        {
+            return;
         }
+        let config = self.project_config.as_ref().expect("project not loaded");
+
+        let (count, errors) = self.data.save_modified_maps(self.filesystem, config);
+        match count {
+            0 if errors.is_empty() => info!(self.toasts, "No maps have been modified"),
+            0 => {}
+            1 => info!(self.toasts, "Saved 1 map"),
+            count => info!(self.toasts, format!("Saved {count} maps")),
+        }
+        for e in errors {
+            error!(self.toasts, e.wrap_err("Error saving map"));
+        }
+    }
 
-        self.handle_project_loading();
+    /// Reads `structure` from disk again, discarding unsaved changes to it, and lets open editors
+    /// know so they can refresh anything they built from the old data.
+    pub fn reload_data(&mut self, structure: Structure) -> color_eyre::Result<()> {
+        let config = self.project_config.as_ref().expect("project not loaded");
+        self.data.reload(structure, self.filesystem, config)?;
+
+        // combo boxes and search lists rebuild their caches when this is set
+        *self.modified_during_prev_frame = true;
+        self.modified.set_this_frame(true);
+
+        self.ctx.data_mut(|d| {
+            *d.get_temp_mut_or_default::<u64>(reload_count_id(structure)) += 1;
+        });
+        Ok(())
+    }
+
+    /// Returns whether or not the project may be saved, warning the user if it's read-only.
+    fn can_save(&mut self) -> bool {
+        let lock_stolen = self
+            .project_manager
+            .lock
+            .as_ref()
+            .is_some_and(|lock| !lock.is_held(self.filesystem));
+        if lock_stolen {
+            self.project_manager.lock = None;
+            self.project_manager.read_only = true;
+            warn!(
+                self.toasts,
+                "Another editor took over this project, so it can't be saved from here anymore"
+            );
+            return false;
+        }
+
+        if self.project_manager.read_only {
+            warn!(
+                self.toasts,
+                "This project was opened read-only because another editor has it open"
+            );
+            return false;
+        }
+
+        true
+    }
+
+    /// Remembers that the user changed something at `location`, so that the recent edits switcher
+    /// can take them back there.
+    pub fn record_edit(&mut self, location: luminol_config::recent_edits::EditLocation) {
+        if !self.recent_edits.record(location) {
+            return;
+        }
+        self.save_recent_edits();
+    }
+
+    /// Writes the recent edits to the project, so they're still there the next time it's opened.
+    pub fn save_recent_edits(&mut self) {
+        let c = "While saving the recent edits";
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let result = ron::ser::to_string_pretty(&*self.recent_edits, pretty_config)
+            .wrap_err(c)
+            .and_then(|recent_edits| {
+                self.filesystem
+                    .write(
+                        luminol_config::recent_edits::RecentEdits::PATH,
+                        recent_edits,
+                    )
+                    .wrap_err(c)
+            });
+        if let Err(e) = result {
+            error!(self.toasts, e);
+        }
+    }
+
+    /// Reads the recent edits of the project that was just opened.
+    fn load_recent_edits(&mut self) {
+        *self.recent_edits = self
+            .filesystem
+            .read_to_string(luminol_config::recent_edits::RecentEdits::PATH)
+            .ok()
+            .and_then(|s| ron::from_str(&s).ok())
+            .unwrap_or_default();
+    }
+
+    /// Locks the project that was just opened, so other editors know it's open.
+    fn lock_project(&mut self) {
+        let Some(config) = self.project_config.as_ref() else {
+            return;
+        };
+        match luminol_filesystem::project_lock::ProjectLock::acquire(
+            self.filesystem,
+            config.project.persistence_id,
+        ) {
+            Ok(lock) => self.project_manager.lock = Some(lock),
+            Err(error) => warn!(
+                self.toasts,
+                format!(
+                    "Could not lock the project, so other editors won't know it's open: {error}"
+                )
+            ),
+        }
+
+        // on web, other tabs answer asynchronously
+        self.ctx
+            .request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    /// Asks the user what to do if another editor had the project open when it was locked.
+    fn handle_project_lock(&mut self) {
+        if let Some(holder) = self
+            .project_manager
+            .lock
+            .as_mut()
+            .and_then(|lock| lock.conflict())
+        {
+            self.project_manager.lock_holder = Some(holder);
+            self.project_manager.lock_modal.open();
+        }
+
+        let Some(holder) = &self.project_manager.lock_holder else {
+            return;
+        };
+
+        let mut read_only = false;
+        let mut steal = false;
+        let mut close = false;
+
+        let modal = &self.project_manager.lock_modal;
+        modal.show(|ui| {
+            modal.title(ui, "Project Already Open");
+            modal.frame(ui, |ui| {
+                modal.body(
+                    ui,
+                    format!(
+                        "This project is already open in {holder}. \
+                        If both of you save, one will overwrite the other's changes."
+                    ),
+                );
+            });
+
+            modal.buttons(ui, |ui| {
+                read_only = modal.suggested_button(ui, "Open Read-Only").clicked();
+                steal = modal.caution_button(ui, "Take Over").clicked();
+                close = modal.button(ui, "Close Project").clicked();
+            });
+        });
+
+        if read_only {
+            self.project_manager.lock = None;
+            self.project_manager.read_only = true;
+            info!(
+                self.toasts,
+                "Opened the project read-only, saving is disabled until it's reopened"
+            );
+        } else if steal {
+            if let Some(lock) = &mut self.project_manager.lock {
+                if let Err(error) = lock.steal(self.filesystem) {
+                    error!(self.toasts, error.wrap_err("Error taking over the project"));
+                }
+            }
+        } else if close {
+            self.close_project();
+        } else {
+            return;
+        }
+        self.project_manager.lock_holder = None;
+    }
+
+    fn handle_saving(&mut self) {
+        let Some(task) = &mut self.project_manager.save_task else {
+            return;
+        };
+        let Some(config) = self.project_config.as_ref() else {
+            self.project_manager.save_task = None;
+            return;
+        };
+
+        egui::Window::new("Saving Project")
+            .id(egui::Id::new("luminol_save_progress"))
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .order(egui::Order::Foreground)
+            .collapsible(false)
+            .resizable(false)
+            .show(self.ctx, |ui| {
+                let text = task
+                    .next_file()
+                    .map_or_else(String::new, |file| format!("Writing {file}"));
+                ui.add(
+                    egui::ProgressBar::new(task.progress())
+                        .desired_width(240.)
+                        .text(text),
+                );
+                ui.vertical_centered(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        task.cancel();
+                    }
+                });
+            });
+
+        let result =
+            self.data
+                .continue_save(task, self.filesystem, config, Some(SAVE_FRAME_BUDGET));
+        let run_closure = match result {
+            Ok(false) => {
+                self.ctx.request_repaint();
+                return;
+            }
+            Ok(true) if task.is_cancelled() => {
+                info!(
+                    self.toasts,
+                    "Save cancelled, some changes have not been saved"
+                );
+                false
+            }
+            Ok(true) => {
+                self.modified.set(false);
+                info!(self.toasts, "Saved project successfully!");
+                true
+            }
+            Err(e) => {
+                error!(self.toasts, e.wrap_err("Error saving project"));
+                false
+            }
+        };
+
+        self.project_manager.save_task = None;
+
+        // only saves started by the unsaved changes modal have to run the closure afterwards
+        if std::mem::take(&mut self.project_manager.run_closure_after_save) {
+            match self.project_manager.closure.take() {
+                Some(closure) if run_closure => closure(self),
+                _ => {}
+            }
+        }
     }
 
     fn handle_project_loading(&mut self) {
@@ -389,15 +837,44 @@ impl<'res> UpdateState<'res> {
                             self.filesystem.project_path().expect("project not open")
                         )
                     );
+                    self.load_recent_edits();
+                    self.lock_project();
                 }
             }
             Some(Err(error)) => {
+                let is_vx_ace = matches!(
+                    error.root_cause().downcast_ref(),
+                    Some(luminol_filesystem::Error::UnsupportedRMVer {
+                        version: luminol_config::RMVer::Ace,
+                        ..
+                    })
+                );
                 error!(self.toasts, error.wrap_err("Error opening the project"));
+                if is_vx_ace {
+                    info!(
+                        self.toasts,
+                        "The maps of VX Ace projects can still be looked at with Tools → VX Ace Map Viewer"
+                    );
+                }
                 self.close_project();
             }
             None => {}
         }
 
+        // This is checked every frame so changes made in the project config take effect on
+        // graphics loaded afterwards
+        let transparent_color = self
+            .project_config
+            .as_ref()
+            .and_then(|config| config.project.transparent_color);
+        if self
+            .graphics
+            .texture_loader
+            .set_color_key(transparent_color)
+        {
+            self.graphics.atlas_loader.clear();
+        }
+
         if let Some(p) = self.project_manager.create_project_promise.take() {
             match p.try_take() {
                 Ok(Ok(project_manager::CreateProjectResult {
@@ -416,6 +893,8 @@ impl<'res> UpdateState<'res> {
                             self.close_project();
                             *self.data = data_cache;
                             self.project_config.replace(config);
+                            self.load_recent_edits();
+                            self.lock_project();
                         }
                         Err(error) => {
                             error!(self.toasts, error.wrap_err("Error creating new project"))
@@ -437,13 +916,29 @@ impl<'res> UpdateState<'res> {
         self.audio.clear_sinks(); // audio loads files borrows from the filesystem. unloading while they are playing is a crash
         self.graphics.atlas_loader.clear();
         self.graphics.texture_loader.clear();
+        if let Err(error) = self.project_manager.release_lock(self.filesystem) {
+            error!(self.toasts, error);
+        }
         self.filesystem.unload_project();
         *self.project_config = None;
+        *self.recent_edits = Default::default();
         self.data.unload();
         self.modified.set(false);
     }
 }
 
+/// How many times `structure` has been reloaded by [`UpdateState::reload_data`]. Editors that
+/// build state from the data, like the map editor's tilemap, compare this with the count from
+/// when they built it.
+pub fn reload_count(ctx: &egui::Context, structure: Structure) -> u64 {
+    ctx.data(|d| d.get_temp(reload_count_id(structure)))
+        .unwrap_or_default()
+}
+
+fn reload_count_id(structure: Structure) -> egui::Id {
+    egui::Id::new("luminol_data_reload_count").with(structure)
+}
+
 pub fn slice_is_sorted<T: Ord>(s: &[T]) -> bool {
     s.windows(2).all(|w| {
         let [a, b] = w else { unreachable!() }; // could maybe do unreachable_unchecked
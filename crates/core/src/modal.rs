@@ -22,6 +22,8 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_config::global::{Config, DestructiveAction};
+
 /// A basic trait describing a modal that edits some value.
 pub trait Modal: Sized {
     /// The output type for this modal.
@@ -36,3 +38,91 @@ pub trait Modal: Sized {
 
     fn reset(&mut self, update_state: &mut crate::UpdateState<'_>, data: Self::Data<'_>);
 }
+
+/// A modal asking the user to confirm a destructive action before it is performed.
+///
+/// Whether the modal is shown at all is controlled by the global config, which also remembers the
+/// actions the user doesn't want to be asked about again.
+pub struct Confirmation {
+    modal: egui_modal::Modal,
+    pending: Option<DestructiveAction>,
+    dont_ask_again: bool,
+}
+
+impl Confirmation {
+    pub fn new(ctx: &egui::Context, id_source: impl std::fmt::Display) -> Self {
+        Self {
+            modal: egui_modal::Modal::new(ctx, id_source),
+            pending: None,
+            dont_ask_again: false,
+        }
+    }
+
+    /// Asks to perform `action`.
+    ///
+    /// Returns `true` if the action can be performed right away. Otherwise, the confirmation modal
+    /// is opened and [`Self::show`] will return the action once the user has confirmed it.
+    pub fn request(&mut self, config: &Config, action: DestructiveAction) -> bool {
+        if !config.should_confirm(action) {
+            return true;
+        }
+
+        if self.pending.is_none() {
+            self.pending = Some(action);
+            self.dont_ask_again = false;
+            self.modal.open();
+        }
+        false
+    }
+
+    /// Returns whether or not the confirmation modal is currently open.
+    pub fn is_open(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Shows the confirmation modal if it's open.
+    ///
+    /// Returns the action that was confirmed by the user this frame, if any.
+    pub fn show(&mut self, config: &mut Config) -> Option<DestructiveAction> {
+        let action = self.pending?;
+        let mut confirmed = false;
+        let mut closed = false;
+
+        let modal = &self.modal;
+        let dont_ask_again = &mut self.dont_ask_again;
+        modal.show(|ui| {
+            modal.title(ui, "Are you sure?");
+            modal.frame(ui, |ui| {
+                modal.body(ui, action.prompt());
+                ui.checkbox(dont_ask_again, "Don't ask again");
+            });
+
+            modal.buttons(ui, |ui| {
+                let confirm_button = modal.caution_button(ui, action.to_string());
+                let cancel_button = modal.button(ui, "Cancel");
+
+                if confirm_button.clicked() {
+                    confirmed = true;
+                    closed = true;
+                } else if cancel_button.clicked() {
+                    closed = true;
+                } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    closed = true;
+                    modal.close();
+                }
+            });
+        });
+
+        if closed {
+            self.pending = None;
+        }
+        if !confirmed {
+            return None;
+        }
+
+        if self.dont_ask_again {
+            config.skipped_confirmations.insert(action);
+        }
+        Some(action)
+    }
+}
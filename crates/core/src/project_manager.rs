@@ -25,6 +25,17 @@
 pub struct ProjectManager {
     pub(crate) modal: egui_modal::Modal,
     pub(crate) closure: Option<Box<ProjectManagerClosure>>,
+    pub(crate) save_task: Option<crate::SaveTask>,
+    pub(crate) run_closure_after_save: bool,
+    /// The `egui` time at which the project will be autosaved, set when it is first modified.
+    pub(crate) autosave_time: Option<f64>,
+
+    pub(crate) lock: Option<luminol_filesystem::project_lock::ProjectLock>,
+    pub(crate) lock_modal: egui_modal::Modal,
+    pub(crate) lock_holder: Option<luminol_filesystem::project_lock::Holder>,
+    /// Whether the project was opened read-only because another editor has it open.
+    /// Saving is refused while this is set.
+    pub read_only: bool,
 
     pub create_project_promise: Option<poll_promise::Promise<CreateProjectPromiseResult>>,
     pub load_filesystem_promise: Option<poll_promise::Promise<FileSystemPromiseResult>>,
@@ -65,15 +76,27 @@ impl ProjectManager {
         Self {
             modal: egui_modal::Modal::new(ctx, "luminol_save_modal"),
             closure: None,
+            save_task: None,
+            run_closure_after_save: false,
+            autosave_time: None,
+            lock: None,
+            lock_modal: egui_modal::Modal::new(ctx, "luminol_project_lock_modal"),
+            lock_holder: None,
+            read_only: false,
             create_project_promise: None,
             load_filesystem_promise: None,
             filesystem_open_result: None,
         }
     }
 
-    /// Returns whether or not the unsaved changes modal is currently open.
+    /// Returns whether or not the unsaved changes modal or the project lock modal is currently open.
     pub fn is_modal_open(&self) -> bool {
-        self.modal.is_open()
+        self.modal.is_open() || self.lock_modal.is_open()
+    }
+
+    /// Returns whether or not the project is currently being saved.
+    pub fn is_saving(&self) -> bool {
+        self.save_task.is_some()
     }
 
     /// Returns whether or not a file or filder picker is currently open.
@@ -83,6 +106,20 @@ impl ProjectManager {
             || self.load_filesystem_promise.is_some()
     }
 
+    /// Releases the lock on the current project, if this editor holds it.
+    /// This is done when closing the project, but also has to be done when Luminol exits.
+    pub fn release_lock(
+        &mut self,
+        filesystem: &luminol_filesystem::project::FileSystem,
+    ) -> color_eyre::Result<()> {
+        self.lock_holder = None;
+        self.read_only = false;
+        match self.lock.take() {
+            Some(lock) => lock.release(filesystem),
+            None => Ok(()),
+        }
+    }
+
     /// Runs a closure after asking the user to save unsaved changes.
     pub fn run_custom(&mut self, closure: impl FnOnce(&mut crate::UpdateState<'_>) + 'static) {
         self.closure = Some(Box::new(closure));
@@ -28,6 +28,9 @@ pub struct Tabs {
 
     id: egui::Id,
     allowed_in_windows: bool,
+
+    /// The tab the user tried to close while it had unsaved UI state, if any.
+    pending_close: Option<egui::Id>,
 }
 
 #[derive(Default)]
@@ -44,6 +47,7 @@ struct TabViewer<'a, 'res> {
     update_state: &'a mut crate::UpdateState<'res>,
     focused_id: Option<egui::Id>,
     allowed_in_windows: bool,
+    pending_close: &'a mut Option<egui::Id>,
 }
 
 impl Tabs {
@@ -52,6 +56,7 @@ impl Tabs {
             id: egui::Id::new(id),
             allowed_in_windows,
             dock_state: egui_dock::DockState::new(Vec::with_capacity(4)),
+            pending_close: None,
         }
     }
 
@@ -67,6 +72,7 @@ impl Tabs {
             dock_state: egui_dock::DockState::new(
                 tabs.into_iter().map(|t| Box::new(t) as Box<_>).collect(),
             ),
+            pending_close: None,
         }
     }
 
@@ -100,8 +106,57 @@ impl Tabs {
                     update_state,
                     focused_id,
                     allowed_in_windows: self.allowed_in_windows,
+                    pending_close: &mut self.pending_close,
                 },
             );
+
+        self.close_confirmation_ui(ui.ctx());
+    }
+
+    /// Asks the user whether they want to discard the UI state of the tab they tried to close.
+    fn close_confirmation_ui(&mut self, ctx: &egui::Context) {
+        let Some(pending_id) = self.pending_close else {
+            return;
+        };
+
+        let modal = egui_modal::Modal::new(ctx, format!("luminol_close_tab_modal_{:?}", self.id));
+        if !modal.is_open() {
+            modal.open();
+        }
+
+        let mut should_close = false;
+        let mut should_discard = false;
+        modal.show(|ui| {
+            modal.title(ui, "Close Tab");
+            modal.frame(ui, |ui| {
+                modal.body(
+                    ui,
+                    "This tab has open editors that will be lost if it is closed. Do you want to discard them?",
+                );
+            });
+
+            modal.buttons(ui, |ui| {
+                let keep_button = modal.suggested_button(ui, "Keep editing");
+                let discard_button = modal.caution_button(ui, "Discard");
+
+                if keep_button.clicked() {
+                    should_close = true;
+                } else if discard_button.clicked() {
+                    should_close = true;
+                    should_discard = true;
+                } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    should_close = true;
+                    modal.close();
+                }
+            });
+        });
+
+        if should_close {
+            self.pending_close = None;
+            if should_discard {
+                self.clean_tabs(|tab| tab.id() != pending_id);
+            }
+        }
     }
 
     /// Display all tabs.
@@ -112,19 +167,37 @@ impl Tabs {
         self.process_edit_tabs(edit_tabs);
     }
 
-    /// Add a tab.
+    /// Add a tab, or focus it if a tab with the same ID is already open.
     pub fn add_tab(&mut self, tab: impl Tab + 'static) {
         self.add_boxed_tab(Box::new(tab))
     }
 
     fn add_boxed_tab(&mut self, tab: Box<dyn Tab>) {
         // FIXME O(n)
-        for (_, node) in self.dock_state.iter_all_nodes() {
-            if let egui_dock::Node::Leaf { tabs, .. } = node {
-                if tabs.iter().any(|t| t.id() == tab.id()) {
-                    return;
-                }
-            }
+        let existing = self
+            .dock_state
+            .iter_surfaces()
+            .enumerate()
+            .find_map(|(i, surface)| {
+                surface
+                    .node_tree()?
+                    .iter()
+                    .enumerate()
+                    .find_map(|(j, node)| {
+                        let k = node.tabs()?.iter().position(|t| t.id() == tab.id())?;
+                        Some((
+                            egui_dock::SurfaceIndex(i),
+                            egui_dock::NodeIndex(j),
+                            egui_dock::TabIndex(k),
+                        ))
+                    })
+            });
+        if let Some((surface_index, node_index, tab_index)) = existing {
+            self.dock_state
+                .set_active_tab((surface_index, node_index, tab_index));
+            self.dock_state
+                .set_focused_node_and_surface((surface_index, node_index));
+            return;
         }
         self.dock_state.push_to_focused_leaf(tab);
     }
@@ -210,7 +283,11 @@ impl EditTabs {
     }
 
     pub fn add_tab(&mut self, tab: impl Tab + 'static) {
-        self.added.push(Box::new(tab))
+        self.add_boxed_tab(Box::new(tab))
+    }
+
+    pub fn add_boxed_tab(&mut self, tab: Box<dyn Tab>) {
+        self.added.push(tab)
     }
 
     pub fn remove_tab<T>(&mut self, tab: &impl Tab) -> bool {
@@ -248,6 +325,15 @@ impl<'a, 'res> egui_dock::TabViewer for TabViewer<'a, 'res> {
         tab.force_close()
     }
 
+    fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
+        if tab.has_unsaved_ui_state() {
+            *self.pending_close = Some(tab.id());
+            false
+        } else {
+            true
+        }
+    }
+
     fn scroll_bars(&self, _tab: &Self::Tab) -> [bool; 2] {
         // We need to disable scroll bars for at least the map editor because otherwise it'll start
         // jiggling when the screen or tab is resized. We're not making that type of game.
@@ -286,6 +372,12 @@ pub trait Tab {
     fn force_close(&mut self) -> bool {
         false
     }
+
+    /// Does this tab have state that only lives in its UI (like open editor windows) and would be
+    /// lost if the tab was closed? If so, the user is asked before the tab is closed.
+    fn has_unsaved_ui_state(&self) -> bool {
+        false
+    }
 }
 
 /*
@@ -0,0 +1,148 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{Receiver, Sender},
+    Arc,
+};
+
+/// Keeps track of the logs of background tasks until the console picks them up.
+#[derive(Default)]
+pub struct TaskManager {
+    new_logs: Vec<TaskLog>,
+}
+
+/// A long-running background task, like packing an archive, that can stream its output to a
+/// read-only terminal in the console instead of showing it in toasts.
+pub struct Task {
+    name: String,
+    writer: LogWriter,
+    status: TaskStatus,
+    start: web_time::Instant,
+}
+
+/// The receiving end of a task's log, shown by the console.
+pub struct TaskLog {
+    pub name: String,
+    pub bytes: Receiver<u8>,
+    pub status: TaskStatus,
+}
+
+/// Whether a task is still running, shared between the task and its log.
+#[derive(Clone, Default)]
+pub struct TaskStatus(Arc<AtomicBool>);
+
+/// Writes text to a task's log. Newlines are turned into carriage returns followed by newlines,
+/// since that's what a terminal expects.
+///
+/// Writing never fails, even once the log has been closed in the console.
+#[derive(Clone)]
+pub struct LogWriter(Sender<u8>);
+
+impl TaskManager {
+    /// Starts a task with a log named `name`, which the console shows in a tab of its own.
+    pub fn start(&mut self, name: impl Into<String>) -> Task {
+        let name = name.into();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let status = TaskStatus::default();
+
+        // The web build has no terminals to show the log in, so its output is thrown away
+        #[cfg(not(target_arch = "wasm32"))]
+        self.new_logs.push(TaskLog {
+            name: name.clone(),
+            bytes: receiver,
+            status: status.clone(),
+        });
+        #[cfg(target_arch = "wasm32")]
+        drop(receiver);
+
+        Task {
+            name,
+            writer: LogWriter(sender),
+            status,
+            start: web_time::Instant::now(),
+        }
+    }
+
+    /// Returns whether any tasks were started since the last call to [`Self::take_new_logs`].
+    pub fn has_new_logs(&self) -> bool {
+        !self.new_logs.is_empty()
+    }
+
+    /// Takes the logs of the tasks started since the last call.
+    pub fn take_new_logs(&mut self) -> Vec<TaskLog> {
+        std::mem::take(&mut self.new_logs)
+    }
+}
+
+impl Task {
+    /// Returns a writer that streams text into this task's log. It can be sent to another thread
+    /// or future.
+    pub fn log_writer(&self) -> LogWriter {
+        self.writer.clone()
+    }
+
+    /// Appends a summary line with how long the task took to its log and marks it as finished.
+    pub fn finish(self, summary: impl std::fmt::Display) {
+        let elapsed = self.start.elapsed().as_secs_f32();
+        let mut writer = self.log_writer();
+        let _ = std::io::Write::write_fmt(
+            &mut writer,
+            format_args!(
+                "\n\x1b[1m{} finished in {elapsed:.1}s: {summary}\x1b[0m\n",
+                self.name
+            ),
+        );
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.status.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl TaskStatus {
+    /// Returns whether the task has finished, either through [`Task::finish`] or by being dropped.
+    pub fn is_finished(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl std::io::Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for &byte in buf {
+            if byte == b'\n' {
+                let _ = self.0.send(b'\r');
+            }
+            let _ = self.0.send(byte);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
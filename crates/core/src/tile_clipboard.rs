@@ -0,0 +1,161 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+
+/// Tiles copied with the select tool, or a stamp that's being pasted. They're kept in
+/// [`crate::ToolbarState`] rather than in a map tab so that they can be pasted into any open map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileClipboard {
+    /// The tileset of the map the tiles were copied from. Tile IDs refer to different graphics in
+    /// other tilesets.
+    pub tileset_id: usize,
+    pub width: usize,
+    pub height: usize,
+    /// The copied tiles of every layer in row-major order, or only those of the layer that was
+    /// selected if `all_layers` is false.
+    pub layers: Vec<Vec<i16>>,
+    pub all_layers: bool,
+}
+
+impl TileClipboard {
+    pub fn from_stamp(stamp: &luminol_config::project::Stamp) -> Self {
+        // The project config can be edited by hand, so make sure every layer is complete
+        let mut layers = stamp.layers.clone();
+        for layer in &mut layers {
+            layer.resize(stamp.width * stamp.height, 0);
+        }
+        Self {
+            tileset_id: stamp.tileset_id,
+            width: stamp.width,
+            height: stamp.height,
+            layers,
+            all_layers: stamp.all_layers,
+        }
+    }
+
+    pub fn to_stamp(&self, name: String) -> luminol_config::project::Stamp {
+        luminol_config::project::Stamp {
+            name,
+            tileset_id: self.tileset_id,
+            width: self.width,
+            height: self.height,
+            layers: self.layers.clone(),
+            all_layers: self.all_layers,
+        }
+    }
+
+    /// Pairs each copied layer with the layer of the map it's pasted into. A single copied layer
+    /// goes into the selected layer, while copies of all layers keep their layer numbers.
+    pub fn target_layers(&self, selected_layer: usize, zsize: usize) -> Vec<(usize, usize)> {
+        if self.all_layers {
+            (0..self.layers.len().min(zsize)).map(|z| (z, z)).collect()
+        } else {
+            vec![(0, selected_layer)]
+        }
+    }
+
+    /// The positions of the map covered when pasting with the top-left tile at `origin`, paired
+    /// with the offset of the copied tile. Tiles past the edge of the map are left out.
+    pub fn positions(
+        &self,
+        origin: (usize, usize),
+        xsize: usize,
+        ysize: usize,
+    ) -> impl Iterator<Item = ((usize, usize), usize)> {
+        let width = self.width;
+        (0..self.height)
+            .cartesian_product(0..self.width)
+            .filter(move |&(y, x)| origin.0 + x < xsize && origin.1 + y < ysize)
+            .map(move |(y, x)| ((origin.0 + x, origin.1 + y), x + y * width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clipboard(all_layers: bool) -> TileClipboard {
+        TileClipboard {
+            tileset_id: 1,
+            width: 3,
+            height: 2,
+            layers: vec![vec![1, 2, 3, 4, 5, 6]; if all_layers { 3 } else { 1 }],
+            all_layers,
+        }
+    }
+
+    #[test]
+    fn single_layers_go_into_the_selected_layer() {
+        assert_eq!(clipboard(false).target_layers(2, 3), [(0, 2)]);
+    }
+
+    #[test]
+    fn all_layers_keep_their_layer() {
+        assert_eq!(
+            clipboard(true).target_layers(2, 3),
+            [(0, 0), (1, 1), (2, 2)]
+        );
+        // Maps with fewer layers only get the ones they have
+        assert_eq!(clipboard(true).target_layers(0, 2), [(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn positions_are_clipped_to_the_map() {
+        let clipboard = clipboard(false);
+        assert_eq!(
+            clipboard.positions((1, 1), 10, 10).collect_vec(),
+            [
+                ((1, 1), 0),
+                ((2, 1), 1),
+                ((3, 1), 2),
+                ((1, 2), 3),
+                ((2, 2), 4),
+                ((3, 2), 5)
+            ]
+        );
+        assert_eq!(
+            clipboard.positions((8, 9), 10, 10).collect_vec(),
+            [((8, 9), 0), ((9, 9), 1)]
+        );
+        assert_eq!(clipboard.positions((10, 0), 10, 10).count(), 0);
+    }
+
+    #[test]
+    fn stamps_round_trip() {
+        let clipboard = clipboard(true);
+        let stamp = clipboard.to_stamp("Stamp".to_string());
+        assert_eq!(TileClipboard::from_stamp(&stamp), clipboard);
+    }
+
+    #[test]
+    fn short_stamp_layers_are_padded() {
+        let mut stamp = clipboard(false).to_stamp("Stamp".to_string());
+        stamp.layers[0].truncate(2);
+        assert_eq!(
+            TileClipboard::from_stamp(&stamp).layers,
+            [vec![1, 2, 0, 0, 0, 0]]
+        );
+    }
+}
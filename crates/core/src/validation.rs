@@ -0,0 +1,173 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::rpg::EventCommand;
+
+use crate::Data;
+
+/// The code of the Label command.
+pub const LABEL: u16 = 118;
+/// The code of the Jump to Label command.
+pub const JUMP_TO_LABEL: u16 = 119;
+
+/// A problem found in the project data by [`validate`].
+#[derive(Debug, Clone)]
+pub struct Issue {
+    /// What the problem is in, e.g. `Actor 0001: Aluxes`.
+    pub location: String,
+    pub message: String,
+}
+
+/// A single check run by the validation pass. Rules push every problem they find into the given
+/// list.
+pub type Rule = fn(&Data, &mut Vec<Issue>);
+
+/// Every rule run by [`validate`], in order.
+pub const RULES: &[Rule] = &[actor_equipment, jump_targets];
+
+/// Runs every validation rule against the project data and returns the problems found.
+///
+/// # Panics
+///
+/// Will panic if the data cache is not loaded.
+pub fn validate(data: &Data) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for rule in RULES {
+        rule(data, &mut issues);
+    }
+    issues
+}
+
+/// Starting equipment the actor's class can't equip. RGSS doesn't check this when setting up the
+/// actor, so the actor starts the game wearing it anyway.
+fn actor_equipment(data: &Data, issues: &mut Vec<Issue>) {
+    let actors = data.actors();
+    let classes = data.classes();
+    let weapons = data.weapons();
+    let armors = data.armors();
+
+    for actor in actors.data.iter() {
+        let location = format!("Actor {:0>4}: {}", actor.id + 1, actor.name);
+        let Some(class) = classes.data.get(actor.class_id) else {
+            issues.push(Issue {
+                location,
+                message: format!("Class {:0>4} does not exist", actor.class_id + 1),
+            });
+            continue;
+        };
+
+        if let Some(weapon_id) = actor.weapon_id {
+            if !class.weapon_set.contains(&weapon_id) {
+                let name = weapons.data.get(weapon_id).map_or("", |w| w.name.as_str());
+                issues.push(Issue {
+                    location: location.clone(),
+                    message: format!(
+                        "Starting weapon {:0>4}: {name} can't be equipped by class {:0>4}: {}",
+                        weapon_id + 1,
+                        class.id + 1,
+                        class.name,
+                    ),
+                });
+            }
+        }
+
+        for armor_id in [
+            actor.armor1_id,
+            actor.armor2_id,
+            actor.armor3_id,
+            actor.armor4_id,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !class.armor_set.contains(&armor_id) {
+                let name = armors.data.get(armor_id).map_or("", |a| a.name.as_str());
+                issues.push(Issue {
+                    location: location.clone(),
+                    message: format!(
+                        "Starting armor {:0>4}: {name} can't be equipped by class {:0>4}: {}",
+                        armor_id + 1,
+                        class.id + 1,
+                        class.name,
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Returns the names of the Label commands in `commands`, along with their indices.
+pub fn labels(commands: &[EventCommand]) -> impl Iterator<Item = (usize, &str)> {
+    commands_with_name(commands, LABEL)
+}
+
+/// Returns the Jump to Label commands in `commands` whose label isn't in `commands`, along with
+/// their indices. RGSS does nothing when running these.
+pub fn missing_jump_targets(commands: &[EventCommand]) -> Vec<(usize, &str)> {
+    commands_with_name(commands, JUMP_TO_LABEL)
+        .filter(|(_, target)| !labels(commands).any(|(_, label)| label == *target))
+        .collect()
+}
+
+fn commands_with_name(commands: &[EventCommand], code: u16) -> impl Iterator<Item = (usize, &str)> {
+    commands
+        .iter()
+        .enumerate()
+        .filter(move |(_, c)| c.code == code)
+        .map(|(i, c)| {
+            let name = c.parameters.first().and_then(|p| p.as_string());
+            (i, name.map_or("", String::as_str))
+        })
+}
+
+/// Jump to Label commands in common events and troop pages that jump to a label their event
+/// doesn't have. Maps aren't checked since the validation pass doesn't load them.
+fn jump_targets(data: &Data, issues: &mut Vec<Issue>) {
+    let mut check = |location: String, commands: &[EventCommand]| {
+        for (index, target) in missing_jump_targets(commands) {
+            issues.push(Issue {
+                location: location.clone(),
+                message: format!(
+                    "Command {} jumps to label {target:?}, which isn't in this event",
+                    index + 1
+                ),
+            });
+        }
+    };
+
+    for event in data.common_events().data.iter() {
+        check(
+            format!("Common Event {:0>4}: {}", event.id + 1, event.name),
+            &event.list,
+        );
+    }
+    for troop in data.troops().data.iter() {
+        for (i, page) in troop.pages.iter().enumerate() {
+            check(
+                format!("Troop {:0>4}: {}, page {}", troop.id + 1, troop.name, i + 1),
+                &page.list,
+            );
+        }
+    }
+}
@@ -64,6 +64,11 @@ impl Windows {
         self.windows.push(window)
     }
 
+    /// Returns whether there are no windows open.
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
     /// Clean all windows that need the data cache.
     /// This is usually when a project is closed.
     pub fn clean_windows(&mut self, f: impl Fn(&Box<dyn Window>) -> bool) {
@@ -128,7 +133,11 @@ impl EditWindows {
     }
 
     pub fn add_window(&mut self, window: impl Window + 'static) {
-        self.added.push(Box::new(window))
+        self.add_boxed_window(Box::new(window))
+    }
+
+    pub fn add_boxed_window(&mut self, window: Box<dyn Window>) {
+        self.added.push(window)
     }
 
     pub fn remove_window(&mut self, window: &impl Window) -> bool {
@@ -163,6 +172,33 @@ pub trait Window {
     }
 }
 
+/// Checks the window rects restored from the previous session and, if any window ended up outside
+/// of the viewport (for example, because it was on a monitor that is no longer connected), lays the
+/// windows out again inside of it.
+///
+/// Returns `true` if the window layout had to be reset.
+pub fn clamp_windows_to_screen(ctx: &egui::Context) -> bool {
+    let screen_rect = ctx.screen_rect();
+    let off_screen = ctx.memory(|m| {
+        m.layer_ids()
+            .filter(|layer_id| layer_id.order == egui::Order::Middle)
+            .filter_map(|layer_id| m.area_rect(layer_id.id))
+            .any(|rect| !screen_rect.contains_rect(rect))
+    });
+
+    // egui doesn't let us move a single area, so forget all of the window positions instead and let
+    // egui lay them out again (the window sizes are stored separately and are kept)
+    if off_screen {
+        reset_window_layout(ctx);
+    }
+    off_screen
+}
+
+/// Forgets the positions of all windows so that they are laid out again from scratch.
+pub fn reset_window_layout(ctx: &egui::Context) {
+    ctx.memory_mut(|m| m.reset_areas());
+}
+
 /*
 impl Window for Box<dyn Window + Send + Sync> {
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
@@ -0,0 +1,427 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The default damage formulas of RPG Maker XP's battle system, from `Game_Battler`, for
+//! previewing skills and items in the editor.
+//!
+//! States, guarding and critical hits are left out. Games with custom battle scripts will
+//! calculate damage differently.
+
+use crate::rpg::{Actor, Armor, Class, Enemy, Item, Skill, Weapon};
+
+/// The percentage of damage taken from an element for each element rank, from A to F.
+const ELEMENT_RANK_RATES: [i32; 7] = [0, 200, 150, 100, 50, 0, -100];
+
+/// The parameters of an actor or enemy that the damage formulas use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Battler {
+    pub maxhp: i32,
+    pub maxsp: i32,
+    pub str: i32,
+    pub dex: i32,
+    pub agi: i32,
+    pub int: i32,
+    pub atk: i32,
+    pub pdef: i32,
+    pub mdef: i32,
+    /// The percentage of damage taken from each element, indexed by element ID.
+    pub element_rates: Vec<i32>,
+}
+
+/// The smallest and largest amount an action can deal, inclusive. Negative amounts heal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl Amount {
+    /// Spreads `amount` out by `variance` percent the way RGSS does, by adding two random numbers
+    /// between 0 and the spread and then subtracting the spread.
+    fn with_variance(amount: i32, variance: i32) -> Self {
+        if variance > 0 && amount != 0 {
+            let amp = (amount.abs() * variance / 100).max(1);
+            Self {
+                min: amount - amp,
+                max: amount + amp,
+            }
+        } else {
+            Self {
+                min: amount,
+                max: amount,
+            }
+        }
+    }
+}
+
+/// Ruby's integer division, which rounds towards negative infinity. All divisors in the formulas
+/// are positive.
+fn div(dividend: i32, divisor: i32) -> i32 {
+    dividend.div_euclid(divisor)
+}
+
+fn element_rate(ranks: &crate::Table1, element_id: usize) -> i32 {
+    // Element ranks are indexed by the element ID as stored in the game data, which starts at 1
+    let rank = if element_id + 1 < ranks.xsize() {
+        ranks[element_id + 1]
+    } else {
+        3
+    };
+    ELEMENT_RANK_RATES
+        .get(rank as usize)
+        .copied()
+        .unwrap_or(100)
+}
+
+impl Battler {
+    /// An actor at `level` with their initial equipment.
+    pub fn from_actor(
+        actor: &Actor,
+        class: Option<&Class>,
+        level: usize,
+        weapons: &[Weapon],
+        armors: &[Armor],
+    ) -> Self {
+        let parameter = |index: usize| {
+            let level = level.min(actor.parameters.ysize().saturating_sub(1));
+            if index < actor.parameters.xsize() {
+                actor.parameters[(index, level)] as i32
+            } else {
+                0
+            }
+        };
+        let weapon = actor.weapon_id.and_then(|id| weapons.get(id));
+        let armors = [
+            actor.armor1_id,
+            actor.armor2_id,
+            actor.armor3_id,
+            actor.armor4_id,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|id| armors.get(id))
+        .collect::<Vec<_>>();
+
+        let plus = |weapon_plus: fn(&Weapon) -> i32, armor_plus: fn(&Armor) -> i32| {
+            weapon.map_or(0, weapon_plus)
+                + armors.iter().map(|&armor| armor_plus(armor)).sum::<i32>()
+        };
+        let element_rates = class.map_or_else(Vec::new, |class| {
+            (0..class.element_ranks.xsize().saturating_sub(1))
+                .map(|element_id| {
+                    let mut rate = element_rate(&class.element_ranks, element_id);
+                    for armor in &armors {
+                        if armor.guard_element_set.contains(&element_id) {
+                            rate = div(rate, 2);
+                        }
+                    }
+                    rate
+                })
+                .collect()
+        });
+
+        Self {
+            maxhp: parameter(0).clamp(1, 9999),
+            maxsp: parameter(1).clamp(0, 9999),
+            str: (parameter(2) + plus(|w| w.str_plus, |a| a.str_plus)).clamp(1, 999),
+            dex: (parameter(3) + plus(|w| w.dex_plus, |a| a.dex_plus)).clamp(1, 999),
+            agi: (parameter(4) + plus(|w| w.agi_plus, |a| a.agi_plus)).clamp(1, 999),
+            int: (parameter(5) + plus(|w| w.int_plus, |a| a.int_plus)).clamp(1, 999),
+            atk: weapon.map_or(0, |weapon| weapon.atk),
+            pdef: plus(|w| w.pdef, |a| a.pdef),
+            mdef: plus(|w| w.mdef, |a| a.mdef),
+            element_rates,
+        }
+    }
+
+    pub fn from_enemy(enemy: &Enemy) -> Self {
+        Self {
+            maxhp: enemy.maxhp,
+            maxsp: enemy.maxsp,
+            str: enemy.str,
+            dex: enemy.dex,
+            agi: enemy.agi,
+            int: enemy.int,
+            atk: enemy.atk,
+            pdef: enemy.pdef,
+            mdef: enemy.mdef,
+            element_rates: (0..enemy.element_ranks.xsize().saturating_sub(1))
+                .map(|element_id| element_rate(&enemy.element_ranks, element_id))
+                .collect(),
+        }
+    }
+
+    /// The percentage of damage taken from an attack with these elements, which is the highest
+    /// rate of any of them, or 100 if there are none.
+    pub fn elements_correct(&self, element_set: &[usize]) -> i32 {
+        if element_set.is_empty() {
+            return 100;
+        }
+        element_set
+            .iter()
+            .map(|&element_id| self.element_rates.get(element_id).copied().unwrap_or(100))
+            .fold(-100, i32::max)
+    }
+}
+
+/// The damage `skill` deals to `target` when used by `user`, from `Game_Battler#skill_effect`.
+pub fn skill_damage(skill: &Skill, user: &Battler, target: &Battler) -> Amount {
+    let mut power = skill.power + div(user.atk * skill.atk_f, 100);
+    if power > 0 {
+        power -= div(target.pdef * skill.pdef_f, 200);
+        power -= div(target.mdef * skill.mdef_f, 200);
+        power = power.max(0);
+    }
+
+    let rate = 20
+        + div(user.str * skill.str_f, 100)
+        + div(user.dex * skill.dex_f, 100)
+        + div(user.agi * skill.agi_f, 100)
+        + div(user.int * skill.int_f, 100);
+
+    let damage = div(power * rate, 20);
+    let damage = div(damage * target.elements_correct(&skill.element_set), 100);
+    Amount::with_variance(damage, skill.variance)
+}
+
+/// The HP and SP that `item` restores to `target`, from `Game_Battler#item_effect`. Unlike
+/// damage, positive amounts heal.
+pub fn item_recovery(item: &Item, target: &Battler) -> (Amount, Amount) {
+    let mut recover_hp = div(target.maxhp * item.recover_hp_rate, 100) + item.recover_hp;
+    let recover_sp = div(target.maxsp * item.recover_sp_rate, 100) + item.recover_sp;
+    if recover_hp < 0 {
+        recover_hp += div(target.pdef * item.pdef_f, 20);
+        recover_hp += div(target.mdef * item.mdef_f, 20);
+        recover_hp = recover_hp.min(0);
+    }
+
+    let elements_correct = target.elements_correct(&item.element_set);
+    let recover_hp = div(recover_hp * elements_correct, 100);
+    let recover_sp = div(recover_sp * elements_correct, 100);
+    (
+        Amount::with_variance(recover_hp, item.variance),
+        Amount::with_variance(recover_sp, item.variance),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The stock Fire skill: power 100, INT-F 100, MDEF-F 100, variance 15, fire element.
+    fn fire() -> Skill {
+        Skill {
+            power: 100,
+            int_f: 100,
+            mdef_f: 100,
+            variance: 15,
+            element_set: vec![0],
+            ..Default::default()
+        }
+    }
+
+    /// The stock Heal skill: power -200, INT-F 100, variance 15.
+    fn heal() -> Skill {
+        Skill {
+            power: -200,
+            int_f: 100,
+            variance: 15,
+            ..Default::default()
+        }
+    }
+
+    fn mage() -> Battler {
+        Battler {
+            maxhp: 741,
+            maxsp: 353,
+            str: 40,
+            dex: 50,
+            agi: 45,
+            int: 100,
+            mdef: 50,
+            element_rates: vec![100],
+            ..Default::default()
+        }
+    }
+
+    fn amount(min: i32, max: i32) -> Amount {
+        Amount { min, max }
+    }
+
+    #[test]
+    fn fire_damage() {
+        // power = 100 - 50 * 100 / 200 = 75, rate = 20 + 100 = 120, damage = 75 * 120 / 20 = 450,
+        // and the spread is 450 * 15 / 100 = 67
+        assert_eq!(skill_damage(&fire(), &mage(), &mage()), amount(383, 517));
+
+        // Rank A doubles the damage
+        let weak = Battler {
+            element_rates: vec![200],
+            ..mage()
+        };
+        assert_eq!(skill_damage(&fire(), &mage(), &weak), amount(765, 1035));
+
+        // Rank E blocks it entirely
+        let immune = Battler {
+            element_rates: vec![0],
+            ..mage()
+        };
+        assert_eq!(skill_damage(&fire(), &mage(), &immune), amount(0, 0));
+    }
+
+    #[test]
+    fn physical_skill_damage() {
+        let skill = Skill {
+            power: 10,
+            atk_f: 100,
+            str_f: 100,
+            pdef_f: 100,
+            variance: 0,
+            ..Default::default()
+        };
+        let user = Battler {
+            str: 60,
+            atk: 90,
+            ..Default::default()
+        };
+        let target = Battler {
+            pdef: 40,
+            ..Default::default()
+        };
+        // power = 10 + 90 - 40 * 100 / 200 = 80, rate = 20 + 60 = 80, damage = 80 * 80 / 20
+        assert_eq!(skill_damage(&skill, &user, &target), amount(320, 320));
+
+        // Defense can't turn an attack into healing
+        let wall = Battler {
+            pdef: 999,
+            ..Default::default()
+        };
+        assert_eq!(skill_damage(&skill, &user, &wall), amount(0, 0));
+    }
+
+    #[test]
+    fn heal_ignores_defense() {
+        // power = -200 (defense only applies to positive power), rate = 120, damage = -1200,
+        // and the spread is 1200 * 15 / 100 = 180
+        let tank = Battler {
+            pdef: 300,
+            mdef: 300,
+            ..mage()
+        };
+        assert_eq!(skill_damage(&heal(), &mage(), &tank), amount(-1380, -1020));
+    }
+
+    #[test]
+    fn negative_amounts_round_down_like_ruby() {
+        let skill = Skill {
+            power: -7,
+            int_f: 1,
+            ..Default::default()
+        };
+        // -7 * 21 / 20 is -7.35, which Ruby rounds down to -8 rather than towards zero
+        assert_eq!(skill_damage(&skill, &mage(), &mage()), amount(-8, -8));
+        assert_eq!(div(-147, 20), -8);
+        assert_eq!(div(147, 20), 7);
+    }
+
+    #[test]
+    fn small_amounts_still_vary_by_one() {
+        let skill = Skill {
+            power: 1,
+            variance: 1,
+            ..Default::default()
+        };
+        // 1 * 20 / 20 = 1, and the spread is at least 1 when there's any variance
+        assert_eq!(skill_damage(&skill, &mage(), &mage()), amount(0, 2));
+    }
+
+    #[test]
+    fn potion_recovery() {
+        // The stock Potion restores a flat 500 HP
+        let potion = Item {
+            recover_hp: 500,
+            ..Default::default()
+        };
+        assert_eq!(
+            item_recovery(&potion, &mage()),
+            (amount(500, 500), amount(0, 0))
+        );
+    }
+
+    #[test]
+    fn rate_recovery() {
+        let item = Item {
+            recover_hp_rate: 100,
+            recover_sp_rate: 50,
+            variance: 10,
+            ..Default::default()
+        };
+        // 741 HP with a spread of 74, and 353 * 50 / 100 = 176 SP with a spread of 17
+        assert_eq!(
+            item_recovery(&item, &mage()),
+            (amount(667, 815), amount(159, 193))
+        );
+    }
+
+    #[test]
+    fn damaging_items_are_reduced_by_defense() {
+        let item = Item {
+            recover_hp: -300,
+            pdef_f: 10,
+            mdef_f: 20,
+            ..Default::default()
+        };
+        let target = Battler {
+            pdef: 100,
+            mdef: 30,
+            ..mage()
+        };
+        // -300 + 100 * 10 / 20 + 30 * 20 / 20 = -220
+        assert_eq!(
+            item_recovery(&item, &target),
+            (amount(-220, -220), amount(0, 0))
+        );
+
+        // Defense can't turn the damage into healing
+        let wall = Battler {
+            pdef: 999,
+            ..mage()
+        };
+        assert_eq!(item_recovery(&item, &wall), (amount(0, 0), amount(0, 0)));
+    }
+
+    #[test]
+    fn element_rates_of_enemies() {
+        // Element ranks start at index 1; ranks A, E and F
+        let mut element_ranks = crate::Table1::new(4);
+        element_ranks.as_mut_slice().copy_from_slice(&[3, 1, 5, 6]);
+        let enemy = Enemy {
+            element_ranks,
+            ..Default::default()
+        };
+        let battler = Battler::from_enemy(&enemy);
+        assert_eq!(battler.element_rates, [200, 0, -100]);
+
+        // The highest rate of all the elements is used
+        assert_eq!(battler.elements_correct(&[]), 100);
+        assert_eq!(battler.elements_correct(&[1, 2]), 0);
+        assert_eq!(battler.elements_correct(&[0, 2]), 200);
+        assert_eq!(battler.elements_correct(&[2]), -100);
+        // Elements the enemy has no rank for take normal damage
+        assert_eq!(battler.elements_correct(&[7]), 100);
+    }
+}
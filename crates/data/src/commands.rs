@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, IntoStaticStr};
 
+use crate::{rpg, ParameterType};
+
 type Code = u16;
 type Parameters = Vec<Parameter>;
 
@@ -20,6 +22,11 @@ pub struct CommandDescription {
     /// Hide this in the command ui
     #[serde(default)]
     pub hidden: bool,
+    /// The category this command belongs to
+    ///
+    /// Used to look up the color this command is tinted with in the command list
+    #[serde(default)]
+    pub category: Option<String>,
 
     /// The text used by lumi!
     #[serde(default)]
@@ -47,6 +54,110 @@ impl CommandDescription {
             CommandKind::Multi { .. } => 1,
         }
     }
+
+    /// The parameters laid out by this command, which multiline commands don't have.
+    pub fn parameters(&self) -> &[Parameter] {
+        match &self.kind {
+            CommandKind::Single(parameters) | CommandKind::Branch { parameters, .. } => parameters,
+            CommandKind::Multi { .. } => &[],
+        }
+    }
+
+    /// Walks the parameters of this command against the raw parameters of an event command,
+    /// pairing every parameter that applies with its value. Only the selected option of a
+    /// selection is walked.
+    pub fn describe_parameters<'a>(
+        &'a self,
+        values: &'a [ParameterType],
+    ) -> Vec<DescribedParameter<'a>> {
+        let mut described = vec![];
+        let mut next_index = 0;
+        for parameter in self.parameters() {
+            parameter.describe(values, &mut next_index, &mut described);
+        }
+        described
+    }
+
+    /// A one-line summary of an event command: the name of this command followed by the value
+    /// of each of its parameters, formatted the same way as in the command list's tooltips.
+    pub fn summary(&self, values: &[ParameterType], system: &rpg::System) -> String {
+        if let CommandKind::Multi { .. } = self.kind {
+            let text = values.first().and_then(ParameterType::as_string);
+            return format!("{}: {}", self.name, text.map_or("", String::as_str));
+        }
+
+        let parameters = self
+            .describe_parameters(values)
+            .into_iter()
+            .map(|p| format!("{} = {}", p.name, p.value_text(system)))
+            .collect::<Vec<_>>();
+        if parameters.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}: {}", self.name, parameters.join(", "))
+        }
+    }
+}
+
+/// A parameter of a command along with its value in an event command, as returned by
+/// [`CommandDescription::describe_parameters`].
+#[derive(Debug, Clone, Copy)]
+pub struct DescribedParameter<'a> {
+    pub name: &'a str,
+    pub description: &'a str,
+    pub kind: &'a ParameterKind,
+    /// The value of this parameter, if the event command has one
+    pub value: Option<&'a ParameterType>,
+}
+
+impl DescribedParameter<'_> {
+    /// Formats the value of this parameter for display. Switch and variable IDs are shown along
+    /// with their names from `system`.
+    pub fn value_text(&self, system: &rpg::System) -> String {
+        let Some(value) = self.value.filter(|v| !v.is_none()) else {
+            return "(none)".to_string();
+        };
+
+        match (self.kind, value) {
+            (ParameterKind::Switch, ParameterType::Integer(id)) => named_id(*id, &system.switches),
+            (ParameterKind::Variable, ParameterType::Integer(id)) => {
+                named_id(*id, &system.variables)
+            }
+            (ParameterKind::IntBool, ParameterType::Integer(value)) => {
+                if *value != 0 { "Yes" } else { "No" }.to_string()
+            }
+            (ParameterKind::Enum { variants }, ParameterType::Integer(value)) => variants
+                .iter()
+                .find(|(_, v)| *v as i32 == *value)
+                .map_or_else(|| value.to_string(), |(name, _)| name.clone()),
+            (ParameterKind::Frames, ParameterType::Integer(frames)) => format!("{frames} frames"),
+            (_, ParameterType::Integer(value)) => value.to_string(),
+            (ParameterKind::String, ParameterType::String(text)) => format!("{text:?}"),
+            (_, ParameterType::String(text)) => text.clone(),
+            (_, ParameterType::Color(color)) => format!(
+                "({}, {}, {}, {})",
+                color.red, color.green, color.blue, color.alpha
+            ),
+            (_, ParameterType::Tone(tone)) => {
+                format!(
+                    "({}, {}, {}, {})",
+                    tone.red, tone.green, tone.blue, tone.gray
+                )
+            }
+            (_, value) => format!("{value:?}"),
+        }
+    }
+}
+
+/// Switch and variable IDs are stored 1-based in command parameters.
+fn named_id(id: i32, names: &[String]) -> String {
+    let name = usize::try_from(id - 1)
+        .ok()
+        .and_then(|index| names.get(index));
+    match name {
+        Some(name) => format!("{id:0>3}: {name}"),
+        None => format!("{id:0>3}"),
+    }
 }
 
 impl Default for CommandDescription {
@@ -57,6 +168,7 @@ impl Default for CommandDescription {
             description: "".to_string(),
             kind: CommandKind::default(),
             hidden: false,
+            category: None,
             lumi_text: "".to_string(),
             guid: rand::random(),
         }
@@ -170,6 +282,17 @@ impl Index {
     pub fn as_usize(self) -> usize {
         self.as_u8() as usize
     }
+
+    /// Returns the index of a parameter and moves `next_index` past it, following the algorithm
+    /// described on [`Index::Assumed`].
+    pub fn resolve(self, next_index: &mut usize) -> usize {
+        let assumed = *next_index;
+        *next_index += 1;
+        match self {
+            Self::Assumed(_) => assumed,
+            Self::Overridden(index) => index as usize,
+        }
+    }
 }
 
 impl Default for Index {
@@ -205,6 +328,68 @@ impl Parameter {
             _ => 0,
         }
     }
+
+    /// Returns how many assumed indices this parameter takes up.
+    pub fn span(&self) -> usize {
+        match self {
+            Self::Single { .. } | Self::Dummy => 1,
+            Self::Group { parameters, .. } => parameters.iter().map(Self::span).sum(),
+            Self::Selection { parameters, .. } => 1 + Self::selection_span(parameters),
+            Self::Label(_) => 0,
+        }
+    }
+
+    /// The options of a selection all start at the same index, so together they take up as many
+    /// indices as the largest of them.
+    pub fn selection_span(options: &[(i8, Parameter)]) -> usize {
+        options.iter().map(|(_, p)| p.span()).max().unwrap_or(0)
+    }
+
+    fn describe<'a>(
+        &'a self,
+        values: &'a [ParameterType],
+        next_index: &mut usize,
+        described: &mut Vec<DescribedParameter<'a>>,
+    ) {
+        match self {
+            Self::Single {
+                index,
+                description,
+                name,
+                kind,
+                ..
+            } => {
+                let index = index.resolve(next_index);
+                described.push(DescribedParameter {
+                    name,
+                    description,
+                    kind,
+                    value: values.get(index),
+                });
+            }
+            Self::Group { parameters, .. } => {
+                for parameter in parameters {
+                    parameter.describe(values, next_index, described);
+                }
+            }
+            Self::Selection {
+                index, parameters, ..
+            } => {
+                let index = index.resolve(next_index);
+                let start = *next_index;
+                let selected = values.get(index).and_then(ParameterType::as_integer);
+                if let Some((_, parameter)) = parameters
+                    .iter()
+                    .find(|(v, _)| Some(&(*v as i32)) == selected)
+                {
+                    parameter.describe(values, &mut { start }, described);
+                }
+                *next_index = start + Self::selection_span(parameters);
+            }
+            Self::Dummy => *next_index += 1,
+            Self::Label(_) => {}
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, EnumIter, IntoStaticStr, Default)]
@@ -222,6 +407,8 @@ pub enum ParameterKind {
     /// Parameter is a signed integer
     #[default]
     Int,
+    /// Parameter is a signed integer between min and max (inclusive)
+    IntRange { min: i32, max: i32 },
     /// Parameter is a bool stored as an integer
     IntBool,
 
@@ -229,6 +416,15 @@ pub enum ParameterKind {
     ///
     /// The variants are a Vec of (String, i8) with the String being the variant, and the i8 being the value
     Enum { variants: Vec<(String, i8)> },
+
+    /// Parameter is a color
+    Color,
+    /// Parameter is a tone
+    Tone,
+    /// Parameter is a duration in frames
+    ///
+    /// Shown along with how long the duration is in seconds
+    Frames,
 }
 
 impl PartialEq for ParameterKind {
@@ -28,5 +28,7 @@ pub mod optional_id_alox;
 pub mod optional_path_alox;
 
 mod parameter_type;
+mod sorted_map;
 
 pub use parameter_type::*;
+pub use sorted_map::SortedMap;
@@ -0,0 +1,64 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use alox_48::SerializeHash;
+
+/// Serializes a `HashMap` with its entries in ascending key order, so that saving the same data
+/// twice produces identical output.
+pub struct SortedMap<'a, K, V>(pub &'a std::collections::HashMap<K, V>);
+
+impl<K, V> SortedMap<'_, K, V>
+where
+    K: Ord,
+{
+    fn sorted(&self) -> Vec<(&K, &V)> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+}
+
+impl<K, V> serde::Serialize for SortedMap<'_, K, V>
+where
+    K: Ord + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.sorted())
+    }
+}
+
+impl<K, V> alox_48::Serialize for SortedMap<'_, K, V>
+where
+    K: Ord + alox_48::Serialize,
+    V: alox_48::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, alox_48::SerError>
+    where
+        S: alox_48::SerializerTrait,
+    {
+        let mut ser = serializer.serialize_hash(self.0.len())?;
+        for (key, value) in self.sorted() {
+            ser.serialize_key(key)?;
+            ser.serialize_value(value)?;
+        }
+        ser.end()
+    }
+}
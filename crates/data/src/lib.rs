@@ -3,6 +3,8 @@
 // Editor specific types
 pub mod rmxp;
 
+pub mod rmvx_ace;
+
 // Shared structs with the same layout
 mod shared;
 
@@ -12,6 +14,7 @@ mod rgss_structs;
 
 pub mod helpers;
 
+pub mod battle_calc;
 pub mod commands;
 
 pub use helpers::*;
@@ -136,6 +136,23 @@ impl<T> OptionVec<T> {
         }
     }
 
+    /// Move elements to new indices, given as pairs of old and new indices.
+    /// Every element is taken out before any is put back, so the new indices may overlap the old
+    /// ones. Pairs whose old index has no element are skipped.
+    pub fn renumber(&mut self, renumbering: &[(usize, usize)]) {
+        let elements: Vec<_> = renumbering
+            .iter()
+            .filter_map(|&(old, new)| self.try_remove(old).ok().map(|element| (new, element)))
+            .collect();
+        for (new, element) in elements {
+            self.insert(new, element);
+        }
+        // don't keep trailing empty slots around from elements that moved down
+        while self.vec.last().is_some_and(|element| element.is_none()) {
+            self.vec.pop();
+        }
+    }
+
     /// Remove the element at the given index and return it.
     /// If the OptionVec is not big enough to contain this index, this will panic.
     /// If there isn't an element at that index, this will panic.
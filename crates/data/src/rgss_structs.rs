@@ -88,11 +88,129 @@ impl From<Tone> for alox_48::Value {
 
 use std::ops::{Index, IndexMut};
 
+/// The fields of any table as they're read from RON or JSON. Tables are checked when they're
+/// read back so that a hand-edited file can't make a table's size disagree with its data.
+struct TableFields {
+    xsize: usize,
+    ysize: usize,
+    zsize: usize,
+    data: Vec<i16>,
+}
+
+impl TableFields {
+    /// Checks that the table has at most `dimensions` dimensions and as much data as its size.
+    fn check(&self, dimensions: usize) -> Result<(), String> {
+        if (dimensions < 2 && self.ysize != 1) || (dimensions < 3 && self.zsize != 1) {
+            return Err(format!("a {dimensions}D table can't have a ysize or zsize"));
+        }
+        let len = self
+            .xsize
+            .checked_mul(self.ysize)
+            .and_then(|len| len.checked_mul(self.zsize))
+            .ok_or_else(|| {
+                format!(
+                    "a table of size {}x{}x{} is too big",
+                    self.xsize, self.ysize, self.zsize
+                )
+            })?;
+        if self.data.len() != len {
+            return Err(format!(
+                "a table of size {}x{}x{} needs {len} values, but {} were given",
+                self.xsize,
+                self.ysize,
+                self.zsize,
+                self.data.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Pretty RON writes the name of every struct and checks it when reading, so each table is read
+/// through a struct with the same name as it.
+mod named_fields {
+    use super::TableFields;
+
+    macro_rules! named_fields {
+        ($($name:ident),*) => {$(
+            #[derive(serde::Deserialize)]
+            pub struct $name {
+                xsize: usize,
+                #[serde(default = "one")]
+                ysize: usize,
+                #[serde(default = "one")]
+                zsize: usize,
+                data: Vec<i16>,
+            }
+
+            impl TryFrom<$name> for super::$name {
+                type Error = String;
+
+                fn try_from(fields: $name) -> Result<Self, Self::Error> {
+                    TableFields {
+                        xsize: fields.xsize,
+                        ysize: fields.ysize,
+                        zsize: fields.zsize,
+                        data: fields.data,
+                    }
+                    .try_into()
+                }
+            }
+        )*};
+    }
+
+    named_fields!(Table1, Table2, Table3);
+
+    fn one() -> usize {
+        1
+    }
+}
+
+impl TryFrom<TableFields> for Table1 {
+    type Error = String;
+
+    fn try_from(fields: TableFields) -> Result<Self, Self::Error> {
+        fields.check(1)?;
+        Ok(Self {
+            xsize: fields.xsize,
+            data: fields.data,
+        })
+    }
+}
+
+impl TryFrom<TableFields> for Table2 {
+    type Error = String;
+
+    fn try_from(fields: TableFields) -> Result<Self, Self::Error> {
+        fields.check(2)?;
+        Ok(Self {
+            xsize: fields.xsize,
+            ysize: fields.ysize,
+            data: fields.data,
+        })
+    }
+}
+
+impl TryFrom<TableFields> for Table3 {
+    type Error = String;
+
+    fn try_from(fields: TableFields) -> Result<Self, Self::Error> {
+        fields.check(3)?;
+        Ok(Self {
+            xsize: fields.xsize,
+            ysize: fields.ysize,
+            zsize: fields.zsize,
+            data: fields.data,
+        })
+    }
+}
+
 /// Normal RGSS has dynamically dimensioned arrays, but in practice that does not map well to Rust.
 /// We don't particularly need dynamically sized arrays anyway.
 /// 1D Table.
 #[derive(Debug, Default, Clone)]
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "named_fields::Table1")]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(from = "alox_48::Userdata", into = "alox_48::Userdata")]
 pub struct Table1 {
@@ -200,6 +318,7 @@ impl IndexMut<usize> for Table1 {
 /// 2D table. See [`Table1`].
 #[derive(Debug, Default, Clone)]
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "named_fields::Table2")]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(from = "alox_48::Userdata", into = "alox_48::Userdata")]
 pub struct Table2 {
@@ -331,6 +450,7 @@ impl IndexMut<(usize, usize)> for Table2 {
 
 #[derive(Debug, Default, Clone)]
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "named_fields::Table3")]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(from = "alox_48::Userdata", into = "alox_48::Userdata")]
 /// 3D table. See [`Table2`].
@@ -487,3 +607,134 @@ impl IndexMut<(usize, usize, usize)> for Table3 {
         &mut self.data[index.0 + self.xsize * (index.1 + self.ysize * index.2)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marshal<T: alox_48::Serialize>(value: &T) -> Vec<u8> {
+        let mut serializer = alox_48::Serializer::new();
+        alox_48::path_to_error::serialize(value, &mut serializer).unwrap();
+        serializer.output
+    }
+
+    /// Loads `value` from Marshal, saves it as JSON, loads that back and saves it as Marshal
+    /// again, which has to give exactly the bytes it started with.
+    fn assert_json_round_trips<T>(value: &T)
+    where
+        T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let bytes = marshal(value);
+        let mut de = alox_48::Deserializer::new(&bytes).unwrap();
+        let loaded: T = alox_48::path_to_error::deserialize(&mut de).unwrap();
+
+        let json = serde_json::to_string(&loaded).unwrap();
+        let reloaded: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            marshal(&reloaded),
+            bytes,
+            "changed after going through {json}"
+        );
+    }
+
+    #[test]
+    fn color_and_tone_round_trip() {
+        assert_json_round_trips(&Color::default());
+        assert_json_round_trips(&Color {
+            red: 12.5,
+            green: 0.25,
+            blue: -0.0,
+            alpha: 128.0,
+        });
+        assert_json_round_trips(&Tone::default());
+        assert_json_round_trips(&Tone {
+            red: -255.0,
+            green: 17.75,
+            blue: 255.0,
+            gray: 0.5,
+        });
+    }
+
+    #[test]
+    fn tables_round_trip() {
+        let values = |len: usize| {
+            (0..len)
+                .map(|i| match i % 4 {
+                    0 => i16::MIN,
+                    1 => i16::MAX,
+                    2 => -(i as i16),
+                    _ => i as i16,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut table1 = Table1::new(7);
+        table1.as_mut_slice().copy_from_slice(&values(7));
+        assert_json_round_trips(&table1);
+        assert_json_round_trips(&Table1::new(0));
+
+        assert_json_round_trips(&Table2::new_data(5, 3, values(15)));
+        assert_json_round_trips(&Table2::new(0, 0));
+
+        assert_json_round_trips(&Table3::new_data(4, 3, 2, values(24)));
+        assert_json_round_trips(&Table3::new(20, 15, 3));
+    }
+
+    /// Saves `value` as pretty RON with struct names, like the data cache does, and checks that
+    /// it loads back the same.
+    fn assert_pretty_ron_round_trips<T>(value: &T, name: &str)
+    where
+        T: alox_48::Serialize,
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let config = ron::ser::PrettyConfig::new().struct_names(true);
+        let ron = ron::ser::to_string_pretty(value, config).unwrap();
+        assert!(ron.starts_with(name), "{ron} isn't named {name}");
+        let reloaded: T = ron::from_str(&ron).unwrap();
+        assert_eq!(
+            marshal(&reloaded),
+            marshal(value),
+            "changed after going through {ron}"
+        );
+    }
+
+    #[test]
+    fn tables_round_trip_through_pretty_ron() {
+        assert_pretty_ron_round_trips(&Table1::new(3), "Table1(");
+        assert_pretty_ron_round_trips(&Table2::new_data(2, 2, vec![1, -2, 3, -4]), "Table2(");
+        assert_pretty_ron_round_trips(&Table3::new(2, 3, 4), "Table3(");
+    }
+
+    #[test]
+    fn tables_with_the_wrong_size_are_rejected() {
+        assert!(serde_json::from_str::<Table1>(r#"{"xsize":3,"data":[1,2,3]}"#).is_ok());
+        assert!(serde_json::from_str::<Table1>(r#"{"xsize":3,"data":[1,2]}"#).is_err());
+        assert!(serde_json::from_str::<Table1>(r#"{"xsize":1,"ysize":2,"data":[1,2]}"#).is_err());
+
+        assert!(
+            serde_json::from_str::<Table2>(r#"{"xsize":2,"ysize":2,"data":[1,2,3,4]}"#).is_ok()
+        );
+        assert!(serde_json::from_str::<Table2>(r#"{"xsize":2,"ysize":2,"data":[1,2,3]}"#).is_err());
+        assert!(
+            serde_json::from_str::<Table2>(r#"{"xsize":1,"ysize":1,"zsize":2,"data":[1,2]}"#)
+                .is_err()
+        );
+
+        assert!(serde_json::from_str::<Table3>(
+            r#"{"xsize":1,"ysize":2,"zsize":2,"data":[1,2,3,4]}"#
+        )
+        .is_ok());
+        assert!(serde_json::from_str::<Table3>(
+            r#"{"xsize":2,"ysize":2,"zsize":2,"data":[1,2,3,4]}"#
+        )
+        .is_err());
+
+        // the size overflows instead of matching the data
+        let huge = format!(
+            r#"{{"xsize":{},"ysize":2,"zsize":2,"data":[]}}"#,
+            usize::MAX
+        );
+        assert!(serde_json::from_str::<Table3>(&huge).is_err());
+    }
+}
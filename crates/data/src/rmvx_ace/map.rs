@@ -0,0 +1,47 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, option_vec, Table3};
+
+#[derive(Default, Debug, serde::Deserialize)]
+#[derive(alox_48::Deserialize)]
+#[marshal(class = "RPG::Map")]
+pub struct Map {
+    pub display_name: String,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub tileset_id: usize,
+    pub width: usize,
+    pub height: usize,
+    pub parallax_name: String,
+    pub note: String,
+    /// Four layers: the lower and upper layers of A tiles, the layer of B to E tiles, and a layer
+    /// with shadows in the lowest 4 bits and the region ID in the upper 8.
+    pub data: Table3,
+    pub events: option_vec::OptionVec<Event>,
+}
+
+/// An event on a VX Ace map. The pages are left out, since they aren't needed to show where events
+/// are.
+#[derive(Default, Debug, serde::Deserialize)]
+#[derive(alox_48::Deserialize)]
+#[marshal(class = "RPG::Event")]
+pub struct Event {
+    pub id: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
@@ -0,0 +1,25 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPG Maker VX Ace data. Luminol can't edit VX Ace projects yet, so only what's needed to look at
+//! their maps is here, and it can only be read. Instance variables that aren't listed are ignored.
+
+pub mod map;
+pub mod tileset;
+
+pub use map::{Event, Map};
+pub use tileset::Tileset;
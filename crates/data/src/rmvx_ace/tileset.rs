@@ -0,0 +1,33 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, Table1};
+
+#[derive(Default, Debug, serde::Deserialize)]
+#[derive(alox_48::Deserialize)]
+#[marshal(class = "RPG::Tileset")]
+pub struct Tileset {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    /// 0 for a field map, 1 for an area map and 2 for a VX compatible map.
+    pub mode: i32,
+    /// The graphics in `Graphics/Tilesets` for A1 to A5 and then B to E.
+    pub tileset_names: Vec<String>,
+    pub flags: Table1,
+    pub note: String,
+}
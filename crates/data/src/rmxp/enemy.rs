@@ -19,7 +19,7 @@ pub use crate::{
     optional_path_serde, Path, Table1,
 };
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Enemy")]
 pub struct Enemy {
@@ -65,7 +65,7 @@ pub struct Enemy {
     pub treasure_prob: i32,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Enemy::Action")]
 pub struct Action {
@@ -100,6 +100,80 @@ impl Default for Action {
     }
 }
 
+/// The state of a battle, used to check which actions an enemy can choose from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BattleState {
+    /// The current turn, starting at 1.
+    pub turn: i32,
+    /// The enemy's HP, as a percentage of its max HP.
+    pub hp_percent: i32,
+    /// The highest level among the party members.
+    pub party_level: i32,
+}
+
+impl Default for BattleState {
+    fn default() -> Self {
+        Self {
+            turn: 1,
+            hp_percent: 100,
+            party_level: 1,
+        }
+    }
+}
+
+impl Action {
+    /// Whether this action's conditions are met, using the same checks as
+    /// `Game_Enemy#make_action`.
+    pub fn is_available(&self, state: &BattleState, switch_on: impl FnOnce(usize) -> bool) -> bool {
+        let (n, a, b) = (state.turn, self.condition_turn_a, self.condition_turn_b);
+        if (b == 0 && n != a) || (b > 0 && (n < 1 || n < a || n % b != a % b)) {
+            return false;
+        }
+        if state.hp_percent > self.condition_hp || state.party_level < self.condition_level {
+            return false;
+        }
+        match self.condition_switch_id {
+            Some(id) => switch_on(id),
+            None => true,
+        }
+    }
+}
+
+/// The chance of each action being picked, following `Game_Enemy#make_action`.
+///
+/// Only available actions rated within 3 of the highest rated available action can be picked.
+/// They are weighted by how far above that threshold their rating is, so with a highest rating of
+/// 10, an action rated 10 is picked 3 times as often as one rated 8 and one rated 7 is never
+/// picked. Unavailable actions have a chance of 0.
+pub fn selection_chances(actions: &[Action], available: impl Fn(&Action) -> bool) -> Vec<f32> {
+    let rating_max = actions
+        .iter()
+        .filter(|a| available(a))
+        .map(|a| a.rating)
+        .max()
+        .unwrap_or_default()
+        .max(0);
+    let weight = |action: &Action| {
+        if available(action) {
+            (action.rating - (rating_max - 3)).max(0)
+        } else {
+            0
+        }
+    };
+
+    let total: i32 = actions.iter().map(weight).sum();
+    actions
+        .iter()
+        .map(|action| {
+            if total > 0 {
+                weight(action) as f32 / total as f32
+            } else {
+                0.
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
 #[derive(
     num_enum::TryFromPrimitive,
@@ -142,3 +216,103 @@ pub enum Basic {
     #[strum(to_string = "Do Nothing")]
     DoNothing = 3,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(rating: i32) -> Action {
+        Action {
+            rating,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_ratings_within_3_of_the_best_can_be_picked() {
+        let actions = [action(10), action(8), action(7), action(1)];
+        let chances = selection_chances(&actions, |_| true);
+        assert_eq!(chances, [0.75, 0.25, 0., 0.]);
+    }
+
+    #[test]
+    fn best_rating_is_taken_from_available_actions() {
+        let mut actions = [action(10), action(6), action(5)];
+        actions[0].condition_switch_id = Some(1);
+
+        // With the switch on, the 10 crowds out both other actions
+        let state = BattleState::default();
+        let chances = selection_chances(&actions, |a| a.is_available(&state, |_| true));
+        assert_eq!(chances, [1., 0., 0.]);
+
+        // With it off, the range is computed from the 6 instead
+        let chances = selection_chances(&actions, |a| a.is_available(&state, |_| false));
+        assert_eq!(chances, [0., 0.6, 0.4]);
+    }
+
+    #[test]
+    fn no_available_actions_have_no_chance() {
+        let actions = [action(5), action(5)];
+        assert_eq!(selection_chances(&actions, |_| false), [0., 0.]);
+        assert!(selection_chances(&[], |_| true).is_empty());
+    }
+
+    #[test]
+    fn turn_conditions_follow_a_plus_bx() {
+        let turn = |turn| BattleState {
+            turn,
+            ..Default::default()
+        };
+
+        // Turns 3, 6, 9...
+        let every_third = Action {
+            condition_turn_a: 3,
+            condition_turn_b: 3,
+            ..Default::default()
+        };
+        let turns: Vec<_> = (1..=10)
+            .filter(|&n| every_third.is_available(&turn(n), |_| true))
+            .collect();
+        assert_eq!(turns, [3, 6, 9]);
+
+        // Turns 5, 8, 11... An offset past the interval shifts the first turn, with no earlier
+        // turns in the same pattern
+        let shifted = Action {
+            condition_turn_a: 5,
+            condition_turn_b: 3,
+            ..Default::default()
+        };
+        let turns: Vec<_> = (1..=10)
+            .filter(|&n| shifted.is_available(&turn(n), |_| true))
+            .collect();
+        assert_eq!(turns, [5, 8]);
+
+        // An interval of 0 means only that one turn
+        let once = Action {
+            condition_turn_a: 4,
+            condition_turn_b: 0,
+            ..Default::default()
+        };
+        let turns: Vec<_> = (0..=10)
+            .filter(|&n| once.is_available(&turn(n), |_| true))
+            .collect();
+        assert_eq!(turns, [4]);
+    }
+
+    #[test]
+    fn hp_and_level_conditions() {
+        let action = Action {
+            condition_hp: 50,
+            condition_level: 10,
+            ..Default::default()
+        };
+        let state = |hp_percent, party_level| BattleState {
+            turn: 1,
+            hp_percent,
+            party_level,
+        };
+        assert!(action.is_available(&state(50, 10), |_| true));
+        assert!(!action.is_available(&state(51, 10), |_| true));
+        assert!(!action.is_available(&state(50, 9), |_| true));
+    }
+}
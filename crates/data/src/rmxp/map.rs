@@ -14,10 +14,10 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-use crate::rpg::{AudioFile, Event};
-use crate::{id_alox, id_serde, option_vec, Table3};
+use crate::rpg::{AudioFile, Event, EventCommand};
+use crate::{id_alox, id_serde, option_vec, ParameterType, Table3};
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Map")]
 pub struct Map {
@@ -39,3 +39,144 @@ pub struct Map {
     #[serde(skip)]
     pub modified: bool,
 }
+
+impl Map {
+    /// The renumbering that makes this map's event IDs contiguous from 1, keeping their order, as
+    /// pairs of old and new IDs. Empty if they already are.
+    pub fn event_id_compaction(&self) -> Vec<(usize, usize)> {
+        self.events
+            .iter()
+            .map(|(id, _)| id)
+            .zip(1..)
+            .filter(|(old, new)| old != new)
+            .collect()
+    }
+
+    /// Gives events new IDs, given as pairs of old and new IDs, and updates the event commands on
+    /// this map that refer to those events.
+    ///
+    /// Scripts, and common events or troops that refer to events on this map, are not updated.
+    pub fn renumber_events(&mut self, renumbering: &[(usize, usize)]) {
+        // only events that exist are moved, so references to any other ID are left as they are
+        let renumbering: std::collections::HashMap<_, _> = renumbering
+            .iter()
+            .copied()
+            .filter(|&(old, _)| self.events.contains(old))
+            .collect();
+        let pairs: Vec<_> = renumbering.iter().map(|(&old, &new)| (old, new)).collect();
+        self.events.renumber(&pairs);
+
+        for (id, event) in self.events.iter_mut() {
+            event.id = id;
+            for page in event.pages.iter_mut() {
+                renumber_event_references(&mut page.list, &renumbering);
+            }
+        }
+    }
+}
+
+/// Updates the event command parameters that refer to events by ID.
+fn renumber_event_references(
+    list: &mut [EventCommand],
+    renumbering: &std::collections::HashMap<usize, usize>,
+) {
+    let renumber = |parameter: Option<&mut ParameterType>| {
+        if let Some(ParameterType::Integer(id)) = parameter {
+            // -1 is the player and 0 is the event running the command
+            if let Some(&new_id) = usize::try_from(*id)
+                .ok()
+                .and_then(|id| renumbering.get(&id))
+            {
+                *id = new_id as i32;
+            }
+        }
+    };
+    let is = |parameter: Option<&ParameterType>, value: i32| matches!(parameter, Some(&ParameterType::Integer(v)) if v == value);
+
+    for command in list {
+        let parameters = &mut command.parameters;
+        match command.code {
+            // Conditional Branch on a character's direction
+            111 if is(parameters.first(), 6) => renumber(parameters.get_mut(1)),
+            // Control Variables set to a character's position or direction
+            122 if is(parameters.get(3), 6) => renumber(parameters.get_mut(4)),
+            // Set Event Location, possibly swapping with another event
+            202 => {
+                renumber(parameters.get_mut(0));
+                if is(parameters.get(1), 2) {
+                    renumber(parameters.get_mut(2));
+                }
+            }
+            // Show Animation and Set Move Route
+            207 | 209 => renumber(parameters.get_mut(0)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(code: u16, parameters: &[i32]) -> EventCommand {
+        EventCommand {
+            code,
+            indent: 0,
+            parameters: parameters
+                .iter()
+                .map(|&p| ParameterType::Integer(p))
+                .collect(),
+            guid: 0,
+        }
+    }
+
+    fn map_with_events(ids: &[usize], list: Vec<EventCommand>) -> Map {
+        let mut map = Map::default();
+        for &id in ids {
+            let mut event = Event::new(0, 0, id);
+            event.pages[0].list.clone_from(&list);
+            map.events.insert(id, event);
+        }
+        map
+    }
+
+    fn targets(map: &Map, id: usize) -> Vec<i32> {
+        map.events[id].pages[0]
+            .list
+            .iter()
+            .map(|command| match command.parameters[0] {
+                ParameterType::Integer(id) => id,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compaction_renumbers_references_to_moved_events() {
+        // Show Animation on the player, this event, both events and a deleted one
+        let list = [-1, 0, 2, 5, 3].map(|id| command(207, &[id, 1])).to_vec();
+        let mut map = map_with_events(&[2, 5], list);
+
+        let renumbering = map.event_id_compaction();
+        assert_eq!(renumbering, [(2, 1), (5, 2)]);
+        map.renumber_events(&renumbering);
+
+        assert_eq!(
+            map.events.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(targets(&map, 1), [-1, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn references_to_missing_events_are_left_alone() {
+        let list = [1, 3, 4].map(|id| command(207, &[id, 1])).to_vec();
+        let mut map = map_with_events(&[1], list);
+
+        // neither 3 nor 4 is an event on this map
+        map.renumber_events(&[(1, 2), (3, 1), (4, 5)]);
+
+        assert_eq!(map.events.iter().map(|(id, _)| id).collect::<Vec<_>>(), [2]);
+        assert_eq!(targets(&map, 2), [2, 3, 4]);
+    }
+}
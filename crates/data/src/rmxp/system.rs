@@ -20,7 +20,7 @@ pub use crate::{
     Path,
 };
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 #[derive(serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[serde(default)] // ??? rmxp???
@@ -93,7 +93,7 @@ pub struct System {
     pub modified: bool,
 }
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::System::Words")]
 #[serde(default)]
@@ -120,7 +120,7 @@ pub struct Words {
     equip: String,
 }
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::System::TestBattler")]
 pub struct TestBattler {
@@ -17,7 +17,7 @@
 
 use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, BlendMode, Path, Table1};
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Tileset")]
 pub struct Tileset {
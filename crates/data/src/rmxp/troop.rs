@@ -16,7 +16,7 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{id_alox, id_serde, optional_id_alox, optional_id_serde, rpg::EventCommand};
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Troop")]
 pub struct Troop {
@@ -28,7 +28,18 @@ pub struct Troop {
     pub pages: Vec<Page>,
 }
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+impl Default for Troop {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: String::new(),
+            members: vec![],
+            pages: vec![Page::default()],
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Troop::Member")]
 pub struct Member {
@@ -41,16 +52,16 @@ pub struct Member {
     pub immortal: bool,
 }
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Troop::Page")]
 pub struct Page {
     pub condition: Condition,
-    pub span: i32,
+    pub span: Span,
     pub list: Vec<EventCommand>,
 }
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Troop::Page::Condition")]
 pub struct Condition {
@@ -70,3 +81,46 @@ pub struct Condition {
     #[marshal(with = "optional_id_alox")]
     pub switch_id: Option<usize>,
 }
+
+impl Default for Condition {
+    fn default() -> Self {
+        Self {
+            turn_valid: false,
+            enemy_valid: false,
+            actor_valid: false,
+            switch_valid: false,
+            turn_a: 0,
+            turn_b: 0,
+            enemy_index: 0,
+            enemy_hp: 50,
+            actor_id: Some(0),
+            actor_hp: 50,
+            switch_id: Some(0),
+        }
+    }
+}
+
+/// How often a troop page can run once its conditions are met.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum Span {
+    /// Once per battle.
+    #[default]
+    Battle = 0,
+    /// Once per turn.
+    Turn = 1,
+    /// Every frame while the conditions are met.
+    Moment = 2,
+}
@@ -290,7 +290,12 @@ impl Default for WgpuConfiguration {
                     required_limits: wgpu::Limits {
                         // When using a depth buffer, we have to be able to create a texture
                         // large enough for the entire surface, and we want to support 4k+ displays.
-                        max_texture_dimension_2d: 8192,
+                        // Older adapters can't do 8192, in which case Luminol falls back to smaller
+                        // atlases instead of failing to create the device.
+                        max_texture_dimension_2d: adapter
+                            .limits()
+                            .max_texture_dimension_2d
+                            .min(8192),
                         ..base_limits
                     },
                 }
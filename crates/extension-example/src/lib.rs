@@ -0,0 +1,138 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! An example of a Luminol extension.
+//!
+//! This adds a "Project Notes" window for writing down notes about the currently open project,
+//! which are saved to `.luminol/notes.txt`.
+//!
+//! To add an extension to Luminol, depend on its crate from the launcher and register it before
+//! the app starts:
+//!
+//! ```ignore
+//! luminol_core::register_extension(luminol_extension_example::NotesExtension);
+//! ```
+//!
+//! The launcher does this for this crate when it's built with the `example-extension` feature.
+
+use color_eyre::eyre::WrapErr;
+use luminol_core::extension::MenuEntry;
+use luminol_filesystem::FileSystem;
+
+/// The extension itself. It only tells Luminol which menu entries to add.
+pub struct NotesExtension;
+
+impl luminol_core::LuminolExtension for NotesExtension {
+    fn name(&self) -> &str {
+        "Notes"
+    }
+
+    fn menu_entries(&self) -> Vec<MenuEntry> {
+        vec![MenuEntry::window("Project Notes", |update_state| {
+            Box::new(NotesWindow::new(update_state))
+        })
+        .requires_project()]
+    }
+}
+
+/// A window for editing the project's notes.
+pub struct NotesWindow {
+    notes: String,
+    modified: bool,
+}
+
+impl NotesWindow {
+    const PATH: &'static str = ".luminol/notes.txt";
+
+    pub fn new(update_state: &mut luminol_core::UpdateState<'_>) -> Self {
+        Self {
+            notes: update_state
+                .filesystem
+                .read_to_string(Self::PATH)
+                .unwrap_or_default(),
+            modified: false,
+        }
+    }
+
+    fn save(&mut self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        filesystem
+            .write(Self::PATH, &self.notes)
+            .wrap_err("While saving project notes")?;
+        self.modified = false;
+        Ok(())
+    }
+}
+
+impl luminol_core::Window for NotesWindow {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_extension_example_notes")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let title = if self.modified {
+            "Project Notes*"
+        } else {
+            "Project Notes"
+        };
+
+        egui::Window::new(title)
+            .id(self.id())
+            .open(open)
+            .default_size([400., 300.])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.modified, egui::Button::new("Save"))
+                        .clicked()
+                    {
+                        if let Err(error) = self.save(update_state.filesystem) {
+                            luminol_core::error!(update_state.toasts, error);
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let response = ui.add(
+                            egui::TextEdit::multiline(&mut self.notes)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(10),
+                        );
+                        self.modified |= response.changed();
+                    });
+            });
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
@@ -832,6 +832,10 @@ where
                 Ok(DirEntry { path, metadata })
             })
             .try_collect()
+            .map(|mut entries: Vec<_>| {
+                crate::sort_dir_entries(&mut entries);
+                entries
+            })
         } else {
             Err(Error::NotExist).wrap_err_with(|| c.clone())
         }
@@ -22,6 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::fmt::Write;
 use std::sync::Arc;
 
 use dashmap::{DashMap, DashSet};
@@ -38,8 +39,37 @@ pub const BYTES_LOADER_ID: &str = egui::load::generate_loader_id!(BytesLoader);
 
 pub const PROTOCOL: &str = "project://";
 
-fn supported_uri_to_path(uri: &str) -> Option<&camino::Utf8Path> {
-    uri.strip_prefix(PROTOCOL).map(camino::Utf8Path::new)
+/// Returns the URI `egui::Image` loads a project file through.
+///
+/// The path is percent-encoded, since egui's loaders treat characters like `#` and `?` in a URI
+/// as the start of a fragment or query, and wouldn't see the rest of the file name.
+pub fn path_to_uri(path: impl AsRef<camino::Utf8Path>) -> String {
+    let mut uri = String::from(PROTOCOL);
+    for byte in path.as_ref().as_str().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            let _ = write!(uri, "%{byte:02X}");
+        }
+    }
+    uri
+}
+
+fn supported_uri_to_path(uri: &str) -> Option<camino::Utf8PathBuf> {
+    let encoded = uri.strip_prefix(PROTOCOL)?;
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut iter = encoded.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let high = (iter.next()? as char).to_digit(16)?;
+            let low = (iter.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok().map(camino::Utf8PathBuf::from)
 }
 
 impl Loader {
@@ -77,7 +107,7 @@ impl egui::load::BytesLoader for Loader {
             return Err(LoadError::NotSupported);
         };
 
-        if let Some(bytes) = self.loaded_files.get(path) {
+        if let Some(bytes) = self.loaded_files.get(&path) {
             return Ok(BytesPoll::Ready {
                 size: None,
                 bytes: Bytes::Shared(bytes.clone()),
@@ -85,11 +115,11 @@ impl egui::load::BytesLoader for Loader {
             });
         }
 
-        if let Some(error) = self.errored_files.get(path) {
+        if let Some(error) = self.errored_files.get(&path) {
             return Err(LoadError::Loading(error.to_string()));
         }
 
-        self.unloaded_files.insert(path.to_path_buf());
+        self.unloaded_files.insert(path);
         Ok(BytesPoll::Pending { size: None })
     }
 
@@ -98,9 +128,9 @@ impl egui::load::BytesLoader for Loader {
             return;
         };
 
-        self.loaded_files.remove(path);
-        self.errored_files.remove(path);
-        self.unloaded_files.remove(path);
+        self.loaded_files.remove(&path);
+        self.errored_files.remove(&path);
+        self.unloaded_files.remove(&path);
     }
 
     fn forget_all(&self) {
@@ -113,3 +143,65 @@ impl egui::load::BytesLoader for Loader {
         self.loaded_files.iter().map(|e| e.len()).sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADVERSARIAL_PATHS: [&str; 9] = [
+        "Graphics/Pictures/a b.png",
+        "Graphics/Pictures/100%.png",
+        "Graphics/Pictures/a#b?.png",
+        "Graphics/Pictures/%41.png",
+        "Graphics/Characters/勇者 ç.png",
+        "Graphics/Pictures/../Titles/title.png",
+        "C:\\Users\\Name\\Project\\Graphics\\Icons\\001-Weapon01.png",
+        "C:/Users/Name/Project/Graphics/Icons/001-Weapon01.png",
+        "Graphics/Pictures/.png",
+    ];
+
+    #[test]
+    fn adversarial_paths_round_trip() {
+        for path in ADVERSARIAL_PATHS {
+            let uri = path_to_uri(path);
+            assert_eq!(
+                supported_uri_to_path(&uri).as_deref(),
+                Some(camino::Utf8Path::new(path)),
+                "{uri}"
+            );
+        }
+    }
+
+    #[test]
+    fn uris_only_contain_unreserved_characters() {
+        for path in ADVERSARIAL_PATHS {
+            let uri = path_to_uri(path);
+            let encoded = uri.strip_prefix(PROTOCOL).unwrap();
+            assert!(
+                encoded
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-._~/%".contains(&b)),
+                "{uri}"
+            );
+        }
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        assert_eq!(path_to_uri("a b"), "project://a%20b");
+        assert_eq!(path_to_uri("100%"), "project://100%25");
+        assert_eq!(path_to_uri("a#b?"), "project://a%23b%3F");
+        assert_eq!(path_to_uri("C:\\x"), "project://C%3A%5Cx");
+        assert_eq!(path_to_uri("é"), "project://%C3%A9");
+        assert_eq!(path_to_uri("../x"), "project://../x");
+    }
+
+    #[test]
+    fn malformed_uris_are_rejected() {
+        assert_eq!(supported_uri_to_path("file://a.png"), None);
+        assert_eq!(supported_uri_to_path("project://a%2"), None);
+        assert_eq!(supported_uri_to_path("project://a%zz.png"), None);
+        // A lone continuation byte isn't valid UTF-8
+        assert_eq!(supported_uri_to_path("project://%80"), None);
+    }
+}
@@ -21,6 +21,7 @@ pub mod erased;
 pub mod list;
 pub mod path_cache;
 pub mod project;
+pub mod project_lock;
 
 mod trie;
 pub use trie::*;
@@ -59,6 +60,14 @@ pub enum Error {
     NoFilesystems,
     #[error("Unable to detect the project's RPG Maker version (perhaps you did not open an RPG Maker project?")]
     UnableToDetectRMVer,
+    #[error(
+        "{version} projects stored as Ruby Marshal data can't be opened yet. Not supported yet:\n- {}",
+        .unsupported.join("\n- ")
+    )]
+    UnsupportedRMVer {
+        version: luminol_config::RMVer,
+        unsupported: &'static [&'static str],
+    },
     #[error("Cancelled loading project")]
     CancelledLoading,
     #[error("Your browser does not support File System Access API")]
@@ -119,6 +128,22 @@ pub struct Metadata {
     pub size: u64,
 }
 
+/// Sorts directory entries into the order [`FileSystem::read_dir`] returns them in: directories
+/// first, then by file name in case-insensitive natural order, so `Map2` comes before `Map10`.
+/// File names that only differ in case are sorted by their bytes, so the order never depends on
+/// the order the entries were found in.
+pub fn sort_dir_entries(entries: &mut [DirEntry]) {
+    entries.sort_unstable_by(|a, b| {
+        a.metadata
+            .is_file
+            .cmp(&b.metadata.is_file)
+            .then_with(|| lexical_sort::natural_lexical_cmp(a.file_name(), b.file_name()))
+            // Names that only differ in case are ordered by their bytes, so the order is total
+            // no matter which comparator is used above
+            .then_with(|| a.file_name().cmp(b.file_name()))
+    });
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct DirEntry {
     pub path: camino::Utf8PathBuf,
@@ -225,6 +250,10 @@ pub trait FileSystem: Send + Sync {
         }
     }
 
+    /// Lists the entries of a directory.
+    ///
+    /// Entries are always ordered by [`sort_dir_entries`], whatever order the host gives them in,
+    /// so listings look the same on every platform.
     fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>>;
 
     /// Corresponds to [`std::fs::read()`].
@@ -275,3 +304,69 @@ where
         self.read_dir(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, is_file: bool) -> DirEntry {
+        DirEntry::new(path.into(), Metadata { is_file, size: 0 })
+    }
+
+    fn sorted(mut entries: Vec<DirEntry>) -> Vec<String> {
+        sort_dir_entries(&mut entries);
+        entries.iter().map(|e| e.file_name().to_string()).collect()
+    }
+
+    #[test]
+    fn numbers_sort_naturally() {
+        let names = [
+            "Map10.rxdata",
+            "Map2.rxdata",
+            "Map1.rxdata",
+            "Map100.rxdata",
+        ]
+        .map(|name| entry(name, true))
+        .to_vec();
+        assert_eq!(
+            sorted(names),
+            [
+                "Map1.rxdata",
+                "Map2.rxdata",
+                "Map10.rxdata",
+                "Map100.rxdata"
+            ]
+        );
+    }
+
+    #[test]
+    fn case_is_ignored_except_to_break_ties() {
+        let names = ["b.png", "A.png", "a.png", "B.png"]
+            .map(|name| entry(name, true))
+            .to_vec();
+        assert_eq!(sorted(names), ["A.png", "a.png", "B.png", "b.png"]);
+    }
+
+    #[test]
+    fn directories_come_first() {
+        let names = vec![
+            entry("Audio", false),
+            entry("Game.ini", true),
+            entry("Data", false),
+            entry("Game.exe", true),
+        ];
+        assert_eq!(sorted(names), ["Audio", "Data", "Game.exe", "Game.ini"]);
+    }
+
+    #[test]
+    fn order_does_not_depend_on_input_order() {
+        let names = [
+            "x.png", "X.png", "x1.png", "X1.png", "x01.png", "x10.png", "x2.png",
+        ];
+        let forward = sorted(names.map(|name| entry(name, true)).to_vec());
+        let mut reversed = names;
+        reversed.reverse();
+        let backward = sorted(reversed.map(|name| entry(name, true)).to_vec());
+        assert_eq!(forward, backward);
+    }
+}
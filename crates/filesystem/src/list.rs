@@ -150,7 +150,8 @@ impl crate::FileSystem for FileSystem {
             }
         }
         // FIXME: remove duplicates in a more efficient manner
-        let entries = entries.into_iter().unique().collect_vec();
+        let mut entries = entries.into_iter().unique().collect_vec();
+        crate::sort_dir_entries(&mut entries);
 
         Ok(entries)
     }
@@ -206,6 +206,10 @@ impl crate::FileSystem for FileSystem {
             })
             .flatten()
             .try_collect()
+            .map(|mut entries: Vec<_>| {
+                crate::sort_dir_entries(&mut entries);
+                entries
+            })
     }
 }
 
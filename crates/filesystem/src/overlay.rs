@@ -202,7 +202,8 @@ where
             entries.extend(self.secondary.read_dir(path)?);
         }
         // FIXME: remove duplicates in a more efficient manner
-        let entries = entries.into_iter().unique().collect_vec();
+        let mut entries = entries.into_iter().unique().collect_vec();
+        crate::sort_dir_entries(&mut entries);
 
         Ok(entries)
     }
@@ -605,6 +605,8 @@ where
             .desensitize(path)
             .ok_or(Error::NotExist)
             .wrap_err_with(|| c.clone())?;
-        self.fs.read_dir(path).wrap_err_with(|| c.clone())
+        let mut entries = self.fs.read_dir(path).wrap_err_with(|| c.clone())?;
+        crate::sort_dir_entries(&mut entries);
+        Ok(entries)
     }
 }
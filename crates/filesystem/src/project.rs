@@ -76,35 +76,36 @@ impl FileSystem {
 
 // Not platform specific
 impl FileSystem {
+    /// Works out which RPG Maker made the project in this folder. The project file is the most
+    /// reliable hint since it's what RPG Maker opens, then the data files, and last the archive of
+    /// a deployed game.
     fn detect_rm_ver(&self) -> Option<luminol_config::RMVer> {
-        if self.exists("Data/Actors.rxdata").ok()? {
-            return Some(luminol_config::RMVer::XP);
-        }
-
-        if self.exists("Data/Actors.rvdata").ok()? {
-            return Some(luminol_config::RMVer::VX);
-        }
-
-        if self.exists("Data/Actors.rvdata2").ok()? {
-            return Some(luminol_config::RMVer::Ace);
-        }
-
-        for path in self.read_dir("").ok()? {
-            let path = path.path();
-            if path.extension() == Some("rgssad") {
-                return Some(luminol_config::RMVer::XP);
-            }
-
-            if path.extension() == Some("rgss2a") {
-                return Some(luminol_config::RMVer::VX);
-            }
+        use luminol_config::RMVer;
 
-            if path.extension() == Some("rgss3a") {
-                return Some(luminol_config::RMVer::Ace);
-            }
-        }
+        const VERSIONS: [RMVer; 3] = [RMVer::XP, RMVer::VX, RMVer::Ace];
+        let entries = self.read_dir("").ok()?;
+        let has_file_with_extension = |extension: &str| {
+            entries
+                .iter()
+                .any(|entry| entry.metadata.is_file && entry.path.extension() == Some(extension))
+        };
 
-        None
+        VERSIONS
+            .into_iter()
+            .find(|ver| has_file_with_extension(ver.project_extension()))
+            .or_else(|| {
+                VERSIONS.into_iter().find(|ver| {
+                    ["Actors", "System", "MapInfos"].into_iter().any(|name| {
+                        self.exists(format!("Data/{name}.{}", ver.data_extension()))
+                            .unwrap_or_default()
+                    })
+                })
+            })
+            .or_else(|| {
+                VERSIONS
+                    .into_iter()
+                    .find(|ver| has_file_with_extension(ver.archive_extension()))
+            })
     }
 
     fn load_project_config(&self) -> Result<luminol_config::project::Config> {
@@ -146,11 +147,23 @@ impl FileSystem {
             .ok()
             .and_then(|s| ron::from_str::<luminol_config::project::Project>(&s).ok())
         {
-            Some(config) if config.persistence_id != 0 => config,
+            Some(config)
+                if config.persistence_id != 0
+                    && self
+                        .detect_rm_ver()
+                        .map_or(true, |ver| ver == config.editor_ver) =>
+            {
+                config
+            }
             Some(mut config) => {
                 while config.persistence_id == 0 {
                     config.persistence_id = rand::random();
                 }
+                // Older versions of Luminol assumed RPG Maker XP for any folder they didn't
+                // recognize, so the saved version may be wrong
+                if let Some(editor_ver) = self.detect_rm_ver() {
+                    config.editor_ver = editor_ver;
+                }
                 self.write(
                     ".luminol/config",
                     ron::ser::to_string_pretty(&config, pretty_config.clone()).wrap_err(c)?,
@@ -237,6 +250,20 @@ impl FileSystem {
         *self = FileSystem::HostLoaded(host);
         let config = self.load_project_config().wrap_err(c)?;
 
+        // Fail here with a list of what's missing rather than later with a confusing error while
+        // deserializing the data
+        let unsupported = config
+            .project
+            .editor_ver
+            .unsupported_features(config.project.data_format);
+        if !unsupported.is_empty() {
+            return Err(Error::UnsupportedRMVer {
+                version: config.project.editor_ver,
+                unsupported,
+            })
+            .wrap_err(c);
+        }
+
         let Self::HostLoaded(host) = std::mem::take(self) else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::PermissionDenied,
@@ -736,3 +763,132 @@ impl crate::FileSystem for FileSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use luminol_config::RMVer;
+
+    /// Copies one of the project folders in `fixtures/projects` to a temporary directory, since
+    /// loading a project writes its config.
+    fn fixture(name: &str) -> (tempfile::TempDir, host::FileSystem) {
+        fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+            std::fs::create_dir_all(to).unwrap();
+            for entry in std::fs::read_dir(from).unwrap() {
+                let entry = entry.unwrap();
+                let to = to.join(entry.file_name());
+                if entry.file_type().unwrap().is_dir() {
+                    copy_dir(&entry.path(), &to);
+                } else {
+                    std::fs::copy(entry.path(), to).unwrap();
+                }
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let from = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/projects")
+            .join(name);
+        copy_dir(&from, dir.path());
+        let host = host::FileSystem::new(camino::Utf8Path::from_path(dir.path()).unwrap());
+        (dir, host)
+    }
+
+    fn detect(name: &str) -> Option<RMVer> {
+        let (_dir, host) = fixture(name);
+        FileSystem::HostLoaded(host).detect_rm_ver()
+    }
+
+    #[test]
+    fn detects_the_version_from_the_project_file() {
+        assert_eq!(detect("xp"), Some(RMVer::XP));
+        assert_eq!(detect("vx"), Some(RMVer::VX));
+        assert_eq!(detect("ace"), Some(RMVer::Ace));
+    }
+
+    #[test]
+    fn detects_the_version_without_a_project_file() {
+        assert_eq!(detect("xp_data_only"), Some(RMVer::XP));
+        assert_eq!(detect("ace_deployed"), Some(RMVer::Ace));
+        assert_eq!(detect("empty"), None);
+    }
+
+    #[test]
+    fn project_file_wins_over_stale_data() {
+        assert_eq!(detect("ace_stale_config"), Some(RMVer::Ace));
+    }
+
+    #[test]
+    fn loads_the_config_of_an_xp_project() {
+        let (_dir, host) = fixture("xp");
+        let filesystem = FileSystem::HostLoaded(host);
+        let config = filesystem.load_project_config().unwrap();
+
+        assert_eq!(config.project.editor_ver, RMVer::XP);
+        assert!(config
+            .project
+            .editor_ver
+            .unsupported_features(config.project.data_format)
+            .is_empty());
+        assert_eq!(
+            config.game_ini.get_from(Some("Game"), "Title"),
+            Some("XP Fixture")
+        );
+        // The new config is written so the next load doesn't have to detect the version again
+        assert!(filesystem.exists(".luminol/config").unwrap());
+        assert!(filesystem.exists(".luminol/commands").unwrap());
+    }
+
+    #[test]
+    fn corrects_a_stale_version_in_the_config() {
+        let (_dir, host) = fixture("ace_stale_config");
+        let filesystem = FileSystem::HostLoaded(host);
+        let config = filesystem.load_project_config().unwrap();
+
+        assert_eq!(config.project.editor_ver, RMVer::Ace);
+        assert_eq!(config.project.project_name, "Stale");
+        assert_eq!(config.project.persistence_id, 1234);
+
+        let saved = filesystem.read_to_string(".luminol/config").unwrap();
+        let saved: luminol_config::project::Project = ron::from_str(&saved).unwrap();
+        assert_eq!(saved.editor_ver, RMVer::Ace);
+    }
+
+    #[test]
+    fn rejects_unsupported_versions_before_loading_data() {
+        for (name, version) in [("vx", RMVer::VX), ("ace", RMVer::Ace)] {
+            let (_dir, host) = fixture(name);
+            let mut filesystem = FileSystem::new();
+            let mut project_config = None;
+            let mut global_config = luminol_config::global::Config::new();
+
+            let Err(error) = filesystem.load_project(host, &mut project_config, &mut global_config)
+            else {
+                panic!("{name} fixture loaded");
+            };
+            match error.downcast_ref::<Error>() {
+                Some(Error::UnsupportedRMVer {
+                    version: error_version,
+                    unsupported,
+                }) => {
+                    assert_eq!(*error_version, version);
+                    assert!(!unsupported.is_empty());
+                }
+                _ => panic!("unexpected error for the {name} fixture: {error:?}"),
+            }
+            assert!(project_config.is_none());
+        }
+    }
+
+    #[test]
+    fn refuses_folders_without_a_project() {
+        let (_dir, host) = fixture("empty");
+        let error = FileSystem::HostLoaded(host)
+            .load_project_config()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::UnableToDetectRMVer)
+        ));
+    }
+}
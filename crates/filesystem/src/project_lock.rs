@@ -0,0 +1,364 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Guards against more than one editor saving to the same project at once.
+//!
+//! On native, the editor that opened a project writes its process ID to [`LOCK_FILE`] and removes
+//! it again when the project is closed. A lock file left behind by a process that isn't running
+//! anymore is ignored. On web, tabs can't see each other's processes, so tabs holding a project
+//! answer pings sent over a `BroadcastChannel` instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::FileSystem as _;
+#[cfg(not(target_arch = "wasm32"))]
+use color_eyre::eyre::WrapErr;
+
+/// Where the lock is written on native, relative to the project root.
+pub const LOCK_FILE: &str = ".luminol/lock";
+
+/// The host path of the lock file held by this process, so it can be removed after a panic.
+#[cfg(not(target_arch = "wasm32"))]
+static HELD_LOCK: std::sync::Mutex<Option<camino::Utf8PathBuf>> = std::sync::Mutex::new(None);
+
+/// The other editor that has a project open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    /// The process ID of the other editor. Always `None` on web.
+    pub pid: Option<u32>,
+    /// When the other editor locked the project, in seconds since the Unix epoch.
+    pub timestamp: Option<u64>,
+}
+
+impl std::fmt::Display for Holder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(pid) = self.pid else {
+            return f.write_str("another browser tab");
+        };
+        write!(f, "another Luminol window (process {pid})")?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        match self
+            .timestamp
+            .map(|timestamp| now.saturating_sub(timestamp) / 60)
+        {
+            Some(0) => f.write_str(", opened less than a minute ago"),
+            Some(1) => f.write_str(", opened a minute ago"),
+            Some(minutes) if minutes < 120 => write!(f, ", opened {minutes} minutes ago"),
+            Some(minutes) => write!(f, ", opened {} hours ago", minutes / 60),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A project's lock, created by [`ProjectLock::acquire`] when the project is opened.
+#[derive(Debug)]
+pub struct ProjectLock {
+    conflict: Option<Holder>,
+    #[cfg(target_arch = "wasm32")]
+    channel: web::Channel,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ProjectLock {
+    /// Locks the project, unless another editor already has it open.
+    ///
+    /// `persistence_id` identifies the project on web and is unused on native.
+    /// If another editor holds the lock, it isn't taken, and [`Self::conflict`] returns who has it.
+    pub fn acquire(
+        filesystem: &crate::project::FileSystem,
+        _persistence_id: u64,
+    ) -> color_eyre::Result<Self> {
+        let conflict = Self::holder(filesystem).filter(|holder| {
+            holder
+                .pid
+                .is_some_and(|pid| pid != std::process::id() && is_process_alive(pid))
+        });
+
+        let lock = Self { conflict };
+        if lock.conflict.is_none() {
+            lock.write(filesystem)?;
+        }
+        Ok(lock)
+    }
+
+    /// Takes the lock from the editor that holds it.
+    /// That editor will refuse to save once it notices.
+    pub fn steal(&mut self, filesystem: &crate::project::FileSystem) -> color_eyre::Result<()> {
+        self.conflict = None;
+        self.write(filesystem)
+    }
+
+    /// Whether this editor still holds the lock, i.e. it wasn't stolen by another one.
+    pub fn is_held(&self, filesystem: &crate::project::FileSystem) -> bool {
+        Self::holder(filesystem).is_some_and(|holder| holder.pid == Some(std::process::id()))
+    }
+
+    /// Removes the lock file, if it's still this editor's.
+    pub fn release(self, filesystem: &crate::project::FileSystem) -> color_eyre::Result<()> {
+        if self.is_held(filesystem) {
+            filesystem
+                .remove_file(LOCK_FILE)
+                .wrap_err("While removing the project lock")?;
+        }
+        if let Ok(mut held) = HELD_LOCK.lock() {
+            *held = None;
+        }
+        Ok(())
+    }
+
+    fn holder(filesystem: &crate::project::FileSystem) -> Option<Holder> {
+        let contents = filesystem.read_to_string(LOCK_FILE).ok()?;
+        let mut holder = Holder {
+            pid: None,
+            timestamp: None,
+        };
+        for line in contents.lines() {
+            match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("pid", pid)) => holder.pid = pid.parse().ok(),
+                Some(("timestamp", timestamp)) => holder.timestamp = timestamp.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(holder)
+    }
+
+    fn write(&self, filesystem: &crate::project::FileSystem) -> color_eyre::Result<()> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+
+        if !filesystem.exists(".luminol")? {
+            filesystem.create_dir(".luminol")?;
+        }
+        filesystem
+            .write(
+                LOCK_FILE,
+                format!("pid = {}\ntimestamp = {timestamp}\n", std::process::id()),
+            )
+            .wrap_err("While writing the project lock")?;
+
+        if let (Some(project_path), Ok(mut held)) = (filesystem.project_path(), HELD_LOCK.lock()) {
+            *held = Some(project_path.join(LOCK_FILE));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ProjectLock {
+    /// Locks the project, unless another editor already has it open.
+    ///
+    /// `persistence_id` identifies the project on web. Other tabs answer asynchronously, so
+    /// [`Self::conflict`] may only return who has the project open a few frames later.
+    pub fn acquire(
+        _filesystem: &crate::project::FileSystem,
+        persistence_id: u64,
+    ) -> color_eyre::Result<Self> {
+        Ok(Self {
+            conflict: None,
+            channel: web::Channel::new(persistence_id)?,
+        })
+    }
+
+    /// Takes the lock from the tab that holds it.
+    /// That tab will refuse to save once it notices.
+    pub fn steal(&mut self, _filesystem: &crate::project::FileSystem) -> color_eyre::Result<()> {
+        self.conflict = None;
+        self.channel.steal()
+    }
+
+    /// Whether this tab still holds the lock, i.e. it wasn't stolen by another one.
+    pub fn is_held(&self, _filesystem: &crate::project::FileSystem) -> bool {
+        !self.channel.is_stolen()
+    }
+
+    /// Stops answering pings for the project.
+    pub fn release(self, _filesystem: &crate::project::FileSystem) -> color_eyre::Result<()> {
+        Ok(())
+    }
+}
+
+impl ProjectLock {
+    /// Returns the editor that had the project open when it was locked, once.
+    pub fn conflict(&mut self) -> Option<Holder> {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(holder) = self.channel.take_conflict() {
+            self.conflict = Some(holder);
+        }
+        self.conflict.take()
+    }
+}
+
+/// Removes the lock file held by this process, if any.
+/// Meant for the panic handler, where the project filesystem can't be used anymore.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn release_after_panic() {
+    // the panic may have happened while the lock was held, so don't wait for it
+    if let Ok(mut held) = HELD_LOCK.try_lock() {
+        if let Some(path) = held.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_process_alive(pid: u32) -> bool {
+    // signal 0 only checks whether the process exists
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+#[cfg(windows)]
+fn is_process_alive(pid: u32) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_or(true, |output| {
+            String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\""))
+        })
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    use super::Holder;
+
+    const CHANNEL_NAME: &str = "luminol-project-lock";
+
+    #[derive(Debug, Default)]
+    struct State {
+        conflict: Option<Holder>,
+        stolen: bool,
+    }
+
+    /// Answers pings from other tabs for the project while it's held.
+    ///
+    /// Messages are `ping:<id>`, sent when a tab opens a project, `held:<id>`, the answer from
+    /// tabs that already have it open, and `steal:<id>`, sent when a tab takes the lock over.
+    pub(super) struct Channel {
+        channel: web_sys::BroadcastChannel,
+        id: String,
+        state: Rc<RefCell<State>>,
+        _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    }
+
+    impl std::fmt::Debug for Channel {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Channel")
+                .field("id", &self.id)
+                .field("state", &self.state)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl Channel {
+        pub(super) fn new(persistence_id: u64) -> color_eyre::Result<Self> {
+            let channel = web_sys::BroadcastChannel::new(CHANNEL_NAME).map_err(|e| {
+                color_eyre::eyre::eyre!("Could not open a broadcast channel: {e:?}")
+            })?;
+            let id = persistence_id.to_string();
+            let state = Rc::new(RefCell::new(State::default()));
+
+            let on_message = {
+                let channel = channel.clone();
+                let id = id.clone();
+                let state = state.clone();
+                Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+                    move |event: web_sys::MessageEvent| {
+                        let Some(message) = event.data().as_string() else {
+                            return;
+                        };
+                        let Some((kind, message_id)) = message.split_once(':') else {
+                            return;
+                        };
+                        if message_id != id {
+                            return;
+                        }
+
+                        let mut state = state.borrow_mut();
+                        match kind {
+                            "ping" if !state.stolen => {
+                                let _ = channel.post_message(&format!("held:{id}").into());
+                            }
+                            "held" => {
+                                state.conflict = Some(Holder {
+                                    pid: None,
+                                    timestamp: None,
+                                })
+                            }
+                            "steal" => state.stolen = true,
+                            _ => {}
+                        }
+                    },
+                )
+            };
+            channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            channel
+                .post_message(&format!("ping:{id}").into())
+                .map_err(|e| color_eyre::eyre::eyre!("Could not ping other tabs: {e:?}"))?;
+
+            Ok(Self {
+                channel,
+                id,
+                state,
+                _on_message: on_message,
+            })
+        }
+
+        pub(super) fn steal(&self) -> color_eyre::Result<()> {
+            *self.state.borrow_mut() = State::default();
+            self.channel
+                .post_message(&format!("steal:{}", self.id).into())
+                .map_err(|e| color_eyre::eyre::eyre!("Could not notify other tabs: {e:?}"))
+        }
+
+        pub(super) fn is_stolen(&self) -> bool {
+            self.state.borrow().stolen
+        }
+
+        pub(super) fn take_conflict(&self) -> Option<Holder> {
+            self.state.borrow_mut().conflict.take()
+        }
+    }
+
+    impl Drop for Channel {
+        fn drop(&mut self) {
+            self.channel.set_onmessage(None);
+            self.channel.close();
+        }
+    }
+}
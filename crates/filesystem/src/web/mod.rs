@@ -305,8 +305,11 @@ impl FileSystemTrait for FileSystem {
     fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>> {
         let path = path.as_ref();
         let c = format!("While reading the contents of the directory {path:?} in a host folder");
-        send_and_recv(|tx| FileSystemCommand::DirReadDir(self.key, path.to_path_buf(), tx))
-            .wrap_err(c)
+        let mut entries =
+            send_and_recv(|tx| FileSystemCommand::DirReadDir(self.key, path.to_path_buf(), tx))
+                .wrap_err(c)?;
+        crate::sort_dir_entries(&mut entries);
+        Ok(entries)
     }
 }
 
@@ -0,0 +1,69 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// Setting this environment variable to any value forces compatibility mode on, for testing the
+/// fallback paths on hardware that doesn't need them.
+pub const FORCE_COMPATIBILITY_ENV_VAR: &str = "LUMINOL_FORCE_COMPATIBILITY_MODE";
+
+/// The texture size used for atlases when compatibility mode is forced on.
+const FORCED_MAX_TEXTURE_DIMENSION: u32 = 4096;
+
+/// Fallbacks chosen at startup based on what the graphics adapter supports.
+#[derive(Debug, Clone)]
+pub struct Compatibility {
+    /// The largest width or height of an atlas texture.
+    pub max_texture_dimension_2d: u32,
+    /// Human-readable descriptions of every active fallback. Empty if none are active.
+    pub fallbacks: Vec<String>,
+}
+
+impl Compatibility {
+    pub fn new(render_state: &luminol_egui_wgpu::RenderState) -> Self {
+        let mut fallbacks = Vec::new();
+        let mut max_texture_dimension_2d = render_state.device.limits().max_texture_dimension_2d;
+
+        if std::env::var_os(FORCE_COMPATIBILITY_ENV_VAR).is_some() {
+            fallbacks.push(format!(
+                "Compatibility mode was forced on with the {FORCE_COMPATIBILITY_ENV_VAR} environment variable"
+            ));
+            max_texture_dimension_2d = max_texture_dimension_2d.min(FORCED_MAX_TEXTURE_DIMENSION);
+        }
+
+        if max_texture_dimension_2d < crate::primitives::tiles::MAX_SIZE {
+            fallbacks.push(format!(
+                "Textures are limited to {max_texture_dimension_2d}x{max_texture_dimension_2d}, so tilesets are split into more columns in their atlases"
+            ));
+        }
+
+        Self {
+            max_texture_dimension_2d,
+            fallbacks,
+        }
+    }
+
+    /// Returns true if any fallback is active.
+    pub fn is_active(&self) -> bool {
+        !self.fallbacks.is_empty()
+    }
+}
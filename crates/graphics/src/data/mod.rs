@@ -33,3 +33,6 @@ pub use viewport::Viewport;
 
 mod transform;
 pub use transform::Transform;
+
+mod overlay_colors;
+pub use overlay_colors::OverlayColors;
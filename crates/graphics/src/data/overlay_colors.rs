@@ -0,0 +1,117 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use wgpu::util::DeviceExt;
+
+use crate::BindGroupLayoutBuilder;
+
+/// The colors of the overlays drawn on top of maps, shared by every collision and grid renderer so
+/// that changing the overlay palette applies everywhere at once.
+#[derive(Debug)]
+pub struct OverlayColors {
+    data: parking_lot::Mutex<Data>,
+    uniform: wgpu::Buffer,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C, align(16))]
+struct Data {
+    collision: [f32; 4],
+    grid_inner: [f32; 4],
+    grid_outer: [f32; 4],
+}
+
+impl Data {
+    fn new(collision: egui::Color32, grid_inner: egui::Color32, grid_outer: egui::Color32) -> Self {
+        // The shaders output colors in gamma space without premultiplied alpha
+        let to_floats =
+            |color: egui::Color32| color.to_srgba_unmultiplied().map(|c| c as f32 / 255.);
+        Self {
+            collision: to_floats(collision),
+            grid_inner: to_floats(grid_inner),
+            grid_outer: to_floats(grid_outer),
+        }
+    }
+}
+
+impl OverlayColors {
+    pub fn new(
+        render_state: &luminol_egui_wgpu::RenderState,
+        collision: egui::Color32,
+        grid_inner: egui::Color32,
+        grid_outer: egui::Color32,
+    ) -> Self {
+        let data = Data::new(collision, grid_inner, grid_outer);
+        let uniform = render_state
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("overlay colors buffer"),
+                contents: bytemuck::bytes_of(&data),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        Self {
+            data: parking_lot::Mutex::new(data),
+            uniform,
+        }
+    }
+
+    /// Changes the overlay colors. This does nothing if they are already set to these colors, so
+    /// it's cheap to call every frame.
+    pub fn set(
+        &self,
+        render_state: &luminol_egui_wgpu::RenderState,
+        collision: egui::Color32,
+        grid_inner: egui::Color32,
+        grid_outer: egui::Color32,
+    ) {
+        let new_data = Data::new(collision, grid_inner, grid_outer);
+        let mut data = self.data.lock();
+        if *data != new_data {
+            *data = new_data;
+            render_state
+                .queue
+                .write_buffer(&self.uniform, 0, bytemuck::bytes_of(&*data));
+        }
+    }
+
+    pub fn as_buffer(&self) -> &wgpu::Buffer {
+        &self.uniform
+    }
+
+    pub fn add_to_bind_group_layout(
+        layout_builder: &mut BindGroupLayoutBuilder,
+    ) -> &mut BindGroupLayoutBuilder {
+        layout_builder.append(
+            wgpu::ShaderStages::FRAGMENT,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            None,
+        )
+    }
+}
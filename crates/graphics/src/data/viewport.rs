@@ -79,6 +79,18 @@ impl Viewport {
         }
     }
 
+    pub fn size(&self) -> glam::Vec2 {
+        self.data.viewport_size
+    }
+
+    pub fn translation(&self) -> glam::Vec2 {
+        self.data.viewport_translation
+    }
+
+    pub fn scale(&self) -> glam::Vec2 {
+        self.data.viewport_scale
+    }
+
     pub fn as_buffer(&self) -> &wgpu::Buffer {
         &self.uniform
     }
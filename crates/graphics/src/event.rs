@@ -22,6 +22,35 @@ use crate::{Atlas, GraphicsState, Quad, Renderable, Sprite, Transform, Viewport}
 pub struct Event {
     pub sprite: Sprite,
     pub sprite_size: egui::Vec2,
+    /// Only present for events with a character graphic and stepping animation enabled.
+    animation: Option<StepAnimation>,
+}
+
+/// The state needed to cycle through the frames of a character graphic.
+struct StepAnimation {
+    base_pattern: i32,
+    direction: i32,
+    /// How long each frame is shown for, in seconds.
+    frame_duration: f64,
+    /// Offset into the animation, in frames, so that not every event steps at the same time.
+    phase: f64,
+    extents: wgpu::Extent3d,
+}
+
+/// Returns the quad of one cell of a character graphic, where `cell_size` is the size of the
+/// graphic divided by 4 in each dimension.
+fn character_quad(cell_size: egui::Vec2, pattern: i32, direction: i32) -> Quad {
+    let pos = egui::Rect::from_min_size(egui::pos2(0., 0.), cell_size);
+
+    // Reduced by 0.01 px on all sides to reduce texture bleeding
+    let tex_coords = egui::Rect::from_min_size(
+        egui::pos2(
+            pattern as f32 * cell_size.x + 0.01,
+            (direction as f32 - 2.) / 2. * cell_size.y + 0.01,
+        ),
+        cell_size - egui::vec2(0.02, 0.02),
+    );
+    Quad::new(pos, tex_coords)
 }
 
 impl Event {
@@ -58,7 +87,7 @@ impl Event {
         };
 
         let (quad, sprite_size) = if let Some(id) = page.graphic.tile_id {
-            // Why does this have to be + 1?
+            // Graphic tile ids are stored offset by one, since 0 means "no tile"
             let quad = atlas.calc_quad((id + 1) as i16);
 
             (quad, egui::vec2(32., 32.))
@@ -68,29 +97,25 @@ impl Event {
 
             (quad, egui::vec2(32., 32.))
         } else {
-            let cw = texture.width() as f32 / 4.;
-            let ch = texture.height() as f32 / 4.;
-            let pos = egui::Rect::from_min_size(
-                egui::pos2(
-                    0., //(event.x as f32 * 32.) + (16. - (cw / 2.)),
-                    0., //(event.y as f32 * 32.) + (32. - ch),
-                ),
-                egui::vec2(cw, ch),
-            );
-
-            // Reduced by 0.01 px on all sides to reduce texture bleeding
-            let tex_coords = egui::Rect::from_min_size(
-                egui::pos2(
-                    page.graphic.pattern as f32 * cw + 0.01,
-                    (page.graphic.direction as f32 - 2.) / 2. * ch + 0.01,
-                ),
-                egui::vec2(cw - 0.02, ch - 0.02),
-            );
-            let quad = Quad::new(pos, tex_coords);
-
-            (quad, egui::vec2(cw, ch))
+            let cell_size = egui::vec2(texture.width() as f32 / 4., texture.height() as f32 / 4.);
+            let quad = character_quad(cell_size, page.graphic.pattern, page.graphic.direction);
+
+            (quad, cell_size)
         };
 
+        // RPG Maker advances the pattern of a stepping event every (18 - 2 * speed) frames at 40
+        // frames per second
+        let animation = (page.step_anime && page.graphic.tile_id.is_none() && !is_placeholder)
+            .then(|| StepAnimation {
+                base_pattern: page.graphic.pattern,
+                direction: page.graphic.direction,
+                frame_duration: (18. - 2. * u8::from(page.move_speed) as f64) / 40.,
+                // Multiplying by the golden ratio spreads the phases of consecutive event IDs
+                // evenly without needing any randomness
+                phase: (event.id as f64 * 1.618_033_988_749_895).fract() * 4.,
+                extents: texture.size(),
+            });
+
         let x = event.x as f32 * 32. + (32. - sprite_size.x) / 2.;
         let y = event.y as f32 * 32. + (32. - sprite_size.y);
         let transform = Transform::new_position(graphics_state, glam::vec2(x, y));
@@ -109,6 +134,7 @@ impl Event {
         Ok(Some(Self {
             sprite,
             sprite_size,
+            animation,
         }))
     }
 
@@ -140,7 +166,7 @@ impl Event {
         };
 
         let (quad, sprite_size) = if let Some(id) = graphic.tile_id {
-            // Why does this have to be + 1?
+            // Graphic tile ids are stored offset by one, since 0 means "no tile"
             let quad = atlas.calc_quad((id + 1) as i16);
 
             (quad, egui::vec2(32., 32.))
@@ -150,27 +176,10 @@ impl Event {
 
             (quad, egui::vec2(32., 32.))
         } else {
-            let cw = texture.width() as f32 / 4.;
-            let ch = texture.height() as f32 / 4.;
-            let pos = egui::Rect::from_min_size(
-                egui::pos2(
-                    0., //(event.x as f32 * 32.) + (16. - (cw / 2.)),
-                    0., //(event.y as f32 * 32.) + (32. - ch),
-                ),
-                egui::vec2(cw, ch),
-            );
-
-            // Reduced by 0.01 px on all sides to reduce texture bleeding
-            let tex_coords = egui::Rect::from_min_size(
-                egui::pos2(
-                    graphic.pattern as f32 * cw + 0.01,
-                    (graphic.direction as f32 - 2.) / 2. * ch + 0.01,
-                ),
-                egui::vec2(cw - 0.02, ch - 0.02),
-            );
-            let quad = Quad::new(pos, tex_coords);
-
-            (quad, egui::vec2(cw, ch))
+            let cell_size = egui::vec2(texture.width() as f32 / 4., texture.height() as f32 / 4.);
+            let quad = character_quad(cell_size, graphic.pattern, graphic.direction);
+
+            (quad, cell_size)
         };
 
         let transform = Transform::unit(graphics_state);
@@ -189,6 +198,7 @@ impl Event {
         Ok(Some(Self {
             sprite,
             sprite_size,
+            animation: None,
         }))
     }
 
@@ -200,6 +210,33 @@ impl Event {
             .set_position(render_state, glam::vec2(x, y));
     }
 
+    /// Returns true if this event has a stepping animation that [`Self::update_animation`] plays.
+    pub fn is_animated(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Shows the frame of the stepping animation for the given time in seconds, or the event's
+    /// original frame if `animate` is false. Does nothing for events that don't step.
+    pub fn update_animation(
+        &mut self,
+        render_state: &luminol_egui_wgpu::RenderState,
+        time: f64,
+        animate: bool,
+    ) {
+        let Some(animation) = &self.animation else {
+            return;
+        };
+
+        let pattern = if animate {
+            let frame = (time / animation.frame_duration + animation.phase).floor() as i32;
+            (animation.base_pattern + frame).rem_euclid(4)
+        } else {
+            animation.base_pattern
+        };
+        let quad = character_quad(self.sprite_size, pattern, animation.direction);
+        self.sprite.set_quad(render_state, quad, animation.extents);
+    }
+
     pub fn sprite(&self) -> &Sprite {
         &self.sprite
     }
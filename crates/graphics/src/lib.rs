@@ -19,6 +19,9 @@
 pub mod binding_helpers;
 pub use binding_helpers::{BindGroupBuilder, BindGroupLayoutBuilder};
 
+pub mod compatibility;
+pub use compatibility::Compatibility;
+
 pub mod loaders;
 pub use loaders::texture::Texture;
 
@@ -36,6 +39,8 @@ pub mod frame;
 pub mod map;
 pub mod plane;
 pub mod tilepicker;
+pub mod vx_ace;
+pub mod windowskin;
 
 pub use event::Event;
 pub use frame::Frame;
@@ -50,6 +55,11 @@ pub struct GraphicsState {
 
     pub nearest_sampler: wgpu::Sampler,
 
+    pub compatibility: Compatibility,
+
+    /// Colors of the collision and grid overlays, bound by every collision and grid renderer.
+    pub overlay_colors: OverlayColors,
+
     pipelines: primitives::Pipelines,
     bind_group_layouts: primitives::BindGroupLayouts,
 
@@ -59,8 +69,10 @@ pub struct GraphicsState {
 
 impl GraphicsState {
     pub fn new(render_state: luminol_egui_wgpu::RenderState) -> Self {
+        let started = web_time::Instant::now();
+        let compatibility = Compatibility::new(&render_state);
         let bind_group_layouts = primitives::BindGroupLayouts::new(&render_state);
-        let pipelines = primitives::Pipelines::new(&render_state, &bind_group_layouts);
+        let pipelines = primitives::Pipelines::new(&render_state, &compatibility);
 
         let texture_loader = loaders::texture::Loader::new(render_state.clone());
         let atlas_cache = loaders::atlas::Loader::default();
@@ -78,19 +90,35 @@ impl GraphicsState {
 
         let (texture_error_tx, texture_error_rx) = crossbeam::channel::unbounded();
 
-        Self {
+        let overlay_colors = OverlayColors::new(
+            &render_state,
+            egui::Color32::from_rgba_unmultiplied(255, 0, 0, 102),
+            egui::Color32::from_rgba_unmultiplied(26, 26, 26, 64),
+            egui::Color32::from_rgba_unmultiplied(179, 179, 179, 64),
+        );
+
+        let graphics_state = Self {
             texture_loader,
             atlas_loader: atlas_cache,
             render_state,
 
             nearest_sampler,
 
+            compatibility,
+
+            overlay_colors,
+
             pipelines,
             bind_group_layouts,
 
             texture_error_tx,
             texture_error_rx,
-        }
+        };
+
+        graphics_state
+            .timings()
+            .record("Creating the graphics state", started);
+        graphics_state
     }
 
     pub fn send_texture_error(&self, error: color_eyre::Report) {
@@ -99,6 +127,23 @@ impl GraphicsState {
             .expect("failed to send texture error");
     }
 
+    /// Sets the colors used for collision and grid overlays.
+    pub fn set_overlay_colors(
+        &self,
+        collision: egui::Color32,
+        grid_inner: egui::Color32,
+        grid_outer: egui::Color32,
+    ) {
+        self.overlay_colors
+            .set(&self.render_state, collision, grid_inner, grid_outer);
+    }
+
+    /// How long graphics setup steps took, for the profiling overlay. Render pipelines are only
+    /// created the first time they are used, so this grows as more things are drawn.
+    pub fn timings(&self) -> &primitives::Timings {
+        self.pipelines.timings()
+    }
+
     pub fn texture_errors(&self) -> impl Iterator<Item = color_eyre::Report> + '_ {
         self.texture_error_rx.try_iter()
     }
@@ -135,3 +180,7 @@ impl<T: Drawable> luminol_egui_wgpu::CallbackTrait for Painter<T> {
         self.prepared.get().draw(render_pass);
     }
 }
+
+// Images decoded by the texture loader are from this version of `image`, which isn't necessarily
+// the one the rest of the workspace uses
+pub use image;
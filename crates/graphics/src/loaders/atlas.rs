@@ -84,6 +84,15 @@ impl Loader {
             .clone()
     }
 
+    /// Writes the decoded images into every tileset atlas that has finished loading.
+    ///
+    /// Returns true if any atlas is still loading.
+    pub fn poll_pending(&self, graphics_state: &GraphicsState) -> bool {
+        self.atlases.iter().fold(false, |loading, atlas| {
+            atlas.poll_pending(graphics_state) || loading
+        })
+    }
+
     pub fn get_atlas(&self, id: usize) -> Option<Atlas> {
         self.atlases.get(&id).map(|atlas| atlas.clone())
     }
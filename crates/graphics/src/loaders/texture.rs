@@ -35,6 +35,9 @@ pub struct Loader {
     blank_autotile_texture: Arc<Texture>,
     placeholder_image: image::RgbaImage,
 
+    /// The color treated as transparent in images without an alpha channel, if any.
+    color_key: parking_lot::RwLock<Option<[u8; 3]>>,
+
     render_state: luminol_egui_wgpu::RenderState,
 }
 
@@ -58,10 +61,52 @@ fn load_wgpu_texture_from_path(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
     path: &str,
+    color_key: Option<[u8; 3]>,
 ) -> color_eyre::Result<wgpu::Texture> {
+    let texture_data = decode_image_from_path(filesystem, path, color_key)?;
+    load_wgpu_texture_from_decoded_image(&texture_data, device, queue, path)
+}
+
+/// Reads and decodes an image without touching the GPU, so that this can be done off the main
+/// thread.
+///
+/// If the image has no alpha channel of its own, pixels of the color `color_key` are made
+/// transparent.
+fn decode_image_from_path(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    path: &str,
+    color_key: Option<[u8; 3]>,
+) -> color_eyre::Result<image::RgbaImage> {
     let file = filesystem.read(path)?;
-    let texture_data = image::load_from_memory(&file)?.to_rgba8();
+    decode_image_from_bytes(&file, color_key)
+}
+
+/// Decodes an image that has already been read, the same way [`decode_image_from_path`] does.
+pub(crate) fn decode_image_from_bytes(
+    bytes: &[u8],
+    color_key: Option<[u8; 3]>,
+) -> color_eyre::Result<image::RgbaImage> {
+    let image = image::load_from_memory(bytes)?;
+    let has_alpha = image.color().has_alpha();
+    let mut image = image.into_rgba8();
+
+    if let Some(color_key) = color_key.filter(|_| !has_alpha) {
+        for pixel in image.pixels_mut() {
+            if pixel.0[..3] == color_key {
+                pixel.0[3] = 0;
+            }
+        }
+    }
 
+    Ok(image)
+}
+
+fn load_wgpu_texture_from_decoded_image(
+    texture_data: &image::RgbaImage,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    path: &str,
+) -> color_eyre::Result<wgpu::Texture> {
     if device.limits().max_texture_dimension_2d < texture_data.width().max(texture_data.height()) {
         return Err(color_eyre::eyre::eyre!(
             "Texture is too large: {}x{}",
@@ -71,7 +116,7 @@ fn load_wgpu_texture_from_path(
     }
 
     Ok(load_wgpu_texture_from_image(
-        &texture_data,
+        texture_data,
         device,
         queue,
         Some(path),
@@ -107,7 +152,7 @@ fn load_wgpu_texture_from_image(
     )
 }
 
-fn register_native_texture(
+pub(crate) fn register_native_texture(
     render_state: luminol_egui_wgpu::RenderState,
     texture: wgpu::Texture,
     label: Option<&str>,
@@ -199,10 +244,42 @@ impl Loader {
             blank_autotile_texture,
             placeholder_image,
 
+            color_key: parking_lot::RwLock::new(None),
+
             render_state,
         }
     }
 
+    /// Returns the color treated as transparent in images without an alpha channel.
+    pub fn color_key(&self) -> Option<[u8; 3]> {
+        *self.color_key.read()
+    }
+
+    /// Sets the color treated as transparent in images without an alpha channel.
+    ///
+    /// If the color changed, cached textures are cleared, since they were decoded with the old
+    /// one. Returns whether the color changed.
+    pub fn set_color_key(&self, color_key: Option<[u8; 3]>) -> bool {
+        let mut current = self.color_key.write();
+        if *current == color_key {
+            return false;
+        }
+        *current = color_key;
+        drop(current);
+
+        self.clear();
+        true
+    }
+
+    /// Reads and decodes an image, applying the color key, without uploading it to the GPU.
+    pub fn decode_image(
+        &self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        path: impl AsRef<camino::Utf8Path>,
+    ) -> color_eyre::Result<image::RgbaImage> {
+        decode_image_from_path(filesystem, path.as_ref().as_str(), self.color_key())
+    }
+
     pub fn load_now_dir(
         &self,
         filesystem: &impl luminol_filesystem::FileSystem,
@@ -225,11 +302,31 @@ impl Loader {
             &self.render_state.device,
             &self.render_state.queue,
             path,
+            self.color_key(),
         )?;
 
         Ok(self.register_texture(path.to_string(), texture))
     }
 
+    /// Decodes an image that isn't in the project, such as a file about to be imported, and
+    /// uploads it to the GPU. `label` is only used to name the texture for debugging.
+    ///
+    /// The texture isn't cached, so it is freed as soon as the caller drops it.
+    pub fn load_from_bytes(&self, label: &str, bytes: &[u8]) -> color_eyre::Result<Arc<Texture>> {
+        let image = decode_image_from_bytes(bytes, self.color_key())?;
+        let texture = load_wgpu_texture_from_decoded_image(
+            &image,
+            &self.render_state.device,
+            &self.render_state.queue,
+            label,
+        )?;
+        Ok(register_native_texture(
+            self.render_state.clone(),
+            texture,
+            Some(label),
+        ))
+    }
+
     pub fn register_texture(
         &self,
         path: impl Into<camino::Utf8PathBuf>,
@@ -19,8 +19,8 @@ use color_eyre::eyre::Context;
 use itertools::Itertools;
 
 use crate::{
-    Atlas, Collision, Drawable, Event, GraphicsState, Grid, Plane, Renderable, Tiles, Transform,
-    Viewport,
+    primitives::tiles::TilesCopy, Atlas, Collision, Drawable, Event, GraphicsState, Grid, Plane,
+    Renderable, Tiles, Transform, Viewport,
 };
 
 pub struct Map {
@@ -31,6 +31,15 @@ pub struct Map {
     pub grid: Grid,
     pub events: luminol_data::OptionVec<Event>,
     pub atlas: Atlas,
+    /// Translucent tiles drawn over the map to preview what a brush would place. They have their
+    /// own instance buffer, so showing them never touches the map's tiles.
+    brush_preview: Option<BrushPreview>,
+    /// Copies of the tiles drawn past the edges of the map that loop.
+    loop_copies: Vec<TilesCopy>,
+    /// Size of the map in pixels.
+    size: glam::Vec2,
+    /// The editor the map was made with, which decides whether it has a shadow layer.
+    editor_ver: luminol_config::RMVer,
 
     pub viewport: Viewport,
     ani_time: Option<f64>,
@@ -40,6 +49,12 @@ pub struct Map {
     pub coll_enabled: bool,
     pub grid_enabled: bool,
     pub event_enabled: bool,
+    pub brush_preview_enabled: bool,
+}
+
+struct BrushPreview {
+    tiles: Tiles,
+    data: luminol_data::Table3,
 }
 
 impl Map {
@@ -49,6 +64,55 @@ impl Map {
         map: &luminol_data::rpg::Map,
         tileset: &luminol_data::rpg::Tileset,
         passages: &luminol_data::Table2,
+        editor_ver: luminol_config::RMVer,
+    ) -> color_eyre::Result<Self> {
+        Self::new_impl(
+            graphics_state,
+            filesystem,
+            map,
+            tileset,
+            passages,
+            editor_ver,
+            true,
+        )
+    }
+
+    /// Creates a map that only draws its tiles, for overlaying it on another map.
+    ///
+    /// Event sprites aren't created at all, and the panorama, fog, collision and grid are hidden.
+    pub fn new_overlay(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        map: &luminol_data::rpg::Map,
+        tileset: &luminol_data::rpg::Tileset,
+        editor_ver: luminol_config::RMVer,
+    ) -> color_eyre::Result<Self> {
+        let passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        let mut overlay = Self::new_impl(
+            graphics_state,
+            filesystem,
+            map,
+            tileset,
+            &passages,
+            editor_ver,
+            false,
+        )?;
+        overlay.pano_enabled = false;
+        overlay.fog_enabled = false;
+        overlay.coll_enabled = false;
+        overlay.grid_enabled = false;
+        overlay.event_enabled = false;
+        Ok(overlay)
+    }
+
+    fn new_impl(
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        map: &luminol_data::rpg::Map,
+        tileset: &luminol_data::rpg::Tileset,
+        passages: &luminol_data::Table2,
+        editor_ver: luminol_config::RMVer,
+        with_events: bool,
     ) -> color_eyre::Result<Self> {
         let atlas = graphics_state
             .atlas_loader
@@ -65,6 +129,7 @@ impl Map {
             &atlas,
             &viewport,
             Transform::unit(graphics_state),
+            editor_ver,
         );
         let grid = Grid::new(
             graphics_state,
@@ -134,6 +199,7 @@ impl Map {
         let events = map
             .events
             .iter()
+            .filter(|_| with_events)
             .map(|(id, event)| {
                 Event::new_map(graphics_state, filesystem, &viewport, event, &atlas)
                     .map(|opt_e| opt_e.map(|e| (id, e)))
@@ -150,6 +216,10 @@ impl Map {
             events,
             viewport,
             atlas,
+            brush_preview: None,
+            editor_ver,
+            loop_copies: Vec::new(),
+            size: glam::vec2(map.width as f32 * 32., map.height as f32 * 32.),
 
             ani_time: None,
 
@@ -158,6 +228,7 @@ impl Map {
             coll_enabled: false,
             grid_enabled: true,
             event_enabled: true,
+            brush_preview_enabled: false,
         })
     }
 
@@ -179,6 +250,198 @@ impl Map {
         self.collision.set_passage(render_state, passage, position);
     }
 
+    /// Draws copies of the map's tiles past the edges of the map that loop, so that the map can be
+    /// seen continuing around them.
+    pub fn set_loop(&mut self, graphics_state: &GraphicsState, horizontal: bool, vertical: bool) {
+        let x_offsets: &[f32] = if horizontal { &[-1., 0., 1.] } else { &[0.] };
+        let y_offsets: &[f32] = if vertical { &[-1., 0., 1.] } else { &[0.] };
+
+        self.loop_copies = y_offsets
+            .iter()
+            .cartesian_product(x_offsets)
+            .filter(|&(&y, &x)| x != 0. || y != 0.)
+            .map(|(&y, &x)| {
+                self.tiles.create_copy(
+                    graphics_state,
+                    &self.atlas,
+                    &self.viewport,
+                    Transform::new_position(graphics_state, glam::vec2(x, y) * self.size),
+                )
+            })
+            .collect();
+    }
+
+    /// Sets the tiles drawn translucently over the map while [`Self::brush_preview_enabled`] is
+    /// true. `preview` must have a single layer. Its top-left tile is drawn on the map tile at
+    /// `position` and it takes the hue of the map layer `layer`.
+    ///
+    /// The preview's buffers are only recreated when its tiles change, so this can be called
+    /// every frame.
+    pub fn set_brush_preview(
+        &mut self,
+        graphics_state: &GraphicsState,
+        preview: &luminol_data::Table3,
+        position: glam::Vec2,
+        layer: usize,
+    ) {
+        let render_state = &graphics_state.render_state;
+
+        let unchanged = self.brush_preview.as_ref().is_some_and(|brush_preview| {
+            brush_preview.data.xsize() == preview.xsize()
+                && brush_preview.data.ysize() == preview.ysize()
+                && brush_preview.data.iter().eq(preview.iter())
+        });
+        if !unchanged {
+            let mut tiles = Tiles::new(
+                graphics_state,
+                preview,
+                &self.atlas,
+                &self.viewport,
+                Transform::unit(graphics_state),
+                self.editor_ver,
+            );
+            tiles.auto_opacity = false;
+            tiles.display.set_opacity(render_state, 0.5, 0);
+            self.brush_preview = Some(BrushPreview {
+                tiles,
+                data: preview.clone(),
+            });
+        }
+
+        if let Some(brush_preview) = &mut self.brush_preview {
+            brush_preview
+                .tiles
+                .transform
+                .set_position(render_state, position * 32.);
+            let hue = self.tiles.display.hue(layer);
+            brush_preview.tiles.display.set_hue(render_state, hue, 0);
+        }
+    }
+
+    /// Renders a region of the map into a new texture that egui can draw anywhere.
+    ///
+    /// `region` is in pixels relative to the top left corner of the map and the texture will be
+    /// `scale` times its size. The map is rendered with the current layer and visibility settings.
+    pub fn render_to_texture(
+        &mut self,
+        graphics_state: &std::sync::Arc<GraphicsState>,
+        region: egui::Rect,
+        scale: f32,
+    ) -> color_eyre::Result<std::sync::Arc<crate::Texture>> {
+        let render_state = &graphics_state.render_state;
+
+        let width = (region.width() * scale).round() as u32;
+        let height = (region.height() * scale).round() as u32;
+        let texture = Self::create_render_target(graphics_state, width, height)?;
+
+        let old_viewport = (
+            self.viewport.size(),
+            self.viewport.translation(),
+            self.viewport.scale(),
+        );
+        self.viewport.set(
+            render_state,
+            glam::vec2(width as f32, height as f32),
+            glam::vec2(-region.min.x, -region.min.y) * scale,
+            glam::Vec2::splat(scale),
+        );
+
+        let brush_preview_enabled = std::mem::take(&mut self.brush_preview_enabled);
+        self.render_into(graphics_state, &texture);
+        self.brush_preview_enabled = brush_preview_enabled;
+
+        // Buffer writes happen before the next submission, so the viewport can only be restored
+        // after the render above has been submitted
+        let (size, translation, scale) = old_viewport;
+        self.viewport.set(render_state, size, translation, scale);
+
+        Ok(texture)
+    }
+
+    /// Creates a texture that the map can be rendered into with [`Self::render_into`].
+    pub fn create_render_target(
+        graphics_state: &GraphicsState,
+        width: u32,
+        height: u32,
+    ) -> color_eyre::Result<std::sync::Arc<crate::Texture>> {
+        let render_state = &graphics_state.render_state;
+
+        let max_texture_dimension_2d = render_state.device.limits().max_texture_dimension_2d;
+        if width == 0
+            || height == 0
+            || width > max_texture_dimension_2d
+            || height > max_texture_dimension_2d
+        {
+            return Err(color_eyre::eyre::eyre!(
+                "Cannot render the map to a {width}x{height} texture"
+            ));
+        }
+
+        let texture = render_state
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("map render texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: render_state.target_format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+
+        Ok(crate::loaders::texture::register_native_texture(
+            render_state.clone(),
+            texture,
+            Some("map render texture"),
+        ))
+    }
+
+    /// Renders the map into `target` with the current viewport, layer and visibility settings,
+    /// and submits the render right away.
+    ///
+    /// Since the render is submitted before this returns, the settings can be changed again
+    /// afterwards without affecting it. This is what allows drawing the same map more than once
+    /// per frame with different settings without duplicating any of its buffers.
+    pub fn render_into(
+        &mut self,
+        graphics_state: &std::sync::Arc<GraphicsState>,
+        target: &crate::Texture,
+    ) {
+        let render_state = &graphics_state.render_state;
+
+        let prepared = self.prepare(graphics_state);
+        let mut command_encoder =
+            render_state
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("map render command encoder"),
+                });
+        {
+            let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("map render render pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            prepared.draw(&mut render_pass);
+        }
+        render_state
+            .queue
+            .submit(std::iter::once(command_encoder.finish()));
+    }
+
     pub fn update_animation(&mut self, render_state: &luminol_egui_wgpu::RenderState, time: f64) {
         if let Some(ani_time) = self.ani_time {
             if time - ani_time >= 16. / 60. {
@@ -193,6 +456,8 @@ impl Map {
 
 pub struct Prepared {
     tiles: <Tiles as Renderable>::Prepared,
+    loop_copies: Vec<<Tiles as Renderable>::Prepared>,
+    brush_preview: Option<<Tiles as Renderable>::Prepared>,
     panorama: Option<<Plane as Renderable>::Prepared>,
     fog: Option<<Plane as Renderable>::Prepared>,
     collision: Option<<Collision as Renderable>::Prepared>,
@@ -205,6 +470,16 @@ impl Renderable for Map {
 
     fn prepare(&mut self, graphics_state: &std::sync::Arc<GraphicsState>) -> Self::Prepared {
         let tiles = self.tiles.prepare(graphics_state);
+        let loop_copies = self
+            .loop_copies
+            .iter()
+            .map(|copy| self.tiles.prepare_copy(copy, graphics_state))
+            .collect();
+        let brush_preview = self
+            .brush_preview
+            .as_mut()
+            .filter(|_| self.brush_preview_enabled)
+            .map(|brush_preview| brush_preview.tiles.prepare(graphics_state));
         let panorama = self
             .panorama
             .as_mut()
@@ -230,6 +505,8 @@ impl Renderable for Map {
 
         Prepared {
             tiles,
+            loop_copies,
+            brush_preview,
             panorama,
             fog,
             collision,
@@ -246,6 +523,12 @@ impl Drawable for Prepared {
         }
 
         self.tiles.draw(render_pass);
+        for copy in &self.loop_copies {
+            copy.draw(render_pass);
+        }
+        if let Some(ref brush_preview) = self.brush_preview {
+            brush_preview.draw(render_pass);
+        }
 
         for event in &self.events {
             event.draw(render_pass);
@@ -46,13 +46,16 @@ impl Atlas {
         animation_name: Option<&camino::Utf8Path>,
     ) -> Atlas {
         let animation_img = animation_name.as_ref().and_then(|animation_name| {
-            let result = filesystem
-                .read(camino::Utf8Path::new("Graphics/Animations").join(animation_name))
-                .and_then(|file| image::load_from_memory(&file).map_err(|e| e.into()))
+            let result = graphics_state
+                .texture_loader
+                .decode_image(
+                    filesystem,
+                    camino::Utf8Path::new("Graphics/Animations").join(animation_name),
+                )
                 .wrap_err_with(|| format!("Error loading atlas animation {animation_name:?}"));
             // we don't actually need to unwrap this to a placeholder image because we fill in the atlas texture with the placeholder image.
             match result {
-                Ok(img) => Some(img.into_rgba8()),
+                Ok(img) => Some(img),
                 Err(e) => {
                     graphics_state.send_texture_error(e);
                     None
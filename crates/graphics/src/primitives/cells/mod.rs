@@ -112,7 +112,11 @@ impl Renderable for Cells {
 impl Drawable for Prepared {
     fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
         render_pass.push_debug_group("cells renderer");
-        render_pass.set_pipeline(&self.graphics_state.pipelines.cells);
+        render_pass.set_pipeline(
+            self.graphics_state
+                .pipelines
+                .cells(&self.graphics_state.bind_group_layouts),
+        );
 
         render_pass.set_bind_group(0, &self.bind_group, &[]);
 
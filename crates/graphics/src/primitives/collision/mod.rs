@@ -18,8 +18,8 @@
 use std::sync::Arc;
 
 use crate::{
-    BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, Renderable, Transform,
-    Viewport,
+    BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, OverlayColors, Renderable,
+    Transform, Viewport,
 };
 
 use instance::Instances;
@@ -58,6 +58,7 @@ impl Collision {
         let mut bind_group_builder = BindGroupBuilder::new();
         bind_group_builder.append_buffer(viewport.as_buffer());
         bind_group_builder.append_buffer(transform.as_buffer());
+        bind_group_builder.append_buffer(graphics_state.overlay_colors.as_buffer());
         let bind_group = bind_group_builder.build(
             &graphics_state.render_state.device,
             Some("collision bind group"),
@@ -90,11 +91,36 @@ impl Collision {
         tiles: &luminol_data::Table3,
         events: Option<&luminol_data::OptionVec<luminol_data::rpg::Event>>,
         layers: impl Iterator<Item = usize> + Clone,
+        f: impl FnMut(usize, usize, i16),
+    ) {
+        Self::calculate_passages_at(
+            passages,
+            priorities,
+            tiles,
+            events,
+            layers,
+            (0..tiles.ysize())
+                .cartesian_product(0..tiles.xsize())
+                .map(|(y, x)| (x, y)),
+            f,
+        )
+    }
+
+    /// Like [`Self::calculate_passages`], but only for the `(x, y)` positions in `positions`. The
+    /// passage value of a position only depends on the tiles and events at that position, so
+    /// this is enough to update the passages after some tiles or events change.
+    pub fn calculate_passages_at(
+        passages: &luminol_data::Table1,
+        priorities: &luminol_data::Table1,
+        tiles: &luminol_data::Table3,
+        events: Option<&luminol_data::OptionVec<luminol_data::rpg::Event>>,
+        layers: impl Iterator<Item = usize> + Clone,
+        positions: impl Iterator<Item = (usize, usize)>,
         mut f: impl FnMut(usize, usize, i16),
     ) {
         let tileset_size = passages.len().min(priorities.len());
 
-        let mut event_map = if let Some(events) = events {
+        let event_map = if let Some(events) = events {
             events
                 .iter()
                 .filter_map(|(_, event)| {
@@ -120,8 +146,8 @@ impl Collision {
             std::collections::HashMap::new()
         };
 
-        for (y, x) in (0..tiles.ysize()).cartesian_product(0..tiles.xsize()) {
-            let tile_event = event_map.remove(&(x, y));
+        for (x, y) in positions {
+            let tile_event = event_map.get(&(x, y)).cloned();
 
             f(
                 x,
@@ -205,7 +231,11 @@ impl Renderable for Collision {
 impl Drawable for Prepared {
     fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
         render_pass.push_debug_group("tilemap collision renderer");
-        render_pass.set_pipeline(&self.graphics_state.pipelines.collision);
+        render_pass.set_pipeline(
+            self.graphics_state
+                .pipelines
+                .collision(&self.graphics_state.bind_group_layouts),
+        );
 
         render_pass.set_bind_group(0, &self.bind_group, &[]);
 
@@ -221,6 +251,126 @@ pub fn create_bind_group_layout(
 
     Viewport::add_to_bind_group_layout(&mut builder);
     Transform::add_to_bind_group_layout(&mut builder);
+    OverlayColors::add_to_bind_group_layout(&mut builder);
 
     builder.build(&render_state.device, Some("collision bind group layout"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 300;
+
+    /// A 300x300 map with three layers of varied tiles, along with a tileset to go with it.
+    fn large_map() -> (
+        luminol_data::Table1,
+        luminol_data::Table1,
+        luminol_data::Table3,
+    ) {
+        let tileset_size = 384 + 8 * 64;
+        let mut passages = luminol_data::Table1::new(tileset_size);
+        let mut priorities = luminol_data::Table1::new(tileset_size);
+        for id in 0..tileset_size {
+            passages[id] = (id % 16) as i16;
+            priorities[id] = (id % 3) as i16;
+        }
+
+        let mut tiles = luminol_data::Table3::new(SIZE, SIZE, 3);
+        for z in 0..3 {
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    // Leave some tiles blank so every collision type is covered
+                    let id = (x * 7 + y * 13 + z * 31) % tileset_size;
+                    tiles[(x, y, z)] = if id < 48 { 0 } else { id as i16 };
+                }
+            }
+        }
+
+        (passages, priorities, tiles)
+    }
+
+    #[test]
+    fn dirty_positions_match_a_full_recompute() {
+        let (passages, priorities, mut tiles) = large_map();
+        let mut full = luminol_data::Table2::new(SIZE, SIZE);
+        Collision::calculate_passages(
+            &passages,
+            &priorities,
+            &tiles,
+            None,
+            (0..3).rev(),
+            |x, y, p| full[(x, y)] = p,
+        );
+
+        let dirty = [(0, 0), (150, 150), (299, 299), (12, 280)];
+        for &(x, y) in &dirty {
+            tiles[(x, y, 1)] = 400;
+        }
+        Collision::calculate_passages_at(
+            &passages,
+            &priorities,
+            &tiles,
+            None,
+            (0..3).rev(),
+            dirty.into_iter(),
+            |x, y, p| full[(x, y)] = p,
+        );
+
+        let mut expected = luminol_data::Table2::new(SIZE, SIZE);
+        Collision::calculate_passages(
+            &passages,
+            &priorities,
+            &tiles,
+            None,
+            (0..3).rev(),
+            |x, y, p| expected[(x, y)] = p,
+        );
+        assert!(full.iter().eq(expected.iter()));
+    }
+
+    /// Compares the per-frame cost of recomputing every passage of a 300x300 map with only
+    /// recomputing the positions a brush stroke touched. Run it with
+    /// `cargo test --release -p luminol-graphics -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "timing benchmark, run explicitly in release mode"]
+    fn bench_dirty_passages_300x300() {
+        const FRAMES: u32 = 50;
+        let (passages, priorities, tiles) = large_map();
+        let mut output = luminol_data::Table2::new(SIZE, SIZE);
+
+        let started = std::time::Instant::now();
+        for _ in 0..FRAMES {
+            Collision::calculate_passages(
+                &passages,
+                &priorities,
+                &tiles,
+                None,
+                (0..3).rev(),
+                |x, y, p| output[(x, y)] = p,
+            );
+        }
+        let full = started.elapsed() / FRAMES;
+
+        // A 3x3 brush stamp
+        let dirty: Vec<_> = (149..152)
+            .flat_map(|y| (149..152).map(move |x| (x, y)))
+            .collect();
+        let started = std::time::Instant::now();
+        for _ in 0..FRAMES {
+            Collision::calculate_passages_at(
+                &passages,
+                &priorities,
+                &tiles,
+                None,
+                (0..3).rev(),
+                dirty.iter().copied(),
+                |x, y, p| output[(x, y)] = p,
+            );
+        }
+        let incremental = started.elapsed() / FRAMES;
+
+        println!("full recompute: {full:?} per frame, 3x3 dirty region: {incremental:?} per frame");
+        assert!(incremental < full);
+    }
+}
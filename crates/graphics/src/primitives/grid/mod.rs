@@ -18,8 +18,8 @@
 use std::sync::Arc;
 
 use crate::{
-    BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, Renderable, Transform,
-    Viewport,
+    BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, OverlayColors, Renderable,
+    Transform, Viewport,
 };
 
 use display::Display;
@@ -53,6 +53,7 @@ impl Grid {
         bind_group_builder.append_buffer(viewport.as_buffer());
         bind_group_builder.append_buffer(transform.as_buffer());
         bind_group_builder.append_buffer(display.as_buffer());
+        bind_group_builder.append_buffer(graphics_state.overlay_colors.as_buffer());
         let bind_group = bind_group_builder.build(
             &graphics_state.render_state.device,
             Some("grid bind group"),
@@ -93,7 +94,11 @@ impl Renderable for Grid {
 impl Drawable for Prepared {
     fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
         render_pass.push_debug_group("tilemap grid renderer");
-        render_pass.set_pipeline(&self.graphics_state.pipelines.grid);
+        render_pass.set_pipeline(
+            self.graphics_state
+                .pipelines
+                .grid(&self.graphics_state.bind_group_layouts),
+        );
 
         render_pass.set_bind_group(0, &self.bind_group, &[]);
 
@@ -110,6 +115,7 @@ pub fn create_bind_group_layout(
     Viewport::add_to_bind_group_layout(&mut builder);
     Transform::add_to_bind_group_layout(&mut builder);
     display::add_to_bind_group_layout(&mut builder);
+    OverlayColors::add_to_bind_group_layout(&mut builder);
 
     builder.build(&render_state.device, Some("grid bind group layout"))
 }
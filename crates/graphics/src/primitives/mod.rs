@@ -25,6 +25,7 @@
 pub mod cells;
 pub mod collision;
 pub mod grid;
+pub mod shadows;
 pub mod sprite;
 pub mod tiles;
 
@@ -34,14 +35,68 @@ pub struct BindGroupLayouts {
     cells: wgpu::BindGroupLayout,
     collision: wgpu::BindGroupLayout,
     grid: wgpu::BindGroupLayout,
+    shadows: wgpu::BindGroupLayout,
 }
 
+/// Render pipelines, created the first time they are used instead of all at once on startup
+/// since shader compilation can be slow on some backends.
 pub struct Pipelines {
-    sprites: std::collections::HashMap<luminol_data::BlendMode, wgpu::RenderPipeline>,
-    tiles: wgpu::RenderPipeline,
-    cells: wgpu::RenderPipeline,
-    collision: wgpu::RenderPipeline,
-    grid: wgpu::RenderPipeline,
+    sprites: [std::sync::OnceLock<wgpu::RenderPipeline>; 3],
+    tiles: std::sync::OnceLock<wgpu::RenderPipeline>,
+    cells: std::sync::OnceLock<wgpu::RenderPipeline>,
+    collision: std::sync::OnceLock<wgpu::RenderPipeline>,
+    grid: std::sync::OnceLock<wgpu::RenderPipeline>,
+    shadows: std::sync::OnceLock<wgpu::RenderPipeline>,
+
+    render_state: luminol_egui_wgpu::RenderState,
+    atlas_limits: tiles::AtlasLimits,
+    timings: Timings,
+}
+
+/// How long one graphics setup step took.
+#[derive(Clone, Debug)]
+pub struct Timing {
+    pub name: String,
+    pub duration: std::time::Duration,
+}
+
+/// How long graphics setup steps like compiling each render pipeline took, for the profiling
+/// overlay.
+#[derive(Default)]
+pub struct Timings {
+    timings: parking_lot::Mutex<Vec<Timing>>,
+    logging: std::sync::atomic::AtomicBool,
+}
+
+impl Timings {
+    pub(crate) fn record(&self, name: impl Into<String>, started: web_time::Instant) {
+        let timing = Timing {
+            name: name.into(),
+            duration: started.elapsed(),
+        };
+        if self.logging.load(std::sync::atomic::Ordering::Relaxed) {
+            log::info!("{} took {:?}", timing.name, timing.duration);
+        }
+        self.timings.lock().push(timing);
+    }
+
+    /// Sets whether timings are logged as they are recorded. When this is turned on, the timings
+    /// recorded so far are logged right away.
+    pub fn set_logging(&self, enabled: bool) {
+        let was_enabled = self
+            .logging
+            .swap(enabled, std::sync::atomic::Ordering::Relaxed);
+        if enabled && !was_enabled {
+            for timing in self.timings.lock().iter() {
+                log::info!("{} took {:?}", timing.name, timing.duration);
+            }
+        }
+    }
+
+    /// Returns every timing recorded so far, oldest first.
+    pub fn get(&self) -> Vec<Timing> {
+        self.timings.lock().clone()
+    }
 }
 
 impl BindGroupLayouts {
@@ -52,43 +107,104 @@ impl BindGroupLayouts {
             cells: cells::create_bind_group_layout(render_state),
             collision: collision::create_bind_group_layout(render_state),
             grid: grid::create_bind_group_layout(render_state),
+            shadows: shadows::create_bind_group_layout(render_state),
         }
     }
 }
 
-macro_rules! create_pipelines {
-(
-    $render_state:ident, $bind_group_layouts:ident,
-    $($name:ident: $fun:path),*
-) => {{
-    let mut composer = naga_oil::compose::Composer::default();
-    $(
-        let $name = match $fun(&mut composer, $render_state, $bind_group_layouts) {
-            Ok(p) => p,
-            Err(err) => {
-                let err = err.emit_to_string(&composer);
-                panic!("Error creating {} render pipeline:\n{err}", stringify!($name))
+macro_rules! pipeline_getters {
+    ($($name:ident: $fun:path $([$($arg:ident),*])?),* $(,)?) => {
+        $(
+            pub fn $name(&self, bind_group_layouts: &BindGroupLayouts) -> &wgpu::RenderPipeline {
+                self.$name.get_or_init(|| {
+                    let started = web_time::Instant::now();
+                    let mut composer = naga_oil::compose::Composer::default();
+                    match $fun(
+                        &mut composer,
+                        &self.render_state,
+                        bind_group_layouts,
+                        $($(self.$arg,)*)?
+                    ) {
+                        Ok(p) => {
+                            self.timings.record(
+                                concat!("Creating the ", stringify!($name), " render pipeline"),
+                                started,
+                            );
+                            p
+                        }
+                        Err(err) => {
+                            let err = err.emit_to_string(&composer);
+                            panic!("Error creating {} render pipeline:\n{err}", stringify!($name))
+                        }
+                    }
+                })
             }
-        };
-    )*
-    Pipelines {
-        $($name,)*
-    }
-}};
+        )*
+    };
 }
 
 impl Pipelines {
     pub fn new(
         render_state: &luminol_egui_wgpu::RenderState,
-        bind_group_layouts: &BindGroupLayouts,
+        compatibility: &crate::Compatibility,
     ) -> Self {
-        create_pipelines! {
-            render_state, bind_group_layouts,
-            sprites: sprite::shader::create_sprite_shaders,
-            tiles: tiles::shader::create_render_pipeline,
-            cells: cells::shader::create_render_pipeline,
-            collision: collision::shader::create_render_pipeline,
-            grid: grid::shader::create_render_pipeline
+        Self {
+            sprites: Default::default(),
+            tiles: Default::default(),
+            cells: Default::default(),
+            collision: Default::default(),
+            grid: Default::default(),
+            shadows: Default::default(),
+
+            render_state: render_state.clone(),
+            atlas_limits: tiles::AtlasLimits::new(compatibility),
+            timings: Timings::default(),
         }
     }
+
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    pipeline_getters! {
+        tiles: tiles::shader::create_render_pipeline[atlas_limits],
+        cells: cells::shader::create_render_pipeline,
+        collision: collision::shader::create_render_pipeline,
+        grid: grid::shader::create_render_pipeline,
+        shadows: shadows::shader::create_render_pipeline,
+    }
+
+    pub fn sprite(
+        &self,
+        bind_group_layouts: &BindGroupLayouts,
+        blend_mode: luminol_data::BlendMode,
+    ) -> &wgpu::RenderPipeline {
+        let index = match blend_mode {
+            luminol_data::BlendMode::Normal => 0,
+            luminol_data::BlendMode::Add => 1,
+            luminol_data::BlendMode::Subtract => 2,
+        };
+        self.sprites[index].get_or_init(|| {
+            let started = web_time::Instant::now();
+            let mut composer = naga_oil::compose::Composer::default();
+            match sprite::shader::create_sprite_shader(
+                &mut composer,
+                &self.render_state,
+                bind_group_layouts,
+                blend_mode,
+            ) {
+                Ok(p) => {
+                    self.timings.record(
+                        format!("Creating the {blend_mode:?} sprite render pipeline"),
+                        started,
+                    );
+                    p
+                }
+                Err(err) => {
+                    let err = err.emit_to_string(&composer);
+                    panic!("Error creating {blend_mode:?} sprite render pipeline:\n{err}")
+                }
+            }
+        })
+    }
 }
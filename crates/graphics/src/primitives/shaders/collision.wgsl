@@ -9,10 +9,18 @@ struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
 }
 
+struct OverlayColors {
+    collision: vec4<f32>,
+    grid_inner: vec4<f32>,
+    grid_outer: vec4<f32>,
+}
+
 @group(0) @binding(0)
 var<uniform> viewport: Trans::Viewport;
 @group(0) @binding(1)
 var<uniform> transform: Trans::Transform;
+@group(0) @binding(2)
+var<uniform> overlay_colors: OverlayColors;
 
 const VERTEX_POSITIONS = array<vec2f, 12>(
     vec2f(16., 16.),
@@ -61,5 +69,5 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) ->
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(1., 0., 0., 0.4);
+    return overlay_colors.collision;
 }
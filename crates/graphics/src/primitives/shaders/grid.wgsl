@@ -14,6 +14,12 @@ struct Display {
     map_size: vec2<u32>,
 }
 
+struct OverlayColors {
+    collision: vec4<f32>,
+    grid_inner: vec4<f32>,
+    grid_outer: vec4<f32>,
+}
+
 // OpenGL and WebGL use the last vertex in each triangle as the provoking vertex, and
 // Direct3D, Metal, Vulkan and WebGPU use the first vertex in each triangle
 #ifdef LUMINOL_BACKEND_GL
@@ -44,6 +50,8 @@ var<uniform> viewport: Trans::Viewport;
 var<uniform> transform: Trans::Transform;
 @group(0) @binding(2)
 var<uniform> display: Display;
+@group(0) @binding(3)
+var<uniform> overlay_colors: OverlayColors;
 
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32, @builtin(instance_index) instance_index: u32) -> VertexOutput {
@@ -69,9 +77,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         discard;
     }
 
-    var color: f32;
-    var alpha: f32;
-
     let diff = abs(input.position - input.vertex_position) * (viewport.viewport_size / 2.);
 
     let adjusted_outer_thickness = 1.001 * display.pixels_per_point;
@@ -79,15 +84,11 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     if diff.x < adjusted_outer_thickness + adjusted_inner_thickness || diff.y < adjusted_outer_thickness + adjusted_inner_thickness {
         if diff.x < adjusted_inner_thickness || diff.y < adjusted_inner_thickness {
-            color = 0.1;
+            return overlay_colors.grid_inner;
         } else {
-            color = 0.7;
+            return overlay_colors.grid_outer;
         }
-        alpha = 0.25;
-    } else {
-        color = 0.;
-        alpha = 0.;
     }
 
-    return vec4<f32>(color, color, color, alpha);
+    return vec4<f32>(0., 0., 0., 0.);
 }
@@ -0,0 +1,60 @@
+#import luminol::translation as Trans // 🏳️‍⚧️
+
+struct InstanceInput {
+    @location(0) tile_position: vec2<f32>,
+    @location(1) shadow: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> viewport: Trans::Viewport;
+@group(0) @binding(1)
+var<uniform> transform: Trans::Transform;
+
+const VERTEX_POSITIONS = array<vec2f, 6>(
+    vec2f(0., 0.),
+    vec2f(16., 0.),
+    vec2f(0., 16.),
+
+    vec2f(16., 0.),
+    vec2f(16., 16.),
+    vec2f(0., 16.),
+);
+
+// Top left, top right, bottom left and bottom right, in the same order as the shadow bits
+const QUADRANT_OFFSETS = array<vec2f, 4>(
+    vec2f(0., 0.),
+    vec2f(16., 0.),
+    vec2f(0., 16.),
+    vec2f(16., 16.),
+);
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let quadrant = vertex_index / 6;
+
+    if (instance.shadow & (1u << quadrant)) == 0u {
+        return out;
+    }
+
+    var vertex_positions = VERTEX_POSITIONS;
+    var quadrant_offsets = QUADRANT_OFFSETS;
+    let vertex_position = vertex_positions[vertex_index % 6]
+        + quadrant_offsets[quadrant]
+        + (instance.tile_position * 32.);
+    let normalized_pos = Trans::translate_vertex(vertex_position, viewport, transform);
+
+    out.clip_position = vec4<f32>(normalized_pos, 0.0, 1.0);
+
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(0., 0., 0., 0.5);
+}
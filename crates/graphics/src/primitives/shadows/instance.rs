@@ -0,0 +1,122 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use wgpu::util::DeviceExt;
+
+#[derive(Debug)]
+pub struct Instances {
+    instance_buffer: wgpu::Buffer,
+
+    map_width: usize,
+    map_height: usize,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct Instance {
+    position: [f32; 2],
+    shadow: u32,
+}
+
+impl Instances {
+    pub fn new(
+        render_state: &luminol_egui_wgpu::RenderState,
+        tiles: &luminol_data::Table3,
+    ) -> Self {
+        let instances = Self::calculate_instances(tiles);
+        let instance_buffer =
+            render_state
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("tilemap shadow instance buffer"),
+                    contents: bytemuck::cast_slice(&instances),
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                });
+
+        Self {
+            instance_buffer,
+
+            map_width: tiles.xsize(),
+            map_height: tiles.ysize(),
+        }
+    }
+
+    pub fn set_shadow(
+        &self,
+        render_state: &luminol_egui_wgpu::RenderState,
+        value: i16,
+        position: (usize, usize),
+    ) {
+        let offset = position.0 + (position.1 * self.map_width);
+        let offset = offset * std::mem::size_of::<Instance>();
+        render_state.queue.write_buffer(
+            &self.instance_buffer,
+            offset as wgpu::BufferAddress,
+            bytemuck::bytes_of(&Instance {
+                position: [position.0 as f32, position.1 as f32],
+                shadow: super::shadow_bits(value) as u32,
+            }),
+        )
+    }
+
+    fn calculate_instances(tiles: &luminol_data::Table3) -> Vec<Instance> {
+        tiles
+            .layer_as_slice(super::LAYER)
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(index, value)| {
+                // We reset the x every xsize elements.
+                let map_x = index % tiles.xsize();
+                // We increment the y every xsize elements.
+                let map_y = index / tiles.xsize();
+
+                Instance {
+                    position: [map_x as f32, map_y as f32],
+                    shadow: super::shadow_bits(value) as u32,
+                }
+            })
+            .collect_vec()
+    }
+
+    pub fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        let count = (self.map_width * self.map_height) as u32;
+
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+
+        // Two triangles for each of the four quadrants of a tile
+        render_pass.draw(0..24, 0..count);
+    }
+
+    pub const fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ARRAY: &[wgpu::VertexAttribute] =
+            &wgpu::vertex_attr_array![0 => Float32x2, 1 => Uint32];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: ARRAY,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::sync::Arc;
+
+use crate::{
+    BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, Renderable, Transform,
+    Viewport,
+};
+
+use instance::Instances;
+
+mod instance;
+pub(crate) mod shader;
+
+/// The map layer that holds the shadow and region data of RPG Maker VX Ace maps.
+pub const LAYER: usize = 3;
+
+/// Returns the four shadow bits stored in a value of the shadow layer, one bit for each quarter
+/// of the tile in the order top left, top right, bottom left, bottom right.
+pub fn shadow_bits(value: i16) -> i16 {
+    value & 0b1111
+}
+
+/// The largest region ID VX Ace allows.
+pub const MAX_REGION_ID: i16 = 63;
+
+/// Returns the region ID stored in the high byte of a value of the shadow layer.
+pub fn region_id(value: i16) -> i16 {
+    (value >> 8) & 0xff
+}
+
+/// Returns `value` with its region ID replaced by `region`, keeping its shadow bits.
+pub fn with_region_id(value: i16, region: i16) -> i16 {
+    (value & 0xff) | (region << 8)
+}
+
+/// Quarter-tile dark overlays drawn from the shadow layer of a map.
+#[derive(Debug)]
+pub struct Shadows {
+    // in an Arc so we can use it in rendering
+    instances: Arc<Instances>,
+    bind_group: Arc<wgpu::BindGroup>,
+}
+
+impl Shadows {
+    pub fn new(
+        graphics_state: &GraphicsState,
+        viewport: &Viewport,
+        transform: &Transform,
+        tiles: &luminol_data::Table3,
+    ) -> Self {
+        let instances = Instances::new(&graphics_state.render_state, tiles);
+
+        let mut bind_group_builder = BindGroupBuilder::new();
+        bind_group_builder.append_buffer(viewport.as_buffer());
+        bind_group_builder.append_buffer(transform.as_buffer());
+        let bind_group = bind_group_builder.build(
+            &graphics_state.render_state.device,
+            Some("shadow bind group"),
+            &graphics_state.bind_group_layouts.shadows,
+        );
+
+        Self {
+            instances: Arc::new(instances),
+            bind_group: Arc::new(bind_group),
+        }
+    }
+
+    /// Updates the shadow of one tile from a value of the shadow layer.
+    pub fn set_shadow(
+        &self,
+        render_state: &luminol_egui_wgpu::RenderState,
+        value: i16,
+        position: (usize, usize),
+    ) {
+        self.instances.set_shadow(render_state, value, position)
+    }
+}
+
+pub struct Prepared {
+    bind_group: Arc<wgpu::BindGroup>,
+    instances: Arc<Instances>,
+    graphics_state: Arc<GraphicsState>,
+}
+
+impl Renderable for Shadows {
+    type Prepared = Prepared;
+
+    fn prepare(&mut self, graphics_state: &Arc<GraphicsState>) -> Self::Prepared {
+        let bind_group = Arc::clone(&self.bind_group);
+        let graphics_state = Arc::clone(graphics_state);
+        let instances = Arc::clone(&self.instances);
+
+        Prepared {
+            bind_group,
+            instances,
+            graphics_state,
+        }
+    }
+}
+
+impl Drawable for Prepared {
+    fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.push_debug_group("tilemap shadow renderer");
+        render_pass.set_pipeline(
+            self.graphics_state
+                .pipelines
+                .shadows(&self.graphics_state.bind_group_layouts),
+        );
+
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+
+        self.instances.draw(render_pass);
+        render_pass.pop_debug_group();
+    }
+}
+
+pub fn create_bind_group_layout(
+    render_state: &luminol_egui_wgpu::RenderState,
+) -> wgpu::BindGroupLayout {
+    let mut builder = BindGroupLayoutBuilder::new();
+
+    Viewport::add_to_bind_group_layout(&mut builder);
+    Transform::add_to_bind_group_layout(&mut builder);
+
+    builder.build(&render_state.device, Some("shadow bind group layout"))
+}
@@ -124,23 +124,16 @@ const BLEND_SUBTRACT: wgpu::BlendState = wgpu::BlendState {
     },
 };
 
-pub fn create_sprite_shaders(
+pub fn create_sprite_shader(
     composer: &mut naga_oil::compose::Composer,
     render_state: &luminol_egui_wgpu::RenderState,
     bind_group_layouts: &BindGroupLayouts,
-) -> Result<HashMap<luminol_data::BlendMode, wgpu::RenderPipeline>, ComposerError> {
-    [
-        (
-            luminol_data::BlendMode::Normal,
-            wgpu::BlendState::ALPHA_BLENDING,
-        ),
-        (luminol_data::BlendMode::Add, BLEND_ADD),
-        (luminol_data::BlendMode::Subtract, BLEND_SUBTRACT),
-    ]
-    .into_iter()
-    .map(|(mode, target)| {
-        let shader = create_shader(composer, render_state, bind_group_layouts, target)?;
-        Ok((mode, shader))
-    })
-    .collect()
+    blend_mode: luminol_data::BlendMode,
+) -> Result<wgpu::RenderPipeline, ComposerError> {
+    let target = match blend_mode {
+        luminol_data::BlendMode::Normal => wgpu::BlendState::ALPHA_BLENDING,
+        luminol_data::BlendMode::Add => BLEND_ADD,
+        luminol_data::BlendMode::Subtract => BLEND_SUBTRACT,
+    };
+    create_shader(composer, render_state, bind_group_layouts, target)
 }
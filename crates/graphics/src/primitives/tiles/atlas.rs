@@ -16,11 +16,10 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
 use color_eyre::eyre::WrapErr;
-use image::EncodableLayout;
-use itertools::Itertools;
 use wgpu::util::DeviceExt;
 
 use super::autotile_ids::AUTOTILES;
+use crate::loaders::texture::decode_image_from_bytes;
 use crate::{GraphicsState, Quad, Texture};
 
 pub const MAX_SIZE: u32 = 8192; // Max texture size in one dimension
@@ -37,12 +36,39 @@ pub const AUTOTILE_ROWS: u32 = AUTOTILE_ID_AMOUNT / AUTOTILE_FRAME_COLS; // spli
 pub const TOTAL_AUTOTILE_ROWS: u32 = AUTOTILE_ROWS * AUTOTILE_AMOUNT; // total number of rows for all autotiles combined
 pub const AUTOTILE_ROW_HEIGHT: u32 = AUTOTILE_ROWS * TILE_SIZE; // This is how high one row of autotiles is
 pub const TOTAL_AUTOTILE_HEIGHT: u32 = AUTOTILE_ROW_HEIGHT * AUTOTILE_AMOUNT; // self explanatory
-pub const HEIGHT_UNDER_AUTOTILES: u32 = MAX_SIZE - TOTAL_AUTOTILE_HEIGHT; // this is the height under autotiles
-pub const ROWS_UNDER_AUTOTILES: u32 = MAX_SIZE / TILE_SIZE - TOTAL_AUTOTILE_ROWS; // number of rows under autotiles
-pub const ROWS_UNDER_AUTOTILES_TIMES_COLUMNS: u32 = ROWS_UNDER_AUTOTILES * TILESET_COLUMNS;
 
 pub const AUTOTILE_FRAME_WIDTH: u32 = AUTOTILE_FRAME_COLS * TILE_SIZE; // This is per frame!
 
+/// The parts of the atlas layout that depend on the largest texture the graphics adapter supports.
+/// This is [`MAX_SIZE`] on most hardware, but can be smaller in compatibility mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasLimits {
+    /// Max texture size in one dimension, rounded down to a whole number of tiles
+    pub max_size: u32,
+}
+
+impl AtlasLimits {
+    pub fn new(compatibility: &crate::Compatibility) -> Self {
+        Self {
+            max_size: compatibility.max_texture_dimension_2d.min(MAX_SIZE) / TILE_SIZE * TILE_SIZE,
+        }
+    }
+
+    /// The height under autotiles
+    pub const fn height_under_autotiles(self) -> u32 {
+        self.max_size - TOTAL_AUTOTILE_HEIGHT
+    }
+
+    /// Number of rows under autotiles
+    pub const fn rows_under_autotiles(self) -> u32 {
+        self.max_size / TILE_SIZE - TOTAL_AUTOTILE_ROWS
+    }
+
+    pub const fn rows_under_autotiles_times_columns(self) -> u32 {
+        self.rows_under_autotiles() * TILESET_COLUMNS
+    }
+}
+
 use image::GenericImageView;
 use std::sync::Arc;
 
@@ -52,65 +78,228 @@ pub struct Atlas {
     tileset_height: u32,
     pub(super) autotile_width: u32,
     pub(super) autotile_frames: [u32; AUTOTILE_AMOUNT as usize],
+    limits: AtlasLimits,
+    /// The images that haven't been written into the atlas texture yet, or `None` once they all
+    /// have been.
+    pending: Arc<parking_lot::Mutex<Option<PendingImages>>>,
+}
+
+/// Where the autotiles and the tileset are placed on the atlas texture.
+#[derive(Clone, Copy)]
+struct Layout {
+    width: u32,
+    height: u32,
+    rows_under: u32,
+    rows_side: u32,
+}
+
+/// An image of an atlas that has been read but not decoded yet.
+enum DecodeJob {
+    Tileset {
+        bytes: Vec<u8>,
+        context: String,
+    },
+    Autotile {
+        index: usize,
+        frames: u32,
+        bytes: Vec<u8>,
+        context: String,
+    },
+}
+
+/// An image that is ready to be written into an atlas. Autotiles have already been expanded into
+/// all 48 of their tiles.
+enum Decoded {
+    Tileset(color_eyre::Result<image::RgbaImage>),
+    Autotile {
+        index: usize,
+        image: color_eyre::Result<image::RgbaImage>,
+    },
+}
+
+impl DecodeJob {
+    fn run(self, color_key: Option<[u8; 3]>) -> Decoded {
+        match self {
+            DecodeJob::Tileset { bytes, context } => {
+                Decoded::Tileset(decode_image_from_bytes(&bytes, color_key).wrap_err(context))
+            }
+            DecodeJob::Autotile {
+                index,
+                frames,
+                bytes,
+                context,
+            } => Decoded::Autotile {
+                index,
+                image: decode_image_from_bytes(&bytes, color_key)
+                    .map(|autotile| expand_autotile(&autotile, frames))
+                    .wrap_err(context),
+            },
+        }
+    }
+}
+
+/// Decodes the images of an atlas without blocking the frame that created it.
+///
+/// On native, every image is decoded in parallel on rayon's thread pool. On web there are no
+/// threads to spare, so one image is decoded each time the atlas is polled instead.
+struct PendingImages {
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: crossbeam::channel::Receiver<Vec<Decoded>>,
+    #[cfg(target_arch = "wasm32")]
+    jobs: Vec<DecodeJob>,
+    #[cfg(target_arch = "wasm32")]
+    decoded: Vec<Decoded>,
+    #[cfg(target_arch = "wasm32")]
+    color_key: Option<[u8; 3]>,
+
+    layout: Layout,
+    empty_autotiles: Vec<usize>,
+    /// Whether an image already failed to load before decoding started
+    failed: bool,
+    started: web_time::Instant,
+    tileset_id: usize,
+}
+
+impl PendingImages {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        jobs: Vec<DecodeJob>,
+        color_key: Option<[u8; 3]>,
+        layout: Layout,
+        empty_autotiles: Vec<usize>,
+        failed: bool,
+        started: web_time::Instant,
+        tileset_id: usize,
+    ) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let receiver = {
+            let (sender, receiver) = crossbeam::channel::bounded(1);
+            rayon::spawn(move || {
+                use rayon::prelude::*;
+                let decoded = jobs.into_par_iter().map(|job| job.run(color_key)).collect();
+                // Nobody needs the images anymore if the atlas was dropped in the meantime
+                let _ = sender.send(decoded);
+            });
+            receiver
+        };
+
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            receiver,
+            #[cfg(target_arch = "wasm32")]
+            jobs,
+            #[cfg(target_arch = "wasm32")]
+            decoded: Vec::new(),
+            #[cfg(target_arch = "wasm32")]
+            color_key,
+
+            layout,
+            empty_autotiles,
+            failed,
+            started,
+            tileset_id,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poll(&mut self) -> Option<Vec<Decoded>> {
+        match self.receiver.try_recv() {
+            Ok(decoded) => Some(decoded),
+            Err(crossbeam::channel::TryRecvError::Empty) => None,
+            // Decoding panicked, so show the placeholder instead of loading forever
+            Err(crossbeam::channel::TryRecvError::Disconnected) => {
+                self.failed = true;
+                Some(Vec::new())
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn poll(&mut self) -> Option<Vec<Decoded>> {
+        if let Some(job) = self.jobs.pop() {
+            self.decoded.push(job.run(self.color_key));
+        }
+        self.jobs
+            .is_empty()
+            .then(|| std::mem::take(&mut self.decoded))
+    }
 }
 
 impl Atlas {
+    /// Creates the atlas for a tileset.
+    ///
+    /// Only the image headers are read before this returns; the atlas is drawn as a loading
+    /// checkerboard until [`Self::poll_pending`] finds the decoded images and swaps them in.
     pub fn new(
         graphics_state: &GraphicsState,
         filesystem: &impl luminol_filesystem::FileSystem,
         tileset: &luminol_data::rpg::Tileset,
     ) -> Atlas {
-        let tileset_img = tileset.tileset_name.as_ref().and_then(|tileset_name| {
-            let result = filesystem
-                .read(camino::Utf8Path::new("Graphics/Tilesets").join(tileset_name))
-                .and_then(|file| image::load_from_memory(&file).map_err(|e| e.into()))
-                .wrap_err_with(|| format!("Error loading atlas tileset {tileset_name:?}"));
-            // we don't actually need to unwrap this to a placeholder image because we fill in the atlas texture with the placeholder image.
-            match result {
-                Ok(img) => Some(img.into_rgba8()),
+        let started = web_time::Instant::now();
+
+        let mut jobs = Vec::new();
+        let mut empty_autotiles = Vec::new();
+        let mut failed = false;
+
+        // A tileset that can't be read leaves the atlas filled with the placeholder image once the
+        // autotiles are done loading
+        let mut tileset_height = 256;
+        if let Some(tileset_name) = &tileset.tileset_name {
+            let context = format!("Error loading atlas tileset {tileset_name:?}");
+            match read_image_header(
+                filesystem,
+                &camino::Utf8Path::new("Graphics/Tilesets").join(tileset_name),
+            )
+            .wrap_err_with(|| context.clone())
+            {
+                Ok((bytes, (_, height))) => {
+                    tileset_height = height / TILE_SIZE * TILE_SIZE;
+                    jobs.push(DecodeJob::Tileset { bytes, context });
+                }
                 Err(e) => {
                     graphics_state.send_texture_error(e);
-                    None
+                    failed = true;
                 }
             }
-        });
-
-        let tileset_height = tileset_img
-            .as_ref()
-            .map(|i| i.height() / TILE_SIZE * TILE_SIZE)
-            .unwrap_or(256);
+        }
 
-        let autotiles = tileset
+        // Why default to one frame? Even though the autotile doesn't exist, it still has an effective width on the atlas of one frame.
+        // Further rendering code breaks down with an autotile width of 0, anyway.
+        let mut autotile_frames = [1; AUTOTILE_AMOUNT as usize];
+        for (index, autotile_name) in tileset
             .autotile_names
             .iter()
-            .map(|s| {
-                if s.is_empty() {
-                    Some(graphics_state.texture_loader.blank_autotile_texture())
-                } else {
-                    graphics_state
-                        .texture_loader
-                        .load_now_dir(filesystem, "Graphics/Autotiles", s)
-                        .wrap_err_with(|| format!("Error loading atlas autotiles {s:?}"))
-                        .map_or_else(
-                            |e| {
-                                graphics_state.send_texture_error(e);
-                                None
-                            },
-                            Some,
-                        )
+            .enumerate()
+            .take(AUTOTILE_AMOUNT as usize)
+        {
+            if autotile_name.is_empty() {
+                empty_autotiles.push(index);
+                continue;
+            }
+
+            let context = format!("Error loading atlas autotiles {autotile_name:?}");
+            match read_image_header(
+                filesystem,
+                &camino::Utf8Path::new("Graphics/Autotiles").join(autotile_name),
+            )
+            .wrap_err_with(|| context.clone())
+            {
+                Ok((bytes, (width, _))) => {
+                    let frames = (width / 96).max(1);
+                    autotile_frames[index] = frames;
+                    jobs.push(DecodeJob::Autotile {
+                        index,
+                        frames,
+                        bytes,
+                        context,
+                    });
                 }
-            })
-            .collect_vec();
-
-        let autotile_frames = std::array::from_fn(|i| {
-            autotiles[i]
-                .as_deref()
-                .map(Texture::width)
-                // Why unwrap with a width of 96? Even though the autotile doesn't exist, it still has an effective width on the atlas of one frame.
-                // Further rendering code breaks down with an autotile width of 0, anyway.
-                .unwrap_or(96)
-                / 96
-        });
+                Err(e) => {
+                    graphics_state.send_texture_error(e);
+                    failed = true;
+                }
+            }
+        }
 
         let autotile_width = autotile_frames
             .iter()
@@ -118,18 +307,16 @@ impl Atlas {
             .max()
             .unwrap_or(AUTOTILE_FRAME_WIDTH);
 
-        let mut encoder = graphics_state.render_state.device.create_command_encoder(
-            &wgpu::CommandEncoderDescriptor {
-                label: Some("tilemap atlas creation"),
-            },
-        );
+        let limits = AtlasLimits::new(&graphics_state.compatibility);
+        let max_size = limits.max_size;
+        let height_under_autotiles = limits.height_under_autotiles();
 
         let width;
         let height;
 
         let rows_under;
         let rows_side;
-        if TOTAL_AUTOTILE_HEIGHT + tileset_height < MAX_SIZE {
+        if TOTAL_AUTOTILE_HEIGHT + tileset_height < max_size {
             width = autotile_width.max(TILESET_WIDTH); // in case we have less autotiles frames than the tileset is wide
             height = TOTAL_AUTOTILE_HEIGHT + tileset_height; // we're sure that the tileset can fit into the atlas just fine
 
@@ -140,20 +327,19 @@ impl Atlas {
             // Take the smallest of these
             rows_under = u32::min(
                 // How many times can the tileset fit under the autotiles?
-                tileset_height.div_ceil(HEIGHT_UNDER_AUTOTILES),
+                tileset_height.div_ceil(height_under_autotiles),
                 // How many columns of autotiles are there
                 autotile_width.div_ceil(TILESET_WIDTH),
             );
-            // Find out how many rows would fit on the side by dividing the left over height by MAX_SIZE
+            // Find out how many rows would fit on the side by dividing the left over height by max_size
             rows_side = tileset_height
-                .saturating_sub(rows_under * HEIGHT_UNDER_AUTOTILES)
-                .div_ceil(MAX_SIZE);
+                .saturating_sub(rows_under * height_under_autotiles)
+                .div_ceil(max_size);
 
             width = ((rows_under + rows_side) * TILESET_WIDTH).max(autotile_width);
-            height = MAX_SIZE;
+            height = max_size;
         }
 
-        let placeholder_img = graphics_state.texture_loader.placeholder_image();
         let atlas_texture = graphics_state.render_state.device.create_texture_with_data(
             &graphics_state.render_state.queue,
             &wgpu::TextureDescriptor {
@@ -173,104 +359,162 @@ impl Atlas {
                 view_formats: &[],
             },
             wgpu::util::TextureDataOrder::LayerMajor,
-            // we can avoid this collect_vec() by mapping a buffer and then copying that to a texture. it'd also allow us to copy everything easier too. do we want to do this?
-            &itertools::iproduct!(0..height, 0..width, 0..4)
-                .map(|(y, x, c)| {
-                    // Tile the placeholder image to fill the atlas
-                    placeholder_img.as_bytes()[(c
-                        + (x % placeholder_img.width()) * 4
-                        + (y % placeholder_img.height()) * 4 * placeholder_img.width())
-                        as usize]
-                })
-                .collect_vec(),
+            &fill_image(width, height, loading_checkerboard_pixel),
         );
-        let mut atlas_copy = atlas_texture.as_image_copy();
-
-        for (index, autotile_texture) in
-            autotiles
-                .into_iter()
-                .enumerate()
-                .flat_map(|(index, autotile_texture)| {
-                    autotile_texture.map(|autotile_texture| (index, autotile_texture))
-                })
-        {
-            let mut autotile_copy = autotile_texture.texture.as_image_copy();
-
-            let frame_y = index as u32 * AUTOTILE_ROW_HEIGHT;
-            for frame in 0..autotile_frames[index] {
-                let frame_x = frame * AUTOTILE_FRAME_WIDTH;
-                for (index, autotile) in AUTOTILES.into_iter().enumerate() {
-                    // Reset x every 8 tiles
-                    let autotile_x = index as u32 % AUTOTILE_FRAME_COLS * TILE_SIZE;
-                    // Increase y every 8 tiles
-                    let autotile_y = index as u32 / AUTOTILE_FRAME_COLS * TILE_SIZE;
-
-                    for (index, sub_tile) in autotile.into_iter().enumerate() {
-                        let sub_tile_x = index as u32 % 2 * 16;
-                        let sub_tile_y = index as u32 / 2 * 16;
-
-                        atlas_copy.origin.x = frame_x + autotile_x + sub_tile_x;
-                        atlas_copy.origin.y = frame_y + autotile_y + sub_tile_y;
-
-                        let tile_x = sub_tile % 6 * 16;
-                        let tile_y = sub_tile / 6 * 16;
-
-                        autotile_copy.origin.x = tile_x + frame * 96;
-                        autotile_copy.origin.y = tile_y;
-
-                        encoder.copy_texture_to_texture(
-                            autotile_copy,
-                            atlas_copy,
-                            wgpu::Extent3d {
-                                width: 16,
-                                height: 16,
-                                depth_or_array_layers: 1,
-                            },
-                        );
-                    }
+
+        let atlas_texture = graphics_state
+            .texture_loader
+            .register_texture(format!("tileset_atlases/{}", tileset.id), atlas_texture);
+
+        let pending = PendingImages::new(
+            jobs,
+            graphics_state.texture_loader.color_key(),
+            Layout {
+                width,
+                height,
+                rows_under,
+                rows_side,
+            },
+            empty_autotiles,
+            failed,
+            started,
+            tileset.id,
+        );
+
+        log::debug!(
+            "Created the loading atlas for tileset {} in {:?}",
+            tileset.id,
+            started.elapsed()
+        );
+
+        Atlas {
+            atlas_texture,
+            autotile_width,
+            tileset_height,
+            autotile_frames,
+            limits,
+            pending: Arc::new(parking_lot::Mutex::new(Some(pending))),
+        }
+    }
+
+    /// Writes the tileset and autotiles into the atlas texture if they have finished decoding.
+    ///
+    /// Returns true while the atlas is still loading.
+    pub fn poll_pending(&self, graphics_state: &GraphicsState) -> bool {
+        let mut pending = self.pending.lock();
+        let Some(decoded) = pending.as_mut().and_then(PendingImages::poll) else {
+            return pending.is_some();
+        };
+        let pending = pending
+            .take()
+            .expect("pending images should exist if they were just polled");
+
+        self.write_decoded(graphics_state, pending, decoded);
+        false
+    }
+
+    fn write_decoded(
+        &self,
+        graphics_state: &GraphicsState,
+        pending: PendingImages,
+        decoded: Vec<Decoded>,
+    ) {
+        let render_state = &graphics_state.render_state;
+        let atlas_texture = &self.atlas_texture.texture;
+        let Layout {
+            width,
+            height,
+            rows_under,
+            rows_side,
+        } = pending.layout;
+        let mut failed = pending.failed;
+
+        let mut tileset_img = None;
+        let mut autotiles = Vec::with_capacity(AUTOTILE_AMOUNT as usize);
+        for decoded in decoded {
+            match decoded {
+                Decoded::Tileset(Ok(image)) => tileset_img = Some(image),
+                Decoded::Autotile {
+                    index,
+                    image: Ok(image),
+                } => autotiles.push((index, image)),
+                Decoded::Tileset(Err(e)) | Decoded::Autotile { image: Err(e), .. } => {
+                    graphics_state.send_texture_error(e);
+                    failed = true;
                 }
             }
         }
 
-        graphics_state
-            .render_state
-            .queue
-            .submit(std::iter::once(encoder.finish()));
+        // Whatever failed to load is shown as the placeholder image rather than as still loading
+        if failed {
+            let placeholder_img = graphics_state.texture_loader.placeholder_image();
+            render_state.queue.write_texture(
+                atlas_texture.as_image_copy(),
+                &fill_image(width, height, |x, y| {
+                    placeholder_img
+                        .get_pixel(x % placeholder_img.width(), y % placeholder_img.height())
+                        .0
+                }),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * 4),
+                    rows_per_image: None,
+                },
+                atlas_texture.size(),
+            );
+        }
 
-        atlas_copy.origin.x = 0;
+        let blank_autotile = image::RgbaImage::new(AUTOTILE_FRAME_WIDTH, AUTOTILE_ROW_HEIGHT);
+        let autotiles = pending
+            .empty_autotiles
+            .iter()
+            .map(|&index| (index, &blank_autotile))
+            .chain(autotiles.iter().map(|(index, image)| (*index, image)));
+        for (index, autotile) in autotiles {
+            write_texture_region(
+                render_state,
+                atlas_texture,
+                autotile.view(0, 0, autotile.width(), autotile.height()),
+                (0, index as u32 * AUTOTILE_ROW_HEIGHT),
+            );
+        }
+
+        let tileset_height = self.tileset_height;
+        let max_size = self.limits.max_size;
+        let height_under_autotiles = self.limits.height_under_autotiles();
         if let Some(tileset_img) = tileset_img {
-            if TOTAL_AUTOTILE_HEIGHT + tileset_height < MAX_SIZE {
+            if TOTAL_AUTOTILE_HEIGHT + tileset_height < max_size {
                 write_texture_region(
-                    &graphics_state.render_state,
-                    &atlas_texture,
+                    render_state,
+                    atlas_texture,
                     tileset_img.view(0, 0, TILESET_WIDTH, tileset_height),
                     (0, TOTAL_AUTOTILE_HEIGHT),
                 )
             } else {
                 for i in 0..rows_under {
-                    let y = HEIGHT_UNDER_AUTOTILES * i;
-                    let height = if y + HEIGHT_UNDER_AUTOTILES > tileset_height {
+                    let y = height_under_autotiles * i;
+                    let height = if y + height_under_autotiles > tileset_height {
                         tileset_height - y
                     } else {
-                        HEIGHT_UNDER_AUTOTILES
+                        height_under_autotiles
                     };
                     write_texture_region(
-                        &graphics_state.render_state,
-                        &atlas_texture,
+                        render_state,
+                        atlas_texture,
                         tileset_img.view(0, y, TILESET_WIDTH, height),
                         (TILESET_WIDTH * i, TOTAL_AUTOTILE_HEIGHT),
                     )
                 }
                 for i in 0..rows_side {
-                    let y = (HEIGHT_UNDER_AUTOTILES * rows_under) + MAX_SIZE * i;
-                    let height = if y + MAX_SIZE > tileset_height {
+                    let y = (height_under_autotiles * rows_under) + max_size * i;
+                    let height = if y + max_size > tileset_height {
                         tileset_height - y
                     } else {
-                        MAX_SIZE
+                        max_size
                     };
                     write_texture_region(
-                        &graphics_state.render_state,
-                        &atlas_texture,
+                        render_state,
+                        atlas_texture,
                         tileset_img.view(0, y, TILESET_WIDTH, height),
                         (TILESET_WIDTH * (rows_under + i), 0),
                     )
@@ -278,16 +522,11 @@ impl Atlas {
             }
         }
 
-        let atlas_texture = graphics_state
-            .texture_loader
-            .register_texture(format!("tileset_atlases/{}", tileset.id), atlas_texture);
-
-        Atlas {
-            atlas_texture,
-            autotile_width,
-            tileset_height,
-            autotile_frames,
-        }
+        log::debug!(
+            "Finished loading the atlas for tileset {} after {:?}",
+            pending.tileset_id,
+            pending.started.elapsed()
+        );
     }
 
     pub fn calc_quad(&self, tile: i16) -> Quad {
@@ -295,7 +534,10 @@ impl Atlas {
 
         let is_autotile = tile_u32 < TOTAL_AUTOTILE_ID_AMOUNT;
         let max_frame_count = self.autotile_width / AUTOTILE_FRAME_WIDTH;
-        let max_tiles_under_autotiles = max_frame_count * ROWS_UNDER_AUTOTILES_TIMES_COLUMNS;
+        let rows_under_autotiles = self.limits.rows_under_autotiles();
+        let rows_under_autotiles_times_columns = self.limits.rows_under_autotiles_times_columns();
+        let max_size = self.limits.max_size;
+        let max_tiles_under_autotiles = max_frame_count * rows_under_autotiles_times_columns;
         let is_under_autotiles =
             !is_autotile && tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT < max_tiles_under_autotiles;
 
@@ -309,10 +551,10 @@ impl Atlas {
         } else if is_under_autotiles {
             egui::pos2(
                 ((tile_u32 % TILESET_COLUMNS
-                    + (tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT) / ROWS_UNDER_AUTOTILES_TIMES_COLUMNS
+                    + (tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT) / rows_under_autotiles_times_columns
                         * TILESET_COLUMNS)
                     * TILE_SIZE) as f32,
-                (((tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT) / TILESET_COLUMNS % ROWS_UNDER_AUTOTILES
+                (((tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT) / TILESET_COLUMNS % rows_under_autotiles
                     + TOTAL_AUTOTILE_ROWS)
                     * TILE_SIZE) as f32,
             )
@@ -320,13 +562,13 @@ impl Atlas {
             egui::pos2(
                 ((tile_u32 % TILESET_COLUMNS
                     + ((tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT - max_tiles_under_autotiles)
-                        / (MAX_SIZE / TILE_SIZE * TILESET_COLUMNS)
+                        / (max_size / TILE_SIZE * TILESET_COLUMNS)
                         + max_frame_count)
                         * TILESET_COLUMNS)
                     * TILE_SIZE) as f32,
                 ((tile_u32 - TOTAL_AUTOTILE_ID_AMOUNT - max_tiles_under_autotiles)
                     / TILESET_COLUMNS
-                    % (MAX_SIZE / TILE_SIZE)
+                    % (max_size / TILE_SIZE)
                     * TILE_SIZE) as f32,
             )
         };
@@ -350,6 +592,12 @@ impl Atlas {
         &self.atlas_texture
     }
 
+    /// Returns the layout limits this atlas was built with
+    #[inline]
+    pub fn limits(&self) -> AtlasLimits {
+        self.limits
+    }
+
     /// Returns the height of the original tileset texture in pixels
     #[inline]
     pub fn tileset_height(&self) -> u32 {
@@ -399,3 +647,86 @@ fn write_texture_region<P>(
         },
     );
 }
+
+/// Reads an image and its dimensions without decoding it.
+fn read_image_header(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    path: &camino::Utf8Path,
+) -> color_eyre::Result<(Vec<u8>, (u32, u32))> {
+    let bytes = filesystem.read(path)?;
+    let dimensions = image::io::Reader::new(std::io::Cursor::new(&bytes))
+        .with_guessed_format()?
+        .into_dimensions()?;
+    Ok((bytes, dimensions))
+}
+
+/// Lays out every shape of an autotile the way it is stored in the atlas: `frames` frames side by
+/// side, each with the 48 tiles of the autotile in rows of [`AUTOTILE_FRAME_COLS`].
+fn expand_autotile(autotile: &image::RgbaImage, frames: u32) -> image::RgbaImage {
+    let mut expanded = image::RgbaImage::new(frames * AUTOTILE_FRAME_WIDTH, AUTOTILE_ROW_HEIGHT);
+
+    for frame in 0..frames {
+        let frame_x = frame * AUTOTILE_FRAME_WIDTH;
+        for (index, autotile_ids) in AUTOTILES.into_iter().enumerate() {
+            // Reset x every 8 tiles
+            let autotile_x = index as u32 % AUTOTILE_FRAME_COLS * TILE_SIZE;
+            // Increase y every 8 tiles
+            let autotile_y = index as u32 / AUTOTILE_FRAME_COLS * TILE_SIZE;
+
+            for (index, sub_tile) in autotile_ids.into_iter().enumerate() {
+                let sub_tile_x = index as u32 % 2 * 16;
+                let sub_tile_y = index as u32 / 2 * 16;
+
+                let tile_x = sub_tile % 6 * 16 + frame * 96;
+                let tile_y = sub_tile / 6 * 16;
+
+                for (x, y) in itertools::iproduct!(0..16, 0..16) {
+                    // Autotiles that are too small are left transparent where they're missing
+                    if let Some(pixel) = autotile.get_pixel_checked(tile_x + x, tile_y + y) {
+                        expanded.put_pixel(
+                            frame_x + autotile_x + sub_tile_x + x,
+                            autotile_y + sub_tile_y + y,
+                            *pixel,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    expanded
+}
+
+/// The color of the checkerboard an atlas is drawn with while it's loading.
+fn loading_checkerboard_pixel(x: u32, y: u32) -> [u8; 4] {
+    if (x / 16 + y / 16) % 2 == 0 {
+        [96, 96, 96, 255]
+    } else {
+        [64, 64, 64, 255]
+    }
+}
+
+/// Returns the raw RGBA data of a `width`x`height` image with the pixel colors given by `pixel`.
+fn fill_image(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4] + Sync) -> Vec<u8> {
+    let mut data = vec![0; width as usize * height as usize * 4];
+    let fill_row = |(y, row): (usize, &mut [u8])| {
+        for (x, rgba) in row.chunks_exact_mut(4).enumerate() {
+            rgba.copy_from_slice(&pixel(x as u32, y as u32));
+        }
+    };
+
+    // Atlases for large tilesets can be hundreds of megabytes, so this is worth splitting up
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use rayon::prelude::*;
+        data.par_chunks_exact_mut(width as usize * 4)
+            .enumerate()
+            .for_each(fill_row);
+    }
+    #[cfg(target_arch = "wasm32")]
+    data.chunks_exact_mut(width as usize * 4)
+        .enumerate()
+        .for_each(fill_row);
+
+    data
+}
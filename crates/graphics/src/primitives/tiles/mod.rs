@@ -18,6 +18,7 @@
 use std::sync::Arc;
 
 use crate::{
+    primitives::shadows::{self, Shadows},
     BindGroupBuilder, BindGroupLayoutBuilder, Drawable, GraphicsState, Renderable, Transform,
     Viewport,
 };
@@ -45,8 +46,13 @@ pub struct Tiles {
 
     instances: Arc<Instances>,
     bind_group: Arc<wgpu::BindGroup>,
+    /// Only present for RPG Maker VX Ace maps with a shadow layer
+    shadows: Option<Shadows>,
 }
 
+/// The layer the shadows of VX Ace maps are drawn beneath.
+const UPPER_LAYER: usize = 2;
+
 impl Tiles {
     pub fn new(
         graphics_state: &GraphicsState,
@@ -55,6 +61,7 @@ impl Tiles {
         atlas: &Atlas,
         viewport: &Viewport,
         transform: Transform,
+        editor_ver: luminol_config::RMVer,
     ) -> Self {
         let autotiles = Autotiles::new(graphics_state, atlas);
         let instances = Instances::new(&graphics_state.render_state, tiles);
@@ -65,19 +72,17 @@ impl Tiles {
             tiles.zsize(),
         );
 
-        let mut bind_group_builder = BindGroupBuilder::new();
-        bind_group_builder
-            .append_texture_view(&atlas.texture().view)
-            .append_sampler(&graphics_state.nearest_sampler)
-            .append_buffer(viewport.as_buffer())
-            .append_buffer(transform.as_buffer())
-            .append_buffer(autotiles.as_buffer())
-            .append_buffer_with_size(display.as_buffer(), display.aligned_layer_size() as u64);
+        // Only VX Ace uses the fourth layer for shadows, anything else is drawn as tiles
+        let shadows = (editor_ver == luminol_config::RMVer::Ace && tiles.zsize() > shadows::LAYER)
+            .then(|| Shadows::new(graphics_state, viewport, &transform, tiles));
 
-        let bind_group = bind_group_builder.build(
-            &graphics_state.render_state.device,
-            Some("tilemap bind group"),
-            &graphics_state.bind_group_layouts.tiles,
+        let bind_group = Self::create_bind_group(
+            graphics_state,
+            atlas,
+            viewport,
+            &transform,
+            &autotiles,
+            &display,
         );
 
         Self {
@@ -90,6 +95,7 @@ impl Tiles {
 
             instances: Arc::new(instances),
             bind_group: Arc::new(bind_group),
+            shadows,
         }
     }
 
@@ -99,8 +105,89 @@ impl Tiles {
         tile_id: i16,
         position: (usize, usize, usize),
     ) {
+        if let Some(shadows) = self
+            .shadows
+            .as_ref()
+            .filter(|_| position.2 == shadows::LAYER)
+        {
+            shadows.set_shadow(render_state, tile_id, (position.0, position.1));
+            return;
+        }
         self.instances.set_tile(render_state, tile_id, position)
     }
+
+    /// Whether the map has a shadow layer, which is drawn as shadows instead of as tiles.
+    pub fn has_shadows(&self) -> bool {
+        self.shadows.is_some()
+    }
+
+    /// Creates a copy of these tiles drawn with a different transform. The copy shares every
+    /// other buffer with these tiles, so it always shows the same tiles with the same layer
+    /// settings. Shadows aren't copied.
+    pub fn create_copy(
+        &self,
+        graphics_state: &GraphicsState,
+        atlas: &Atlas,
+        viewport: &Viewport,
+        transform: Transform,
+    ) -> TilesCopy {
+        let bind_group = Self::create_bind_group(
+            graphics_state,
+            atlas,
+            viewport,
+            &transform,
+            &self.autotiles,
+            &self.display,
+        );
+        TilesCopy {
+            transform,
+            bind_group: Arc::new(bind_group),
+        }
+    }
+
+    /// Prepares a copy made with [`Self::create_copy`] for drawing.
+    pub fn prepare_copy(&self, copy: &TilesCopy, graphics_state: &Arc<GraphicsState>) -> Prepared {
+        Prepared {
+            bind_group: Arc::clone(&copy.bind_group),
+            instances: Arc::clone(&self.instances),
+            graphics_state: Arc::clone(graphics_state),
+
+            layer_offsets: self.display.layer_offsets(),
+            enabled_layers: self.enabled_layers.clone(),
+            has_shadows: self.has_shadows(),
+            shadows: None,
+        }
+    }
+
+    fn create_bind_group(
+        graphics_state: &GraphicsState,
+        atlas: &Atlas,
+        viewport: &Viewport,
+        transform: &Transform,
+        autotiles: &Autotiles,
+        display: &Display,
+    ) -> wgpu::BindGroup {
+        let mut bind_group_builder = BindGroupBuilder::new();
+        bind_group_builder
+            .append_texture_view(&atlas.texture().view)
+            .append_sampler(&graphics_state.nearest_sampler)
+            .append_buffer(viewport.as_buffer())
+            .append_buffer(transform.as_buffer())
+            .append_buffer(autotiles.as_buffer())
+            .append_buffer_with_size(display.as_buffer(), display.aligned_layer_size() as u64);
+
+        bind_group_builder.build(
+            &graphics_state.render_state.device,
+            Some("tilemap bind group"),
+            &graphics_state.bind_group_layouts.tiles,
+        )
+    }
+}
+
+/// Tiles drawn again somewhere else, made with [`Tiles::create_copy`].
+pub struct TilesCopy {
+    pub transform: Transform,
+    bind_group: Arc<wgpu::BindGroup>,
 }
 
 pub struct Prepared {
@@ -110,6 +197,10 @@ pub struct Prepared {
 
     layer_offsets: Vec<u32>,
     enabled_layers: Vec<bool>,
+    /// Whether the shadow layer is drawn as shadows rather than as tiles, even if the shadows
+    /// themselves are hidden or not drawn by this copy.
+    has_shadows: bool,
+    shadows: Option<shadows::Prepared>,
 }
 
 impl Renderable for Tiles {
@@ -132,6 +223,12 @@ impl Renderable for Tiles {
             }
         }
 
+        let shadows = self
+            .shadows
+            .as_mut()
+            .filter(|_| self.enabled_layers[shadows::LAYER])
+            .map(|shadows| shadows.prepare(&graphics_state));
+
         Prepared {
             bind_group,
             instances,
@@ -139,21 +236,26 @@ impl Renderable for Tiles {
 
             layer_offsets: self.display.layer_offsets(),
             enabled_layers: self.enabled_layers.clone(),
+            has_shadows: self.has_shadows(),
+            shadows,
         }
     }
 }
 
-impl Drawable for Prepared {
-    fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+impl Prepared {
+    fn draw_layers<'rpass>(
+        &'rpass self,
+        render_pass: &mut wgpu::RenderPass<'rpass>,
+        layers: std::ops::Range<usize>,
+    ) {
         render_pass.push_debug_group("tilemap tiles renderer");
-        render_pass.set_pipeline(&self.graphics_state.pipelines.tiles);
+        render_pass.set_pipeline(
+            self.graphics_state
+                .pipelines
+                .tiles(&self.graphics_state.bind_group_layouts),
+        );
 
-        for layer in self
-            .enabled_layers
-            .iter()
-            .enumerate()
-            .filter_map(|(layer, enabled)| enabled.then_some(layer))
-        {
+        for layer in layers.filter(|&layer| self.enabled_layers[layer]) {
             render_pass.set_bind_group(0, &self.bind_group, &[self.layer_offsets[layer]]);
 
             self.instances.draw(render_pass, layer);
@@ -162,6 +264,23 @@ impl Drawable for Prepared {
     }
 }
 
+impl Drawable for Prepared {
+    fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        if !self.has_shadows {
+            self.draw_layers(render_pass, 0..self.enabled_layers.len());
+            return;
+        }
+
+        // The shadow layer isn't drawn as tiles. Shadows go over the ground layers but beneath
+        // the upper layer, like in VX Ace.
+        self.draw_layers(render_pass, 0..UPPER_LAYER);
+        if let Some(ref shadows) = self.shadows {
+            shadows.draw(render_pass);
+        }
+        self.draw_layers(render_pass, UPPER_LAYER..shadows::LAYER);
+    }
+}
+
 pub fn create_bind_group_layout(
     render_state: &luminol_egui_wgpu::RenderState,
 ) -> wgpu::BindGroupLayout {
@@ -22,6 +22,7 @@ pub fn create_render_pipeline(
     composer: &mut naga_oil::compose::Composer,
     render_state: &luminol_egui_wgpu::RenderState,
     bind_group_layouts: &BindGroupLayouts,
+    limits: super::atlas::AtlasLimits,
 ) -> Result<wgpu::RenderPipeline, naga_oil::compose::ComposerError> {
     composer.add_composable_module(naga_oil::compose::ComposableModuleDescriptor {
         source: include_str!("../shaders/translation.wgsl"),
@@ -57,7 +58,7 @@ pub fn create_render_pipeline(
             (
                 "ROWS_UNDER_AUTOTILES_TIMES_COLUMNS".to_string(),
                 naga_oil::compose::ShaderDefValue::UInt(
-                    super::atlas::ROWS_UNDER_AUTOTILES_TIMES_COLUMNS,
+                    limits.rows_under_autotiles_times_columns(),
                 ),
             ),
             (
@@ -74,7 +75,7 @@ pub fn create_render_pipeline(
             ),
             (
                 "ROWS_UNDER_AUTOTILES".to_string(),
-                naga_oil::compose::ShaderDefValue::UInt(super::atlas::ROWS_UNDER_AUTOTILES),
+                naga_oil::compose::ShaderDefValue::UInt(limits.rows_under_autotiles()),
             ),
             (
                 "TOTAL_AUTOTILE_ROWS".to_string(),
@@ -82,7 +83,7 @@ pub fn create_render_pipeline(
             ),
             (
                 "MAX_SIZE".to_string(),
-                naga_oil::compose::ShaderDefValue::UInt(super::atlas::MAX_SIZE),
+                naga_oil::compose::ShaderDefValue::UInt(limits.max_size),
             ),
             (
                 "AUTOTILE_FRAME_WIDTH".to_string(),
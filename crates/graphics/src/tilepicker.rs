@@ -49,6 +49,7 @@ impl Tilepicker {
         tileset: &luminol_data::rpg::Tileset,
         filesystem: &impl luminol_filesystem::FileSystem,
         exclude_autotiles: bool,
+        editor_ver: luminol_config::RMVer,
     ) -> Self {
         let atlas = graphics_state
             .atlas_loader
@@ -80,6 +81,7 @@ impl Tilepicker {
             &atlas,
             &viewport,
             Transform::unit(graphics_state),
+            editor_ver,
         );
 
         let grid = Grid::new(
@@ -0,0 +1,331 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Draws RPG Maker VX Ace maps into an image.
+//!
+//! The tilemap renderer is built around RPG Maker XP's atlas of one tileset and seven autotiles,
+//! while VX Ace splits its tiles across nine sheets with several autotile layouts. Until the
+//! renderer supports those, VX Ace maps are drawn on the CPU for read-only viewing.
+
+use image::{Pixel, Rgba, RgbaImage};
+
+/// Size of a tile in pixels.
+pub const TILE_SIZE: u32 = 32;
+const HALF_TILE_SIZE: u32 = TILE_SIZE / 2;
+
+/// The largest image `render_map` makes before scaling the map down, so it still fits in a texture.
+pub const MAX_IMAGE_SIZE: u32 = 8192;
+
+const TILE_ID_A5: u16 = 1536;
+const TILE_ID_A1: u16 = 2048;
+const TILE_ID_MAX: u16 = 8192;
+
+/// Indices of the sheets in `Tileset::tileset_names`.
+const SHEET_A1: usize = 0;
+const SHEET_A2: usize = 1;
+const SHEET_A3: usize = 2;
+const SHEET_A4: usize = 3;
+const SHEET_A5: usize = 4;
+const SHEET_B: usize = 5;
+
+/// Where each quarter of a tile comes from, in half tiles from the corner of the autotile, in the
+/// order top left, top right, bottom left, bottom right. Indexed by the shape of the autotile.
+type AutotileTable = [[[u32; 2]; 4]];
+
+/// Floor autotiles are 2 by 3 tiles, and have 48 shapes.
+#[rustfmt::skip]
+const FLOOR_AUTOTILE_TABLE: [[[u32; 2]; 4]; 48] = [
+    [[2, 4], [1, 4], [2, 3], [1, 3]], [[2, 0], [1, 4], [2, 3], [1, 3]],
+    [[2, 4], [3, 0], [2, 3], [1, 3]], [[2, 0], [3, 0], [2, 3], [1, 3]],
+    [[2, 4], [1, 4], [2, 3], [3, 1]], [[2, 0], [1, 4], [2, 3], [3, 1]],
+    [[2, 4], [3, 0], [2, 3], [3, 1]], [[2, 0], [3, 0], [2, 3], [3, 1]],
+    [[2, 4], [1, 4], [2, 1], [1, 3]], [[2, 0], [1, 4], [2, 1], [1, 3]],
+    [[2, 4], [3, 0], [2, 1], [1, 3]], [[2, 0], [3, 0], [2, 1], [1, 3]],
+    [[2, 4], [1, 4], [2, 1], [3, 1]], [[2, 0], [1, 4], [2, 1], [3, 1]],
+    [[2, 4], [3, 0], [2, 1], [3, 1]], [[2, 0], [3, 0], [2, 1], [3, 1]],
+    [[0, 4], [1, 4], [0, 3], [1, 3]], [[0, 4], [3, 0], [0, 3], [1, 3]],
+    [[0, 4], [1, 4], [0, 3], [3, 1]], [[0, 4], [3, 0], [0, 3], [3, 1]],
+    [[2, 2], [1, 2], [2, 3], [1, 3]], [[2, 2], [1, 2], [2, 3], [3, 1]],
+    [[2, 2], [1, 2], [2, 1], [1, 3]], [[2, 2], [1, 2], [2, 1], [3, 1]],
+    [[2, 4], [3, 4], [2, 3], [3, 3]], [[2, 4], [3, 4], [2, 1], [3, 3]],
+    [[2, 0], [3, 4], [2, 3], [3, 3]], [[2, 0], [3, 4], [2, 1], [3, 3]],
+    [[2, 4], [1, 4], [2, 5], [1, 5]], [[2, 0], [1, 4], [2, 5], [1, 5]],
+    [[2, 4], [3, 0], [2, 5], [1, 5]], [[2, 0], [3, 0], [2, 5], [1, 5]],
+    [[0, 4], [3, 4], [0, 3], [3, 3]], [[2, 2], [1, 2], [2, 5], [1, 5]],
+    [[0, 2], [1, 2], [0, 3], [1, 3]], [[0, 2], [1, 2], [0, 3], [3, 1]],
+    [[2, 2], [3, 2], [2, 3], [3, 3]], [[2, 2], [3, 2], [2, 1], [3, 3]],
+    [[2, 4], [3, 4], [2, 5], [3, 5]], [[2, 0], [3, 4], [2, 5], [3, 5]],
+    [[0, 4], [1, 4], [0, 5], [1, 5]], [[0, 4], [3, 0], [0, 5], [1, 5]],
+    [[0, 2], [3, 2], [0, 3], [3, 3]], [[0, 2], [1, 2], [0, 5], [1, 5]],
+    [[0, 4], [3, 4], [0, 5], [3, 5]], [[2, 2], [3, 2], [2, 5], [3, 5]],
+    [[0, 2], [3, 2], [0, 5], [3, 5]], [[0, 0], [1, 0], [0, 1], [1, 1]],
+];
+
+/// Wall autotiles are 2 by 2 tiles, and have 16 shapes.
+#[rustfmt::skip]
+const WALL_AUTOTILE_TABLE: [[[u32; 2]; 4]; 16] = [
+    [[2, 2], [1, 2], [2, 1], [1, 1]], [[0, 2], [1, 2], [0, 1], [1, 1]],
+    [[2, 0], [1, 0], [2, 1], [1, 1]], [[0, 0], [1, 0], [0, 1], [1, 1]],
+    [[2, 2], [3, 2], [2, 1], [3, 1]], [[0, 2], [3, 2], [0, 1], [3, 1]],
+    [[2, 0], [3, 0], [2, 1], [3, 1]], [[0, 0], [3, 0], [0, 1], [3, 1]],
+    [[2, 2], [1, 2], [2, 3], [1, 3]], [[0, 2], [1, 2], [0, 3], [1, 3]],
+    [[2, 0], [1, 0], [2, 3], [1, 3]], [[0, 0], [1, 0], [0, 3], [1, 3]],
+    [[2, 2], [3, 2], [2, 3], [3, 3]], [[0, 2], [3, 2], [0, 3], [3, 3]],
+    [[2, 0], [3, 0], [2, 3], [3, 3]], [[0, 0], [3, 0], [0, 3], [3, 3]],
+];
+
+/// Waterfall autotiles are 2 by 1 tiles, and have 4 shapes.
+#[rustfmt::skip]
+const WATERFALL_AUTOTILE_TABLE: [[[u32; 2]; 4]; 4] = [
+    [[2, 0], [1, 0], [2, 1], [1, 1]], [[0, 0], [1, 0], [0, 1], [1, 1]],
+    [[2, 0], [3, 0], [2, 1], [3, 1]], [[0, 0], [3, 0], [0, 1], [3, 1]],
+];
+
+const SHADOW_COLOR: Rgba<u8> = Rgba([0, 0, 0, 128]);
+
+/// The graphics of a tileset, in the order of `Tileset::tileset_names`: A1 to A5 and then B to E.
+pub type Sheets = [Option<RgbaImage>; 9];
+
+/// Reads the graphics of `tileset` from `Graphics/Tilesets`. Also returns the names of the ones
+/// that couldn't be read.
+pub fn load_sheets(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    tileset: &luminol_data::rmvx_ace::Tileset,
+) -> (Sheets, Vec<String>) {
+    let mut missing = Vec::new();
+    let sheets = std::array::from_fn(|i| {
+        let name = tileset
+            .tileset_names
+            .get(i)
+            .filter(|name| !name.is_empty())?;
+        let image = ["png", "jpg", "bmp"].into_iter().find_map(|extension| {
+            let bytes = filesystem
+                .read(format!("Graphics/Tilesets/{name}.{extension}"))
+                .ok()?;
+            image::load_from_memory(&bytes).ok()
+        });
+        if image.is_none() {
+            missing.push(name.clone());
+        }
+        image.map(|image| image.into_rgba8())
+    });
+    (sheets, missing)
+}
+
+/// Draws every tile of `map` using the graphics of its tileset. Tiles from missing sheets are left
+/// out, and animated autotiles are drawn on their first frame.
+///
+/// Maps too large to fit in [`MAX_IMAGE_SIZE`] are drawn at half size.
+pub fn render_map(map: &luminol_data::rmvx_ace::Map, sheets: &Sheets) -> egui::ColorImage {
+    let data = &map.data;
+    let (width, height) = (data.xsize() as u32, data.ysize() as u32);
+    let scale_shift = u32::from(width.max(height) * TILE_SIZE > MAX_IMAGE_SIZE);
+
+    let mut canvas = Canvas {
+        image: RgbaImage::new(
+            (width * TILE_SIZE) >> scale_shift,
+            (height * TILE_SIZE) >> scale_shift,
+        ),
+        scale_shift,
+    };
+
+    for y in 0..data.ysize() {
+        for x in 0..data.xsize() {
+            let origin = (x as u32 * TILE_SIZE, y as u32 * TILE_SIZE);
+            for z in 0..data.zsize().min(3) {
+                if z == 2 && data.zsize() > 3 {
+                    canvas.draw_shadow(origin, data[(x, y, 3)] as u16 & 0xf);
+                }
+                canvas.draw_tile(sheets, origin, data[(x, y, z)] as u16);
+            }
+        }
+    }
+
+    egui::ColorImage::from_rgba_unmultiplied(
+        [
+            canvas.image.width() as usize,
+            canvas.image.height() as usize,
+        ],
+        canvas.image.as_raw(),
+    )
+}
+
+struct Canvas {
+    image: RgbaImage,
+    /// How many times the map is halved in size.
+    scale_shift: u32,
+}
+
+impl Canvas {
+    fn draw_tile(&mut self, sheets: &Sheets, origin: (u32, u32), id: u16) {
+        // the first B tile is always blank
+        if id == 0 || id >= TILE_ID_MAX {
+            return;
+        }
+
+        if id < TILE_ID_A5 {
+            // B to E have 256 tiles each, in two columns of 8 tiles
+            let index = id % 256;
+            let tile = (index % 8 + index / 128 * 8, index % 128 / 8);
+            let sheet = sheets.get(SHEET_B + id as usize / 256);
+            self.draw_whole_tile(sheet.and_then(Option::as_ref), origin, tile);
+        } else if id < TILE_ID_A1 {
+            let index = id - TILE_ID_A5;
+            self.draw_whole_tile(sheets[SHEET_A5].as_ref(), origin, (index % 8, index / 8));
+        } else {
+            let kind = (id - TILE_ID_A1) / 48;
+            let shape = ((id - TILE_ID_A1) % 48) as usize;
+            let (sheet, corner, table) = autotile_layout(kind);
+            let Some(quarters) = table.get(shape) else {
+                return;
+            };
+            for (i, [qx, qy]) in (0..).zip(quarters) {
+                self.blit_quarter(
+                    sheets[sheet].as_ref(),
+                    (
+                        corner.0 * TILE_SIZE + qx * HALF_TILE_SIZE,
+                        corner.1 * TILE_SIZE + qy * HALF_TILE_SIZE,
+                    ),
+                    (
+                        origin.0 + i % 2 * HALF_TILE_SIZE,
+                        origin.1 + i / 2 * HALF_TILE_SIZE,
+                    ),
+                );
+            }
+        }
+    }
+
+    fn draw_whole_tile(&mut self, sheet: Option<&RgbaImage>, origin: (u32, u32), tile: (u16, u16)) {
+        for i in 0..4 {
+            let offset = (i % 2 * HALF_TILE_SIZE, i / 2 * HALF_TILE_SIZE);
+            self.blit_quarter(
+                sheet,
+                (
+                    tile.0 as u32 * TILE_SIZE + offset.0,
+                    tile.1 as u32 * TILE_SIZE + offset.1,
+                ),
+                (origin.0 + offset.0, origin.1 + offset.1),
+            );
+        }
+    }
+
+    /// Darkens the quarters of a tile whose bits are set in `shadow`, starting from the top left.
+    fn draw_shadow(&mut self, origin: (u32, u32), shadow: u16) {
+        for i in 0..4 {
+            if shadow & (1 << i) == 0 {
+                continue;
+            }
+            let dest = (
+                origin.0 + i % 2 * HALF_TILE_SIZE,
+                origin.1 + i / 2 * HALF_TILE_SIZE,
+            );
+            for y in 0..HALF_TILE_SIZE >> self.scale_shift {
+                for x in 0..HALF_TILE_SIZE >> self.scale_shift {
+                    self.blend_pixel(dest, (x, y), SHADOW_COLOR);
+                }
+            }
+        }
+    }
+
+    /// Draws a half tile from `source` in `sheet` at `dest`, both in pixels at full size.
+    fn blit_quarter(&mut self, sheet: Option<&RgbaImage>, source: (u32, u32), dest: (u32, u32)) {
+        let Some(sheet) = sheet else {
+            return;
+        };
+        for y in 0..HALF_TILE_SIZE >> self.scale_shift {
+            for x in 0..HALF_TILE_SIZE >> self.scale_shift {
+                let Some(&pixel) = sheet.get_pixel_checked(
+                    source.0 + (x << self.scale_shift),
+                    source.1 + (y << self.scale_shift),
+                ) else {
+                    continue;
+                };
+                self.blend_pixel(dest, (x, y), pixel);
+            }
+        }
+    }
+
+    fn blend_pixel(&mut self, dest: (u32, u32), offset: (u32, u32), pixel: Rgba<u8>) {
+        let x = (dest.0 >> self.scale_shift) + offset.0;
+        let y = (dest.1 >> self.scale_shift) + offset.1;
+        if let Some(target) = self.image.get_pixel_mut_checked(x, y) {
+            target.blend(&pixel);
+        }
+    }
+}
+
+/// The sheet an autotile kind is on, the tile its top left corner is at, and the layout of its
+/// shapes. Animated autotiles use their first frame.
+fn autotile_layout(kind: u16) -> (usize, (u32, u32), &'static AutotileTable) {
+    let kind = kind as u32;
+    match kind {
+        // A1 has the sea in its top left quarter, and three kinds of water with a waterfall
+        // beside each in the other quarters
+        0 => (SHEET_A1, (0, 0), &FLOOR_AUTOTILE_TABLE),
+        1 => (SHEET_A1, (0, 3), &FLOOR_AUTOTILE_TABLE),
+        2 => (SHEET_A1, (6, 0), &FLOOR_AUTOTILE_TABLE),
+        3 => (SHEET_A1, (6, 3), &FLOOR_AUTOTILE_TABLE),
+        4..=15 => {
+            let quarter = ((kind / 4 % 2) * 8, (kind / 8) * 6);
+            let is_waterfall = kind % 2 == 1;
+            let corner = (
+                quarter.0 + if is_waterfall { 6 } else { 0 },
+                quarter.1 + (kind % 4 / 2) * 3,
+            );
+            if is_waterfall {
+                (SHEET_A1, corner, &WATERFALL_AUTOTILE_TABLE)
+            } else {
+                (SHEET_A1, corner, &FLOOR_AUTOTILE_TABLE)
+            }
+        }
+        16..=47 => {
+            let index = kind - 16;
+            (
+                SHEET_A2,
+                (index % 8 * 2, index / 8 * 3),
+                &FLOOR_AUTOTILE_TABLE,
+            )
+        }
+        48..=79 => {
+            let index = kind - 48;
+            (
+                SHEET_A3,
+                (index % 8 * 2, index / 8 * 2),
+                &WALL_AUTOTILE_TABLE,
+            )
+        }
+        _ => {
+            // A4 alternates between rows of wall tops, which are laid out like floors, and rows of
+            // walls
+            let index = kind - 80;
+            let row = index / 8;
+            let corner = (index % 8 * 2, row / 2 * 5 + row % 2 * 3);
+            if row % 2 == 0 {
+                (SHEET_A4, corner, &FLOOR_AUTOTILE_TABLE)
+            } else {
+                (SHEET_A4, corner, &WALL_AUTOTILE_TABLE)
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Draws RGSS windows from windowskin graphics on the CPU, for previews in the editor.
+
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+/// The size of the corners and the thickness of the edges of a window's frame.
+const FRAME_PART_SIZE: u32 = 16;
+
+/// The 8 text colors of RPG Maker XP's `Window_Base#text_color`. Unlike later versions, XP
+/// hardcodes them instead of reading them from the windowskin.
+pub const XP_TEXT_COLORS: [[u8; 4]; 8] = [
+    [255, 255, 255, 255],
+    [128, 128, 255, 255],
+    [255, 128, 128, 255],
+    [128, 255, 128, 255],
+    [128, 255, 255, 255],
+    [255, 128, 255, 255],
+    [255, 255, 128, 255],
+    [192, 192, 192, 255],
+];
+
+/// Where the parts of a window are in a windowskin graphic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// RPG Maker XP's 192×128 windowskins: a 128×128 background and the frame to its right.
+    Xp,
+    /// RPG Maker VX and VX Ace's 128×128 windowskins: a 64×64 background with a 64×64 pattern
+    /// tiled over it below, the frame to its right and the text color palette under the frame.
+    Vx,
+}
+
+impl Layout {
+    /// Guesses the layout of a windowskin from its size. Returns `None` if it's too small to be
+    /// a windowskin.
+    pub fn detect(skin: &RgbaImage) -> Option<Self> {
+        match skin.dimensions() {
+            (width, height) if width >= 192 && height >= 128 => Some(Self::Xp),
+            (width, height) if width >= 128 && height >= 128 => Some(Self::Vx),
+            _ => None,
+        }
+    }
+
+    /// The position and size of the square stretched to fill the window's background.
+    fn background(self) -> (u32, u32, u32) {
+        match self {
+            Self::Xp => (0, 0, 128),
+            Self::Vx => (0, 0, 64),
+        }
+    }
+
+    /// The position of the 64×64 square holding the window's frame.
+    fn frame_origin(self) -> (u32, u32) {
+        match self {
+            Self::Xp => (128, 0),
+            Self::Vx => (64, 0),
+        }
+    }
+}
+
+/// Returns the text colors a windowskin provides: the 32 colors of the palette of VX and VX Ace
+/// windowskins, or [`XP_TEXT_COLORS`] for XP ones.
+pub fn text_colors(skin: &RgbaImage) -> Vec<[u8; 4]> {
+    match Layout::detect(skin) {
+        // Same as `Window_Base#text_color` in VX and VX Ace
+        Some(Layout::Vx) => (0..32)
+            .map(|i| skin.get_pixel(64 + (i % 8) * 8, 96 + (i / 8) * 8).0)
+            .collect(),
+        _ => XP_TEXT_COLORS.to_vec(),
+    }
+}
+
+/// Draws a `width`×`height` window with the given windowskin, the same way RGSS does.
+///
+/// The background is stretched to fill the window minus a 2 pixel margin and drawn with
+/// `back_opacity`, with the pattern of VX and VX Ace windowskins tiled over it. The frame is
+/// drawn on top, with its edges tiled between its corners.
+///
+/// Returns `None` if the windowskin is too small to be one.
+pub fn compose_window(
+    skin: &RgbaImage,
+    width: u32,
+    height: u32,
+    back_opacity: u8,
+) -> Option<RgbaImage> {
+    let layout = Layout::detect(skin)?;
+    let width = width.max(FRAME_PART_SIZE * 2);
+    let height = height.max(FRAME_PART_SIZE * 2);
+    let mut window = RgbaImage::new(width, height);
+
+    let (inner_width, inner_height) = (width - 4, height - 4);
+    let (x, y, size) = layout.background();
+    let background = imageops::resize(
+        &imageops::crop_imm(skin, x, y, size, size).to_image(),
+        inner_width,
+        inner_height,
+        FilterType::Nearest,
+    );
+    blend(
+        &mut window,
+        &background,
+        (2, 2),
+        (inner_width, inner_height),
+        back_opacity,
+    );
+    if layout == Layout::Vx {
+        let pattern = imageops::crop_imm(skin, 0, 64, 64, 64).to_image();
+        tile(
+            &mut window,
+            &pattern,
+            (2, 2),
+            (inner_width, inner_height),
+            back_opacity,
+        );
+    }
+
+    let (frame_x, frame_y) = layout.frame_origin();
+    let part = |x: u32, y: u32, width: u32, height: u32| {
+        imageops::crop_imm(skin, frame_x + x, frame_y + y, width, height).to_image()
+    };
+    let s = FRAME_PART_SIZE;
+
+    // The edges
+    let edge_length = (width - 2 * s, height - 2 * s);
+    tile(
+        &mut window,
+        &part(s, 0, 2 * s, s),
+        (s, 0),
+        (edge_length.0, s),
+        255,
+    );
+    tile(
+        &mut window,
+        &part(s, 3 * s, 2 * s, s),
+        (s, height - s),
+        (edge_length.0, s),
+        255,
+    );
+    tile(
+        &mut window,
+        &part(0, s, s, 2 * s),
+        (0, s),
+        (s, edge_length.1),
+        255,
+    );
+    tile(
+        &mut window,
+        &part(3 * s, s, s, 2 * s),
+        (width - s, s),
+        (s, edge_length.1),
+        255,
+    );
+
+    // The corners
+    for (x, y) in [(0, 0), (3 * s, 0), (0, 3 * s), (3 * s, 3 * s)] {
+        let position = (
+            if x == 0 { 0 } else { width - s },
+            if y == 0 { 0 } else { height - s },
+        );
+        blend(&mut window, &part(x, y, s, s), position, (s, s), 255);
+    }
+
+    Some(window)
+}
+
+/// Repeats `src` over the `size` area of `dst` at `position`.
+fn tile(dst: &mut RgbaImage, src: &RgbaImage, position: (u32, u32), size: (u32, u32), opacity: u8) {
+    if src.width() == 0 || src.height() == 0 {
+        return;
+    }
+    for y in (0..size.1).step_by(src.height() as usize) {
+        for x in (0..size.0).step_by(src.width() as usize) {
+            blend(
+                dst,
+                src,
+                (position.0 + x, position.1 + y),
+                (
+                    (size.0 - x).min(src.width()),
+                    (size.1 - y).min(src.height()),
+                ),
+                opacity,
+            );
+        }
+    }
+}
+
+/// Draws the top left `size` area of `src` over `dst` at `position`, with its alpha multiplied by
+/// `opacity`.
+fn blend(
+    dst: &mut RgbaImage,
+    src: &RgbaImage,
+    position: (u32, u32),
+    size: (u32, u32),
+    opacity: u8,
+) {
+    let width = size
+        .0
+        .min(src.width())
+        .min(dst.width().saturating_sub(position.0));
+    let height = size
+        .1
+        .min(src.height())
+        .min(dst.height().saturating_sub(position.1));
+
+    for y in 0..height {
+        for x in 0..width {
+            let source = src.get_pixel(x, y).0;
+            let source_alpha = source[3] as u32 * opacity as u32 / 255;
+            if source_alpha == 0 {
+                continue;
+            }
+
+            let dest = &mut dst.get_pixel_mut(position.0 + x, position.1 + y).0;
+            let dest_alpha = dest[3] as u32 * (255 - source_alpha) / 255;
+            let alpha = source_alpha + dest_alpha;
+            for (dest_channel, source_channel) in dest.iter_mut().zip(source).take(3) {
+                *dest_channel = ((source_channel as u32 * source_alpha
+                    + *dest_channel as u32 * dest_alpha)
+                    / alpha) as u8;
+            }
+            dest[3] = alpha as u8;
+        }
+    }
+}
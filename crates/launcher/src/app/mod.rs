@@ -35,6 +35,7 @@ mod top_bar;
 /// The main Luminol struct. Handles rendering, GUI state, that sort of thing.
 pub struct App {
     top_bar: top_bar::TopBar,
+    recent_edits_switcher: luminol_ui::components::RecentEditsSwitcher,
     #[cfg(not(target_arch = "wasm32"))]
     log: log_window::LogWindow,
     lumi: Lumi,
@@ -55,10 +56,16 @@ pub struct App {
     project_config: Option<luminol_config::project::Config>,
 
     toolbar: luminol_core::ToolbarState,
+    recent_edits: luminol_config::recent_edits::RecentEdits,
 
     modified: luminol_core::ModifiedState,
     modified_during_prev_frame: bool,
     project_manager: luminol_core::ProjectManager,
+    tasks: luminol_core::TaskManager,
+    /// Projects passed on the command line or forwarded by other instances of Luminol.
+    /// `None` means another instance was started without a project.
+    #[cfg(not(target_arch = "wasm32"))]
+    open_project_rx: std::sync::mpsc::Receiver<Option<camino::Utf8PathBuf>>,
 
     #[cfg(not(target_arch = "wasm32"))]
     _runtime: tokio::runtime::Runtime,
@@ -69,15 +76,6 @@ pub struct App {
     steamworks: Steamworks,
 }
 
-macro_rules! let_with_mut_on_native {
-    ($name:ident, $value:expr) => {
-        #[cfg(not(target_arch = "wasm32"))]
-        let mut $name = $value;
-        #[cfg(target_arch = "wasm32")]
-        let $name = $value;
-    };
-}
-
 impl App {
     /// Called once before the first frame.
     #[must_use]
@@ -86,7 +84,9 @@ impl App {
         report: Option<String>,
         modified: luminol_core::ModifiedState,
         #[cfg(not(target_arch = "wasm32"))] log_byte_rx: std::sync::mpsc::Receiver<u8>,
-        #[cfg(not(target_arch = "wasm32"))] try_load_path: Option<std::ffi::OsString>,
+        #[cfg(not(target_arch = "wasm32"))] open_project_rx: std::sync::mpsc::Receiver<
+            Option<camino::Utf8PathBuf>,
+        >,
         #[cfg(target_arch = "wasm32")] audio: luminol_audio::Audio,
         #[cfg(feature = "steamworks")] steamworks: Steamworks,
     ) -> Self {
@@ -202,33 +202,8 @@ impl App {
 
         let storage = cc.storage.unwrap();
 
-        let_with_mut_on_native!(
-            global_config,
-            luminol_eframe::get_value(storage, "SavedState").unwrap_or_default()
-        );
-        let_with_mut_on_native!(project_config, None);
-
-        let_with_mut_on_native!(filesystem, luminol_filesystem::project::FileSystem::new());
-        let_with_mut_on_native!(data, luminol_core::Data::default());
-
-        let_with_mut_on_native!(toasts, luminol_core::Toasts::default());
-
-        #[cfg(not(target_arch = "wasm32"))]
-        if let Some(path) = try_load_path {
-            let path = camino::Utf8PathBuf::from_path_buf(std::path::PathBuf::from(path))
-                .expect("project path not utf-8");
-
-            match filesystem.load_project_from_path(&mut project_config, &mut global_config, path) {
-                Ok(_) => {
-                    if let Err(e) =
-                        data.load(&filesystem, &mut toasts, project_config.as_mut().unwrap())
-                    {
-                        luminol_core::error!(toasts, e)
-                    }
-                }
-                Err(e) => luminol_core::error!(toasts, e),
-            }
-        }
+        let global_config: luminol_config::global::Config =
+            luminol_eframe::get_value(storage, "SavedState").unwrap_or_default();
 
         if let Some(style) = luminol_eframe::get_value::<egui::Style>(storage, "EguiStyle") {
             cc.egui_ctx.set_style(style);
@@ -256,17 +231,18 @@ impl App {
 
         Self {
             top_bar: top_bar::TopBar::default(),
+            recent_edits_switcher: luminol_ui::components::RecentEditsSwitcher::new(),
             #[cfg(not(target_arch = "wasm32"))]
             log: log_window::LogWindow::new(&global_config.terminal, log_byte_rx),
             lumi,
 
             audio,
             graphics,
-            filesystem,
-            data,
+            filesystem: luminol_filesystem::project::FileSystem::new(),
+            data: luminol_core::Data::default(),
             bytes_loader,
 
-            toasts,
+            toasts: luminol_core::Toasts::default(),
             windows: report.map_or_else(luminol_core::Windows::new, |report| {
                 luminol_core::Windows::new_with_windows(vec![
                     luminol_ui::windows::reporter::Window::new(report, crate::git_revision()),
@@ -278,12 +254,16 @@ impl App {
                 true,
             ),
             global_config,
-            project_config,
+            project_config: None,
             toolbar: luminol_core::ToolbarState::default(),
+            recent_edits: Default::default(),
 
             modified,
             modified_during_prev_frame: false,
             project_manager: luminol_core::ProjectManager::new(&cc.egui_ctx),
+            tasks: luminol_core::TaskManager::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            open_project_rx,
 
             #[cfg(not(target_arch = "wasm32"))]
             _runtime: runtime,
@@ -328,6 +308,15 @@ impl luminol_eframe::App for App {
             }
         });
 
+        let overlay_colors = self.global_config.overlay_palette.colors();
+        self.graphics.set_overlay_colors(
+            overlay_colors.collision,
+            overlay_colors.grid_inner,
+            overlay_colors.grid_outer,
+        );
+
+        self.data.track_modifications();
+
         let mut update_state = luminol_core::UpdateState {
             ctx,
             audio: &mut self.audio,
@@ -341,15 +330,38 @@ impl luminol_eframe::App for App {
             project_config: &mut self.project_config,
             global_config: &mut self.global_config,
             toolbar: &mut self.toolbar,
+            recent_edits: &mut self.recent_edits,
             modified: self.modified.clone(),
             modified_during_prev_frame: &mut self.modified_during_prev_frame,
             project_manager: &mut self.project_manager,
+            tasks: &mut self.tasks,
             build_diagnostics: &BUILD_DIAGNOSTIC,
         };
 
-        // If a file/folder picker is open, prevent the user from interacting with the application
-        // with the mouse.
-        if update_state.project_manager.is_picker_open() {
+        // Open projects passed on the command line or forwarded by other instances of Luminol
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut project_path = None;
+            let mut focus = false;
+            for path in self.open_project_rx.try_iter() {
+                focus = true;
+                project_path = path.or(project_path);
+            }
+
+            if focus {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            if let Some(project_path) = project_path {
+                update_state
+                    .project_manager
+                    .load_recent_project(project_path.into_string());
+            }
+        }
+
+        // If a file/folder picker is open or the project is being saved, prevent the user from
+        // interacting with the application with the mouse.
+        if update_state.project_manager.is_picker_open() || update_state.project_manager.is_saving()
+        {
             egui::Area::new("luminol_picker_overlay".into()).show(ctx, |ui| {
                 ui.allocate_response(
                     ui.ctx().input(|i| i.screen_rect.size()),
@@ -382,6 +394,9 @@ impl luminol_eframe::App for App {
             });
         });
 
+        // Handle Ctrl+Tab before the tabs and windows get a chance to use the Tab key.
+        self.recent_edits_switcher.ui(ctx, &mut update_state);
+
         // Central panel with tabs.
         egui::CentralPanel::default()
             .frame(egui::Frame::central_panel(&ctx.style()).inner_margin(0.))
@@ -399,13 +414,33 @@ impl luminol_eframe::App for App {
                 }
             });
 
+        // Windows restored from the previous session may be outside of the viewport if the screen
+        // layout changed since then.
+        if ctx.frame_nr() == 0 && luminol_core::window::clamp_windows_to_screen(ctx) {
+            luminol_core::info!(
+                update_state.toasts,
+                "Some windows were off-screen, so the window layout was reset"
+            );
+        }
+
         // Update all windows.
         self.windows.display_without_edit(ctx, &mut update_state);
 
+        // Background tasks that were started this frame show their logs in the task log console
+        #[cfg(not(target_arch = "wasm32"))]
+        if update_state.tasks.has_new_logs() {
+            update_state
+                .edit_windows
+                .add_window(luminol_ui::windows::console::Window::task_logs());
+        }
+
         // Handle loading and closing projects, and if applicable, show the modal asking the user
         // if they want to save their changes.
         update_state.manage_projects(true);
 
+        // Nothing borrows the data anymore this frame, so it's safe to save it here
+        update_state.autosave();
+
         // If we don't do this tabs added by windows won't be added.
         // It also cleans up code nicely.
         self.tabs
@@ -413,6 +448,12 @@ impl luminol_eframe::App for App {
         self.windows
             .process_edit_windows(std::mem::take(update_state.edit_windows));
 
+        // Swap in tileset atlases that finished loading in the background, and keep repainting
+        // until they all have so the loading checkerboard doesn't linger.
+        if self.graphics.atlas_loader.poll_pending(&self.graphics) {
+            ctx.request_repaint();
+        }
+
         // Create toasts for any texture loading errors encountered this frame.
         for error in self.graphics.texture_errors() {
             luminol_core::error!(self.toasts, error);
@@ -421,6 +462,24 @@ impl luminol_eframe::App for App {
         // Show toasts.
         self.toasts.show(ctx);
 
+        if self.top_bar.show_profiling_overlay {
+            egui::Area::new(egui::Id::new("luminol_profiling_overlay"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8., -8.))
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.strong("Graphics timings");
+                        for timing in self.graphics.timings().get() {
+                            ui.label(format!(
+                                "{}: {:.1} ms",
+                                timing.name,
+                                timing.duration.as_secs_f64() * 1000.
+                            ));
+                        }
+                    });
+                });
+        }
+
         self.lumi.ui(ctx);
 
         super::RESTART_AFTER_PANIC.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -447,6 +506,13 @@ impl luminol_eframe::App for App {
         luminol_eframe::set_value(storage, "SavedState", &self.global_config);
     }
 
+    /// Called once on shutdown, after [`Self::save`].
+    fn on_exit(&mut self) {
+        if let Err(error) = self.project_manager.release_lock(&self.filesystem) {
+            luminol_core::tracing::error!("{error:?}");
+        }
+    }
+
     fn persist_egui_memory(&self) -> bool {
         true
     }
@@ -22,8 +22,12 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_core::Structure;
 use strum::IntoEnumIterator;
 
+const READ_ONLY_TEXT: &str =
+    "This project was opened read-only because another editor has it open. Reopen it to save.";
+
 /// The top bar for managing the project.
 #[derive(Default)]
 pub struct TopBar {
@@ -31,6 +35,8 @@ pub struct TopBar {
     fullscreen: bool,
     #[cfg(not(target_arch = "wasm32"))]
     pub(super) show_log: bool,
+    pub(super) show_profiling_overlay: bool,
+    unsaved_changes: luminol_ui::components::UnsavedChanges,
 }
 
 impl TopBar {
@@ -54,6 +60,8 @@ impl TopBar {
             && update_state.filesystem.project_loaded();
         let mut save_project = ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::S))
             && update_state.filesystem.project_loaded();
+        let mut save_all = false;
+        let mut save_maps = false;
         if ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::N)) {
             update_state
                 .edit_windows
@@ -99,7 +107,24 @@ impl TopBar {
                     update_state.project_manager.close_project();
                 }
 
-                save_project |= ui.button("Save Project").clicked();
+                ui.add_enabled_ui(!update_state.project_manager.read_only, |ui| {
+                    save_project |= ui
+                        .button("Save Project")
+                        .on_disabled_hover_text(READ_ONLY_TEXT)
+                        .clicked();
+                    save_all = ui
+                        .button("Save All Data")
+                        .on_hover_text(
+                            "Writes every loaded data file, even ones that haven't been modified",
+                        )
+                        .on_disabled_hover_text(READ_ONLY_TEXT)
+                        .clicked();
+                    save_maps = ui
+                        .button("Save Modified Maps")
+                        .on_hover_text("Writes only the maps that have been modified")
+                        .on_disabled_hover_text(READ_ONLY_TEXT)
+                        .clicked();
+                });
             });
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -132,6 +157,12 @@ impl TopBar {
                     .add_window(luminol_ui::windows::preferences::Window::default())
             }
 
+            if ui.button("Appearance").clicked() {
+                update_state
+                    .edit_windows
+                    .add_window(luminol_ui::windows::appearance::Window::default())
+            }
+
             ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
                 if ui.button("Project Config").clicked() {
                     let config = update_state.project_config.as_ref().unwrap();
@@ -150,6 +181,24 @@ impl TopBar {
 
         ui.separator();
 
+        // Maps are listed under the map tree, since that's where they're opened from
+        let modified = update_state
+            .data
+            .modified_structures()
+            .into_iter()
+            .map(|(structure, _)| match structure {
+                Structure::Map(_) => Structure::MapInfos,
+                structure => structure,
+            })
+            .collect::<std::collections::HashSet<_>>();
+        let label = |name: &str, structure: Structure| {
+            if modified.contains(&structure) {
+                format!("{name} ●")
+            } else {
+                name.to_string()
+            }
+        };
+
         ui.menu_button("Data", |ui| {
             ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
@@ -161,31 +210,54 @@ impl TopBar {
             }
 
             ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
-                if ui.button("Maps").clicked() {
+                if ui.button(label("Maps", Structure::MapInfos)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::map_picker::Window::default());
                 }
 
+                if ui.button("World Overview").clicked() {
+                    update_state
+                        .edit_tabs
+                        .add_tab(luminol_ui::tabs::world::Tab::new(update_state));
+                }
+
+                if ui
+                    .button("Go to Start Position")
+                    .on_hover_text("Open the map new games start on, centered on the start tile")
+                    .clicked()
+                {
+                    luminol_ui::tabs::map::go_to_start_position(update_state);
+                }
+
                 ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Tilesets [TODO]").clicked() {
+                    if ui
+                        .button(label("Tilesets [TODO]", Structure::Tilesets))
+                        .clicked()
+                    {
                         todo!();
                     }
                 });
 
-                if ui.button("Animations").clicked() {
+                if ui
+                    .button(label("Animations", Structure::Animations))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::animations::Window::default());
                 }
 
-                if ui.button("Common Events").clicked() {
+                if ui
+                    .button(label("Common Events", Structure::CommonEvents))
+                    .clicked()
+                {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::common_event_edit::Window::default());
                 }
 
-                if ui.button("Scripts").clicked() {
+                if ui.button(label("Scripts", Structure::Scripts)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::script_edit::Window::default());
@@ -197,39 +269,39 @@ impl TopBar {
                     );
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("System [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui.button(label("System", Structure::System)).clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::system::Window::new());
+                }
 
                 ui.separator();
 
-                if ui.button("Items").clicked() {
+                if ui.button(label("Items", Structure::Items)).clicked() {
                     update_state
                         .edit_windows
-                        .add_window(luminol_ui::windows::items::Window::new(update_state));
+                        .add_window(luminol_ui::windows::items::Window::new());
                 }
 
-                if ui.button("Skills").clicked() {
+                if ui.button(label("Skills", Structure::Skills)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::skills::Window::new());
                 }
 
-                if ui.button("Weapons").clicked() {
+                if ui.button(label("Weapons", Structure::Weapons)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::weapons::Window::new());
                 }
 
-                if ui.button("Armor").clicked() {
+                if ui.button(label("Armor", Structure::Armors)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::armor::Window::new());
                 }
 
-                if ui.button("States").clicked() {
+                if ui.button(label("States", Structure::States)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::states::Window::new());
@@ -237,29 +309,29 @@ impl TopBar {
 
                 ui.separator();
 
-                if ui.button("Actors").clicked() {
+                if ui.button(label("Actors", Structure::Actors)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::actors::Window::new(update_state));
                 }
 
-                if ui.button("Classes").clicked() {
+                if ui.button(label("Classes", Structure::Classes)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::classes::Window::new());
                 }
 
-                if ui.button("Enemies").clicked() {
+                if ui.button(label("Enemies", Structure::Enemies)).clicked() {
                     update_state
                         .edit_windows
                         .add_window(luminol_ui::windows::enemies::Window::new(update_state));
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Troops [TODO]").clicked() {
-                        todo!();
-                    }
-                });
+                if ui.button(label("Troops", Structure::Troops)).clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::troops::Window::new());
+                }
             });
         });
 
@@ -286,6 +358,108 @@ impl TopBar {
                     .edit_windows
                     .add_window(luminol_ui::windows::script_manager::Window::default());
             }
+
+            if ui.button("VX Ace Map Viewer").clicked() {
+                update_state
+                    .edit_windows
+                    .add_window(luminol_ui::windows::vx_ace_map_viewer::Window::default());
+            }
+
+            ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
+                if ui.button("Run script...").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::batch_script::Window::default());
+                }
+
+                if ui.button("Icon Browser").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::icon_browser::Window::default());
+                }
+
+                if ui.button("Compact Event IDs").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::compact_event_ids::Window::default());
+                }
+
+                if ui.button("Rename Asset").clicked() {
+                    update_state.edit_windows.add_window(
+                        luminol_ui::windows::asset_rename::Window::new(
+                            luminol_core::asset_references::DIRECTORIES[0],
+                            None,
+                        ),
+                    );
+                }
+
+                if ui.button("Validate Project").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::validation::Window::default());
+                }
+
+                if ui.button("Export Dialogue Script").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::dialogue_export::Window::default());
+                }
+
+                if ui.button("Import Dialogue Edits").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::dialogue_import::Window::default());
+                }
+            });
+        });
+
+        ui.separator();
+
+        let extensions = luminol_core::extension::extensions();
+        if !extensions.is_empty() {
+            ui.menu_button("Extensions", |ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                // Hide this menu if the unsaved changes modal or a file/folder picker is open
+                if update_state.project_manager.is_modal_open()
+                    || update_state.project_manager.is_picker_open()
+                {
+                    ui.close_menu();
+                }
+
+                for extension in extensions.iter() {
+                    ui.menu_button(extension.name(), |ui| {
+                        for entry in extension.menu_entries() {
+                            let enabled =
+                                !entry.requires_project || update_state.filesystem.project_loaded();
+                            if ui
+                                .add_enabled(enabled, egui::Button::new(&entry.label))
+                                .clicked()
+                            {
+                                entry.open(update_state);
+                            }
+                        }
+                    });
+                }
+            });
+
+            ui.separator();
+        }
+        drop(extensions);
+
+        ui.menu_button("Window", |ui| {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+            // Hide this menu if the unsaved changes modal or a file/folder picker is open
+            if update_state.project_manager.is_modal_open()
+                || update_state.project_manager.is_picker_open()
+            {
+                ui.close_menu();
+            }
+
+            if ui.button("Reset window layout").clicked() {
+                luminol_core::window::reset_window_layout(ui.ctx());
+            }
         });
 
         ui.separator();
@@ -352,6 +526,16 @@ impl TopBar {
                     .add_window(luminol_ui::windows::misc::WgpuDebugInfo::new(update_state));
             }
 
+            if ui
+                .toggle_value(&mut self.show_profiling_overlay, "Profiling overlay")
+                .changed()
+            {
+                update_state
+                    .graphics
+                    .timings()
+                    .set_logging(self.show_profiling_overlay);
+            }
+
             #[cfg(not(target_arch = "wasm32"))]
             {
                 ui.separator();
@@ -422,6 +606,8 @@ impl TopBar {
 
         ui.separator();
 
+        self.unsaved_changes.ui(ui, update_state);
+
         ui.vertical(|ui| {
             ui.add_space(ui.spacing().button_padding.y.max(
                 (ui.spacing().interact_size.y - ui.text_style_height(&egui::TextStyle::Body)) / 2.,
@@ -429,10 +615,110 @@ impl TopBar {
             ui.label("Brush:");
         });
 
-        for brush in luminol_core::Pencil::iter() {
+        // Only VX Ace maps have shadows to draw
+        let is_ace = update_state
+            .project_config
+            .as_ref()
+            .is_some_and(|config| matches!(config.project.editor_ver, luminol_config::RMVer::Ace));
+        if !is_ace && update_state.toolbar.pencil == luminol_core::Pencil::Shadow {
+            update_state.toolbar.pencil = luminol_core::Pencil::Pen;
+        }
+
+        // Custom shapes are selected through their icons below instead, and smart tiles through
+        // the tilepicker
+        for brush in luminol_core::Pencil::iter().filter(|&brush| {
+            (is_ace || brush != luminol_core::Pencil::Shadow)
+                && !matches!(
+                    brush,
+                    luminol_core::Pencil::Custom | luminol_core::Pencil::Smart
+                )
+        }) {
             ui.selectable_value(&mut update_state.toolbar.pencil, brush, brush.to_string());
         }
 
+        if matches!(
+            update_state.toolbar.pencil,
+            luminol_core::Pencil::Pen | luminol_core::Pencil::Smart
+        ) {
+            ui.add(
+                egui::DragValue::new(&mut update_state.toolbar.brush_size)
+                    .range(1..=luminol_core::ToolbarState::MAX_BRUSH_SIZE)
+                    .prefix("Size: "),
+            )
+            .on_hover_text("How many tiles wide the brush draws");
+            ui.add_enabled(
+                update_state.toolbar.brush_size > 1,
+                egui::Checkbox::new(&mut update_state.toolbar.brush_round, "Round"),
+            )
+            .on_hover_text("Draw a circle instead of a square");
+        }
+
+        if matches!(
+            update_state.toolbar.pencil,
+            luminol_core::Pencil::Rectangle | luminol_core::Pencil::Circle
+        ) {
+            ui.checkbox(&mut update_state.toolbar.shape_hollow, "Hollow")
+                .on_hover_text("Only draw the 1-tile-thick border of the shape");
+        }
+
+        if update_state.toolbar.pencil == luminol_core::Pencil::Fill {
+            ui.checkbox(&mut update_state.toolbar.fill_global, "Global")
+                .on_hover_text("Replace every matching tile on the layer, even if not touching");
+        }
+
+        if !matches!(
+            update_state.toolbar.pencil,
+            luminol_core::Pencil::Shadow | luminol_core::Pencil::Select
+        ) {
+            egui::ComboBox::from_id_source("luminol_symmetry")
+                .selected_text(update_state.toolbar.symmetry.to_string())
+                .show_ui(ui, |ui| {
+                    for symmetry in luminol_core::Symmetry::iter() {
+                        ui.selectable_value(
+                            &mut update_state.toolbar.symmetry,
+                            symmetry,
+                            symmetry.to_string(),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(
+                "Also draw every tile mirrored across the center of the map, or across the axis \
+                placed from the map tab",
+            );
+        }
+
+        let brush_shapes = update_state
+            .project_config
+            .as_ref()
+            .map_or(&[][..], |config| config.project.brush_shapes.as_slice());
+        if update_state.toolbar.pencil == luminol_core::Pencil::Custom
+            && update_state.toolbar.brush_shape >= brush_shapes.len()
+        {
+            update_state.toolbar.pencil = luminol_core::Pencil::Pen;
+        }
+        for (i, shape) in brush_shapes.iter().enumerate() {
+            let selected = update_state.toolbar.pencil == luminol_core::Pencil::Custom
+                && update_state.toolbar.brush_shape == i;
+            let height = ui.spacing().interact_size.y;
+            if luminol_ui::components::brush_shape_icon(ui, shape, selected, height).clicked() {
+                update_state.toolbar.pencil = luminol_core::Pencil::Custom;
+                update_state.toolbar.brush_shape = i;
+            }
+        }
+        if ui
+            .add_enabled(
+                update_state.project_config.is_some(),
+                egui::Button::new("Shapes..."),
+            )
+            .on_hover_text("Draw custom brush shapes for this project")
+            .clicked()
+        {
+            update_state
+                .edit_windows
+                .add_window(luminol_ui::windows::brush_shapes::Window::default());
+        }
+
         ui.add(egui::Slider::new(
             &mut update_state.toolbar.brush_density,
             0.0..=1.0,
@@ -451,16 +737,12 @@ impl TopBar {
             update_state.project_manager.open_project_picker();
         }
 
-        if save_project {
-            if let Some(config) = update_state.project_config {
-                match update_state.data.save(update_state.filesystem, config) {
-                    Ok(_) => {
-                        update_state.modified.set(false);
-                        luminol_core::info!(update_state.toasts, "Saved project successfully!");
-                    }
-                    Err(e) => luminol_core::error!(update_state.toasts, e),
-                }
-            }
+        if save_maps {
+            update_state.save_modified_maps();
+        }
+
+        if save_project || save_all {
+            update_state.save_project(save_all);
         }
 
         if update_state
@@ -18,6 +18,7 @@ use std::{
 };
 
 mod log;
+mod single_instance;
 
 const FILTERS: &[&str] = &[
     "_",
@@ -138,6 +139,9 @@ fn setup_hooks() -> Result<()> {
         let report = panic_hook.panic_report(info).to_string();
         eprintln!("{report}");
 
+        // the project won't be closed cleanly, so other editors would think it's still open
+        luminol_filesystem::project_lock::release_after_panic();
+
         if !crate::RESTART_AFTER_PANIC.load(atomic::Ordering::Relaxed) {
             return;
         }
@@ -180,6 +184,40 @@ fn setup_hooks() -> Result<()> {
     Ok(())
 }
 
+/// Turns a path passed on the command line into the directory of the project it belongs to.
+///
+/// This accepts the project directory itself, as well as its Game.ini or RPG Maker project file,
+/// so Luminol can be used to open either of those from a file manager.
+fn project_path_from_arg(arg: std::ffi::OsString) -> Option<camino::Utf8PathBuf> {
+    let path = std::path::PathBuf::from(arg);
+    // other instances of Luminol may have a different working directory
+    let path = env::current_dir()
+        .map(|dir| dir.join(&path))
+        .unwrap_or(path);
+    let path = match camino::Utf8PathBuf::from_path_buf(path) {
+        Ok(path) => path,
+        Err(path) => {
+            eprintln!("Project path is not valid UTF-8: {path:?}");
+            return None;
+        }
+    };
+
+    let is_project_file = path
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("Game.ini"))
+        || path.extension().is_some_and(|extension| {
+            ["rxproj", "rvproj", "rvproj2"]
+                .iter()
+                .any(|e| extension.eq_ignore_ascii_case(e))
+        });
+
+    if path.is_file() && is_project_file {
+        path.parent().map(ToOwned::to_owned)
+    } else {
+        Some(path)
+    }
+}
+
 fn init_log() -> (sync::Arc<OnceCell<egui::Context>>, sync::mpsc::Receiver<u8>) {
     let (log_byte_tx, log_byte_rx) = sync::mpsc::channel();
     let ctx_cell = sync::Arc::new(OnceCell::new());
@@ -191,6 +229,7 @@ fn run_app(
     report: Option<String>,
     ctx_cell: sync::Arc<OnceCell<egui::Context>>,
     log_byte_rx: sync::mpsc::Receiver<u8>,
+    open_project_rx: sync::mpsc::Receiver<Option<camino::Utf8PathBuf>>,
     #[cfg(feature = "steamworks")] steamworks: crate::steam::Steamworks,
 ) -> Result<()> {
     let icon_image = image::load_from_memory(ICON)?;
@@ -230,7 +269,7 @@ fn run_app(
                 report,
                 Default::default(),
                 log_byte_rx,
-                std::env::args_os().nth(1),
+                open_project_rx,
                 #[cfg(feature = "steamworks")]
                 steamworks,
             )))
@@ -242,6 +281,12 @@ fn run_app(
 }
 
 pub fn run() -> Result<()> {
+    /* Hand the project over to the running instance of Luminol if there is one */
+    let project_path = env::args_os().nth(1).and_then(project_path_from_arg);
+    if single_instance::forward(project_path.as_deref()) {
+        return Ok(());
+    }
+
     /* Load the latest panic report */
     let report = load_panic_report();
 
@@ -262,11 +307,21 @@ pub fn run() -> Result<()> {
     /* Initialise the log system */
     let (ctx_cell, log_byte_rx) = init_log();
 
+    /* Open the project passed on the command line and any forwarded by other instances */
+    let (open_project_tx, open_project_rx) = sync::mpsc::channel();
+    if let Some(path) = project_path {
+        let _ = open_project_tx.send(Some(path));
+    }
+    if let Err(error) = single_instance::listen(open_project_tx, ctx_cell.clone()) {
+        ::log::warn!("Failed to listen for other instances of Luminol: {error:?}");
+    }
+
     /* Show the graphical user interface */
     run_app(
         report,
         ctx_cell,
         log_byte_rx,
+        open_project_rx,
         #[cfg(feature = "steamworks")]
         steamworks,
     )?;
@@ -0,0 +1,299 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Keeps Luminol to a single running instance.
+//!
+//! The first instance listens on a loopback socket and writes its port, along with a random
+//! secret, to a file in the user's data directory that only they can read. Instances started
+//! after that send the secret and the project they were asked to open to that socket and exit,
+//! and the first instance opens the project and brings its window to the front. Connections that
+//! don't come from the same machine or don't know the secret are ignored, so other users can't
+//! make Luminol open projects.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{mpsc::Sender, Arc};
+use std::{fs, io, path::PathBuf, thread, time::Duration};
+
+use once_cell::sync::OnceCell;
+
+/// Sent first by every instance handing over a project, so unrelated programs that happen to be
+/// listening on a stale port aren't mistaken for Luminol.
+const HANDSHAKE: &str = "luminol-open-project";
+const ACKNOWLEDGEMENT: &str = "ok";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where the running instance can be reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Instance {
+    port: u16,
+    /// Proves a connection comes from someone who can read the instance file.
+    secret: String,
+}
+
+impl Instance {
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        let port = lines.next()?.trim().parse().ok()?;
+        let secret = lines.next()?.trim().to_string();
+        (!secret.is_empty()).then_some(Self { port, secret })
+    }
+}
+
+fn instance_file() -> Option<PathBuf> {
+    // The same directory eframe keeps Luminol's settings in
+    luminol_eframe::storage_dir("astrabit.luminol").map(|dir| dir.join("instance"))
+}
+
+/// Writes the instance file so only the current user can read it.
+fn write_instance_file(path: &std::path::Path, instance: &Instance) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, so tighten the permissions of an old one as well
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+
+    writeln!(file, "{}", instance.port)?;
+    writeln!(file, "{}", instance.secret)?;
+    file.flush()
+}
+
+/// Hands `project_path` over to an instance of Luminol that is already running.
+/// `None` just brings the running instance to the front.
+///
+/// Returns `true` if another instance took over, in which case this one should exit.
+pub fn forward(project_path: Option<&camino::Utf8Path>) -> bool {
+    let Some(instance) = instance_file()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| Instance::parse(&contents))
+    else {
+        return false;
+    };
+    send(&instance, project_path).is_ok()
+}
+
+/// Starts accepting projects forwarded by other instances of Luminol on a background thread.
+/// Each one is passed on to `sender`, and the egui context is repainted so it's handled promptly.
+pub fn listen(
+    sender: Sender<Option<camino::Utf8PathBuf>>,
+    ctx_cell: Arc<OnceCell<egui::Context>>,
+) -> io::Result<()> {
+    let path = instance_file().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "could not find the user's data directory",
+        )
+    })?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let instance = Instance {
+        port: listener.local_addr()?.port(),
+        secret: format!("{:032x}", rand::random::<u128>()),
+    };
+    write_instance_file(&path, &instance)?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            // a connection that isn't another instance of Luminol is simply dropped
+            let Ok(project_path) = stream.and_then(|stream| receive(stream, &instance.secret))
+            else {
+                continue;
+            };
+            if sender.send(project_path).is_err() {
+                break;
+            }
+            if let Some(ctx) = ctx_cell.get() {
+                ctx.request_repaint();
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn send(instance: &Instance, project_path: Option<&camino::Utf8Path>) -> io::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, instance.port));
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    writeln!(stream, "{HANDSHAKE}")?;
+    writeln!(stream, "{}", instance.secret)?;
+    writeln!(stream, "{}", project_path.map_or("", |path| path.as_str()))?;
+    stream.flush()?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.trim_end() == ACKNOWLEDGEMENT {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected reply from the running instance",
+        ))
+    }
+}
+
+fn receive(mut stream: TcpStream, secret: &str) -> io::Result<Option<camino::Utf8PathBuf>> {
+    // The listener is only bound to loopback, but don't rely on that alone
+    if !stream.peer_addr()?.ip().is_loopback() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "connection is not from this machine",
+        ));
+    }
+
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.trim_end() != HANDSHAKE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "connection is not from Luminol",
+        ));
+    }
+
+    line.clear();
+    reader.read_line(&mut line)?;
+    if line.trim_end() != secret {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "connection does not know the secret of this instance",
+        ));
+    }
+
+    line.clear();
+    reader.read_line(&mut line)?;
+    let project_path = line.trim_end_matches(['\r', '\n']);
+
+    writeln!(stream, "{ACKNOWLEDGEMENT}")?;
+    Ok((!project_path.is_empty()).then(|| project_path.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sends `project_path` with `secret` to a listener that expects `expected_secret`, and
+    /// returns what each side got.
+    fn hand_off(
+        secret: &str,
+        expected_secret: &str,
+        project_path: Option<&str>,
+    ) -> (io::Result<()>, io::Result<Option<camino::Utf8PathBuf>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let instance = Instance {
+            port: listener.local_addr().unwrap().port(),
+            secret: secret.to_string(),
+        };
+
+        let expected_secret = expected_secret.to_string();
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept()?;
+            receive(stream, &expected_secret)
+        });
+        let sent = send(&instance, project_path.map(camino::Utf8Path::new));
+        (sent, receiver.join().unwrap())
+    }
+
+    #[test]
+    fn project_is_handed_over() {
+        let path = "/home/user/My Game #2/Game.ini";
+        let (sent, received) = hand_off("secret", "secret", Some(path));
+        sent.unwrap();
+        assert_eq!(
+            received.unwrap().as_deref(),
+            Some(camino::Utf8Path::new(path))
+        );
+    }
+
+    #[test]
+    fn no_project_just_focuses() {
+        let (sent, received) = hand_off("secret", "secret", None);
+        sent.unwrap();
+        assert_eq!(received.unwrap(), None);
+    }
+
+    #[test]
+    fn wrong_secret_is_refused() {
+        let (sent, received) = hand_off("guess", "secret", Some("/tmp/evil"));
+        assert!(sent.is_err());
+        assert_eq!(
+            received.unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn other_programs_are_refused() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept()?;
+            receive(stream, "secret")
+        });
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        writeln!(stream, "GET / HTTP/1.1").unwrap();
+        assert_eq!(
+            receiver.join().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn instance_file_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("instance");
+        let instance = Instance {
+            port: 40123,
+            secret: "0123456789abcdef".to_string(),
+        };
+        write_instance_file(&path, &instance).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(Instance::parse(&contents), Some(instance));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn malformed_instance_files_are_ignored() {
+        assert_eq!(Instance::parse(""), None);
+        assert_eq!(Instance::parse("40123\n"), None);
+        assert_eq!(Instance::parse("not a port\nsecret\n"), None);
+    }
+}
@@ -69,6 +69,8 @@ mod steam;
 /// Only then can Luminol show the main window.
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    register_extensions();
+
     if let Err(why) = entrypoint::run() {
         entrypoint::handle_fatal_error(why);
     }
@@ -97,5 +99,15 @@ pub fn luminol_main_start() {
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub async fn luminol_worker_start(canvas: web_sys::OffscreenCanvas) {
+    // The app runs in this worker, so this is where the extensions need to be registered
+    register_extensions();
+
     entrypoint::worker_start(canvas).await;
 }
+
+/// Registers the extensions this build of Luminol was compiled with.
+/// Forks that add their own extensions should register them here.
+fn register_extensions() {
+    #[cfg(feature = "example-extension")]
+    luminol_core::register_extension(luminol_extension_example::NotesExtension);
+}
@@ -58,11 +58,7 @@ impl Channel {
             config.initial_size.0 as usize,
             config.initial_size.1 as usize,
         );
-        let term = Term::new(
-            alacritty_terminal::term::Config::default(),
-            &term_size,
-            event_proxy,
-        );
+        let term = Term::new(super::term_config(config), &term_size, event_proxy);
 
         Self {
             processor,
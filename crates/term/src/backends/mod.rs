@@ -31,6 +31,16 @@ use std::sync::mpsc::Receiver;
 pub use channel::Channel;
 pub use process::Process;
 
+/// Build the alacritty terminal options from Luminol's terminal config.
+pub(crate) fn term_config(
+    config: &luminol_config::terminal::Config,
+) -> alacritty_terminal::term::Config {
+    alacritty_terminal::term::Config {
+        scrolling_history: config.scrollback,
+        ..Default::default()
+    }
+}
+
 pub trait Backend {
     type EventListener: alacritty_terminal::event::EventListener;
 
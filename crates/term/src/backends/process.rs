@@ -81,11 +81,7 @@ impl Process {
             config.initial_size.0 as usize,
             config.initial_size.1 as usize,
         );
-        let term = Term::new(
-            alacritty_terminal::term::Config::default(),
-            &term_size,
-            event_proxy.clone(),
-        );
+        let term = Term::new(super::term_config(config), &term_size, event_proxy.clone());
         let term = Arc::new(FairMutex::new(term));
 
         let event_loop = alacritty_terminal::event_loop::EventLoop::new(
@@ -30,7 +30,7 @@ use alacritty_terminal::term::{LineDamageBounds, TermDamage, TermMode};
 use alacritty_terminal::vte::ansi::CursorShape;
 use alacritty_terminal::Grid;
 use egui::epaint::text::cursor::RCursor;
-use luminol_config::terminal::CursorBlinking;
+use luminol_config::terminal::{CursorBlinking, CursorShape as CursorShapeOverride};
 
 use crate::backends::Backend;
 
@@ -43,6 +43,10 @@ pub struct Terminal<T> {
 
     layout_job: egui::text::LayoutJob,
     ime_text: Option<String>,
+    /// Multi-line text waiting for the user to confirm pasting it.
+    pending_paste: Option<String>,
+    /// The config the terminal was last laid out with, used to pick up changes from the preferences.
+    applied_config: Option<luminol_config::terminal::Config>,
 
     pub id: egui::Id,
     pub title: String,
@@ -68,6 +72,8 @@ impl<T> Terminal<T> {
 
             layout_job: egui::text::LayoutJob::default(),
             ime_text: None,
+            pending_paste: None,
+            applied_config: None,
 
             title: "Luminol Terminal".to_string(),
         }
@@ -157,12 +163,14 @@ where
         self.backend.update()
     }
 
+    /// Updates the damaged cells in `job`. Returns `false` if the damage doesn't line up with the
+    /// job, in which case it has to be laid out again from scratch.
     fn layout_job_damage(
         job: &mut egui::text::LayoutJob,
         config: &luminol_config::terminal::Config,
         grid: &Grid<Cell>,
         damage: impl IntoIterator<Item = LineDamageBounds>,
-    ) {
+    ) -> bool {
         for line in damage {
             for column in line.left..=line.right {
                 let point = Point::new(Line(line.line as i32), Column(column));
@@ -171,7 +179,9 @@ where
                 // we have to offset the index by the line to account for additional newlines
                 let index = line.line * grid.columns() + column + line.line; // index is an index into a section
 
-                let section = &mut job.sections[index];
+                let Some(section) = job.sections.get_mut(index) else {
+                    return false;
+                };
 
                 let mut buf = [0; 4];
                 let text = cell.c.encode_utf8(&mut buf);
@@ -194,6 +204,7 @@ where
                 }
             }
         }
+        true
     }
 
     fn layout_job_full(
@@ -231,9 +242,7 @@ where
         }
 
         let italics = cell.flags.contains(Flags::ITALIC);
-        let underline = cell
-            .flags
-            .contains(Flags::UNDERLINE)
+        let underline = (cell.flags.contains(Flags::UNDERLINE) || cell.hyperlink().is_some())
             .then_some(egui::Stroke::new(1.0, color))
             .unwrap_or_default();
         let strikethrough = cell
@@ -283,25 +292,35 @@ where
             }
         });
 
+        self.show_paste_confirmation(ui.ctx());
+
         let config = &update_state.global_config.terminal;
         let font_id = config.font.clone();
 
+        if self.applied_config.as_ref() != Some(config) {
+            self.backend
+                .with_term(|term| term.set_options(crate::backends::term_config(config)));
+            self.layout_job = Default::default();
+            self.applied_config = Some(config.clone());
+        }
+
         let (screen_columns, screen_lines, total_lines, display_offset, cursor_style, cursor_point) =
             self.backend.with_term(|term| {
-                match term.damage() {
+                let repaired = match term.damage() {
                     // we only do partial repaints if the layout job is empty
                     TermDamage::Partial(damage) if !self.layout_job.is_empty() => {
                         // We have to collect here to avoid borrowing the terminal mutably twice (even though it isn't, really)
                         let damage = damage.collect::<Vec<_>>();
                         Self::layout_job_damage(&mut self.layout_job, config, term.grid(), damage)
                     }
-                    _ => {
-                        self.layout_job = Self::layout_job_full(
-                            term.columns(),
-                            config,
-                            term.renderable_content().display_iter,
-                        );
-                    }
+                    _ => false,
+                };
+                if !repaired {
+                    self.layout_job = Self::layout_job_full(
+                        term.columns(),
+                        config,
+                        term.renderable_content().display_iter,
+                    );
                 }
                 term.reset_damage();
 
@@ -374,11 +393,39 @@ where
             ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
         }
 
+        let hovered_hyperlink = response.hover_pos().and_then(|pos| {
+            let rcursor = galley.cursor_from_pos(pos - response.rect.min).rcursor;
+            self.hyperlink_at(rcursor, display_offset)
+        });
+        let response = if let Some(uri) = hovered_hyperlink {
+            // Links come from whatever is running in the terminal, so only web pages are opened
+            if is_web_link(&uri) {
+                let ctrl = ui.input(|i| i.modifiers.ctrl);
+                if ctrl {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                }
+                if ctrl && response.clicked() {
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(&uri));
+                }
+                response.on_hover_text(format!("{uri}\nCtrl+click to open"))
+            } else {
+                response.on_hover_text(uri)
+            }
+        } else {
+            response
+        };
+
         if response.clicked() && !response.lost_focus() {
             response.request_focus();
         }
 
-        let mut cursor_shape = cursor_style.shape;
+        let mut cursor_shape = match config.cursor_shape {
+            _ if cursor_style.shape == CursorShape::Hidden => CursorShape::Hidden,
+            CursorShapeOverride::Terminal => cursor_style.shape,
+            CursorShapeOverride::Block => CursorShape::Block,
+            CursorShapeOverride::Underline => CursorShape::Underline,
+            CursorShapeOverride::Beam => CursorShape::Beam,
+        };
         if !response.has_focus() {
             cursor_shape = CursorShape::HollowBlock;
             self.ime_text = None;
@@ -498,6 +545,19 @@ where
         Ok(())
     }
 
+    /// The target of the OSC 8 hyperlink at `rcursor` in the galley, if there is one.
+    fn hyperlink_at(&mut self, rcursor: RCursor, display_offset: usize) -> Option<String> {
+        self.backend.with_term(|term| {
+            if rcursor.column >= term.columns() || rcursor.row >= term.screen_lines() {
+                return None;
+            }
+            let line = Line(rcursor.row as i32 - display_offset as i32);
+            term.grid()[Point::new(line, Column(rcursor.column))]
+                .hyperlink()
+                .map(|hyperlink| hyperlink.uri().to_string())
+        })
+    }
+
     fn handle_scroll(
         &mut self,
         cursor_pos: Option<RCursor>,
@@ -609,7 +669,7 @@ where
                     }
 
                     if modifiers.shift {
-                        self.backend.send(text.into_bytes());
+                        self.paste(&text, term_mode);
                     }
                     term_modified = true;
                 }
@@ -656,6 +716,74 @@ where
         }
     }
 
+    /// Sends pasted text to the terminal.
+    ///
+    /// If the terminal doesn't support bracketed paste, text containing newlines would run each
+    /// line as soon as it arrives, so it's held back until the user confirms it instead.
+    fn paste(&mut self, text: &str, term_mode: TermMode) {
+        // terminals expect a carriage return when enter is pressed
+        let text = text.replace("\r\n", "\r").replace('\n', "\r");
+
+        if term_mode.contains(TermMode::BRACKETED_PASTE) {
+            // an escape in the pasted text could end the bracketed paste early
+            let text = text.replace('\x1b', "");
+            self.backend
+                .send(format!("\x1b[200~{text}\x1b[201~").into_bytes());
+        } else if text.contains('\r') {
+            self.pending_paste = Some(text);
+        } else {
+            self.backend.send(text.into_bytes());
+        }
+    }
+
+    fn show_paste_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(text) = &self.pending_paste else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Paste multiple lines?")
+            .id(self.id.with("paste_confirmation"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let line_breaks = text.matches('\r').count();
+                ui.label(format!(
+                    "The pasted text contains {line_breaks} line break(s). \
+                    Each line will be run as soon as it is pasted."
+                ));
+                egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                    egui::ScrollArea::both().max_height(200.).show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(text.replace('\r', "\n")).monospace(),
+                            )
+                            .extend(),
+                        );
+                    });
+                });
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Paste").clicked();
+                    cancelled = ui.button("Cancel").clicked()
+                        || ui.input(|i| i.key_pressed(egui::Key::Escape));
+                });
+            });
+
+        if confirmed {
+            if let Some(text) = self.pending_paste.take() {
+                self.backend.send(text.into_bytes());
+                self.backend.with_term(|term| {
+                    term.scroll_display(alacritty_terminal::grid::Scroll::Bottom);
+                });
+                self.layout_job = Default::default();
+            }
+        } else if cancelled {
+            self.pending_paste = None;
+        }
+    }
+
     pub fn kill(&mut self) {
         self.backend.kill();
     }
@@ -665,3 +793,120 @@ fn invert_color(color: egui::Color32) -> egui::Color32 {
     let [r, g, b, a] = color.to_array();
     egui::Color32::from_rgba_premultiplied(!r, !g, !b, a)
 }
+
+/// Whether an OSC 8 hyperlink points to a web page, which is all Ctrl+click will open.
+fn is_web_link(uri: &str) -> bool {
+    uri.split_once("://").is_some_and(|(scheme, rest)| {
+        !rest.is_empty()
+            && (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel_terminal(bytes: &[u8]) -> (ChannelTerminal, luminol_config::terminal::Config) {
+        let config = luminol_config::terminal::Config::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for &byte in bytes {
+            sender.send(byte).unwrap();
+        }
+        let mut terminal = ChannelTerminal::channel(receiver, &config);
+        terminal.backend.update();
+        (terminal, config)
+    }
+
+    /// The text of each line of the terminal, as it would be laid out.
+    fn lines(
+        terminal: &mut ChannelTerminal,
+        config: &luminol_config::terminal::Config,
+    ) -> Vec<String> {
+        let job = terminal.backend.with_term(|term| {
+            ChannelTerminal::layout_job_full(
+                term.columns(),
+                config,
+                term.renderable_content().display_iter,
+            )
+        });
+        job.text
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn sixel_data_is_swallowed() {
+        let sixel =
+            b"\x1bPq\"1;1;8;6#0;2;0;0;0#1;2;100;100;0#1~~@@vv@@~~@@~~$#0??}}GG}}??}}??-\x1b\\";
+        let mut bytes = b"before".to_vec();
+        bytes.extend_from_slice(sixel);
+        bytes.extend_from_slice(b"after\r\nnext line");
+
+        let (mut terminal, config) = channel_terminal(&bytes);
+        let lines = lines(&mut terminal, &config);
+
+        assert_eq!(lines.len(), config.initial_size.1 as usize);
+        assert_eq!(lines[0], "beforeafter");
+        assert_eq!(lines[1], "next line");
+        assert!(lines[2..].iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn unknown_osc_sequences_are_swallowed() {
+        let (mut terminal, config) =
+            channel_terminal(b"a\x1b]1337;File=inline=1:AAAA\x07b\x1b]9999;junk\x1b\\c");
+        assert_eq!(lines(&mut terminal, &config)[0], "abc");
+    }
+
+    #[test]
+    fn hyperlinks_are_read_from_cells() {
+        let (mut terminal, _config) = channel_terminal(
+            b"see \x1b]8;;https://example.com/docs\x1b\\the docs\x1b]8;;\x1b\\ here",
+        );
+        let at = |column| RCursor { row: 0, column };
+
+        assert_eq!(terminal.hyperlink_at(at(0), 0), None);
+        assert_eq!(
+            terminal.hyperlink_at(at(4), 0).as_deref(),
+            Some("https://example.com/docs")
+        );
+        assert_eq!(
+            terminal.hyperlink_at(at(11), 0).as_deref(),
+            Some("https://example.com/docs")
+        );
+        assert_eq!(terminal.hyperlink_at(at(12), 0), None);
+    }
+
+    #[test]
+    fn multi_line_pastes_wait_for_confirmation() {
+        let (mut terminal, _config) = channel_terminal(b"");
+
+        terminal.paste("echo hi", TermMode::NONE);
+        assert_eq!(terminal.pending_paste, None);
+
+        terminal.paste("echo hi\r\necho there\n", TermMode::BRACKETED_PASTE);
+        assert_eq!(terminal.pending_paste, None);
+
+        terminal.paste("echo hi\r\necho there\n", TermMode::NONE);
+        assert_eq!(
+            terminal.pending_paste.as_deref(),
+            Some("echo hi\recho there\r")
+        );
+    }
+
+    #[test]
+    fn only_web_links_are_opened() {
+        assert!(is_web_link("https://example.com"));
+        assert!(is_web_link("http://localhost:8080/index.html"));
+        assert!(is_web_link("HTTPS://EXAMPLE.COM"));
+
+        assert!(!is_web_link("file:///etc/passwd"));
+        assert!(!is_web_link("javascript:alert(1)"));
+        assert!(!is_web_link("ssh://example.com"));
+        assert!(!is_web_link("smb://server/share"));
+        assert!(!is_web_link("https://"));
+        assert!(!is_web_link("example.com"));
+        assert!(!is_web_link(""));
+    }
+}
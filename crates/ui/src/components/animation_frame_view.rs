@@ -150,6 +150,11 @@ impl AnimationFrameView {
             glam::Vec2::splat(scale),
         );
 
+        ui.painter().add(super::preview_background(
+            update_state.global_config.preview_background,
+            canvas_rect,
+            canvas_rect,
+        ));
         let painter = luminol_graphics::Painter::new(self.frame.prepare(&update_state.graphics));
         ui.painter()
             .add(luminol_egui_wgpu::Callback::new_paint_callback(
@@ -0,0 +1,271 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_config::global::PreviewBackground;
+use luminol_filesystem::FileSystem;
+use std::sync::Arc;
+
+use super::preview_background;
+
+/// The largest size a thumbnail is drawn at.
+const THUMBNAIL_SIZE: egui::Vec2 = egui::vec2(128., 128.);
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp"];
+const AUDIO_EXTENSIONS: &[&str] = &["ogg", "wav", "mid", "midi", "mp3", "flac"];
+
+/// Shows a preview of an asset file: a thumbnail for images, or a play button and duration for
+/// audio.
+///
+/// Assets are only loaded the first time they are previewed, after which they are cached for as
+/// long as this struct is alive.
+#[derive(Default)]
+pub struct AssetPreview {
+    cache: std::collections::HashMap<camino::Utf8PathBuf, Cached>,
+}
+
+/// How a previewed sound should be played.
+#[derive(Clone, Copy)]
+pub struct Playback {
+    pub source: luminol_audio::Source,
+    pub volume: u8,
+    pub pitch: u8,
+    /// How fast MIDI files play, as a multiple of their normal tempo
+    pub midi_speed: f64,
+    /// Where looping MIDI files jump back to, in samples, instead of their loop marker
+    pub midi_loop_start: Option<u64>,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            source: luminol_audio::Source::SE,
+            volume: 100,
+            pitch: 100,
+            midi_speed: 1.,
+            midi_loop_start: None,
+        }
+    }
+}
+
+enum Cached {
+    /// The file could not be found, or isn't something we know how to preview.
+    None,
+    Image(Result<Arc<luminol_graphics::Texture>, String>),
+    Audio {
+        path: camino::Utf8PathBuf,
+        duration: Duration,
+    },
+}
+
+enum Duration {
+    Loading(poll_promise::Promise<color_eyre::Result<std::time::Duration>>),
+    Loaded(std::time::Duration),
+    Unknown,
+}
+
+impl AssetPreview {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Display a preview of the asset at `path`, which is relative to the project root and may
+    /// leave out the file extension.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        path: &camino::Utf8Path,
+    ) -> egui::Response {
+        self.ui_with_playback(ui, update_state, path, Playback::default())
+    }
+
+    /// Like [`Self::ui`], but plays audio with the given settings.
+    pub fn ui_with_playback(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        path: &camino::Utf8Path,
+        playback: Playback,
+    ) -> egui::Response {
+        match self.get(update_state, path) {
+            Cached::Audio { path, duration } => {
+                ui.horizontal(|ui| {
+                    if ui.button("▶").on_hover_text("Play").clicked() {
+                        Self::play(update_state, path, playback);
+                    }
+                    if ui.button("⏹").on_hover_text("Stop").clicked() {
+                        update_state.audio.stop(playback.source);
+                    }
+                    duration.ui(ui);
+                })
+                .response
+            }
+            cached => cached.ui(ui, update_state.global_config.preview_background),
+        }
+    }
+
+    /// Attaches a preview of the asset at `path` to a response, shown while it is hovered.
+    pub fn on_hover(
+        &mut self,
+        response: egui::Response,
+        update_state: &luminol_core::UpdateState<'_>,
+        path: &camino::Utf8Path,
+    ) -> egui::Response {
+        response.on_hover_ui(|ui| {
+            self.get(update_state, path)
+                .ui(ui, update_state.global_config.preview_background);
+        })
+    }
+
+    /// Play the audio file at `path` with the given settings.
+    pub fn play(
+        update_state: &mut luminol_core::UpdateState<'_>,
+        path: impl AsRef<camino::Utf8Path>,
+        playback: Playback,
+    ) {
+        if let Err(e) = update_state.audio.play(
+            path,
+            update_state.filesystem,
+            playback.volume,
+            playback.pitch,
+            Some(playback.source),
+            update_state
+                .project_config
+                .as_ref()
+                .expect("project not loaded")
+                .project
+                .volume_scale,
+        ) {
+            luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err("Error playing from audio file")
+            );
+            return;
+        }
+
+        // These only do anything if the file turned out to be a MIDI file
+        update_state
+            .audio
+            .set_midi_speed(playback.midi_speed, playback.source);
+        update_state
+            .audio
+            .set_midi_loop_start(playback.midi_loop_start, playback.source);
+    }
+
+    fn get(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        path: &camino::Utf8Path,
+    ) -> &mut Cached {
+        let cached = self
+            .cache
+            .entry(path.to_owned())
+            .or_insert_with(|| Cached::load(update_state, path));
+        if let Cached::Audio { duration, .. } = cached {
+            duration.poll();
+        }
+        cached
+    }
+}
+
+impl Cached {
+    fn load(update_state: &luminol_core::UpdateState<'_>, path: &camino::Utf8Path) -> Self {
+        let Ok(path) = update_state.filesystem.desensitize(path) else {
+            return Self::None;
+        };
+        let extension = path.extension().unwrap_or_default().to_lowercase();
+
+        if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            let texture = update_state
+                .graphics
+                .texture_loader
+                .load_now(update_state.filesystem, &path)
+                .map_err(|e| e.to_string());
+            Self::Image(texture)
+        } else if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+            // Only the headers are read, and that happens off the UI thread on native
+            let duration = match update_state
+                .filesystem
+                .open_file(&path, luminol_filesystem::OpenFlags::Read)
+            {
+                Ok(file) => Duration::Loading(luminol_core::spawn_future(async move {
+                    luminol_audio::duration(file)
+                })),
+                Err(_) => Duration::Unknown,
+            };
+            Self::Audio { path, duration }
+        } else {
+            Self::None
+        }
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, background: PreviewBackground) -> egui::Response {
+        match self {
+            Self::None => ui.weak("No preview available"),
+            Self::Image(Err(e)) => ui.colored_label(ui.visuals().error_fg_color, e),
+            Self::Image(Ok(texture)) => {
+                ui.vertical(|ui| {
+                    // The size of the image is only known after adding it, so reserve a spot
+                    // behind it for the background
+                    let background_idx = ui.painter().add(egui::Shape::Noop);
+                    let response = ui.add(
+                        egui::Image::new(egui::load::SizedTexture::from(&**texture))
+                            .max_size(THUMBNAIL_SIZE),
+                    );
+                    ui.painter().set(
+                        background_idx,
+                        preview_background(background, response.rect, ui.clip_rect()),
+                    );
+                    ui.weak(format!("{} × {}", texture.width(), texture.height()));
+                })
+                .response
+            }
+            Self::Audio { duration, .. } => duration.ui(ui),
+        }
+    }
+}
+
+impl Duration {
+    fn poll(&mut self) {
+        if let Self::Loading(promise) = self {
+            if let Some(result) = promise.ready() {
+                *self = match result {
+                    Ok(duration) => Self::Loaded(*duration),
+                    Err(_) => Self::Unknown,
+                };
+            }
+        }
+    }
+
+    fn ui(&self, ui: &mut egui::Ui) -> egui::Response {
+        match self {
+            Self::Loading(_) => ui.spinner(),
+            Self::Loaded(duration) => {
+                let seconds = duration.as_secs();
+                ui.label(format!("{}:{:02}", seconds / 60, seconds % 60))
+            }
+            Self::Unknown => ui.weak("--:--"),
+        }
+    }
+}
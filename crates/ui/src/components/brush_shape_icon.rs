@@ -0,0 +1,66 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_config::project::BrushShape;
+
+/// Draws a small preview of a brush shape's mask that can be clicked to select the shape.
+/// `height` is the size of the square icon.
+pub fn brush_shape_icon(
+    ui: &mut egui::Ui,
+    shape: &BrushShape,
+    selected: bool,
+    height: f32,
+) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(height, height), egui::Sense::click());
+
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact_selectable(&response, selected);
+        ui.painter().rect(
+            rect,
+            visuals.rounding,
+            visuals.weak_bg_fill,
+            visuals.bg_stroke,
+        );
+
+        // Every shape is drawn at the scale of the largest mask so they can be compared at a glance
+        let inner = rect.shrink(3.);
+        let cell_size = inner.width() / BrushShape::MAX_SIZE as f32;
+        let origin = inner.center() - egui::Vec2::splat(cell_size * shape.size as f32 / 2.);
+        for (y, x) in (0..shape.size).cartesian_product(0..shape.size) {
+            if shape.get(x, y) {
+                ui.painter().rect_filled(
+                    egui::Rect::from_min_size(
+                        origin + egui::vec2(x as f32, y as f32) * cell_size,
+                        egui::Vec2::splat(cell_size),
+                    ),
+                    0.,
+                    visuals.fg_stroke.color,
+                );
+            }
+        }
+    }
+
+    response.on_hover_text(&shape.name)
+}
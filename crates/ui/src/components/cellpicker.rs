@@ -101,6 +101,11 @@ impl Cellpicker {
             glam::Vec2::splat(self.scale),
         );
 
+        ui.painter().add(super::preview_background(
+            update_state.global_config.preview_background,
+            canvas_rect,
+            absolute_scroll_rect,
+        ));
         let painter = luminol_graphics::Painter::new(self.view.prepare(&update_state.graphics));
         ui.painter()
             .add(luminol_egui_wgpu::Callback::new_paint_callback(
@@ -0,0 +1,220 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use super::inventory::id_combo_box;
+use super::new_command;
+
+const BATTLE_PROCESSING: u16 = 301;
+const IF_WIN: u16 = 601;
+const IF_ESCAPE: u16 = 602;
+const IF_LOSE: u16 = 603;
+const BRANCH_END: u16 = 604;
+
+// Parameter indices
+const TROOP: usize = 0;
+const CAN_ESCAPE: usize = 1;
+const CAN_LOSE: usize = 2;
+
+/// Edits the parameters of the Battle Processing command, and keeps the win, escape and lose
+/// branches after it in sync with whether the battle can be escaped or lost.
+pub struct BattleCommandEditor {
+    id_source: egui::Id,
+}
+
+impl BattleCommandEditor {
+    pub const CODE: u16 = BATTLE_PROCESSING;
+
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            id_source: id_source.into(),
+        }
+    }
+
+    /// Returns the parameters RPG Maker XP gives a newly inserted Battle Processing command.
+    pub fn default_parameters() -> Vec<ParameterType> {
+        vec![
+            ParameterType::Integer(1),
+            ParameterType::Bool(false),
+            ParameterType::Bool(false),
+        ]
+    }
+
+    /// Display the parameters of the Battle Processing command at `index`.
+    ///
+    /// RPG Maker XP follows the command with an "If Win" branch whenever the battle can be escaped
+    /// or lost, and an "If Escape" or "If Lose" branch for each of those, so changing either
+    /// option inserts or removes commands after `index`.
+    ///
+    /// Returns whether any command was changed.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+        index: usize,
+    ) -> bool {
+        if commands[index].code != BATTLE_PROCESSING {
+            return false;
+        }
+
+        let mut modified = false;
+        let defaults = Self::default_parameters();
+        if commands[index].parameters.len() < defaults.len() {
+            let parameters = &mut commands[index].parameters;
+            parameters.extend(defaults.into_iter().skip(parameters.len()));
+            modified = true;
+        }
+        let parameters = &mut commands[index].parameters;
+
+        let mut can_escape = parameters[CAN_ESCAPE].truthy();
+        let mut can_lose = parameters[CAN_LOSE].truthy();
+        let mut branches_changed = false;
+
+        egui::Grid::new(self.id_source.with("grid"))
+            .num_columns(2)
+            .spacing([8., 4.])
+            .show(ui, |ui| {
+                ui.label("Troop");
+                let troops = update_state.data.troops();
+                modified |= id_combo_box(
+                    ui,
+                    update_state,
+                    self.id_source.with("troop"),
+                    &mut parameters[TROOP],
+                    troops.data.len(),
+                    |id| format!("{:0>3}: {}", id + 1, troops.data[id].name),
+                    None,
+                );
+                drop(troops);
+                ui.end_row();
+
+                ui.label("");
+                branches_changed |= ui.checkbox(&mut can_escape, "Can Escape").changed();
+                ui.end_row();
+
+                ui.label("");
+                branches_changed |= ui
+                    .checkbox(&mut can_lose, "Continue Even When Loser")
+                    .changed();
+                ui.end_row();
+            });
+
+        if branches_changed {
+            parameters[CAN_ESCAPE] = ParameterType::Bool(can_escape);
+            parameters[CAN_LOSE] = ParameterType::Bool(can_lose);
+            update_branches(commands, index, can_escape, can_lose);
+            modified = true;
+        }
+
+        modified
+    }
+}
+
+/// Replaces the branches after the Battle Processing command at `index` with the ones its
+/// options call for, keeping the contents of any branch that is still needed.
+fn update_branches(
+    commands: &mut Vec<EventCommand>,
+    index: usize,
+    can_escape: bool,
+    can_lose: bool,
+) {
+    let indent = commands[index].indent;
+    let [win, escape, lose] = take_branches(commands, index);
+    let body_or_empty = |body: Option<Vec<EventCommand>>| {
+        body.unwrap_or_else(|| vec![new_command(0, indent + 1, vec![])])
+    };
+
+    let replacement = if can_escape || can_lose {
+        let mut replacement = vec![new_command(IF_WIN, indent, vec![])];
+        replacement.extend(body_or_empty(win));
+        if can_escape {
+            replacement.push(new_command(IF_ESCAPE, indent, vec![]));
+            replacement.extend(body_or_empty(escape));
+        }
+        if can_lose {
+            replacement.push(new_command(IF_LOSE, indent, vec![]));
+            replacement.extend(body_or_empty(lose));
+        }
+        replacement.push(new_command(BRANCH_END, indent, vec![]));
+        replacement
+    } else {
+        // Without any branches the event only carries on after the battle if it was won, so the
+        // contents of the win branch can simply be moved out of it
+        let mut body = win.unwrap_or_default();
+        if body
+            .last()
+            .is_some_and(|c| c.code == 0 && c.indent == indent + 1)
+        {
+            body.pop();
+        }
+        for command in &mut body {
+            command.indent = command.indent.saturating_sub(1);
+        }
+        body
+    };
+
+    commands.splice(index + 1..index + 1, replacement);
+}
+
+/// Removes the branches after the Battle Processing command at `index`, if there are any, and
+/// returns the contents of the win, escape and lose branches in that order.
+fn take_branches(commands: &mut Vec<EventCommand>, index: usize) -> [Option<Vec<EventCommand>>; 3] {
+    let mut bodies = [None, None, None];
+
+    let indent = commands[index].indent;
+    if !commands
+        .get(index + 1)
+        .is_some_and(|c| c.code == IF_WIN && c.indent == indent)
+    {
+        return bodies;
+    }
+    let Some(end) = commands[index + 1..]
+        .iter()
+        .position(|c| c.code == BRANCH_END && c.indent == indent)
+    else {
+        return bodies;
+    };
+
+    let mut current = None;
+    for command in commands.drain(index + 1..=index + 1 + end) {
+        if command.indent == indent {
+            current = match command.code {
+                IF_WIN => Some(0),
+                IF_ESCAPE => Some(1),
+                IF_LOSE => Some(2),
+                _ => None,
+            };
+            if let Some(branch) = current {
+                bodies[branch] = Some(vec![]);
+            }
+        } else if let Some(branch) = current {
+            bodies[branch].get_or_insert_with(Vec::new).push(command);
+        }
+    }
+
+    bodies
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::commands::CommandDescription;
+use luminol_data::rpg::EventCommand;
+
+use super::{
+    BattleCommandEditor, InventoryCommandEditor, LabelCommandEditor, ParameterForm,
+    PictureCommandEditor, ShopCommandEditor,
+};
+
+/// The editors a command can be opened in. Commands without an editor of their own are edited
+/// with a form laid out by their description in the command database.
+pub(super) struct Editors {
+    battle: BattleCommandEditor,
+    form: ParameterForm,
+    inventory: InventoryCommandEditor,
+    label: LabelCommandEditor,
+    picture: PictureCommandEditor,
+    shop: ShopCommandEditor,
+}
+
+impl Editors {
+    pub(super) fn new(id: egui::Id) -> Self {
+        Self {
+            battle: BattleCommandEditor::new(id.with("battle")),
+            form: ParameterForm::new(id.with("form")),
+            inventory: InventoryCommandEditor::new(id.with("inventory")),
+            label: LabelCommandEditor::new(id.with("label")),
+            picture: PictureCommandEditor::new(id.with("picture")),
+            shop: ShopCommandEditor::new(id.with("shop")),
+        }
+    }
+
+    /// Display the editor for the command at `index`.
+    ///
+    /// Returns whether any command was changed.
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        description: Option<&CommandDescription>,
+        commands: &mut Vec<EventCommand>,
+        index: usize,
+    ) -> bool {
+        let code = commands[index].code;
+        if code == BattleCommandEditor::CODE {
+            self.battle.ui(ui, update_state, commands, index)
+        } else if code == ShopCommandEditor::CODE {
+            self.shop.ui(ui, update_state, commands, index)
+        } else if InventoryCommandEditor::CODES.contains(&code) {
+            self.inventory.ui(ui, update_state, &mut commands[index])
+        } else if LabelCommandEditor::CODES.contains(&code) {
+            self.label.ui(ui, commands, index)
+        } else if PictureCommandEditor::CODES.contains(&code) {
+            self.picture.ui(ui, update_state, commands, index)
+        } else if let Some(description) = description {
+            self.form
+                .ui(ui, update_state, description, &mut commands[index])
+        } else {
+            ui.weak("This command isn't in the command database, so it can't be edited");
+            false
+        }
+    }
+}
+
+impl super::CommandView {
+    /// Opens the command with this guid for editing, closing any other.
+    pub fn edit(&mut self, guid: u16) {
+        self.editing = Some(guid);
+    }
+
+    /// Returns whether a command is open in the editor window.
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Shows a window with the parameters of the command opened for editing, if there is one.
+    /// Double clicking a command in the list opens it.
+    ///
+    /// Returns whether any command was changed.
+    pub fn editor_window(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let Some(guid) = self.editing else {
+            return false;
+        };
+        // The command may have been removed since it was opened
+        let Some(index) = commands.iter().position(|c| c.guid == guid) else {
+            self.editing = None;
+            return false;
+        };
+
+        let code = commands[index].code;
+        let description = update_state
+            .project_config
+            .as_ref()
+            .and_then(|config| config.command_db.get(code))
+            .cloned();
+        let title = description
+            .as_ref()
+            .map_or_else(|| format!("Command {code}"), |d| d.name.clone());
+
+        let mut open = true;
+        let mut modified = false;
+        egui::Window::new(title)
+            .id(self.id.with("editor"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                modified = self
+                    .editors
+                    .ui(ui, update_state, description.as_ref(), commands, index);
+            });
+        if !open {
+            self.editing = None;
+        }
+
+        modified
+    }
+}
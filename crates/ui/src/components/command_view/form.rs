@@ -0,0 +1,444 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+
+use egui::Widget;
+use luminol_core::Modal;
+use luminol_data::commands::{CommandDescription, Parameter, ParameterKind};
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use crate::components::ToneEdit;
+use crate::modals::database_modal::{SwitchModal, VariableModal};
+
+pub(super) const SELF_SWITCHES: [&str; 4] = ["A", "B", "C", "D"];
+const DEFAULT_FRAMES: i32 = 20;
+
+/// Edits the parameters of a command by following its description in the command database, so
+/// commands made of simple values don't each need an editor of their own.
+pub struct ParameterForm {
+    id_source: egui::Id,
+
+    /// Modals for switch and variable parameters, by parameter index.
+    switch_modals: HashMap<usize, SwitchModal>,
+    variable_modals: HashMap<usize, VariableModal>,
+}
+
+impl ParameterForm {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            id_source: id_source.into(),
+            switch_modals: HashMap::new(),
+            variable_modals: HashMap::new(),
+        }
+    }
+
+    /// Returns the parameters of a newly inserted command with this description.
+    pub fn default_parameters(description: &CommandDescription) -> Vec<ParameterType> {
+        let mut values = vec![];
+        for parameter in description.parameters() {
+            fill_defaults(parameter, &mut values, &mut 0, false);
+        }
+        values
+    }
+
+    /// Display the parameters of `command` as laid out by `description`. Any parameter that is
+    /// missing is filled in with a default value first.
+    ///
+    /// Returns whether any parameter was changed.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        description: &CommandDescription,
+        command: &mut EventCommand,
+    ) -> bool {
+        let values = &mut command.parameters;
+
+        let mut modified = false;
+        let mut next_index = 0;
+        for parameter in description.parameters() {
+            modified |= fill_defaults(parameter, values, &mut next_index, false);
+        }
+
+        if description.parameters().is_empty() {
+            ui.weak("No parameters to edit");
+            return modified;
+        }
+
+        let timing = FrameTiming::new(update_state, command.code);
+        egui::Grid::new(self.id_source.with(command.guid))
+            .num_columns(2)
+            .spacing([8., 4.])
+            .show(ui, |ui| {
+                let mut next_index = 0;
+                for parameter in description.parameters() {
+                    modified |= self.parameter_ui(
+                        ui,
+                        update_state,
+                        parameter,
+                        values,
+                        &mut next_index,
+                        timing,
+                    );
+                }
+            });
+
+        modified
+    }
+
+    fn parameter_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        parameter: &Parameter,
+        values: &mut Vec<ParameterType>,
+        next_index: &mut usize,
+        timing: FrameTiming,
+    ) -> bool {
+        match parameter {
+            Parameter::Single {
+                index,
+                description,
+                name,
+                kind,
+                ..
+            } => {
+                let index = index.resolve(next_index);
+                ui.label(name).on_hover_text(description);
+                let modified = self.value_ui(ui, update_state, kind, index, values, timing);
+                ui.end_row();
+                modified
+            }
+            Parameter::Group { parameters, .. } => {
+                let mut modified = false;
+                for parameter in parameters {
+                    modified |=
+                        self.parameter_ui(ui, update_state, parameter, values, next_index, timing);
+                }
+                modified
+            }
+            Parameter::Selection {
+                index, parameters, ..
+            } => {
+                let index = index.resolve(next_index);
+                let start = *next_index;
+
+                let mut modified = false;
+                let mut selected = *values[index].into_integer();
+                ui.label("");
+                ui.horizontal(|ui| {
+                    for (value, parameter) in parameters {
+                        let value = *value as i32;
+                        if ui.radio(selected == value, label(parameter)).clicked()
+                            && selected != value
+                        {
+                            selected = value;
+                            modified = true;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                if modified {
+                    // The options share their indices, so whatever the previous option left
+                    // there is probably the wrong kind of value
+                    values[index] = ParameterType::Integer(selected);
+                    if let Some((_, parameter)) =
+                        parameters.iter().find(|(v, _)| *v as i32 == selected)
+                    {
+                        fill_defaults(parameter, values, &mut { start }, true);
+                    }
+                }
+
+                if let Some((_, parameter)) = parameters.iter().find(|(v, _)| *v as i32 == selected)
+                {
+                    modified |= self.parameter_ui(
+                        ui,
+                        update_state,
+                        parameter,
+                        values,
+                        &mut { start },
+                        timing,
+                    );
+                }
+
+                *next_index = start + Parameter::selection_span(parameters);
+                modified
+            }
+            Parameter::Dummy => {
+                *next_index += 1;
+                false
+            }
+            Parameter::Label(text) => {
+                ui.label(text);
+                ui.end_row();
+                false
+            }
+        }
+    }
+
+    fn value_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        kind: &ParameterKind,
+        index: usize,
+        values: &mut [ParameterType],
+        timing: FrameTiming,
+    ) -> bool {
+        let value = &mut values[index];
+        match kind {
+            ParameterKind::Switch | ParameterKind::Variable => {
+                let value = value.into_integer();
+                // Switch and variable IDs are stored 1-based in command parameters
+                let mut id = (*value - 1).max(0) as usize;
+                let changed = if matches!(kind, ParameterKind::Switch) {
+                    let modal = self.switch_modals.entry(index).or_insert_with(|| {
+                        SwitchModal::new(self.id_source.with(("switch", index)))
+                    });
+                    ui.add(modal.button(&mut id, update_state)).changed()
+                } else {
+                    let modal = self.variable_modals.entry(index).or_insert_with(|| {
+                        VariableModal::new(self.id_source.with(("variable", index)))
+                    });
+                    ui.add(modal.button(&mut id, update_state)).changed()
+                };
+                if changed {
+                    *value = id as i32 + 1;
+                }
+                changed
+            }
+            ParameterKind::SelfSwitch => {
+                let value = value.into_string();
+                let mut changed = false;
+                egui::ComboBox::from_id_source(self.id_source.with(("self_switch", index)))
+                    .selected_text(value.as_str())
+                    .show_ui(ui, |ui| {
+                        for self_switch in SELF_SWITCHES {
+                            changed |= ui
+                                .selectable_value(value, self_switch.to_string(), self_switch)
+                                .changed();
+                        }
+                    });
+                changed
+            }
+            ParameterKind::String => ui.text_edit_singleline(value.into_string()).changed(),
+            ParameterKind::Int => egui::DragValue::new(value.into_integer()).ui(ui).changed(),
+            ParameterKind::IntRange { min, max } => {
+                egui::Slider::new(value.into_integer(), *min..=*max)
+                    .ui(ui)
+                    .changed()
+            }
+            ParameterKind::IntBool => {
+                let value = value.into_integer();
+                let mut checked = *value != 0;
+                let changed = ui.checkbox(&mut checked, "").changed();
+                if changed {
+                    *value = checked as i32;
+                }
+                changed
+            }
+            ParameterKind::Enum { variants } => {
+                let value = value.into_integer();
+                let selected_text = variants
+                    .iter()
+                    .find(|(_, v)| *v as i32 == *value)
+                    .map_or_else(|| value.to_string(), |(name, _)| name.clone());
+                let mut changed = false;
+                egui::ComboBox::from_id_source(self.id_source.with(("enum", index)))
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (name, v) in variants {
+                            changed |= ui.selectable_value(value, *v as i32, name).changed();
+                        }
+                    });
+                changed
+            }
+            ParameterKind::Color => {
+                let color = value.into_color();
+                let mut rgba = [color.red, color.green, color.blue, color.alpha]
+                    .map(|c| c.clamp(0., 255.).round() as u8);
+                let changed = ui.color_edit_button_srgba_unmultiplied(&mut rgba).changed();
+                if changed {
+                    [color.red, color.green, color.blue, color.alpha] = rgba.map(f64::from);
+                }
+                changed
+            }
+            ParameterKind::Tone => ui.add(ToneEdit::new(value.into_tone())).changed(),
+            ParameterKind::Frames => {
+                let frames = value.into_integer();
+                ui.horizontal(|ui| {
+                    let changed = egui::DragValue::new(frames)
+                        .range(timing.min..=timing.max)
+                        .suffix(" frames")
+                        .ui(ui)
+                        .changed();
+                    ui.weak(format!(
+                        "≈ {:.2}s at {} fps",
+                        timing.seconds(*frames),
+                        timing.frame_rate
+                    ));
+                    changed
+                })
+                .inner
+            }
+        }
+    }
+}
+
+/// A short name for an option of a selection.
+fn label(parameter: &Parameter) -> String {
+    match parameter {
+        Parameter::Single { name, .. } => name.clone(),
+        Parameter::Label(text) => text.clone(),
+        Parameter::Group { parameters, .. } => parameters.first().map(label).unwrap_or_default(),
+        Parameter::Selection { .. } | Parameter::Dummy => String::new(),
+    }
+}
+
+/// Fills in missing values of a parameter with defaults, or every value if `force` is set.
+///
+/// Returns whether any value was changed.
+fn fill_defaults(
+    parameter: &Parameter,
+    values: &mut Vec<ParameterType>,
+    next_index: &mut usize,
+    force: bool,
+) -> bool {
+    let mut set_default = |index: usize, default: ParameterType| {
+        if values.len() <= index {
+            values.resize(index + 1, ParameterType::None);
+        }
+        if force || values[index].is_none() {
+            values[index] = default;
+            true
+        } else {
+            false
+        }
+    };
+
+    match parameter {
+        Parameter::Single { index, kind, .. } => {
+            let index = index.resolve(next_index);
+            set_default(index, default_value(kind))
+        }
+        Parameter::Group { parameters, .. } => {
+            let mut modified = false;
+            for parameter in parameters {
+                modified |= fill_defaults(parameter, values, next_index, force);
+            }
+            modified
+        }
+        Parameter::Selection {
+            index, parameters, ..
+        } => {
+            let index = index.resolve(next_index);
+            let start = *next_index;
+            *next_index = start + Parameter::selection_span(parameters);
+
+            let Some(first) = parameters.first() else {
+                return false;
+            };
+            let mut modified = set_default(index, ParameterType::Integer(first.0 as i32));
+
+            // Only the selected option's values are stored
+            let selected = values[index].as_integer().copied();
+            if let Some((_, parameter)) =
+                parameters.iter().find(|(v, _)| Some(*v as i32) == selected)
+            {
+                modified |= fill_defaults(parameter, values, &mut { start }, force);
+            }
+            modified
+        }
+        Parameter::Dummy => {
+            *next_index += 1;
+            false
+        }
+        Parameter::Label(_) => false,
+    }
+}
+
+fn default_value(kind: &ParameterKind) -> ParameterType {
+    match kind {
+        ParameterKind::String => ParameterType::String(String::new()),
+        ParameterKind::SelfSwitch => ParameterType::String(SELF_SWITCHES[0].to_string()),
+        ParameterKind::Switch | ParameterKind::Variable => ParameterType::Integer(1),
+        ParameterKind::Int | ParameterKind::IntBool => ParameterType::Integer(0),
+        ParameterKind::IntRange { min, max } => ParameterType::Integer(min + (max - min) / 2),
+        ParameterKind::Enum { variants } => {
+            ParameterType::Integer(variants.first().map_or(0, |(_, v)| *v as i32))
+        }
+        ParameterKind::Color => ParameterType::Color(luminol_data::Color {
+            red: 255.,
+            green: 255.,
+            blue: 255.,
+            alpha: 255.,
+        }),
+        ParameterKind::Tone => ParameterType::Tone(luminol_data::Tone::default()),
+        ParameterKind::Frames => ParameterType::Integer(DEFAULT_FRAMES),
+    }
+}
+
+/// How long the frame counts of a command last in game.
+#[derive(Clone, Copy)]
+struct FrameTiming {
+    /// The frame rate games made with the project's editor run at by default.
+    frame_rate: f64,
+    /// How many frames the game waits for each frame in the parameter.
+    scale: f64,
+    min: i32,
+    max: i32,
+}
+
+impl FrameTiming {
+    fn new(update_state: &luminol_core::UpdateState<'_>, code: u16) -> Self {
+        let is_xp = !matches!(
+            update_state
+                .project_config
+                .as_ref()
+                .map(|config| config.project.editor_ver),
+            Some(luminol_config::RMVer::VX | luminol_config::RMVer::Ace)
+        );
+        // Wait, Change Screen Color Tone, Screen Flash and Screen Shake
+        let doubled = matches!(code, 106 | 223 | 224 | 225);
+        let (min, max) = if matches!(code, 223..=225) {
+            (1, 200)
+        } else {
+            (0, 999)
+        };
+        Self {
+            frame_rate: if is_xp { 40. } else { 60. },
+            // RPG Maker XP runs these commands for twice as many frames as they say
+            scale: if is_xp && doubled { 2. } else { 1. },
+            min,
+            max,
+        }
+    }
+
+    fn seconds(&self, frames: i32) -> f64 {
+        frames as f64 * self.scale / self.frame_rate
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use egui::Widget;
+use luminol_data::commands::{CommandDescription, CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg::{self, EventCommand};
+use luminol_data::ParameterType;
+
+use super::form::SELF_SWITCHES;
+
+static MULTI_LINE_KIND: ParameterKind = ParameterKind::String;
+
+/// Returns the parameter of commands with this description that can be edited right in the
+/// command list, along with its index. That is the text of multiline commands, or the only
+/// parameter of commands that have exactly one if it fits in a row.
+pub(super) fn inline_parameter(
+    description: &CommandDescription,
+) -> Option<(usize, &ParameterKind)> {
+    if let CommandKind::Multi { .. } = description.kind {
+        return Some((0, &MULTI_LINE_KIND));
+    }
+
+    let [Parameter::Single { index, kind, .. }] = description.parameters() else {
+        return None;
+    };
+    (!matches!(kind, ParameterKind::Color | ParameterKind::Tone))
+        .then(|| (index.resolve(&mut 0), kind))
+}
+
+/// A parameter edited in the command list, kept until its editor loses focus.
+struct InlineEdit {
+    command_index: usize,
+    guid: u16,
+    parameter_index: usize,
+    value: ParameterType,
+    drawn: bool,
+}
+
+/// Edits parameters right in the command list. Values are written back to the commands once
+/// their editor loses focus or a drag ends, so typing or dragging doesn't change the command on
+/// every frame.
+#[derive(Default)]
+pub(super) struct InlineEditor {
+    editing: Option<InlineEdit>,
+    finished: Vec<InlineEdit>,
+}
+
+impl InlineEditor {
+    pub fn begin_frame(&mut self) {
+        if let Some(edit) = &mut self.editing {
+            edit.drawn = false;
+        }
+    }
+
+    /// Shows an editor for a parameter of `command`, which is at `command_index`.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        system: &rpg::System,
+        command_index: usize,
+        command: &EventCommand,
+        (parameter_index, kind): (usize, &ParameterKind),
+    ) {
+        let Some(stored) = command.parameters.get(parameter_index) else {
+            return;
+        };
+        let is_this = |edit: &InlineEdit| {
+            edit.command_index == command_index
+                && edit.guid == command.guid
+                && edit.parameter_index == parameter_index
+        };
+
+        let mut value = match &self.editing {
+            Some(edit) if is_this(edit) => edit.value.clone(),
+            _ => stored.clone(),
+        };
+        let id = ui.id().with(("inline", command_index, parameter_index));
+        let response = value_ui(ui, id, kind, &mut value, system);
+
+        let edit = InlineEdit {
+            command_index,
+            guid: command.guid,
+            parameter_index,
+            value,
+            drawn: true,
+        };
+        if response.has_focus() || response.dragged() {
+            // Focus can move to another editor before the previous one is drawn
+            if let Some(previous) = self.editing.replace(edit).filter(|e| !is_this(e)) {
+                self.finished.push(previous);
+            }
+        } else {
+            if self.editing.as_ref().is_some_and(is_this) {
+                self.editing = None;
+            }
+            if edit.value != *stored {
+                self.finished.push(edit);
+            }
+        }
+    }
+
+    /// Writes finished edits back to `commands`. An edit whose editor wasn't shown this frame,
+    /// because another row was selected, is finished too.
+    ///
+    /// Returns whether any command was changed.
+    pub fn end_frame(&mut self, commands: &mut [EventCommand]) -> bool {
+        if self.editing.as_ref().is_some_and(|e| !e.drawn) {
+            self.finished.extend(self.editing.take());
+        }
+
+        let mut modified = false;
+        for edit in self.finished.drain(..) {
+            let Some(value) = commands
+                .get_mut(edit.command_index)
+                .filter(|c| c.guid == edit.guid)
+                .and_then(|c| c.parameters.get_mut(edit.parameter_index))
+            else {
+                continue;
+            };
+            if *value != edit.value {
+                *value = edit.value;
+                modified = true;
+            }
+        }
+        modified
+    }
+}
+
+fn value_ui(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    kind: &ParameterKind,
+    value: &mut ParameterType,
+    system: &rpg::System,
+) -> egui::Response {
+    match kind {
+        ParameterKind::Switch | ParameterKind::Variable => {
+            let names = if matches!(kind, ParameterKind::Switch) {
+                &system.switches
+            } else {
+                &system.variables
+            };
+            // Switch and variable IDs are stored 1-based in command parameters
+            let value = value.into_integer();
+            let name = |id: i32| {
+                let name = usize::try_from(id - 1).ok().and_then(|i| names.get(i));
+                format!("{id:0>3}: {}", name.map_or("", String::as_str))
+            };
+            egui::ComboBox::from_id_source(id)
+                .selected_text(name(*value))
+                .show_ui(ui, |ui| {
+                    for id in 1..=names.len() as i32 {
+                        ui.selectable_value(value, id, name(id));
+                    }
+                })
+                .response
+        }
+        ParameterKind::SelfSwitch => {
+            let value = value.into_string();
+            egui::ComboBox::from_id_source(id)
+                .selected_text(value.as_str())
+                .show_ui(ui, |ui| {
+                    for self_switch in SELF_SWITCHES {
+                        ui.selectable_value(value, self_switch.to_string(), self_switch);
+                    }
+                })
+                .response
+        }
+        ParameterKind::String => egui::TextEdit::singleline(value.into_string())
+            .id(id)
+            .ui(ui),
+        ParameterKind::Int => egui::DragValue::new(value.into_integer()).ui(ui),
+        ParameterKind::IntRange { min, max } => egui::DragValue::new(value.into_integer())
+            .range(*min..=*max)
+            .ui(ui),
+        ParameterKind::IntBool => {
+            let value = value.into_integer();
+            let mut checked = *value != 0;
+            let response = ui.checkbox(&mut checked, "");
+            *value = checked as i32;
+            response
+        }
+        ParameterKind::Enum { variants } => {
+            let value = value.into_integer();
+            let selected_text = variants
+                .iter()
+                .find(|(_, v)| *v as i32 == *value)
+                .map_or_else(|| value.to_string(), |(name, _)| name.clone());
+            egui::ComboBox::from_id_source(id)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (name, v) in variants {
+                        ui.selectable_value(value, *v as i32, name);
+                    }
+                })
+                .response
+        }
+        ParameterKind::Frames => egui::DragValue::new(value.into_integer())
+            .range(0..=999)
+            .suffix(" frames")
+            .ui(ui),
+        ParameterKind::Color | ParameterKind::Tone => ui.weak("Not editable here"),
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::validation::{labels, JUMP_TO_LABEL, LABEL};
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+/// Edits the Label and Jump to Label commands, warning about jumps to a label the event doesn't
+/// have and labels that share their name with another.
+pub struct LabelCommandEditor {
+    id_source: egui::Id,
+}
+
+impl LabelCommandEditor {
+    pub const CODES: [u16; 2] = [LABEL, JUMP_TO_LABEL];
+
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            id_source: id_source.into(),
+        }
+    }
+
+    /// Returns the parameters of a newly inserted Label or Jump to Label command.
+    pub fn default_parameters() -> Vec<ParameterType> {
+        vec![ParameterType::String(String::new())]
+    }
+
+    /// Display the parameters of the Label or Jump to Label command at `index`. The labels a
+    /// jump can go to are the ones in `commands`.
+    ///
+    /// Returns whether any command was changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui, commands: &mut [EventCommand], index: usize) -> bool {
+        let code = commands[index].code;
+        if !Self::CODES.contains(&code) {
+            return false;
+        }
+
+        let names: Vec<String> = labels(commands)
+            .filter(|(i, _)| *i != index)
+            .map(|(_, name)| name.to_string())
+            .collect();
+
+        let mut modified = false;
+        let parameters = &mut commands[index].parameters;
+        if parameters.is_empty() {
+            *parameters = Self::default_parameters();
+            modified = true;
+        }
+        let name = parameters[0].into_string();
+
+        egui::Grid::new(self.id_source.with("grid"))
+            .num_columns(2)
+            .spacing([8., 4.])
+            .show(ui, |ui| {
+                ui.label(if code == LABEL { "Name" } else { "Label" });
+                ui.horizontal(|ui| {
+                    modified |= ui.text_edit_singleline(name).changed();
+
+                    if code == JUMP_TO_LABEL {
+                        egui::ComboBox::from_id_source(self.id_source.with("labels"))
+                            .selected_text("")
+                            .width(0.)
+                            .show_ui(ui, |ui| {
+                                if names.is_empty() {
+                                    ui.weak("This event has no labels");
+                                }
+                                for label in &names {
+                                    modified |=
+                                        ui.selectable_value(name, label.clone(), label).changed();
+                                }
+                            });
+                    }
+
+                    let warning = if name.is_empty() {
+                        Some("The name is empty".to_string())
+                    } else if code == LABEL && names.contains(name) {
+                        Some(
+                            "Another label in this event has this name, jumps will only ever go to \
+                            the first of them"
+                                .to_string(),
+                        )
+                    } else if code == JUMP_TO_LABEL && !names.contains(name) {
+                        Some(format!(
+                            "This event has no label named {name:?}, so this command does nothing"
+                        ))
+                    } else {
+                        None
+                    };
+                    if let Some(warning) = warning {
+                        warning_icon(ui).on_hover_text(warning);
+                    }
+                });
+                ui.end_row();
+            });
+
+        modified
+    }
+}
+
+/// Shows the icon used to flag a problem with a command.
+pub(super) fn warning_icon(ui: &mut egui::Ui) -> egui::Response {
+    ui.label(egui::RichText::new("⚠").color(ui.visuals().warn_fg_color))
+}
@@ -24,16 +24,40 @@
 
 #[macro_use]
 mod macros;
+mod battle;
 mod command_ui;
+mod editor;
+mod form;
+mod inline;
+mod inventory;
+mod label;
 mod parameter_ui;
+mod picture;
+mod shop;
 mod ui;
 
+pub use battle::BattleCommandEditor;
+pub use form::ParameterForm;
+pub use inventory::InventoryCommandEditor;
+pub use label::LabelCommandEditor;
+pub use picture::PictureCommandEditor;
+pub use shop::ShopCommandEditor;
+
 use std::collections::HashMap;
 
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
 pub struct CommandView {
-    _selected_index: usize,
+    selected_index: usize,
     _window_state: WindowState,
-    _id: egui::Id,
+    id: egui::Id,
+    inline: inline::InlineEditor,
+    editors: editor::Editors,
+    /// The guid of the command opened in the editor window.
+    editing: Option<u16>,
+    /// A command picked from the outline, to be scrolled to the next time the list is shown.
+    scroll_to: Option<usize>,
     _modals: HashMap<u64, bool>, // todo find a better way to handle modals
 }
 
@@ -44,19 +68,56 @@ enum WindowState {
 impl Default for CommandView {
     fn default() -> Self {
         Self {
-            _selected_index: 0,
+            selected_index: 0,
             _window_state: WindowState::None,
-            _id: egui::Id::new("command_view"),
+            id: egui::Id::new("command_view"),
+            inline: Default::default(),
+            editors: editor::Editors::new(egui::Id::new("command_view")),
+            editing: None,
+            scroll_to: None,
             _modals: HashMap::new(),
         }
     }
 }
 
 impl CommandView {
+    /// Commands shown in a row above the command list, since they are used far more
+    /// often than the rest: Change Gold, Change Items, Change Weapons, Change Armor and Change
+    /// Party Member.
+    pub const FAVORITE_COMMANDS: [u16; 5] = InventoryCommandEditor::CODES;
+
     pub fn new(id: impl std::hash::Hash) -> Self {
+        let id = egui::Id::new(id);
         Self {
-            _id: egui::Id::new(id),
+            id,
+            editors: editor::Editors::new(id),
             ..Default::default()
         }
     }
+
+    /// Returns how many commands starting at `index` are shown as a single line. Shop
+    /// Processing continues its goods list onto commands that aren't shown on their own.
+    pub fn logical_len(commands: &[EventCommand], index: usize) -> usize {
+        match commands[index].code {
+            ShopCommandEditor::CODE => ShopCommandEditor::len(commands, index),
+            _ => 1,
+        }
+    }
+}
+
+fn new_command(code: u16, indent: usize, parameters: Vec<ParameterType>) -> EventCommand {
+    EventCommand {
+        code,
+        indent,
+        parameters,
+        guid: rand::random(),
+    }
+}
+
+fn integer(parameters: &[ParameterType], index: usize) -> i32 {
+    parameters
+        .get(index)
+        .and_then(ParameterType::as_integer)
+        .copied()
+        .unwrap_or_default()
 }
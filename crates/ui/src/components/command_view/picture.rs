@@ -0,0 +1,357 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::sync::Arc;
+
+use egui::Widget;
+use luminol_core::Modal;
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use super::integer;
+use crate::modals::{database_modal::VariableModal, graphic_picker::basic::Modal as GraphicPicker};
+
+const SHOW_PICTURE: u16 = 231;
+const MOVE_PICTURE: u16 = 232;
+
+const PICTURE_DIRECTORY: &str = "Graphics/Pictures";
+const SCREEN_SIZE: egui::Vec2 = egui::vec2(640., 480.);
+
+// Parameter indices, shared by both commands except for the second one
+const NUMBER: usize = 0;
+const NAME: usize = 1;
+const DURATION: usize = 1;
+const ORIGIN: usize = 2;
+const DESIGNATION: usize = 3;
+const X: usize = 4;
+const Y: usize = 5;
+const ZOOM_X: usize = 6;
+const ZOOM_Y: usize = 7;
+const OPACITY: usize = 8;
+const BLEND_TYPE: usize = 9;
+
+/// Edits the parameters of the Show Picture and Move Picture commands, with a preview of where
+/// the picture ends up on screen.
+pub struct PictureCommandEditor {
+    id_source: egui::Id,
+
+    /// The picker is rebuilt whenever a different command is edited, since it caches the
+    /// graphic it was created with.
+    graphic_picker: Option<(u16, GraphicPicker)>,
+    x_variable_modal: VariableModal,
+    y_variable_modal: VariableModal,
+
+    preview_texture: Option<(String, Option<Arc<luminol_graphics::Texture>>)>,
+}
+
+impl PictureCommandEditor {
+    pub const CODES: [u16; 2] = [SHOW_PICTURE, MOVE_PICTURE];
+
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        let id_source = id_source.into();
+        Self {
+            id_source,
+            graphic_picker: None,
+            x_variable_modal: VariableModal::new(id_source.with("x_variable")),
+            y_variable_modal: VariableModal::new(id_source.with("y_variable")),
+            preview_texture: None,
+        }
+    }
+
+    /// Returns the parameters RPG Maker XP gives a newly inserted picture command.
+    pub fn default_parameters(code: u16) -> Vec<ParameterType> {
+        let second = if code == MOVE_PICTURE {
+            ParameterType::Integer(20)
+        } else {
+            ParameterType::String(String::new())
+        };
+        vec![
+            ParameterType::Integer(1),
+            second,
+            ParameterType::Integer(0),
+            ParameterType::Integer(0),
+            ParameterType::Integer(0),
+            ParameterType::Integer(0),
+            ParameterType::Integer(100),
+            ParameterType::Integer(100),
+            ParameterType::Integer(255),
+            ParameterType::Integer(0),
+        ]
+    }
+
+    /// Display the parameters of the picture command at `index`. Move Picture doesn't name a
+    /// graphic, so its preview uses the closest Show Picture before it with the same number.
+    ///
+    /// Returns whether any parameter was changed.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut [EventCommand],
+        index: usize,
+    ) -> bool {
+        let code = commands[index].code;
+        if code != SHOW_PICTURE && code != MOVE_PICTURE {
+            return false;
+        }
+
+        let mut modified = false;
+        if commands[index].parameters.len() < BLEND_TYPE + 1 {
+            let defaults = Self::default_parameters(code);
+            let parameters = &mut commands[index].parameters;
+            parameters.extend(defaults.into_iter().skip(parameters.len()));
+            modified = true;
+        }
+
+        let number = integer(&commands[index].parameters, NUMBER);
+        let shown_name = if code == MOVE_PICTURE {
+            commands[..index]
+                .iter()
+                .rev()
+                .find(|c| c.code == SHOW_PICTURE && integer(&c.parameters, NUMBER) == number)
+                .and_then(|c| c.parameters.get(NAME)?.as_string().cloned())
+        } else {
+            None
+        };
+
+        let command = &mut commands[index];
+        let guid = command.guid;
+        let parameters = &mut command.parameters;
+
+        egui::Grid::new(self.id_source.with("grid"))
+            .num_columns(2)
+            .spacing([8., 4.])
+            .show(ui, |ui| {
+                ui.label("Number");
+                modified |= egui::DragValue::new(parameters[NUMBER].into_integer())
+                    .range(1..=50)
+                    .ui(ui)
+                    .changed();
+                ui.end_row();
+
+                if code == SHOW_PICTURE {
+                    ui.label("Graphic");
+                    modified |= self.graphic_ui(ui, update_state, guid, &mut parameters[NAME]);
+                } else {
+                    ui.label("Duration");
+                    modified |= egui::DragValue::new(parameters[DURATION].into_integer())
+                        .range(1..=200)
+                        .suffix(" frames")
+                        .ui(ui)
+                        .changed();
+                }
+                ui.end_row();
+
+                ui.label("Origin");
+                let origin = parameters[ORIGIN].into_integer();
+                egui::ComboBox::from_id_source(self.id_source.with("origin"))
+                    .selected_text(if *origin == 1 { "Center" } else { "Upper Left" })
+                    .show_ui(ui, |ui| {
+                        modified |= ui.selectable_value(origin, 0, "Upper Left").changed();
+                        modified |= ui.selectable_value(origin, 1, "Center").changed();
+                    });
+                ui.end_row();
+
+                ui.label("Designation");
+                let designation = parameters[DESIGNATION].into_integer();
+                ui.horizontal(|ui| {
+                    modified |= ui.radio_value(designation, 0, "Direct").changed();
+                    modified |= ui.radio_value(designation, 1, "Variables").changed();
+                });
+                let by_variable = *designation == 1;
+                ui.end_row();
+
+                for (label, param, modal) in [
+                    ("X", X, &mut self.x_variable_modal),
+                    ("Y", Y, &mut self.y_variable_modal),
+                ] {
+                    ui.label(label);
+                    let value = parameters[param].into_integer();
+                    if by_variable {
+                        // Variable IDs are stored 1-based in command parameters
+                        let mut id = (*value - 1).max(0) as usize;
+                        if ui.add(modal.button(&mut id, update_state)).changed() {
+                            *value = id as i32 + 1;
+                            modified = true;
+                        }
+                    } else {
+                        modified |= egui::DragValue::new(value)
+                            .range(-9999..=9999)
+                            .ui(ui)
+                            .changed();
+                    }
+                    ui.end_row();
+                }
+
+                for (label, param) in [("Zoom X", ZOOM_X), ("Zoom Y", ZOOM_Y)] {
+                    ui.label(label);
+                    modified |= egui::DragValue::new(parameters[param].into_integer())
+                        .range(0..=2000)
+                        .suffix("%")
+                        .ui(ui)
+                        .changed();
+                    ui.end_row();
+                }
+
+                ui.label("Opacity");
+                modified |= ui
+                    .add(egui::Slider::new(
+                        parameters[OPACITY].into_integer(),
+                        0..=255,
+                    ))
+                    .changed();
+                ui.end_row();
+
+                ui.label("Blending");
+                let blend_type = parameters[BLEND_TYPE].into_integer();
+                egui::ComboBox::from_id_source(self.id_source.with("blend_type"))
+                    .selected_text(match *blend_type {
+                        1 => "Add",
+                        2 => "Subtract",
+                        _ => "Normal",
+                    })
+                    .show_ui(ui, |ui| {
+                        modified |= ui.selectable_value(blend_type, 0, "Normal").changed();
+                        modified |= ui.selectable_value(blend_type, 1, "Add").changed();
+                        modified |= ui.selectable_value(blend_type, 2, "Subtract").changed();
+                    });
+                ui.end_row();
+            });
+
+        ui.separator();
+
+        let name = if code == SHOW_PICTURE {
+            parameters[NAME].as_string().cloned()
+        } else {
+            shown_name
+        };
+        self.preview_ui(ui, update_state, name.as_deref(), parameters);
+
+        modified
+    }
+
+    fn graphic_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        guid: u16,
+        name: &mut ParameterType,
+    ) -> bool {
+        let name = name.into_string();
+        let mut path = (!name.is_empty()).then(|| camino::Utf8PathBuf::from(name.as_str()));
+
+        if !matches!(self.graphic_picker, Some((id, _)) if id == guid) {
+            // The picker expects its initial graphic to exist, so don't give it a missing one
+            let existing = path.as_deref().filter(|path| {
+                update_state
+                    .filesystem
+                    .desensitize(camino::Utf8Path::new(PICTURE_DIRECTORY).join(path))
+                    .is_ok()
+            });
+            let picker = GraphicPicker::new(
+                update_state,
+                PICTURE_DIRECTORY.into(),
+                existing,
+                egui::vec2(80., 60.),
+                self.id_source.with("graphic_picker"),
+            );
+            self.graphic_picker = Some((guid, picker));
+        }
+        let (_, picker) = self.graphic_picker.as_mut().unwrap();
+
+        let changed = picker.button(&mut path, update_state).ui(ui).changed();
+        if changed {
+            *name = path.map(String::from).unwrap_or_default();
+        }
+        changed
+    }
+
+    fn preview_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        name: Option<&str>,
+        parameters: &[ParameterType],
+    ) {
+        let scale = (ui.available_width() / SCREEN_SIZE.x).clamp(0.25, 0.5);
+        let (screen_rect, _) = ui.allocate_exact_size(SCREEN_SIZE * scale, egui::Sense::hover());
+        let painter = ui.painter_at(screen_rect);
+        painter.rect_filled(screen_rect, 0., egui::Color32::BLACK);
+
+        if integer(parameters, DESIGNATION) == 1 {
+            painter.text(
+                screen_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Position is set by variables",
+                egui::FontId::proportional(12.),
+                ui.visuals().weak_text_color(),
+            );
+            return;
+        }
+
+        let texture = name.filter(|name| !name.is_empty()).and_then(|name| {
+            if !matches!(&self.preview_texture, Some((cached, _)) if cached == name) {
+                let texture = update_state
+                    .graphics
+                    .texture_loader
+                    .load_now_dir(update_state.filesystem, PICTURE_DIRECTORY, name)
+                    .ok();
+                self.preview_texture = Some((name.to_string(), texture));
+            }
+            self.preview_texture.as_ref()?.1.clone()
+        });
+
+        let zoom = egui::vec2(
+            integer(parameters, ZOOM_X) as f32,
+            integer(parameters, ZOOM_Y) as f32,
+        ) / 100.;
+        let size = texture
+            .as_ref()
+            .map_or(egui::vec2(64., 64.), |texture| texture.size_vec2())
+            * zoom;
+        let position = egui::vec2(integer(parameters, X) as f32, integer(parameters, Y) as f32);
+        let min = if integer(parameters, ORIGIN) == 1 {
+            position - size / 2.
+        } else {
+            position
+        };
+        let picture_rect = egui::Rect::from_min_size(screen_rect.min + min * scale, size * scale);
+
+        if let Some(texture) = texture {
+            let opacity = integer(parameters, OPACITY).clamp(0, 255) as u8;
+            painter.image(
+                texture.texture_id,
+                picture_rect,
+                egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+                egui::Color32::from_white_alpha(opacity),
+            );
+        } else {
+            painter.rect_stroke(
+                picture_rect,
+                0.,
+                egui::Stroke::new(1., ui.visuals().weak_text_color()),
+            );
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use super::inventory::{goods_combo_box, IconCache};
+use super::{integer, new_command};
+
+const SHOP_PROCESSING: u16 = 302;
+const SHOP_GOODS: u16 = 605;
+
+const GOODS_KINDS: [&str; 3] = ["Item", "Weapon", "Armor"];
+
+/// Edits the goods list of the Shop Processing command.
+///
+/// The command itself holds the first of the goods and every other one is stored in a Shop
+/// Goods command after it, so the list is edited as a whole rather than one command at a time.
+pub struct ShopCommandEditor {
+    id_source: egui::Id,
+
+    /// Icons are loaded the first time an entry using them is shown.
+    icons: IconCache,
+}
+
+impl ShopCommandEditor {
+    pub const CODE: u16 = SHOP_PROCESSING;
+
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            id_source: id_source.into(),
+            icons: IconCache::new(),
+        }
+    }
+
+    /// Returns the parameters RPG Maker XP gives a newly inserted Shop Processing command.
+    pub fn default_parameters() -> Vec<ParameterType> {
+        vec![ParameterType::Integer(0), ParameterType::Integer(1)]
+    }
+
+    /// Returns how many commands the goods list of the Shop Processing command at `index` spans,
+    /// including the command itself.
+    pub fn len(commands: &[EventCommand], index: usize) -> usize {
+        1 + commands[index + 1..]
+            .iter()
+            .take_while(|c| c.code == SHOP_GOODS)
+            .count()
+    }
+
+    /// Describes the whole goods list of the Shop Processing command at `index` in one line.
+    pub fn summary(
+        update_state: &luminol_core::UpdateState<'_>,
+        commands: &[EventCommand],
+        index: usize,
+    ) -> String {
+        let goods = commands[index..index + Self::len(commands, index)]
+            .iter()
+            .map(|c| {
+                let id = integer(&c.parameters, 1);
+                let name =
+                    usize::try_from(id - 1)
+                        .ok()
+                        .and_then(|i| match integer(&c.parameters, 0) {
+                            0 => update_state
+                                .data
+                                .items()
+                                .data
+                                .get(i)
+                                .map(|i| i.name.clone()),
+                            1 => update_state
+                                .data
+                                .weapons()
+                                .data
+                                .get(i)
+                                .map(|w| w.name.clone()),
+                            _ => update_state
+                                .data
+                                .armors()
+                                .data
+                                .get(i)
+                                .map(|a| a.name.clone()),
+                        });
+                format!("[{}]", name.unwrap_or_else(|| format!("{id:0>3}")))
+            })
+            .join(", ");
+        format!("Shop Processing: {goods}")
+    }
+
+    /// Display the goods list of the Shop Processing command at `index`. Adding or removing
+    /// goods inserts or removes Shop Goods commands after it.
+    ///
+    /// Returns whether any command was changed.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<EventCommand>,
+        index: usize,
+    ) -> bool {
+        if commands[index].code != SHOP_PROCESSING {
+            return false;
+        }
+
+        let len = Self::len(commands, index);
+        let indent = commands[index].indent;
+        let mut goods: Vec<[ParameterType; 2]> = commands[index..index + len]
+            .iter()
+            .map(|c| {
+                let mut parameters = c.parameters.iter().cloned();
+                [
+                    parameters.next().unwrap_or(ParameterType::Integer(0)),
+                    parameters.next().unwrap_or(ParameterType::Integer(1)),
+                ]
+            })
+            .collect();
+
+        let mut modified = false;
+        let mut removed = None;
+        let can_remove = goods.len() > 1;
+
+        egui::Grid::new(self.id_source.with("goods"))
+            .num_columns(3)
+            .spacing([8., 4.])
+            .show(ui, |ui| {
+                for (i, [kind, id]) in goods.iter_mut().enumerate() {
+                    let kind = kind.into_integer();
+                    egui::ComboBox::from_id_source(self.id_source.with(("kind", i)))
+                        .selected_text(*GOODS_KINDS.get(*kind as usize).unwrap_or(&GOODS_KINDS[0]))
+                        .show_ui(ui, |ui| {
+                            for (value, label) in GOODS_KINDS.into_iter().enumerate() {
+                                if ui.selectable_value(kind, value as i32, label).changed() {
+                                    *id = ParameterType::Integer(1);
+                                    modified = true;
+                                }
+                            }
+                        });
+
+                    modified |= goods_combo_box(
+                        ui,
+                        update_state,
+                        &mut self.icons,
+                        self.id_source.with(("id", i)),
+                        *kind,
+                        id,
+                    );
+
+                    if ui
+                        .add_enabled(can_remove, egui::Button::new("✖"))
+                        .on_hover_text("Remove")
+                        .clicked()
+                    {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = removed {
+            goods.remove(i);
+            modified = true;
+        }
+
+        if ui.button("Add").clicked() {
+            goods.push([ParameterType::Integer(0), ParameterType::Integer(1)]);
+            modified = true;
+        }
+
+        if modified {
+            // The existing commands are reused where possible so they keep their guids
+            let replacement = goods
+                .into_iter()
+                .enumerate()
+                .map(|(i, parameters)| {
+                    let parameters = Vec::from(parameters);
+                    if i < len {
+                        EventCommand {
+                            parameters,
+                            ..commands[index + i].clone()
+                        }
+                    } else {
+                        new_command(SHOP_GOODS, indent, parameters)
+                    }
+                })
+                .collect_vec();
+            commands.splice(index..index + len, replacement);
+        }
+
+        modified
+    }
+}
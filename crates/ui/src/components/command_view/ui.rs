@@ -22,14 +22,398 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::collections::HashSet;
+
+use luminol_core::validation::{missing_jump_targets, JUMP_TO_LABEL, LABEL};
+use luminol_data::commands::{CommandDescription, CommandKind};
+use luminol_data::rpg::{self, EventCommand};
+
+use super::inline;
+use super::label::warning_icon;
+
+const COMMENT: u16 = 108;
+
 impl super::CommandView {
-    #[allow(clippy::ptr_arg)]
+    /// Shows a dropdown of the comments and labels in `commands`, which eventers use to mark
+    /// sections of an event. Picking one selects it and scrolls the list to it.
+    pub fn outline_ui(&mut self, ui: &mut egui::Ui, commands: &[EventCommand]) {
+        let entries = commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| matches!(c.code, COMMENT | LABEL))
+            .map(|(i, c)| {
+                let text = c.parameters.first().and_then(|p| p.as_string());
+                let text = text.map_or("", String::as_str);
+                let text = if c.code == COMMENT {
+                    comment_style(ui, egui::RichText::new(text))
+                } else {
+                    egui::RichText::new(format!("Label: {text}"))
+                };
+                (i, text)
+            })
+            .collect::<Vec<_>>();
+
+        ui.add_enabled_ui(!entries.is_empty(), |ui| {
+            egui::ComboBox::from_id_source(self.id.with("outline"))
+                .selected_text("Outline")
+                .show_ui(ui, |ui| {
+                    for (index, text) in entries {
+                        if ui
+                            .selectable_label(self.selected_index == index, text)
+                            .clicked()
+                        {
+                            self.scroll_to = Some(index);
+                        }
+                    }
+                });
+        });
+    }
+
+    /// Display `commands` as a list, with guides for each indent level and a chevron on every
+    /// branch to collapse it. Rows are tinted with the color of their command's category.
+    ///
+    /// Which branches are collapsed is kept in egui memory by command guid. Hovering a command
+    /// shows its description and parameters, with switch and variable names from `system`.
+    ///
+    /// The selected command can be edited right in the list if it has a single simple parameter,
+    /// or is a multiline command. Returns whether any command was changed or inserted. Double
+    /// clicking a command opens it in [`Self::editor_window`] instead.
+    ///
+    /// The most used commands have buttons above the list to insert them, see
+    /// [`Self::FAVORITE_COMMANDS`].
     pub fn ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _db: &luminol_config::command_db::CommandDB,
-        _commands: &mut Vec<luminol_data::rpg::EventCommand>,
-    ) {
-        todo!()
+        ui: &mut egui::Ui,
+        db: &luminol_config::command_db::CommandDB,
+        system: &rpg::System,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let collapsed_id = self.id.with("collapsed");
+        let mut collapsed: HashSet<u16> = ui.data(|d| d.get_temp(collapsed_id)).unwrap_or_default();
+
+        let inserted = self.favorites_ui(ui, db, commands);
+        self.inline.begin_frame();
+
+        let scroll_to = self.scroll_to.take();
+        if let Some(index) = scroll_to.filter(|i| *i < commands.len()) {
+            self.selected_index = index;
+            expand_ancestors(commands, index, &mut collapsed);
+        }
+        let missing_jump_targets: HashSet<usize> = missing_jump_targets(commands)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut index = 0;
+        while index < commands.len() {
+            let command = &commands[index];
+            let description = db.get(command.code);
+            let tint = db.category_color(command.code);
+            let collapsed_end = collapsed_end(db, commands, index);
+            let is_collapsed = collapsed_end.is_some() && collapsed.contains(&command.guid);
+            let is_selected = self.selected_index == index;
+            let inline_parameter = description
+                .filter(|_| is_selected && command.code != 0)
+                .and_then(inline::inline_parameter)
+                .filter(|(i, _)| command.parameters.get(*i).is_some());
+
+            let is_named = matches!(command.code, LABEL | JUMP_TO_LABEL);
+            let text = match description {
+                _ if command.code == 0 => egui::RichText::new("@>").weak(),
+                Some(description) => match (&description.kind, command.parameters.first()) {
+                    (CommandKind::Multi { .. }, _) if inline_parameter.is_some() => {
+                        egui::RichText::new(format!("{}:", description.name))
+                    }
+                    (CommandKind::Multi { .. }, Some(parameter)) => egui::RichText::new(format!(
+                        "{}: {}",
+                        description.name,
+                        parameter.as_string().map_or("", String::as_str)
+                    )),
+                    (_, Some(parameter)) if is_named && inline_parameter.is_none() => {
+                        egui::RichText::new(format!(
+                            "{}: {}",
+                            description.name,
+                            parameter.as_string().map_or("", String::as_str)
+                        ))
+                    }
+                    _ => egui::RichText::new(&description.name),
+                },
+                None => egui::RichText::new(format!("Command {}", command.code)),
+            };
+            let text = if command.code == COMMENT {
+                comment_style(ui, text)
+            } else {
+                text
+            };
+
+            let (response, toggled) = row_ui(
+                ui,
+                command.indent,
+                tint,
+                collapsed_end.map(|_| is_collapsed),
+                |ui| {
+                    let response = ui.add(egui::SelectableLabel::new(is_selected, text));
+                    if let Some(parameter) = inline_parameter {
+                        ui.add_space(4.);
+                        self.inline.ui(ui, system, index, command, parameter);
+                    }
+                    if missing_jump_targets.contains(&index) {
+                        ui.add_space(4.);
+                        warning_icon(ui).on_hover_text("This event has no label with this name");
+                    }
+                    response
+                },
+            );
+            if scroll_to == Some(index) {
+                response.scroll_to_me(Some(egui::Align::Center));
+            }
+            let response = match description.filter(|_| command.code != 0) {
+                Some(description) => {
+                    response.on_hover_ui(|ui| hover_ui(ui, description, command, system))
+                }
+                None => response,
+            };
+            if response.clicked() {
+                self.selected_index = index;
+            }
+            if response.double_clicked() && command.code != 0 {
+                self.edit(command.guid);
+            }
+            if toggled && !collapsed.remove(&command.guid) {
+                collapsed.insert(command.guid);
+            }
+
+            if let Some(end) = collapsed_end.filter(|_| is_collapsed) {
+                let hidden = end - index - 1;
+                row_ui(ui, command.indent + 1, None, None, |ui| {
+                    ui.weak(if hidden == 1 {
+                        "1 command hidden".to_string()
+                    } else {
+                        format!("{hidden} commands hidden")
+                    })
+                });
+                index = end;
+                continue;
+            }
+
+            // Multiline commands continue onto commands that are shown as part of them
+            if let Some(CommandKind::Multi { code, .. }) = description.map(|d| &d.kind) {
+                let indent = command.indent;
+                let mut next = index + 1;
+                while let Some(line) = commands.get(next).filter(|c| c.code == *code) {
+                    let text = line.parameters.first().and_then(|p| p.as_string());
+                    let (response, _) = row_ui(ui, indent, tint, None, |ui| {
+                        if let Some(parameter) = inline_parameter {
+                            let response = ui.add(egui::SelectableLabel::new(is_selected, ":"));
+                            ui.add_space(4.);
+                            self.inline.ui(ui, system, next, line, parameter);
+                            response
+                        } else {
+                            let text = egui::RichText::new(format!(
+                                ": {}",
+                                text.map_or("", String::as_str)
+                            ));
+                            let text = if command.code == COMMENT {
+                                comment_style(ui, text)
+                            } else {
+                                text
+                            };
+                            ui.add(egui::SelectableLabel::new(is_selected, text))
+                        }
+                    });
+                    if response.clicked() {
+                        self.selected_index = index;
+                    }
+                    next += 1;
+                }
+                index = next;
+                continue;
+            }
+
+            index += Self::logical_len(commands, index);
+        }
+
+        ui.data_mut(|d| d.insert_temp(collapsed_id, collapsed));
+
+        self.inline.end_frame(commands) || inserted
+    }
+
+    /// Shows a button for each of [`Self::FAVORITE_COMMANDS`], which inserts that command before
+    /// the selected one and opens it in the editor window.
+    ///
+    /// Returns whether a command was inserted.
+    fn favorites_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        db: &luminol_config::command_db::CommandDB,
+        commands: &mut Vec<EventCommand>,
+    ) -> bool {
+        let mut inserted = false;
+        ui.horizontal_wrapped(|ui| {
+            for code in Self::FAVORITE_COMMANDS {
+                let name = db
+                    .get(code)
+                    .map_or_else(|| format!("Command {code}"), |d| d.name.clone());
+                if !ui.small_button(name).clicked() {
+                    continue;
+                }
+
+                let index = self.selected_index.min(commands.len().saturating_sub(1));
+                let indent = commands.get(index).map_or(0, |c| c.indent);
+                let command = super::new_command(
+                    code,
+                    indent,
+                    super::InventoryCommandEditor::default_parameters(code),
+                );
+                self.edit(command.guid);
+                commands.insert(index, command);
+                self.selected_index = index;
+                inserted = true;
+            }
+        });
+        ui.separator();
+        inserted
     }
 }
+
+/// Comments are shown in green italics so they stand out as section markers.
+fn comment_style(ui: &egui::Ui, text: egui::RichText) -> egui::RichText {
+    let color = if ui.visuals().dark_mode {
+        egui::Color32::from_rgb(120, 200, 120)
+    } else {
+        egui::Color32::from_rgb(0, 128, 0)
+    };
+    text.italics().color(color)
+}
+
+/// Expands every branch the command at `index` is inside of, so it can be seen.
+fn expand_ancestors(commands: &[EventCommand], index: usize, collapsed: &mut HashSet<u16>) {
+    let mut indent = commands[index].indent;
+    for command in commands[..index].iter().rev() {
+        if command.indent < indent {
+            collapsed.remove(&command.guid);
+            indent = command.indent;
+        }
+    }
+}
+
+/// The tooltip of a command: its name and description from the command database, followed by
+/// the current value of each of its parameters.
+fn hover_ui(
+    ui: &mut egui::Ui,
+    description: &CommandDescription,
+    command: &EventCommand,
+    system: &rpg::System,
+) {
+    ui.strong(&description.name);
+    if !description.description.is_empty() {
+        ui.label(&description.description);
+    }
+
+    let parameters = description.describe_parameters(&command.parameters);
+    if parameters.is_empty() {
+        return;
+    }
+
+    ui.separator();
+    egui::Grid::new("command_view_hover_parameters")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for parameter in parameters {
+                ui.weak(parameter.name);
+                ui.label(parameter.value_text(system));
+                ui.end_row();
+            }
+        });
+}
+
+/// Shows one row of the command list. `chevron` is whether the row is collapsed, if it is a
+/// branch that can be.
+///
+/// Returns the response of the row's contents and whether the chevron was clicked.
+fn row_ui(
+    ui: &mut egui::Ui,
+    indent: usize,
+    tint: Option<egui::Color32>,
+    chevron: Option<bool>,
+    add_contents: impl FnOnce(&mut egui::Ui) -> egui::Response,
+) -> (egui::Response, bool) {
+    let indent_width = ui.spacing().indent;
+    let row_height = ui.spacing().interact_size.y;
+    let background = ui.painter().add(egui::Shape::Noop);
+
+    let mut toggled = false;
+    let inner = ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.;
+        let (guides_rect, _) = ui.allocate_exact_size(
+            egui::vec2(indent_width * indent as f32, row_height),
+            egui::Sense::hover(),
+        );
+
+        let (_, chevron_response) =
+            ui.allocate_exact_size(egui::vec2(indent_width, row_height), egui::Sense::click());
+        if let Some(is_collapsed) = chevron {
+            toggled = chevron_response.clicked();
+            let openness = if is_collapsed { 0. } else { 1. };
+            egui::collapsing_header::paint_default_icon(ui, openness, &chevron_response);
+        }
+
+        (guides_rect, add_contents(ui))
+    });
+    let (guides_rect, response) = inner.inner;
+
+    // Extend everything over the spacing between rows so the guides and tints are continuous
+    let y_range = inner
+        .response
+        .rect
+        .y_range()
+        .expand(ui.spacing().item_spacing.y / 2.);
+
+    if let Some(tint) = tint {
+        let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), y_range);
+        ui.painter()
+            .set(background, egui::Shape::rect_filled(rect, 0., tint));
+    }
+
+    let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+    for level in 0..indent {
+        let x = guides_rect.left() + indent_width * (level as f32 + 0.5);
+        ui.painter().vline(x, y_range, stroke);
+    }
+
+    (response, toggled)
+}
+
+/// If the command at `index` starts a branch, returns the index of the first command after it
+/// that is still shown when the branch is collapsed. That is the end command of the branch if
+/// the command database knows it, otherwise the next command that isn't indented further.
+fn collapsed_end(
+    db: &luminol_config::command_db::CommandDB,
+    commands: &[EventCommand],
+    index: usize,
+) -> Option<usize> {
+    let command = &commands[index];
+    if !commands
+        .get(index + 1)
+        .is_some_and(|next| next.indent > command.indent)
+    {
+        return None;
+    }
+
+    let end_code = match db.get(command.code).map(|d| &d.kind) {
+        Some(CommandKind::Branch { end_code, .. }) => Some(*end_code),
+        _ => None,
+    };
+    let rest = &commands[index + 1..];
+    let offset = rest
+        .iter()
+        .position(|c| match end_code {
+            Some(end_code) => {
+                c.indent < command.indent || (c.indent == command.indent && c.code == end_code)
+            }
+            None => c.indent <= command.indent,
+        })
+        .unwrap_or(rest.len());
+    Some(index + 1 + offset)
+}
@@ -0,0 +1,210 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_data::battle_calc::{self, Amount, Battler};
+use luminol_data::rpg;
+
+/// Previews how much a skill or item deals or heals with RPG Maker XP's default battle formulas.
+pub struct DamageCalculator {
+    user: BattlerChoice,
+    target: BattlerChoice,
+}
+
+/// The database entries needed to work out the parameters of actors and enemies.
+pub struct BattlerData<'a> {
+    pub actors: &'a [rpg::Actor],
+    pub classes: &'a [rpg::Class],
+    pub enemies: &'a [rpg::Enemy],
+    pub weapons: &'a [rpg::Weapon],
+    pub armors: &'a [rpg::Armor],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BattlerChoice {
+    /// An actor at a level, with their initial equipment
+    Actor {
+        id: usize,
+        level: usize,
+    },
+    Enemy {
+        id: usize,
+    },
+}
+
+const DISCLAIMER: &str =
+    "Calculated with the default battle formulas, leaving out states, guarding \
+    and critical hits. Games with custom battle scripts will differ.";
+
+impl Default for DamageCalculator {
+    fn default() -> Self {
+        Self {
+            user: BattlerChoice::Actor { id: 0, level: 1 },
+            target: BattlerChoice::Enemy { id: 0 },
+        }
+    }
+}
+
+impl DamageCalculator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Shows the damage `skill` deals, with pickers for its user and target.
+    pub fn skill_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        data: &BattlerData<'_>,
+        skill: &rpg::Skill,
+    ) {
+        let id = egui::Id::new("luminol_damage_calculator").with(id_source);
+        Self::battler_ui(ui, id.with("user"), "User", &mut self.user, data);
+        Self::battler_ui(ui, id.with("target"), "Target", &mut self.target, data);
+
+        let (Some(user), Some(target)) = (self.user.battler(data), self.target.battler(data))
+        else {
+            ui.weak("Choose a user and a target");
+            return;
+        };
+
+        let damage = battle_calc::skill_damage(skill, &user, &target);
+        if damage.max < 0 {
+            let healing = Amount {
+                min: -damage.max,
+                max: -damage.min,
+            };
+            ui.label(format!("Healing: {}", format_amount(healing)));
+        } else {
+            ui.label(format!("Damage: {}", format_amount(damage)));
+        }
+        ui.weak(DISCLAIMER);
+    }
+
+    /// Shows the HP and SP `item` restores, with a picker for its target.
+    pub fn item_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        data: &BattlerData<'_>,
+        item: &rpg::Item,
+    ) {
+        let id = egui::Id::new("luminol_damage_calculator").with(id_source);
+        Self::battler_ui(ui, id.with("target"), "Target", &mut self.target, data);
+
+        let Some(target) = self.target.battler(data) else {
+            ui.weak("Choose a target");
+            return;
+        };
+
+        let (hp, sp) = battle_calc::item_recovery(item, &target);
+        ui.label(format!("HP recovered: {}", format_amount(hp)));
+        ui.label(format!("SP recovered: {}", format_amount(sp)));
+        ui.weak(DISCLAIMER);
+    }
+
+    fn battler_ui(
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        label: &str,
+        choice: &mut BattlerChoice,
+        data: &BattlerData<'_>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+
+            let is_actor = matches!(choice, BattlerChoice::Actor { .. });
+            egui::ComboBox::from_id_source(id.with("kind"))
+                .selected_text(if is_actor { "Actor" } else { "Enemy" })
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(is_actor, "Actor").clicked() && !is_actor {
+                        *choice = BattlerChoice::Actor { id: 0, level: 1 };
+                    }
+                    if ui.selectable_label(!is_actor, "Enemy").clicked() && is_actor {
+                        *choice = BattlerChoice::Enemy { id: 0 };
+                    }
+                });
+
+            match choice {
+                BattlerChoice::Actor {
+                    id: actor_id,
+                    level,
+                } => {
+                    Self::entry_combo_box(ui, id, actor_id, data.actors, |actor| &actor.name);
+                    ui.add(egui::DragValue::new(level).range(1..=99).prefix("Level: "));
+                }
+                BattlerChoice::Enemy { id: enemy_id } => {
+                    Self::entry_combo_box(ui, id, enemy_id, data.enemies, |enemy| &enemy.name);
+                }
+            }
+        });
+    }
+
+    fn entry_combo_box<T>(
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        selected: &mut usize,
+        entries: &[T],
+        name: impl Fn(&T) -> &String,
+    ) {
+        egui::ComboBox::from_id_source(id.with("entry"))
+            .selected_text(entries.get(*selected).map_or_else(
+                || "".into(),
+                |entry| format!("{:0>4}: {}", *selected + 1, name(entry)),
+            ))
+            .show_ui(ui, |ui| {
+                for (index, entry) in entries.iter().enumerate() {
+                    ui.selectable_value(
+                        selected,
+                        index,
+                        format!("{:0>4}: {}", index + 1, name(entry)),
+                    );
+                }
+            });
+    }
+}
+
+impl BattlerChoice {
+    fn battler(self, data: &BattlerData<'_>) -> Option<Battler> {
+        match self {
+            Self::Actor { id, level } => data.actors.get(id).map(|actor| {
+                Battler::from_actor(
+                    actor,
+                    data.classes.get(actor.class_id),
+                    level,
+                    data.weapons,
+                    data.armors,
+                )
+            }),
+            Self::Enemy { id } => data.enemies.get(id).map(Battler::from_enemy),
+        }
+    }
+}
+
+fn format_amount(amount: Amount) -> String {
+    if amount.min == amount.max {
+        amount.min.to_string()
+    } else {
+        format!("{} to {}", amount.min, amount.max)
+    }
+}
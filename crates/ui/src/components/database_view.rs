@@ -22,9 +22,14 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use std::collections::VecDeque;
+
 use super::UiExt;
 use itertools::Itertools;
 
+/// The maximum number of edits that can be undone in a database view.
+const HISTORY_SIZE: usize = 50;
+
 pub struct DatabaseViewResponse<R> {
     /// The returned value of the `inner` closure passed to `show` if the editor pane was rendered,
     /// otherwise `None`.
@@ -38,6 +43,21 @@ pub struct DatabaseView {
     show_called_at_least_once: bool,
     selected_id: usize,
     maximum: Option<usize>,
+    /// Asks before lowering the maximum, since that deletes entries
+    confirmation: Option<luminol_core::Confirmation>,
+    history: History,
+}
+
+/// The undo and redo stacks of a database view. Entries are kept as Marshal data since most
+/// database types can't be cloned.
+#[derive(Default)]
+struct History {
+    /// The ID and data of the selected entry from before the current edit started.
+    snapshot: Option<(usize, Vec<u8>)>,
+    undo: VecDeque<(usize, Vec<u8>)>,
+    redo: Vec<(usize, Vec<u8>)>,
+    /// Whether the data may have changed since the snapshot was taken.
+    dirty: bool,
 }
 
 impl DatabaseView {
@@ -45,6 +65,16 @@ impl DatabaseView {
         Default::default()
     }
 
+    pub fn selected_id(&self) -> usize {
+        self.selected_id
+    }
+
+    /// Makes the next database view of entries of type `T` that is shown select the entry with
+    /// this ID and scroll to it.
+    pub fn select_later<T: 'static>(ctx: &egui::Context, id: usize) {
+        ctx.data_mut(|d| d.insert_temp(pending_selection_id::<T>(), id));
+    }
+
     pub fn show<T, R>(
         &mut self,
         ui: &mut egui::Ui,
@@ -55,7 +85,8 @@ impl DatabaseView {
         inner: impl FnOnce(&mut egui::Ui, &mut Vec<T>, usize, &mut luminol_core::UpdateState<'_>) -> R,
     ) -> egui::InnerResponse<DatabaseViewResponse<R>>
     where
-        T: luminol_data::rpg::DatabaseEntry,
+        T: luminol_data::rpg::DatabaseEntry + 'static,
+        T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
     {
         let mut modified = false;
 
@@ -69,6 +100,27 @@ impl DatabaseView {
         if self.maximum.is_none() {
             self.maximum = Some(vec.len());
         }
+        let pending_selection =
+            ui.data_mut(|d| d.remove_temp::<usize>(pending_selection_id::<T>()));
+        if let Some(id) = pending_selection {
+            self.selected_id = id;
+        }
+
+        // An edit is committed to the undo history once the text field or the dragged widget
+        // that made it is let go of
+        if *update_state.modified_during_prev_frame {
+            self.history.dirty = true;
+        }
+        if !is_editing(ui) {
+            self.history.commit(vec, self.selected_id);
+        }
+
+        let confirmation = self.confirmation.get_or_insert_with(|| {
+            luminol_core::Confirmation::new(
+                ui.ctx(),
+                format!("luminol_database_view_confirmation_{}", ui.id().value()),
+            )
+        });
 
         let button_height = ui.spacing().interact_size.y.max(
             ui.text_style_height(&egui::TextStyle::Button) + 2. * ui.spacing().button_padding.y,
@@ -92,13 +144,16 @@ impl DatabaseView {
                                     )
                                     .clicked()
                                 {
-                                    modified = true;
-                                    let mut index = vec.len();
-                                    vec.resize_with(self.maximum.unwrap(), || {
-                                        let item = T::default_with_id(index);
-                                        index += 1;
-                                        item
-                                    });
+                                    let maximum = self.maximum.unwrap();
+                                    if maximum >= vec.len()
+                                        || update_state.confirm_destructive(
+                                            confirmation,
+                                            luminol_config::global::DestructiveAction::ShrinkDatabase,
+                                        )
+                                    {
+                                        modified = true;
+                                        set_maximum(vec, maximum);
+                                    }
                                 };
                             });
 
@@ -181,7 +236,18 @@ impl DatabaseView {
                                             );
                                         }
 
-                                        egui::ScrollArea::vertical().id_source(p).show_rows(
+                                        let mut scroll_area =
+                                            egui::ScrollArea::vertical().id_source(p);
+                                        if let Some(row) = pending_selection.and_then(|id| {
+                                            search_matched_ids.iter().position(|&i| i == id)
+                                        }) {
+                                            scroll_area = scroll_area.vertical_scroll_offset(
+                                                row as f32
+                                                    * (button_height
+                                                        + ui.spacing().item_spacing.y),
+                                            );
+                                        }
+                                        scroll_area.show_rows(
                                             ui,
                                             button_height,
                                             search_matched_ids.len(),
@@ -248,29 +314,189 @@ impl DatabaseView {
             });
         });
 
-        ui.with_left_margin(ui.spacing().window_margin.left, |ui| {
-            ui.with_cross_justify(|ui| {
-                egui::ScrollArea::vertical()
-                    .id_source(p)
-                    .show(ui, |ui| {
-                        ui.set_width(ui.available_width());
-                        ui.set_min_width(
-                            2. * (ui.spacing().slider_width + ui.spacing().interact_size.x)
+        if let Some(luminol_config::global::DestructiveAction::ShrinkDatabase) =
+            confirmation.show(update_state.global_config)
+        {
+            modified = true;
+            set_maximum(vec, self.maximum.unwrap());
+        }
+
+        let mut response = ui
+            .with_left_margin(ui.spacing().window_margin.left, |ui| {
+                ui.with_cross_justify(|ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source(p)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.set_min_width(
+                                2. * (ui.spacing().slider_width + ui.spacing().interact_size.x)
                                 + ui.spacing().indent
                                 + 12. // `egui::Frame::group` inner margins are hardcoded to 6
                                       // points on each side
                                 + 5. * ui.spacing().item_spacing.x,
-                        );
-
-                        DatabaseViewResponse {
-                            inner: (self.selected_id < vec.len())
-                                .then(|| inner(ui, vec, self.selected_id, update_state)),
-                            modified,
-                        }
-                    })
-                    .inner
+                            );
+
+                            DatabaseViewResponse {
+                                inner: (self.selected_id < vec.len())
+                                    .then(|| inner(ui, vec, self.selected_id, update_state)),
+                                modified,
+                            }
+                        })
+                        .inner
+                })
             })
-        })
-        .inner
+            .inner;
+
+        if self.history_shortcuts(ui, vec) {
+            response.inner.modified = true;
+        }
+        response
     }
+
+    /// Undoes or redoes an edit if Ctrl+Z, Ctrl+Y or Ctrl+Shift+Z was pressed while the mouse is
+    /// over this window. Returns whether an entry was changed.
+    fn history_shortcuts<T>(&mut self, ui: &mut egui::Ui, vec: &mut [T]) -> bool
+    where
+        T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+    {
+        let is_hovered = ui
+            .input(|i| i.pointer.latest_pos())
+            .and_then(|pos| ui.ctx().layer_id_at(pos))
+            == Some(ui.layer_id());
+        if !is_hovered || is_editing(ui) {
+            return false;
+        }
+
+        let (undo, redo) = ui.input_mut(|i| {
+            let redo = i.consume_key(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::Z,
+            ) || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+            let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+            (undo, redo)
+        });
+        if !undo && !redo {
+            return false;
+        }
+
+        // Record anything that was edited since the last commit first, so that it can be redone
+        self.history.dirty = true;
+        self.history.commit(vec, self.selected_id);
+        match self.history.step(vec, redo) {
+            Some(id) => {
+                self.selected_id = id;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl History {
+    /// Pushes the snapshot onto the undo stack if its entry was changed, then snapshots the
+    /// selected entry.
+    fn commit<T: alox_48::Serialize>(&mut self, vec: &[T], selected_id: usize) {
+        let is_same_entry = self
+            .snapshot
+            .as_ref()
+            .is_some_and(|(id, _)| *id == selected_id);
+        if !self.dirty && is_same_entry {
+            return;
+        }
+
+        if let Some((id, old)) = self.snapshot.take() {
+            if vec
+                .get(id)
+                .and_then(to_marshal)
+                .is_some_and(|new| new != old)
+            {
+                self.redo.clear();
+                self.push_undo(id, old);
+            }
+        }
+        self.snapshot = vec
+            .get(selected_id)
+            .and_then(to_marshal)
+            .map(|data| (selected_id, data));
+        self.dirty = false;
+    }
+
+    fn push_undo(&mut self, id: usize, data: Vec<u8>) {
+        while self.undo.len() >= HISTORY_SIZE {
+            self.undo.pop_front();
+        }
+        self.undo.push_back((id, data));
+    }
+
+    /// Restores the last entry of the undo stack, or of the redo stack if `redo` is true, and
+    /// returns its ID.
+    fn step<T>(&mut self, vec: &mut [T], redo: bool) -> Option<usize>
+    where
+        T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+    {
+        loop {
+            let (id, data) = if redo {
+                self.redo.pop()?
+            } else {
+                self.undo.pop_back()?
+            };
+            // Entries removed by lowering the maximum can't be restored
+            let Some(entry) = vec.get_mut(id) else {
+                continue;
+            };
+            let (Some(current), Some(restored)) = (to_marshal(entry), from_marshal(&data)) else {
+                continue;
+            };
+
+            *entry = restored;
+            if redo {
+                self.push_undo(id, current);
+            } else {
+                self.redo.push((id, current));
+            }
+            self.snapshot = Some((id, data));
+            self.dirty = false;
+            return Some(id);
+        }
+    }
+}
+
+/// Whether a text field has focus or a widget is being dragged, in which case the current edit
+/// isn't finished yet.
+fn is_editing(ui: &egui::Ui) -> bool {
+    ui.input(|i| i.pointer.any_down())
+        || ui
+            .memory(|m| m.focused())
+            .is_some_and(|id| egui::TextEdit::load_state(ui.ctx(), id).is_some())
+}
+
+fn to_marshal<T: alox_48::Serialize>(entry: &T) -> Option<Vec<u8>> {
+    let mut serializer = alox_48::Serializer::new();
+    alox_48::path_to_error::serialize(entry, &mut serializer).ok()?;
+    Some(serializer.output)
+}
+
+fn from_marshal<T>(data: &[u8]) -> Option<T>
+where
+    T: for<'de> alox_48::Deserialize<'de>,
+{
+    let mut de = alox_48::Deserializer::new(data).ok()?;
+    alox_48::path_to_error::deserialize(&mut de).ok()
+}
+
+fn pending_selection_id<T: 'static>() -> egui::Id {
+    egui::Id::new("luminol_database_view_pending_selection").with(std::any::TypeId::of::<T>())
+}
+
+/// Adds or removes entries at the end of `vec` so that it has `maximum` entries.
+fn set_maximum<T>(vec: &mut Vec<T>, maximum: usize)
+where
+    T: luminol_data::rpg::DatabaseEntry,
+{
+    let mut index = vec.len();
+    vec.resize_with(maximum, || {
+        let item = T::default_with_id(index);
+        index += 1;
+        item
+    });
 }
@@ -23,6 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use super::UiExt;
+#[cfg(not(target_arch = "wasm32"))]
+use color_eyre::eyre::WrapErr;
 use itertools::Itertools;
 
 pub struct FileSystemView<T> {
@@ -35,6 +37,11 @@ pub struct FileSystemView<T> {
     pivot_id: Option<indextree::NodeId>,
     pivot_visited: bool,
     show_tooltip: bool,
+    /// The host folder shown by this view, if it shows one. Its files can be opened with external
+    /// programs (or downloaded on web) from their context menus.
+    host: Option<luminol_filesystem::host::FileSystem>,
+    #[cfg(target_arch = "wasm32")]
+    download_promise: Option<poll_promise::Promise<luminol_filesystem::Result<()>>>,
 }
 
 #[derive(Debug)]
@@ -110,9 +117,19 @@ where
             pivot_id: None,
             pivot_visited: false,
             show_tooltip: true,
+            host: None,
+            #[cfg(target_arch = "wasm32")]
+            download_promise: None,
         }
     }
 
+    /// Lets the files in this view be opened with the external programs set in the preferences,
+    /// or downloaded on web. `host` should be the host folder this view shows.
+    pub fn with_host(mut self, host: luminol_filesystem::host::FileSystem) -> Self {
+        self.host = Some(host);
+        self
+    }
+
     pub fn filesystem(&self) -> &T {
         &self.filesystem
     }
@@ -139,6 +156,18 @@ where
         self.row_index = 0;
         self.pivot_visited = false;
 
+        #[cfg(target_arch = "wasm32")]
+        if let Some(p) = self.download_promise.take() {
+            match p.try_take() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err("Error downloading a copy of a file")
+                ),
+                Err(p) => self.download_promise = Some(p),
+            }
+        }
+
         let response = egui::Frame::none().show(ui, |ui| {
             self.render_subtree(
                 ui,
@@ -183,17 +212,9 @@ where
             let selected = *selected;
             *initialized = true;
 
-            let mut ancestors = node_id
-                .ancestors(&self.arena)
-                .filter_map(|n| {
-                    let name = self.arena[n].get().name();
-                    (!name.is_empty()).then_some(name)
-                })
-                .collect_vec();
-            ancestors.reverse();
-            let path = ancestors.join("/");
-
-            let mut subentries = self.filesystem.read_dir(&path).unwrap_or_else(|e| {
+            let path = self.path(node_id);
+
+            let subentries = self.filesystem.read_dir(&path).unwrap_or_else(|e| {
                 luminol_core::error!(
                     update_state.toasts,
                     e.wrap_err(format!(
@@ -202,17 +223,6 @@ where
                 );
                 Vec::new()
             });
-            subentries.sort_unstable_by(|a, b| {
-                if a.metadata.is_file && !b.metadata.is_file {
-                    std::cmp::Ordering::Greater
-                } else if b.metadata.is_file && !a.metadata.is_file {
-                    std::cmp::Ordering::Less
-                } else {
-                    let path_a = a.path.iter().next_back().unwrap();
-                    let path_b = b.path.iter().next_back().unwrap();
-                    lexical_sort::natural_lexical_cmp(path_a, path_b)
-                }
-            });
             length = Some(subentries.len());
 
             for subentry in subentries {
@@ -271,14 +281,32 @@ where
 
         let mut header_response = None;
 
+        let file_path = (self.host.is_some()
+            && matches!(self.arena[node_id].get(), Entry::File { .. }))
+        .then(|| self.path(node_id));
+
         match self.arena[node_id].get_mut() {
             Entry::File { name, selected } => {
                 ui.with_stripe(is_faint, |ui| {
                     ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
 
-                    if ui.selectable_label(*selected, name.to_string()).clicked() {
+                    let response = ui.selectable_label(*selected, name.to_string());
+                    if response.clicked() {
                         should_toggle = true;
                     };
+
+                    if let (Some(host), Some(file_path)) = (&self.host, &file_path) {
+                        response.context_menu(|ui| {
+                            external_program_menu(
+                                ui,
+                                update_state,
+                                host,
+                                camino::Utf8Path::new(file_path),
+                                #[cfg(target_arch = "wasm32")]
+                                &mut self.download_promise,
+                            );
+                        });
+                    }
                 });
             }
             Entry::Dir {
@@ -438,6 +466,19 @@ where
         }
     }
 
+    /// Returns the path of an entry relative to the root of the filesystem.
+    fn path(&self, node_id: indextree::NodeId) -> String {
+        let mut ancestors = node_id
+            .ancestors(&self.arena)
+            .filter_map(|n| {
+                let name = self.arena[n].get().name();
+                (!name.is_empty()).then_some(name)
+            })
+            .collect_vec();
+        ancestors.reverse();
+        ancestors.join("/")
+    }
+
     fn toggle(&mut self, node_id: indextree::NodeId) {
         match self.arena[node_id].get() {
             Entry::File { selected, .. } => {
@@ -629,6 +670,70 @@ where
     }
 }
 
+/// The context menu entries for a file in a host folder.
+fn external_program_menu(
+    ui: &mut egui::Ui,
+    update_state: &mut luminol_core::UpdateState<'_>,
+    host: &luminol_filesystem::host::FileSystem,
+    path: &camino::Utf8Path,
+    #[cfg(target_arch = "wasm32")] download_promise: &mut Option<
+        poll_promise::Promise<luminol_filesystem::Result<()>>,
+    >,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let extension = path.extension().unwrap_or_default().to_lowercase();
+        let Some(program) = update_state
+            .global_config
+            .external_programs
+            .get(&extension)
+            .cloned()
+        else {
+            ui.add_enabled(false, egui::Button::new("Open with external program"))
+                .on_disabled_hover_text(format!(
+                    "Set a program for .{extension} files in Preferences > Editor Settings"
+                ));
+            return;
+        };
+
+        if ui.button(format!("Open with {program}")).clicked() {
+            ui.close_menu();
+
+            let result = std::fs::canonicalize(host.root_path().join(path))
+                .wrap_err("Error resolving the absolute path of the file")
+                .and_then(|absolute_path| {
+                    std::process::Command::new(&program)
+                        .arg(absolute_path)
+                        .spawn()
+                        .wrap_err("Error starting the program")
+                });
+            if let Err(e) = result {
+                luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err(format!("Error opening {path} with {program}"))
+                );
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use luminol_filesystem::FileSystem;
+
+        let _ = update_state;
+        if ui.button("Download a copy").clicked() {
+            ui.close_menu();
+
+            let host = host.clone();
+            let path = path.to_owned();
+            *download_promise = Some(luminol_core::spawn_future(async move {
+                let file = host.open_file(&path, luminol_filesystem::OpenFlags::Read)?;
+                file.save(path.file_name().unwrap_or_default(), "").await
+            }));
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Metadata {
     pub path: String,
@@ -0,0 +1,205 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use luminol_core::prelude::*;
+
+/// The directory icons are read from.
+pub const ICON_DIRECTORY: &str = "Graphics/Icons";
+
+/// The width and height of a cell in the grid. RPG Maker XP icons are 24×24.
+const CELL_SIZE: f32 = 32.;
+
+/// Shows a context menu for the icon with the given name.
+pub type ContextMenu<'a> = &'a mut dyn FnMut(&mut egui::Ui, &camino::Utf8Path);
+
+/// A searchable grid of every icon in `Graphics/Icons`.
+#[derive(Default)]
+pub struct IconGrid {
+    /// The file names of every icon, read the first time the grid is shown.
+    entries: Option<Vec<camino::Utf8PathBuf>>,
+    /// Indices into `entries` that match the search text.
+    filtered_entries: Vec<usize>,
+    search_text: String,
+
+    /// Icons are loaded the first time they are scrolled into view.
+    textures: HashMap<camino::Utf8PathBuf, Option<Arc<Texture>>>,
+}
+
+impl IconGrid {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Forgets the icons read so far, so they are read from the filesystem again the next time
+    /// the grid is shown.
+    pub fn reload(&mut self) {
+        self.entries = None;
+        self.textures.clear();
+    }
+
+    /// Shows a search box and the grid of icons.
+    ///
+    /// `selected` is the name of the selected icon, without an extension like icon names are
+    /// stored in the database. If `context_menu` is given, it is shown when an icon is
+    /// right-clicked, with the name of that icon.
+    ///
+    /// Returns the name of the icon that was clicked, if any.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &UpdateState<'_>,
+        selected: Option<&camino::Utf8Path>,
+        mut context_menu: Option<ContextMenu<'_>>,
+    ) -> Option<camino::Utf8PathBuf> {
+        let entries = self.entries.get_or_insert_with(|| {
+            let entries = update_state
+                .filesystem
+                .read_dir(ICON_DIRECTORY)
+                .map(|entries| {
+                    entries
+                        .into_iter()
+                        .filter(|entry| entry.metadata.is_file)
+                        .map(|entry| {
+                            camino::Utf8PathBuf::from(entry.path.file_name().unwrap_or_default())
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            self.filtered_entries = (0..entries.len()).collect();
+            self.search_text.clear();
+            entries
+        });
+
+        if ui
+            .add(egui::TextEdit::singleline(&mut self.search_text).hint_text("Search 🔎"))
+            .changed()
+        {
+            let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+            self.filtered_entries = (entries.iter().enumerate())
+                .filter(|(_, entry)| {
+                    matcher
+                        .fuzzy(entry.as_str(), &self.search_text, false)
+                        .is_some()
+                })
+                .map(|(index, _)| index)
+                .collect();
+        }
+
+        ui.add_space(ui.spacing().item_spacing.y);
+
+        if self.filtered_entries.is_empty() {
+            ui.weak(format!("No icons found in {ICON_DIRECTORY}"));
+            return None;
+        }
+
+        let spacing = ui.spacing().item_spacing;
+        let columns = ((ui.available_width() + spacing.x) / (CELL_SIZE + spacing.x))
+            .floor()
+            .max(1.) as usize;
+        let rows = self.filtered_entries.len().div_ceil(columns);
+
+        let mut clicked = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .show_rows(ui, CELL_SIZE, rows, |ui, row_range| {
+                for row in row_range {
+                    ui.horizontal(|ui| {
+                        for &index in self
+                            .filtered_entries
+                            .iter()
+                            .skip(row * columns)
+                            .take(columns)
+                        {
+                            let file_name = &entries[index];
+                            let name =
+                                camino::Utf8Path::new(file_name.file_stem().unwrap_or_default());
+                            let is_selected = selected
+                                .is_some_and(|s| s.as_str().eq_ignore_ascii_case(name.as_str()));
+
+                            let texture =
+                                self.textures.entry(file_name.clone()).or_insert_with(|| {
+                                    update_state
+                                        .graphics
+                                        .texture_loader
+                                        .load_now_dir(
+                                            update_state.filesystem,
+                                            ICON_DIRECTORY,
+                                            file_name,
+                                        )
+                                        .ok()
+                                });
+
+                            let response = Self::cell_ui(ui, texture.as_deref(), is_selected)
+                                .on_hover_text(file_name.as_str());
+                            if response.clicked() {
+                                clicked = Some(name.to_path_buf());
+                            }
+                            if let Some(context_menu) = context_menu.as_deref_mut() {
+                                response.context_menu(|ui| context_menu(ui, name));
+                            }
+                        }
+                    });
+                }
+            });
+
+        clicked
+    }
+
+    fn cell_ui(ui: &mut egui::Ui, texture: Option<&Texture>, is_selected: bool) -> egui::Response {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::Vec2::splat(CELL_SIZE), egui::Sense::click());
+
+        let visuals = ui.style().interact_selectable(&response, is_selected);
+        ui.painter()
+            .rect(rect, visuals.rounding, visuals.bg_fill, visuals.bg_stroke);
+
+        let inner_rect = rect.shrink(4.);
+        match texture {
+            Some(texture) => {
+                // icons bigger than the cell are scaled down, but smaller ones aren't scaled up
+                let size = texture.size_vec2();
+                let scale = (inner_rect.size() / size).min_elem().min(1.);
+                egui::Image::from_texture(egui::load::SizedTexture::from(texture)).paint_at(
+                    ui,
+                    egui::Rect::from_center_size(inner_rect.center(), size * scale),
+                );
+            }
+            None => {
+                ui.painter().text(
+                    inner_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "?",
+                    egui::FontId::default(),
+                    ui.visuals().error_fg_color,
+                );
+            }
+        }
+
+        response
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+
+/// Small images of maps with one pixel per tile, colored with the average color of the tiles
+/// stacked there. They are made on the CPU from the tileset and autotile images, so they are
+/// cheap enough to make for every map in the project.
+#[derive(Default)]
+pub struct MapThumbnails {
+    thumbnails: HashMap<usize, Option<egui::TextureHandle>>,
+    /// The average color of every tile ID in each tileset, keyed by tileset ID.
+    tile_colors: HashMap<usize, Vec<[f32; 4]>>,
+}
+
+impl MapThumbnails {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Forgets every thumbnail, so they are made again from the current data.
+    pub fn clear(&mut self) {
+        self.thumbnails.clear();
+        self.tile_colors.clear();
+    }
+
+    /// Forgets the thumbnail of one map, so that it's made again from the current data.
+    pub fn forget(&mut self, map_id: usize) {
+        self.thumbnails.remove(&map_id);
+    }
+
+    /// The thumbnail of the map with the given ID, made the first time it is asked for. `None` if
+    /// the map has no tileset.
+    pub fn get(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> Option<&egui::TextureHandle> {
+        self.thumbnails
+            .entry(map_id)
+            .or_insert_with(|| {
+                let map = update_state.data.get_or_load_map(
+                    map_id,
+                    update_state.filesystem,
+                    update_state.project_config.as_ref().unwrap(),
+                );
+                let colors = self
+                    .tile_colors
+                    .entry(map.tileset_id)
+                    .or_insert_with(|| Self::tile_colors(update_state, map.tileset_id));
+                if colors.is_empty() {
+                    return None;
+                }
+
+                let (width, height) = (map.data.xsize(), map.data.ysize());
+                let mut pixels = Vec::with_capacity(width * height);
+                for y in 0..height {
+                    for x in 0..width {
+                        // blend the layers from the bottom up
+                        let mut color = [0.; 4];
+                        for z in 0..map.data.zsize() {
+                            let tile = colors
+                                .get(map.data[(x, y, z)].max(0) as usize)
+                                .copied()
+                                .unwrap_or_default();
+                            let below = color[3];
+                            let alpha = tile[3] + below * (1. - tile[3]);
+                            if alpha > 0. {
+                                for (c, t) in color.iter_mut().zip(tile).take(3) {
+                                    *c = (t * tile[3] + *c * below * (1. - tile[3])) / alpha;
+                                }
+                            }
+                            color[3] = alpha;
+                        }
+                        pixels.push(egui::Color32::from_rgba_unmultiplied(
+                            (color[0] * 255.) as u8,
+                            (color[1] * 255.) as u8,
+                            (color[2] * 255.) as u8,
+                            (color[3] * 255.) as u8,
+                        ));
+                    }
+                }
+
+                Some(update_state.ctx.load_texture(
+                    format!("luminol_map_thumbnail_{map_id}"),
+                    egui::ColorImage {
+                        size: [width, height],
+                        pixels,
+                    },
+                    egui::TextureOptions::NEAREST,
+                ))
+            })
+            .as_ref()
+    }
+
+    /// The average color of every tile ID in a tileset: transparent for the empty tile, the whole
+    /// autotile image for autotile IDs, and the tile itself for tileset IDs.
+    fn tile_colors(
+        update_state: &luminol_core::UpdateState<'_>,
+        tileset_id: usize,
+    ) -> Vec<[f32; 4]> {
+        let tilesets = update_state.data.tilesets();
+        let Some(tileset) = tilesets.data.get(tileset_id) else {
+            return Vec::new();
+        };
+        let decode = |directory: &str, name: &str| {
+            update_state
+                .graphics
+                .texture_loader
+                .decode_image(
+                    update_state.filesystem,
+                    camino::Utf8Path::new(directory).join(name),
+                )
+                .ok()
+        };
+
+        let mut colors = vec![[0.; 4]; 48];
+        for i in 0..7 {
+            let color = (tileset.autotile_names.get(i))
+                .filter(|name| !name.is_empty())
+                .and_then(|name| decode("Graphics/Autotiles", name.as_str()))
+                .map(|image| average_color(&image, 0, 0, image.width(), image.height()))
+                .unwrap_or_default();
+            colors.resize(colors.len() + 48, color);
+        }
+
+        if let Some(image) = tileset
+            .tileset_name
+            .as_ref()
+            .and_then(|name| decode("Graphics/Tilesets", name.as_str()))
+        {
+            let rows = image.height() / 32;
+            for i in 0..rows * 8 {
+                colors.push(average_color(&image, (i % 8) * 32, (i / 8) * 32, 32, 32));
+            }
+        }
+
+        colors
+    }
+}
+
+/// The average color of a part of an image, weighted by alpha, as straight RGBA from 0 to 1.
+fn average_color(
+    image: &luminol_graphics::image::RgbaImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> [f32; 4] {
+    let mut sum = [0.; 4];
+    let mut count = 0.;
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            let [r, g, b, a] = image.get_pixel(px, py).0.map(|c| c as f32 / 255.);
+            sum[0] += r * a;
+            sum[1] += g * a;
+            sum[2] += b * a;
+            sum[3] += a;
+            count += 1.;
+        }
+    }
+    if sum[3] == 0. {
+        return [0.; 4];
+    }
+    [
+        sum[0] / sum[3],
+        sum[1] / sum[3],
+        sum[2] / sum[3],
+        sum[3] / count,
+    ]
+}
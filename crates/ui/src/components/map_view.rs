@@ -18,9 +18,12 @@
 use color_eyre::eyre::{ContextCompat, WrapErr};
 use itertools::Itertools;
 use luminol_graphics::{Drawable, Renderable};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
+const START_POSITION_ICON: &[u8] =
+    luminol_macros::include_asset!("assets/icons/start_position.png");
+
 pub struct MapView {
     /// Toggle to display the visible region in-game.
     pub visible_display: bool,
@@ -39,10 +42,23 @@ pub struct MapView {
     pub selected_layer: SelectedLayer,
     pub selected_event_id: Option<usize>,
     pub cursor_pos: egui::Pos2,
+    /// The tile moved with the arrow keys on tile layers, separately from the cursor that follows
+    /// the mouse. `None` until an arrow key is pressed, and again once the mouse moves.
+    pub keyboard_cursor: Option<egui::Pos2>,
+    /// The corner the keyboard cursor was at when shift started extending a rectangle from it
+    pub keyboard_anchor: Option<egui::Pos2>,
     pub snap_to_grid: bool,
+    /// The rectangle of tiles outlined by the select tool, from the top-left tile to the
+    /// bottom-right tile inclusive
+    pub tile_selection: Option<egui::Rect>,
+    /// The rectangle of tiles being picked up with the eyedropper, from the top-left tile to the
+    /// bottom-right tile inclusive
+    pub eyedropper_rect: Option<egui::Rect>,
 
     /// The map coordinates of the tile being hovered over
     pub hover_tile: Option<egui::Pos2>,
+    /// The map coordinates of the mouse, without rounding down to a tile
+    pub hover_pos: Option<egui::Pos2>,
 
     /// True if selected_event_id is being hovered over by the mouse
     /// (as opposed to the map cursor)
@@ -54,14 +70,103 @@ pub struct MapView {
     /// Whether to display the tile IDs on the map
     pub display_tile_ids: bool,
 
+    /// Whether to mark the tile the player starts the game on
+    pub display_start_position: bool,
+    /// Whether to show the whole map in a corner of the view
+    pub display_minimap: bool,
+    /// The part of the map that was visible last frame, in tiles from its top-left corner
+    pub visible_tiles: egui::Rect,
+    /// The tile the player starts the game on, if it's on this map
+    pub start_position: Option<egui::Pos2>,
+
     pub scale: f32,
     previous_scale: f32,
 
+    /// When true, the scale is snapped to integer multiples or fractions of the original tile size
+    /// so that every texel of the tileset covers the same number of screen pixels.
+    pub pixel_perfect: bool,
+
     /// Used to store the bounding boxes of event graphics in order to render them on top of the
     /// fog and collision layers
     pub event_rects: Vec<egui::Rect>,
 
     pub data_id: egui::Id,
+
+    /// Layer settings of the second pane when the view is split in two. Both panes draw the same
+    /// tilemap with the same pan and scale, but each has its own layer visibility and darkening.
+    pub split: Option<SplitPane>,
+    /// The second pane is rendered into this texture before the first pane is drawn.
+    split_texture: Option<std::sync::Arc<luminol_graphics::Texture>>,
+    /// True while the second pane is being shown.
+    showing_split_pane: bool,
+
+    /// Another map drawn under or over this one.
+    pub onion_skin: Option<OnionSkin>,
+
+    /// Which edges of the map loop around. Set with [`Self::set_map_loop`].
+    map_loop: luminol_config::map_loops::MapLoop,
+
+    /// Where the axes of the mirror painting mode cross, in tiles from the top-left corner of the
+    /// map. `None` puts them at the center of the map.
+    pub symmetry_axis: Option<egui::Pos2>,
+}
+
+/// A translucent copy of another map drawn under or over the map being edited, to line up maps
+/// that connect to each other. Only its tiles are drawn.
+pub struct OnionSkin {
+    pub map_id: usize,
+    pub settings: luminol_config::onion_skins::OnionSkin,
+    map: luminol_graphics::Map,
+    /// Size of the map in tiles
+    size: egui::Vec2,
+}
+
+impl OnionSkin {
+    /// Loads the map with the given ID, if it isn't already, and creates an onion skin of it.
+    pub fn new(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+        settings: luminol_config::onion_skins::OnionSkin,
+    ) -> color_eyre::Result<Self> {
+        let map = update_state.data.get_or_load_map(
+            map_id,
+            update_state.filesystem,
+            update_state.project_config.as_ref().unwrap(),
+        );
+        let tilesets = update_state.data.tilesets();
+        let tileset = tilesets
+            .data
+            .get(map.tileset_id)
+            .wrap_err_with(|| format!("Map {map_id:0>3} has an invalid tileset"))?;
+
+        let editor_ver = update_state
+            .project_config
+            .as_ref()
+            .unwrap()
+            .project
+            .editor_ver;
+        let mut overlay = luminol_graphics::Map::new_overlay(
+            &update_state.graphics,
+            update_state.filesystem,
+            &map,
+            tileset,
+            editor_ver,
+        )?;
+        // The opacity of every layer is set to the opacity of the onion skin instead
+        overlay.tiles.auto_opacity = false;
+
+        Ok(Self {
+            map_id,
+            settings,
+            map: overlay,
+            size: egui::vec2(map.width as f32, map.height as f32),
+        })
+    }
+}
+
+pub struct SplitPane {
+    pub enabled_layers: Vec<bool>,
+    pub darken_unselected_layers: bool,
 }
 
 struct PreviewEvent {
@@ -81,30 +186,70 @@ impl MapView {
         update_state: &luminol_core::UpdateState<'_>,
         map_id: usize,
     ) -> color_eyre::Result<MapView> {
-        let map = update_state.data.get_or_load_map(
-            map_id,
-            update_state.filesystem,
-            update_state.project_config.as_ref().unwrap(),
-        );
-        let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
+        let passages = {
+            let map = update_state.data.get_or_load_map(
+                map_id,
+                update_state.filesystem,
+                update_state.project_config.as_ref().unwrap(),
+            );
+            let tilesets = update_state.data.tilesets();
+            let tileset = &tilesets.data[map.tileset_id];
+            Self::calculate_passages(
+                &tileset.passages,
+                &tileset.priorities,
+                &map.data,
+                &map.events,
+            )
+        };
 
-        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+        Self::with_passages(update_state, map_id, &passages)
+    }
+
+    /// Works out the passage values the collision preview of a map starts with. This is the
+    /// slowest part of making a view of a large map and doesn't need the UI, so it can be done in
+    /// the background before calling [`Self::with_passages`].
+    pub fn calculate_passages(
+        passages: &luminol_data::Table1,
+        priorities: &luminol_data::Table1,
+        tiles: &luminol_data::Table3,
+        events: &luminol_data::OptionVec<luminol_data::rpg::Event>,
+    ) -> luminol_data::Table2 {
+        let mut computed_passages = luminol_data::Table2::new(tiles.xsize(), tiles.ysize());
         luminol_graphics::Collision::calculate_passages(
-            &tileset.passages,
-            &tileset.priorities,
-            &map.data,
-            Some(&map.events),
-            (0..map.data.zsize()).rev(),
-            |x, y, passage| passages[(x, y)] = passage,
+            passages,
+            priorities,
+            tiles,
+            Some(events),
+            (0..tiles.zsize()).rev(),
+            |x, y, passage| computed_passages[(x, y)] = passage,
         );
+        computed_passages
+    }
+
+    /// Makes a view of a map whose passage values were already worked out with
+    /// [`Self::calculate_passages`]. The map must already be loaded.
+    pub fn with_passages(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+        passages: &luminol_data::Table2,
+    ) -> color_eyre::Result<MapView> {
+        let map = update_state.data.get_map(map_id);
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[map.tileset_id];
 
+        let editor_ver = update_state
+            .project_config
+            .as_ref()
+            .unwrap()
+            .project
+            .editor_ver;
         let map = luminol_graphics::Map::new(
             &update_state.graphics,
             update_state.filesystem,
             &map,
             tileset,
-            &passages,
+            passages,
+            editor_ver,
         )?;
 
         let data_id = egui::Id::new("luminol_map_view")
@@ -137,25 +282,147 @@ impl MapView {
             selected_layer: SelectedLayer::default(),
             selected_event_id: None,
             cursor_pos,
+            keyboard_cursor: None,
+            keyboard_anchor: None,
             snap_to_grid: false,
+            tile_selection: None,
+            eyedropper_rect: None,
 
             darken_unselected_layers: true,
 
             hover_tile: None,
+            hover_pos: None,
 
             selected_event_is_hovered: false,
 
             display_tile_ids: false,
 
+            display_start_position: true,
+            display_minimap: true,
+            visible_tiles: egui::Rect::NOTHING,
+            start_position: None,
+
             scale,
             previous_scale: scale,
+            pixel_perfect: false,
 
             event_rects: Vec::new(),
 
             data_id,
+
+            split: None,
+            split_texture: None,
+            showing_split_pane: false,
+
+            onion_skin: None,
+
+            map_loop: Default::default(),
+            symmetry_axis: None,
         })
     }
 
+    /// The rectangle of tiles covered by the keyboard cursor, from the top-left tile to the
+    /// bottom-right tile inclusive.
+    pub fn keyboard_rect(&self) -> Option<egui::Rect> {
+        let cursor = self.keyboard_cursor?;
+        Some(egui::Rect::from_two_pos(
+            self.keyboard_anchor.unwrap_or(cursor),
+            cursor,
+        ))
+    }
+
+    pub fn map_loop(&self) -> luminol_config::map_loops::MapLoop {
+        self.map_loop
+    }
+
+    /// Where the axes of the mirror painting mode cross on `map`, in tiles from its top-left
+    /// corner.
+    pub fn symmetry_center(&self, map: &luminol_data::rpg::Map) -> egui::Pos2 {
+        self.symmetry_axis.unwrap_or(egui::pos2(
+            map.data.xsize() as f32 / 2.,
+            map.data.ysize() as f32 / 2.,
+        ))
+    }
+
+    /// Sets which edges of the map loop around. Copies of the map are drawn past the edges that
+    /// loop and hovering over them hovers over the tiles they are copies of.
+    pub fn set_map_loop(
+        &mut self,
+        graphics_state: &luminol_graphics::GraphicsState,
+        map_loop: luminol_config::map_loops::MapLoop,
+    ) {
+        self.map_loop = map_loop;
+        self.map
+            .set_loop(graphics_state, map_loop.horizontal, map_loop.vertical);
+    }
+
+    /// Pans the view so that the given tile is in the center and moves the cursor to it.
+    pub fn center_on(
+        &mut self,
+        ctx: &egui::Context,
+        map: &luminol_data::rpg::Map,
+        tile: egui::Pos2,
+    ) {
+        let tile_size = 32. * self.scale / (ctx.pixels_per_point() * 100.);
+        let map_size = egui::vec2(map.width as f32, map.height as f32);
+        self.pan = (map_size / 2. - tile.to_vec2() - egui::Vec2::splat(0.5)) * tile_size;
+        self.inter_tile_pan = egui::Vec2::ZERO;
+        self.cursor_pos = tile;
+    }
+
+    /// The scales (in percent) allowed when pixel-perfect rendering is enabled.
+    pub const PIXEL_PERFECT_SCALES: [f32; 6] = [25., 100. / 3., 50., 100., 200., 300.];
+
+    /// Whether the region layer of a VX Ace map is selected. It shares the map layer that holds
+    /// the shadows.
+    pub fn is_region_layer_selected(&self) -> bool {
+        self.map.tiles.has_shadows()
+            && self.selected_layer
+                == SelectedLayer::Tiles(luminol_graphics::primitives::shadows::LAYER)
+    }
+
+    /// Returns the pixel-perfect scale closest to `scale`.
+    pub fn snap_to_pixel_perfect_scale(scale: f32) -> f32 {
+        Self::PIXEL_PERFECT_SCALES
+            .into_iter()
+            .min_by(|a, b| {
+                // Compare in log space so that e.g. 150% is halfway between 100% and 200%
+                let a = (a.ln() - scale.ln()).abs();
+                let b = (b.ln() - scale.ln()).abs();
+                a.total_cmp(&b)
+            })
+            .unwrap()
+    }
+
+    /// Returns the next larger or smaller pixel-perfect scale after `scale`.
+    pub fn step_pixel_perfect_scale(scale: f32, zoom_in: bool) -> f32 {
+        let scale = Self::snap_to_pixel_perfect_scale(scale);
+        let index = Self::PIXEL_PERFECT_SCALES
+            .iter()
+            .position(|s| *s == scale)
+            .unwrap();
+        let index = if zoom_in {
+            (index + 1).min(Self::PIXEL_PERFECT_SCALES.len() - 1)
+        } else {
+            index.saturating_sub(1)
+        };
+        Self::PIXEL_PERFECT_SCALES[index]
+    }
+
+    /// Splits the view into two panes or joins them back together. The second pane starts with
+    /// the same layer settings as the first one.
+    pub fn set_split(&mut self, split: bool) {
+        if !split {
+            self.split = None;
+            self.split_texture = None;
+        } else if self.split.is_none() {
+            self.split = Some(SplitPane {
+                enabled_layers: self.map.tiles.enabled_layers.clone(),
+                darken_unselected_layers: self.darken_unselected_layers,
+            });
+        }
+    }
+
     // FIXME lots of arguments
     #[allow(clippy::too_many_arguments)]
     pub fn ui(
@@ -169,6 +436,106 @@ impl MapView {
         drawing_shape_pos: Option<egui::Pos2>,
         force_show_pattern_rect: bool,
         is_focused: bool,
+    ) -> egui::Response {
+        let Some(mut split) = self.split.take() else {
+            return self.pane_ui(
+                ui,
+                update_state,
+                map,
+                tilepicker,
+                dragging_event,
+                drawing_shape,
+                drawing_shape_pos,
+                force_show_pattern_rect,
+                is_focused,
+            );
+        };
+
+        let (first_rect, second_rect) = ui.max_rect().split_left_right_at_fraction(0.5);
+
+        // The second pane is rendered and submitted right away, so it has to be shown before the
+        // first pane overwrites the viewport and layer settings of the tilemap for this frame
+        std::mem::swap(
+            &mut self.map.tiles.enabled_layers,
+            &mut split.enabled_layers,
+        );
+        std::mem::swap(
+            &mut self.darken_unselected_layers,
+            &mut split.darken_unselected_layers,
+        );
+        self.showing_split_pane = true;
+        let second_response = self.pane_ui(
+            &mut ui.child_ui_with_id_source(second_rect, *ui.layout(), "second_pane", None),
+            update_state,
+            map,
+            tilepicker,
+            dragging_event,
+            drawing_shape,
+            drawing_shape_pos,
+            force_show_pattern_rect,
+            is_focused,
+        );
+        self.showing_split_pane = false;
+        std::mem::swap(
+            &mut self.map.tiles.enabled_layers,
+            &mut split.enabled_layers,
+        );
+        std::mem::swap(
+            &mut self.darken_unselected_layers,
+            &mut split.darken_unselected_layers,
+        );
+
+        let second_hover = (
+            self.hover_tile,
+            self.hover_pos,
+            self.selected_event_id,
+            self.selected_event_is_hovered,
+        );
+
+        let first_response = self.pane_ui(
+            &mut ui.child_ui_with_id_source(first_rect, *ui.layout(), "first_pane", None),
+            update_state,
+            map,
+            tilepicker,
+            dragging_event,
+            drawing_shape,
+            drawing_shape_pos,
+            force_show_pattern_rect,
+            is_focused,
+        );
+
+        // Whichever pane the mouse is over decides what is being hovered
+        if first_response.hover_pos().is_none() && second_response.hover_pos().is_some() {
+            (
+                self.hover_tile,
+                self.hover_pos,
+                self.selected_event_id,
+                self.selected_event_is_hovered,
+            ) = second_hover;
+        }
+
+        ui.painter().vline(
+            second_rect.left(),
+            second_rect.y_range(),
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+
+        self.split = Some(split);
+        first_response | second_response
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn pane_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+        tilepicker: &super::Tilepicker,
+        dragging_event: bool,
+        drawing_shape: bool,
+        drawing_shape_pos: Option<egui::Pos2>,
+        force_show_pattern_rect: bool,
+        is_focused: bool,
     ) -> egui::Response {
         // Allocate the largest size we can for the tilemap
         let canvas_rect = ui.max_rect();
@@ -182,13 +549,25 @@ impl MapView {
         let clip_offset = (max_clip - min_clip) / 2.;
         let canvas_rect = ui.ctx().screen_rect().intersect(canvas_rect);
 
-        self.cursor_pos = self.cursor_pos.clamp(
-            egui::Pos2::ZERO,
-            egui::pos2(
-                map.data.xsize().saturating_sub(1) as f32,
-                map.data.ysize().saturating_sub(1) as f32,
-            ),
+        let max_pos = egui::pos2(
+            map.data.xsize().saturating_sub(1) as f32,
+            map.data.ysize().saturating_sub(1) as f32,
         );
+        self.cursor_pos = self.cursor_pos.clamp(egui::Pos2::ZERO, max_pos);
+        if !matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
+            self.keyboard_cursor = None;
+            self.keyboard_anchor = None;
+        }
+        for pos in [&mut self.keyboard_cursor, &mut self.keyboard_anchor]
+            .into_iter()
+            .flatten()
+        {
+            *pos = pos.clamp(egui::Pos2::ZERO, max_pos);
+        }
+
+        if self.pixel_perfect {
+            self.scale = Self::snap_to_pixel_perfect_scale(self.scale);
+        }
 
         // If the user changed the scale using the scale slider, pan the map so that the scale uses
         // the center of the visible part of the map as the scale center
@@ -202,9 +581,18 @@ impl MapView {
             let old_scale = self.scale;
             let delta = ui.input(|i| i.smooth_scroll_delta.y);
 
-            // Apply scroll and cap max zoom to 15%
-            self.scale *= (delta / 9.0f32.exp2()).exp2();
-            self.scale = self.scale.clamp(15., 300.);
+            if self.pixel_perfect {
+                // Smooth scrolling would get snapped back to the same scale every frame, so step
+                // through the pixel-perfect scales once per scroll event instead
+                let raw_delta = ui.input(|i| i.raw_scroll_delta.y);
+                if raw_delta != 0. {
+                    self.scale = Self::step_pixel_perfect_scale(self.scale, raw_delta > 0.);
+                }
+            } else {
+                // Apply scroll and cap max zoom to 15%
+                self.scale *= (delta / 9.0f32.exp2()).exp2();
+                self.scale = self.scale.clamp(15., 300.);
+            }
 
             // Get the normalized cursor position relative to pan
             let pos_norm = (pos - self.pan - canvas_center) / old_scale;
@@ -227,22 +615,39 @@ impl MapView {
 
         let ctrl_drag = ui.input(|i| {
             if is_focused {
-                // Handle pan
-                if i.key_pressed(egui::Key::ArrowUp) && self.cursor_pos.y > 0. {
-                    self.cursor_pos.y -= 1.0;
+                let mut delta = egui::Vec2::ZERO;
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    delta.y -= 1.;
                 }
-                if i.key_pressed(egui::Key::ArrowDown)
-                    && self.cursor_pos.y < map.data.ysize() as f32 - 1.
-                {
-                    self.cursor_pos.y += 1.0;
+                if i.key_pressed(egui::Key::ArrowDown) {
+                    delta.y += 1.;
                 }
-                if i.key_pressed(egui::Key::ArrowLeft) && self.cursor_pos.x > 0. {
-                    self.cursor_pos.x -= 1.0;
+                if i.key_pressed(egui::Key::ArrowLeft) {
+                    delta.x -= 1.;
                 }
-                if i.key_pressed(egui::Key::ArrowRight)
-                    && self.cursor_pos.x < map.data.xsize() as f32 - 1.
-                {
-                    self.cursor_pos.x += 1.0;
+                if i.key_pressed(egui::Key::ArrowRight) {
+                    delta.x += 1.;
+                }
+
+                if delta != egui::Vec2::ZERO {
+                    if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
+                        // On tile layers the keyboard cursor is moved instead, and holding shift
+                        // extends a rectangle from where it was
+                        let cursor = self.keyboard_cursor.unwrap_or(self.cursor_pos);
+                        self.keyboard_anchor = i
+                            .modifiers
+                            .shift
+                            .then(|| self.keyboard_anchor.unwrap_or(cursor));
+                        self.keyboard_cursor =
+                            Some((cursor + delta).clamp(egui::Pos2::ZERO, max_pos));
+                    } else {
+                        self.cursor_pos =
+                            (self.cursor_pos + delta).clamp(egui::Pos2::ZERO, max_pos);
+                    }
+                }
+                if i.key_pressed(egui::Key::Escape) {
+                    self.keyboard_cursor = None;
+                    self.keyboard_anchor = None;
                 }
             }
 
@@ -279,12 +684,27 @@ impl MapView {
 
         // We check here after we calculate the scale and whatnot
         self.hover_tile = None;
+        self.hover_pos = None;
         if let Some(pos) = response.hover_pos() {
             let mut pos_tile = (pos - self.pan - canvas_center) / tile_size
                 + egui::Vec2::new(map.width as f32 / 2., map.height as f32 / 2.);
-            // Force the cursor to a tile instead of in-between
-            pos_tile.x = pos_tile.x.floor().clamp(0., map.width as f32 - 1.);
-            pos_tile.y = pos_tile.y.floor().clamp(0., map.height as f32 - 1.);
+            self.hover_pos = Some(pos_tile.to_pos2());
+            if ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO) {
+                self.keyboard_cursor = None;
+                self.keyboard_anchor = None;
+            }
+            // Force the cursor to a tile instead of in-between. Past the edges that loop, the
+            // copies of the map are hovered over instead.
+            pos_tile.x = if self.map_loop.horizontal {
+                pos_tile.x.floor().rem_euclid(map.width as f32)
+            } else {
+                pos_tile.x.floor().clamp(0., map.width as f32 - 1.)
+            };
+            pos_tile.y = if self.map_loop.vertical {
+                pos_tile.y.floor().rem_euclid(map.height as f32)
+            } else {
+                pos_tile.y.floor().clamp(0., map.height as f32 - 1.)
+            };
             self.hover_tile = Some(pos_tile.to_pos2());
             // Handle input
             if matches!(self.selected_layer, SelectedLayer::Tiles(_))
@@ -302,6 +722,10 @@ impl MapView {
             min: canvas_pos - map_size2,
             max: canvas_pos + map_size2,
         };
+        self.visible_tiles = egui::Rect::from_min_max(
+            ((canvas_rect.min - map_rect.min) / tile_size).to_pos2(),
+            ((canvas_rect.max - map_rect.min) / tile_size).to_pos2(),
+        );
 
         self.map.tiles.selected_layer = match self.selected_layer {
             SelectedLayer::Events => None,
@@ -311,13 +735,18 @@ impl MapView {
             SelectedLayer::Tiles(_) => None,
         };
 
+        let mut translation = glam::vec2(
+            canvas_rect.width() / 2. + self.pan.x + clip_offset.x - width2 * 32. * scale,
+            canvas_rect.height() / 2. + self.pan.y + clip_offset.y - height2 * 32. * scale,
+        );
+        if self.pixel_perfect {
+            // Tiles that start between two pixels would still be sampled unevenly
+            translation = translation.round();
+        }
         self.map.viewport.set(
             &update_state.graphics.render_state,
             glam::vec2(canvas_rect.width(), canvas_rect.height()),
-            glam::vec2(
-                canvas_rect.width() / 2. + self.pan.x + clip_offset.x - width2 * 32. * scale,
-                canvas_rect.height() / 2. + self.pan.y + clip_offset.y - height2 * 32. * scale,
-            ),
+            translation,
             glam::Vec2::splat(scale),
         );
 
@@ -326,12 +755,26 @@ impl MapView {
         ui.ctx()
             .request_repaint_after(std::time::Duration::from_secs_f32(16. / 60.));
 
-        let painter = luminol_graphics::Painter::new(self.map.prepare(&update_state.graphics));
-        ui.painter()
-            .add(luminol_egui_wgpu::Callback::new_paint_callback(
-                canvas_rect,
-                painter,
-            ));
+        self.update_brush_preview(
+            update_state,
+            map,
+            tilepicker,
+            drawing_shape_pos,
+            panning_map_view,
+        );
+
+        self.paint_onion_skin(ui, update_state, canvas_rect, map_rect, translation, false);
+        if self.showing_split_pane {
+            self.paint_split_pane(ui, update_state, canvas_rect);
+        } else {
+            let painter = luminol_graphics::Painter::new(self.map.prepare(&update_state.graphics));
+            ui.painter()
+                .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                    canvas_rect,
+                    painter,
+                ));
+        }
+        self.paint_onion_skin(ui, update_state, canvas_rect, map_rect, translation, true);
 
         ui.painter().rect_stroke(
             map_rect,
@@ -345,7 +788,9 @@ impl MapView {
         );
         let pattern_rect = egui::Rect::from_min_size(
             map_rect.min + (self.cursor_pos.to_vec2() * tile_size),
-            if tilepicker.brush_random || (!force_show_pattern_rect && drawing_shape_pos.is_some())
+            if tilepicker.brush_random
+                || self.is_region_layer_selected()
+                || (!force_show_pattern_rect && drawing_shape_pos.is_some())
             {
                 egui::Vec2::splat(tile_size)
             } else {
@@ -367,6 +812,7 @@ impl MapView {
         self.selected_event_is_hovered = false;
 
         if self.map.event_enabled {
+            let overlay_colors = update_state.global_config.overlay_palette.colors();
             let mut selected_event = None;
             let mut selected_event_rect = None;
 
@@ -400,6 +846,13 @@ impl MapView {
                 if let Some(sprite) = sprite {
                     // FIXME only update if necessary
                     sprite.set_position(&update_state.graphics.render_state, event.x, event.y);
+                    // Stepping animations are only previewed alongside move routes to avoid
+                    // constantly redrawing otherwise static maps
+                    sprite.update_animation(
+                        &update_state.graphics.render_state,
+                        ui.input(|i| i.time),
+                        self.move_preview,
+                    );
                     sprite.sprite.graphic.set_opacity_multiplier(
                         &update_state.graphics.render_state,
                         if self.darken_unselected_layers
@@ -512,12 +965,12 @@ impl MapView {
                                 Some(id) if id == event.id => ui.painter().rect_stroke(
                                     response.rect,
                                     5.,
-                                    egui::Stroke::new(2., egui::Color32::YELLOW),
+                                    egui::Stroke::new(2., overlay_colors.selected_event),
                                 ),
                                 _ => ui.painter().rect_stroke(
                                     response.rect,
                                     5.,
-                                    egui::Stroke::new(1., egui::Color32::WHITE),
+                                    egui::Stroke::new(1., overlay_colors.event_outline),
                                 ),
                             };
                         });
@@ -570,12 +1023,12 @@ impl MapView {
                     );
                 }
 
-                // Draw a magenta rectangle on the border of events that are being edited
+                // Draw a rectangle on the border of events that are being edited
                 if event.extra_data.is_editor_open {
                     ui.painter().rect_stroke(
                         box_rect,
                         5.,
-                        egui::Stroke::new(3., egui::Color32::from_rgb(255, 0, 255)),
+                        egui::Stroke::new(3., overlay_colors.editing_event),
                     );
                 }
             }
@@ -585,28 +1038,68 @@ impl MapView {
 
             self.selected_event_id = selected_event.map(|e| e.id);
 
-            // Draw white rectangles on the border of all events
+            // Draw rectangles on the border of all events
             while let Some(rect) = self.event_rects.pop() {
-                ui.painter()
-                    .rect_stroke(rect, 5., egui::Stroke::new(1., egui::Color32::WHITE));
+                ui.painter().rect_stroke(
+                    rect,
+                    5.,
+                    egui::Stroke::new(1., overlay_colors.event_outline),
+                );
             }
 
-            // Draw a yellow rectangle on the border of the selected event's graphic
+            // Draw a rectangle on the border of the selected event's graphic
             if let Some(selected_event) = selected_event {
                 // Make sure the event editor isn't open so we don't draw over the
-                // magenta rectangle
+                // editing rectangle
                 if !selected_event.extra_data.is_editor_open {
                     if let Some(rect) = selected_event_rect {
                         ui.painter().rect_stroke(
                             rect,
                             5.,
-                            egui::Stroke::new(3., egui::Color32::YELLOW),
+                            egui::Stroke::new(3., overlay_colors.selected_event),
                         );
                     }
                 }
             }
         }
 
+        if self.display_start_position && self.map.event_enabled {
+            self.paint_start_position(ui, canvas_rect, map_rect, tile_size);
+        }
+
+        // Draw the region IDs over the map while the region layer is selected
+        if self.is_region_layer_selected() {
+            let layer = map
+                .data
+                .layer_as_slice(luminol_graphics::primitives::shadows::LAYER);
+            for (i, value) in layer.iter().copied().enumerate() {
+                let region = luminol_graphics::primitives::shadows::region_id(value);
+                if region == 0 {
+                    continue;
+                }
+
+                let x = i % map.data.xsize();
+                let y = i / map.data.xsize();
+                let tile_rect = egui::Rect::from_min_size(
+                    map_rect.min + egui::vec2(x as f32, y as f32) * tile_size,
+                    egui::Vec2::splat(tile_size),
+                );
+                if !canvas_rect.intersects(tile_rect) {
+                    continue;
+                }
+
+                ui.painter()
+                    .rect_filled(tile_rect, 0., super::RegionPicker::color(region));
+                ui.painter().text(
+                    tile_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    region.to_string(),
+                    egui::FontId::monospace(12. * scale),
+                    egui::Color32::WHITE,
+                );
+            }
+        }
+
         // FIXME: If we want to be fast, we should be rendering all the tile ids to a texture once and then just rendering that texture here
         if self.display_tile_ids {
             if let SelectedLayer::Tiles(layer) = self.selected_layer {
@@ -666,12 +1159,22 @@ impl MapView {
         }
 
         // Display cursor.
-        if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
-            ui.painter().rect_stroke(
-                pattern_rect,
-                5.,
-                egui::Stroke::new(1., egui::Color32::WHITE),
+        let toolbar = &update_state.toolbar;
+        let shows_brush_outline = toolbar.brush_size > 1
+            && matches!(
+                toolbar.pencil,
+                luminol_core::Pencil::Pen | luminol_core::Pencil::Smart
             );
+        if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
+            if shows_brush_outline {
+                self.paint_brush_outline(ui, toolbar, map, map_rect, tile_size);
+            } else {
+                ui.painter().rect_stroke(
+                    pattern_rect,
+                    5.,
+                    egui::Stroke::new(1., egui::Color32::WHITE),
+                );
+            }
         }
         ui.painter().rect_stroke(
             cursor_rect,
@@ -679,6 +1182,95 @@ impl MapView {
             egui::Stroke::new(1., egui::Color32::YELLOW),
         );
 
+        // Show the axes the brushes mirror tiles across
+        if matches!(self.selected_layer, SelectedLayer::Tiles(_))
+            && toolbar.symmetry != luminol_core::Symmetry::Off
+        {
+            let center = map_rect.min + self.symmetry_center(map).to_vec2() * tile_size;
+            let stroke = egui::Stroke::new(1., egui::Color32::LIGHT_BLUE);
+            if toolbar.symmetry.mirrors_x() {
+                ui.painter().vline(center.x, map_rect.y_range(), stroke);
+            }
+            if toolbar.symmetry.mirrors_y() {
+                ui.painter().hline(map_rect.x_range(), center.y, stroke);
+            }
+        }
+
+        if let Some(tile_selection) = self.tile_selection {
+            let tile_selection_rect = egui::Rect::from_min_max(
+                map_rect.min + (tile_selection.min.to_vec2() * tile_size),
+                map_rect.min + ((tile_selection.max.to_vec2() + egui::Vec2::splat(1.)) * tile_size),
+            )
+            .intersect(map_rect);
+            ui.painter().rect_stroke(
+                tile_selection_rect,
+                0.,
+                egui::Stroke::new(
+                    2.,
+                    update_state
+                        .global_config
+                        .overlay_palette
+                        .colors()
+                        .tile_selection,
+                ),
+            );
+        }
+
+        // The keyboard cursor is dashed so that it can be told apart from the mouse cursor
+        if let Some(keyboard_rect) = self.keyboard_rect() {
+            let keyboard_rect = egui::Rect::from_min_max(
+                map_rect.min + (keyboard_rect.min.to_vec2() * tile_size),
+                map_rect.min + ((keyboard_rect.max.to_vec2() + egui::Vec2::splat(1.)) * tile_size),
+            );
+            let corners = [
+                keyboard_rect.left_top(),
+                keyboard_rect.right_top(),
+                keyboard_rect.right_bottom(),
+                keyboard_rect.left_bottom(),
+                keyboard_rect.left_top(),
+            ];
+            ui.painter().extend(egui::Shape::dashed_line(
+                &corners,
+                egui::Stroke::new(2., egui::Color32::LIGHT_GREEN),
+                6.,
+                4.,
+            ));
+        }
+
+        if let Some(eyedropper_rect) = self.eyedropper_rect {
+            let eyedropper_rect = egui::Rect::from_min_max(
+                map_rect.min + (eyedropper_rect.min.to_vec2() * tile_size),
+                map_rect.min
+                    + ((eyedropper_rect.max.to_vec2() + egui::Vec2::splat(1.)) * tile_size),
+            )
+            .intersect(map_rect);
+            ui.painter().rect_stroke(
+                eyedropper_rect,
+                0.,
+                egui::Stroke::new(2., egui::Color32::YELLOW),
+            );
+        }
+
+        // Let the user know why things may look or perform differently than usual
+        if update_state.graphics.compatibility.is_active() {
+            let galley = ui.painter().layout_no_wrap(
+                "⚠ Running in compatibility mode".to_string(),
+                egui::FontId::proportional(12.),
+                egui::Color32::YELLOW,
+            );
+            let text_rect = egui::Align2::RIGHT_BOTTOM.anchor_size(
+                canvas_rect.right_bottom() - egui::vec2(8., 8.),
+                galley.size(),
+            );
+            ui.painter().rect_filled(
+                text_rect.expand(4.),
+                4.,
+                egui::Color32::from_black_alpha(192),
+            );
+            ui.painter()
+                .galley(text_rect.min, galley, egui::Color32::YELLOW);
+        }
+
         ui.ctx().data_mut(|d| {
             d.insert_persisted(
                 self.data_id,
@@ -689,6 +1281,283 @@ impl MapView {
         response
     }
 
+    /// Shows a translucent preview of the tiles the pencil would place at the hovered tile.
+    fn update_brush_preview(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+        tilepicker: &super::Tilepicker,
+        drawing_shape_pos: Option<egui::Pos2>,
+        panning: bool,
+    ) {
+        self.map.brush_preview_enabled = false;
+
+        let SelectedLayer::Tiles(layer) = self.selected_layer else {
+            return;
+        };
+        let Some(hover_tile) = self.hover_tile else {
+            return;
+        };
+        // Tiles being drawn are already shown on the map itself
+        if panning
+            || drawing_shape_pos.is_some()
+            || self.is_region_layer_selected()
+            || !self.map.tiles.enabled_layers[layer]
+        {
+            return;
+        }
+
+        let toolbar = &update_state.toolbar;
+        let offsets = match toolbar.pencil {
+            luminol_core::Pencil::Pen if toolbar.brush_size > 1 => {
+                toolbar.brush_offsets().collect_vec()
+            }
+            luminol_core::Pencil::Pen if !tilepicker.brush_random => {
+                let width = tilepicker.selected_tiles_right - tilepicker.selected_tiles_left + 1;
+                let height = tilepicker.selected_tiles_bottom - tilepicker.selected_tiles_top + 1;
+                (0..height as i32)
+                    .cartesian_product(0..width as i32)
+                    .map(|(y, x)| (x, y))
+                    .collect_vec()
+            }
+            luminol_core::Pencil::Pen
+            | luminol_core::Pencil::Line
+            | luminol_core::Pencil::Rectangle
+            | luminol_core::Pencil::Circle
+            | luminol_core::Pencil::Fill => vec![(0, 0)],
+            _ => return,
+        };
+
+        let (min_x, max_x) = offsets
+            .iter()
+            .map(|&(x, _)| x)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let (min_y, max_y) = offsets
+            .iter()
+            .map(|&(_, y)| y)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+
+        let mut preview = luminol_data::Table3::new(
+            (max_x - min_x + 1) as usize,
+            (max_y - min_y + 1) as usize,
+            1,
+        );
+        for (offset_x, offset_y) in offsets {
+            let absolute_x = hover_tile.x as i32 + offset_x;
+            let absolute_y = hover_tile.y as i32 + offset_y;
+
+            // Tiles past the edges of the map that don't loop are left blank
+            let Some((wrapped_x, wrapped_y)) =
+                self.map_loop
+                    .wrap(absolute_x, absolute_y, map.data.xsize(), map.data.ysize())
+            else {
+                continue;
+            };
+
+            preview[((offset_x - min_x) as usize, (offset_y - min_y) as usize, 0)] = tilepicker
+                .get_tile_from_offset(
+                    wrapped_x as i16,
+                    wrapped_y as i16,
+                    layer as i16,
+                    offset_x as i16,
+                    offset_y as i16,
+                )
+                .to_id();
+        }
+
+        self.map.set_brush_preview(
+            &update_state.graphics,
+            &preview,
+            glam::vec2(hover_tile.x + min_x as f32, hover_tile.y + min_y as f32),
+            layer,
+        );
+        self.map.brush_preview_enabled = true;
+    }
+
+    /// Outlines the tiles a brush larger than one tile would draw on if the mouse was pressed,
+    /// leaving out the ones past the edges of the map.
+    fn paint_brush_outline(
+        &self,
+        ui: &egui::Ui,
+        toolbar: &luminol_core::ToolbarState,
+        map: &luminol_data::rpg::Map,
+        map_rect: egui::Rect,
+        tile_size: f32,
+    ) {
+        let (xsize, ysize) = (map.data.xsize() as i32, map.data.ysize() as i32);
+        let tiles: HashSet<(i32, i32)> = toolbar
+            .brush_offsets()
+            .map(|(x, y)| (self.cursor_pos.x as i32 + x, self.cursor_pos.y as i32 + y))
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < xsize && y < ysize)
+            .collect();
+
+        // Only the edges between a tile in the brush and one outside of it are drawn
+        let stroke = egui::Stroke::new(1., egui::Color32::WHITE);
+        for &(x, y) in &tiles {
+            let rect = egui::Rect::from_min_size(
+                map_rect.min + egui::vec2(x as f32, y as f32) * tile_size,
+                egui::Vec2::splat(tile_size),
+            );
+            let edges = [
+                ((x, y - 1), rect.left_top(), rect.right_top()),
+                ((x, y + 1), rect.left_bottom(), rect.right_bottom()),
+                ((x - 1, y), rect.left_top(), rect.left_bottom()),
+                ((x + 1, y), rect.right_top(), rect.right_bottom()),
+            ];
+            for (neighbor, start, end) in edges {
+                if !tiles.contains(&neighbor) {
+                    ui.painter().line_segment([start, end], stroke);
+                }
+            }
+        }
+    }
+
+    /// Marks the tile the player starts the game on with a flag sprite and a label, so that it can
+    /// be told apart from the events at a glance.
+    fn paint_start_position(
+        &self,
+        ui: &egui::Ui,
+        canvas_rect: egui::Rect,
+        map_rect: egui::Rect,
+        tile_size: f32,
+    ) {
+        let Some(start_position) = self.start_position else {
+            return;
+        };
+        let rect = egui::Rect::from_min_size(
+            map_rect.min + start_position.to_vec2() * tile_size,
+            egui::Vec2::splat(tile_size),
+        );
+        // The label sticks out of the top of the tile
+        if !canvas_rect.intersects(rect.expand(tile_size)) {
+            return;
+        }
+
+        let painter = ui.painter();
+        let color = egui::Color32::from_rgb(0, 200, 83);
+        painter.rect_filled(rect, 5., color.gamma_multiply(0.25));
+        painter.rect_stroke(rect, 5., egui::Stroke::new(2., color));
+        egui::Image::from_bytes(
+            "bytes://assets/icons/start_position.png",
+            START_POSITION_ICON,
+        )
+        .texture_options(egui::TextureOptions::NEAREST)
+        .paint_at(ui, rect);
+
+        let galley = painter.layout_no_wrap(
+            "Start".to_string(),
+            egui::FontId::proportional((tile_size * 0.35).clamp(8., 14.)),
+            egui::Color32::WHITE,
+        );
+        let label_rect = egui::Align2::CENTER_BOTTOM
+            .anchor_size(rect.center_top() - egui::vec2(0., 2.), galley.size())
+            .expand(2.);
+        painter.rect_filled(label_rect, 3., color);
+        painter.galley(
+            label_rect.min + egui::vec2(2., 2.),
+            galley,
+            egui::Color32::WHITE,
+        );
+    }
+
+    /// Draws the onion skin if it goes on the given side of the map.
+    /// The second pane of a split view doesn't show it, since both panes would share its viewport.
+    fn paint_onion_skin(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        canvas_rect: egui::Rect,
+        map_rect: egui::Rect,
+        translation: glam::Vec2,
+        above: bool,
+    ) {
+        if self.showing_split_pane {
+            return;
+        }
+        let Some(onion_skin) = self
+            .onion_skin
+            .as_mut()
+            .filter(|onion_skin| onion_skin.settings.above == above)
+        else {
+            return;
+        };
+
+        let render_state = &update_state.graphics.render_state;
+        let scale = self.scale / (ui.ctx().pixels_per_point() * 100.);
+        let [dx, dy] = onion_skin.settings.offset;
+        let offset = glam::vec2(dx as f32, dy as f32) * 32. * scale;
+
+        let opacity = onion_skin.settings.opacity as f32 / 255.;
+        for layer in 0..onion_skin.map.tiles.enabled_layers.len() {
+            onion_skin
+                .map
+                .tiles
+                .display
+                .set_opacity(render_state, opacity, layer);
+        }
+        onion_skin.map.viewport.set(
+            render_state,
+            glam::vec2(canvas_rect.width(), canvas_rect.height()),
+            translation + offset,
+            glam::Vec2::splat(scale),
+        );
+
+        let painter =
+            luminol_graphics::Painter::new(onion_skin.map.prepare(&update_state.graphics));
+        ui.painter()
+            .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                canvas_rect,
+                painter,
+            ));
+
+        ui.painter().rect_stroke(
+            egui::Rect::from_min_size(
+                map_rect.min + egui::vec2(offset.x, offset.y),
+                onion_skin.size * 32. * scale,
+            ),
+            5.,
+            egui::Stroke::new(1., egui::Color32::LIGHT_BLUE),
+        );
+    }
+
+    /// Renders the tilemap into the texture of the second pane and draws that texture, instead of
+    /// drawing the tilemap with a paint callback like the first pane does.
+    fn paint_split_pane(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        canvas_rect: egui::Rect,
+    ) {
+        let size = canvas_rect.size() * ui.ctx().pixels_per_point();
+        let (width, height) = (size.x.round() as u32, size.y.round() as u32);
+
+        // The texture is only recreated when the size of the pane changes
+        if !self
+            .split_texture
+            .as_ref()
+            .is_some_and(|texture| texture.width() == width && texture.height() == height)
+        {
+            self.split_texture =
+                luminol_graphics::Map::create_render_target(&update_state.graphics, width, height)
+                    .ok();
+        }
+        let Some(texture) = &self.split_texture else {
+            return;
+        };
+
+        self.map.render_into(&update_state.graphics, texture);
+        ui.painter().image(
+            texture.texture_id,
+            canvas_rect,
+            egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+            egui::Color32::WHITE,
+        );
+    }
+
     /// Saves the current state of the map to an image file of the user's choice (will prompt the
     /// user with a file picker).
     /// This function returns a future that you need to `.await` to finish saving the image, but
@@ -30,15 +30,45 @@ pub mod syntax_highlighting;
 
 /// The tilemap.
 mod map_view;
-pub use map_view::{MapView, SelectedLayer};
+pub use map_view::{MapView, OnionSkin, SelectedLayer, SplitPane};
 mod tilepicker;
 pub use tilepicker::{SelectedTile, Tilepicker};
+mod region_picker;
+pub use region_picker::RegionPicker;
 
 mod sound_tab;
 pub use sound_tab::SoundTab;
 
+mod asset_preview;
+pub use asset_preview::{AssetPreview, Playback};
+
+mod preview_background;
+pub use preview_background::preview_background;
+
+mod tone_edit;
+pub use tone_edit::ToneEdit;
+
+mod brush_shape_icon;
+pub use brush_shape_icon::brush_shape_icon;
+
+mod icon_grid;
+pub use icon_grid::{IconGrid, ICON_DIRECTORY};
+
+mod map_thumbnails;
+pub use map_thumbnails::MapThumbnails;
+
+mod windowskin_preview;
+pub use windowskin_preview::{WindowskinPreview, WINDOWSKIN_DIRECTORY};
+
 mod command_view;
-pub use command_view::CommandView;
+pub use command_view::{
+    BattleCommandEditor, CommandView, InventoryCommandEditor, LabelCommandEditor, ParameterForm,
+    PictureCommandEditor, ShopCommandEditor,
+};
+
+mod unsaved_changes;
+pub(crate) use unsaved_changes::open_editor;
+pub use unsaved_changes::UnsavedChanges;
 
 mod filesystem_view;
 pub use filesystem_view::FileSystemView;
@@ -57,6 +87,12 @@ pub use cellpicker::Cellpicker;
 mod id_vec;
 pub use id_vec::{IdVecPlusMinusSelection, IdVecSelection, RankSelection};
 
+mod damage_calculator;
+pub use damage_calculator::{BattlerData, DamageCalculator};
+
+mod recent_edits;
+pub use recent_edits::RecentEditsSwitcher;
+
 mod ui_ext;
 pub use ui_ext::UiExt;
 
@@ -201,7 +237,7 @@ where
                 ui.add_space(spacing);
                 ui.horizontal(|ui| {
                     ui.add(egui::Label::new(format!("{}:", self.name)).truncate());
-                    ui.add(egui::Checkbox::without_text(self.checked));
+                    changed |= ui.add(egui::Checkbox::without_text(self.checked)).changed();
                 });
                 if ui.add_enabled(*self.checked, self.widget).changed() {
                     changed = true;
@@ -295,8 +331,10 @@ pub struct OptionalIdComboBox<'a, R, I, H, F> {
     reference: &'a mut R,
     id_iter: I,
     formatter: F,
+    icon: Option<Box<dyn Fn(usize) -> Option<egui::load::SizedTexture> + 'a>>,
     search_needs_update: bool,
     allow_none: bool,
+    invalid_reason: Option<String>,
 }
 
 impl<'a, R, I, H, F> OptionalIdComboBox<'a, R, I, H, F>
@@ -319,18 +357,41 @@ where
             reference,
             id_iter,
             formatter,
+            icon: None,
             search_needs_update: *update_state.modified_during_prev_frame,
             allow_none: true,
+            invalid_reason: None,
         }
     }
 
+    /// Shows an icon next to each entry in the combo box, for entries that have one.
+    pub fn icons(mut self, icon: impl Fn(usize) -> Option<egui::load::SizedTexture> + 'a) -> Self {
+        self.icon = Some(Box::new(icon));
+        self
+    }
+
+    /// Marks the current selection as invalid if `reason` is `Some`, showing it in red with the
+    /// reason as its tooltip.
+    pub fn invalid_reason(mut self, reason: Option<String>) -> Self {
+        self.invalid_reason = reason;
+        self
+    }
+
     fn ui_inner(
-        self,
+        mut self,
         ui: &mut egui::Ui,
         formatter: impl Fn(&Self) -> String,
         f: impl FnOnce(Self, &mut egui::Ui, Vec<usize>, bool, bool) -> bool,
     ) -> egui::Response {
         let source = egui::Id::new(&self.id_source);
+        let invalid_reason = self.invalid_reason.take();
+        let selected_text: egui::WidgetText = if invalid_reason.is_some() {
+            egui::RichText::new(formatter(&self))
+                .color(ui.visuals().error_fg_color)
+                .into()
+        } else {
+            formatter(&self).into()
+        };
         let state_id = ui.make_persistent_id(source).with("OptionalIdComboBox");
         let popup_id = ui.make_persistent_id(source).with("popup");
         let is_popup_open = ui.memory(|m| m.is_popup_open(popup_id));
@@ -339,7 +400,7 @@ where
         let inner_response = egui::ComboBox::from_id_source(&self.id_source)
             .wrap()
             .width(ui.available_width() - ui.spacing().item_spacing.x)
-            .selected_text(formatter(&self))
+            .selected_text(selected_text)
             .show_ui(ui, |ui| {
                 // Get cached search string and search matches from egui memory
                 let (mut search_string, search_matched_ids_lock) = is_popup_open
@@ -418,6 +479,9 @@ where
                 search_box_clicked
             });
         let mut response = inner_response.response;
+        if let Some(reason) = invalid_reason {
+            response = response.on_hover_text(reason);
+        }
 
         if inner_response.inner == Some(true) {
             // Force the combo box to stay open if the search box was clicked
@@ -491,9 +555,14 @@ where
                     ui.with_stripe(is_faint, |ui| {
                         ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
 
-                        if ui
-                            .selectable_label(*this.reference == Some(id), (this.formatter)(id))
-                            .clicked()
+                        let icon = this.icon.as_ref().and_then(|icon| icon(id));
+                        if selectable_id_row(
+                            ui,
+                            (this.formatter)(id),
+                            icon,
+                            *this.reference == Some(id),
+                        )
+                        .clicked()
                         {
                             *this.reference = Some(id);
                             changed = true;
@@ -531,8 +600,8 @@ where
                     ui.with_stripe(is_faint, |ui| {
                         ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
 
-                        if ui
-                            .selectable_label(*this.reference == id, (this.formatter)(id))
+                        let icon = this.icon.as_ref().and_then(|icon| icon(id));
+                        if selectable_id_row(ui, (this.formatter)(id), icon, *this.reference == id)
                             .clicked()
                         {
                             *this.reference = id;
@@ -548,6 +617,25 @@ where
     }
 }
 
+fn selectable_id_row(
+    ui: &mut egui::Ui,
+    text: String,
+    icon: Option<egui::load::SizedTexture>,
+    selected: bool,
+) -> egui::Response {
+    match icon {
+        Some(texture) => {
+            let size = egui::Vec2::splat(ui.spacing().interact_size.y);
+            ui.horizontal(|ui| {
+                ui.add(egui::Image::new(texture).fit_to_exact_size(size));
+                ui.selectable_label(selected, text)
+            })
+            .inner
+        }
+        None => ui.selectable_label(selected, text),
+    }
+}
+
 pub fn close_options_ui(ui: &mut egui::Ui, open: &mut bool, save: &mut bool) {
     ui.horizontal(|ui| {
         if ui.button("Ok").clicked() {
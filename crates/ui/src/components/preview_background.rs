@@ -0,0 +1,72 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_config::global::PreviewBackground;
+
+const CHECKER_SIZE: f32 = 8.;
+const CHECKER_LIGHT: egui::Color32 = egui::Color32::from_gray(204);
+const CHECKER_DARK: egui::Color32 = egui::Color32::from_gray(153);
+
+/// Returns the shape to draw behind an image preview covering `rect`, according to the user's
+/// preference. Only the part inside `clip_rect` is built, since a checkerboard for a large image
+/// would otherwise take a lot of squares.
+pub fn preview_background(
+    background: PreviewBackground,
+    rect: egui::Rect,
+    clip_rect: egui::Rect,
+) -> egui::Shape {
+    let color = match background {
+        PreviewBackground::Checkerboard => CHECKER_LIGHT,
+        PreviewBackground::Map => egui::Color32::BLACK,
+        PreviewBackground::Custom(color) => color,
+    };
+    let visible = rect.intersect(clip_rect);
+    if !visible.is_positive() {
+        return egui::Shape::Noop;
+    }
+
+    let mut mesh = egui::Mesh::default();
+    mesh.add_colored_rect(visible, color);
+
+    if background == PreviewBackground::Checkerboard {
+        // The squares are aligned to the top left of `rect` so they move along with the image
+        // when it is scrolled
+        let first = ((visible.min - rect.min) / CHECKER_SIZE).floor();
+        let last = ((visible.max - rect.min) / CHECKER_SIZE).ceil();
+        for row in first.y as i32..last.y as i32 {
+            for column in first.x as i32..last.x as i32 {
+                if (row + column) % 2 == 0 {
+                    continue;
+                }
+                let square = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(column as f32, row as f32) * CHECKER_SIZE,
+                    egui::Vec2::splat(CHECKER_SIZE),
+                );
+                mesh.add_colored_rect(square.intersect(visible), CHECKER_DARK);
+            }
+        }
+    }
+
+    egui::Shape::mesh(mesh)
+}
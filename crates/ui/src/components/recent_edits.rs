@@ -0,0 +1,260 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_config::recent_edits::{Database, EditLocation};
+use luminol_core::Structure;
+use luminol_data::rpg;
+
+use super::DatabaseView;
+
+/// The Ctrl+Tab overlay for switching between the places that were edited most recently.
+///
+/// Pressing Ctrl+Tab opens it with the previous location selected, pressing Tab again while
+/// holding Ctrl cycles through the list (Shift goes backwards) and releasing Ctrl opens the
+/// selected location's editor.
+#[derive(Default)]
+pub struct RecentEditsSwitcher {
+    /// The highlighted index into the recent edits while the switcher is open.
+    selected: Option<usize>,
+}
+
+impl RecentEditsSwitcher {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Handles the shortcut and shows the switcher if it's open. Should be called every frame.
+    pub fn ui(&mut self, ctx: &egui::Context, update_state: &mut luminol_core::UpdateState<'_>) {
+        let len = update_state.recent_edits.locations.len();
+        if update_state.project_config.is_none() || len == 0 {
+            self.selected = None;
+            return;
+        }
+
+        let (forward, backward) = ctx.input_mut(|i| {
+            let backward = i.consume_key(
+                egui::Modifiers::CTRL | egui::Modifiers::SHIFT,
+                egui::Key::Tab,
+            );
+            let forward = i.consume_key(egui::Modifiers::CTRL, egui::Key::Tab);
+            (forward, backward)
+        });
+        self.selected = match self.selected {
+            None if forward => Some(1.min(len - 1)),
+            None if backward => Some(len - 1),
+            Some(selected) if forward => Some((selected + 1) % len),
+            Some(selected) if backward => Some((selected + len - 1) % len),
+            selected => selected.map(|selected| selected.min(len - 1)),
+        };
+        let Some(selected) = self.selected else {
+            return;
+        };
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.selected = None;
+            return;
+        }
+
+        let mut jump_to = None;
+        egui::Area::new(egui::Id::new("luminol_recent_edits_switcher"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                    ui.strong("Recent edits");
+                    ui.separator();
+
+                    let locations = update_state.recent_edits.locations.clone();
+                    for (index, location) in locations.into_iter().enumerate() {
+                        let text = match label(update_state, location) {
+                            Some(label) => egui::RichText::new(label),
+                            None => egui::RichText::new(fallback_label(location))
+                                .weak()
+                                .strikethrough(),
+                        };
+                        if ui.selectable_label(index == selected, text).clicked() {
+                            jump_to = Some(index);
+                        }
+                    }
+
+                    ui.separator();
+                    ui.weak("Tab to cycle, release Ctrl to open, Escape to cancel");
+                });
+            });
+
+        if jump_to.is_none() && ctx.input(|i| !i.modifiers.ctrl) {
+            jump_to = Some(selected);
+        }
+        if let Some(index) = jump_to {
+            self.selected = None;
+            jump(update_state, index);
+        }
+    }
+}
+
+/// Describes a location, or returns `None` if it doesn't exist anymore.
+fn label(update_state: &luminol_core::UpdateState<'_>, location: EditLocation) -> Option<String> {
+    match location {
+        EditLocation::Event { map_id, event_id } => {
+            let map_infos = update_state.data.map_infos();
+            let map_info = map_infos.data.get(&map_id)?;
+            Some(format!(
+                "Map {map_id:0>3}: {}, event {event_id:0>3}",
+                map_info.name
+            ))
+        }
+        EditLocation::Database { database, id } => {
+            let data = &update_state.data;
+            let name = match database {
+                Database::Actors => data.actors().data.get(id)?.name.clone(),
+                Database::Classes => data.classes().data.get(id)?.name.clone(),
+                Database::Skills => data.skills().data.get(id)?.name.clone(),
+                Database::Items => data.items().data.get(id)?.name.clone(),
+                Database::Weapons => data.weapons().data.get(id)?.name.clone(),
+                Database::Armors => data.armors().data.get(id)?.name.clone(),
+                Database::Enemies => data.enemies().data.get(id)?.name.clone(),
+                Database::Troops => data.troops().data.get(id)?.name.clone(),
+                Database::States => data.states().data.get(id)?.name.clone(),
+                Database::Animations => data.animations().data.get(id)?.name.clone(),
+            };
+            Some(format!("{database} {:0>4}: {name}", id + 1))
+        }
+        EditLocation::Script { index } => {
+            let scripts = update_state.data.scripts();
+            let script = scripts.data.get(index)?;
+            Some(format!("Script: {}", script.name))
+        }
+    }
+}
+
+/// Describes a location that doesn't exist anymore.
+fn fallback_label(location: EditLocation) -> String {
+    match location {
+        EditLocation::Event { map_id, event_id } => {
+            format!("Map {map_id:0>3}, event {event_id:0>3}")
+        }
+        EditLocation::Database { database, id } => format!("{database} {:0>4}", id + 1),
+        EditLocation::Script { index } => format!("Script {index}"),
+    }
+}
+
+/// Whether the event still exists, loading its map if it isn't loaded.
+fn event_exists(
+    update_state: &luminol_core::UpdateState<'_>,
+    map_id: usize,
+    event_id: usize,
+) -> bool {
+    if !update_state.data.map_infos().data.contains_key(&map_id) {
+        return false;
+    }
+    let map = update_state.data.get_or_load_map(
+        map_id,
+        update_state.filesystem,
+        update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded"),
+    );
+    map.events.get(event_id).is_some()
+}
+
+/// Opens the editor of the recent edit at `index`, or removes it from the list if it doesn't
+/// exist anymore.
+fn jump(update_state: &mut luminol_core::UpdateState<'_>, index: usize) {
+    let location = update_state.recent_edits.locations[index];
+
+    let exists = match location {
+        EditLocation::Event { map_id, event_id } => event_exists(update_state, map_id, event_id),
+        _ => label(update_state, location).is_some(),
+    };
+    if !exists {
+        update_state.recent_edits.locations.remove(index);
+        update_state.save_recent_edits();
+        luminol_core::warn!(
+            update_state.toasts,
+            format!("{} doesn't exist anymore", fallback_label(location))
+        );
+        return;
+    }
+
+    let ctx = update_state.ctx;
+    match location {
+        EditLocation::Event { map_id, event_id } => {
+            crate::tabs::map::open_event_later(ctx, map_id, event_id);
+            super::unsaved_changes::open_editor(Structure::Map(map_id), update_state);
+        }
+        EditLocation::Database { database, id } => {
+            let structure = match database {
+                Database::Actors => {
+                    DatabaseView::select_later::<rpg::Actor>(ctx, id);
+                    Structure::Actors
+                }
+                Database::Classes => {
+                    DatabaseView::select_later::<rpg::Class>(ctx, id);
+                    Structure::Classes
+                }
+                Database::Skills => {
+                    DatabaseView::select_later::<rpg::Skill>(ctx, id);
+                    Structure::Skills
+                }
+                Database::Items => {
+                    DatabaseView::select_later::<rpg::Item>(ctx, id);
+                    Structure::Items
+                }
+                Database::Weapons => {
+                    DatabaseView::select_later::<rpg::Weapon>(ctx, id);
+                    Structure::Weapons
+                }
+                Database::Armors => {
+                    DatabaseView::select_later::<rpg::Armor>(ctx, id);
+                    Structure::Armors
+                }
+                Database::Enemies => {
+                    DatabaseView::select_later::<rpg::Enemy>(ctx, id);
+                    Structure::Enemies
+                }
+                Database::Troops => {
+                    DatabaseView::select_later::<rpg::Troop>(ctx, id);
+                    Structure::Troops
+                }
+                Database::States => {
+                    DatabaseView::select_later::<rpg::State>(ctx, id);
+                    Structure::States
+                }
+                Database::Animations => {
+                    DatabaseView::select_later::<rpg::Animation>(ctx, id);
+                    Structure::Animations
+                }
+            };
+            super::unsaved_changes::open_editor(structure, update_state);
+        }
+        EditLocation::Script { index } => {
+            crate::windows::script_edit::Window::open_script_later(ctx, index);
+            super::unsaved_changes::open_editor(Structure::Scripts, update_state);
+        }
+    }
+
+    update_state.record_edit(location);
+}
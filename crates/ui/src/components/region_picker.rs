@@ -0,0 +1,87 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_graphics::primitives::shadows::MAX_REGION_ID;
+
+/// A strip of region IDs to draw with on the region layer of VX Ace maps, shown in place of the
+/// tilepicker while that layer is selected.
+#[derive(Default)]
+pub struct RegionPicker {
+    pub selected_region: i16,
+}
+
+impl RegionPicker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The color region `region` is drawn with on the map. Region 0 means no region.
+    pub fn color(region: i16) -> egui::Color32 {
+        if region == 0 {
+            return egui::Color32::TRANSPARENT;
+        }
+        // step around the color wheel by the golden angle so neighbouring IDs look different
+        let hue = (region as f32 * 0.618_034).fract();
+        egui::ecolor::Hsva::new(hue, 0.75, 0.9, 0.45).into()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Region");
+        ui.separator();
+
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(2., 2.);
+
+            for region in 0..=MAX_REGION_ID {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(32., 32.), egui::Sense::click());
+
+                if ui.is_rect_visible(rect) {
+                    let painter = ui.painter();
+                    painter.rect_filled(rect, 2., Self::color(region));
+                    painter.text(
+                        rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        region.to_string(),
+                        egui::FontId::monospace(12.),
+                        ui.visuals().text_color(),
+                    );
+
+                    let stroke = if self.selected_region == region {
+                        egui::Stroke::new(2., egui::Color32::WHITE)
+                    } else if response.hovered() {
+                        ui.visuals().widgets.hovered.fg_stroke
+                    } else {
+                        ui.visuals().widgets.noninteractive.bg_stroke
+                    };
+                    painter.rect_stroke(rect, 2., stroke);
+                }
+
+                if response.clicked() {
+                    self.selected_region = region;
+                }
+            }
+        });
+    }
+}
@@ -22,17 +22,23 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use super::UiExt;
+use super::{AssetPreview, Playback, UiExt};
 
 pub struct SoundTab {
     /// The source for this tab.
     pub source: luminol_audio::Source,
     pub audio_file: luminol_data::rpg::AudioFile,
+    /// How fast MIDI files play, as a multiple of their normal tempo
+    midi_speed: f64,
+    /// Where looping MIDI files jump back to in seconds, instead of their loop marker
+    midi_loop_start: Option<f64>,
 
     search_text: String,
     folder_children: Vec<luminol_filesystem::DirEntry>,
     filtered_children: Vec<luminol_filesystem::DirEntry>,
 
+    preview: AssetPreview,
+
     scrolled_on_first_open: bool,
 }
 
@@ -43,72 +49,168 @@ impl SoundTab {
         source: luminol_audio::Source,
         audio_file: luminol_data::rpg::AudioFile,
     ) -> Self {
-        let mut folder_children = filesystem
+        let folder_children = filesystem
             .read_dir(format!("Audio/{source}"))
             .unwrap_or_default();
-        folder_children.sort_unstable_by(|a, b| {
-            lexical_sort::natural_lexical_cmp(a.file_name(), b.file_name())
-        });
         Self {
             source,
             audio_file,
+            midi_speed: 1.,
+            midi_loop_start: None,
 
             filtered_children: folder_children.clone(),
             search_text: String::new(),
             folder_children,
 
+            preview: AssetPreview::new(),
+
             scrolled_on_first_open: false,
         }
     }
 
-    fn play(&self, update_state: &mut luminol_core::UpdateState<'_>) {
-        if let Some(track) = &self.audio_file.name {
-            let path = camino::Utf8Path::new("Audio")
+    fn track_path(&self) -> Option<camino::Utf8PathBuf> {
+        self.audio_file.name.as_ref().map(|track| {
+            camino::Utf8Path::new("Audio")
                 .join(self.source.as_path())
-                .join(track);
-            let pitch = self.audio_file.pitch;
-            let volume = self.audio_file.volume;
-            let source = self.source;
-
-            if let Err(e) = update_state.audio.play(
-                path,
-                update_state.filesystem,
-                volume,
-                pitch,
-                Some(source),
-                update_state
-                    .project_config
-                    .as_ref()
-                    .expect("project not loaded")
-                    .project
-                    .volume_scale,
-            ) {
-                luminol_core::error!(
-                    update_state.toasts,
-                    e.wrap_err("Error playing from audio file")
-                );
-            }
+                .join(track)
+        })
+    }
+
+    fn playback(&self) -> Playback {
+        Playback {
+            source: self.source,
+            volume: self.audio_file.volume,
+            pitch: self.audio_file.pitch,
+            midi_speed: self.midi_speed,
+            midi_loop_start: self.midi_loop_start_samples(),
+        }
+    }
+
+    fn midi_loop_start_samples(&self) -> Option<u64> {
+        self.midi_loop_start
+            .map(|seconds| (seconds * luminol_audio::MIDI_SAMPLE_RATE as f64) as u64)
+    }
+
+    fn play(&self, update_state: &mut luminol_core::UpdateState<'_>) {
+        if let Some(path) = self.track_path() {
+            AssetPreview::play(update_state, path, self.playback());
         } else {
             update_state.audio.stop(self.source);
         }
     }
 
+    /// Shows a button that makes the selected sound the autoplay BGM or BGS of the map whose tab
+    /// is focused.
+    fn use_for_map_ui(&self, ui: &mut egui::Ui, update_state: &mut luminol_core::UpdateState<'_>) {
+        let map_id = crate::tabs::map::focused(ui.ctx());
+        let response = ui
+            .add_enabled(
+                map_id.is_some() && self.audio_file.name.is_some(),
+                egui::Button::new("Use for current map"),
+            )
+            .on_disabled_hover_text(if map_id.is_none() {
+                "Focus a map tab to use this for its map"
+            } else {
+                "Select a sound first"
+            });
+
+        let Some(map_id) = map_id else {
+            return;
+        };
+        let map_name = update_state.data.map_infos().data[&map_id].name.clone();
+        let response = response.on_hover_text(format!(
+            "Autoplays this {} on map {map_id}: {map_name}",
+            self.source
+        ));
+        if !response.clicked() {
+            return;
+        }
+
+        let mut map = update_state.data.get_map(map_id);
+        if self.source == luminol_audio::Source::BGM {
+            map.autoplay_bgm = true;
+            map.bgm = self.audio_file.clone();
+        } else {
+            map.autoplay_bgs = true;
+            map.bgs = self.audio_file.clone();
+        }
+        map.modified = true;
+        update_state.modified.set(true);
+    }
+
+    /// Shows controls for the tempo and loop point of MIDI files, which are applied to the sound
+    /// that's playing right away.
+    fn midi_ui(&mut self, ui: &mut egui::Ui, update_state: &mut luminol_core::UpdateState<'_>) {
+        ui.separator();
+        ui.label("MIDI")
+            .on_hover_text("These settings only affect MIDI files");
+
+        let slider = egui::Slider::new(&mut self.midi_speed, 0.25..=4.)
+            .logarithmic(true)
+            .max_decimals(2)
+            .suffix("×")
+            .text("Tempo");
+        if ui.add(slider).changed() {
+            update_state
+                .audio
+                .set_midi_speed(self.midi_speed, self.source);
+        }
+
+        // Only BGM and BGS loop
+        let looping = matches!(
+            self.source,
+            luminol_audio::Source::BGM | luminol_audio::Source::BGS
+        );
+        ui.add_enabled_ui(looping, |ui| {
+            ui.horizontal(|ui| {
+                let mut custom_loop = self.midi_loop_start.is_some();
+                let mut changed = ui
+                    .checkbox(&mut custom_loop, "Loop from")
+                    .on_hover_text(
+                        "Unchecked, songs loop from their loop marker, like they do in game",
+                    )
+                    .changed();
+                if changed {
+                    self.midi_loop_start = custom_loop.then_some(0.);
+                }
+
+                let mut seconds = self.midi_loop_start.unwrap_or_default();
+                let response = ui.add_enabled(
+                    custom_loop,
+                    egui::DragValue::new(&mut seconds)
+                        .range(0. ..=3600.)
+                        .speed(0.1)
+                        .suffix(" s"),
+                );
+                if custom_loop {
+                    self.midi_loop_start = Some(seconds);
+                }
+                // The song starts over when the loop start changes, so wait until it's been set
+                changed |= response.drag_stopped() || response.lost_focus();
+
+                if changed {
+                    update_state
+                        .audio
+                        .set_midi_loop_start(self.midi_loop_start_samples(), self.source);
+                }
+            });
+        });
+    }
+
     /// Display this SoundTab.
     pub fn ui(&mut self, ui: &mut egui::Ui, update_state: &mut luminol_core::UpdateState<'_>) {
         egui::SidePanel::right("sound_tab_controls")
             .resizable(false)
             .show_inside(ui, |ui| {
                 ui.vertical(|ui| {
-                    ui.horizontal(|ui| {
-                        if ui.button("Play").clicked() {
-                            self.play(update_state);
-                        }
-
-                        if ui.button("Stop").clicked() {
-                            // Stop sound.
-                            update_state.audio.stop(self.source);
-                        }
-                    });
+                    if let Some(path) = self.track_path() {
+                        let playback = self.playback();
+                        self.preview
+                            .ui_with_playback(ui, update_state, &path, playback);
+                    } else if ui.button("Stop").clicked() {
+                        // Stop sound.
+                        update_state.audio.stop(self.source);
+                    }
 
                     ui.horizontal(|ui| {
                         let step = ui
@@ -147,6 +249,15 @@ impl SoundTab {
                                 .set_pitch(self.audio_file.pitch, self.source);
                         };
                     });
+
+                    self.midi_ui(ui, update_state);
+
+                    if matches!(
+                        self.source,
+                        luminol_audio::Source::BGM | luminol_audio::Source::BGS
+                    ) {
+                        self.use_for_map_ui(ui, update_state);
+                    }
                 });
             });
 
@@ -231,12 +342,17 @@ impl SoundTab {
                                             entry_name.as_str(),
                                         ));
                                         if res.clicked() {
-                                            self.audio_file.name = Some(
+                                            let name: luminol_data::Path = Some(
                                                 entry_name
                                                     .file_stem()
                                                     .unwrap_or(entry_name.as_str())
                                                     .into(),
                                             );
+                                            if self.audio_file.name != name {
+                                                // Loop points belong to a song
+                                                self.midi_loop_start = None;
+                                            }
+                                            self.audio_file.name = name;
                                         }
                                         res
                                     });
@@ -26,6 +26,8 @@ pub struct Tilepicker {
     pub view: luminol_graphics::Tilepicker,
 
     drag_origin: Option<egui::Pos2>,
+    /// Set when the selection changed from outside the tilepicker so that it's scrolled into view.
+    scroll_to_selection: bool,
 
     /// When true, brush tile ID randomization is enabled.
     pub brush_random: bool,
@@ -75,11 +77,18 @@ impl Tilepicker {
         let tilesets = update_state.data.tilesets();
         let tileset = &tilesets.data[map.tileset_id];
 
+        let editor_ver = update_state
+            .project_config
+            .as_ref()
+            .unwrap()
+            .project
+            .editor_ver;
         let view = luminol_graphics::Tilepicker::new(
             &update_state.graphics,
             tileset,
             update_state.filesystem,
             false,
+            editor_ver,
         );
 
         let mut brush_seed = [0u8; 16];
@@ -103,6 +112,7 @@ impl Tilepicker {
             selected_tiles_bottom: 0,
 
             drag_origin: None,
+            scroll_to_selection: false,
             brush_seed,
             brush_random: false,
         }
@@ -147,6 +157,181 @@ impl Tilepicker {
         }
     }
 
+    /// Returns the position of a tile in the tilepicker, in tiles.
+    pub fn tile_position(tile: SelectedTile) -> (i16, i16) {
+        match tile {
+            SelectedTile::Autotile(autotile) => (autotile, 0),
+            SelectedTile::Tile(id) => ((id - 384) % 8, (id - 384) / 8 + 1),
+        }
+    }
+
+    /// Returns the top-left tile of the selection.
+    pub fn first_selected_tile(&self) -> SelectedTile {
+        match self.selected_tiles_top {
+            ..=0 => SelectedTile::Autotile(self.selected_tiles_left),
+            y => SelectedTile::Tile(self.selected_tiles_left + (y - 1) * 8 + 384),
+        }
+    }
+
+    /// Selects a single tile in the tilepicker and scrolls to it.
+    pub fn select_tile(&mut self, tile: SelectedTile) {
+        let (x, y) = Self::tile_position(tile);
+        self.selected_tiles_left = x;
+        self.selected_tiles_right = x;
+        self.selected_tiles_top = y;
+        self.selected_tiles_bottom = y;
+        self.scroll_to_selection = true;
+    }
+
+    /// Selects the tiles from `min` to `max` inclusive, given as positions in the tilepicker.
+    pub fn select_rect(&mut self, min: (i16, i16), max: (i16, i16)) {
+        self.selected_tiles_left = min.0;
+        self.selected_tiles_right = max.0;
+        self.selected_tiles_top = min.1;
+        self.selected_tiles_bottom = max.1;
+        self.scroll_to_selection = true;
+    }
+
+    /// Returns true if exactly this one tile is selected in the tilepicker.
+    pub fn is_tile_selected(&self, tile: SelectedTile) -> bool {
+        let (x, y) = Self::tile_position(tile);
+        self.selected_tiles_left == x
+            && self.selected_tiles_right == x
+            && self.selected_tiles_top == y
+            && self.selected_tiles_bottom == y
+    }
+
+    /// Displays a row of buttons for the tiles that have a special meaning to the engine, so that
+    /// they can be picked without hunting for them in the tilepicker.
+    pub fn special_tiles_ui(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new("Special").underline());
+
+            let erase = SelectedTile::Autotile(0);
+            if ui
+                .selectable_label(self.is_tile_selected(erase), "⌫ Erase")
+                .on_hover_text("Tile 0, the empty tile. Drawing with it clears the selected layer")
+                .clicked()
+            {
+                self.select_tile(erase);
+            }
+        })
+        .response
+    }
+
+    /// Displays the project's smart tiles for a tileset, and a button to tag the selected block of
+    /// tiles as a new one. Clicking a smart tile switches the brush to it.
+    pub fn smart_tiles_ui(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        tileset_id: usize,
+        ui: &mut egui::Ui,
+    ) -> egui::Response {
+        let smart_tiles = &mut update_state
+            .project_config
+            .as_mut()
+            .expect("project not loaded")
+            .project
+            .smart_tiles;
+        let toolbar = &mut *update_state.toolbar;
+
+        let mut modified = false;
+        let mut deleted = None;
+
+        let response = ui
+            .horizontal_wrapped(|ui| {
+                ui.label(egui::RichText::new("Smart tiles").underline());
+
+                for (i, smart_tile) in smart_tiles
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(_, smart_tile)| smart_tile.tileset_id == tileset_id)
+                {
+                    let selected =
+                        toolbar.pencil == luminol_core::Pencil::Smart && toolbar.smart_tile == i;
+                    let response = ui
+                        .selectable_label(selected, &smart_tile.name)
+                        .on_hover_text(match smart_tile.layout {
+                            luminol_config::project::SmartTileLayout::Block3x3 => {
+                                "3x3 block. Right-click to rename or delete"
+                            }
+                            luminol_config::project::SmartTileLayout::Full47 => {
+                                "47 variants. Right-click to rename or delete"
+                            }
+                        });
+                    if response.clicked() {
+                        toolbar.pencil = luminol_core::Pencil::Smart;
+                        toolbar.smart_tile = i;
+
+                        let (x, y) = Self::tile_position(SelectedTile::Tile(smart_tile.origin));
+                        let (width, height) = smart_tile.layout.size();
+                        self.selected_tiles_left = x;
+                        self.selected_tiles_top = y;
+                        self.selected_tiles_right = x + width - 1;
+                        self.selected_tiles_bottom = y + height - 1;
+                    }
+                    response.context_menu(|ui| {
+                        modified |= ui.text_edit_singleline(&mut smart_tile.name).changed();
+                        if ui.button("Delete").clicked() {
+                            deleted = Some(i);
+                            ui.close_menu();
+                        }
+                    });
+                }
+
+                // Smart tiles are made of regular tiles, either in a 3x3 block or in the same
+                // layout as the 47 autotile variants
+                let layout = luminol_config::project::SmartTileLayout::from_size(
+                    self.selected_tiles_right - self.selected_tiles_left + 1,
+                    self.selected_tiles_bottom - self.selected_tiles_top + 1,
+                )
+                .filter(|_| self.selected_tiles_top > 0);
+                if ui
+                    .add_enabled(layout.is_some(), egui::Button::new("+ Tag selection"))
+                    .on_hover_text(
+                        "Select a 3x3 block of tiles, or an 8x6 block with the 47 variants of an \
+                        autotile, to draw it like an autotile",
+                    )
+                    .on_disabled_hover_text(
+                        "Select a 3x3 block of tiles, or an 8x6 block with the 47 variants of an \
+                        autotile, outside of the autotile row",
+                    )
+                    .clicked()
+                {
+                    if let Some(layout) = layout {
+                        let origin =
+                            384 + self.selected_tiles_left + (self.selected_tiles_top - 1) * 8;
+                        toolbar.pencil = luminol_core::Pencil::Smart;
+                        toolbar.smart_tile = smart_tiles.len();
+                        smart_tiles.push(luminol_config::project::SmartTile {
+                            name: format!("Smart tile {}", smart_tiles.len() + 1),
+                            tileset_id,
+                            layout,
+                            origin,
+                        });
+                        modified = true;
+                    }
+                }
+            })
+            .response;
+
+        if let Some(i) = deleted {
+            smart_tiles.remove(i);
+            if toolbar.smart_tile == i && toolbar.pencil == luminol_core::Pencil::Smart {
+                toolbar.pencil = luminol_core::Pencil::Pen;
+            } else if toolbar.smart_tile > i {
+                toolbar.smart_tile -= 1;
+            }
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+        }
+
+        response
+    }
+
     pub fn ui(
         &mut self,
         update_state: &luminol_core::UpdateState<'_>,
@@ -196,8 +381,16 @@ impl Tilepicker {
             (self.selected_tiles_top * 32) as f32..=((self.selected_tiles_bottom + 1) * 32) as f32,
         )
         .translate(canvas_rect.min.to_vec2());
+        let selection_color = update_state
+            .global_config
+            .overlay_palette
+            .colors()
+            .tile_selection;
         ui.painter()
-            .rect_stroke(rect, 5.0, egui::Stroke::new(1.0, egui::Color32::WHITE));
+            .rect_stroke(rect, 5.0, egui::Stroke::new(1.0, selection_color));
+        if std::mem::take(&mut self.scroll_to_selection) {
+            ui.scroll_to_rect(rect, Some(egui::Align::Center));
+        }
 
         let Some(pos) = response.interact_pointer_pos() else {
             return response;
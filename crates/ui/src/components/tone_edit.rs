@@ -0,0 +1,65 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// Edits a [`luminol_data::Tone`] with a slider for each of its channels.
+pub struct ToneEdit<'a> {
+    tone: &'a mut luminol_data::Tone,
+}
+
+impl<'a> ToneEdit<'a> {
+    pub fn new(tone: &'a mut luminol_data::Tone) -> Self {
+        Self { tone }
+    }
+}
+
+impl<'a> egui::Widget for ToneEdit<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let mut changed = false;
+        let channels = [
+            ("Red", &mut self.tone.red, -255.),
+            ("Green", &mut self.tone.green, -255.),
+            ("Blue", &mut self.tone.blue, -255.),
+            ("Gray", &mut self.tone.gray, 0.),
+        ];
+
+        let mut response = ui
+            .vertical(|ui| {
+                for (label, value, min) in channels {
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(value, min..=255.)
+                                .fixed_decimals(0)
+                                .text(label),
+                        )
+                        .changed();
+                }
+            })
+            .response;
+
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
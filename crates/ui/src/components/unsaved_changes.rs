@@ -0,0 +1,203 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Structure;
+
+/// The top bar popover listing the data that has changed since it was last saved, with a way to
+/// open the editor for each entry or revert it to what's on disk.
+#[derive(Default)]
+pub struct UnsavedChanges {
+    confirmation: Option<luminol_core::Confirmation>,
+    pending_revert: Option<Structure>,
+}
+
+impl UnsavedChanges {
+    pub fn ui(&mut self, ui: &mut egui::Ui, update_state: &mut luminol_core::UpdateState<'_>) {
+        let confirmation = self.confirmation.get_or_insert_with(|| {
+            luminol_core::Confirmation::new(ui.ctx(), "luminol_unsaved_changes_confirmation")
+        });
+
+        let modified = update_state.data.modified_structures();
+        if !modified.is_empty() {
+            let mut open = None;
+            let mut revert = None;
+
+            ui.menu_button(format!("● {} unsaved", modified.len()), |ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                egui::Grid::new("luminol_unsaved_changes_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Data");
+                        ui.strong("Modified");
+                        ui.strong("On disk");
+                        ui.label("");
+                        ui.end_row();
+
+                        for &(structure, timestamps) in &modified {
+                            let response = ui.add_enabled(
+                                structure != Structure::Tilesets,
+                                egui::Button::new(label(update_state, structure)).frame(false),
+                            );
+                            if response.on_hover_text("Open in editor").clicked() {
+                                open = Some(structure);
+                            }
+
+                            ui.label(
+                                timestamps
+                                    .modified_since
+                                    .map_or_else(String::new, |time| elapsed_text(time.elapsed())),
+                            );
+                            ui.weak(elapsed_text(timestamps.on_disk.elapsed()));
+
+                            if ui.button("Revert").clicked() {
+                                revert = Some(structure);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                if open.is_some() || revert.is_some() {
+                    ui.close_menu();
+                }
+            });
+
+            ui.separator();
+
+            if let Some(structure) = open {
+                open_editor(structure, update_state);
+            }
+
+            if let Some(structure) = revert {
+                if update_state.confirm_destructive(
+                    confirmation,
+                    luminol_config::global::DestructiveAction::RevertData,
+                ) {
+                    revert_structure(structure, update_state);
+                } else {
+                    self.pending_revert = Some(structure);
+                }
+            }
+        }
+
+        if confirmation.show(update_state.global_config).is_some() {
+            if let Some(structure) = self.pending_revert.take() {
+                revert_structure(structure, update_state);
+            }
+        }
+    }
+}
+
+fn revert_structure(structure: Structure, update_state: &mut luminol_core::UpdateState<'_>) {
+    match update_state.reload_data(structure) {
+        Ok(()) => luminol_core::info!(update_state.toasts, format!("Reverted {structure}")),
+        Err(e) => luminol_core::error!(
+            update_state.toasts,
+            e.wrap_err(format!("While reverting {structure}"))
+        ),
+    }
+}
+
+fn label(update_state: &luminol_core::UpdateState<'_>, structure: Structure) -> String {
+    match structure {
+        Structure::Map(id) => {
+            let name = update_state
+                .data
+                .map_infos()
+                .data
+                .get(&id)
+                .map(|info| info.name.clone())
+                .unwrap_or_default();
+            format!("{structure}: {name}")
+        }
+        _ => structure.to_string(),
+    }
+}
+
+fn elapsed_text(elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", secs / 60),
+        _ => format!("{} h {} min ago", secs / 3600, secs / 60 % 60),
+    }
+}
+
+/// Opens the editor for `structure`, the same one as in the Data menu.
+pub(crate) fn open_editor(structure: Structure, update_state: &mut luminol_core::UpdateState<'_>) {
+    use crate::windows;
+
+    match structure {
+        Structure::Actors => {
+            let window = windows::actors::Window::new(update_state);
+            update_state.edit_windows.add_window(window)
+        }
+        Structure::Animations => update_state
+            .edit_windows
+            .add_window(windows::animations::Window::default()),
+        Structure::Armors => update_state
+            .edit_windows
+            .add_window(windows::armor::Window::new()),
+        Structure::Classes => update_state
+            .edit_windows
+            .add_window(windows::classes::Window::new()),
+        Structure::CommonEvents => update_state
+            .edit_windows
+            .add_window(windows::common_event_edit::Window::default()),
+        Structure::Enemies => {
+            let window = windows::enemies::Window::new(update_state);
+            update_state.edit_windows.add_window(window)
+        }
+        Structure::Items => update_state
+            .edit_windows
+            .add_window(windows::items::Window::new()),
+        Structure::Skills => update_state
+            .edit_windows
+            .add_window(windows::skills::Window::new()),
+        Structure::States => update_state
+            .edit_windows
+            .add_window(windows::states::Window::new()),
+        Structure::Tilesets => {}
+        Structure::Troops => update_state
+            .edit_windows
+            .add_window(windows::troops::Window::new()),
+        Structure::Weapons => update_state
+            .edit_windows
+            .add_window(windows::weapons::Window::new()),
+        Structure::MapInfos => update_state
+            .edit_windows
+            .add_window(windows::map_picker::Window::default()),
+        Structure::Scripts => update_state
+            .edit_windows
+            .add_window(windows::script_edit::Window::default()),
+        Structure::System => update_state
+            .edit_windows
+            .add_window(windows::system::Window::new()),
+        Structure::Map(id) => {
+            let tab = crate::tabs::map::Tab::new(id, update_state);
+            update_state.edit_tabs.add_tab(tab);
+        }
+    }
+}
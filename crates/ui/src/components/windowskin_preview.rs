@@ -0,0 +1,158 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::prelude::*;
+
+use super::preview_background;
+
+/// The directory windowskins are read from.
+pub const WINDOWSKIN_DIRECTORY: &str = "Graphics/Windowskins";
+
+/// The size of the sample window.
+const SAMPLE_SIZE: (u32, u32) = (320, 80);
+/// The opacity RGSS draws the background of message windows with.
+const BACK_OPACITY: u8 = 160;
+
+/// Shows a windowskin as a sample window, with its text colors as swatches below it.
+#[derive(Default)]
+pub struct WindowskinPreview {
+    cached: Option<Cached>,
+}
+
+struct Cached {
+    name: camino::Utf8PathBuf,
+    /// The sample window and the windowskin's text colors, or why they couldn't be made.
+    preview: Result<(egui::TextureHandle, Vec<egui::Color32>), String>,
+}
+
+impl WindowskinPreview {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Shows the preview of the windowskin named `name`, which is loaded the first time it is
+    /// shown.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &UpdateState<'_>,
+        name: Option<&camino::Utf8Path>,
+    ) -> egui::Response {
+        let Some(name) = name else {
+            return ui.weak("No windowskin");
+        };
+        if !self
+            .cached
+            .as_ref()
+            .is_some_and(|cached| cached.name.as_path() == name)
+        {
+            self.cached = Some(Cached {
+                name: name.to_path_buf(),
+                preview: Self::load(ui.ctx(), update_state, name),
+            });
+        }
+        let Some(cached) = &self.cached else {
+            unreachable!()
+        };
+
+        match &cached.preview {
+            Err(error) => ui.colored_label(ui.visuals().error_fg_color, error),
+            Ok((texture, colors)) => {
+                ui.vertical(|ui| {
+                    let (rect, _) =
+                        ui.allocate_exact_size(texture.size_vec2(), egui::Sense::hover());
+                    ui.painter().add(preview_background(
+                        update_state.global_config.preview_background,
+                        rect,
+                        ui.clip_rect(),
+                    ));
+                    egui::Image::from_texture(egui::load::SizedTexture::from(texture))
+                        .paint_at(ui, rect);
+                    if let Some(&color) = colors.first() {
+                        ui.painter().text(
+                            rect.min + egui::vec2(16., 16.),
+                            egui::Align2::LEFT_TOP,
+                            "Sample text",
+                            egui::FontId::proportional(20.),
+                            color,
+                        );
+                    }
+
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing.x = 2.;
+                        for (i, &color) in colors.iter().enumerate() {
+                            let (rect, response) = ui
+                                .allocate_exact_size(egui::Vec2::splat(12.), egui::Sense::hover());
+                            ui.painter().rect(
+                                rect,
+                                0.,
+                                color,
+                                ui.visuals().widgets.noninteractive.fg_stroke,
+                            );
+                            // the message code that uses this color
+                            response.on_hover_text(format!("\\C[{i}]"));
+                        }
+                    });
+                })
+                .response
+            }
+        }
+    }
+
+    fn load(
+        ctx: &egui::Context,
+        update_state: &UpdateState<'_>,
+        name: &camino::Utf8Path,
+    ) -> Result<(egui::TextureHandle, Vec<egui::Color32>), String> {
+        let skin = update_state
+            .graphics
+            .texture_loader
+            .decode_image(
+                update_state.filesystem,
+                camino::Utf8Path::new(WINDOWSKIN_DIRECTORY).join(name),
+            )
+            .map_err(|error| format!("Couldn't load {name}: {error}"))?;
+        let window = luminol_graphics::windowskin::compose_window(
+            &skin,
+            SAMPLE_SIZE.0,
+            SAMPLE_SIZE.1,
+            BACK_OPACITY,
+        )
+        .ok_or_else(|| format!("{name} is too small to be a windowskin"))?;
+
+        let colors = luminol_graphics::windowskin::text_colors(&skin)
+            .into_iter()
+            .map(|[r, g, b, a]| egui::Color32::from_rgba_unmultiplied(r, g, b, a))
+            .collect();
+        let texture = ctx.load_texture(
+            format!("luminol_windowskin_preview_{name}"),
+            egui::ColorImage::from_rgba_unmultiplied(
+                [window.width() as usize, window.height() as usize],
+                window.as_raw(),
+            ),
+            egui::TextureOptions::NEAREST,
+        );
+        Ok((texture, colors))
+    }
+}
@@ -164,8 +164,10 @@ tab_enum! {
 // I tried adding #[cfg] support in the macro and it would compile but rust-analyzer wasn't happy with it
 #[cfg(not(target_arch = "wasm32"))]
 window_enum! {
+    #[allow(clippy::large_enum_variant)]
     pub enum Window {
         About(windows::about::Window),
+        Appearance(windows::appearance::Window),
         CommonEvent(windows::common_event_edit::Window),
         ProjectConfig(windows::config_window::Window),
         Console(windows::console::Window),
@@ -184,8 +186,10 @@ window_enum! {
 }
 #[cfg(target_arch = "wasm32")]
 window_enum! {
+    #[allow(clippy::large_enum_variant)]
     pub enum Window {
         About(windows::about::Window),
+        Appearance(windows::appearance::Window),
         CommonEvent(windows::common_event_edit::Window),
         ProjectConfig(windows::config_window::Window),
         EventEdit(windows::event_edit::Window),
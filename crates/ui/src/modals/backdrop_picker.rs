@@ -0,0 +1,399 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+
+use crate::components::UiExt;
+use luminol_core::prelude::*;
+// the texture loader decodes images with the graphics crate's version of `image`
+use luminol_graphics::image;
+
+/// The size transitions are previewed at. Transitions are made for the full 640x480 screen, but
+/// the animation is done on the CPU every frame so it's scaled down.
+const TRANSITION_PREVIEW_SIZE: (u32, u32) = (320, 240);
+/// How many seconds the transition preview takes, and how long it then holds the second frame.
+const TRANSITION_DURATION: f64 = 1.5;
+const TRANSITION_HOLD: f64 = 0.5;
+/// How soft the edge of the transition is, like the `vague` argument of `Graphics.transition`.
+const TRANSITION_VAGUE: f32 = 40.;
+
+/// The full-screen graphics that [`Modal`] can pick.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Battleback,
+    Title,
+    Gameover,
+    Transition,
+}
+
+impl Kind {
+    pub fn directory(self) -> &'static str {
+        match self {
+            Self::Battleback => "Graphics/Battlebacks",
+            Self::Title => "Graphics/Titles",
+            Self::Gameover => "Graphics/Gameovers",
+            Self::Transition => "Graphics/Transitions",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Battleback => "Battleback Picker",
+            Self::Title => "Title Graphic Picker",
+            Self::Gameover => "Gameover Graphic Picker",
+            Self::Transition => "Transition Picker",
+        }
+    }
+
+    /// The area of the screen this graphic covers in game, which previews are letterboxed into.
+    fn screen_size(self) -> egui::Vec2 {
+        match self {
+            Self::Battleback => egui::vec2(640., 320.),
+            Self::Title | Self::Gameover | Self::Transition => egui::vec2(640., 480.),
+        }
+    }
+}
+
+/// Picks a battleback, title, gameover or transition graphic, with a large preview of the
+/// selection. Transitions are previewed as an animated wipe between two sample frames.
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+    kind: Kind,
+}
+
+enum State {
+    Closed,
+    Open {
+        entries: Vec<camino::Utf8PathBuf>,
+        filtered_entries: Vec<camino::Utf8PathBuf>,
+        search_text: String,
+
+        selected: Option<camino::Utf8PathBuf>,
+        /// Previews of the graphics that have been selected since the picker was opened. They
+        /// are dropped, freeing their textures, when it closes.
+        previews: HashMap<camino::Utf8PathBuf, Result<Preview, String>>,
+    },
+}
+
+enum Preview {
+    Image(egui::TextureHandle),
+    Transition {
+        /// The brightness of each pixel of the transition graphic, scaled to
+        /// [`TRANSITION_PREVIEW_SIZE`].
+        mask: image::GrayImage,
+        /// The texture the current step of the animation is drawn to.
+        texture: egui::TextureHandle,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>, kind: Kind) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+            kind,
+        }
+    }
+
+    fn load_entries(&self, update_state: &mut UpdateState<'_>) -> Vec<camino::Utf8PathBuf> {
+        match update_state.filesystem.read_dir(self.kind.directory()) {
+            Ok(entries) => entries
+                .into_iter()
+                .filter_map(|m| m.path.file_stem().map(camino::Utf8PathBuf::from))
+                .collect(),
+            Err(error) => {
+                luminol_core::error!(
+                    update_state.toasts,
+                    error.wrap_err(format!("While listing {}", self.kind.directory()))
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    fn filter(entries: &[camino::Utf8PathBuf], filter: &str) -> Vec<camino::Utf8PathBuf> {
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+        entries
+            .iter()
+            .filter(|entry| matcher.fuzzy(entry.as_str(), filter, false).is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    type Data<'m> = &'m mut Option<camino::Utf8PathBuf>;
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        |ui: &mut egui::Ui| {
+            let is_open = matches!(self.state, State::Open { .. });
+            let text = data.as_ref().map_or("(None)", |name| name.as_str());
+            let mut response = ui.add(egui::Button::new(text).selected(is_open));
+
+            if response.clicked() && !is_open {
+                let entries = self.load_entries(update_state);
+                self.state = State::Open {
+                    filtered_entries: entries.clone(),
+                    entries,
+                    search_text: String::new(),
+                    selected: data.clone(),
+                    previews: HashMap::new(),
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), data) {
+                response.mark_changed();
+            }
+
+            response
+        }
+    }
+
+    fn reset(&mut self, _update_state: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        self.state = State::Closed;
+    }
+}
+
+impl Modal {
+    fn show_window(
+        &mut self,
+        update_state: &UpdateState<'_>,
+        ctx: &egui::Context,
+        data: &mut Option<camino::Utf8PathBuf>,
+    ) -> bool {
+        let State::Open {
+            entries,
+            filtered_entries,
+            search_text,
+            selected,
+            previews,
+        } = &mut self.state
+        else {
+            return false;
+        };
+
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        egui::Window::new(self.kind.title())
+            .resizable(true)
+            .default_size([880., 540.])
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .show(ctx, |ui| {
+                egui::SidePanel::left(self.id_source.with("sidebar")).show_inside(ui, |ui| {
+                    let out = egui::TextEdit::singleline(search_text)
+                        .hint_text("Search 🔎")
+                        .show(ui);
+                    if out.response.changed() {
+                        *filtered_entries = Self::filter(entries, search_text);
+                    }
+
+                    ui.separator();
+
+                    ui.with_cross_justify(|ui| {
+                        egui::ScrollArea::vertical()
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+
+                                if ui.selectable_label(selected.is_none(), "(None)").clicked() {
+                                    *selected = None;
+                                }
+                                for (i, entry) in filtered_entries.iter().enumerate() {
+                                    ui.with_stripe(i % 2 == 0, |ui| {
+                                        let checked = selected.as_ref() == Some(entry);
+                                        let response = ui.selectable_label(checked, entry.as_str());
+                                        if response.double_clicked() {
+                                            keep_open = false;
+                                            needs_save = true;
+                                        }
+                                        if response.clicked() {
+                                            *selected = Some(entry.clone());
+                                        }
+                                    });
+                                }
+                            });
+                    });
+                });
+
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom")).show_inside(ui, |ui| {
+                    ui.add_space(ui.style().spacing.item_spacing.y);
+                    crate::components::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    let Some(name) = selected.as_ref() else {
+                        ui.weak("No graphic");
+                        return;
+                    };
+                    let preview = previews.entry(name.clone()).or_insert_with(|| {
+                        Self::load_preview(ui.ctx(), update_state, self.kind, name)
+                    });
+                    match preview {
+                        Ok(preview) => Self::preview_ui(ui, self.kind, preview),
+                        Err(error) => {
+                            ui.colored_label(ui.visuals().error_fg_color, error.as_str());
+                        }
+                    }
+                });
+            });
+
+        if needs_save {
+            data.clone_from(selected);
+        }
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+
+        needs_save
+    }
+
+    fn load_preview(
+        ctx: &egui::Context,
+        update_state: &UpdateState<'_>,
+        kind: Kind,
+        name: &camino::Utf8Path,
+    ) -> Result<Preview, String> {
+        let image = update_state
+            .graphics
+            .texture_loader
+            .decode_image(
+                update_state.filesystem,
+                camino::Utf8Path::new(kind.directory()).join(name),
+            )
+            .map_err(|error| format!("Couldn't load {name}: {error}"))?;
+        let texture_name = format!("luminol_backdrop_preview_{}_{name}", kind.directory());
+
+        if kind != Kind::Transition {
+            let texture = ctx.load_texture(
+                texture_name,
+                egui::ColorImage::from_rgba_unmultiplied(
+                    [image.width() as usize, image.height() as usize],
+                    image.as_raw(),
+                ),
+                egui::TextureOptions::LINEAR,
+            );
+            return Ok(Preview::Image(texture));
+        }
+
+        let (width, height) = TRANSITION_PREVIEW_SIZE;
+        let mask = image::imageops::grayscale(&image::imageops::resize(
+            &image,
+            width,
+            height,
+            image::imageops::FilterType::Triangle,
+        ));
+        let texture = ctx.load_texture(
+            texture_name,
+            transition_frame(&mask, 0.),
+            egui::TextureOptions::LINEAR,
+        );
+        Ok(Preview::Transition { mask, texture })
+    }
+
+    /// Shows a preview letterboxed into the area of the screen it covers in game, scaled down to
+    /// fit the available space.
+    fn preview_ui(ui: &mut egui::Ui, kind: Kind, preview: &mut Preview) {
+        let screen_size = kind.screen_size();
+        let scale = (ui.available_width() / screen_size.x)
+            .min(ui.available_height() / screen_size.y)
+            .clamp(0.25, 1.);
+        let (screen_rect, _) = ui.allocate_exact_size(screen_size * scale, egui::Sense::hover());
+        ui.painter()
+            .rect_filled(screen_rect, 0., egui::Color32::BLACK);
+
+        let texture = match preview {
+            Preview::Image(texture) => texture,
+            Preview::Transition { mask, texture } => {
+                let cycle = TRANSITION_DURATION + TRANSITION_HOLD;
+                let progress = (ui.input(|i| i.time) % cycle / TRANSITION_DURATION).min(1.);
+                texture.set(
+                    transition_frame(mask, progress as f32),
+                    egui::TextureOptions::LINEAR,
+                );
+                ui.ctx().request_repaint();
+                texture
+            }
+        };
+
+        // Keep the graphic's aspect ratio, with black bars on the sides that it doesn't cover
+        let image_size = texture.size_vec2();
+        let fit = (screen_rect.width() / image_size.x).min(screen_rect.height() / image_size.y);
+        let image_rect = egui::Rect::from_center_size(screen_rect.center(), image_size * fit);
+        egui::Image::from_texture(egui::load::SizedTexture::from(&*texture))
+            .paint_at(ui, image_rect);
+
+        ui.weak(format!(
+            "{}x{} (shown at {}x{} in game)",
+            image_size.x, image_size.y, screen_size.x, screen_size.y
+        ));
+    }
+}
+
+/// Draws one step of a transition between two sample frames. Like `Graphics.transition`, the
+/// second frame shows through the darkest parts of the transition graphic first.
+fn transition_frame(mask: &image::GrayImage, progress: f32) -> egui::ColorImage {
+    const FROM: [egui::Color32; 2] = [
+        egui::Color32::from_rgb(70, 110, 170),
+        egui::Color32::from_rgb(140, 190, 230),
+    ];
+    const TO: [egui::Color32; 2] = [
+        egui::Color32::from_rgb(60, 20, 30),
+        egui::Color32::from_rgb(150, 50, 40),
+    ];
+
+    let threshold = progress * (255. + TRANSITION_VAGUE);
+    let height = mask.height().max(1) as f32;
+    let pixels = mask
+        .enumerate_pixels()
+        .map(|(_, y, image::Luma([brightness]))| {
+            // Both sample frames are vertical gradients
+            let t = y as f32 / height;
+            let from = lerp_color(FROM[0], FROM[1], t);
+            let to = lerp_color(TO[0], TO[1], t);
+            let amount = ((threshold - *brightness as f32) / TRANSITION_VAGUE).clamp(0., 1.);
+            lerp_color(from, to, amount)
+        })
+        .collect();
+
+    egui::ColorImage {
+        size: [mask.width() as usize, mask.height() as usize],
+        pixels,
+    }
+}
+
+fn lerp_color(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    egui::Color32::from_rgb(
+        channel(a.r(), b.r()),
+        channel(a.g(), b.g()),
+        channel(a.b(), b.b()),
+    )
+}
@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{Cellpicker, UiExt};
+use crate::components::{AssetPreview, Cellpicker, UiExt};
 use luminol_core::prelude::*;
 
 use super::Entry;
@@ -32,6 +32,8 @@ pub struct Modal {
     id_source: egui::Id,
     animation_name: Option<camino::Utf8PathBuf>,
     animation_hue: i32,
+    preview: AssetPreview,
+
     scrolled_on_first_open: bool,
 }
 
@@ -52,6 +54,7 @@ impl Modal {
             id_source,
             animation_name: animation.animation_name.clone(),
             animation_hue: animation.animation_hue,
+            preview: AssetPreview::new(),
             scrolled_on_first_open: false,
         }
     }
@@ -216,6 +219,12 @@ impl Modal {
                                                 !*invalid,
                                                 egui::SelectableLabel::new(checked, text),
                                             );
+                                            let res = self.preview.on_hover(
+                                                res,
+                                                update_state,
+                                                &camino::Utf8Path::new("Graphics/Animations")
+                                                    .join(&*path),
+                                            );
 
                                             if res.clicked() {
                                                 self.animation_name = Some(
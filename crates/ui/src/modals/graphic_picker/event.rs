@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{EnumComboBox, UiExt};
+use crate::components::{preview_background, AssetPreview, EnumComboBox, UiExt};
 use color_eyre::eyre::WrapErr;
 use egui::Widget;
 use luminol_core::prelude::*;
@@ -37,6 +37,8 @@ pub struct Modal {
 
     button_sprite: Option<ButtonSprite>,
 
+    preview: AssetPreview,
+
     scrolled_on_first_open: bool,
 }
 
@@ -103,6 +105,7 @@ impl Modal {
 
             button_sprite,
 
+            preview: AssetPreview::new(),
             scrolled_on_first_open: false,
         }
     }
@@ -131,8 +134,9 @@ impl luminol_core::Modal for Modal {
                 let selected = if let Some(tile_id) = data.tile_id {
                     let tilepicker = Self::load_tilepicker(update_state, self.tileset_id);
 
+                    // Graphic tile ids are stored offset by one, since 0 means "no tile"
                     Selected::Tile {
-                        tile_id,
+                        tile_id: tile_id + 1,
                         tilepicker,
                     }
                 } else if let Some(path) = data.character_name.clone() {
@@ -219,11 +223,18 @@ impl Modal {
         let tilesets = update_state.data.tilesets();
         let tileset = &tilesets.data[tileset_id];
 
+        let editor_ver = update_state
+            .project_config
+            .as_ref()
+            .unwrap()
+            .project
+            .editor_ver;
         let mut tilepicker = Tilepicker::new(
             &update_state.graphics,
             tileset,
             update_state.filesystem,
-            true,
+            false,
+            editor_ver,
         );
         tilepicker.tiles.auto_opacity = false;
 
@@ -386,6 +397,12 @@ impl Modal {
                                                 !*invalid,
                                                 egui::SelectableLabel::new(checked, text),
                                             );
+                                            let res = self.preview.on_hover(
+                                                res,
+                                                update_state,
+                                                &camino::Utf8Path::new("Graphics/Characters")
+                                                    .join(&*path),
+                                            );
 
                                             if res.clicked() {
                                                 let name = camino::Utf8PathBuf::from(
@@ -544,7 +561,10 @@ impl Modal {
                                 tilepicker,
                             } => {
                                 let (canvas_rect, response) = ui.allocate_exact_size(
-                                    egui::vec2(256., tilepicker.atlas.tileset_height() as f32),
+                                    egui::vec2(
+                                        256.,
+                                        tilepicker.atlas.tileset_height() as f32 + 32.,
+                                    ),
                                     egui::Sense::click(),
                                 );
 
@@ -576,6 +596,11 @@ impl Modal {
                                     ui.input(|i| i.time),
                                 );
 
+                                ui.painter().add(preview_background(
+                                    update_state.global_config.preview_background,
+                                    canvas_rect,
+                                    absolute_scroll_rect,
+                                ));
                                 let painter =
                                     Painter::new(tilepicker.prepare(&update_state.graphics));
                                 ui.painter()
@@ -584,10 +609,8 @@ impl Modal {
                                         painter,
                                     ));
 
-                                let tile_x = (*tile_id - 384) % 8;
-                                let tile_y = (*tile_id - 384) / 8;
                                 let rect = egui::Rect::from_min_size(
-                                    egui::Pos2::new(tile_x as f32, tile_y as f32) * 32.,
+                                    tile_id_to_position(*tile_id) * 32.,
                                     egui::Vec2::splat(32.),
                                 )
                                 .translate(canvas_rect.min.to_vec2());
@@ -601,7 +624,11 @@ impl Modal {
                                     let pos = (response.interact_pointer_pos().unwrap()
                                         - response.rect.min)
                                         / 32.;
-                                    *tile_id = pos.x as usize + pos.y as usize * 8 + 384;
+                                    if let Some(id) =
+                                        position_to_tile_id(pos.x as usize, pos.y as usize)
+                                    {
+                                        *tile_id = id;
+                                    }
                                 }
                             }
                         });
@@ -615,7 +642,7 @@ impl Modal {
                     data.character_name = None;
                 }
                 Selected::Tile { tile_id, .. } => {
-                    data.tile_id = Some(*tile_id);
+                    data.tile_id = Some(*tile_id - 1);
                     data.character_name = None;
                 }
                 Selected::Graphic {
@@ -644,3 +671,26 @@ impl Modal {
         needs_save
     }
 }
+
+/// Returns the position (in tiles) of a tile in the tilepicker, where the first row holds the
+/// autotiles and the tileset proper starts on the second row.
+fn tile_id_to_position(tile_id: usize) -> egui::Pos2 {
+    if tile_id < 384 {
+        egui::pos2((tile_id / 48) as f32, 0.)
+    } else {
+        egui::pos2(
+            ((tile_id - 384) % 8) as f32,
+            ((tile_id - 384) / 8 + 1) as f32,
+        )
+    }
+}
+
+/// The inverse of [`tile_id_to_position`]. Returns `None` for the empty autotile slot.
+fn position_to_tile_id(x: usize, y: usize) -> Option<usize> {
+    match (x, y) {
+        (0, 0) => None,
+        // Use the same autotile shape that the tilepicker displays
+        (x, 0) => Some(x * 48 + 47),
+        (x, y) => Some(384 + (y - 1) * 8 + x),
+    }
+}
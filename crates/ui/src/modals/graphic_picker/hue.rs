@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::UiExt;
+use crate::components::{AssetPreview, UiExt};
 use color_eyre::eyre::WrapErr;
 use luminol_core::prelude::*;
 
@@ -37,6 +37,8 @@ pub struct Modal {
 
     button_sprite: Option<ButtonSprite>,
 
+    preview: AssetPreview,
+
     scrolled_on_first_open: bool,
 }
 
@@ -84,6 +86,7 @@ impl Modal {
             button_size,
             directory,
             button_sprite,
+            preview: AssetPreview::new(),
             scrolled_on_first_open: false,
         }
     }
@@ -274,6 +277,7 @@ impl Modal {
                                         ui,
                                         rows,
                                         selected,
+                                        &mut self.preview,
                                         |path| {
                                             Self::load_preview_sprite(
                                                 update_state,
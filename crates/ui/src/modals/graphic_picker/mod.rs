@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::UiExt;
+use crate::components::{preview_background, AssetPreview, UiExt};
 use luminol_core::prelude::*;
 
 pub mod actor;
@@ -100,7 +100,7 @@ impl Entry {
         update_state: &UpdateState<'_>,
         directory: &camino::Utf8Path,
     ) -> Vec<Self> {
-        let mut entries: Vec<_> = update_state
+        update_state
             .filesystem
             .read_dir(directory)
             .unwrap()
@@ -109,11 +109,7 @@ impl Entry {
                 path: m.path.file_name().unwrap_or_default().into(),
                 invalid: false,
             })
-            .collect();
-        entries.sort_unstable_by(|a, b| {
-            lexical_sort::natural_lexical_cmp(a.path.as_str(), b.path.as_str())
-        });
-        entries
+            .collect()
     }
 
     fn filter(entries: &[Self], filter: &str) -> Vec<Entry> {
@@ -125,6 +121,7 @@ impl Entry {
             .collect()
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn ui(
         entries: &mut [Self],
         directory: &camino::Utf8Path,
@@ -132,6 +129,7 @@ impl Entry {
         ui: &mut egui::Ui,
         rows: std::ops::Range<usize>,
         selected: &mut Selected,
+        preview: &mut AssetPreview,
         load_preview_sprite: impl Fn(&camino::Utf8Path) -> PreviewSprite,
     ) {
         let selected_name = match &selected {
@@ -152,6 +150,7 @@ impl Entry {
             let faint = (i + rows.start) % 2 == 0;
             ui.with_stripe(faint, |ui| {
                 let res = ui.add_enabled(!*invalid, egui::SelectableLabel::new(checked, text));
+                let res = preview.on_hover(res, update_state, &directory.join(&*path));
 
                 if res.clicked() {
                     *selected = Selected::Entry {
@@ -215,6 +214,11 @@ impl PreviewSprite {
             glam::Vec2::ONE,
         );
 
+        ui.painter().add(preview_background(
+            update_state.global_config.preview_background,
+            canvas_rect,
+            absolute_scroll_rect,
+        ));
         let painter = Painter::new(self.sprite.prepare(&update_state.graphics));
         ui.painter()
             .add(luminol_egui_wgpu::Callback::new_paint_callback(
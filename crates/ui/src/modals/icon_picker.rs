@@ -0,0 +1,189 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::sync::Arc;
+
+use crate::components::{IconGrid, ICON_DIRECTORY};
+use luminol_core::prelude::*;
+
+/// Picks an icon from `Graphics/Icons` using an [`IconGrid`].
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+
+    grid: IconGrid,
+    /// The icon shown on the button, along with its name.
+    button_icon: Option<(camino::Utf8PathBuf, Option<Arc<Texture>>)>,
+}
+
+enum State {
+    Closed,
+    Open {
+        selected: Option<camino::Utf8PathBuf>,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+            grid: IconGrid::new(),
+            button_icon: None,
+        }
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    type Data<'m> = &'m mut Option<camino::Utf8PathBuf>;
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        |ui: &mut egui::Ui| {
+            let is_open = matches!(self.state, State::Open { .. });
+            let desired_size = egui::vec2(32., 32.) + ui.spacing().button_padding * 2.;
+            let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
+
+            let visuals = ui.style().interact_selectable(&response, is_open);
+            let rect = rect.expand(visuals.expansion);
+            ui.painter()
+                .rect(rect, visuals.rounding, visuals.bg_fill, visuals.bg_stroke);
+
+            if self.button_icon.as_ref().map(|(name, _)| name) != data.as_ref() {
+                self.button_icon = data.clone().map(|name| {
+                    let texture = update_state
+                        .graphics
+                        .texture_loader
+                        .load_now_dir(update_state.filesystem, ICON_DIRECTORY, &name)
+                        .ok();
+                    (name, texture)
+                });
+            }
+            if let Some((name, texture)) = &self.button_icon {
+                match texture {
+                    Some(texture) => {
+                        let size = texture.size_vec2();
+                        let scale = (rect.shrink(4.).size() / size).min_elem().min(1.);
+                        egui::Image::from_texture(egui::load::SizedTexture::from(&**texture))
+                            .paint_at(
+                                ui,
+                                egui::Rect::from_center_size(rect.center(), size * scale),
+                            );
+                    }
+                    None => {
+                        ui.painter().text(
+                            rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            "?",
+                            egui::FontId::default(),
+                            ui.visuals().error_fg_color,
+                        );
+                    }
+                }
+                response = response.on_hover_text(name.as_str());
+            }
+
+            if response.clicked() && !is_open {
+                self.state = State::Open {
+                    selected: data.clone(),
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), data) {
+                response.mark_changed();
+            }
+
+            response
+        }
+    }
+
+    fn reset(&mut self, _update_state: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        self.state = State::Closed;
+        self.grid.reload();
+    }
+}
+
+impl Modal {
+    fn show_window(
+        &mut self,
+        update_state: &UpdateState<'_>,
+        ctx: &egui::Context,
+        data: &mut Option<camino::Utf8PathBuf>,
+    ) -> bool {
+        let State::Open { selected } = &mut self.state else {
+            return false;
+        };
+
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        egui::Window::new("Icon Picker")
+            .resizable(true)
+            .default_size([400., 300.])
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom")).show_inside(ui, |ui| {
+                    ui.add_space(ui.style().spacing.item_spacing.y);
+                    crate::components::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(selected.is_none(), "(None)").clicked() {
+                            *selected = None;
+                        }
+                        ui.label(selected.as_ref().map_or("", |name| name.as_str()));
+                    });
+                    ui.separator();
+
+                    if let Some(name) = self.grid.ui(ui, update_state, selected.as_deref(), None) {
+                        // double click to pick an icon right away
+                        if selected.as_ref() == Some(&name)
+                            && ui.input(|i| {
+                                i.pointer
+                                    .button_double_clicked(egui::PointerButton::Primary)
+                            })
+                        {
+                            keep_open = false;
+                            needs_save = true;
+                        }
+                        *selected = Some(name);
+                    }
+                });
+            });
+
+        if needs_save {
+            data.clone_from(selected);
+        }
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+
+        needs_save
+    }
+}
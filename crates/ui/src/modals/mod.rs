@@ -26,6 +26,12 @@ pub mod sound_picker;
 
 pub mod graphic_picker;
 
+pub mod icon_picker;
+
+pub mod windowskin_picker;
+
+pub mod backdrop_picker;
+
 pub mod database_modal;
 
 pub mod animations;
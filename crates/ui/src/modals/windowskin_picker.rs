@@ -0,0 +1,205 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::{UiExt, WindowskinPreview, WINDOWSKIN_DIRECTORY};
+use luminol_core::prelude::*;
+
+/// Picks a windowskin from `Graphics/Windowskins`, previewing the selection as a window.
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+
+    preview: WindowskinPreview,
+}
+
+enum State {
+    Closed,
+    Open {
+        entries: Vec<camino::Utf8PathBuf>,
+        filtered_entries: Vec<camino::Utf8PathBuf>,
+        search_text: String,
+
+        selected: Option<camino::Utf8PathBuf>,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+            preview: WindowskinPreview::new(),
+        }
+    }
+
+    fn load_entries(update_state: &mut UpdateState<'_>) -> Vec<camino::Utf8PathBuf> {
+        match update_state.filesystem.read_dir(WINDOWSKIN_DIRECTORY) {
+            Ok(entries) => entries
+                .into_iter()
+                .filter_map(|m| m.path.file_stem().map(camino::Utf8PathBuf::from))
+                .collect(),
+            Err(error) => {
+                luminol_core::error!(
+                    update_state.toasts,
+                    error.wrap_err("While listing the windowskins")
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    fn filter(entries: &[camino::Utf8PathBuf], filter: &str) -> Vec<camino::Utf8PathBuf> {
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+        entries
+            .iter()
+            .filter(|entry| matcher.fuzzy(entry.as_str(), filter, false).is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    type Data<'m> = &'m mut Option<camino::Utf8PathBuf>;
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        |ui: &mut egui::Ui| {
+            let is_open = matches!(self.state, State::Open { .. });
+            let text = data.as_ref().map_or("(None)", |name| name.as_str());
+            let mut response = ui.add(egui::Button::new(text).selected(is_open));
+
+            if response.clicked() && !is_open {
+                let entries = Self::load_entries(update_state);
+                self.state = State::Open {
+                    filtered_entries: entries.clone(),
+                    entries,
+                    search_text: String::new(),
+                    selected: data.clone(),
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), data) {
+                response.mark_changed();
+            }
+
+            response
+        }
+    }
+
+    fn reset(&mut self, _update_state: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        self.state = State::Closed;
+        // the windowskin may have been changed on disk
+        self.preview = WindowskinPreview::new();
+    }
+}
+
+impl Modal {
+    fn show_window(
+        &mut self,
+        update_state: &UpdateState<'_>,
+        ctx: &egui::Context,
+        data: &mut Option<camino::Utf8PathBuf>,
+    ) -> bool {
+        let State::Open {
+            entries,
+            filtered_entries,
+            search_text,
+            selected,
+        } = &mut self.state
+        else {
+            return false;
+        };
+
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        egui::Window::new("Windowskin Picker")
+            .resizable(true)
+            .default_size([560., 300.])
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .show(ctx, |ui| {
+                egui::SidePanel::left(self.id_source.with("sidebar")).show_inside(ui, |ui| {
+                    let out = egui::TextEdit::singleline(search_text)
+                        .hint_text("Search 🔎")
+                        .show(ui);
+                    if out.response.changed() {
+                        *filtered_entries = Self::filter(entries, search_text);
+                    }
+
+                    ui.separator();
+
+                    ui.with_cross_justify(|ui| {
+                        egui::ScrollArea::vertical()
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+
+                                if ui.selectable_label(selected.is_none(), "(None)").clicked() {
+                                    *selected = None;
+                                }
+                                for (i, entry) in filtered_entries.iter().enumerate() {
+                                    ui.with_stripe(i % 2 == 0, |ui| {
+                                        let checked = selected.as_ref() == Some(entry);
+                                        let response = ui.selectable_label(checked, entry.as_str());
+                                        if response.double_clicked() {
+                                            keep_open = false;
+                                            needs_save = true;
+                                        }
+                                        if response.clicked() {
+                                            *selected = Some(entry.clone());
+                                        }
+                                    });
+                                }
+                            });
+                    });
+                });
+
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom")).show_inside(ui, |ui| {
+                    ui.add_space(ui.style().spacing.item_spacing.y);
+                    crate::components::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    egui::ScrollArea::both()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            self.preview.ui(ui, update_state, selected.as_deref());
+                        });
+                });
+            });
+
+        if needs_save {
+            data.clone_from(selected);
+        }
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+
+        needs_save
+    }
+}
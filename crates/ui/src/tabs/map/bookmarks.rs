@@ -0,0 +1,150 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_config::bookmarks::{Bookmark, Bookmarks};
+use luminol_filesystem::FileSystem;
+
+fn read_bookmarks(filesystem: &impl FileSystem) -> Bookmarks {
+    filesystem
+        .read_to_string(Bookmarks::PATH)
+        .ok()
+        .and_then(|s| ron::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+impl super::Editor {
+    /// Loads the bookmarks of a map from the project's bookmarks file.
+    pub(super) fn load_bookmarks(filesystem: &impl FileSystem, map_id: usize) -> Vec<Bookmark> {
+        read_bookmarks(filesystem)
+            .maps
+            .remove(&map_id)
+            .unwrap_or_default()
+    }
+
+    /// Writes the bookmarks of this map to the project's bookmarks file, leaving the bookmarks of
+    /// other maps alone.
+    fn save_bookmarks(&self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        let c = "While saving map bookmarks";
+
+        // Other map tabs may have changed their bookmarks since we loaded ours
+        let mut bookmarks = read_bookmarks(filesystem);
+        if self.bookmarks.is_empty() {
+            bookmarks.maps.remove(&self.id);
+        } else {
+            bookmarks.maps.insert(self.id, self.bookmarks.clone());
+        }
+
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let bookmarks = ron::ser::to_string_pretty(&bookmarks, pretty_config).wrap_err(c)?;
+        filesystem.write(Bookmarks::PATH, bookmarks).wrap_err(c)
+    }
+
+    /// Adds a bookmark at the map cursor, named `name` or a generated name if `name` is empty.
+    pub(super) fn add_bookmark_at_cursor(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        name: String,
+    ) {
+        let name = if name.trim().is_empty() {
+            format!("Bookmark {}", self.bookmarks.len() + 1)
+        } else {
+            name
+        };
+        self.bookmarks.push(Bookmark {
+            name,
+            x: self.view.cursor_pos.x as usize,
+            y: self.view.cursor_pos.y as usize,
+        });
+
+        if let Err(e) = self.save_bookmarks(update_state.filesystem) {
+            luminol_core::error!(update_state.toasts, e);
+        }
+    }
+
+    /// Shows the contents of the bookmarks menu.
+    pub(super) fn bookmarks_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+        let mut jump_to = None;
+        let mut delete = None;
+        if self.bookmarks.is_empty() {
+            ui.weak("No bookmarks on this map");
+        }
+        egui::Grid::new(egui::Id::new("luminol_map_bookmarks").with(self.id))
+            .striped(true)
+            .show(ui, |ui| {
+                for (index, bookmark) in self.bookmarks.iter().enumerate() {
+                    if ui
+                        .button(&bookmark.name)
+                        .on_hover_text(format!("({}, {})", bookmark.x, bookmark.y))
+                        .clicked()
+                    {
+                        jump_to = Some(egui::pos2(bookmark.x as f32, bookmark.y as f32));
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button(egui::RichText::new("🗑").color(egui::Color32::RED))
+                        .on_hover_text("Delete this bookmark")
+                        .clicked()
+                    {
+                        delete = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(tile) = jump_to {
+            let map = update_state.data.get_map(self.id);
+            self.view.center_on(ui.ctx(), &map, tile);
+        }
+        if let Some(index) = delete {
+            self.bookmarks.remove(index);
+            if let Err(e) = self.save_bookmarks(update_state.filesystem) {
+                luminol_core::error!(update_state.toasts, e);
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_bookmark_name)
+                    .hint_text("Bookmark name")
+                    .desired_width(120.),
+            );
+            if ui
+                .button("Add at cursor")
+                .on_hover_text("Ctrl+B adds a bookmark at the cursor without opening this menu")
+                .clicked()
+            {
+                let name = std::mem::take(&mut self.new_bookmark_name);
+                self.add_bookmark_at_cursor(update_state, name);
+            }
+        });
+    }
+}
@@ -23,18 +23,82 @@
 // Program grant you additional permission to convey the resulting work.
 use crate::components::SelectedTile;
 use itertools::Itertools;
+use luminol_graphics::primitives::shadows;
+use std::collections::HashSet;
+
+impl super::Editor {
+    /// Paints with the brush at the keyboard cursor when space or enter is pressed. It's a stroke
+    /// like a click with the mouse, so it's added to the history the same way once the mouse isn't
+    /// drawing. Shapes are drawn from one corner of the keyboard rectangle to the other, while the
+    /// other brushes are used on every tile of it.
+    pub(super) fn handle_keyboard_brush(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        tile_layer: usize,
+    ) {
+        let Some(cursor) = self.view.keyboard_cursor else {
+            return;
+        };
+        let pencil = update_state.toolbar.pencil;
+        // The shadow pen works on quarters of tiles, which the keyboard cursor can't point at
+        if matches!(
+            pencil,
+            luminol_core::Pencil::Shadow | luminol_core::Pencil::Select
+        ) || self.drawing_shape_pos.is_some()
+            || !ui.input(|i| i.key_pressed(egui::Key::Space) || i.key_pressed(egui::Key::Enter))
+        {
+            return;
+        }
+
+        self.tilemap_undo_cache_layer = tile_layer;
+        self.tilemap_undo_cache.copy_from_slice(&self.layer_cache);
+
+        let anchor = self.view.keyboard_anchor.unwrap_or(cursor);
+        let positions = match pencil {
+            luminol_core::Pencil::Line
+            | luminol_core::Pencil::Rectangle
+            | luminol_core::Pencil::Circle => vec![(anchor.x, anchor.y), (cursor.x, cursor.y)],
+            luminol_core::Pencil::Fill => vec![(cursor.x, cursor.y)],
+            _ => {
+                let rect = egui::Rect::from_two_pos(anchor, cursor);
+                (rect.min.y as usize..=rect.max.y as usize)
+                    .cartesian_product(rect.min.x as usize..=rect.max.x as usize)
+                    .map(|(y, x)| (x as f32, y as f32))
+                    .collect_vec()
+            }
+        };
+        for (x, y) in positions {
+            self.handle_brush(
+                x as usize,
+                y as usize,
+                tile_layer,
+                update_state.toolbar,
+                update_state
+                    .project_config
+                    .as_ref()
+                    .map(|config| &config.project),
+                map,
+            );
+        }
+    }
 
-impl super::Tab {
     pub(super) fn handle_brush(
         &mut self,
         map_x: usize,
         map_y: usize,
         tile_layer: usize,
-        pencil: luminol_core::Pencil,
+        toolbar: &luminol_core::ToolbarState,
+        project: Option<&luminol_config::project::Project>,
         map: &mut luminol_data::rpg::Map,
     ) {
+        let pencil = toolbar.pencil;
         let map_pos = egui::pos2(map_x as f32, map_y as f32);
-        let initial_tile = SelectedTile::from_id(map.data[(map_x, map_y, tile_layer)]);
+        let initial_id = map.data[(map_x, map_y, tile_layer)];
+        let initial_tile = SelectedTile::from_id(initial_id);
+        let is_region_layer = self.view.is_region_layer_selected();
+        let map_loop = self.view.map_loop();
         let left = self.tilepicker.selected_tiles_left;
         let right = self.tilepicker.selected_tiles_right;
         let top = self.tilepicker.selected_tiles_top;
@@ -44,10 +108,21 @@ impl super::Tab {
 
         match pencil {
             luminol_core::Pencil::Pen => {
-                let (rect_width, rect_height) = if self.tilepicker.brush_random {
+                let (rect_width, rect_height) = if self.tilepicker.brush_random || is_region_layer {
                     (1, 1)
                 } else {
-                    (width, height)
+                    (width as i32, height as i32)
+                };
+
+                // A brush size of 1 stamps the selected tiles with their top-left corner on the
+                // cursor, and larger brush sizes fill the brush area with the selected tiles
+                let offsets = if toolbar.brush_size > 1 {
+                    toolbar.brush_offsets().collect_vec()
+                } else {
+                    (0..rect_height)
+                        .cartesian_product(0..rect_width)
+                        .map(|(y, x)| (x, y))
+                        .collect_vec()
                 };
 
                 let drawing_shape_pos = if let Some(drawing_shape_pos) = self.drawing_shape_pos {
@@ -56,29 +131,102 @@ impl super::Tab {
                     self.drawing_shape_pos = Some(map_pos);
                     map_pos
                 };
-                for (y, x) in (0..rect_height).cartesian_product(0..rect_width) {
-                    let absolute_x = map_x + x as usize;
-                    let absolute_y = map_y + y as usize;
+                for (offset_x, offset_y) in offsets {
+                    let absolute_x = map_x as i32 + offset_x;
+                    let absolute_y = map_y as i32 + offset_y;
 
-                    // Skip out-of-bounds tiles
-                    if absolute_x >= map.data.xsize() || absolute_y >= map.data.ysize() {
+                    // Wrap around the edges that loop and skip tiles past the other edges
+                    let Some((wrapped_x, wrapped_y)) =
+                        map_loop.wrap(absolute_x, absolute_y, map.data.xsize(), map.data.ysize())
+                    else {
                         continue;
-                    }
+                    };
 
                     self.set_tile(
                         map,
                         self.tilepicker.get_tile_from_offset(
-                            absolute_x as i16,
-                            absolute_y as i16,
+                            wrapped_x as i16,
+                            wrapped_y as i16,
                             tile_layer as i16,
-                            x + (map_x as f32 - drawing_shape_pos.x) as i16,
-                            y + (map_y as f32 - drawing_shape_pos.y) as i16,
+                            (absolute_x - drawing_shape_pos.x as i32) as i16,
+                            (absolute_y - drawing_shape_pos.y as i32) as i16,
                         ),
-                        (absolute_x, absolute_y, tile_layer),
+                        (wrapped_x, wrapped_y, tile_layer),
                     );
                 }
             }
 
+            luminol_core::Pencil::Custom => {
+                let Some(shape) =
+                    project.and_then(|project| project.brush_shapes.get(toolbar.brush_shape))
+                else {
+                    return;
+                };
+
+                let drawing_shape_pos = if let Some(drawing_shape_pos) = self.drawing_shape_pos {
+                    drawing_shape_pos
+                } else {
+                    self.drawing_shape_pos = Some(map_pos);
+                    map_pos
+                };
+                for (offset_x, offset_y) in shape.offsets() {
+                    let absolute_x = map_x as i32 + offset_x;
+                    let absolute_y = map_y as i32 + offset_y;
+
+                    // Wrap around the edges that loop and skip tiles past the other edges
+                    let Some((wrapped_x, wrapped_y)) =
+                        map_loop.wrap(absolute_x, absolute_y, map.data.xsize(), map.data.ysize())
+                    else {
+                        continue;
+                    };
+
+                    self.set_tile(
+                        map,
+                        self.tilepicker.get_tile_from_offset(
+                            wrapped_x as i16,
+                            wrapped_y as i16,
+                            tile_layer as i16,
+                            (absolute_x - drawing_shape_pos.x as i32) as i16,
+                            (absolute_y - drawing_shape_pos.y as i32) as i16,
+                        ),
+                        (wrapped_x, wrapped_y, tile_layer),
+                    );
+                }
+            }
+
+            luminol_core::Pencil::Smart => {
+                // Smart tiles are made of tiles from one tileset, so they can't be drawn on maps
+                // that use another one
+                let Some(smart_tile) = project
+                    .and_then(|project| project.smart_tiles.get(toolbar.smart_tile))
+                    .filter(|smart_tile| smart_tile.tileset_id == map.tileset_id)
+                else {
+                    return;
+                };
+                if is_region_layer {
+                    return;
+                }
+
+                // Marks the stroke as started so that it's added to the history as one entry
+                if self.drawing_shape_pos.is_none() {
+                    self.drawing_shape_pos = Some(map_pos);
+                }
+
+                for (offset_x, offset_y) in toolbar.brush_offsets() {
+                    let absolute_x = map_x as i32 + offset_x;
+                    let absolute_y = map_y as i32 + offset_y;
+
+                    // Wrap around the edges that loop and skip tiles past the other edges
+                    let Some((wrapped_x, wrapped_y)) =
+                        map_loop.wrap(absolute_x, absolute_y, map.data.xsize(), map.data.ysize())
+                    else {
+                        continue;
+                    };
+
+                    self.set_smart_tile(map, smart_tile, (wrapped_x, wrapped_y, tile_layer));
+                }
+            }
+
             luminol_core::Pencil::Fill => {
                 let drawing_shape_pos = if let Some(drawing_shape_pos) = self.drawing_shape_pos {
                     drawing_shape_pos
@@ -87,6 +235,36 @@ impl super::Tab {
                     map_pos
                 };
 
+                let is_matching = |id: i16| {
+                    if is_region_layer {
+                        shadows::region_id(id) == shadows::region_id(initial_id)
+                    } else {
+                        SelectedTile::from_id(id) == initial_tile
+                    }
+                };
+
+                if toolbar.fill_global {
+                    // Replace every matching tile on the layer, whether or not it's contiguous
+                    for (y, x) in (0..map.data.ysize()).cartesian_product(0..map.data.xsize()) {
+                        let position = (x, y, tile_layer);
+                        if !is_matching(map.data[position]) {
+                            continue;
+                        }
+                        self.set_tile(
+                            map,
+                            self.tilepicker.get_tile_from_offset(
+                                x as i16,
+                                y as i16,
+                                tile_layer as i16,
+                                x as i16 - drawing_shape_pos.x as i16,
+                                y as i16 - drawing_shape_pos.y as i16,
+                            ),
+                            position,
+                        );
+                    }
+                    return;
+                }
+
                 // Use depth-first search to find all of the orthogonally
                 // contiguous matching tiles
                 let mut stack = vec![(map_x, map_y, tile_layer); 1];
@@ -104,23 +282,20 @@ impl super::Tab {
                     );
                     self.dfs_cache[position.0 + position.1 * map.data.xsize()] = true;
 
-                    let x_array: [isize; 4] = [-1, 1, 0, 0];
-                    let y_array: [isize; 4] = [0, 0, -1, 1];
+                    let x_array: [i32; 4] = [-1, 1, 0, 0];
+                    let y_array: [i32; 4] = [0, 0, -1, 1];
                     for (x, y) in x_array.into_iter().zip(y_array.into_iter()) {
-                        // Don't search tiles that are out of bounds
-                        if (x == -1 && position.0 == 0)
-                            || (x == 1 && position.0 + 1 == map.data.xsize())
-                            || (y == -1 && position.1 == 0)
-                            || (y == 1 && position.1 + 1 == map.data.ysize())
-                        {
+                        // Don't search tiles that are out of bounds, but carry on across the
+                        // edges that loop
+                        let Some((x, y)) = map_loop.wrap(
+                            position.0 as i32 + x,
+                            position.1 as i32 + y,
+                            map.data.xsize(),
+                            map.data.ysize(),
+                        ) else {
                             continue;
-                        }
-
-                        let position = (
-                            position.0.saturating_add_signed(x),
-                            position.1.saturating_add_signed(y),
-                            position.2,
-                        );
+                        };
+                        let position = (x, y, position.2);
 
                         // Don't search tiles that we've already searched before
                         // because that would cause an infinite loop
@@ -128,7 +303,7 @@ impl super::Tab {
                             continue;
                         }
 
-                        if SelectedTile::from_id(map.data[position]) == initial_tile {
+                        if is_matching(map.data[position]) {
                             stack.push(position);
                         }
                     }
@@ -157,12 +332,23 @@ impl super::Tab {
                                 self.brush_layer_cache[x + y * map.data.xsize()];
                         }
                     }
+                    self.mark_tile_dirty(0, 0);
+                    self.mark_tile_dirty(map.data.xsize() - 1, map.data.ysize() - 1);
                 }
 
                 if let Some(drawing_shape_pos) = self.drawing_shape_pos {
                     let bounding_rect = egui::Rect::from_two_pos(drawing_shape_pos, map_pos);
-                    for y in (bounding_rect.min.y as usize)..=(bounding_rect.max.y as usize) {
-                        for x in (bounding_rect.min.x as usize)..=(bounding_rect.max.x) as usize {
+                    let (min_x, min_y) =
+                        (bounding_rect.min.x as usize, bounding_rect.min.y as usize);
+                    let (max_x, max_y) =
+                        (bounding_rect.max.x as usize, bounding_rect.max.y as usize);
+                    for y in min_y..=max_y {
+                        for x in min_x..=max_x {
+                            // A hollow rectangle is only drawn along its border
+                            let is_border = x == min_x || x == max_x || y == min_y || y == max_y;
+                            if toolbar.shape_hollow && !is_border {
+                                continue;
+                            }
                             let position = (x, y, tile_layer);
                             self.set_tile(
                                 map,
@@ -182,6 +368,68 @@ impl super::Tab {
                 }
             }
 
+            luminol_core::Pencil::Line => {
+                if !self.drawing_shape {
+                    // Save the current layer
+                    for x in 0..map.data.xsize() {
+                        for y in 0..map.data.ysize() {
+                            self.brush_layer_cache[x + y * map.data.xsize()] =
+                                map.data[(x, y, tile_layer)];
+                        }
+                    }
+                    self.drawing_shape = true;
+                } else {
+                    // Restore the previously stored state of the current layer
+                    for y in 0..map.data.ysize() {
+                        for x in 0..map.data.xsize() {
+                            map.data[(x, y, tile_layer)] =
+                                self.brush_layer_cache[x + y * map.data.xsize()];
+                        }
+                    }
+                    self.mark_tile_dirty(0, 0);
+                    self.mark_tile_dirty(map.data.xsize() - 1, map.data.ysize() - 1);
+                }
+
+                // Unlike the other shapes, the line is also drawn on the frame the mouse is
+                // pressed so that a click without dragging places a single tile
+                let drawing_shape_pos = *self.drawing_shape_pos.get_or_insert(map_pos);
+                // Use Bresenham's algorithm so that diagonal lines have no gaps. If the start
+                // and the end are the same tile, this draws just that tile.
+                let (mut x, mut y) = (drawing_shape_pos.x as i32, drawing_shape_pos.y as i32);
+                let (end_x, end_y) = (map_x as i32, map_y as i32);
+                let dx = (end_x - x).abs();
+                let dy = -(end_y - y).abs();
+                let step_x = if x < end_x { 1 } else { -1 };
+                let step_y = if y < end_y { 1 } else { -1 };
+                let mut error = dx + dy;
+                loop {
+                    self.set_tile(
+                        map,
+                        self.tilepicker.get_tile_from_offset(
+                            x as i16,
+                            y as i16,
+                            tile_layer as i16,
+                            x as i16 - drawing_shape_pos.x as i16,
+                            y as i16 - drawing_shape_pos.y as i16,
+                        ),
+                        (x as usize, y as usize, tile_layer),
+                    );
+
+                    if x == end_x && y == end_y {
+                        break;
+                    }
+                    let doubled_error = 2 * error;
+                    if doubled_error >= dy {
+                        error += dy;
+                        x += step_x;
+                    }
+                    if doubled_error <= dx {
+                        error += dx;
+                        y += step_y;
+                    }
+                }
+            }
+
             luminol_core::Pencil::Circle => {
                 if !self.drawing_shape {
                     // Save the current layer
@@ -200,6 +448,8 @@ impl super::Tab {
                                 self.brush_layer_cache[x + y * map.data.xsize()];
                         }
                     }
+                    self.mark_tile_dirty(0, 0);
+                    self.mark_tile_dirty(map.data.xsize() - 1, map.data.ysize() - 1);
                 }
 
                 // Use Bresenham's algorithm to draw the ellipse.
@@ -207,20 +457,13 @@ impl super::Tab {
                 // (x, y).
                 if let Some(drawing_shape_pos) = self.drawing_shape_pos {
                     let bounding_rect = egui::Rect::from_two_pos(drawing_shape_pos, map_pos);
+                    // The tiles of the filled ellipse are collected first so that the outline can
+                    // be found from them
+                    let mut cells = HashSet::new();
                     // Edge case: Bresenham's algorithm breaks down when drawing a
                     // 1x1 ellipse.
                     if drawing_shape_pos == map_pos {
-                        self.set_tile(
-                            map,
-                            self.tilepicker.get_tile_from_offset(
-                                map_x as i16,
-                                map_y as i16,
-                                tile_layer as i16,
-                                map_x as i16 - drawing_shape_pos.x as i16,
-                                map_y as i16 - drawing_shape_pos.y as i16,
-                            ),
-                            (map_x, map_y, tile_layer),
-                        );
+                        cells.insert((map_x, map_y));
                     } else {
                         let bounding_rect = bounding_rect.translate(egui::vec2(0.5, 0.5));
 
@@ -261,17 +504,7 @@ impl super::Tab {
                                 for j in [x, -x] {
                                     let x = (x0 + j).floor();
                                     let y = (y0 + i).floor();
-                                    self.set_tile(
-                                        map,
-                                        self.tilepicker.get_tile_from_offset(
-                                            x as i16,
-                                            y as i16,
-                                            tile_layer as i16,
-                                            x as i16 - drawing_shape_pos.x as i16,
-                                            y as i16 - drawing_shape_pos.y as i16,
-                                        ),
-                                        (x as usize, y as usize, tile_layer),
-                                    );
+                                    cells.insert((x as usize, y as usize));
                                 }
                             }
 
@@ -306,17 +539,7 @@ impl super::Tab {
                                 for j in [y, -y] {
                                     let x = (x0 + i).floor();
                                     let y = (y0 + j).floor();
-                                    self.set_tile(
-                                        map,
-                                        self.tilepicker.get_tile_from_offset(
-                                            x as i16,
-                                            y as i16,
-                                            tile_layer as i16,
-                                            x as i16 - drawing_shape_pos.x as i16,
-                                            y as i16 - drawing_shape_pos.y as i16,
-                                        ),
-                                        (x as usize, y as usize, tile_layer),
-                                    );
+                                    cells.insert((x as usize, y as usize));
                                 }
                             }
 
@@ -334,10 +557,75 @@ impl super::Tab {
                             y += 1.;
                         }
                     }
+
+                    // The outline is every tile of the ellipse that is next to a tile outside of
+                    // it, which leaves no gaps between the tiles of the outline at any size
+                    let is_outline = |&(x, y): &(usize, usize)| {
+                        x == 0
+                            || y == 0
+                            || !cells.contains(&(x - 1, y))
+                            || !cells.contains(&(x + 1, y))
+                            || !cells.contains(&(x, y - 1))
+                            || !cells.contains(&(x, y + 1))
+                    };
+                    let cells = cells
+                        .iter()
+                        .copied()
+                        .filter(|cell| !toolbar.shape_hollow || is_outline(cell))
+                        .collect_vec();
+                    for (x, y) in cells {
+                        self.set_tile(
+                            map,
+                            self.tilepicker.get_tile_from_offset(
+                                x as i16,
+                                y as i16,
+                                tile_layer as i16,
+                                x as i16 - drawing_shape_pos.x as i16,
+                                y as i16 - drawing_shape_pos.y as i16,
+                            ),
+                            (x, y, tile_layer),
+                        );
+                    }
                 } else {
                     self.drawing_shape_pos = Some(map_pos);
                 }
             }
+
+            luminol_core::Pencil::Shadow => {
+                let Some(hover_pos) = self.view.hover_pos else {
+                    return;
+                };
+
+                // Find the quarter of the tile under the cursor
+                let mut quadrant = 1;
+                if hover_pos.x.rem_euclid(1.) >= 0.5 {
+                    quadrant <<= 1;
+                }
+                if hover_pos.y.rem_euclid(1.) >= 0.5 {
+                    quadrant <<= 2;
+                }
+
+                let position = (map_x, map_y, tile_layer);
+                let value = map.data[position];
+
+                // The first quadrant of a stroke decides whether the stroke adds or removes
+                // shadows so that dragging over a quadrant doesn't keep toggling it
+                if self.drawing_shape_pos.is_none() {
+                    self.drawing_shape_pos = Some(map_pos);
+                    self.shadow_pen_adds = value & quadrant == 0;
+                }
+
+                // Only the low nibble holds shadows, region IDs are in the high byte
+                map.data[position] = if self.shadow_pen_adds {
+                    value | quadrant
+                } else {
+                    value & !quadrant
+                };
+                self.mark_tile_dirty(map_x, map_y);
+            }
+
+            // Selecting is handled by the clipboard, not the brush
+            luminol_core::Pencil::Select => {}
         };
     }
 }
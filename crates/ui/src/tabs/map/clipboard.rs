@@ -0,0 +1,297 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use luminol_core::TileClipboard;
+
+/// What the select tool is pasting, and where its preview was drawn last.
+pub(super) struct Paste {
+    clipboard: Arc<TileClipboard>,
+    /// The top-left tile and the selected layer the preview was drawn at.
+    preview: Option<(usize, usize, usize)>,
+}
+
+impl super::Editor {
+    /// Handles the select tool for a frame: dragging selects a rectangle of tiles, copying puts
+    /// them on the clipboard and pasting shows the clipboard under the cursor until a click places
+    /// it.
+    ///
+    /// Returns the tiles that were copied, which the caller puts in
+    /// [`luminol_core::ToolbarState::tile_clipboard`] since the map is borrowed from the update
+    /// state.
+    #[must_use]
+    pub(super) fn handle_select_tool(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        response: &egui::Response,
+        map: &mut luminol_data::rpg::Map,
+        tile_layer: usize,
+        is_focused: bool,
+    ) -> Option<TileClipboard> {
+        let cursor = (
+            self.view.cursor_pos.x as usize,
+            self.view.cursor_pos.y as usize,
+        );
+
+        // The copy and paste shortcuts can arrive as clipboard events instead of key presses
+        let (copy_pressed, paste_pressed, escape_pressed, shift) = if is_focused {
+            ui.input_mut(|i| {
+                let copy = i.events.iter().any(|e| matches!(e, egui::Event::Copy))
+                    | i.consume_key(egui::Modifiers::COMMAND, egui::Key::C);
+                let paste = i.events.iter().any(|e| matches!(e, egui::Event::Paste(_)))
+                    | i.consume_key(egui::Modifiers::COMMAND, egui::Key::V);
+                (
+                    copy,
+                    paste,
+                    i.key_pressed(egui::Key::Escape),
+                    i.modifiers.shift,
+                )
+            })
+        } else {
+            (false, false, false, false)
+        };
+
+        if let Some(mut paste) = self.paste.take() {
+            self.clear_paste_preview(update_state, map, &mut paste);
+            if escape_pressed {
+                self.view.tile_selection = None;
+                return None;
+            }
+
+            if response.clicked_by(egui::PointerButton::Primary) {
+                self.paste_tiles(update_state, map, &paste.clipboard, cursor, tile_layer);
+                return None;
+            }
+
+            self.draw_paste_preview(update_state, map, &mut paste, cursor, tile_layer);
+            self.paste = Some(paste);
+            return None;
+        }
+
+        let copied = copy_pressed
+            .then(|| self.selected_tiles(map, tile_layer, shift))
+            .flatten();
+        if paste_pressed {
+            self.start_pasting(update_state.toolbar);
+            return copied;
+        }
+        if escape_pressed {
+            self.view.tile_selection = None;
+        }
+
+        if response.is_pointer_button_down_on()
+            && ui.input(|i| {
+                i.pointer.button_down(egui::PointerButton::Primary) && !i.modifiers.command
+            })
+        {
+            let origin = *self.selection_origin.get_or_insert(cursor);
+            self.view.tile_selection = Some(egui::Rect::from_two_pos(
+                egui::pos2(origin.0 as f32, origin.1 as f32),
+                egui::pos2(cursor.0 as f32, cursor.1 as f32),
+            ));
+        } else {
+            self.selection_origin = None;
+        }
+
+        copied
+    }
+
+    /// Puts the selected tiles of the selected layer, or of every layer if `all_layers` is true, on
+    /// the clipboard. Does nothing if no tiles are selected.
+    pub(super) fn copy_selection(
+        &mut self,
+        toolbar: &mut luminol_core::ToolbarState,
+        map: &luminol_data::rpg::Map,
+        tile_layer: usize,
+        all_layers: bool,
+    ) {
+        if let Some(clipboard) = self.selected_tiles(map, tile_layer, all_layers) {
+            toolbar.tile_clipboard = Some(Arc::new(clipboard));
+        }
+    }
+
+    /// The selected tiles of the selected layer, or of every layer if `all_layers` is true, or
+    /// `None` if no tiles are selected.
+    pub(super) fn selected_tiles(
+        &self,
+        map: &luminol_data::rpg::Map,
+        tile_layer: usize,
+        all_layers: bool,
+    ) -> Option<TileClipboard> {
+        let selection = self.view.tile_selection?;
+
+        let (min_x, min_y) = (selection.min.x as usize, selection.min.y as usize);
+        let (max_x, max_y) = (
+            (selection.max.x as usize).min(map.data.xsize() - 1),
+            (selection.max.y as usize).min(map.data.ysize() - 1),
+        );
+        let layers = if all_layers {
+            (0..map.data.zsize()).collect_vec()
+        } else {
+            vec![tile_layer]
+        };
+        Some(TileClipboard {
+            tileset_id: map.tileset_id,
+            width: max_x - min_x + 1,
+            height: max_y - min_y + 1,
+            layers: layers
+                .into_iter()
+                .map(|z| {
+                    (min_y..=max_y)
+                        .cartesian_product(min_x..=max_x)
+                        .map(|(y, x)| map.data[(x, y, z)])
+                        .collect()
+                })
+                .collect(),
+            all_layers,
+        })
+    }
+
+    /// Starts showing the clipboard under the cursor, if anything was copied. Clicking places it
+    /// and pressing escape cancels.
+    pub(super) fn start_pasting(&mut self, toolbar: &luminol_core::ToolbarState) {
+        let Some(clipboard) = toolbar.tile_clipboard.clone() else {
+            return;
+        };
+        self.paste = Some(Paste {
+            clipboard,
+            preview: None,
+        });
+    }
+
+    /// Returns true if the tiles being pasted were copied from a map with another tileset.
+    pub(super) fn is_pasting_other_tileset(&self, tileset_id: usize) -> bool {
+        self.paste
+            .as_ref()
+            .is_some_and(|paste| paste.clipboard.tileset_id != tileset_id)
+    }
+
+    /// Stops pasting, for example because another brush was picked.
+    pub(super) fn cancel_paste(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+    ) {
+        if let Some(mut paste) = self.paste.take() {
+            self.clear_paste_preview(update_state, map, &mut paste);
+        }
+        self.selection_origin = None;
+        self.view.tile_selection = None;
+    }
+
+    /// Shows the clipboard on the tilemap without changing the map data.
+    fn draw_paste_preview(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+        paste: &mut Paste,
+        origin: (usize, usize),
+        tile_layer: usize,
+    ) {
+        let (xsize, ysize, zsize) = (map.data.xsize(), map.data.ysize(), map.data.zsize());
+        for (src, z) in paste.clipboard.target_layers(tile_layer, zsize) {
+            for ((x, y), i) in paste.clipboard.positions(origin, xsize, ysize) {
+                self.view.map.set_tile(
+                    &update_state.graphics.render_state,
+                    paste.clipboard.layers[src][i],
+                    (x, y, z),
+                );
+            }
+        }
+        paste.preview = Some((origin.0, origin.1, tile_layer));
+
+        self.view.tile_selection = Some(egui::Rect::from_min_max(
+            egui::pos2(origin.0 as f32, origin.1 as f32),
+            egui::pos2(
+                (origin.0 + paste.clipboard.width - 1) as f32,
+                (origin.1 + paste.clipboard.height - 1) as f32,
+            ),
+        ));
+    }
+
+    /// Puts back the tiles the paste preview was drawn over.
+    fn clear_paste_preview(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+        paste: &mut Paste,
+    ) {
+        let Some((x, y, tile_layer)) = paste.preview.take() else {
+            return;
+        };
+        let (xsize, ysize, zsize) = (map.data.xsize(), map.data.ysize(), map.data.zsize());
+        for (_, z) in paste.clipboard.target_layers(tile_layer, zsize) {
+            for ((x, y), _) in paste.clipboard.positions((x, y), xsize, ysize) {
+                self.view.map.set_tile(
+                    &update_state.graphics.render_state,
+                    map.data[(x, y, z)],
+                    (x, y, z),
+                );
+            }
+        }
+    }
+
+    /// Writes the clipboard to the map with its top-left tile at `origin`, as one history entry
+    /// for each layer that changed. Tiles that would go past the edge of the map are left out.
+    fn paste_tiles(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        clipboard: &TileClipboard,
+        origin: (usize, usize),
+        tile_layer: usize,
+    ) {
+        let (xsize, ysize, zsize) = (map.data.xsize(), map.data.ysize(), map.data.zsize());
+        for (src, z) in clipboard.target_layers(tile_layer, zsize) {
+            let mut delta = Vec::new();
+            for ((x, y), i) in clipboard.positions(origin, xsize, ysize) {
+                let id = clipboard.layers[src][i];
+                if map.data[(x, y, z)] == id {
+                    continue;
+                }
+                delta.push((x, y, z, map.data[(x, y, z)]));
+                map.data[(x, y, z)] = id;
+                self.dirty_passages.insert((x, y));
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, id, (x, y, z));
+            }
+
+            if !delta.is_empty() {
+                self.push_to_history(update_state, map, super::HistoryEntry::Tiles { delta });
+            }
+        }
+
+        self.view.tile_selection = Some(egui::Rect::from_min_max(
+            egui::pos2(origin.0 as f32, origin.1 as f32),
+            egui::pos2(
+                (origin.0 + clipboard.width).min(xsize) as f32 - 1.,
+                (origin.1 + clipboard.height).min(ysize) as f32 - 1.,
+            ),
+        ));
+    }
+}
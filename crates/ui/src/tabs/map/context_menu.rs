@@ -0,0 +1,158 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use super::HistoryEntry;
+
+/// An event copied from the right-click menu of a map. It's kept in egui's temporary data so that
+/// it can be pasted into any open map.
+pub(super) struct EventClipboard {
+    name: String,
+    pages: Vec<luminol_data::rpg::EventPage>,
+}
+
+impl EventClipboard {
+    fn id() -> egui::Id {
+        egui::Id::new("luminol_event_clipboard")
+    }
+
+    fn get(ctx: &egui::Context) -> Option<std::sync::Arc<Self>> {
+        ctx.data(|d| d.get_temp(Self::id()))
+    }
+
+    fn set(self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), std::sync::Arc::new(self)));
+    }
+}
+
+enum Action {
+    NewEvent,
+    EditEvent(usize),
+    CopyEvent(usize),
+    PasteEvent(std::sync::Arc<EventClipboard>),
+    SetStartPosition,
+}
+
+impl super::Editor {
+    /// Shows the right-click menu of the map, with actions for the tile that was right-clicked.
+    pub(super) fn context_menu(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        response: &egui::Response,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        // The map cursor follows the mouse, so the tile has to be remembered for as long as the
+        // menu is open
+        if response.secondary_clicked() {
+            self.context_menu_tile = self.view.hover_tile;
+        }
+        let Some(tile) = self.context_menu_tile else {
+            return;
+        };
+        let (x, y) = (tile.x as i32, tile.y as i32);
+        let event_id = map
+            .events
+            .iter()
+            .find(|(_, event)| event.x == x && event.y == y)
+            .map(|(id, _)| id);
+        let clipboard = EventClipboard::get(&response.ctx);
+
+        let mut action = None;
+        response.context_menu(|ui| {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+            ui.weak(format!("Tile ({x}, {y})"));
+            ui.separator();
+
+            if let Some(event_id) = event_id {
+                if ui.button("Edit event").clicked() {
+                    action = Some(Action::EditEvent(event_id));
+                }
+                if ui.button("Copy event").clicked() {
+                    action = Some(Action::CopyEvent(event_id));
+                }
+            } else {
+                if ui.button("New event").clicked() {
+                    action = Some(Action::NewEvent);
+                }
+                if ui
+                    .add_enabled(clipboard.is_some(), egui::Button::new("Paste event"))
+                    .on_disabled_hover_text("Copy an event from this menu first")
+                    .clicked()
+                {
+                    action = clipboard.clone().map(Action::PasteEvent);
+                }
+            }
+
+            ui.separator();
+
+            if ui
+                .button("Set player start position")
+                .on_hover_text("Start new games on this tile")
+                .clicked()
+            {
+                action = Some(Action::SetStartPosition);
+            }
+
+            if action.is_some() {
+                ui.close_menu();
+            }
+        });
+
+        match action {
+            Some(Action::NewEvent) => {
+                if let Some(id) = self.add_event(update_state, map, tile) {
+                    self.push_to_history(update_state, map, HistoryEntry::EventCreated(id));
+                }
+            }
+            Some(Action::EditEvent(id)) => {
+                self.event_windows
+                    .add_window(crate::windows::event_edit::Window::new(
+                        update_state,
+                        &map.events[id],
+                        self.id,
+                        map.tileset_id,
+                    ));
+            }
+            Some(Action::CopyEvent(id)) => {
+                let event = &map.events[id];
+                EventClipboard {
+                    name: event.name.clone(),
+                    pages: event.pages.clone(),
+                }
+                .set(&response.ctx);
+            }
+            Some(Action::PasteEvent(clipboard)) => {
+                let Some(id) = Self::new_event_id(map, x, y) else {
+                    return;
+                };
+                let mut event = luminol_data::rpg::Event::new(x, y, id);
+                event.name.clone_from(&clipboard.name);
+                event.pages.clone_from(&clipboard.pages);
+                map.events.insert(id, event);
+                self.push_to_history(update_state, map, HistoryEntry::EventCreated(id));
+            }
+            Some(Action::SetStartPosition) => self.set_start_position(update_state, x, y),
+            None => {}
+        }
+    }
+}
@@ -21,3 +21,205 @@
 // it with Steamworks API by Valve Corporation, containing parts covered by
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
+
+use super::HistoryEntry;
+
+impl super::Editor {
+    /// Applies an undo or redo history entry to the map and returns the entry that reverses it.
+    pub(super) fn apply_history_entry(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        entry: HistoryEntry,
+    ) -> HistoryEntry {
+        self.minimap_dirty = true;
+        match entry {
+            HistoryEntry::Tiles { mut delta } => {
+                for d in delta.iter_mut() {
+                    let position = (d.0, d.1, d.2);
+                    let new_id = d.3;
+                    *d = (d.0, d.1, d.2, map.data[position]);
+                    map.data[position] = new_id;
+                    self.dirty_passages.insert((d.0, d.1));
+                    self.view
+                        .map
+                        .set_tile(&update_state.graphics.render_state, new_id, position);
+                }
+                HistoryEntry::Tiles { delta }
+            }
+
+            HistoryEntry::EventMoved { id, x, y } => {
+                let event = map.events.get_mut(id).unwrap();
+                let new_entry = HistoryEntry::EventMoved {
+                    id,
+                    x: event.x,
+                    y: event.y,
+                };
+                event.x = x;
+                event.y = y;
+                new_entry
+            }
+
+            HistoryEntry::EventCreated(id) => {
+                let event = map.events.remove(id);
+                let sprite = self.view.map.events.try_remove(id).ok();
+                HistoryEntry::EventDeleted { event, sprite }
+            }
+
+            HistoryEntry::EventDeleted { event, sprite } => {
+                let id = event.id;
+                map.events.insert(id, event);
+                if let Some(sprite) = sprite {
+                    self.view.map.events.insert(id, sprite);
+                }
+                HistoryEntry::EventCreated(id)
+            }
+
+            HistoryEntry::EventsRenumbered(renumbering) => {
+                self.renumber_events(map, &renumbering);
+                HistoryEntry::EventsRenumbered(
+                    renumbering
+                        .into_iter()
+                        .map(|(old, new)| (new, old))
+                        .collect(),
+                )
+            }
+
+            // The entries are reversed in the opposite order from the one they were made in
+            HistoryEntry::Batch {
+                description,
+                entries,
+            } => HistoryEntry::Batch {
+                description,
+                entries: entries
+                    .into_iter()
+                    .rev()
+                    .map(|entry| self.apply_history_entry(update_state, map, entry))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Undoes the most recent change to the map, if there is one.
+    pub(super) fn undo(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        let Some(entry) = self.history.pop_back() else {
+            return;
+        };
+        let new_entry = self.apply_history_entry(update_state, map, entry);
+        update_state.modified.set(true);
+        map.modified = true;
+        self.redo_history.push_back(new_entry);
+        truncate(
+            &mut self.redo_history,
+            update_state.global_config.map_history_size,
+        );
+    }
+
+    /// Redoes the most recently undone change to the map, if there is one.
+    pub(super) fn redo(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        let Some(entry) = self.redo_history.pop_back() else {
+            return;
+        };
+        let new_entry = self.apply_history_entry(update_state, map, entry);
+        update_state.modified.set(true);
+        map.modified = true;
+        self.history.push_back(new_entry);
+        truncate(
+            &mut self.history,
+            update_state.global_config.map_history_size,
+        );
+    }
+
+    /// Shows the undo and redo buttons for the map toolbar.
+    pub(super) fn undo_redo_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) {
+        let undo_text = self.history.back().map_or_else(
+            || "Nothing to undo".to_string(),
+            |entry| format!("Undo: {} (Ctrl+Z)", entry.description(false)),
+        );
+        let redo_text = self.redo_history.back().map_or_else(
+            || "Nothing to redo".to_string(),
+            |entry| format!("Redo: {} (Ctrl+Y)", entry.description(true)),
+        );
+
+        let can_edit = self.event_drag_info.is_none() && self.drawing_shape_pos.is_none();
+        let undo_clicked = ui
+            .add_enabled(
+                can_edit && !self.history.is_empty(),
+                egui::Button::new("Undo"),
+            )
+            .on_hover_text(&undo_text)
+            .on_disabled_hover_text(&undo_text)
+            .clicked();
+        let redo_clicked = ui
+            .add_enabled(
+                can_edit && !self.redo_history.is_empty(),
+                egui::Button::new("Redo"),
+            )
+            .on_hover_text(&redo_text)
+            .on_disabled_hover_text(&redo_text)
+            .clicked();
+
+        if undo_clicked || redo_clicked {
+            let mut map = update_state.data.get_map(self.id);
+            if undo_clicked {
+                self.undo(update_state, &mut map);
+            } else {
+                self.redo(update_state, &mut map);
+            }
+        }
+    }
+}
+
+impl HistoryEntry {
+    /// Describes the change this entry takes back, for undo and redo hover text. Undoing an entry
+    /// puts the entry that reverses it on the redo stack, so the entries on the redo stack
+    /// describe the opposite of what they contain.
+    fn description(&self, is_redo: bool) -> String {
+        match self {
+            HistoryEntry::Tiles { delta } => {
+                let layers: std::collections::BTreeSet<_> =
+                    delta.iter().map(|&(_, _, layer, _)| layer + 1).collect();
+                match layers.len() {
+                    0 => "tile edit".to_string(),
+                    1 => format!("tile edit on layer {}", layers.first().unwrap()),
+                    _ => format!(
+                        "tile edit on layers {}",
+                        layers
+                            .iter()
+                            .map(|layer| layer.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            }
+            HistoryEntry::EventMoved { id, .. } => format!("move of event {id}"),
+            HistoryEntry::EventCreated(id) if is_redo => format!("deletion of event {id}"),
+            HistoryEntry::EventCreated(id) => format!("creation of event {id}"),
+            HistoryEntry::EventDeleted { event, .. } if is_redo => {
+                format!("creation of event {}", event.id)
+            }
+            HistoryEntry::EventDeleted { event, .. } => format!("deletion of event {}", event.id),
+            HistoryEntry::EventsRenumbered(_) => "event ID compaction".to_string(),
+            HistoryEntry::Batch { description, .. } => description.to_string(),
+        }
+    }
+}
+
+/// Drops the oldest entries of a history stack until it has at most `size` entries.
+pub(super) fn truncate(history: &mut std::collections::VecDeque<HistoryEntry>, size: usize) {
+    while history.len() > size {
+        history.pop_front();
+    }
+}
@@ -0,0 +1,366 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Exporting a tile layer as an image for blocking it out in an image editor, and importing it
+//! back.
+//!
+//! Every pixel of the layer image is one tile, colored by [`tile_color`]. The legend image that's
+//! exported next to it has one column per tile ID used in the layer: the top pixel is the color of
+//! the tile and the bottom pixel holds the tile ID, in the red (low byte) and green (high byte)
+//! channels. Importing looks up the colors in the legend, so new areas can be painted with any
+//! color already in it.
+
+use color_eyre::eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use luminol_filesystem::FileSystem;
+
+/// A layer image that's been read back, ready to replace the contents of the layer.
+pub(super) struct ImportedLayer {
+    pub layer: usize,
+    /// The new tile IDs of the layer, in row-major order. Pixels with colors that aren't in the
+    /// legend are `None` and leave the tile unchanged.
+    pub tiles: Vec<Option<i16>>,
+    /// The colors that aren't in the legend, without duplicates.
+    pub unmapped_colors: Vec<[u8; 3]>,
+}
+
+/// Returns the color of a tile ID in layer images. Different tile IDs always have different
+/// colors, and the empty tile is black.
+pub(super) fn tile_color(id: i16) -> [u8; 3] {
+    // Multiplying by an odd number is a bijection on the 24-bit colors, so this never maps two
+    // tile IDs to the same color while still spreading out the colors of neighboring IDs
+    let value = (id as u16 as u32).wrapping_mul(0x9e3779) & 0xffffff;
+    [(value >> 16) as u8, (value >> 8) as u8, value as u8]
+}
+
+/// Builds the legend image for the given tile IDs, sorted so that the same layer always gives the
+/// same legend.
+pub(super) fn legend(ids: impl IntoIterator<Item = i16>) -> image::RgbaImage {
+    let ids = ids.into_iter().sorted().dedup().collect_vec();
+    let mut legend = image::RgbaImage::new(ids.len().max(1) as u32, 2);
+    for (x, &id) in ids.iter().enumerate() {
+        let [r, g, b] = tile_color(id);
+        let [low, high] = id.to_le_bytes();
+        legend.put_pixel(x as u32, 0, image::Rgba([r, g, b, 255]));
+        legend.put_pixel(x as u32, 1, image::Rgba([low, high, 0, 255]));
+    }
+    legend
+}
+
+/// Reads a legend image back into a map from colors to tile IDs.
+pub(super) fn read_legend(
+    legend: &image::RgbaImage,
+) -> color_eyre::Result<std::collections::HashMap<[u8; 3], i16>> {
+    if legend.height() != 2 {
+        return Err(eyre!(
+            "The legend should be 2 pixels tall, but it's {} pixels tall",
+            legend.height()
+        ));
+    }
+
+    let mut colors = std::collections::HashMap::with_capacity(legend.width() as usize);
+    for x in 0..legend.width() {
+        let [r, g, b, _] = legend.get_pixel(x, 0).0;
+        let [low, high, _, _] = legend.get_pixel(x, 1).0;
+        let id = i16::from_le_bytes([low, high]);
+        if let Some(other_id) = colors.insert([r, g, b], id) {
+            if other_id != id {
+                return Err(eyre!(
+                    "The legend uses the color #{r:02x}{g:02x}{b:02x} for both tile {other_id} and tile {id}"
+                ));
+            }
+        }
+    }
+    Ok(colors)
+}
+
+/// Encodes a layer of a map as a layer image and its legend.
+pub(super) fn export(
+    map: &luminol_data::rpg::Map,
+    layer: usize,
+) -> (image::RgbaImage, image::RgbaImage) {
+    let image =
+        image::RgbaImage::from_fn(map.data.xsize() as u32, map.data.ysize() as u32, |x, y| {
+            let [r, g, b] = tile_color(map.data[(x as usize, y as usize, layer)]);
+            image::Rgba([r, g, b, 255])
+        });
+    (
+        image,
+        legend(map.data.layer_as_slice(layer).iter().copied()),
+    )
+}
+
+/// Decodes a layer image using its legend. The image has to be exactly as large as the map.
+pub(super) fn import(
+    image: &image::RgbaImage,
+    legend: &image::RgbaImage,
+    layer: usize,
+    xsize: usize,
+    ysize: usize,
+) -> color_eyre::Result<ImportedLayer> {
+    if (image.width() as usize, image.height() as usize) != (xsize, ysize) {
+        return Err(eyre!(
+            "The image is {}x{} pixels, but the map is {xsize}x{ysize} tiles",
+            image.width(),
+            image.height()
+        ));
+    }
+
+    let colors = read_legend(legend)?;
+    let mut unmapped_colors = Vec::new();
+    let tiles = image
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b, _] = pixel.0;
+            let id = colors.get(&[r, g, b]).copied();
+            if id.is_none() && !unmapped_colors.contains(&[r, g, b]) {
+                unmapped_colors.push([r, g, b]);
+            }
+            id
+        })
+        .collect();
+
+    Ok(ImportedLayer {
+        layer,
+        tiles,
+        unmapped_colors,
+    })
+}
+
+fn file_names(map_id: usize, layer: usize) -> (String, String) {
+    let name = format!("Map{map_id:0>3}_layer{}", layer + 1);
+    (format!("{name}.png"), format!("{name}_legend.png"))
+}
+
+fn encode_png(image: &image::RgbaImage) -> color_eyre::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image.write_to(
+        &mut std::io::Cursor::new(&mut bytes),
+        image::ImageFormat::Png,
+    )?;
+    Ok(bytes)
+}
+
+impl super::Editor {
+    /// Asks for a folder and writes the layer image and its legend to it.
+    pub(super) fn export_layer_image(
+        &self,
+        map: &luminol_data::rpg::Map,
+        layer: usize,
+    ) -> impl std::future::Future<Output = color_eyre::Result<()>> {
+        let c = "While exporting the layer as an image";
+        let (image, legend) = export(map, layer);
+        let (image_name, legend_name) = file_names(self.id, layer);
+
+        async move {
+            let dest_fs = luminol_filesystem::host::FileSystem::from_folder_picker()
+                .await
+                .wrap_err(c)?;
+            dest_fs
+                .write(&image_name, encode_png(&image).wrap_err(c)?)
+                .wrap_err(c)?;
+            dest_fs
+                .write(&legend_name, encode_png(&legend).wrap_err(c)?)
+                .wrap_err(c)?;
+            Ok(())
+        }
+    }
+
+    /// Asks for the folder a layer image was exported to and reads it and its legend back.
+    pub(super) fn import_layer_image(
+        &self,
+        map: &luminol_data::rpg::Map,
+        layer: usize,
+    ) -> impl std::future::Future<Output = color_eyre::Result<ImportedLayer>> {
+        let c = "While importing the layer from an image";
+        let (xsize, ysize) = (map.data.xsize(), map.data.ysize());
+        let (image_name, legend_name) = file_names(self.id, layer);
+
+        async move {
+            let src_fs = luminol_filesystem::host::FileSystem::from_folder_picker()
+                .await
+                .wrap_err(c)?;
+            let read_image = |name: &str| -> color_eyre::Result<image::RgbaImage> {
+                let bytes = src_fs
+                    .read(name)
+                    .wrap_err_with(|| format!("The folder should contain {name}"))?;
+                Ok(image::load_from_memory(&bytes)
+                    .wrap_err_with(|| format!("While decoding {name}"))?
+                    .into_rgba8())
+            };
+            let image = read_image(&image_name).wrap_err(c)?;
+            let legend = read_image(&legend_name).wrap_err(c)?;
+            import(&image, &legend, layer, xsize, ysize).wrap_err(c)
+        }
+    }
+
+    /// Replaces the contents of a layer with an imported layer image as a single history entry.
+    /// Returns how many tiles changed.
+    pub(super) fn apply_layer_image(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        imported: ImportedLayer,
+    ) -> usize {
+        let layer = imported.layer;
+        if layer >= map.data.zsize() {
+            return 0;
+        }
+
+        let xsize = map.data.xsize();
+        let mut delta = Vec::new();
+        for (i, id) in imported.tiles.into_iter().enumerate() {
+            let position = (i % xsize, i / xsize, layer);
+            let Some(id) = id.filter(|&id| id != map.data[position]) else {
+                continue;
+            };
+            delta.push((position.0, position.1, layer, map.data[position]));
+            map.data[position] = id;
+            self.dirty_passages.insert((position.0, position.1));
+            self.view
+                .map
+                .set_tile(&update_state.graphics.render_state, id, position);
+        }
+
+        let count = delta.len();
+        if count > 0 {
+            self.push_to_history(update_state, map, super::HistoryEntry::Tiles { delta });
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(xsize: usize, ysize: usize, layer: &[i16]) -> luminol_data::rpg::Map {
+        let mut data = luminol_data::Table3::new(xsize, ysize, 3);
+        for (i, &id) in layer.iter().enumerate() {
+            data[(i % xsize, i / xsize, 1)] = id;
+        }
+        luminol_data::rpg::Map {
+            width: xsize,
+            height: ysize,
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tile_colors_are_unique() {
+        let mut seen = std::collections::HashSet::with_capacity(1 << 16);
+        for id in i16::MIN..=i16::MAX {
+            assert!(
+                seen.insert(tile_color(id)),
+                "tile {id} shares its color with another tile"
+            );
+        }
+        assert_eq!(tile_color(0), [0, 0, 0]);
+    }
+
+    #[test]
+    fn legend_is_stable() {
+        let legend_a = legend([384, 48, 0, 384, -1, 2000]);
+        let legend_b = legend([2000, -1, 384, 0, 48, 48]);
+        assert_eq!(legend_a, legend_b);
+        assert_eq!(legend_a.dimensions(), (5, 2));
+
+        // Sorted by tile ID, with the ID readable from the bottom row
+        let ids = (0..legend_a.width())
+            .map(|x| {
+                let [low, high, _, _] = legend_a.get_pixel(x, 1).0;
+                i16::from_le_bytes([low, high])
+            })
+            .collect_vec();
+        assert_eq!(ids, [-1, 0, 48, 384, 2000]);
+
+        // An empty layer still gets a legend that can be saved as an image
+        assert_eq!(legend([]).dimensions(), (1, 2));
+    }
+
+    #[test]
+    fn legend_round_trips() {
+        let ids = [0, 1, 48, 384, 1234, i16::MAX, i16::MIN];
+        let colors = read_legend(&legend(ids)).unwrap();
+        assert_eq!(colors.len(), ids.len());
+        for id in ids {
+            assert_eq!(colors[&tile_color(id)], id);
+        }
+    }
+
+    #[test]
+    fn legend_rejects_ambiguous_colors() {
+        let mut legend = legend([384, 385]);
+        let first = *legend.get_pixel(0, 0);
+        legend.put_pixel(1, 0, first);
+        assert!(read_legend(&legend).is_err());
+
+        // The same color listed twice for the same tile is fine
+        let mut legend = super::legend([384, 385]);
+        let first = (*legend.get_pixel(0, 0), *legend.get_pixel(0, 1));
+        legend.put_pixel(1, 0, first.0);
+        legend.put_pixel(1, 1, first.1);
+        assert_eq!(read_legend(&legend).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn legend_must_be_two_pixels_tall() {
+        assert!(read_legend(&image::RgbaImage::new(4, 3)).is_err());
+    }
+
+    #[test]
+    fn layer_round_trips() {
+        let tiles = [0, 384, 385, 0, 48, 48, 1000, 384, 0, 2, 3, 4];
+        let map = map(4, 3, &tiles);
+
+        let (image, legend) = export(&map, 1);
+        assert_eq!(image.dimensions(), (4, 3));
+        let imported = import(&image, &legend, 1, 4, 3).unwrap();
+
+        assert_eq!(imported.layer, 1);
+        assert_eq!(imported.tiles, tiles.map(Some));
+        assert!(imported.unmapped_colors.is_empty());
+    }
+
+    #[test]
+    fn unmapped_colors_are_reported_once() {
+        let map = map(3, 1, &[384, 0, 384]);
+        let (mut image, legend) = export(&map, 1);
+        image.put_pixel(0, 0, image::Rgba([255, 0, 255, 255]));
+        image.put_pixel(1, 0, image::Rgba([255, 0, 255, 255]));
+
+        let imported = import(&image, &legend, 1, 3, 1).unwrap();
+        assert_eq!(imported.tiles, [None, None, Some(384)]);
+        assert_eq!(imported.unmapped_colors, [[255, 0, 255]]);
+    }
+
+    #[test]
+    fn size_mismatch_is_rejected() {
+        let map = map(4, 3, &[]);
+        let (image, legend) = export(&map, 1);
+        assert!(import(&image, &legend, 1, 4, 4).is_err());
+        assert!(import(&image, &legend, 1, 3, 3).is_err());
+    }
+}
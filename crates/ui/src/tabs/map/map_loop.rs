@@ -0,0 +1,88 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_config::map_loops::{MapLoop, MapLoops};
+use luminol_filesystem::FileSystem;
+
+fn read_map_loops(filesystem: &impl FileSystem) -> MapLoops {
+    filesystem
+        .read_to_string(MapLoops::PATH)
+        .ok()
+        .and_then(|s| ron::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+impl super::Editor {
+    /// Loads which edges of a map loop from the project's map loop file.
+    pub(super) fn load_map_loop(filesystem: &impl FileSystem, map_id: usize) -> MapLoop {
+        read_map_loops(filesystem)
+            .maps
+            .remove(&map_id)
+            .unwrap_or_default()
+    }
+
+    /// Writes which edges of this map loop to the project's map loop file, leaving the other maps
+    /// alone.
+    fn save_map_loop(&self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        let c = "While saving map looping";
+
+        // Other map tabs may have changed their looping since we loaded ours
+        let mut map_loops = read_map_loops(filesystem);
+        let map_loop = self.view.map_loop();
+        if map_loop.is_looping() {
+            map_loops.maps.insert(self.id, map_loop);
+        } else {
+            map_loops.maps.remove(&self.id);
+        }
+
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let map_loops = ron::ser::to_string_pretty(&map_loops, pretty_config).wrap_err(c)?;
+        filesystem.write(MapLoops::PATH, map_loops).wrap_err(c)
+    }
+
+    /// Shows the map looping settings in the display options menu.
+    pub(super) fn map_loop_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut map_loop = self.view.map_loop();
+        let mut changed = ui
+            .checkbox(&mut map_loop.horizontal, "Loop horizontally")
+            .on_hover_text("The left and right edges of the map connect to each other")
+            .changed();
+        changed |= ui
+            .checkbox(&mut map_loop.vertical, "Loop vertically")
+            .on_hover_text("The top and bottom edges of the map connect to each other")
+            .changed();
+
+        if changed {
+            self.view.set_map_loop(&update_state.graphics, map_loop);
+            if let Err(e) = self.save_map_loop(update_state.filesystem) {
+                luminol_core::error!(update_state.toasts, e);
+            }
+        }
+    }
+}
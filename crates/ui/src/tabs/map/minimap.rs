@@ -0,0 +1,94 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// The largest width or height of the minimap in points.
+const MINIMAP_SIZE: f32 = 160.;
+
+impl super::Editor {
+    /// The minimap image, made again from the map data if the map changed since it was last made.
+    /// It's only remade between strokes, since making it takes a while on large maps.
+    pub(super) fn minimap_texture(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) -> Option<egui::TextureHandle> {
+        if self.minimap_dirty && self.drawing_shape_pos.is_none() {
+            self.minimap.forget(self.id);
+            self.minimap_dirty = false;
+        }
+        self.minimap.get(update_state, self.id).cloned()
+    }
+
+    /// Shows the minimap in the top-right corner of the map canvas with the visible part of the
+    /// map outlined. Clicking or dragging on it centers the view there.
+    pub(super) fn minimap_ui(
+        &mut self,
+        ui: &egui::Ui,
+        canvas_rect: egui::Rect,
+        texture: &egui::TextureHandle,
+        map: &luminol_data::rpg::Map,
+    ) {
+        // One pixel of the image per tile of the map
+        let size = texture.size_vec2();
+        let scale = (MINIMAP_SIZE / size.max_elem()).min(4.);
+        let rect = egui::Align2::RIGHT_TOP
+            .anchor_size(canvas_rect.right_top() + egui::vec2(-8., 8.), size * scale);
+
+        let response = ui.interact(
+            rect,
+            ui.id().with("luminol_minimap"),
+            egui::Sense::click_and_drag(),
+        );
+
+        let painter = ui.painter();
+        painter.rect_filled(rect.expand(2.), 2., egui::Color32::from_black_alpha(192));
+        painter.image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+            egui::Color32::WHITE,
+        );
+        let visible_rect = egui::Rect::from_min_max(
+            rect.min + self.view.visible_tiles.min.to_vec2() * scale,
+            rect.min + self.view.visible_tiles.max.to_vec2() * scale,
+        )
+        .intersect(rect);
+        if visible_rect.is_positive() {
+            painter.rect_stroke(
+                visible_rect,
+                0.,
+                egui::Stroke::new(1.5, egui::Color32::WHITE),
+            );
+        }
+
+        if response.clicked() || response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let tile = ((pos - rect.min) / scale).to_pos2().floor().clamp(
+                    egui::Pos2::ZERO,
+                    egui::pos2(map.width as f32 - 1., map.height as f32 - 1.),
+                );
+                self.view.center_on(ui.ctx(), map, tile);
+            }
+        }
+    }
+}
@@ -23,11 +23,12 @@
 // Program grant you additional permission to convey the resulting work.
 
 #![allow(unused_imports)]
-use crate::components::{MapView, SelectedLayer, Tilepicker};
+use crate::components::{MapView, RegionPicker, SelectedLayer, Tilepicker};
 use egui::Pos2;
-use std::{cell::RefMut, collections::HashMap, collections::VecDeque};
-
-const HISTORY_SIZE: usize = 50;
+use std::{
+    cell::RefMut,
+    collections::{HashMap, HashSet, VecDeque},
+};
 
 struct EventDragInfo {
     /// ID of the event being dragged
@@ -41,20 +42,93 @@ struct EventDragInfo {
     offset: egui::Vec2,
 }
 
+/// Bounding box of the tiles on the selected layer that were changed during the current frame
+#[derive(Clone, Copy)]
+struct DirtyTiles {
+    min_x: usize,
+    min_y: usize,
+    max_x: usize,
+    max_y: usize,
+}
+
+/// The parts of an event that affect the passage values of the position it's on: its position,
+/// and whether the first page is passable and what tile it uses as its graphic
+type EventPassageKey = (i32, i32, Option<(bool, Option<usize>)>);
+
+fn event_passage_keys(
+    events: &luminol_data::OptionVec<luminol_data::rpg::Event>,
+) -> HashMap<usize, EventPassageKey> {
+    events
+        .iter()
+        .map(|(id, event)| {
+            let page = event
+                .pages
+                .first()
+                .map(|page| (page.through, page.graphic.tile_id));
+            (id, (event.x, event.y, page))
+        })
+        .collect()
+}
+
 use crate::windows::event_edit;
+use luminol_config::global::EventCreation;
 
 use itertools::Itertools;
 
+mod bookmarks;
 mod brush;
+mod clipboard;
+mod context_menu;
 mod history;
+mod layer_image;
+mod map_loop;
+mod minimap;
+mod onion_skin;
+mod replace;
+mod shift;
+mod stamps;
+mod start_position;
 mod util;
 
+pub use start_position::{center_on_later, go_to_start_position};
+
+/// A map editor tab. Large maps take a while to load, so the tab opens right away and loads the
+/// map over several frames, with the slowest part running off the UI thread.
 pub struct Tab {
+    /// ID of the map that is being edited.
+    pub id: usize,
+    state: State,
+    /// Set when loading fails or is cancelled so that the tab is closed.
+    force_close: bool,
+    /// How many times the map had been reloaded from disk when the editor was built
+    reload_count: u64,
+
+    /// Used to update the count of open tabs for this map when the tab is closed
+    ctx: egui::Context,
+}
+
+enum State {
+    /// Nothing has been loaded yet. Loading starts on the next frame so that the progress bar is
+    /// drawn before the UI thread is blocked by loading the map data.
+    Opened,
+    /// The map data is loaded next.
+    LoadingData,
+    /// The passage values for the collision preview are being worked out in the background.
+    CalculatingPassages(poll_promise::Promise<luminol_data::Table2>),
+    /// The tilemap and its GPU buffers are built next.
+    BuildingTilemap(luminol_data::Table2),
+    Ready(Box<Editor>),
+}
+
+/// The map editor itself, once the map is loaded.
+struct Editor {
     /// ID of the map that is being edited.
     pub id: usize,
     /// The tilemap.
     pub view: MapView,
     pub tilepicker: Tilepicker,
+    /// Shown instead of the tilepicker while the region layer of a VX Ace map is selected
+    region_picker: RegionPicker,
 
     drawing_shape: bool,
     event_windows: luminol_core::Windows,
@@ -63,6 +137,8 @@ pub struct Tab {
     event_drag_info: Option<EventDragInfo>,
 
     layer_cache: Vec<i16>,
+    /// The tiles that have to be written from the map data to the tilemap at the end of the frame
+    dirty_tiles: Option<DirtyTiles>,
 
     /// This cache is used by the depth-first search when using the fill brush
     dfs_cache: Vec<bool>,
@@ -72,38 +148,90 @@ pub struct Tab {
     /// When drawing with any brush,
     /// this is set to the position of the original tile we began drawing on
     drawing_shape_pos: Option<egui::Pos2>,
+    /// Whether the current stroke of the shadow pen adds shadows instead of removing them
+    shadow_pen_adds: bool,
 
     /// Undo history
     history: VecDeque<HistoryEntry>,
     /// When operations are undone, they are put here so that they can be redone
-    redo_history: Vec<HistoryEntry>,
+    redo_history: VecDeque<HistoryEntry>,
     /// When starting to draw tiles, this is set to the state of the layer before
     /// any tiles are drawn in order to compute the deltas for the history
     tilemap_undo_cache: Vec<i16>,
     /// The layer tilemap_undo_cache refers to
     tilemap_undo_cache_layer: usize,
 
+    /// Where the mouse was pressed with alt held, until it's either released there to pick up the
+    /// tile or dragged away to draw
+    eyedropper_pos: Option<(usize, usize)>,
+    /// Where the mouse was pressed with alt and shift held to pick up a rectangle of tiles
+    eyedropper_origin: Option<(usize, usize)>,
+    /// Where the select tool started dragging out the tile selection
+    selection_origin: Option<(usize, usize)>,
+    /// Set while the select tool is pasting copied tiles
+    paste: Option<clipboard::Paste>,
+    /// The settings of the shift map operation
+    shift_options: shift::ShiftOptions,
+    /// The settings of the replace tiles operation
+    replace_options: replace::ReplaceOptions,
+    /// Previews of the project's stamps in the tilepicker panel
+    stamp_previews: stamps::StampPreviews,
+    /// The minimap image of the map
+    minimap: crate::components::MapThumbnails,
+    /// Set when the map changes so that the minimap is made again
+    minimap_dirty: bool,
+    /// Where the start position marker is being dragged to
+    start_position_drag: Option<egui::Pos2>,
+    /// The tile the right-click menu was opened on
+    context_menu_tile: Option<egui::Pos2>,
+    /// When and on which tile the event layer was last clicked, to tell if the next click makes it
+    /// a double-click
+    last_event_click: Option<(f64, egui::Pos2)>,
+
     /// This stores the passage values for every position on the map so that we can figure out
     /// which passage values have changed in the current frame
     passages: luminol_data::Table2,
+    /// Positions whose passage values have to be recomputed at the end of the frame because the
+    /// tiles or events on them changed
+    dirty_passages: HashSet<(usize, usize)>,
+    /// The layer visibility the passages were last computed with; the passages of the whole map
+    /// are recomputed when this changes
+    passage_layers: Vec<bool>,
+    passage_events_enabled: bool,
+    /// The tileset passages and priorities the passages were last computed with
+    passage_tileset: Option<(luminol_data::Table1, luminol_data::Table1)>,
+    /// The events as of the last passage update, used to find the events that changed since
+    passage_events: HashMap<usize, EventPassageKey>,
 
     /// Brush density between 0 and 1 inclusive; determines the proportion of randomly chosen tiles
     /// the brush draws on if less than 1
     brush_density: f32,
     /// Seed for the PRNG used for the brush when brush density is less than 1
     brush_seed: [u8; 16],
+    /// The mirror painting mode of the toolbar
+    symmetry: luminol_core::Symmetry,
 
     /// Asynchronous task used to save the map as an image file
     save_as_image_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
+    export_layer_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
+    import_layer_promise:
+        Option<poll_promise::Promise<color_eyre::Result<layer_image::ImportedLayer>>>,
+
+    /// Named positions on this map, persisted in the project's bookmarks file
+    bookmarks: Vec<luminol_config::bookmarks::Bookmark>,
+    /// Text box contents for the name of the next bookmark
+    new_bookmark_name: String,
+
+    /// Asks the user before performing destructive actions on this map
+    confirmation: luminol_core::Confirmation,
+    /// ID of the event that will be deleted once the user confirms it
+    event_pending_deletion: Option<usize>,
 }
 
-// TODO: If we add support for changing event IDs, these need to be added as history entries
-// in order to not corrupt the EventMoved and EventCreated entries.
 enum HistoryEntry {
-    /// Contains the (x, y, tile_id) delta for a changed map layer.
+    /// Contains the (x, y, layer, tile_id) delta for changed tiles.
     Tiles {
-        layer: usize,
-        delta: Vec<(usize, usize, i16)>,
+        delta: Vec<(usize, usize, usize, i16)>,
     },
     /// Contains the original map coordinates of a moved event and the ID of the event.
     EventMoved { id: usize, x: i32, y: i32 },
@@ -114,35 +242,130 @@ enum HistoryEntry {
         event: luminol_data::rpg::Event,
         sprite: Option<luminol_graphics::Event>,
     },
+    /// Contains the (old ID, new ID) pairs that undo a change of event IDs. Every entry before
+    /// this one refers to events by the IDs they had before the change.
+    EventsRenumbered(Vec<(usize, usize)>),
+    /// Contains several changes that are undone together, in the order they were made, and a
+    /// description of them as a whole.
+    Batch {
+        description: &'static str,
+        entries: Vec<HistoryEntry>,
+    },
+}
+
+/// How many tabs exist for a map, kept in egui's temporary data. Opening a map that is already
+/// open creates a second tab that is dropped right away, so this can't be a flag.
+fn open_marker_id(id: usize) -> egui::Id {
+    egui::Id::new("luminol_map_tab_open").with(id)
+}
+
+/// Whether the map with the given ID is open in a tab. Tools that change a map's events
+/// outside of its tab should leave open maps alone, since the tab's undo history refers to its
+/// events by ID.
+pub fn is_open(ctx: &egui::Context, id: usize) -> bool {
+    ctx.data(|d| d.get_temp::<usize>(open_marker_id(id)))
+        .is_some_and(|count| count > 0)
+}
+
+fn focused_marker_id() -> egui::Id {
+    egui::Id::new("luminol_focused_map")
+}
+
+/// The ID of the map whose tab was focused most recently, if that tab is still open. Tools
+/// outside of the map editor that act on the current map use this.
+pub fn focused(ctx: &egui::Context) -> Option<usize> {
+    ctx.data(|d| d.get_temp::<usize>(focused_marker_id()))
+        .filter(|&id| is_open(ctx, id))
+}
+
+fn pending_event_id(map_id: usize) -> egui::Id {
+    egui::Id::new("luminol_map_tab_pending_event").with(map_id)
+}
+
+/// Makes the tab of the map with the given ID select the event with ID `event_id`, center the
+/// view on it and open its editor the next time the tab is shown.
+pub fn open_event_later(ctx: &egui::Context, map_id: usize, event_id: usize) {
+    ctx.data_mut(|d| d.insert_temp(pending_event_id(map_id), event_id));
 }
 
 impl Tab {
-    /// Create a new map editor.
-    pub fn new(
-        id: usize,
-        update_state: &mut luminol_core::UpdateState<'_>,
-    ) -> color_eyre::Result<Self> {
-        // *sigh*
-        // borrow checker.
-        let view = MapView::new(update_state, id)?;
-        let tilepicker = Tilepicker::new(update_state, id);
+    /// Create a new map editor. The map is loaded while the tab is shown.
+    pub fn new(id: usize, update_state: &luminol_core::UpdateState<'_>) -> Self {
+        update_state
+            .ctx
+            .data_mut(|d| *d.get_temp_mut_or_default::<usize>(open_marker_id(id)) += 1);
 
-        let map = update_state.data.get_or_load_map(
+        Self {
             id,
-            update_state.filesystem,
-            update_state.project_config.as_ref().unwrap(),
-        );
-        let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
-
-        let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
-        luminol_graphics::Collision::calculate_passages(
-            &tileset.passages,
-            &tileset.priorities,
-            &map.data,
-            Some(&map.events),
-            (0..map.data.zsize()).rev(),
-            |x, y, passage| passages[(x, y)] = passage,
+            state: State::Opened,
+            force_close: false,
+            reload_count: luminol_core::reload_count(
+                update_state.ctx,
+                luminol_core::Structure::Map(id),
+            ),
+            ctx: update_state.ctx.clone(),
+        }
+    }
+
+    /// Performs the next step of loading the map.
+    fn load(&mut self, update_state: &mut luminol_core::UpdateState<'_>) -> color_eyre::Result<()> {
+        self.state = match std::mem::replace(&mut self.state, State::Opened) {
+            State::Opened => State::LoadingData,
+
+            State::LoadingData => {
+                let map = update_state.data.get_or_load_map(
+                    self.id,
+                    update_state.filesystem,
+                    update_state.project_config.as_ref().unwrap(),
+                );
+                let tilesets = update_state.data.tilesets();
+                let tileset = &tilesets.data[map.tileset_id];
+
+                let passages = tileset.passages.clone();
+                let priorities = tileset.priorities.clone();
+                let tiles = map.data.clone();
+                let events = map.events.clone();
+                State::CalculatingPassages(luminol_core::spawn_future(async move {
+                    MapView::calculate_passages(&passages, &priorities, &tiles, &events)
+                }))
+            }
+
+            State::CalculatingPassages(promise) => match promise.try_take() {
+                Ok(passages) => State::BuildingTilemap(passages),
+                Err(promise) => State::CalculatingPassages(promise),
+            },
+
+            State::BuildingTilemap(passages) => {
+                let view = MapView::with_passages(update_state, self.id, &passages)?;
+                let tilepicker = Tilepicker::new(update_state, self.id);
+                State::Ready(Box::new(Editor::new(
+                    self.id,
+                    update_state,
+                    view,
+                    tilepicker,
+                    passages,
+                )))
+            }
+
+            State::Ready(editor) => State::Ready(editor),
+        };
+        Ok(())
+    }
+}
+
+impl Editor {
+    fn new(
+        id: usize,
+        update_state: &luminol_core::UpdateState<'_>,
+        mut view: MapView,
+        tilepicker: Tilepicker,
+        passages: luminol_data::Table2,
+    ) -> Self {
+        let map = update_state.data.get_map(id);
+
+        view.set_map_loop(
+            &update_state.graphics,
+            Self::load_map_loop(update_state.filesystem, id),
         );
 
         let mut brush_seed = [0u8; 16];
@@ -157,11 +380,12 @@ impl Tab {
         );
         brush_seed[8..16].copy_from_slice(&(id as u64).to_le_bytes());
 
-        Ok(Self {
+        Self {
             id,
 
             view,
             tilepicker,
+            region_picker: RegionPicker::new(),
 
             drawing_shape: false,
             event_windows: luminol_core::Windows::default(),
@@ -170,27 +394,158 @@ impl Tab {
             event_drag_info: None,
 
             layer_cache: vec![0; map.data.xsize() * map.data.ysize()],
+            dirty_tiles: None,
 
             dfs_cache: vec![false; map.data.xsize() * map.data.ysize()],
             brush_layer_cache: vec![0; map.data.xsize() * map.data.ysize()],
             drawing_shape_pos: None,
+            shadow_pen_adds: false,
 
-            history: VecDeque::with_capacity(HISTORY_SIZE),
-            redo_history: Vec::with_capacity(HISTORY_SIZE),
+            history: VecDeque::new(),
+            redo_history: VecDeque::new(),
             tilemap_undo_cache: vec![0; map.data.xsize() * map.data.ysize()],
             tilemap_undo_cache_layer: 0,
 
+            eyedropper_pos: None,
+            eyedropper_origin: None,
+            selection_origin: None,
+            paste: None,
+            shift_options: Default::default(),
+            replace_options: Default::default(),
+            stamp_previews: Default::default(),
+            minimap: Default::default(),
+            minimap_dirty: false,
+            start_position_drag: None,
+            context_menu_tile: None,
+            last_event_click: None,
+
             passages,
+            dirty_passages: HashSet::new(),
+            passage_layers: Vec::new(),
+            passage_events_enabled: false,
+            passage_tileset: None,
+            passage_events: HashMap::new(),
 
             brush_density: 1.,
             brush_seed,
+            symmetry: luminol_core::Symmetry::Off,
 
             save_as_image_promise: None,
-        })
+            export_layer_promise: None,
+            import_layer_promise: None,
+
+            bookmarks: Self::load_bookmarks(update_state.filesystem, id),
+            new_bookmark_name: String::new(),
+
+            confirmation: luminol_core::Confirmation::new(
+                update_state.ctx,
+                format!("luminol_map_confirmation_{id}"),
+            ),
+            event_pending_deletion: None,
+        }
+    }
+}
+
+impl Drop for Tab {
+    fn drop(&mut self) {
+        self.ctx.data_mut(|d| {
+            let count = d.get_temp_mut_or_default::<usize>(open_marker_id(self.id));
+            *count = count.saturating_sub(1);
+        });
     }
 }
 
 impl luminol_core::Tab for Tab {
+    fn name(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
+        match &self.state {
+            State::Ready(editor) => editor.name(update_state),
+            _ => format!(
+                "Map {}: {}",
+                self.id,
+                update_state.data.map_infos().data[&self.id].name
+            ),
+        }
+    }
+
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_map").with(self.id)
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn force_close(&mut self) -> bool {
+        match &mut self.state {
+            State::Ready(editor) => self.force_close || editor.force_close(),
+            _ => self.force_close,
+        }
+    }
+
+    fn has_unsaved_ui_state(&self) -> bool {
+        match &self.state {
+            State::Ready(editor) => editor.has_unsaved_ui_state(),
+            _ => false,
+        }
+    }
+
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        is_focused: bool,
+    ) {
+        if is_focused {
+            ui.data_mut(|d| d.insert_temp(focused_marker_id(), self.id));
+        }
+
+        // The tilemap and undo history are stale once the map is reloaded from disk, so the
+        // editor is built again from scratch
+        let reload_count =
+            luminol_core::reload_count(ui.ctx(), luminol_core::Structure::Map(self.id));
+        if reload_count != self.reload_count {
+            self.reload_count = reload_count;
+            self.state = State::Opened;
+        }
+
+        let (progress, text) = match &mut self.state {
+            State::Ready(editor) => {
+                editor.show(ui, update_state, is_focused);
+                return;
+            }
+            State::Opened | State::LoadingData => (0., "Loading map data"),
+            State::CalculatingPassages(_) => (1. / 3., "Calculating collision"),
+            State::BuildingTilemap(_) => (2. / 3., "Building tilemap"),
+        };
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.);
+            ui.add(
+                egui::ProgressBar::new(progress)
+                    .text(text)
+                    .desired_width(256.)
+                    .animate(true),
+            );
+            if ui.button("Cancel").clicked() {
+                self.force_close = true;
+            }
+        });
+        ui.ctx().request_repaint();
+
+        if self.force_close {
+            return;
+        }
+        if let Err(e) = self.load(update_state) {
+            luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err(format!("While opening map {}", self.id))
+            );
+            self.force_close = true;
+        }
+    }
+}
+
+impl luminol_core::Tab for Editor {
     fn name(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
         let map_infos = update_state.data.map_infos();
         format!(
@@ -213,6 +568,10 @@ impl luminol_core::Tab for Tab {
         self.force_close
     }
 
+    fn has_unsaved_ui_state(&self) -> bool {
+        !self.event_windows.is_empty()
+    }
+
     fn show(
         &mut self,
         ui: &mut egui::Ui,
@@ -220,6 +579,23 @@ impl luminol_core::Tab for Tab {
         is_focused: bool,
     ) {
         self.brush_density = update_state.toolbar.brush_density;
+        self.symmetry = update_state.toolbar.symmetry;
+
+        self.center_on_pending_position(ui.ctx(), update_state);
+        if let Some(event_id) = ui.data_mut(|d| d.remove_temp::<usize>(pending_event_id(self.id))) {
+            let map = update_state.data.get_map(self.id);
+            if let Some(event) = map.events.get(event_id).cloned() {
+                self.view.selected_event_id = Some(event_id);
+                self.view
+                    .center_on(ui.ctx(), &map, egui::pos2(event.x as f32, event.y as f32));
+                self.event_windows.add_window(event_edit::Window::new(
+                    update_state,
+                    &event,
+                    self.id,
+                    map.tileset_id,
+                ));
+            }
+        }
 
         // Display the toolbar.
         // FIXME: find a proper place for this toolbar! it looks very out of place right now.
@@ -231,19 +607,38 @@ impl luminol_core::Tab for Tab {
                 })
                 .show(ui, |ui| {
                     ui.horizontal_wrapped(|ui| {
+                        self.undo_redo_ui(ui, update_state);
+
+                        ui.separator();
+
                         ui.add(
                             egui::Slider::new(&mut self.view.scale, 15.0..=300.)
                                 .text("Scale")
                                 .logarithmic(true)
                                 .fixed_decimals(0),
                         );
+                        if self.view.pixel_perfect {
+                            self.view.scale = MapView::snap_to_pixel_perfect_scale(self.view.scale);
+                        }
+                        ui.toggle_value(&mut self.view.pixel_perfect, "Pixel-perfect")
+                            .on_hover_text(
+                                "Snaps the scale to whole multiples of the tile size so that tiles render crisply",
+                            );
 
                         ui.separator();
 
+                        // Only VX Ace uses the region IDs
+                        let is_ace = update_state.project_config.as_ref().is_some_and(|config| {
+                            matches!(config.project.editor_ver, luminol_config::RMVer::Ace)
+                        });
+
                         ui.menu_button(
                             // Format the text based on what layer is selected.
                             match self.view.selected_layer {
                                 SelectedLayer::Events => "Events ⏷".to_string(),
+                                SelectedLayer::Tiles(_) if self.view.is_region_layer_selected() => {
+                                    "Regions ⏷".to_string()
+                                }
                                 SelectedLayer::Tiles(layer) => {
                                     format!("Layer {} ⏷", layer + 1)
                                 }
@@ -260,6 +655,7 @@ impl luminol_core::Tab for Tab {
                                         ui.checkbox(&mut self.view.map.pano_enabled, "👁");
                                         ui.end_row();
 
+                                        let has_shadows = self.view.map.tiles.has_shadows();
                                         for (index, layer) in self
                                             .view
                                             .map
@@ -268,6 +664,29 @@ impl luminol_core::Tab for Tab {
                                             .iter_mut()
                                             .enumerate()
                                         {
+                                            // The shadow layer is edited with the shadow pen
+                                            // instead of by selecting it
+                                            if has_shadows
+                                                && index == luminol_graphics::primitives::shadows::LAYER
+                                            {
+                                                ui.label(egui::RichText::new("Shadows").underline());
+                                                ui.checkbox(layer, "👁");
+                                                ui.end_row();
+
+                                                // Region IDs share the layer with the shadows
+                                                if is_ace {
+                                                    ui.columns(1, |columns| {
+                                                        columns[0].selectable_value(
+                                                            &mut self.view.selected_layer,
+                                                            SelectedLayer::Tiles(index),
+                                                            "Regions",
+                                                        );
+                                                    });
+                                                    ui.end_row();
+                                                }
+                                                continue;
+                                            }
+
                                             ui.columns(1, |columns| {
                                                 columns[0].selectable_value(
                                                     &mut self.view.selected_layer,
@@ -302,7 +721,58 @@ impl luminol_core::Tab for Tab {
                                         ui.checkbox(&mut self.view.map.grid_enabled, "👁");
                                         ui.end_row();
                                     });
+
+                                if let SelectedLayer::Tiles(layer) = self.view.selected_layer {
+                                    ui.separator();
+
+                                    if ui
+                                        .add_enabled(
+                                            self.export_layer_promise.is_none(),
+                                            egui::Button::new("Export as image..."),
+                                        )
+                                        .on_hover_text(
+                                            "Save this layer as an image with one pixel per tile, \
+                                            and a legend of the tile colors",
+                                        )
+                                        .clicked()
+                                    {
+                                        self.export_layer_promise =
+                                            Some(luminol_core::spawn_future(
+                                                self.export_layer_image(
+                                                    &update_state.data.get_map(self.id),
+                                                    layer,
+                                                ),
+                                            ));
+                                        ui.close_menu();
+                                    }
+
+                                    if ui
+                                        .add_enabled(
+                                            self.import_layer_promise.is_none(),
+                                            egui::Button::new("Import from image..."),
+                                        )
+                                        .on_hover_text(
+                                            "Replace this layer with an edited image from the \
+                                            folder it was exported to",
+                                        )
+                                        .clicked()
+                                    {
+                                        self.import_layer_promise =
+                                            Some(luminol_core::spawn_future(
+                                                self.import_layer_image(
+                                                    &update_state.data.get_map(self.id),
+                                                    layer,
+                                                ),
+                                            ));
+                                        ui.close_menu();
+                                    }
+                                }
                             },
+                        )
+                        .response
+                        .on_hover_text(
+                            "Press 1 to 9 to select a layer or E for the events, and hold Alt to \
+                            toggle their visibility instead",
                         );
 
                         ui.separator();
@@ -325,10 +795,220 @@ impl luminol_core::Tab for Tab {
                                 .on_disabled_hover_text(
                                     "Display the tile IDs of the currently selected layer",
                                 );
+                            ui.checkbox(
+                                &mut self.view.display_start_position,
+                                "Display player start position",
+                            )
+                            .on_hover_text(
+                                "Mark the tile new games start on. Drag the marker on the events \
+                                layer to move it",
+                            );
+                            ui.checkbox(&mut self.view.display_minimap, "Display minimap")
+                                .on_hover_text(
+                                    "Show the whole map in a corner, and click it to move the view",
+                                );
+
+                            let mut split = self.view.split.is_some();
+                            if ui
+                                .checkbox(&mut split, "Split view")
+                                .on_hover_text(
+                                    "Shows the map twice side by side, with separate layer visibility for each side",
+                                )
+                                .changed()
+                            {
+                                self.view.set_split(split);
+                            }
+
+                            ui.separator();
+                            self.onion_skin_ui(ui, update_state);
+
+                            ui.separator();
+                            self.map_loop_ui(ui, update_state);
+                        });
+
+                        let has_shadows = self.view.map.tiles.has_shadows();
+                        let split_grid_id = self.id().with("split_layer_visibility");
+                        if let Some(split) = &mut self.view.split {
+                            ui.separator();
+
+                            ui.menu_button("Right side ⏷", |ui| {
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                                egui::Grid::new(split_grid_id)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for (index, layer) in
+                                            split.enabled_layers.iter_mut().enumerate()
+                                        {
+                                            if has_shadows
+                                                && index == luminol_graphics::primitives::shadows::LAYER
+                                            {
+                                                ui.label("Shadows");
+                                            } else {
+                                                ui.label(format!("Layer {}", index + 1));
+                                            }
+                                            ui.checkbox(layer, "👁");
+                                            ui.end_row();
+                                        }
+                                    });
+
+                                ui.checkbox(
+                                    &mut split.darken_unselected_layers,
+                                    "Darken unselected layers",
+                                );
+                            });
+                        }
+
+                        ui.separator();
+
+                        ui.menu_button("Bookmarks ⏷", |ui| {
+                            self.bookmarks_ui(ui, update_state);
                         });
 
                         ui.separator();
 
+                        ui.add_enabled_ui(
+                            self.event_drag_info.is_none() && self.drawing_shape_pos.is_none(),
+                            |ui| {
+                                ui.menu_button("Shift map ⏷", |ui| {
+                                    self.shift_map_ui(ui, update_state);
+                                })
+                                .response
+                                .on_hover_text("Move every layer of this map by an offset")
+                            },
+                        );
+
+                        ui.add_enabled_ui(
+                            self.event_drag_info.is_none() && self.drawing_shape_pos.is_none(),
+                            |ui| {
+                                ui.menu_button("Replace tiles ⏷", |ui| {
+                                    self.replace_tiles_ui(ui, update_state);
+                                })
+                                .response
+                                .on_hover_text("Replace every instance of a tile with another one")
+                            },
+                        );
+
+                        if update_state.toolbar.symmetry != luminol_core::Symmetry::Off {
+                            ui.menu_button("Mirror axis ⏷", |ui| {
+                                if ui
+                                    .button("Move to the cursor")
+                                    .on_hover_text(
+                                        "Mirror tiles across the center of the tile under the map \
+                                        cursor",
+                                    )
+                                    .clicked()
+                                {
+                                    self.view.symmetry_axis =
+                                        Some(self.view.cursor_pos + egui::Vec2::splat(0.5));
+                                    ui.close_menu();
+                                }
+                                if ui
+                                    .add_enabled(
+                                        self.view.symmetry_axis.is_some(),
+                                        egui::Button::new("Reset to the map center"),
+                                    )
+                                    .clicked()
+                                {
+                                    self.view.symmetry_axis = None;
+                                    ui.close_menu();
+                                }
+                            })
+                            .response
+                            .on_hover_text("Where the brushes mirror tiles across");
+                        }
+
+                        let can_renumber = self.event_drag_info.is_none()
+                            && self.drawing_shape_pos.is_none()
+                            && self.event_windows.is_empty();
+                        if ui
+                            .add_enabled(can_renumber, egui::Button::new("Compact event IDs"))
+                            .on_hover_text(
+                                "Renumbers the events on this map so their IDs have no gaps, and updates the event commands on this map that refer to them.\n\
+                                Script calls and common events that refer to events by ID are not updated.",
+                            )
+                            .on_disabled_hover_text(
+                                "Finish dragging or drawing and close this map's event editors first",
+                            )
+                            .clicked()
+                        {
+                            let mut map = update_state.data.get_map(self.id);
+                            let count = self.compact_event_ids(update_state, &mut map);
+                            drop(map);
+                            if count == 0 {
+                                luminol_core::info!(
+                                    update_state.toasts,
+                                    "The event IDs on this map have no gaps"
+                                );
+                            } else {
+                                luminol_core::info!(
+                                    update_state.toasts,
+                                    format!(
+                                        "Renumbered {count} events. Scripts that refer to events by ID were not updated."
+                                    )
+                                );
+                            }
+                        }
+
+                        ui.separator();
+
+                        if let (luminol_core::Pencil::Select, SelectedLayer::Tiles(tile_layer)) =
+                            (update_state.toolbar.pencil, self.view.selected_layer)
+                        {
+                            let has_selection = self.view.tile_selection.is_some();
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new("Copy"))
+                                .on_hover_text("Copy the selected tiles of this layer (Ctrl+C)")
+                                .clicked()
+                            {
+                                self.copy_selection(
+                                    update_state.toolbar,
+                                    &update_state.data.get_map(self.id),
+                                    tile_layer,
+                                    false,
+                                );
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new("Copy all layers"))
+                                .on_hover_text("Copy the selected tiles of every layer (Ctrl+Shift+C)")
+                                .clicked()
+                            {
+                                self.copy_selection(
+                                    update_state.toolbar,
+                                    &update_state.data.get_map(self.id),
+                                    tile_layer,
+                                    true,
+                                );
+                            }
+                            if ui
+                                .add_enabled(
+                                    update_state.toolbar.tile_clipboard.is_some(),
+                                    egui::Button::new("Paste"),
+                                )
+                                .on_hover_text(
+                                    "Show the copied tiles under the cursor, then click to place \
+                                    them or press Escape to cancel (Ctrl+V)",
+                                )
+                                .clicked()
+                            {
+                                self.start_pasting(update_state.toolbar);
+                            }
+                            if self.is_pasting_other_tileset(
+                                update_state.data.get_map(self.id).tileset_id,
+                            ) {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    "⚠ Copied from another tileset",
+                                )
+                                .on_hover_text(
+                                    "The tiles were copied from a map with a different tileset, so \
+                                    they will show this map's graphics for the same tile IDs",
+                                );
+                            }
+
+                            ui.separator();
+                        }
+
                         if ui.button("Save map preview").clicked()
                             && self.save_as_image_promise.is_none()
                         {
@@ -350,6 +1030,18 @@ impl luminol_core::Tab for Tab {
                 });
         });
 
+        if is_focused && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::B)) {
+            self.add_bookmark_at_cursor(update_state, String::new());
+        }
+
+        // The tab loses focus while a text field has keyboard focus, so typing won't switch layers
+        if is_focused {
+            let is_ace = update_state.project_config.as_ref().is_some_and(|config| {
+                matches!(config.project.editor_ver, luminol_config::RMVer::Ace)
+            });
+            self.handle_layer_shortcuts(ui, is_ace);
+        }
+
         // Display the tilepicker.
         let spacing = ui.spacing();
         let tilepicker_default_width = 256. + spacing.indent;
@@ -357,6 +1049,17 @@ impl luminol_core::Tab for Tab {
             .default_width(tilepicker_default_width)
             .max_width(tilepicker_default_width)
             .show_inside(ui, |ui| {
+                if self.view.is_region_layer_selected() {
+                    egui::ScrollArea::vertical().show(ui, |ui| self.region_picker.ui(ui));
+                    return;
+                }
+
+                self.tilepicker.special_tiles_ui(ui);
+                let tileset_id = update_state.data.get_map(self.id).tileset_id;
+                self.tilepicker.smart_tiles_ui(update_state, tileset_id, ui);
+                self.stamps_ui(update_state, ui);
+                ui.separator();
+
                 egui::ScrollArea::both()
                     .id_source(
                         update_state
@@ -376,17 +1079,34 @@ impl luminol_core::Tab for Tab {
 
         egui::CentralPanel::default().show_inside(ui, |ui| {
             egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                // The minimap is made before getting the map since it borrows the map itself
+                let minimap = if self.view.display_minimap {
+                    self.minimap_texture(update_state)
+                } else {
+                    None
+                };
+
                 // Get the map.
                 let mut map = update_state.data.get_map(self.id);
                 let tilesets = update_state.data.tilesets();
                 let tileset = &tilesets.data[map.tileset_id];
 
+                let brush_layer = self.brush_layer(update_state.toolbar.pencil, &map);
+
                 // Save the state of the selected layer into the cache
-                if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
+                if let Some(tile_layer) = brush_layer {
                     self.layer_cache
                         .copy_from_slice(map.data.layer_as_slice(tile_layer));
                 }
 
+                let system = update_state.data.system();
+                self.view.start_position = (system.start_map_id == self.id)
+                    .then(|| egui::pos2(system.start_x as f32, system.start_y as f32));
+                drop(system);
+                if self.start_position_drag.is_some() {
+                    self.view.start_position = self.start_position_drag;
+                }
+
                 let response = self.view.ui(
                     ui,
                     update_state,
@@ -399,6 +1119,11 @@ impl luminol_core::Tab for Tab {
                     is_focused,
                 );
 
+                self.handle_start_position_drag(ui, update_state, &response);
+                if let Some(texture) = &minimap {
+                    self.minimap_ui(ui, response.rect, texture, &map);
+                }
+
                 let _layers_max = map.data.zsize();
                 let map_x = self.view.cursor_pos.x as i32;
                 let map_y = self.view.cursor_pos.y as i32;
@@ -437,6 +1162,11 @@ impl luminol_core::Tab for Tab {
                     }
                 }
 
+                // The stroke painted from the keyboard is finished right below, like a click
+                if let Some(tile_layer) = brush_layer.filter(|_| is_focused) {
+                    self.handle_keyboard_brush(ui, update_state, &mut map, tile_layer);
+                }
+
                 if !response.is_pointer_button_down_on()
                     || ui.input(|i| !i.pointer.button_down(egui::PointerButton::Primary))
                 {
@@ -444,28 +1174,59 @@ impl luminol_core::Tab for Tab {
                         self.drawing_shape = false;
                     }
 
+                    if let Some((x, y)) = self.eyedropper_pos.take() {
+                        self.pick_tile(&map, x, y);
+                    }
+
+                    if let Some(rect) = self.view.eyedropper_rect.take() {
+                        self.eyedropper_origin = None;
+                        self.pick_tiles(
+                            update_state.toasts,
+                            &map,
+                            (rect.min.x as usize, rect.min.y as usize),
+                            (rect.max.x as usize, rect.max.y as usize),
+                        );
+                    }
+
                     if self.drawing_shape_pos.is_some() {
                         self.drawing_shape_pos = None;
+                        let layer = self.tilemap_undo_cache_layer;
                         let delta = (0..map.data.ysize())
                             .cartesian_product(0..map.data.xsize())
                             .filter_map(|(y, x)| {
                                 let old_id = self.tilemap_undo_cache[x + y * map.data.xsize()];
-                                (map.data[(x, y, self.tilemap_undo_cache_layer)] != old_id)
-                                    .then_some((x, y, old_id))
+                                (map.data[(x, y, layer)] != old_id).then_some((x, y, layer, old_id))
                             })
                             .collect();
-                        self.push_to_history(
-                            update_state,
-                            &mut map,
-                            HistoryEntry::Tiles {
-                                layer: self.tilemap_undo_cache_layer,
-                                delta,
-                            },
-                        );
+                        self.push_to_history(update_state, &mut map, HistoryEntry::Tiles { delta });
                     }
                 }
 
-                if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
+                // The select tool only works on tile layers
+                let is_select_tool = update_state.toolbar.pencil == luminol_core::Pencil::Select;
+                if !is_select_tool || brush_layer.is_none() {
+                    self.cancel_paste(update_state, &map);
+                }
+
+                if let Some(tile_layer) = brush_layer.filter(|_| is_select_tool) {
+                    let copied = self.handle_select_tool(
+                        ui,
+                        update_state,
+                        &response,
+                        &mut map,
+                        tile_layer,
+                        is_focused,
+                    );
+                    if let Some(copied) = copied {
+                        update_state.toolbar.tile_clipboard = Some(std::sync::Arc::new(copied));
+                    }
+                } else if let Some(tile_layer) = brush_layer {
+                    // Middle-clicking also picks up the tile under the cursor, while dragging
+                    // with the middle button pans the map
+                    if response.clicked_by(egui::PointerButton::Middle) {
+                        self.pick_tile(&map, map_x as usize, map_y as usize);
+                    }
+
                     // Tile drawing
                     if response.is_pointer_button_down_on()
                         && ui.input(|i| {
@@ -473,20 +1234,61 @@ impl luminol_core::Tab for Tab {
                                 && !i.modifiers.command
                         })
                     {
-                        if self.drawing_shape_pos.is_none() {
-                            // Before drawing tiles, save the state of the current layer so we can
-                            // undo it later if we need to
-                            self.tilemap_undo_cache_layer = tile_layer;
-                            self.tilemap_undo_cache.copy_from_slice(&self.layer_cache);
+                        // Pressing the mouse with alt held picks up the tile under the cursor
+                        // if it's released without moving. Dragging away draws as usual instead,
+                        // with alt toggling the randomization.
+                        // Dragging with both alt and shift held picks up a rectangle of tiles.
+                        let cursor = (map_x as usize, map_y as usize);
+                        if self.drawing_shape_pos.is_none()
+                            && self.eyedropper_pos.is_none()
+                            && self.eyedropper_origin.is_none()
+                            && ui.input(|i| i.modifiers.alt && i.modifiers.shift)
+                        {
+                            self.eyedropper_origin = Some(cursor);
                         }
+                        if let Some(origin) = self.eyedropper_origin {
+                            self.view.eyedropper_rect = Some(egui::Rect::from_two_pos(
+                                egui::pos2(origin.0 as f32, origin.1 as f32),
+                                egui::pos2(cursor.0 as f32, cursor.1 as f32),
+                            ));
+                        } else {
+                            if self.drawing_shape_pos.is_none()
+                                && self.eyedropper_pos.is_none()
+                                && ui.input(|i| i.modifiers.alt)
+                            {
+                                self.eyedropper_pos = Some(cursor);
+                            }
 
-                        self.handle_brush(
-                            map_x as usize,
-                            map_y as usize,
-                            tile_layer,
-                            update_state.toolbar.pencil,
-                            &mut map,
-                        );
+                            match self.eyedropper_pos {
+                                Some(eyedropper_pos) if eyedropper_pos == cursor => {}
+                                eyedropper_pos => {
+                                    self.eyedropper_pos = None;
+
+                                    if self.drawing_shape_pos.is_none() {
+                                        // Before drawing tiles, save the state of the current
+                                        // layer so we can undo it later if we need to
+                                        self.tilemap_undo_cache_layer = tile_layer;
+                                        self.tilemap_undo_cache.copy_from_slice(&self.layer_cache);
+                                    }
+
+                                    // If the stroke started out as a possible eyedropper click,
+                                    // the tile it started on hasn't been drawn yet
+                                    for (x, y) in eyedropper_pos.into_iter().chain([cursor]) {
+                                        self.handle_brush(
+                                            x,
+                                            y,
+                                            tile_layer,
+                                            update_state.toolbar,
+                                            update_state
+                                                .project_config
+                                                .as_ref()
+                                                .map(|config| &config.project),
+                                            &mut map,
+                                        );
+                                    }
+                                }
+                            }
+                        }
                     }
                 } else if let Some(selected_event_id) = self.view.selected_event_id {
                     if response.double_clicked()
@@ -505,14 +1307,15 @@ impl luminol_core::Tab for Tab {
                     }
 
                     // Press delete or backspace to delete the selected event
-                    if is_delete_pressed {
-                        let event = map.events.remove(selected_event_id);
-                        let sprite = self.view.map.events.try_remove(selected_event_id).ok();
-                        self.push_to_history(
-                            update_state,
-                            &mut map,
-                            HistoryEntry::EventDeleted { event, sprite },
-                        );
+                    if is_delete_pressed && !self.confirmation.is_open() {
+                        if update_state.confirm_destructive(
+                            &mut self.confirmation,
+                            luminol_config::global::DestructiveAction::DeleteEvent,
+                        ) {
+                            self.delete_event(update_state, &mut map, selected_event_id);
+                        } else {
+                            self.event_pending_deletion = Some(selected_event_id);
+                        }
                     }
 
                     if let Some(hover_tile) = self.view.hover_tile {
@@ -567,12 +1370,25 @@ impl luminol_core::Tab for Tab {
                         }
                     }
                 } else {
-                    // Double-click/press enter on an empty space to add an event
+                    // Add an event on an empty space with the gesture picked in the preferences
                     // (hold shift to prevent events from being selected)
-                    if response.double_clicked()
-                        || (is_focused && ui.input(|i| i.key_pressed(egui::Key::Enter)))
-                    {
-                        if let Some(id) = self.add_event(update_state, &mut map) {
+                    let is_enter_pressed =
+                        is_focused && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let global_config = &update_state.global_config;
+                    let create_event = match global_config.event_creation {
+                        EventCreation::DoubleClick => {
+                            self.is_tile_double_clicked(
+                                &response,
+                                global_config.event_double_click_time,
+                            ) || is_enter_pressed
+                        }
+                        EventCreation::EnterOnly => is_enter_pressed,
+                        EventCreation::ContextMenu => false,
+                    };
+                    if create_event {
+                        if let Some(id) =
+                            self.add_event(update_state, &mut map, self.view.cursor_pos)
+                        {
                             self.push_to_history(
                                 update_state,
                                 &mut map,
@@ -582,6 +1398,8 @@ impl luminol_core::Tab for Tab {
                     }
                 }
 
+                self.context_menu(update_state, &response, &mut map);
+
                 // Handle undo/redo keypresses
                 let is_dragged_by_primary = response.dragged_by(egui::PointerButton::Primary);
                 let is_undo_pressed = is_focused
@@ -594,65 +1412,11 @@ impl luminol_core::Tab for Tab {
                             && (i.modifiers.shift || i.key_pressed(egui::Key::Y))
                             && (!i.modifiers.shift || i.key_pressed(egui::Key::Z))
                     });
-                if !is_dragged_by_primary && (is_undo_pressed || is_redo_pressed) {
-                    let new_entry = match if is_undo_pressed {
-                        self.history.pop_back()
-                    } else {
-                        self.redo_history.pop()
-                    } {
-                        None => None,
-
-                        Some(HistoryEntry::Tiles { layer, mut delta }) => {
-                            for d in delta.iter_mut() {
-                                let position = (d.0, d.1, layer);
-                                let new_id = d.2;
-                                *d = (d.0, d.1, map.data[position]);
-                                map.data[position] = new_id;
-                                self.view.map.set_tile(
-                                    &update_state.graphics.render_state,
-                                    new_id,
-                                    position,
-                                );
-                            }
-                            Some(HistoryEntry::Tiles { layer, delta })
-                        }
-
-                        Some(HistoryEntry::EventMoved { id, x, y }) => {
-                            let event = map.events.get_mut(id).unwrap();
-                            let new_entry = Some(HistoryEntry::EventMoved {
-                                id,
-                                x: event.x,
-                                y: event.y,
-                            });
-                            event.x = x;
-                            event.y = y;
-                            new_entry
-                        }
-
-                        Some(HistoryEntry::EventCreated(id)) => {
-                            let event = map.events.remove(id);
-                            let sprite = self.view.map.events.try_remove(id).ok();
-                            Some(HistoryEntry::EventDeleted { event, sprite })
-                        }
-
-                        Some(HistoryEntry::EventDeleted { event, sprite }) => {
-                            let id = event.id;
-                            map.events.insert(id, event);
-                            if let Some(sprite) = sprite {
-                                self.view.map.events.insert(id, sprite);
-                            }
-                            Some(HistoryEntry::EventCreated(id))
-                        }
-                    };
-
-                    if let Some(new_entry) = new_entry {
-                        update_state.modified.set(true);
-                        map.modified = true;
-                        if is_undo_pressed {
-                            self.redo_history.push(new_entry);
-                        } else {
-                            self.history.push_back(new_entry);
-                        }
+                if !is_dragged_by_primary {
+                    if is_undo_pressed {
+                        self.undo(update_state, &mut map);
+                    } else if is_redo_pressed {
+                        self.redo(update_state, &mut map);
                     }
                 }
 
@@ -660,13 +1424,17 @@ impl luminol_core::Tab for Tab {
                     event.extra_data.is_editor_open = false;
                 }
 
-                if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
-                    // Write the buffered tile changes to the tilemap
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
+                if let (Some(tile_layer), Some(dirty_tiles)) =
+                    (brush_layer, self.dirty_tiles.take())
+                {
+                    // Write the buffered tile changes to the tilemap. Only the part of the layer
+                    // the brush touched this frame can have changed.
+                    for y in dirty_tiles.min_y..=dirty_tiles.max_y {
+                        for x in dirty_tiles.min_x..=dirty_tiles.max_x {
                             let position = (x, y, tile_layer);
                             let new_tile_id = map.data[position];
                             if new_tile_id != self.layer_cache[x + y * map.data.xsize()] {
+                                self.dirty_passages.insert((x, y));
                                 self.view.map.set_tile(
                                     &update_state.graphics.render_state,
                                     new_tile_id,
@@ -677,35 +1445,156 @@ impl luminol_core::Tab for Tab {
                     }
                 }
 
-                // Update the collision preview
-                luminol_graphics::Collision::calculate_passages(
-                    &tileset.passages,
-                    &tileset.priorities,
-                    &map.data,
-                    if self.view.map.event_enabled {
-                        Some(&map.events)
-                    } else {
-                        None
-                    },
-                    (0..map.data.zsize())
-                        .filter(|&i| self.view.map.tiles.enabled_layers[i])
-                        .rev(),
-                    |x, y, passage| {
-                        if self.passages[(x, y)] != passage {
-                            self.view.map.set_passage(
-                                &update_state.graphics.render_state,
-                                passage,
-                                (x, y),
-                            );
-                            self.passages[(x, y)] = passage;
+                // Update the collision preview. Only the positions whose tiles or events changed
+                // this frame need to be recomputed, unless the layer visibility or the tileset
+                // changed, which can affect every position on the map.
+                let event_keys = event_passage_keys(&map.events);
+                for (id, key) in event_keys.iter() {
+                    let old_key = self.passage_events.get(id);
+                    if old_key != Some(key) {
+                        self.dirty_passages.insert((key.0 as usize, key.1 as usize));
+                        if let Some(old_key) = old_key {
+                            self.dirty_passages
+                                .insert((old_key.0 as usize, old_key.1 as usize));
                         }
-                    },
-                );
+                    }
+                }
+                for (id, old_key) in self.passage_events.iter() {
+                    if !event_keys.contains_key(id) {
+                        self.dirty_passages
+                            .insert((old_key.0 as usize, old_key.1 as usize));
+                    }
+                }
+                self.passage_events = event_keys;
+
+                let tileset_changed =
+                    self.passage_tileset
+                        .as_ref()
+                        .map_or(true, |(passages, priorities)| {
+                            passages.as_slice() != tileset.passages.as_slice()
+                                || priorities.as_slice() != tileset.priorities.as_slice()
+                        });
+                let visibility_changed = self.passage_layers != self.view.map.tiles.enabled_layers
+                    || self.passage_events_enabled != self.view.map.event_enabled;
+
+                let events = if self.view.map.event_enabled {
+                    Some(&map.events)
+                } else {
+                    None
+                };
+                let layers = (0..map.data.zsize())
+                    .filter(|&i| self.view.map.tiles.enabled_layers[i])
+                    .rev()
+                    .collect_vec();
+                let render_state = &update_state.graphics.render_state;
+                let view_map = &mut self.view.map;
+                let cached_passages = &mut self.passages;
+                let mut update_passage = |x, y, passage| {
+                    if cached_passages[(x, y)] != passage {
+                        view_map.set_passage(render_state, passage, (x, y));
+                        cached_passages[(x, y)] = passage;
+                    }
+                };
+
+                if tileset_changed || visibility_changed {
+                    self.dirty_passages.clear();
+                    luminol_graphics::Collision::calculate_passages(
+                        &tileset.passages,
+                        &tileset.priorities,
+                        &map.data,
+                        events,
+                        layers.iter().copied(),
+                        update_passage,
+                    );
+                    self.passage_layers
+                        .clone_from(&self.view.map.tiles.enabled_layers);
+                    self.passage_events_enabled = self.view.map.event_enabled;
+                    if tileset_changed {
+                        self.passage_tileset =
+                            Some((tileset.passages.clone(), tileset.priorities.clone()));
+                    }
+                } else if !self.dirty_passages.is_empty() {
+                    let (xsize, ysize) = (map.data.xsize(), map.data.ysize());
+                    luminol_graphics::Collision::calculate_passages_at(
+                        &tileset.passages,
+                        &tileset.priorities,
+                        &map.data,
+                        events,
+                        layers.iter().copied(),
+                        self.dirty_passages
+                            .drain()
+                            .filter(|&(x, y)| x < xsize && y < ysize),
+                        &mut update_passage,
+                    );
+                }
             })
         });
 
+        if let Some(luminol_config::global::DestructiveAction::DeleteEvent) =
+            self.confirmation.show(update_state.global_config)
+        {
+            if let Some(id) = self.event_pending_deletion.take() {
+                let mut map = update_state.data.get_map(self.id);
+                if map.events.get(id).is_some() {
+                    self.delete_event(update_state, &mut map, id);
+                }
+            }
+        }
+
         self.event_windows.display(ui.ctx(), update_state);
 
+        if let Some(p) = self.export_layer_promise.take() {
+            match p.try_take() {
+                Ok(Ok(())) => luminol_core::info!(update_state.toasts, "Exported the layer"),
+                Ok(Err(error))
+                    if !matches!(
+                        error.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) =>
+                {
+                    luminol_core::error!(update_state.toasts, error);
+                }
+                Ok(Err(_)) => {}
+                Err(p) => self.export_layer_promise = Some(p),
+            }
+        }
+
+        if let Some(p) = self.import_layer_promise.take() {
+            match p.try_take() {
+                Ok(Ok(imported)) => {
+                    let unmapped_colors = imported
+                        .unmapped_colors
+                        .iter()
+                        .map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}"))
+                        .join(", ");
+                    let mut map = update_state.data.get_map(self.id);
+                    let count = self.apply_layer_image(update_state, &mut map, imported);
+                    luminol_core::info!(
+                        update_state.toasts,
+                        format!("Imported the layer, {count} tiles changed")
+                    );
+                    if !unmapped_colors.is_empty() {
+                        luminol_core::warn!(
+                            update_state.toasts,
+                            format!(
+                                "These colors aren't in the legend, so their tiles were left unchanged: {unmapped_colors}"
+                            )
+                        );
+                    }
+                }
+                Ok(Err(error))
+                    if !matches!(
+                        error.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) =>
+                {
+                    luminol_core::error!(update_state.toasts, error);
+                }
+                Ok(Err(_)) => {}
+                Err(p) => self.import_layer_promise = Some(p),
+            }
+        }
+
         if let Some(p) = self.save_as_image_promise.take() {
             match p.try_take() {
                 Ok(Ok(())) => {}
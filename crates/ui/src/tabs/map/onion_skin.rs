@@ -0,0 +1,169 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_config::onion_skins::OnionSkins;
+use luminol_filesystem::FileSystem;
+
+use crate::components::OnionSkin;
+
+fn read_onion_skins(filesystem: &impl FileSystem) -> OnionSkins {
+    filesystem
+        .read_to_string(OnionSkins::PATH)
+        .ok()
+        .and_then(|s| ron::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+impl super::Editor {
+    /// Writes the onion skin of this map to the project's onion skin file, leaving the onion skins
+    /// of other maps alone.
+    fn save_onion_skin(&self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        let c = "While saving map onion skin";
+
+        let Some(onion_skin) = &self.view.onion_skin else {
+            return Ok(());
+        };
+
+        // Other map tabs may have changed their onion skins since we loaded ours
+        let mut onion_skins = read_onion_skins(filesystem);
+        onion_skins.maps.insert(self.id, onion_skin.map_id);
+        onion_skins
+            .pairs
+            .insert((self.id, onion_skin.map_id), onion_skin.settings);
+
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let onion_skins = ron::ser::to_string_pretty(&onion_skins, pretty_config).wrap_err(c)?;
+        filesystem.write(OnionSkins::PATH, onion_skins).wrap_err(c)
+    }
+
+    /// Overlays the map with the given ID on this one, with the settings last used for that map.
+    fn show_onion_skin(&mut self, update_state: &mut luminol_core::UpdateState<'_>, map_id: usize) {
+        let settings = read_onion_skins(update_state.filesystem)
+            .pairs
+            .remove(&(self.id, map_id))
+            .unwrap_or_default();
+
+        match OnionSkin::new(update_state, map_id, settings) {
+            Ok(onion_skin) => self.view.onion_skin = Some(onion_skin),
+            Err(e) => luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err(format!("Error loading map {map_id:0>3} as an onion skin"))
+            ),
+        }
+    }
+
+    /// Shows the onion skin settings in the display options menu.
+    pub(super) fn onion_skin_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut visible = self.view.onion_skin.is_some();
+        if ui
+            .checkbox(&mut visible, "Onion skin")
+            .on_hover_text(
+                "Shows another map translucently under or over this one, to line up maps that connect to each other",
+            )
+            .changed()
+        {
+            if visible {
+                // Start with the map used last time, or the parent map if there isn't one
+                let map_id = read_onion_skins(update_state.filesystem)
+                    .maps
+                    .get(&self.id)
+                    .copied()
+                    .or_else(|| {
+                        let map_infos = update_state.data.map_infos();
+                        map_infos
+                            .data
+                            .get(&self.id)
+                            .map(|info| info.parent_id)
+                            .filter(|id| map_infos.data.contains_key(id))
+                    })
+                    .unwrap_or(self.id);
+                self.show_onion_skin(update_state, map_id);
+            } else {
+                self.view.onion_skin = None;
+            }
+        }
+
+        let Some(onion_skin) = &mut self.view.onion_skin else {
+            return;
+        };
+
+        let mut map_id = onion_skin.map_id;
+        let mut changed = false;
+        ui.indent(
+            egui::Id::new("luminol_map_onion_skin").with(self.id),
+            |ui| {
+                let mut map_infos: Vec<_> = (update_state.data.map_infos().data.iter())
+                    .map(|(&id, info)| (id, info.name.clone()))
+                    .collect();
+                map_infos.sort_unstable_by_key(|(id, _)| *id);
+
+                let selected_text = map_infos.iter().find(|(id, _)| *id == map_id).map_or_else(
+                    || format!("{map_id:0>3}"),
+                    |(id, name)| format!("{id:0>3}: {name}"),
+                );
+                egui::ComboBox::from_id_source(
+                    egui::Id::new("luminol_map_onion_skin_map").with(self.id),
+                )
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (id, name) in map_infos {
+                        ui.selectable_value(&mut map_id, id, format!("{id:0>3}: {name}"));
+                    }
+                });
+
+                let settings = &mut onion_skin.settings;
+                ui.horizontal(|ui| {
+                    ui.label("Offset");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.offset[0]).prefix("x: "))
+                        .changed();
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.offset[1]).prefix("y: "))
+                        .changed();
+                })
+                .response
+                .on_hover_text("Position of the other map relative to this one, in tiles");
+                changed |= ui
+                    .add(egui::Slider::new(&mut settings.opacity, 0..=255).text("Opacity"))
+                    .changed();
+                changed |= ui.checkbox(&mut settings.above, "Draw above").changed();
+            },
+        );
+
+        if map_id != onion_skin.map_id {
+            self.show_onion_skin(update_state, map_id);
+            changed = true;
+        }
+        if changed {
+            if let Err(e) = self.save_onion_skin(update_state.filesystem) {
+                luminol_core::error!(update_state.toasts, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+
+use crate::components::SelectedTile;
+
+/// The settings of the replace tiles operation, kept between uses.
+#[derive(Default)]
+pub(super) struct ReplaceOptions {
+    find: Option<SelectedTile>,
+    replace: Option<SelectedTile>,
+    /// Whether every tile layer is searched instead of only the selected one
+    all_layers: bool,
+    /// Whether only the tiles in the tile selection are replaced
+    selection_only: bool,
+}
+
+fn tile_label(tile: Option<SelectedTile>) -> String {
+    match tile {
+        None => "(none)".into(),
+        Some(SelectedTile::Autotile(0)) => "Empty".into(),
+        Some(SelectedTile::Autotile(autotile)) => format!("Autotile {autotile}"),
+        Some(SelectedTile::Tile(id)) => format!("Tile {id}"),
+    }
+}
+
+/// Returns true if a tile ID on the map is an instance of `tile`. Autotiles match in any shape.
+fn matches(id: i16, tile: SelectedTile) -> bool {
+    match tile {
+        SelectedTile::Autotile(autotile) => id < 384 && id / 48 == autotile,
+        SelectedTile::Tile(tile_id) => id == tile_id,
+    }
+}
+
+/// Returns what a tile ID on the map becomes, or `None` if it isn't an instance of `find`.
+fn replacement(id: i16, find: SelectedTile, replace: SelectedTile) -> Option<i16> {
+    if !matches(id, find) {
+        return None;
+    }
+    Some(match (find, replace) {
+        (SelectedTile::Autotile(_), SelectedTile::Autotile(autotile)) if autotile != 0 => {
+            autotile * 48 + id % 48
+        }
+        _ => replace.to_id(),
+    })
+}
+
+impl super::Editor {
+    /// Shows the replace tiles settings and the button that replaces the tiles.
+    pub(super) fn replace_tiles_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let selected_tile = self.tilepicker.first_selected_tile();
+        let has_selection = self.view.tile_selection.is_some();
+        let options = &mut self.replace_options;
+
+        egui::Grid::new(self.view.data_id.with("replace_tiles"))
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (label, tile) in [
+                    ("Find", &mut options.find),
+                    ("Replace with", &mut options.replace),
+                ] {
+                    ui.label(label);
+                    ui.label(tile_label(*tile));
+                    if ui
+                        .button("Use picked tile")
+                        .on_hover_text("Use the top-left tile selected in the tilepicker")
+                        .clicked()
+                    {
+                        *tile = Some(selected_tile);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        ui.checkbox(&mut options.all_layers, "All layers")
+            .on_hover_text("Replace the tile on every layer instead of only the selected one");
+        ui.add_enabled(
+            has_selection,
+            egui::Checkbox::new(&mut options.selection_only, "Only in selection"),
+        )
+        .on_disabled_hover_text("Select some tiles with the select tool first");
+
+        let can_replace = options.find.is_some()
+            && options.replace.is_some()
+            && options.find != options.replace
+            && (options.all_layers
+                || (matches!(self.view.selected_layer, super::SelectedLayer::Tiles(_))
+                    && !self.view.is_region_layer_selected()));
+        if ui
+            .add_enabled(can_replace, egui::Button::new("Replace"))
+            .clicked()
+        {
+            let mut map = update_state.data.get_map(self.id);
+            let count = self.replace_tiles(update_state, &mut map);
+            drop(map);
+            if count == 0 {
+                luminol_core::info!(update_state.toasts, "No tiles matched");
+            } else {
+                luminol_core::info!(update_state.toasts, format!("Replaced {count} tiles"));
+            }
+            ui.close_menu();
+        }
+    }
+
+    /// Replaces every instance of the tile to find with the replacement tile as a single history
+    /// entry, and returns how many were replaced. Autotiles keep their shape when replaced with
+    /// another autotile, and the autotiles around replaced tiles are reshaped to connect to them.
+    fn replace_tiles(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) -> usize {
+        let ReplaceOptions {
+            find: Some(find),
+            replace: Some(replace),
+            all_layers,
+            selection_only,
+        } = self.replace_options
+        else {
+            return 0;
+        };
+        let (xsize, ysize, zsize) = (map.data.xsize(), map.data.ysize(), map.data.zsize());
+
+        let layers = if all_layers {
+            // The shadow layer doesn't hold tiles
+            let has_shadows = self.view.map.tiles.has_shadows();
+            (0..zsize)
+                .filter(|&z| !has_shadows || z != luminol_graphics::primitives::shadows::LAYER)
+                .collect_vec()
+        } else if let super::SelectedLayer::Tiles(layer) = self.view.selected_layer {
+            vec![layer]
+        } else {
+            return 0;
+        };
+        let (min_x, min_y, max_x, max_y) = match self.view.tile_selection {
+            Some(selection) if selection_only => (
+                selection.min.x as usize,
+                selection.min.y as usize,
+                (selection.max.x as usize).min(xsize - 1),
+                (selection.max.y as usize).min(ysize - 1),
+            ),
+            _ => (0, 0, xsize - 1, ysize - 1),
+        };
+
+        let old_data = map.data.clone();
+        let mut count = 0;
+        let mut reshape = std::collections::HashSet::new();
+        for (z, y, x) in layers
+            .into_iter()
+            .cartesian_product(min_y..=max_y)
+            .cartesian_product(min_x..=max_x)
+            .map(|((z, y), x)| (z, y, x))
+        {
+            let Some(tile_id) = replacement(old_data[(x, y, z)], find, replace) else {
+                continue;
+            };
+
+            map.data[(x, y, z)] = tile_id;
+            count += 1;
+
+            for (dx, dy) in (-1..=1).cartesian_product(-1..=1) {
+                let (x, y) = (x as i32 + dx, y as i32 + dy);
+                if (0..xsize as i32).contains(&x) && (0..ysize as i32).contains(&y) {
+                    reshape.insert((x as usize, y as usize, z));
+                }
+            }
+        }
+
+        // Autotiles that were placed and the ones next to tiles that changed have to be reshaped,
+        // but autotiles that were swapped for other autotiles keep their shape
+        let keeps_shape = matches!(
+            (find, replace),
+            (SelectedTile::Autotile(find), SelectedTile::Autotile(replace))
+                if find != 0 && replace != 0
+        );
+        if !keeps_shape {
+            for position in reshape {
+                let tile_id = self.recompute_autotile(map, position);
+                map.data[position] = tile_id;
+            }
+        }
+
+        let mut delta = Vec::new();
+        for (z, y, x) in (0..zsize)
+            .cartesian_product(0..ysize)
+            .cartesian_product(0..xsize)
+            .map(|((z, y), x)| (z, y, x))
+        {
+            let id = map.data[(x, y, z)];
+            if id != old_data[(x, y, z)] {
+                delta.push((x, y, z, old_data[(x, y, z)]));
+                self.dirty_passages.insert((x, y));
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, id, (x, y, z));
+            }
+        }
+
+        if !delta.is_empty() {
+            self.push_to_history(update_state, map, super::HistoryEntry::Tiles { delta });
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autotiles_match_in_any_shape() {
+        let find = SelectedTile::Autotile(2);
+        assert!(matches(96, find));
+        assert!(matches(96 + 47, find));
+        assert!(!matches(48 + 47, find));
+        assert!(!matches(144, find));
+
+        assert!(matches(400, SelectedTile::Tile(400)));
+        assert!(!matches(401, SelectedTile::Tile(400)));
+    }
+
+    #[test]
+    fn autotiles_keep_their_shape() {
+        let (find, replace) = (SelectedTile::Autotile(2), SelectedTile::Autotile(5));
+        assert_eq!(replacement(96 + 13, find, replace), Some(240 + 13));
+        assert_eq!(replacement(144 + 13, find, replace), None);
+    }
+
+    #[test]
+    fn other_replacements_use_the_tile_id() {
+        let tile = SelectedTile::Tile(400);
+        assert_eq!(
+            replacement(96 + 13, SelectedTile::Autotile(2), tile),
+            Some(400)
+        );
+        assert_eq!(
+            replacement(400, tile, SelectedTile::Autotile(5)),
+            Some(SelectedTile::Autotile(5).to_id())
+        );
+        assert_eq!(
+            replacement(
+                96 + 13,
+                SelectedTile::Autotile(2),
+                SelectedTile::Autotile(0)
+            ),
+            Some(0)
+        );
+        assert_eq!(replacement(0, SelectedTile::Autotile(0), tile), Some(400));
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+
+/// The settings of the shift map operation, kept between uses.
+pub(super) struct ShiftOptions {
+    dx: i32,
+    dy: i32,
+    /// Whether tiles that go past an edge of the map come back in on the other side, instead of
+    /// the vacated tiles being emptied.
+    wrap: bool,
+    /// Whether events are moved too. Events that would go past an edge stop at it.
+    move_events: bool,
+}
+
+impl Default for ShiftOptions {
+    fn default() -> Self {
+        Self {
+            dx: 0,
+            dy: 0,
+            wrap: true,
+            move_events: true,
+        }
+    }
+}
+
+impl super::Editor {
+    /// Shows the shift map settings and the button that shifts the map.
+    pub(super) fn shift_map_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) {
+        let options = &mut self.shift_options;
+
+        egui::Grid::new(self.view.data_id.with("shift_map"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Right");
+                ui.add(egui::DragValue::new(&mut options.dx).suffix(" tiles"));
+                ui.end_row();
+
+                ui.label("Down");
+                ui.add(egui::DragValue::new(&mut options.dy).suffix(" tiles"));
+                ui.end_row();
+            });
+        ui.checkbox(&mut options.wrap, "Wrap tiles around")
+            .on_hover_text(
+                "Tiles that go past an edge of the map come back in on the other side. \
+                Otherwise, the tiles left behind are emptied.",
+            );
+        ui.checkbox(&mut options.move_events, "Move events")
+            .on_hover_text("Events that would go past an edge of the map stop at it");
+
+        if ui
+            .add_enabled(
+                options.dx != 0 || options.dy != 0,
+                egui::Button::new("Shift"),
+            )
+            .clicked()
+        {
+            let mut map = update_state.data.get_map(self.id);
+            self.shift_map(update_state, &mut map);
+            ui.close_menu();
+        }
+    }
+
+    /// Moves every tile layer of the map, and the events if enabled, by the offset in the shift
+    /// options as a single history entry.
+    fn shift_map(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        let ShiftOptions {
+            dx,
+            dy,
+            wrap,
+            move_events,
+        } = self.shift_options;
+        let (xsize, ysize, zsize) = (map.data.xsize(), map.data.ysize(), map.data.zsize());
+        let old_data = map.data.clone();
+
+        let mut delta = Vec::new();
+        for (z, y, x) in (0..zsize)
+            .cartesian_product(0..ysize)
+            .cartesian_product(0..xsize)
+            .map(|((z, y), x)| (z, y, x))
+        {
+            let src_x = x as i32 - dx;
+            let src_y = y as i32 - dy;
+            let id = if wrap {
+                old_data[(
+                    src_x.rem_euclid(xsize as i32) as usize,
+                    src_y.rem_euclid(ysize as i32) as usize,
+                    z,
+                )]
+            } else if (0..xsize as i32).contains(&src_x) && (0..ysize as i32).contains(&src_y) {
+                old_data[(src_x as usize, src_y as usize, z)]
+            } else {
+                0
+            };
+
+            if id != old_data[(x, y, z)] {
+                delta.push((x, y, z, old_data[(x, y, z)]));
+                map.data[(x, y, z)] = id;
+                self.dirty_passages.insert((x, y));
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, id, (x, y, z));
+            }
+        }
+
+        let mut entries = vec![super::HistoryEntry::Tiles { delta }];
+        if move_events {
+            for (id, event) in map.events.iter_mut() {
+                let x = (event.x + dx).clamp(0, xsize as i32 - 1);
+                let y = (event.y + dy).clamp(0, ysize as i32 - 1);
+                if (x, y) != (event.x, event.y) {
+                    entries.push(super::HistoryEntry::EventMoved {
+                        id,
+                        x: event.x,
+                        y: event.y,
+                    });
+                    event.x = x;
+                    event.y = y;
+                }
+            }
+        }
+
+        self.push_to_history(
+            update_state,
+            map,
+            super::HistoryEntry::Batch {
+                description: "map shift",
+                entries,
+            },
+        );
+    }
+}
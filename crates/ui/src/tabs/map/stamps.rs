@@ -0,0 +1,336 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use itertools::Itertools;
+use luminol_config::project::Stamp;
+// the texture loader decodes images with the graphics crate's version of `image`
+use luminol_graphics::image;
+
+use crate::components::{SelectedLayer, SelectedTile};
+
+/// The largest width or height of a stamp preview in pixels. Bigger stamps are scaled down.
+const PREVIEW_SIZE: usize = 64;
+
+/// Small images of the project's stamps, drawn on the CPU from the tileset and autotile images.
+#[derive(Default)]
+pub(super) struct StampPreviews {
+    /// The previews keyed by a hash of the tiles of the stamp, so that renaming a stamp keeps its
+    /// preview.
+    textures: HashMap<u64, egui::TextureHandle>,
+    /// The tileset the images were decoded for, the autotile images and the tileset image.
+    images: Option<(
+        usize,
+        Vec<Option<image::RgbaImage>>,
+        Option<image::RgbaImage>,
+    )>,
+}
+
+impl StampPreviews {
+    fn key(stamp: &Stamp) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (stamp.tileset_id, stamp.width, stamp.height, &stamp.layers).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The preview of a stamp, made the first time it is asked for.
+    fn get(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        stamp: &Stamp,
+    ) -> egui::TextureHandle {
+        let key = Self::key(stamp);
+        if let Some(texture) = self.textures.get(&key) {
+            return texture.clone();
+        }
+
+        if self.images.as_ref().map(|(id, ..)| *id) != Some(stamp.tileset_id) {
+            self.images = Some(Self::decode_images(update_state, stamp.tileset_id));
+        }
+        let (_, autotiles, tileset) = self.images.as_ref().unwrap();
+
+        // Every tile is 32 pixels wide, but only every `step`th pixel is kept for big stamps
+        let step = (stamp.width.max(stamp.height) * 32).div_ceil(PREVIEW_SIZE);
+        let (width, height) = (stamp.width * 32 / step, stamp.height * 32 / step);
+        let mut pixels = Vec::with_capacity(width * height);
+        for (y, x) in (0..height).cartesian_product(0..width) {
+            let (px, py) = (x * step, y * step);
+            let i = px / 32 + py / 32 * stamp.width;
+            let (px, py) = ((px % 32) as u32, (py % 32) as u32);
+
+            // blend the layers from the bottom up
+            let mut color = [0.; 4];
+            for layer in &stamp.layers {
+                let id = layer.get(i).copied().unwrap_or_default();
+                let pixel = match id {
+                    0..=47 => None,
+                    48..=383 => autotiles[(id / 48 - 1) as usize]
+                        .as_ref()
+                        // Autotile images start with the tile they're shown as in the tilepicker
+                        .and_then(|image| image.get_pixel_checked(px, py)),
+                    _ => tileset.as_ref().and_then(|image| {
+                        let id = (id - 384) as u32;
+                        image.get_pixel_checked(id % 8 * 32 + px, id / 8 * 32 + py)
+                    }),
+                };
+                let Some(pixel) = pixel else {
+                    continue;
+                };
+                let tile = pixel.0.map(|c| c as f32 / 255.);
+                let below = color[3];
+                let alpha = tile[3] + below * (1. - tile[3]);
+                if alpha > 0. {
+                    for (c, t) in color.iter_mut().zip(tile).take(3) {
+                        *c = (t * tile[3] + *c * below * (1. - tile[3])) / alpha;
+                    }
+                }
+                color[3] = alpha;
+            }
+            pixels.push(egui::Color32::from_rgba_unmultiplied(
+                (color[0] * 255.) as u8,
+                (color[1] * 255.) as u8,
+                (color[2] * 255.) as u8,
+                (color[3] * 255.) as u8,
+            ));
+        }
+
+        let texture = update_state.ctx.load_texture(
+            format!("luminol_stamp_preview_{key}"),
+            egui::ColorImage {
+                size: [width, height],
+                pixels,
+            },
+            egui::TextureOptions::NEAREST,
+        );
+        self.textures.insert(key, texture.clone());
+        texture
+    }
+
+    fn decode_images(
+        update_state: &luminol_core::UpdateState<'_>,
+        tileset_id: usize,
+    ) -> (
+        usize,
+        Vec<Option<image::RgbaImage>>,
+        Option<image::RgbaImage>,
+    ) {
+        let tilesets = update_state.data.tilesets();
+        let Some(tileset) = tilesets.data.get(tileset_id) else {
+            return (tileset_id, vec![None; 7], None);
+        };
+        let decode = |directory: &str, name: &str| {
+            update_state
+                .graphics
+                .texture_loader
+                .decode_image(
+                    update_state.filesystem,
+                    camino::Utf8Path::new(directory).join(name),
+                )
+                .ok()
+        };
+
+        let autotiles = (0..7)
+            .map(|i| {
+                (tileset.autotile_names.get(i))
+                    .filter(|name| !name.is_empty())
+                    .and_then(|name| decode("Graphics/Autotiles", name.as_str()))
+            })
+            .collect();
+        let tileset_image = tileset
+            .tileset_name
+            .as_ref()
+            .and_then(|name| decode("Graphics/Tilesets", name.as_str()));
+        (tileset_id, autotiles, tileset_image)
+    }
+}
+
+impl super::Editor {
+    /// Displays the project's stamps for the tileset of the map, and a menu to save the tiles
+    /// selected on the map or in the tilepicker as a new one. Clicking a stamp pastes it with the
+    /// select tool.
+    pub(super) fn stamps_ui(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        ui: &mut egui::Ui,
+    ) {
+        let map = update_state.data.get_map(self.id);
+        let tileset_id = map.tileset_id;
+        let tile_layer = match self.view.selected_layer {
+            SelectedLayer::Tiles(tile_layer) => Some(tile_layer),
+            SelectedLayer::Events => None,
+        };
+        // The tile selection shows where the tiles go while pasting, so it can't be saved then
+        let selected_tiles = |all_layers| {
+            tile_layer
+                .filter(|_| self.paste.is_none())
+                .and_then(|tile_layer| self.selected_tiles(&map, tile_layer, all_layers))
+        };
+        let map_layer = selected_tiles(false);
+        let map_all_layers = selected_tiles(true);
+        drop(map);
+        let tilepicker_tiles = self.tilepicker_stamp_tiles();
+
+        let stamps = &update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded")
+            .project
+            .stamps;
+        let previews = stamps
+            .iter()
+            .map(|stamp| {
+                (stamp.tileset_id == tileset_id)
+                    .then(|| self.stamp_previews.get(update_state, stamp))
+            })
+            .collect_vec();
+
+        let stamps = &mut update_state
+            .project_config
+            .as_mut()
+            .expect("project not loaded")
+            .project
+            .stamps;
+
+        let mut modified = false;
+        let mut deleted = None;
+        let mut pasted = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new("Stamps").underline());
+
+            for (i, (stamp, preview)) in stamps.iter_mut().zip(previews).enumerate() {
+                let Some(preview) = preview else {
+                    continue;
+                };
+                let image = egui::Image::from_texture(&preview).max_size(egui::vec2(32., 32.));
+                let response = ui
+                    .add(egui::Button::image_and_text(image, &stamp.name))
+                    .on_hover_text(format!(
+                        "{}x{} tiles of {}. Right-click to rename or delete",
+                        stamp.width,
+                        stamp.height,
+                        if stamp.all_layers {
+                            "every layer"
+                        } else {
+                            "one layer"
+                        }
+                    ));
+                if response.clicked() {
+                    pasted = Some(luminol_core::TileClipboard::from_stamp(stamp));
+                }
+                response.context_menu(|ui| {
+                    modified |= ui.text_edit_singleline(&mut stamp.name).changed();
+                    if ui.button("Delete").clicked() {
+                        deleted = Some(i);
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            ui.menu_button("+ Save as stamp", |ui| {
+                let name = format!("Stamp {}", stamps.len() + 1);
+                let mut saved = None;
+                if ui
+                    .add_enabled(map_layer.is_some(), egui::Button::new("Map selection"))
+                    .on_hover_text("The tiles selected with the select tool in this layer")
+                    .clicked()
+                {
+                    saved = map_layer.map(|tiles| tiles.to_stamp(name.clone()));
+                }
+                if ui
+                    .add_enabled(
+                        map_all_layers.is_some(),
+                        egui::Button::new("Map selection, all layers"),
+                    )
+                    .on_hover_text("The tiles selected with the select tool in every layer")
+                    .clicked()
+                {
+                    saved = map_all_layers.map(|tiles| tiles.to_stamp(name.clone()));
+                }
+                if ui
+                    .button("Tilepicker selection")
+                    .on_hover_text("The tiles selected in the tilepicker, as a single layer")
+                    .clicked()
+                {
+                    saved = Some(Stamp {
+                        name,
+                        tileset_id,
+                        width: tilepicker_tiles.0,
+                        height: tilepicker_tiles.1,
+                        layers: vec![tilepicker_tiles.2],
+                        all_layers: false,
+                    });
+                }
+
+                if let Some(stamp) = saved {
+                    stamps.push(stamp);
+                    modified = true;
+                    ui.close_menu();
+                }
+            });
+        });
+
+        if let Some(i) = deleted {
+            stamps.remove(i);
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+        }
+
+        if let Some(clipboard) = pasted {
+            update_state.toolbar.tile_clipboard = Some(std::sync::Arc::new(clipboard));
+            update_state.toolbar.pencil = luminol_core::Pencil::Select;
+            if tile_layer.is_none() {
+                self.view.selected_layer = SelectedLayer::Tiles(0);
+            }
+            self.start_pasting(update_state.toolbar);
+        }
+    }
+
+    /// The width, height and tile IDs in row-major order of the tilepicker selection. Autotiles
+    /// are saved as their first ID, like the tilepicker draws them.
+    fn tilepicker_stamp_tiles(&self) -> (usize, usize, Vec<i16>) {
+        let tilepicker = &self.tilepicker;
+        let width = tilepicker.selected_tiles_right - tilepicker.selected_tiles_left + 1;
+        let height = tilepicker.selected_tiles_bottom - tilepicker.selected_tiles_top + 1;
+        let tiles = (0..height)
+            .cartesian_product(0..width)
+            .map(|(y, x)| {
+                let (x, y) = (
+                    tilepicker.selected_tiles_left + x,
+                    tilepicker.selected_tiles_top + y,
+                );
+                match y {
+                    ..=0 => SelectedTile::Autotile(x).to_id(),
+                    _ => SelectedTile::Tile(x + (y - 1) * 8 + 384).to_id(),
+                }
+            })
+            .collect();
+        (width as usize, height as usize, tiles)
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Structure;
+
+use super::SelectedLayer;
+
+fn pending_position_id(map_id: usize) -> egui::Id {
+    egui::Id::new("luminol_map_tab_pending_position").with(map_id)
+}
+
+/// Makes the tab of the map with the given ID center the view on a tile the next time the tab is
+/// shown.
+pub fn center_on_later(ctx: &egui::Context, map_id: usize, tile: egui::Pos2) {
+    ctx.data_mut(|d| d.insert_temp(pending_position_id(map_id), tile));
+}
+
+/// Opens the map new games start on and centers the view on the start position.
+pub fn go_to_start_position(update_state: &mut luminol_core::UpdateState<'_>) {
+    let system = update_state.data.system();
+    let (map_id, x, y) = (system.start_map_id, system.start_x, system.start_y);
+    drop(system);
+
+    if !update_state.data.map_infos().data.contains_key(&map_id) {
+        luminol_core::warn!(
+            update_state.toasts,
+            format!("The start position is on map {map_id:0>3}, which doesn't exist")
+        );
+        return;
+    }
+
+    center_on_later(update_state.ctx, map_id, egui::pos2(x as f32, y as f32));
+    crate::components::open_editor(Structure::Map(map_id), update_state);
+}
+
+impl super::Editor {
+    /// Centers the view on the tile passed to [`center_on_later`], if any.
+    pub(super) fn center_on_pending_position(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) {
+        if let Some(tile) = ctx.data_mut(|d| d.remove_temp(pending_position_id(self.id))) {
+            self.view
+                .center_on(ctx, &update_state.data.get_map(self.id), tile);
+        }
+    }
+
+    /// Makes new games start on a tile of this map.
+    pub(super) fn set_start_position(
+        &self,
+        update_state: &luminol_core::UpdateState<'_>,
+        x: i32,
+        y: i32,
+    ) {
+        let mut system = update_state.data.system();
+        if system.start_map_id == self.id && system.start_x == x && system.start_y == y {
+            return;
+        }
+        system.start_map_id = self.id;
+        system.start_x = x;
+        system.start_y = y;
+        system.modified = true;
+        update_state.modified.set(true);
+    }
+
+    /// Handles dragging the start position marker on the events layer. The marker follows the
+    /// mouse and the start position is moved where it's dropped.
+    pub(super) fn handle_start_position_drag(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        response: &egui::Response,
+    ) {
+        if let Some(tile) = self.start_position_drag {
+            if response.dragged_by(egui::PointerButton::Primary) {
+                if let Some(hover_tile) = self.view.hover_tile {
+                    self.start_position_drag = Some(hover_tile);
+                }
+            } else {
+                self.start_position_drag = None;
+                self.set_start_position(update_state, tile.x as i32, tile.y as i32);
+            }
+            return;
+        }
+
+        // Events are dragged instead when there's one on the start position
+        if matches!(self.view.selected_layer, SelectedLayer::Events)
+            && self.view.display_start_position
+            && self.view.map.event_enabled
+            && self.event_drag_info.is_none()
+            && !self.view.selected_event_is_hovered
+            && response.drag_started_by(egui::PointerButton::Primary)
+            && ui.input(|i| !i.modifiers.command)
+            && self.view.start_position.is_some()
+            && self.view.start_position == self.view.hover_tile
+        {
+            self.start_position_drag = self.view.start_position;
+        }
+    }
+}
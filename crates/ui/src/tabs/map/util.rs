@@ -22,7 +22,130 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-impl super::Tab {
+use itertools::Itertools;
+
+/// Returns which of the 8 neighbors of `position` are connected to it, as a bitfield with the
+/// bits in this order, from the most significant:
+///
+/// ```text
+/// 765
+/// 0 4
+/// 123
+/// ```
+///
+/// Out-of-bounds neighbors are always connected; the others are if `is_connected` returns true for
+/// their tile ID.
+fn neighbor_bitfield(
+    map: &luminol_data::rpg::Map,
+    position: (usize, usize, usize),
+    is_connected: impl Fn(i16) -> bool,
+) -> u8 {
+    let x_array: [i8; 8] = [-1, 0, 1, 1, 1, 0, -1, -1];
+    let y_array: [i8; 8] = [-1, -1, -1, 0, 1, 1, 1, 0];
+
+    let mut bitfield = 0u8;
+
+    // Loop through the 8 neighbors of this position
+    for (x, y) in x_array.into_iter().zip(y_array.into_iter()) {
+        bitfield <<= 1;
+        // Out-of-bounds tiles always count as valid neighbors
+        let is_out_of_bounds = ((x == -1 && position.0 == 0)
+            || (x == 1 && position.0 + 1 == map.data.xsize()))
+            || ((y == -1 && position.1 == 0) || (y == 1 && position.1 + 1 == map.data.ysize()));
+        let is_same_kind = !is_out_of_bounds
+            && is_connected(
+                map.data[(
+                    if x == -1 {
+                        position.0 - 1
+                    } else {
+                        position.0 + x as usize
+                    },
+                    if y == -1 {
+                        position.1 - 1
+                    } else {
+                        position.1 + y as usize
+                    },
+                    position.2,
+                )],
+            );
+
+        if is_out_of_bounds || is_same_kind {
+            bitfield |= 1
+        }
+    }
+
+    bitfield
+}
+
+/// Returns which of the 47 autotile variants (0 to 46) connects to the neighbors in `bitfield`,
+/// as returned by [`neighbor_bitfield`].
+fn autotile_variant(bitfield: u8) -> u8 {
+    // Check how many edges have valid neighbors
+    match (bitfield & 0b01010101).count_ones() {
+        4 => {
+            // If the autotile is surrounded on all 4 edges,
+            // then the autotile variant is one of the first 16,
+            // depending on which corners are surrounded
+            let tl = (bitfield & 0b10000000 == 0) as u8;
+            let tr = (bitfield & 0b00100000 == 0) as u8;
+            let br = (bitfield & 0b00001000 == 0) as u8;
+            let bl = (bitfield & 0b00000010 == 0) as u8;
+            tl | (tr << 1) | (br << 2) | (bl << 3)
+        }
+
+        3 => {
+            // Rotate the bitfield 90 degrees counterclockwise until
+            // the one edge that is not surrounded is at the left
+            let mut bitfield = bitfield;
+            let mut i = 16u8;
+            while bitfield & 0b00000001 != 0 {
+                bitfield = bitfield.rotate_left(2);
+                i += 4;
+            }
+            // Now, the variant is one of the next 16
+            let tr = (bitfield & 0b00100000 == 0) as u8;
+            let br = (bitfield & 0b00001000 == 0) as u8;
+            i + (tr | (br << 1))
+        }
+
+        // Top and bottom edges
+        2 if bitfield & 0b01000100 == 0b01000100 => 32,
+
+        // Left and right edges
+        2 if bitfield & 0b00010001 == 0b00010001 => 33,
+
+        2 => {
+            // Rotate the bitfield 90 degrees counterclockwise until
+            // the two edges that are surrounded are at the right and bottom
+            let mut bitfield = bitfield;
+            let mut i = 34u8;
+            while bitfield & 0b00010100 != 0b00010100 {
+                bitfield = bitfield.rotate_left(2);
+                i += 2;
+            }
+            let br = (bitfield & 0b00001000 == 0) as u8;
+            i + br
+        }
+
+        1 => {
+            // Rotate the bitfield 90 degrees clockwise until
+            // the edge is at the bottom
+            let mut bitfield = bitfield;
+            let mut i = 42u8;
+            while bitfield & 0b00000100 == 0 {
+                bitfield = bitfield.rotate_right(2);
+                i += 1;
+            }
+            i
+        }
+
+        0 => 46,
+
+        _ => unreachable!(),
+    }
+}
+
+impl super::Editor {
     pub(super) fn recompute_autotile(
         &self,
         map: &luminol_data::rpg::Map,
@@ -37,140 +160,253 @@ impl super::Tab {
             return 0;
         }
 
-        let x_array: [i8; 8] = [-1, 0, 1, 1, 1, 0, -1, -1];
-        let y_array: [i8; 8] = [-1, -1, -1, 0, 1, 1, 1, 0];
-
-        /*
-         * 765
-         * 0 4
-         * 123
-         */
-        let mut bitfield = 0u8;
-
-        // Loop through the 8 neighbors of this position
-        for (x, y) in x_array.into_iter().zip(y_array.into_iter()) {
-            bitfield <<= 1;
-            // Out-of-bounds tiles always count as valid neighbors
-            let is_out_of_bounds = ((x == -1 && position.0 == 0)
-                || (x == 1 && position.0 + 1 == map.data.xsize()))
-                || ((y == -1 && position.1 == 0) || (y == 1 && position.1 + 1 == map.data.ysize()));
-            // Otherwise, we only consider neighbors that are autotiles of the same type
-            let is_same_autotile = !is_out_of_bounds
-                && map.data[(
-                    if x == -1 {
-                        position.0 - 1
-                    } else {
-                        position.0 + x as usize
-                    },
-                    if y == -1 {
-                        position.1 - 1
-                    } else {
-                        position.1 + y as usize
-                    },
-                    position.2,
-                )] / 48
-                    == autotile;
+        // We only consider neighbors that are autotiles of the same type
+        let bitfield = neighbor_bitfield(map, position, |id| id / 48 == autotile);
+        autotile * 48 + autotile_variant(bitfield) as i16
+    }
+
+    /// Returns the tile of `smart_tile` that connects to the neighbors of `position`, or the tile
+    /// that's already there if it's not part of `smart_tile`.
+    pub(super) fn recompute_smart_tile(
+        map: &luminol_data::rpg::Map,
+        smart_tile: &luminol_config::project::SmartTile,
+        position: (usize, usize, usize),
+    ) -> i16 {
+        let id = map.data[position];
+        if !smart_tile.contains(id) {
+            return id;
+        }
 
-            if is_out_of_bounds || is_same_autotile {
-                bitfield |= 1
+        let bitfield = neighbor_bitfield(map, position, |id| smart_tile.contains(id));
+        match smart_tile.layout {
+            luminol_config::project::SmartTileLayout::Full47 => {
+                smart_tile.origin + autotile_variant(bitfield) as i16
+            }
+            luminol_config::project::SmartTileLayout::Block3x3 => {
+                // Pick the column and row from whichever edges aren't connected, using the middle
+                // one if both or neither of the opposite edges are
+                let left = bitfield & 0b00000001 != 0;
+                let right = bitfield & 0b00010000 != 0;
+                let top = bitfield & 0b01000000 != 0;
+                let bottom = bitfield & 0b00000100 != 0;
+                let x = match (left, right) {
+                    (false, true) => 0,
+                    (true, false) => 2,
+                    _ => 1,
+                };
+                let y = match (top, bottom) {
+                    (false, true) => 0,
+                    (true, false) => 2,
+                    _ => 1,
+                };
+                smart_tile.origin + x + y * 8
             }
         }
+    }
 
-        // Check how many edges have valid neighbors
-        autotile * 48
-            + match (bitfield & 0b01010101).count_ones() {
-                4 => {
-                    // If the autotile is surrounded on all 4 edges,
-                    // then the autotile variant is one of the first 16,
-                    // depending on which corners are surrounded
-                    let tl = (bitfield & 0b10000000 == 0) as u8;
-                    let tr = (bitfield & 0b00100000 == 0) as u8;
-                    let br = (bitfield & 0b00001000 == 0) as u8;
-                    let bl = (bitfield & 0b00000010 == 0) as u8;
-                    tl | (tr << 1) | (br << 2) | (bl << 3)
-                }
+    /// Returns the layer the brush draws on: the selected tile layer, or the shadow layer if the
+    /// shadow pen is active and the map has one.
+    pub(super) fn brush_layer(
+        &self,
+        pencil: luminol_core::Pencil,
+        map: &luminol_data::rpg::Map,
+    ) -> Option<usize> {
+        match self.view.selected_layer {
+            super::SelectedLayer::Tiles(_)
+                if pencil == luminol_core::Pencil::Shadow
+                    && map.data.zsize() > luminol_graphics::primitives::shadows::LAYER =>
+            {
+                Some(luminol_graphics::primitives::shadows::LAYER)
+            }
+            super::SelectedLayer::Tiles(tile_layer) => Some(tile_layer),
+            super::SelectedLayer::Events => None,
+        }
+    }
 
-                3 => {
-                    // Rotate the bitfield 90 degrees counterclockwise until
-                    // the one edge that is not surrounded is at the left
-                    let mut bitfield = bitfield;
-                    let mut i = 16u8;
-                    while bitfield & 0b00000001 != 0 {
-                        bitfield = bitfield.rotate_left(2);
-                        i += 4;
-                    }
-                    // Now, the variant is one of the next 16
-                    let tr = (bitfield & 0b00100000 == 0) as u8;
-                    let br = (bitfield & 0b00001000 == 0) as u8;
-                    i + (tr | (br << 1))
+    /// Handles the layer shortcuts: 1 to 9 select a tile layer and E selects the events, while the
+    /// same keys with Alt held toggle the visibility of the layer instead.
+    pub(super) fn handle_layer_shortcuts(&mut self, ui: &egui::Ui, is_ace: bool) {
+        let has_shadows = self.view.map.tiles.has_shadows();
+        let layer_count = self.view.map.tiles.enabled_layers.len();
+        for shortcut in ui.input_mut(|i| layer_shortcuts(i, layer_count)) {
+            match shortcut {
+                LayerShortcut::Toggle(super::SelectedLayer::Tiles(index)) => {
+                    let enabled = &mut self.view.map.tiles.enabled_layers[index];
+                    *enabled = !*enabled;
                 }
-
-                // Top and bottom edges
-                2 if bitfield & 0b01000100 == 0b01000100 => 32,
-
-                // Left and right edges
-                2 if bitfield & 0b00010001 == 0b00010001 => 33,
-
-                2 => {
-                    // Rotate the bitfield 90 degrees counterclockwise until
-                    // the two edges that are surrounded are at the right and bottom
-                    let mut bitfield = bitfield;
-                    let mut i = 34u8;
-                    while bitfield & 0b00010100 != 0b00010100 {
-                        bitfield = bitfield.rotate_left(2);
-                        i += 2;
-                    }
-                    let br = (bitfield & 0b00001000 == 0) as u8;
-                    i + br
+                LayerShortcut::Toggle(super::SelectedLayer::Events) => {
+                    self.view.map.event_enabled = !self.view.map.event_enabled;
                 }
+                // The shadow layer is only selectable for its region IDs in VX Ace
+                LayerShortcut::Select(super::SelectedLayer::Tiles(index))
+                    if has_shadows
+                        && index == luminol_graphics::primitives::shadows::LAYER
+                        && !is_ace => {}
+                LayerShortcut::Select(layer) => self.view.selected_layer = layer,
+            }
+        }
+    }
 
-                1 => {
-                    // Rotate the bitfield 90 degrees clockwise until
-                    // the edge is at the bottom
-                    let mut bitfield = bitfield;
-                    let mut i = 42u8;
-                    while bitfield & 0b00000100 == 0 {
-                        bitfield = bitfield.rotate_right(2);
-                        i += 1;
-                    }
-                    i
-                }
+    /// Selects the tile at a position of the selected layer in the tilepicker, or its region ID in
+    /// the region picker. Autotiles are picked as a whole rather than as the variant drawn there.
+    pub(super) fn pick_tile(&mut self, map: &luminol_data::rpg::Map, x: usize, y: usize) {
+        let super::SelectedLayer::Tiles(layer) = self.view.selected_layer else {
+            return;
+        };
+        let id = map.data[(x, y, layer)];
+        if self.view.is_region_layer_selected() {
+            self.region_picker.selected_region =
+                luminol_graphics::primitives::shadows::region_id(id);
+        } else {
+            self.tilepicker
+                .select_tile(crate::components::SelectedTile::from_id(id));
+        }
+    }
 
-                0 => 46,
+    /// Selects the rectangle of tiles between two positions of the selected layer in the
+    /// tilepicker. The tilepicker can only select tiles that are laid out the same way in the
+    /// tileset, so if these aren't, only the tile at `min` is picked up.
+    pub(super) fn pick_tiles(
+        &mut self,
+        toasts: &mut luminol_core::Toasts,
+        map: &luminol_data::rpg::Map,
+        min: (usize, usize),
+        max: (usize, usize),
+    ) {
+        let super::SelectedLayer::Tiles(layer) = self.view.selected_layer else {
+            return;
+        };
+        if min == max || self.view.is_region_layer_selected() {
+            self.pick_tile(map, min.0, min.1);
+            return;
+        }
 
-                _ => unreachable!(),
-            } as i16
+        let position_at = |x: usize, y: usize| {
+            crate::components::Tilepicker::tile_position(crate::components::SelectedTile::from_id(
+                map.data[(x, y, layer)],
+            ))
+        };
+        let origin = position_at(min.0, min.1);
+        let is_laid_out_like_tileset = (min.1..=max.1)
+            .flat_map(|y| (min.0..=max.0).map(move |x| (x, y)))
+            .all(|(x, y)| {
+                position_at(x, y) == (origin.0 + (x - min.0) as i16, origin.1 + (y - min.1) as i16)
+            });
+
+        if is_laid_out_like_tileset {
+            self.tilepicker.select_rect(
+                origin,
+                (
+                    origin.0 + (max.0 - min.0) as i16,
+                    origin.1 + (max.1 - min.1) as i16,
+                ),
+            );
+        } else {
+            luminol_core::warn!(
+                *toasts,
+                "Only picked up the top-left tile, since the tiles aren't laid out like this in \
+                the tileset"
+            );
+            self.pick_tile(map, min.0, min.1);
+        }
     }
 
-    pub(super) fn set_tile(
+    /// Marks a tile on the selected layer as changed so that it's written to the tilemap at the end
+    /// of the frame.
+    pub(super) fn mark_tile_dirty(&mut self, x: usize, y: usize) {
+        self.dirty_tiles = Some(match self.dirty_tiles {
+            Some(dirty_tiles) => super::DirtyTiles {
+                min_x: dirty_tiles.min_x.min(x),
+                min_y: dirty_tiles.min_y.min(y),
+                max_x: dirty_tiles.max_x.max(x),
+                max_y: dirty_tiles.max_y.max(y),
+            },
+            None => super::DirtyTiles {
+                min_x: x,
+                min_y: y,
+                max_x: x,
+                max_y: y,
+            },
+        });
+    }
+
+    /// Returns true if the brush density leaves out the tile at this position.
+    fn is_skipped_by_density(&self, position: (usize, usize, usize)) -> bool {
+        if self.brush_density == 1. {
+            return false;
+        }
+        if self.brush_density == 0. {
+            return true;
+        }
+
+        // Pick a pseudorandom normal f32 uniformly in the interval [0, 1)
+        let mut preimage = [0u8; 40];
+        preimage[0..16].copy_from_slice(&self.brush_seed);
+        preimage[16..24].copy_from_slice(&(position.0 as u64).to_le_bytes());
+        preimage[24..32].copy_from_slice(&(position.1 as u64).to_le_bytes());
+        preimage[32..40].copy_from_slice(&(position.2 as u64).to_le_bytes());
+        let image = (murmur3::murmur3_32(&mut std::io::Cursor::new(preimage), 1729).unwrap()
+            & 16777215) as f32
+            / 16777216f32;
+
+        // Set the tile only if that's less than the brush density
+        image >= self.brush_density
+    }
+
+    /// Returns `position` and the positions the mirror painting mode mirrors it to, without
+    /// duplicates. Mirrored positions past the edges of the map are left out unless the edge loops.
+    fn mirrored_positions(
         &self,
+        map: &luminol_data::rpg::Map,
+        position: (usize, usize, usize),
+    ) -> Vec<(usize, usize, usize)> {
+        mirror_positions(
+            self.symmetry,
+            self.view.symmetry_center(map),
+            self.view.map_loop(),
+            (map.data.xsize(), map.data.ysize()),
+            position,
+        )
+    }
+
+    /// Draws a tile at a position, and at the mirrored positions if the mirror painting mode is
+    /// on.
+    pub(super) fn set_tile(
+        &mut self,
         map: &mut luminol_data::rpg::Map,
         tile: crate::components::SelectedTile,
         position: (usize, usize, usize),
     ) {
-        if self.brush_density != 1. {
-            if self.brush_density == 0. {
-                return;
-            }
+        if self.is_skipped_by_density(position) {
+            return;
+        }
 
-            // Pick a pseudorandom normal f32 uniformly in the interval [0, 1)
-            let mut preimage = [0u8; 40];
-            preimage[0..16].copy_from_slice(&self.brush_seed);
-            preimage[16..24].copy_from_slice(&(position.0 as u64).to_le_bytes());
-            preimage[24..32].copy_from_slice(&(position.1 as u64).to_le_bytes());
-            preimage[32..40].copy_from_slice(&(position.2 as u64).to_le_bytes());
-            let image = (murmur3::murmur3_32(&mut std::io::Cursor::new(preimage), 1729).unwrap()
-                & 16777215) as f32
-                / 16777216f32;
-
-            // Set the tile only if that's less than the brush density
-            if image >= self.brush_density {
-                return;
-            }
+        for position in self.mirrored_positions(map, position) {
+            self.place_tile(map, tile, position);
+        }
+    }
+
+    fn place_tile(
+        &mut self,
+        map: &mut luminol_data::rpg::Map,
+        tile: crate::components::SelectedTile,
+        position: (usize, usize, usize),
+    ) {
+        // The region layer takes the region ID from the region picker instead of the tilepicker,
+        // and has no autotiles
+        if self.view.is_region_layer_selected() {
+            map.data[position] = luminol_graphics::primitives::shadows::with_region_id(
+                map.data[position],
+                self.region_picker.selected_region,
+            );
+            self.mark_tile_dirty(position.0, position.1);
+            return;
         }
 
         map.data[position] = tile.to_id();
 
+        // Changing a tile can change the shape of the autotiles around it, so the whole 3x3
+        // neighborhood has to be recomputed
         for y in -1i8..=1i8 {
             for x in -1i8..=1i8 {
                 // Don't check tiles that are out of bounds
@@ -195,15 +431,63 @@ impl super::Tab {
                 );
                 let tile_id = self.recompute_autotile(map, position);
                 map.data[position] = tile_id;
+                self.mark_tile_dirty(position.0, position.1);
             }
         }
     }
 
-    pub(super) fn add_event(
+    /// Draws `smart_tile` at a position and changes it and the neighboring tiles of the same smart
+    /// tile to the variants that connect to each other. Also draws it at the mirrored positions if
+    /// the mirror painting mode is on.
+    pub(super) fn set_smart_tile(
         &mut self,
-        update_state: &luminol_core::UpdateState<'_>,
         map: &mut luminol_data::rpg::Map,
-    ) -> Option<usize> {
+        smart_tile: &luminol_config::project::SmartTile,
+        position: (usize, usize, usize),
+    ) {
+        if self.is_skipped_by_density(position) {
+            return;
+        }
+
+        for position in self.mirrored_positions(map, position) {
+            self.place_smart_tile(map, smart_tile, position);
+        }
+    }
+
+    fn place_smart_tile(
+        &mut self,
+        map: &mut luminol_data::rpg::Map,
+        smart_tile: &luminol_config::project::SmartTile,
+        position: (usize, usize, usize),
+    ) {
+        // Any tile of the block will do, the right variant is picked below
+        if !smart_tile.contains(map.data[position]) {
+            map.data[position] = smart_tile.origin;
+        }
+
+        for y in -1i8..=1i8 {
+            for x in -1i8..=1i8 {
+                // Don't check tiles that are out of bounds
+                if ((x == -1 && position.0 == 0) || (x == 1 && position.0 + 1 == map.data.xsize()))
+                    || ((y == -1 && position.1 == 0)
+                        || (y == 1 && position.1 + 1 == map.data.ysize()))
+                {
+                    continue;
+                }
+                let position = (
+                    position.0.saturating_add_signed(x as isize),
+                    position.1.saturating_add_signed(y as isize),
+                    position.2,
+                );
+                map.data[position] = Self::recompute_smart_tile(map, smart_tile, position);
+                self.mark_tile_dirty(position.0, position.1);
+            }
+        }
+    }
+
+    /// Finds an ID for a new event at a position. Returns `None` if there already is an event
+    /// there or the map has no IDs left.
+    pub(super) fn new_event_id(map: &luminol_data::rpg::Map, x: i32, y: i32) -> Option<usize> {
         let mut first_vacant_id = 1;
         let mut max_event_id = 0;
 
@@ -213,29 +497,62 @@ impl super::Tab {
             }
             max_event_id = event.id;
 
-            if event.x == self.view.cursor_pos.x as i32 && event.y == self.view.cursor_pos.y as i32
-            {
+            if event.x == x && event.y == y {
                 return None;
             }
         }
 
         // Try first to allocate the event number directly after the current highest one.
         // However, valid event number range in RPG Maker XP and VX is 1-999.
-        let new_event_id = if max_event_id < 999 {
-            max_event_id + 1
+        if max_event_id < 999 {
+            Some(max_event_id + 1)
         }
         // Otherwise, we'll try to use a non-allocated event ID that isn't zero.
         else if first_vacant_id <= 999 {
-            first_vacant_id
+            Some(first_vacant_id)
         } else {
-            return None;
-        };
+            None
+        }
+    }
 
-        let event = luminol_data::rpg::Event::new(
-            self.view.cursor_pos.x as i32,
-            self.view.cursor_pos.y as i32,
-            new_event_id,
-        );
+    /// Returns true if the event layer was clicked twice on the same tile within `max_delay`
+    /// seconds. Unlike egui's double-clicks, the mouse is free to move during and between the
+    /// clicks as long as they're on the same tile, so taps on a trackpad that come out as short
+    /// drags still count.
+    pub(super) fn is_tile_double_clicked(
+        &mut self,
+        response: &egui::Response,
+        max_delay: f64,
+    ) -> bool {
+        if !(response.clicked_by(egui::PointerButton::Primary)
+            || response.drag_stopped_by(egui::PointerButton::Primary))
+        {
+            return false;
+        }
+
+        let time = response.ctx.input(|i| i.time);
+        let tile = self.view.cursor_pos;
+        match self.last_event_click.take() {
+            Some((last_time, last_tile)) if last_tile == tile && time - last_time <= max_delay => {
+                true
+            }
+            _ => {
+                self.last_event_click = Some((time, tile));
+                false
+            }
+        }
+    }
+
+    /// Adds an event at a tile and opens its event editor.
+    pub(super) fn add_event(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        tile: egui::Pos2,
+    ) -> Option<usize> {
+        let (x, y) = (tile.x as i32, tile.y as i32);
+        let new_event_id = Self::new_event_id(map, x, y)?;
+        let event = luminol_data::rpg::Event::new(x, y, new_event_id);
 
         self.event_windows
             .add_window(crate::windows::event_edit::Window::new(
@@ -249,6 +566,66 @@ impl super::Tab {
         Some(new_event_id)
     }
 
+    /// Removes an event from the map, saving it to the history so that it can be restored.
+    pub(super) fn delete_event(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        id: usize,
+    ) {
+        let event = map.events.remove(id);
+        let sprite = self.view.map.events.try_remove(id).ok();
+        self.push_to_history(
+            update_state,
+            map,
+            super::HistoryEntry::EventDeleted { event, sprite },
+        );
+    }
+
+    /// Makes the IDs of this map's events contiguous and adds the change to the history.
+    /// Returns how many events were renumbered.
+    pub(super) fn compact_event_ids(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) -> usize {
+        let renumbering = map.event_id_compaction();
+        if renumbering.is_empty() {
+            return 0;
+        }
+        self.renumber_events(map, &renumbering);
+        let count = renumbering.len();
+        self.push_to_history(
+            update_state,
+            map,
+            super::HistoryEntry::EventsRenumbered(
+                renumbering
+                    .into_iter()
+                    .map(|(old, new)| (new, old))
+                    .collect(),
+            ),
+        );
+        count
+    }
+
+    /// Gives this map's events new IDs, along with their sprites and the selection.
+    pub(super) fn renumber_events(
+        &mut self,
+        map: &mut luminol_data::rpg::Map,
+        renumbering: &[(usize, usize)],
+    ) {
+        map.renumber_events(renumbering);
+        self.view.map.events.renumber(renumbering);
+        if let Some(selected_event_id) = &mut self.view.selected_event_id {
+            if let Some(&(_, new)) = renumbering
+                .iter()
+                .find(|&&(old, _)| old == *selected_event_id)
+            {
+                *selected_event_id = new;
+            }
+        }
+    }
+
     pub(super) fn push_to_history(
         &mut self,
         update_state: &luminol_core::UpdateState<'_>,
@@ -257,10 +634,253 @@ impl super::Tab {
     ) {
         update_state.modified.set(true);
         map.modified = true;
+        self.minimap_dirty = true;
         self.redo_history.clear();
-        if self.history.len() == super::HISTORY_SIZE {
-            self.history.pop_front();
-        }
         self.history.push_back(entry);
+        super::history::truncate(
+            &mut self.history,
+            update_state.global_config.map_history_size,
+        );
+    }
+}
+
+/// What a layer shortcut does to a layer.
+#[derive(Debug, PartialEq, Eq)]
+enum LayerShortcut {
+    Select(super::SelectedLayer),
+    Toggle(super::SelectedLayer),
+}
+
+/// Consumes the layer shortcuts pressed this frame, for a map with `layer_count` tile layers.
+fn layer_shortcuts(i: &mut egui::InputState, layer_count: usize) -> Vec<LayerShortcut> {
+    const KEYS: [egui::Key; 9] = [
+        egui::Key::Num1,
+        egui::Key::Num2,
+        egui::Key::Num3,
+        egui::Key::Num4,
+        egui::Key::Num5,
+        egui::Key::Num6,
+        egui::Key::Num7,
+        egui::Key::Num8,
+        egui::Key::Num9,
+    ];
+
+    let keys = KEYS
+        .into_iter()
+        .take(layer_count)
+        .enumerate()
+        .map(|(index, key)| (super::SelectedLayer::Tiles(index), key))
+        .chain([(super::SelectedLayer::Events, egui::Key::E)]);
+
+    // Alt is ignored when matching the plain keys, so the toggles are checked first
+    let mut shortcuts = Vec::new();
+    for (layer, key) in keys {
+        if i.consume_key(egui::Modifiers::ALT, key) {
+            shortcuts.push(LayerShortcut::Toggle(layer));
+        } else if i.consume_key(egui::Modifiers::NONE, key) {
+            shortcuts.push(LayerShortcut::Select(layer));
+        }
+    }
+    shortcuts
+}
+
+/// Returns `position` and the positions it's mirrored to across the axes crossing at `center`, on
+/// a map of the given size.
+fn mirror_positions(
+    symmetry: luminol_core::Symmetry,
+    center: egui::Pos2,
+    map_loop: luminol_config::map_loops::MapLoop,
+    (xsize, ysize): (usize, usize),
+    position: (usize, usize, usize),
+) -> Vec<(usize, usize, usize)> {
+    // Mirroring tile `x` across an axis at `center` gives the tile `2 * center - 1 - x`
+    let sum_x = (center.x * 2.).round() as i32 - 1;
+    let sum_y = (center.y * 2.).round() as i32 - 1;
+    let (x, y) = (position.0 as i32, position.1 as i32);
+
+    let mut candidates = vec![(x, y)];
+    if symmetry.mirrors_x() {
+        candidates.push((sum_x - x, y));
+    }
+    if symmetry.mirrors_y() {
+        candidates.push((x, sum_y - y));
+    }
+    if symmetry.mirrors_x() && symmetry.mirrors_y() {
+        candidates.push((sum_x - x, sum_y - y));
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(x, y)| map_loop.wrap(x, y, xsize, ysize))
+        .map(|(x, y)| (x, y, position.2))
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use luminol_config::project::{SmartTile, SmartTileLayout};
+
+    use super::super::Editor;
+
+    /// A 5x5 map with a 3x3 block of `id` in the middle.
+    fn map_with_block(id: i16) -> luminol_data::rpg::Map {
+        let mut data = luminol_data::Table3::new(5, 5, 1);
+        for y in 1..4 {
+            for x in 1..4 {
+                data[(x, y, 0)] = id;
+            }
+        }
+        luminol_data::rpg::Map {
+            width: 5,
+            height: 5,
+            data,
+            ..Default::default()
+        }
+    }
+
+    fn smart_tile(layout: SmartTileLayout) -> SmartTile {
+        SmartTile {
+            name: "Fence".to_string(),
+            tileset_id: 1,
+            layout,
+            origin: 392,
+        }
+    }
+
+    #[test]
+    fn full_47_layout_uses_autotile_variants() {
+        let smart_tile = smart_tile(SmartTileLayout::Full47);
+        let mut map = map_with_block(smart_tile.origin);
+        let recompute = |map: &_, x, y| Editor::recompute_smart_tile(map, &smart_tile, (x, y, 0));
+
+        // surrounded on every side, then the top-left corner and the middle of the top edge
+        assert_eq!(recompute(&map, 2, 2), smart_tile.origin);
+        assert_eq!(recompute(&map, 1, 1), smart_tile.origin + 34);
+        assert_eq!(recompute(&map, 2, 1), smart_tile.origin + 20);
+
+        // a tile on its own, with the block cleared around it
+        for y in 1..4 {
+            for x in 1..4 {
+                if (x, y) != (2, 2) {
+                    map.data[(x, y, 0)] = 0;
+                }
+            }
+        }
+        assert_eq!(recompute(&map, 2, 2), smart_tile.origin + 46);
+    }
+
+    #[test]
+    fn block_3x3_layout_uses_the_edges() {
+        let smart_tile = smart_tile(SmartTileLayout::Block3x3);
+        let map = map_with_block(smart_tile.origin + 9);
+        let recompute = |x, y| Editor::recompute_smart_tile(&map, &smart_tile, (x, y, 0));
+
+        for y in 1..4 {
+            for x in 1..4 {
+                let expected = smart_tile.origin + (x as i16 - 1) + (y as i16 - 1) * 8;
+                assert_eq!(recompute(x, y), expected, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn other_tiles_are_left_alone() {
+        let smart_tile = smart_tile(SmartTileLayout::Full47);
+        let map = map_with_block(400 + 8 * 6);
+        assert_eq!(
+            Editor::recompute_smart_tile(&map, &smart_tile, (2, 2, 0)),
+            448
+        );
+        assert_eq!(
+            Editor::recompute_smart_tile(&map, &smart_tile, (0, 0, 0)),
+            0
+        );
+    }
+
+    #[test]
+    fn mirrored_positions_cross_the_axes() {
+        use luminol_core::Symmetry;
+        let center = egui::pos2(2.5, 2.);
+        let no_loop = luminol_config::map_loops::MapLoop::default();
+        let mirror = |symmetry, map_loop, x, y| {
+            super::mirror_positions(symmetry, center, map_loop, (5, 4), (x, y, 1))
+        };
+
+        assert_eq!(mirror(Symmetry::Off, no_loop, 0, 0), [(0, 0, 1)]);
+        assert_eq!(
+            mirror(Symmetry::Horizontal, no_loop, 0, 0),
+            [(0, 0, 1), (4, 0, 1)]
+        );
+        assert_eq!(
+            mirror(Symmetry::Vertical, no_loop, 0, 0),
+            [(0, 0, 1), (0, 3, 1)]
+        );
+        assert_eq!(
+            mirror(Symmetry::Both, no_loop, 1, 1),
+            [(1, 1, 1), (3, 1, 1), (1, 2, 1), (3, 2, 1)]
+        );
+
+        // the middle column is on the vertical axis, so it isn't drawn twice
+        assert_eq!(mirror(Symmetry::Horizontal, no_loop, 2, 0), [(2, 0, 1)]);
+    }
+
+    #[test]
+    fn mirrored_positions_past_the_edges_need_a_loop() {
+        use luminol_core::Symmetry;
+        let center = egui::pos2(1., 1.);
+        let mirror = |map_loop| {
+            super::mirror_positions(Symmetry::Horizontal, center, map_loop, (5, 4), (4, 0, 0))
+        };
+
+        assert_eq!(
+            mirror(luminol_config::map_loops::MapLoop::default()),
+            [(4, 0, 0)]
+        );
+        assert_eq!(
+            mirror(luminol_config::map_loops::MapLoop {
+                horizontal: true,
+                vertical: false,
+            }),
+            [(4, 0, 0), (2, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn layer_shortcuts_select_and_toggle_layers() {
+        use super::super::SelectedLayer;
+        use super::LayerShortcut;
+
+        let key = |key, modifiers| egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers,
+        };
+        let ctx = egui::Context::default();
+        let mut shortcuts = Vec::new();
+        let _ = ctx.run(
+            egui::RawInput {
+                events: vec![
+                    key(egui::Key::Num2, egui::Modifiers::NONE),
+                    key(egui::Key::Num3, egui::Modifiers::ALT),
+                    key(egui::Key::E, egui::Modifiers::ALT),
+                    // past the last layer of the map
+                    key(egui::Key::Num5, egui::Modifiers::NONE),
+                ],
+                ..Default::default()
+            },
+            |ctx| shortcuts = ctx.input_mut(|i| super::layer_shortcuts(i, 4)),
+        );
+
+        assert_eq!(
+            shortcuts,
+            [
+                LayerShortcut::Select(SelectedLayer::Tiles(1)),
+                LayerShortcut::Toggle(SelectedLayer::Tiles(2)),
+                LayerShortcut::Toggle(SelectedLayer::Events),
+            ]
+        );
     }
 }
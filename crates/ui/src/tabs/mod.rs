@@ -25,3 +25,5 @@
 pub mod map;
 /// The getting started screen.
 pub mod started;
+/// The world overview, showing how maps connect.
+pub mod world;
@@ -0,0 +1,319 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::HashMap;
+
+use color_eyre::eyre::WrapErr;
+use luminol_config::world_layout::WorldLayout;
+use luminol_core::prelude::*;
+use luminol_data::ParameterType;
+
+use crate::components::MapThumbnails;
+
+/// How many pixels a tile takes up at 100% zoom.
+const TILE_SIZE: f32 = 4.;
+/// The space left between maps that haven't been arranged yet, in tiles.
+const GAP: f32 = 8.;
+/// How wide a row of maps that haven't been arranged yet can get before wrapping, in tiles.
+const ROW_WIDTH: f32 = 400.;
+
+/// Lays out every map on a canvas the user can arrange, with arrows for the Transfer Player
+/// commands that lead from one map to another.
+pub struct Tab {
+    layout: WorldLayout,
+    /// The maps and links, scanned the first time the tab is shown.
+    world: Option<World>,
+    thumbnails: MapThumbnails,
+
+    /// Where the origin of the world is drawn, relative to the canvas.
+    pan: egui::Vec2,
+    zoom: f32,
+}
+
+struct World {
+    maps: Vec<MapNode>,
+    links: Vec<Link>,
+}
+
+struct MapNode {
+    id: usize,
+    name: String,
+    /// The map's size in tiles.
+    size: egui::Vec2,
+}
+
+/// A Transfer Player command, from the event running it to where it sends the player.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Link {
+    from: (usize, i32, i32),
+    to: (usize, i32, i32),
+}
+
+fn read_layout(filesystem: &impl FileSystem) -> WorldLayout {
+    filesystem
+        .read_to_string(WorldLayout::PATH)
+        .ok()
+        .and_then(|s| ron::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+impl Tab {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        Self {
+            layout: read_layout(update_state.filesystem),
+            world: None,
+            thumbnails: MapThumbnails::new(),
+            pan: egui::vec2(16., 16.),
+            zoom: 1.,
+        }
+    }
+
+    fn save_layout(&self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        let c = "While saving the world layout";
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let layout = ron::ser::to_string_pretty(&self.layout, pretty_config).wrap_err(c)?;
+        filesystem.write(WorldLayout::PATH, layout).wrap_err(c)
+    }
+
+    /// Finds every map and the Transfer Player commands between them, and places the maps that
+    /// haven't been arranged yet below the ones that have.
+    fn scan(&mut self, update_state: &luminol_core::UpdateState<'_>) -> World {
+        let mut map_infos: Vec<_> = (update_state.data.map_infos().data.iter())
+            .map(|(&id, info)| (id, info.name.clone()))
+            .collect();
+        map_infos.sort_unstable_by_key(|(id, _)| *id);
+
+        let mut maps = Vec::with_capacity(map_infos.len());
+        let mut links = Vec::new();
+        for (id, name) in map_infos {
+            let map = update_state.data.get_or_load_map(
+                id,
+                update_state.filesystem,
+                update_state.project_config.as_ref().unwrap(),
+            );
+            maps.push(MapNode {
+                id,
+                name,
+                size: egui::vec2(map.width as f32, map.height as f32),
+            });
+
+            for (_, event) in map.events.iter() {
+                for page in event.pages.iter() {
+                    for command in page.list.iter() {
+                        // only transfers to a fixed place can be drawn
+                        if let (
+                            201,
+                            [ParameterType::Integer(0), ParameterType::Integer(map_id), ParameterType::Integer(x), ParameterType::Integer(y), ..],
+                        ) = (command.code, command.parameters.as_slice())
+                        {
+                            links.push(Link {
+                                from: (id, event.x, event.y),
+                                to: (*map_id as usize, *x, *y),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        links.sort_unstable();
+        links.dedup();
+
+        let mut y = (maps.iter())
+            .filter_map(|map| {
+                let [_, y] = self.layout.positions.get(&map.id)?;
+                Some(y + map.size.y + GAP)
+            })
+            .fold(0., f32::max);
+        let (mut x, mut row_height) = (0., 0.);
+        for map in maps.iter() {
+            if self.layout.positions.contains_key(&map.id) {
+                continue;
+            }
+            if x > 0. && x + map.size.x > ROW_WIDTH {
+                (x, y) = (0., y + row_height + GAP);
+                row_height = 0.;
+            }
+            self.layout.positions.insert(map.id, [x, y]);
+            x += map.size.x + GAP;
+            row_height = map.size.y.max(row_height);
+        }
+
+        World { maps, links }
+    }
+}
+
+impl luminol_core::Tab for Tab {
+    fn name(&self, _update_state: &luminol_core::UpdateState<'_>) -> String {
+        "World Overview".to_string()
+    }
+
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_world_overview")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        _is_focused: bool,
+    ) {
+        egui::TopBottomPanel::top(self.id().with("toolbar")).show_inside(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                if ui.button("Rescan").clicked() {
+                    self.world = None;
+                    self.thumbnails.clear();
+                }
+                if ui.button("Reset view").clicked() {
+                    self.pan = egui::vec2(16., 16.);
+                    self.zoom = 1.;
+                }
+                ui.weak(
+                    "Drag maps to arrange them and the background to pan. Scroll to zoom, and \
+                    double-click a map to open it.",
+                );
+            });
+        });
+
+        if self.world.is_none() {
+            self.world = Some(self.scan(update_state));
+        }
+        let Some(world) = &self.world else {
+            unreachable!()
+        };
+
+        let id = self.id();
+        let mut open_map_id = None;
+        let mut save_layout = false;
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            let (canvas, response) =
+                ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+
+            if response.dragged() {
+                self.pan += response.drag_delta();
+            }
+            if let Some(pointer) = response.hover_pos() {
+                let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+                if scroll != 0. {
+                    // zoom around the cursor
+                    let zoom = (self.zoom * (scroll / 200.).exp()).clamp(0.1, 10.);
+                    let world_pos = (pointer - canvas.min - self.pan) / self.zoom;
+                    self.pan = pointer - canvas.min - world_pos * zoom;
+                    self.zoom = zoom;
+                }
+            }
+
+            let painter = ui.painter_at(canvas);
+            painter.rect_filled(canvas, 0., ui.visuals().extreme_bg_color);
+
+            let scale = TILE_SIZE * self.zoom;
+            let mut rects = HashMap::with_capacity(world.maps.len());
+            for map in world.maps.iter() {
+                let position = self.layout.positions.entry(map.id).or_default();
+                let mut rect = egui::Rect::from_min_size(
+                    canvas.min + self.pan + egui::Vec2::from(*position) * scale,
+                    map.size * scale,
+                );
+
+                let map_response = ui
+                    .interact(
+                        rect,
+                        id.with(("map", map.id)),
+                        egui::Sense::click_and_drag(),
+                    )
+                    .on_hover_text(format!(
+                        "Map {:0>3}: {} ({}×{})",
+                        map.id, map.name, map.size.x, map.size.y
+                    ));
+                if map_response.dragged() {
+                    let delta = map_response.drag_delta() / scale;
+                    position[0] += delta.x;
+                    position[1] += delta.y;
+                    rect = rect.translate(map_response.drag_delta());
+                }
+                if map_response.drag_stopped() {
+                    save_layout = true;
+                }
+                if map_response.double_clicked() {
+                    open_map_id = Some(map.id);
+                }
+
+                match self.thumbnails.get(update_state, map.id) {
+                    Some(thumbnail) => painter.image(
+                        thumbnail.id(),
+                        rect,
+                        egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+                        egui::Color32::WHITE,
+                    ),
+                    None => painter.rect_filled(rect, 0., ui.visuals().faint_bg_color),
+                };
+                let stroke = if map_response.hovered() || map_response.dragged() {
+                    ui.visuals().selection.stroke
+                } else {
+                    ui.visuals().widgets.noninteractive.fg_stroke
+                };
+                painter.rect_stroke(rect, 0., stroke);
+                painter.text(
+                    rect.left_top() - egui::vec2(0., 2.),
+                    egui::Align2::LEFT_BOTTOM,
+                    format!("{:0>3}: {}", map.id, map.name),
+                    egui::FontId::proportional(12.),
+                    ui.visuals().text_color(),
+                );
+
+                rects.insert(map.id, rect);
+            }
+
+            // arrows go from the middle of the event's tile to the middle of the destination tile
+            let tile_center = |rect: &egui::Rect, x: i32, y: i32| {
+                rect.min + egui::vec2(x as f32 + 0.5, y as f32 + 0.5) * scale
+            };
+            let stroke = egui::Stroke::new(1.5, ui.visuals().warn_fg_color);
+            for link in world.links.iter() {
+                let (Some(from), Some(to)) = (rects.get(&link.from.0), rects.get(&link.to.0))
+                else {
+                    continue;
+                };
+                let start = tile_center(from, link.from.1, link.from.2);
+                let end = tile_center(to, link.to.1, link.to.2);
+                painter.arrow(start, end - start, stroke);
+            }
+        });
+
+        if save_layout {
+            if let Err(e) = self.save_layout(update_state.filesystem) {
+                luminol_core::error!(update_state.toasts, e);
+            }
+        }
+
+        if let Some(id) = open_map_id {
+            let tab = crate::tabs::map::Tab::new(id, update_state);
+            update_state.edit_tabs.add_tab(tab);
+        }
+    }
+}
@@ -54,6 +54,7 @@ impl luminol_core::Window for Window {
     ) {
         // Show the window. Name it "About Luminol"
         egui::Window::new("About Luminol")
+            .id(self.id())
             // Open is passed in. egui sets it to false if the window is closed.
             .open(open)
             .resizable(false)
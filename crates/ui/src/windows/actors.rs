@@ -24,6 +24,7 @@
 
 use crate::components::{Field, OptionalIdComboBox, UiExt};
 use itertools::Itertools;
+use luminol_config::recent_edits::{Database, EditLocation};
 
 use crate::modals::graphic_picker::actor::Modal as GraphicPicker;
 use luminol_core::Modal;
@@ -329,36 +330,66 @@ impl luminol_core::Window for Window {
                         }
                         let class = classes.data.get(actor.class_id);
 
+                        // computed before the combo boxes borrow the equipment fields
+                        let weapon_invalid_reason = unequippable_reason(
+                            class.map(|c| c.weapon_set.as_slice()),
+                            actor.weapon_id,
+                            "weapon",
+                        );
+                        let armor1_invalid_reason = unequippable_reason(
+                            class.map(|c| c.armor_set.as_slice()),
+                            actor.armor1_id,
+                            "armor",
+                        );
+                        let armor2_invalid_reason = unequippable_reason(
+                            class.map(|c| c.armor_set.as_slice()),
+                            actor.armor2_id,
+                            "armor",
+                        );
+                        let armor3_invalid_reason = unequippable_reason(
+                            class.map(|c| c.armor_set.as_slice()),
+                            actor.armor3_id,
+                            "armor",
+                        );
+                        let armor4_invalid_reason = unequippable_reason(
+                            class.map(|c| c.armor_set.as_slice()),
+                            actor.armor4_id,
+                            "armor",
+                        );
+
                         ui.with_padded_stripe(false, |ui| {
                             ui.add(Field::new("Starting Weapon", |ui: &mut egui::Ui| {
                                 egui::Frame::none()
                                     .show(ui, |ui| {
                                         ui.columns(2, |columns| {
                                             modified |= columns[0]
-                                                .add(OptionalIdComboBox::new(
-                                                    update_state,
-                                                    (actor.id, "weapon_id"),
-                                                    &mut actor.weapon_id,
-                                                    class
-                                                        .map_or_else(Default::default, |c| {
-                                                            c.weapon_set.iter().copied()
-                                                        })
-                                                        .filter(|id| {
-                                                            (0..weapons.data.len()).contains(id)
-                                                        }),
-                                                    |id| {
-                                                        weapons.data.get(id).map_or_else(
-                                                            || "".into(),
-                                                            |w| {
-                                                                format!(
-                                                                    "{:0>4}: {}",
-                                                                    id + 1,
-                                                                    w.name
-                                                                )
-                                                            },
-                                                        )
-                                                    },
-                                                ))
+                                                .add(
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        (actor.id, "weapon_id"),
+                                                        &mut actor.weapon_id,
+                                                        class
+                                                            .map_or_else(Default::default, |c| {
+                                                                c.weapon_set.iter().copied()
+                                                            })
+                                                            .filter(|id| {
+                                                                (0..weapons.data.len()).contains(id)
+                                                            }),
+                                                        |id| {
+                                                            weapons.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |w| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        w.name
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    )
+                                                    .invalid_reason(weapon_invalid_reason),
+                                                )
                                                 .changed();
                                             modified |= columns[1]
                                                 .checkbox(&mut actor.weapon_fix, "Fixed")
@@ -375,38 +406,42 @@ impl luminol_core::Window for Window {
                                     .show(ui, |ui| {
                                         ui.columns(2, |columns| {
                                             modified |= columns[0]
-                                                .add(OptionalIdComboBox::new(
-                                                    update_state,
-                                                    (actor.id, "armor1_id"),
-                                                    &mut actor.armor1_id,
-                                                    class
-                                                        .map_or_else(Default::default, |c| {
-                                                            c.armor_set.iter().copied()
-                                                        })
-                                                        .filter(|id| {
-                                                            (0..armors.data.len()).contains(id)
-                                                                && armors.data.get(*id).is_some_and(
-                                                                    |a| {
-                                                                        matches!(
-                                                                            a.kind,
-                                                                            Kind::Shield
-                                                                        )
-                                                                    },
-                                                                )
-                                                        }),
-                                                    |id| {
-                                                        armors.data.get(id).map_or_else(
-                                                            || "".into(),
-                                                            |a| {
-                                                                format!(
-                                                                    "{:0>4}: {}",
-                                                                    id + 1,
-                                                                    a.name,
-                                                                )
-                                                            },
-                                                        )
-                                                    },
-                                                ))
+                                                .add(
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        (actor.id, "armor1_id"),
+                                                        &mut actor.armor1_id,
+                                                        class
+                                                            .map_or_else(Default::default, |c| {
+                                                                c.armor_set.iter().copied()
+                                                            })
+                                                            .filter(|id| {
+                                                                (0..armors.data.len()).contains(id)
+                                                                    && armors
+                                                                        .data
+                                                                        .get(*id)
+                                                                        .is_some_and(|a| {
+                                                                            matches!(
+                                                                                a.kind,
+                                                                                Kind::Shield
+                                                                            )
+                                                                        })
+                                                            }),
+                                                        |id| {
+                                                            armors.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |a| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        a.name,
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    )
+                                                    .invalid_reason(armor1_invalid_reason),
+                                                )
                                                 .changed();
                                             modified |= columns[1]
                                                 .checkbox(&mut actor.armor1_fix, "Fixed")
@@ -423,38 +458,42 @@ impl luminol_core::Window for Window {
                                     .show(ui, |ui| {
                                         ui.columns(2, |columns| {
                                             modified |= columns[0]
-                                                .add(OptionalIdComboBox::new(
-                                                    update_state,
-                                                    (actor.id, "armor2_id"),
-                                                    &mut actor.armor2_id,
-                                                    class
-                                                        .map_or_else(Default::default, |c| {
-                                                            c.armor_set.iter().copied()
-                                                        })
-                                                        .filter(|id| {
-                                                            (0..armors.data.len()).contains(id)
-                                                                && armors.data.get(*id).is_some_and(
-                                                                    |a| {
-                                                                        matches!(
-                                                                            a.kind,
-                                                                            Kind::Helmet
-                                                                        )
-                                                                    },
-                                                                )
-                                                        }),
-                                                    |id| {
-                                                        armors.data.get(id).map_or_else(
-                                                            || "".into(),
-                                                            |a| {
-                                                                format!(
-                                                                    "{:0>4}: {}",
-                                                                    id + 1,
-                                                                    a.name,
-                                                                )
-                                                            },
-                                                        )
-                                                    },
-                                                ))
+                                                .add(
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        (actor.id, "armor2_id"),
+                                                        &mut actor.armor2_id,
+                                                        class
+                                                            .map_or_else(Default::default, |c| {
+                                                                c.armor_set.iter().copied()
+                                                            })
+                                                            .filter(|id| {
+                                                                (0..armors.data.len()).contains(id)
+                                                                    && armors
+                                                                        .data
+                                                                        .get(*id)
+                                                                        .is_some_and(|a| {
+                                                                            matches!(
+                                                                                a.kind,
+                                                                                Kind::Helmet
+                                                                            )
+                                                                        })
+                                                            }),
+                                                        |id| {
+                                                            armors.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |a| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        a.name,
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    )
+                                                    .invalid_reason(armor2_invalid_reason),
+                                                )
                                                 .changed();
                                             modified |= columns[1]
                                                 .checkbox(&mut actor.armor2_fix, "Fixed")
@@ -471,38 +510,42 @@ impl luminol_core::Window for Window {
                                     .show(ui, |ui| {
                                         ui.columns(2, |columns| {
                                             modified |= columns[0]
-                                                .add(OptionalIdComboBox::new(
-                                                    update_state,
-                                                    (actor.id, "armor3_id"),
-                                                    &mut actor.armor3_id,
-                                                    class
-                                                        .map_or_else(Default::default, |c| {
-                                                            c.armor_set.iter().copied()
-                                                        })
-                                                        .filter(|id| {
-                                                            (0..armors.data.len()).contains(id)
-                                                                && armors.data.get(*id).is_some_and(
-                                                                    |a| {
-                                                                        matches!(
-                                                                            a.kind,
-                                                                            Kind::BodyArmor
-                                                                        )
-                                                                    },
-                                                                )
-                                                        }),
-                                                    |id| {
-                                                        armors.data.get(id).map_or_else(
-                                                            || "".into(),
-                                                            |a| {
-                                                                format!(
-                                                                    "{:0>4}: {}",
-                                                                    id + 1,
-                                                                    a.name,
-                                                                )
-                                                            },
-                                                        )
-                                                    },
-                                                ))
+                                                .add(
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        (actor.id, "armor3_id"),
+                                                        &mut actor.armor3_id,
+                                                        class
+                                                            .map_or_else(Default::default, |c| {
+                                                                c.armor_set.iter().copied()
+                                                            })
+                                                            .filter(|id| {
+                                                                (0..armors.data.len()).contains(id)
+                                                                    && armors
+                                                                        .data
+                                                                        .get(*id)
+                                                                        .is_some_and(|a| {
+                                                                            matches!(
+                                                                                a.kind,
+                                                                                Kind::BodyArmor
+                                                                            )
+                                                                        })
+                                                            }),
+                                                        |id| {
+                                                            armors.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |a| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        a.name,
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    )
+                                                    .invalid_reason(armor3_invalid_reason),
+                                                )
                                                 .changed();
                                             modified |= columns[1]
                                                 .checkbox(&mut actor.armor3_fix, "Fixed")
@@ -519,38 +562,42 @@ impl luminol_core::Window for Window {
                                     .show(ui, |ui| {
                                         ui.columns(2, |columns| {
                                             modified |= columns[0]
-                                                .add(OptionalIdComboBox::new(
-                                                    update_state,
-                                                    (actor.id, "armor4_id"),
-                                                    &mut actor.armor4_id,
-                                                    class
-                                                        .map_or_else(Default::default, |c| {
-                                                            c.armor_set.iter().copied()
-                                                        })
-                                                        .filter(|id| {
-                                                            (0..armors.data.len()).contains(id)
-                                                                && armors.data.get(*id).is_some_and(
-                                                                    |a| {
-                                                                        matches!(
-                                                                            a.kind,
-                                                                            Kind::Accessory
-                                                                        )
-                                                                    },
-                                                                )
-                                                        }),
-                                                    |id| {
-                                                        armors.data.get(id).map_or_else(
-                                                            || "".into(),
-                                                            |a| {
-                                                                format!(
-                                                                    "{:0>4}: {}",
-                                                                    id + 1,
-                                                                    a.name,
-                                                                )
-                                                            },
-                                                        )
-                                                    },
-                                                ))
+                                                .add(
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        (actor.id, "armor4_id"),
+                                                        &mut actor.armor4_id,
+                                                        class
+                                                            .map_or_else(Default::default, |c| {
+                                                                c.armor_set.iter().copied()
+                                                            })
+                                                            .filter(|id| {
+                                                                (0..armors.data.len()).contains(id)
+                                                                    && armors
+                                                                        .data
+                                                                        .get(*id)
+                                                                        .is_some_and(|a| {
+                                                                            matches!(
+                                                                                a.kind,
+                                                                                Kind::Accessory
+                                                                            )
+                                                                        })
+                                                            }),
+                                                        |id| {
+                                                            armors.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |a| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        a.name,
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    )
+                                                    .invalid_reason(armor4_invalid_reason),
+                                                )
                                                 .changed();
                                             modified |= columns[1]
                                                 .checkbox(&mut actor.armor4_fix, "Fixed")
@@ -712,6 +759,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             actors.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Actors,
+                id: self.view.selected_id(),
+            });
         }
 
         // we have to drop things before we can restore data, because the compiler isn't smart enough to do that for us right now
@@ -723,3 +774,10 @@ impl luminol_core::Window for Window {
         *update_state.data = data;
     }
 }
+
+/// Why the actor's starting equipment is invalid, if it's not in the set of equipment its class can
+/// equip.
+fn unequippable_reason(set: Option<&[usize]>, id: Option<usize>, kind: &str) -> Option<String> {
+    let (set, id) = (set?, id?);
+    (!set.contains(&id)).then(|| format!("The actor's class can't equip this {kind}"))
+}
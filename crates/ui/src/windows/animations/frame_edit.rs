@@ -1060,20 +1060,21 @@ pub fn show_frame_edit(
 
                 let frame = &mut animation.frames[state.frame_index];
 
-                // Ctrl+Z for undo
-                if ui.input(|i| {
-                    i.modifiers.command && !i.modifiers.shift && i.key_pressed(egui::Key::Z)
-                }) {
+                // Ctrl+Z for undo and Ctrl+Y or Ctrl+Shift+Z for redo. The keys are consumed so
+                // that the database view doesn't also undo an edit of the animation.
+                let (undo, redo) = ui.input_mut(|i| {
+                    let redo = i.consume_key(
+                        egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                        egui::Key::Z,
+                    ) || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+                    let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                    (undo, redo)
+                });
+                if undo {
                     state.history.undo(animation.id, state.frame_index, frame);
                     state.frame_needs_update = true;
                 }
-
-                // Ctrl+Y or Ctrl+Shift+Z for redo
-                if ui.input(|i| {
-                    i.modifiers.command
-                        && (i.key_pressed(egui::Key::Y)
-                            || (i.modifiers.shift && i.key_pressed(egui::Key::Z)))
-                }) {
+                if redo {
                     state.history.redo(animation.id, state.frame_index, frame);
                     state.frame_needs_update = true;
                 }
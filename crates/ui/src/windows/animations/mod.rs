@@ -36,6 +36,8 @@ pub struct Window {
     selected_animation_name: Option<String>,
     previous_animation: Option<usize>,
     previous_battler_name: Option<camino::Utf8PathBuf>,
+    previous_battler_hue: i32,
+    battler_picker: Option<crate::modals::graphic_picker::hue::Modal>,
     frame_edit_state: FrameEditState,
     timing_edit_state: TimingEditState,
 
@@ -221,6 +223,8 @@ impl Default for Window {
             selected_animation_name: None,
             previous_animation: None,
             previous_battler_name: None,
+            previous_battler_hue: 0,
+            battler_picker: None,
             frame_edit_state: FrameEditState {
                 animation_fps: 20.,
                 frame_index: 0,
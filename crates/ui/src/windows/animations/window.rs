@@ -23,6 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{Cellpicker, Field, UiExt};
+use crate::modals::graphic_picker::hue::Modal as GraphicPicker;
+use luminol_config::recent_edits::{Database, EditLocation};
 use luminol_core::Modal;
 use strum::IntoEnumIterator;
 
@@ -47,7 +49,7 @@ impl luminol_core::Window for super::Window {
         let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
         let mut animations = data.animations();
         let animations_len = animations.data.len();
-        let system = data.system();
+        let mut system = data.system();
 
         let mut modified = false;
 
@@ -148,8 +150,38 @@ impl luminol_core::Window for super::Window {
                         });
 
                         ui.with_padded_stripe(false, |ui| {
-                            if self.previous_battler_name != system.battler_name {
+                            // RPG Maker XP keeps the battler animations are previewed against in
+                            // the system data
+                            let battler_picker = self.battler_picker.get_or_insert_with(|| {
+                                GraphicPicker::new(
+                                    update_state,
+                                    "Graphics/Battlers".into(),
+                                    system.battler_name.as_deref(),
+                                    system.battler_hue,
+                                    egui::vec2(64., 64.),
+                                    "animation_target_battler_picker",
+                                )
+                            });
+                            let system = &mut *system;
+                            if ui
+                                .add(Field::new(
+                                    "Target Battler",
+                                    battler_picker.button(
+                                        (&mut system.battler_name, &mut system.battler_hue),
+                                        update_state,
+                                    ),
+                                ))
+                                .changed()
+                            {
+                                system.modified = true;
+                                update_state.modified.set(true);
+                            }
+
+                            if self.previous_battler_name != system.battler_name
+                                || self.previous_battler_hue != system.battler_hue
+                            {
                                 if let Some(frame_view) = &mut self.frame_edit_state.frame_view {
+                                    frame_view.frame.battler_texture = None;
                                     if let Some(battler_name) = &system.battler_name {
                                         match update_state.graphics.texture_loader.load_now(
                                             update_state.filesystem,
@@ -162,7 +194,7 @@ impl luminol_core::Window for super::Window {
                                                 frame_view.frame.battler_texture = None;
                                                 super::util::log_battler_error(
                                                     update_state,
-                                                    &system,
+                                                    system,
                                                     animation,
                                                     e,
                                                 );
@@ -171,7 +203,7 @@ impl luminol_core::Window for super::Window {
                                     }
                                     frame_view.frame.rebuild_battler(
                                         &update_state.graphics,
-                                        &system,
+                                        system,
                                         animation,
                                         luminol_data::Color {
                                             red: 255.,
@@ -184,6 +216,7 @@ impl luminol_core::Window for super::Window {
                                 }
 
                                 self.previous_battler_name.clone_from(&system.battler_name);
+                                self.previous_battler_hue = system.battler_hue;
                             }
 
                             if self.previous_animation != Some(animation.id) {
@@ -223,7 +256,7 @@ impl luminol_core::Window for super::Window {
                                     frame_view.frame.atlas = atlas.clone();
                                     frame_view.frame.update_battler(
                                         &update_state.graphics,
-                                        &system,
+                                        system,
                                         animation,
                                         Some(
                                             flash_maps
@@ -265,7 +298,7 @@ impl luminol_core::Window for super::Window {
                                 update_state,
                                 clip_rect,
                                 &mut self.modals,
-                                &system,
+                                system,
                                 animation,
                                 &mut self.frame_edit_state,
                             );
@@ -451,6 +484,10 @@ impl luminol_core::Window for super::Window {
         if modified {
             update_state.modified.set(true);
             animations.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Animations,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(animations);
@@ -0,0 +1,157 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use strum::IntoEnumIterator;
+
+use super::preferences::{PresetTheme, CODE_SAMPLE, PRESET_VISUALS};
+
+/// A window for quickly changing how Luminol looks.
+///
+/// Changes are applied as soon as they're made. Cancelling restores the appearance from when the
+/// window was opened.
+#[derive(Default)]
+pub struct Window {
+    /// The visuals and code theme from when the window was opened
+    snapshot: Option<(egui::Visuals, luminol_config::CodeTheme)>,
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_appearance_window")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let (snapshot_visuals, snapshot_theme) = self
+            .snapshot
+            .get_or_insert_with(|| {
+                (
+                    ctx.style().visuals.clone(),
+                    update_state.global_config.theme,
+                )
+            })
+            .clone();
+
+        let mut visuals = ctx.style().visuals.clone();
+        let theme = &mut update_state.global_config.theme;
+        let mut finished = false;
+        let mut cancelled = false;
+
+        egui::Window::new("Appearance")
+            .id(self.id())
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Visuals").strong());
+
+                let is_dark = visuals == egui::Visuals::dark();
+                let is_light = visuals == egui::Visuals::light();
+                ui.horizontal(|ui| {
+                    if ui.radio(is_dark, "Dark").clicked() {
+                        visuals = egui::Visuals::dark();
+                    }
+                    if ui.radio(is_light, "Light").clicked() {
+                        visuals = egui::Visuals::light();
+                    }
+                    // Picking "Custom" doesn't change anything by itself, the visuals become
+                    // custom once they're edited below
+                    ui.add(egui::RadioButton::new(!is_dark && !is_light, "Custom"));
+                });
+
+                let preset_name = PRESET_VISUALS
+                    .iter()
+                    .find(|preset| preset.visuals == visuals)
+                    .map_or("Custom", |preset| preset.name);
+                egui::ComboBox::from_label("Preset theme")
+                    .selected_text(preset_name)
+                    .show_ui(ui, |ui| {
+                        for PresetTheme {
+                            name,
+                            visuals: preset_visuals,
+                            description,
+                        } in PRESET_VISUALS.iter()
+                        {
+                            if ui
+                                .selectable_label(preset_name == *name, *name)
+                                .on_hover_text(*description)
+                                .clicked()
+                            {
+                                visuals = preset_visuals.clone();
+                            }
+                        }
+                    });
+
+                ui.collapsing("Customize visuals", |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(300.)
+                        .show(ui, |ui| visuals.ui(ui));
+                });
+
+                ui.separator();
+
+                ui.label(egui::RichText::new("Code theme").strong());
+                egui::ComboBox::from_id_source("luminol_appearance_code_theme")
+                    .selected_text(theme.syntect_theme.to_string())
+                    .show_ui(ui, |ui| {
+                        for t in luminol_config::SyntectTheme::iter() {
+                            ui.selectable_value(&mut theme.syntect_theme, t, t.to_string());
+                        }
+                    });
+                ui.group(|ui| {
+                    ui.label(crate::components::syntax_highlighting::highlight(
+                        ui.ctx(),
+                        *theme,
+                        CODE_SAMPLE,
+                        "rb",
+                    ));
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    finished = ui.button("OK").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            visuals = snapshot_visuals;
+            *theme = snapshot_theme;
+        }
+        theme.dark_mode = visuals.dark_mode;
+        if visuals != ctx.style().visuals {
+            ctx.set_visuals(visuals);
+        }
+
+        if finished || cancelled {
+            *open = false;
+        }
+        if !*open {
+            self.snapshot = None;
+        }
+    }
+}
@@ -24,6 +24,7 @@
 
 use crate::components::{FileSystemView, UiExt};
 use luminol_filesystem::{File, FileSystem, OpenFlags};
+use std::io::Write;
 
 static CREATE_DEFAULT_SELECTED_DIRS: once_cell::sync::Lazy<
     qp_trie::Trie<qp_trie::wrapper::BString, ()>,
@@ -39,6 +40,8 @@ pub struct Window {
     mode: Mode,
     initialized: bool,
     progress: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    /// The task logging the files being extracted or added to an archive.
+    task: Option<luminol_core::Task>,
 }
 
 enum Mode {
@@ -78,6 +81,7 @@ impl Default for Window {
             },
             initialized: false,
             progress: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(usize::MAX)),
+            task: None,
         }
     }
 }
@@ -126,11 +130,14 @@ impl luminol_core::Window for Window {
 
                     Mode::Create { view, .. } => {
                         let name = host.root_path().to_string();
-                        *view = Some(FileSystemView::new(
-                            "luminol_archive_manager_create_view".into(),
-                            host,
-                            name,
-                        ));
+                        *view = Some(
+                            FileSystemView::new(
+                                "luminol_archive_manager_create_view".into(),
+                                host.clone(),
+                                name,
+                            )
+                            .with_host(host),
+                        );
                     }
                 }
             }
@@ -138,6 +145,7 @@ impl luminol_core::Window for Window {
 
         let mut window_open = true;
         egui::Window::new("RGSSAD Archive Manager")
+            .id(self.id())
             .open(&mut window_open)
             .show(ctx, |ui| {
                 let enabled = match &self.mode {
@@ -233,6 +241,7 @@ impl luminol_core::Window for Window {
 impl Window {
     fn show_inner(&mut self, ui: &mut egui::Ui, update_state: &mut luminol_core::UpdateState<'_>) {
         let progress = self.progress.clone();
+        let task = &mut self.task;
 
         match &mut self.mode {
             Mode::Extract {
@@ -315,6 +324,10 @@ impl Window {
                                             *progress_total = file_paths.len();
                                             progress.store(usize::MAX, std::sync::atomic::Ordering::Relaxed);
 
+                                            let new_task = update_state.tasks.start("Extract archive");
+                                            let mut log = new_task.log_writer();
+                                            *task = Some(new_task);
+
                                             *save_promise = Some(luminol_core::spawn_future(async move {
                                                 let dest_fs = luminol_filesystem::host::FileSystem::from_folder_picker().await?;
                                                 progress.store(0, std::sync::atomic::Ordering::Relaxed);
@@ -327,6 +340,7 @@ impl Window {
                                                     let mut src_file = view_filesystem.open_file(&path, OpenFlags::Read)?;
                                                     let mut dest_file = dest_fs.open_file(&path, OpenFlags::Write | OpenFlags::Create | OpenFlags::Truncate)?;
                                                     async_std::io::copy(&mut src_file, &mut dest_file).await?;
+                                                    let _ = writeln!(log, "{path}");
 
                                                     progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                                                     ctx.request_repaint();
@@ -357,9 +371,15 @@ impl Window {
                 if let Some(p) = save_promise.take() {
                     match p.try_take() {
                         Ok(Ok(())) => {
+                            if let Some(task) = task.take() {
+                                task.finish(format!("extracted {progress_total} files"));
+                            }
                             luminol_core::info!(update_state.toasts, "Extracted successfully!")
                         }
                         Ok(Err(e)) => {
+                            if let Some(task) = task.take() {
+                                task.finish(format!("failed: {e}"));
+                            }
                             if !matches!(
                                 e.root_cause().downcast_ref(),
                                 Some(luminol_filesystem::Error::CancelledLoading)
@@ -386,11 +406,14 @@ impl Window {
                     match p.try_take() {
                         Ok(Ok(handle)) => {
                             let name = handle.root_path().to_string();
-                            *view = Some(FileSystemView::new(
-                                "luminol_archive_manager_create_view".into(),
-                                handle,
-                                name,
-                            ));
+                            *view = Some(
+                                FileSystemView::new(
+                                    "luminol_archive_manager_create_view".into(),
+                                    handle.clone(),
+                                    name,
+                                )
+                                .with_host(handle),
+                            );
                         }
                         Ok(Err(e)) => {
                             if !matches!(
@@ -425,93 +448,100 @@ impl Window {
                     || save_promise.is_none()
                 {
                     ui.columns(2, |columns| {
-                        columns[0].with_cross_justify_center(
-                            |ui| {
-                                if load_promise.is_none() && ui.button("Choose source folder").clicked()
-                                {
-                                    *load_promise = Some(luminol_core::spawn_future(
-                                        luminol_filesystem::host::FileSystem::from_folder_picker(),
-                                    ));
-                                } else if load_promise.is_some() {
-                                    ui.spinner();
-                                }
-                            },
-                        );
+                        columns[0].with_cross_justify_center(|ui| {
+                            if load_promise.is_none() && ui.button("Choose source folder").clicked()
+                            {
+                                *load_promise = Some(luminol_core::spawn_future(
+                                    luminol_filesystem::host::FileSystem::from_folder_picker(),
+                                ));
+                            } else if load_promise.is_some() {
+                                ui.spinner();
+                            }
+                        });
 
-                        columns[1].with_cross_justify_center(
-                            |ui| {
-                                if save_promise.is_none()
-                                    && ui
-                                        .add_enabled(
-                                            view.as_ref()
-                                                .is_some_and(|view| view.iter().next().is_some()),
-                                            egui::Button::new("Create from selected files"),
-                                        )
-                                        .clicked()
-                                {
-                                    if let Some(view) = view {
-                                        let version = *version;
-                                        match Self::find_files(view) {
-                                            Ok(file_paths) => {
-                                                let ctx = ui.ctx().clone();
-                                                let progress = progress.clone();
-                                                let view_filesystem = view.filesystem().clone();
-                                                *progress_total = file_paths.len();
-                                                progress.store(usize::MAX, std::sync::atomic::Ordering::Relaxed);
-
-                                                *save_promise =
-                                                    Some(luminol_core::spawn_future(async move {
-                                                        let mut file = luminol_filesystem::host::File::new()?;
-
-                                                        let mut is_first = true;
-
-                                                        progress.store(0, std::sync::atomic::Ordering::Relaxed);
-                                                        ctx.request_repaint();
-
-                                                        let _ = luminol_filesystem::archiver::FileSystem::from_buffer_and_files(
-                                                            &mut file,
-                                                            if version == 2 {
-                                                                1
-                                                            } else {
-                                                                version
-                                                            },
-                                                            file_paths.iter().map(|path| {
-                                                                if is_first {
-                                                                    is_first = false;
-                                                                } else {
-                                                                    progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                                                                    ctx.request_repaint();
-                                                                }
-
-                                                                let file = view_filesystem.open_file(path, OpenFlags::Read)?;
-                                                                let size = file.metadata()?.size as u32;
-                                                                Ok((path, size, file))
-                                                            }),
-                                                        ).await?;
-
-                                                        progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                                                        ctx.request_repaint();
-
-                                                        file.save(
-                                                            match version {
-                                                                1 => "Game.rgssad",
-                                                                2 => "Game.rgss2a",
-                                                                3 => "Game.rgss3a",
-                                                                _ => unreachable!(),
-                                                            },
-                                                            "RGSSAD archives",
-                                                        )
-                                                        .await
-                                                    }));
-                                            }
-                                            Err(e) => luminol_core::error!(update_state.toasts, e.wrap_err("Error enumerating files to create archive from")),
+                        columns[1].with_cross_justify_center(|ui| {
+                            if save_promise.is_none()
+                                && ui
+                                    .add_enabled(
+                                        view.as_ref()
+                                            .is_some_and(|view| view.iter().next().is_some()),
+                                        egui::Button::new("Create from selected files"),
+                                    )
+                                    .clicked()
+                            {
+                                if let Some(view) = view {
+                                    match Self::find_files(view) {
+                                        Ok(file_paths) => {
+                                            *progress_total = file_paths.len();
+                                            let new_task =
+                                                update_state.tasks.start("Create archive");
+                                            *save_promise = Some(Self::create_archive(
+                                                ui.ctx().clone(),
+                                                view.filesystem().clone(),
+                                                file_paths,
+                                                *version,
+                                                progress.clone(),
+                                                new_task.log_writer(),
+                                            ));
+                                            *task = Some(new_task);
                                         }
+                                        Err(e) => luminol_core::error!(
+                                            update_state.toasts,
+                                            e.wrap_err(
+                                                "Error enumerating files to create archive from"
+                                            )
+                                        ),
                                     }
-                                } else if save_promise.is_some() {
-                                    ui.spinner();
                                 }
-                            },
-                        );
+                            } else if save_promise.is_some() {
+                                ui.spinner();
+                            }
+                        });
+                    });
+
+                    let host = update_state.filesystem.host();
+                    ui.vertical_centered_justified(|ui| {
+                        if save_promise.is_none()
+                            && ui
+                                .add_enabled(
+                                    host.is_some(),
+                                    egui::Button::new("Repack open project"),
+                                )
+                                .on_hover_text(
+                                    "Create an archive from every file in the open project's \
+                                    folder, except for archives",
+                                )
+                                .clicked()
+                        {
+                            if let Some(host) = host {
+                                match Self::find_project_files(&host) {
+                                    Ok(file_paths) => {
+                                        *progress_total = file_paths.len();
+                                        luminol_core::info!(
+                                            update_state.toasts,
+                                            format!(
+                                                "Repacking {progress_total} files from the open \
+                                                project"
+                                            )
+                                        );
+                                        let new_task = update_state.tasks.start("Repack project");
+                                        *save_promise = Some(Self::create_archive(
+                                            ui.ctx().clone(),
+                                            host,
+                                            file_paths,
+                                            *version,
+                                            progress.clone(),
+                                            new_task.log_writer(),
+                                        ));
+                                        *task = Some(new_task);
+                                    }
+                                    Err(e) => luminol_core::error!(
+                                        update_state.toasts,
+                                        e.wrap_err("Error enumerating the files of the project")
+                                    ),
+                                }
+                            }
+                        }
                     });
                 } else {
                     ui.add(
@@ -527,12 +557,18 @@ impl Window {
                 if let Some(p) = save_promise.take() {
                     match p.try_take() {
                         Ok(Ok(())) => {
+                            if let Some(task) = task.take() {
+                                task.finish(format!("added {progress_total} files"));
+                            }
                             luminol_core::info!(
                                 update_state.toasts,
                                 "Created archive successfully!"
                             );
                         }
                         Ok(Err(e)) => {
+                            if let Some(task) = task.take() {
+                                task.finish(format!("failed: {e}"));
+                            }
                             if !matches!(
                                 e.root_cause().downcast_ref(),
                                 Some(luminol_filesystem::Error::CancelledLoading)
@@ -550,6 +586,80 @@ impl Window {
         }
     }
 
+    /// Writes the files at `file_paths` into a new archive and asks the user where to save it.
+    ///
+    /// Each file is only opened when it is about to be written, so the files never need to fit in
+    /// memory all at once.
+    fn create_archive(
+        ctx: egui::Context,
+        filesystem: luminol_filesystem::host::FileSystem,
+        file_paths: Vec<camino::Utf8PathBuf>,
+        version: u8,
+        progress: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        mut log: luminol_core::tasks::LogWriter,
+    ) -> poll_promise::Promise<luminol_filesystem::Result<()>> {
+        progress.store(usize::MAX, std::sync::atomic::Ordering::Relaxed);
+
+        luminol_core::spawn_future(async move {
+            let mut file = luminol_filesystem::host::File::new()?;
+
+            let mut is_first = true;
+
+            progress.store(0, std::sync::atomic::Ordering::Relaxed);
+            ctx.request_repaint();
+
+            let _ = luminol_filesystem::archiver::FileSystem::from_buffer_and_files(
+                &mut file,
+                if version == 2 { 1 } else { version },
+                file_paths.iter().map(|path| {
+                    if is_first {
+                        is_first = false;
+                    } else {
+                        progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        ctx.request_repaint();
+                    }
+
+                    let file = filesystem.open_file(path, OpenFlags::Read)?;
+                    let size = file.metadata()?.size as u32;
+                    let _ = writeln!(log, "{path} ({size} bytes)");
+                    Ok((path, size, file))
+                }),
+            )
+            .await?;
+
+            progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            ctx.request_repaint();
+
+            file.save(
+                match version {
+                    1 => "Game.rgssad",
+                    2 => "Game.rgss2a",
+                    3 => "Game.rgss3a",
+                    _ => unreachable!(),
+                },
+                "RGSSAD archives",
+            )
+            .await
+        })
+    }
+
+    /// Finds every file in the folder of the open project, leaving out any archives in it.
+    fn find_project_files(
+        host: &luminol_filesystem::host::FileSystem,
+    ) -> luminol_filesystem::Result<Vec<camino::Utf8PathBuf>> {
+        let mut vec = Vec::new();
+        Self::find_files_recurse(&mut vec, host, "".into(), false)?;
+        vec.retain(|path| {
+            !path.extension().is_some_and(|extension| {
+                matches!(
+                    extension.to_lowercase().as_str(),
+                    "rgssad" | "rgss2a" | "rgss3a"
+                )
+            })
+        });
+        Ok(vec)
+    }
+
     fn find_files(
         view: &FileSystemView<impl luminol_filesystem::ReadDir>,
     ) -> luminol_filesystem::Result<Vec<camino::Utf8PathBuf>> {
@@ -23,6 +23,7 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{EnumComboBox, Field, IdVecSelection, OptionalIdComboBox, UiExt};
+use luminol_config::recent_edits::{Database, EditLocation};
 
 #[derive(Default)]
 pub struct Window {
@@ -247,6 +248,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             armors.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Armors,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(armors);
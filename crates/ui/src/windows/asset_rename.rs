@@ -0,0 +1,290 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::asset_references::{self, DIRECTORIES};
+use luminol_core::prelude::*;
+
+/// Renames an asset file and updates every reference to it in the database and the maps.
+pub struct Window {
+    directory: &'static str,
+    /// The file being renamed, relative to `directory`.
+    file: Option<camino::Utf8PathBuf>,
+    /// The new name of the file, without its extension.
+    new_name: String,
+
+    /// The files in `directory`, loaded the first time they are shown.
+    entries: Option<Vec<camino::Utf8PathBuf>>,
+    /// The references to `file` that would be updated: where they are, and what they currently
+    /// say.
+    references: Option<Vec<(String, String)>>,
+}
+
+impl Window {
+    pub fn new(directory: &'static str, file: Option<camino::Utf8PathBuf>) -> Self {
+        Self {
+            directory,
+            new_name: file
+                .as_ref()
+                .and_then(|file| file.file_stem())
+                .unwrap_or_default()
+                .to_string(),
+            file,
+            entries: None,
+            references: None,
+        }
+    }
+
+    fn load_entries(&self, update_state: &mut UpdateState<'_>) -> Vec<camino::Utf8PathBuf> {
+        match update_state.filesystem.read_dir(self.directory) {
+            Ok(entries) => entries
+                .into_iter()
+                .filter(|entry| entry.metadata.is_file)
+                .filter_map(|entry| entry.path.file_name().map(camino::Utf8PathBuf::from))
+                .collect(),
+            Err(error) => {
+                luminol_core::error!(
+                    update_state.toasts,
+                    error.wrap_err(format!("While listing the files in {}", self.directory))
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// The file name `file` will be renamed to, keeping its extension.
+    fn new_file(&self, file: &camino::Utf8Path) -> camino::Utf8PathBuf {
+        let mut new_file = camino::Utf8PathBuf::from(self.new_name.trim());
+        if let Some(extension) = file.extension() {
+            new_file.set_extension(extension);
+        }
+        new_file
+    }
+
+    fn find_references(
+        update_state: &UpdateState<'_>,
+        directory: &str,
+        file: &camino::Utf8Path,
+    ) -> Vec<(String, String)> {
+        let mut references = Vec::new();
+        asset_references::for_each_reference(
+            update_state.data,
+            update_state.filesystem,
+            update_state.project_config.as_ref().unwrap(),
+            |reference| {
+                if reference.refers_to(directory, file) {
+                    references.push((
+                        reference.location.to_string(),
+                        reference.name.get().unwrap_or_default().to_string(),
+                    ));
+                }
+                false
+            },
+        );
+        references
+    }
+
+    fn rename(&mut self, update_state: &mut UpdateState<'_>, file: &camino::Utf8Path) {
+        let new_file = self.new_file(file);
+        let directory = camino::Utf8Path::new(self.directory);
+        if let Err(error) = update_state
+            .filesystem
+            .rename(directory.join(file), directory.join(&new_file))
+        {
+            luminol_core::error!(
+                update_state.toasts,
+                error.wrap_err(format!("While renaming {file} to {new_file}"))
+            );
+            return;
+        }
+
+        // references may or may not include the extension, so keep whichever they had
+        let new_stem = new_file.with_extension("");
+        let mut count = 0;
+        asset_references::for_each_reference(
+            update_state.data,
+            update_state.filesystem,
+            update_state.project_config.as_ref().unwrap(),
+            |mut reference| {
+                if !reference.refers_to(self.directory, file) {
+                    return false;
+                }
+                let has_extension = reference
+                    .name
+                    .get()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(file.as_str()));
+                reference.name.set(if has_extension {
+                    new_file.as_str()
+                } else {
+                    new_stem.as_str()
+                });
+                count += 1;
+                true
+            },
+        );
+        if count > 0 {
+            update_state.modified.set(true);
+        }
+
+        luminol_core::info!(
+            update_state.toasts,
+            format!("Renamed {file} to {new_file} and updated {count} references")
+        );
+        self.file = Some(new_file);
+        self.entries = None;
+        self.references = None;
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_asset_rename")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        if self.entries.is_none() {
+            self.entries = Some(self.load_entries(update_state));
+        }
+
+        let mut rename = false;
+
+        egui::Window::new("Rename Asset")
+            .id(self.id())
+            .default_width(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                egui::Grid::new(self.id().with("grid"))
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Directory");
+                        let directory = self.directory;
+                        egui::ComboBox::from_id_source(self.id().with("directory"))
+                            .selected_text(self.directory)
+                            .show_ui(ui, |ui| {
+                                for &directory in DIRECTORIES {
+                                    ui.selectable_value(&mut self.directory, directory, directory);
+                                }
+                            });
+                        if self.directory != directory {
+                            self.file = None;
+                            self.entries = None;
+                            self.references = None;
+                        }
+                        ui.end_row();
+
+                        ui.label("File");
+                        let file = self.file.clone();
+                        egui::ComboBox::from_id_source(self.id().with("file"))
+                            .selected_text(self.file.as_ref().map_or("", |file| file.as_str()))
+                            .show_ui(ui, |ui| {
+                                for entry in self.entries.iter().flatten() {
+                                    ui.selectable_value(
+                                        &mut self.file,
+                                        Some(entry.clone()),
+                                        entry.as_str(),
+                                    );
+                                }
+                            });
+                        if self.file != file {
+                            self.new_name = (self.file.as_ref())
+                                .and_then(|file| file.file_stem())
+                                .unwrap_or_default()
+                                .to_string();
+                            self.references = None;
+                        }
+                        ui.end_row();
+
+                        ui.label("New name");
+                        ui.text_edit_singleline(&mut self.new_name);
+                        ui.end_row();
+                    });
+
+                let Some(file) = self.file.clone() else {
+                    ui.weak("Pick a file to rename.");
+                    return;
+                };
+
+                ui.separator();
+
+                if ui.button("Find references").clicked() {
+                    self.references =
+                        Some(Self::find_references(update_state, self.directory, &file));
+                }
+
+                let Some(references) = &self.references else {
+                    return;
+                };
+                let new_file = self.new_file(&file);
+                ui.label(format!(
+                    "Renaming {file} to {new_file} will update {} references:",
+                    references.len()
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(200.)
+                    .show(ui, |ui| {
+                        egui::Grid::new(self.id().with("references"))
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (location, name) in references {
+                                    ui.label(location);
+                                    ui.weak(name);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                let exists = self.entries.iter().flatten().any(|entry| {
+                    entry.as_str().eq_ignore_ascii_case(new_file.as_str())
+                        && !entry.as_str().eq_ignore_ascii_case(file.as_str())
+                });
+                if exists {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{new_file} already exists"),
+                    );
+                }
+                let enabled = !self.new_name.trim().is_empty() && new_file != file && !exists;
+                if ui
+                    .add_enabled(enabled, egui::Button::new("Rename"))
+                    .clicked()
+                {
+                    rename = true;
+                }
+            });
+
+        if rename {
+            if let Some(file) = self.file.clone() {
+                self.rename(update_state, &file);
+            }
+        }
+    }
+}
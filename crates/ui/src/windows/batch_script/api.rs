@@ -0,0 +1,782 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use parking_lot::Mutex;
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+type RhaiResult<T> = Result<T, Box<EvalAltResult>>;
+type SharedSnapshot = Arc<Mutex<Snapshot>>;
+
+/// The database tables scripts can read the names of.
+const DATABASE_KINDS: &[&str] = &[
+    "actors",
+    "animations",
+    "armors",
+    "classes",
+    "common_events",
+    "enemies",
+    "items",
+    "skills",
+    "states",
+    "tilesets",
+    "troops",
+    "weapons",
+    "switches",
+    "variables",
+];
+
+/// A copy of the project data that scripts operate on.
+///
+/// Scripts run in the background, so they can't borrow the data cache. Instead, the maps are copied
+/// into a snapshot before the script starts and the changes the script made are written back into
+/// the data cache once it has finished. Only the tiles and event fields the script changed are
+/// written back, so edits made to the maps in the meantime are kept.
+#[derive(Default)]
+pub struct Snapshot {
+    maps: BTreeMap<usize, MapSnapshot>,
+    database: BTreeMap<&'static str, Vec<String>>,
+    /// Human-readable descriptions of the changes made to events
+    changes: Vec<String>,
+}
+
+struct MapSnapshot {
+    name: String,
+    data: luminol_data::Table3,
+    tileset_id: usize,
+    events: BTreeMap<usize, EventSnapshot>,
+    /// The events as they were before the script ran
+    original_events: BTreeMap<usize, EventSnapshot>,
+    /// Positions of the tiles the script changed on this map
+    changed_tiles: BTreeSet<(usize, usize, usize)>,
+    /// Whether this map has to be written back into the data cache
+    modified: bool,
+}
+
+#[derive(Clone, PartialEq)]
+struct EventSnapshot {
+    name: String,
+    x: i32,
+    y: i32,
+    /// The through flag of every page of the event
+    through: Vec<bool>,
+}
+
+macro_rules! database_names {
+    ($data:expr, $database:ident, $($kind:ident),* $(,)?) => {
+        $(
+            $database.insert(
+                stringify!($kind),
+                $data.$kind().data.iter().map(|row| row.name.clone()).collect(),
+            );
+        )*
+    };
+}
+
+impl Snapshot {
+    /// Creates a snapshot with the names of the database entries and no maps. Maps are added with
+    /// [`Self::add_map`].
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let mut database = BTreeMap::new();
+        database_names!(
+            update_state.data,
+            database,
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            skills,
+            states,
+            tilesets,
+            troops,
+            weapons,
+        );
+        let system = update_state.data.system();
+        database.insert("switches", system.switches.clone());
+        database.insert("variables", system.variables.clone());
+
+        Self {
+            maps: BTreeMap::new(),
+            database,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Copies a map into the snapshot, loading it if it isn't loaded yet.
+    pub fn add_map(&mut self, update_state: &luminol_core::UpdateState<'_>, id: usize) {
+        let config = update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded");
+        let name = update_state
+            .data
+            .map_infos()
+            .data
+            .get(&id)
+            .map(|info| info.name.clone())
+            .unwrap_or_default();
+
+        let map = update_state
+            .data
+            .get_or_load_map(id, &*update_state.filesystem, config);
+        let events: BTreeMap<_, _> = map
+            .events
+            .iter()
+            .map(|(event_id, event)| {
+                (
+                    event_id,
+                    EventSnapshot {
+                        name: event.name.clone(),
+                        x: event.x,
+                        y: event.y,
+                        through: event.pages.iter().map(|page| page.through).collect(),
+                    },
+                )
+            })
+            .collect();
+
+        self.maps.insert(
+            id,
+            MapSnapshot {
+                name,
+                data: map.data.clone(),
+                tileset_id: map.tileset_id,
+                original_events: events.clone(),
+                events,
+                changed_tiles: BTreeSet::new(),
+                modified: false,
+            },
+        );
+    }
+
+    /// Describes the changes the script made, one line per change.
+    pub fn summary(&self) -> Vec<String> {
+        let mut summary = self.changes.clone();
+        summary.extend(
+            self.maps
+                .iter()
+                .filter(|(_, map)| !map.changed_tiles.is_empty())
+                .map(|(id, map)| {
+                    format!(
+                        "Map {id:0>3} ({}): {} tile(s) changed",
+                        map.name,
+                        map.changed_tiles.len()
+                    )
+                }),
+        );
+        summary
+    }
+
+    /// Writes the changes the script made back into the data cache.
+    ///
+    /// Maps that are open in a tab are left alone, since the tab's undo history would no longer
+    /// match the map. Their IDs are returned in [`Applied::skipped`].
+    pub fn apply(self, update_state: &luminol_core::UpdateState<'_>) -> Applied {
+        let config = update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded");
+
+        let mut applied = Applied::default();
+        for (id, snapshot) in self.maps.into_iter().filter(|(_, map)| map.modified) {
+            if crate::tabs::map::is_open(update_state.ctx, id) {
+                applied.skipped.push(id);
+                continue;
+            }
+
+            let mut map = update_state
+                .data
+                .get_or_load_map(id, &*update_state.filesystem, config);
+            for position in snapshot.changed_tiles {
+                if position.0 < map.data.xsize()
+                    && position.1 < map.data.ysize()
+                    && position.2 < map.data.zsize()
+                {
+                    map.data[position] = snapshot.data[position];
+                }
+            }
+            for (event_id, event_snapshot) in snapshot.events {
+                let Some(original) = snapshot.original_events.get(&event_id) else {
+                    continue;
+                };
+                let Some(event) = map.events.get_mut(event_id) else {
+                    continue;
+                };
+                if event_snapshot.name != original.name {
+                    event.name = event_snapshot.name;
+                }
+                if event_snapshot.x != original.x {
+                    event.x = event_snapshot.x;
+                }
+                if event_snapshot.y != original.y {
+                    event.y = event_snapshot.y;
+                }
+                for ((page, &through), &original_through) in event
+                    .pages
+                    .iter_mut()
+                    .zip(&event_snapshot.through)
+                    .zip(&original.through)
+                {
+                    if through != original_through {
+                        page.through = through;
+                    }
+                }
+            }
+
+            map.modified = true;
+            applied.count += 1;
+        }
+
+        if applied.count != 0 {
+            update_state.modified.set(true);
+        }
+        applied
+    }
+}
+
+/// What [`Snapshot::apply`] did.
+#[derive(Default)]
+pub struct Applied {
+    /// How many maps were written back into the data cache
+    pub count: usize,
+    /// Maps the script changed that were skipped because they are open in a tab
+    pub skipped: Vec<usize>,
+}
+
+/// The outcome of a script that ran to completion.
+pub struct RunResult {
+    pub snapshot: Snapshot,
+    pub value: String,
+}
+
+/// A script that is being run.
+///
+/// The top-level statements of the script run in one go. If the script defines a
+/// `for_each_map(map)` function, it is called afterwards once for every map, and the run can be
+/// paused between any two calls. On web, where the script has to share the main thread with the
+/// UI, this keeps the editor responsive during long runs.
+pub struct ScriptRun {
+    engine: Engine,
+    ast: rhai::AST,
+    scope: rhai::Scope<'static>,
+    snapshot: SharedSnapshot,
+    cancel: Arc<AtomicBool>,
+    /// The maps `for_each_map` still has to be called with, last one first, or `None` if the
+    /// top-level statements haven't run yet
+    remaining_maps: Option<Vec<usize>>,
+    value: Dynamic,
+}
+
+impl ScriptRun {
+    /// Compiles a script. Setting `cancel` stops the script at the next opportunity.
+    pub fn new(
+        code: &str,
+        snapshot: Snapshot,
+        on_output: impl Fn(String) + Send + Sync + Clone + 'static,
+        cancel: Arc<AtomicBool>,
+    ) -> color_eyre::Result<Self> {
+        let snapshot = Arc::new(Mutex::new(snapshot));
+        let mut engine = create_engine(snapshot.clone(), on_output);
+        let progress_cancel = cancel.clone();
+        engine.on_progress(move |_| {
+            progress_cancel
+                .load(Ordering::Relaxed)
+                .then(|| "cancelled by the user".into())
+        });
+
+        let ast = engine
+            .compile(code)
+            .map_err(|error| color_eyre::eyre::eyre!("{error}"))?;
+
+        Ok(Self {
+            engine,
+            ast,
+            scope: rhai::Scope::new(),
+            snapshot,
+            cancel,
+            remaining_maps: None,
+            value: Dynamic::UNIT,
+        })
+    }
+
+    /// Runs the script until it has finished or `budget` has passed, whichever comes first.
+    /// Returns the result once the script has finished.
+    pub fn step(&mut self, budget: std::time::Duration) -> Option<color_eyre::Result<RunResult>> {
+        let started = web_time::Instant::now();
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Some(Err(color_eyre::eyre::eyre!("cancelled by the user")));
+            }
+
+            let result = match self.remaining_maps.as_mut() {
+                None => {
+                    let has_for_each_map = self
+                        .ast
+                        .iter_functions()
+                        .any(|f| f.name == "for_each_map" && f.params.len() == 1);
+                    let mut remaining_maps = if has_for_each_map {
+                        self.snapshot.lock().maps.keys().copied().collect()
+                    } else {
+                        Vec::new()
+                    };
+                    remaining_maps.reverse();
+                    self.remaining_maps = Some(remaining_maps);
+
+                    self.engine
+                        .eval_ast_with_scope::<Dynamic>(&mut self.scope, &self.ast)
+                        .map(|value| self.value = value)
+                }
+                Some(remaining_maps) => {
+                    let Some(id) = remaining_maps.pop() else {
+                        return Some(Ok(self.finish()));
+                    };
+                    self.engine
+                        .call_fn_with_options::<Dynamic>(
+                            rhai::CallFnOptions::new().eval_ast(false),
+                            &mut self.scope,
+                            &self.ast,
+                            "for_each_map",
+                            (MapHandle {
+                                snapshot: self.snapshot.clone(),
+                                id,
+                            },),
+                        )
+                        .map(|_| ())
+                }
+            };
+            if let Err(error) = result {
+                return Some(Err(color_eyre::eyre::eyre!("{error}")));
+            }
+
+            if started.elapsed() >= budget {
+                return None;
+            }
+        }
+    }
+
+    /// Runs the script to the end, for running it on a thread of its own.
+    pub fn run_to_completion(mut self) -> color_eyre::Result<RunResult> {
+        loop {
+            if let Some(result) = self.step(std::time::Duration::MAX) {
+                return result;
+            }
+        }
+    }
+
+    fn finish(&mut self) -> RunResult {
+        let value = std::mem::take(&mut self.value);
+        RunResult {
+            snapshot: std::mem::take(&mut *self.snapshot.lock()),
+            value: if value.is_unit() {
+                String::new()
+            } else {
+                value.to_string()
+            },
+        }
+    }
+}
+
+fn to_index(value: i64, what: &str) -> RhaiResult<usize> {
+    usize::try_from(value).map_err(|_| format!("{what} must not be negative (got {value})").into())
+}
+
+/// A map, as seen by scripts.
+#[derive(Clone)]
+struct MapHandle {
+    snapshot: SharedSnapshot,
+    id: usize,
+}
+
+/// An event on a map, as seen by scripts.
+#[derive(Clone)]
+struct EventHandle {
+    snapshot: SharedSnapshot,
+    map_id: usize,
+    id: usize,
+}
+
+impl MapHandle {
+    fn with_map<R>(&self, f: impl FnOnce(&mut MapSnapshot) -> RhaiResult<R>) -> RhaiResult<R> {
+        let mut snapshot = self.snapshot.lock();
+        let map = snapshot
+            .maps
+            .get_mut(&self.id)
+            .ok_or_else(|| format!("map {} does not exist", self.id))?;
+        f(map)
+    }
+
+    fn tile_position(
+        map: &MapSnapshot,
+        x: i64,
+        y: i64,
+        z: i64,
+    ) -> RhaiResult<(usize, usize, usize)> {
+        let position = (to_index(x, "x")?, to_index(y, "y")?, to_index(z, "layer")?);
+        if position.0 >= map.data.xsize()
+            || position.1 >= map.data.ysize()
+            || position.2 >= map.data.zsize()
+        {
+            return Err(format!("tile position {position:?} is outside of the map").into());
+        }
+        Ok(position)
+    }
+
+    fn tile(&mut self, x: i64, y: i64, z: i64) -> RhaiResult<i64> {
+        self.with_map(|map| {
+            let position = Self::tile_position(map, x, y, z)?;
+            Ok(map.data[position] as i64)
+        })
+    }
+
+    fn set_tile(&mut self, x: i64, y: i64, z: i64, tile: i64) -> RhaiResult<()> {
+        let tile = i16::try_from(tile).map_err(|_| format!("{tile} is not a valid tile ID"))?;
+        self.with_map(|map| {
+            let position = Self::tile_position(map, x, y, z)?;
+            if map.data[position] != tile {
+                map.data[position] = tile;
+                map.changed_tiles.insert(position);
+                map.modified = true;
+            }
+            Ok(())
+        })
+    }
+
+    fn replace_tiles(&mut self, z: i64, from: i64, to: i64) -> RhaiResult<i64> {
+        let from = i16::try_from(from).map_err(|_| format!("{from} is not a valid tile ID"))?;
+        let to = i16::try_from(to).map_err(|_| format!("{to} is not a valid tile ID"))?;
+        self.with_map(|map| {
+            let z = to_index(z, "layer")?;
+            if z >= map.data.zsize() {
+                return Err(format!("layer {z} does not exist").into());
+            }
+
+            let mut count = 0;
+            for y in 0..map.data.ysize() {
+                for x in 0..map.data.xsize() {
+                    if from != to && map.data[(x, y, z)] == from {
+                        map.data[(x, y, z)] = to;
+                        map.changed_tiles.insert((x, y, z));
+                        count += 1;
+                    }
+                }
+            }
+            if count != 0 {
+                map.modified = true;
+            }
+            Ok(count as i64)
+        })
+    }
+
+    fn events(&mut self) -> RhaiResult<Array> {
+        let ids = self.with_map(|map| Ok(map.events.keys().copied().collect::<Vec<_>>()))?;
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                Dynamic::from(EventHandle {
+                    snapshot: self.snapshot.clone(),
+                    map_id: self.id,
+                    id,
+                })
+            })
+            .collect())
+    }
+
+    fn event(&mut self, id: i64) -> RhaiResult<EventHandle> {
+        let id = to_index(id, "event ID")?;
+        self.with_map(|map| {
+            if map.events.contains_key(&id) {
+                Ok(())
+            } else {
+                Err(format!("event {id} does not exist").into())
+            }
+        })?;
+        Ok(EventHandle {
+            snapshot: self.snapshot.clone(),
+            map_id: self.id,
+            id,
+        })
+    }
+
+    fn mark_modified(&mut self) -> RhaiResult<()> {
+        self.with_map(|map| {
+            map.modified = true;
+            Ok(())
+        })
+    }
+}
+
+impl EventHandle {
+    fn with_event<R>(
+        &self,
+        f: impl FnOnce(&mut EventSnapshot, &mut Vec<String>) -> RhaiResult<R>,
+    ) -> RhaiResult<R> {
+        let mut snapshot = self.snapshot.lock();
+        let Snapshot { maps, changes, .. } = &mut *snapshot;
+        let map = maps
+            .get_mut(&self.map_id)
+            .ok_or_else(|| format!("map {} does not exist", self.map_id))?;
+        let event = map
+            .events
+            .get_mut(&self.id)
+            .ok_or_else(|| format!("event {} does not exist", self.id))?;
+
+        let mut new_changes = Vec::new();
+        let result = f(event, &mut new_changes)?;
+        if !new_changes.is_empty() {
+            map.modified = true;
+            changes.extend(
+                new_changes.into_iter().map(|change| {
+                    format!("Map {:0>3}, event {:0>3}: {change}", self.map_id, self.id)
+                }),
+            );
+        }
+        Ok(result)
+    }
+
+    fn name(&mut self) -> RhaiResult<String> {
+        self.with_event(|event, _| Ok(event.name.clone()))
+    }
+
+    fn set_name(&mut self, name: String) -> RhaiResult<()> {
+        self.with_event(|event, changes| {
+            if event.name != name {
+                changes.push(format!("name {:?} -> {name:?}", event.name));
+                event.name = name;
+            }
+            Ok(())
+        })
+    }
+
+    fn x(&mut self) -> RhaiResult<i64> {
+        self.with_event(|event, _| Ok(event.x as i64))
+    }
+
+    fn set_x(&mut self, x: i64) -> RhaiResult<()> {
+        let x = i32::try_from(x).map_err(|_| format!("{x} is not a valid position"))?;
+        self.with_event(|event, changes| {
+            if event.x != x {
+                changes.push(format!("x {} -> {x}", event.x));
+                event.x = x;
+            }
+            Ok(())
+        })
+    }
+
+    fn y(&mut self) -> RhaiResult<i64> {
+        self.with_event(|event, _| Ok(event.y as i64))
+    }
+
+    fn set_y(&mut self, y: i64) -> RhaiResult<()> {
+        let y = i32::try_from(y).map_err(|_| format!("{y} is not a valid position"))?;
+        self.with_event(|event, changes| {
+            if event.y != y {
+                changes.push(format!("y {} -> {y}", event.y));
+                event.y = y;
+            }
+            Ok(())
+        })
+    }
+
+    fn page_count(&mut self) -> RhaiResult<i64> {
+        self.with_event(|event, _| Ok(event.through.len() as i64))
+    }
+
+    fn through(&mut self) -> RhaiResult<bool> {
+        self.with_event(|event, _| Ok(event.through.iter().all(|&through| through)))
+    }
+
+    fn set_through(&mut self, through: bool) -> RhaiResult<()> {
+        self.with_event(|event, changes| {
+            for (page, page_through) in event.through.iter_mut().enumerate() {
+                if *page_through != through {
+                    changes.push(format!("page {} through {through}", page + 1));
+                    *page_through = through;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn page_through(&mut self, page: i64) -> RhaiResult<bool> {
+        let page = to_index(page, "page")?;
+        self.with_event(|event, _| {
+            event
+                .through
+                .get(page)
+                .copied()
+                .ok_or_else(|| format!("page {page} does not exist").into())
+        })
+    }
+
+    fn set_page_through(&mut self, page: i64, through: bool) -> RhaiResult<()> {
+        let page = to_index(page, "page")?;
+        self.with_event(|event, changes| {
+            let page_through = event
+                .through
+                .get_mut(page)
+                .ok_or_else(|| format!("page {page} does not exist"))?;
+            if *page_through != through {
+                changes.push(format!("page {} through {through}", page + 1));
+                *page_through = through;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Creates a script engine with the map editing API registered on it.
+///
+/// Every function operates on `snapshot`; `print` and `debug` output is sent to `on_output`.
+pub fn create_engine(
+    snapshot: SharedSnapshot,
+    on_output: impl Fn(String) + Send + Sync + Clone + 'static,
+) -> Engine {
+    let mut engine = Engine::new();
+
+    let print_output = on_output.clone();
+    engine.on_print(move |text| print_output(text.to_string()));
+    engine.on_debug(move |text, _source, position| on_output(format!("[{position}] {text}")));
+
+    engine
+        .register_type_with_name::<MapHandle>("Map")
+        .register_get("id", |map: &mut MapHandle| map.id as i64)
+        .register_get("name", |map: &mut MapHandle| {
+            map.with_map(|map| Ok(map.name.clone()))
+        })
+        .register_get("width", |map: &mut MapHandle| {
+            map.with_map(|map| Ok(map.data.xsize() as i64))
+        })
+        .register_get("height", |map: &mut MapHandle| {
+            map.with_map(|map| Ok(map.data.ysize() as i64))
+        })
+        .register_get("layers", |map: &mut MapHandle| {
+            map.with_map(|map| Ok(map.data.zsize() as i64))
+        })
+        .register_get("tileset_id", |map: &mut MapHandle| {
+            map.with_map(|map| Ok(map.tileset_id as i64))
+        })
+        .register_fn("tile", MapHandle::tile)
+        .register_fn("set_tile", MapHandle::set_tile)
+        .register_fn("replace_tiles", MapHandle::replace_tiles)
+        .register_fn("events", MapHandle::events)
+        .register_fn("event", MapHandle::event)
+        .register_fn("mark_modified", MapHandle::mark_modified);
+
+    engine
+        .register_type_with_name::<EventHandle>("Event")
+        .register_get("id", |event: &mut EventHandle| event.id as i64)
+        .register_get("map_id", |event: &mut EventHandle| event.map_id as i64)
+        .register_get_set("name", EventHandle::name, EventHandle::set_name)
+        .register_get_set("x", EventHandle::x, EventHandle::set_x)
+        .register_get_set("y", EventHandle::y, EventHandle::set_y)
+        .register_get("page_count", EventHandle::page_count)
+        .register_get_set("through", EventHandle::through, EventHandle::set_through)
+        .register_fn("through", EventHandle::page_through)
+        .register_fn("set_through", EventHandle::set_page_through);
+
+    let map_snapshot = snapshot.clone();
+    engine.register_fn("map_ids", move || -> Array {
+        map_snapshot
+            .lock()
+            .maps
+            .keys()
+            .map(|&id| Dynamic::from(id as i64))
+            .collect()
+    });
+
+    let map_snapshot = snapshot.clone();
+    engine.register_fn("maps", move || -> Array {
+        let ids = map_snapshot.lock().maps.keys().copied().collect::<Vec<_>>();
+        ids.into_iter()
+            .map(|id| {
+                Dynamic::from(MapHandle {
+                    snapshot: map_snapshot.clone(),
+                    id,
+                })
+            })
+            .collect()
+    });
+
+    let map_snapshot = snapshot.clone();
+    engine.register_fn("map", move |id: i64| -> RhaiResult<MapHandle> {
+        let id = to_index(id, "map ID")?;
+        if !map_snapshot.lock().maps.contains_key(&id) {
+            return Err(format!("map {id} does not exist").into());
+        }
+        Ok(MapHandle {
+            snapshot: map_snapshot.clone(),
+            id,
+        })
+    });
+
+    let map_snapshot = snapshot.clone();
+    engine.register_fn("mark_modified", move |id: i64| -> RhaiResult<()> {
+        MapHandle {
+            snapshot: map_snapshot.clone(),
+            id: to_index(id, "map ID")?,
+        }
+        .mark_modified()
+    });
+
+    let database_snapshot = snapshot.clone();
+    engine.register_fn("database_names", move |kind: &str| -> RhaiResult<Array> {
+        database_snapshot
+            .lock()
+            .database
+            .get(kind)
+            .map(|names| names.iter().cloned().map(Dynamic::from).collect())
+            .ok_or_else(|| {
+                format!(
+                    "unknown database kind {kind:?} (expected one of {})",
+                    DATABASE_KINDS.join(", ")
+                )
+                .into()
+            })
+    });
+
+    engine.register_fn(
+        "database_name",
+        move |kind: &str, id: i64| -> RhaiResult<String> {
+            let index =
+                usize::try_from(id - 1).map_err(|_| format!("{kind} has no entry with ID {id}"))?;
+            snapshot
+                .lock()
+                .database
+                .get(kind)
+                .ok_or_else(|| format!("unknown database kind {kind:?}"))?
+                .get(index)
+                .cloned()
+                .ok_or_else(|| format!("{kind} has no entry with ID {id}").into())
+        },
+    );
+
+    engine
+}
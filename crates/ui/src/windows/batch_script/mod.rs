@@ -0,0 +1,376 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use parking_lot::Mutex;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+mod api;
+
+const API_REFERENCE: &str = "\
+map_ids() / maps() / map(id)        all map IDs / all maps / one map
+map.id, name, width, height, layers, tileset_id
+map.tile(x, y, layer)               tile ID at a position
+map.set_tile(x, y, layer, tile)     change a tile
+map.replace_tiles(layer, from, to)  replace every `from` tile on a layer, returns the count
+map.events() / map.event(id)        all events / one event on the map
+event.id, name, x, y, page_count, through (get and set, through applies to every page)
+event.through(page) / event.set_through(page, value)
+mark_modified(map_id) / map.mark_modified()
+database_names(kind) / database_name(kind, id)
+    kinds: actors, animations, armors, classes, common_events, enemies, items,
+           skills, states, tilesets, troops, weapons, switches, variables
+print(value)                        write to the output pane
+fn for_each_map(map) { ... }        if defined, called for every map after the rest of the script;
+                                    the script can be cancelled between calls";
+
+/// How long the window spends copying maps or running a script each frame when it can't do so in
+/// the background.
+const FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// A window for running scripts that edit the project's maps in bulk.
+pub struct Window {
+    code: String,
+    dry_run: bool,
+    output: Arc<Mutex<Vec<String>>>,
+    cancel: Arc<AtomicBool>,
+    state: State,
+}
+
+enum State {
+    Idle,
+    /// The project's maps are being copied for the script to edit. Maps are loaded from the data
+    /// cache, which can only be done on the main thread, so a few are copied every frame.
+    Preparing {
+        snapshot: api::Snapshot,
+        /// The maps that still have to be copied, last one first
+        remaining: Vec<usize>,
+        total: usize,
+    },
+    /// The script is running on a thread of its own.
+    #[cfg(not(target_arch = "wasm32"))]
+    Running(poll_promise::Promise<color_eyre::Result<api::RunResult>>),
+    /// The script is running on the main thread a slice at a time, since web builds have no
+    /// threads to spare.
+    #[cfg(target_arch = "wasm32")]
+    Running(Box<api::ScriptRun>),
+}
+
+impl State {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn running(run: api::ScriptRun) -> Self {
+        let (sender, promise) = poll_promise::Promise::new();
+        std::thread::spawn(move || sender.send(run.run_to_completion()));
+        State::Running(promise)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn running(run: api::ScriptRun) -> Self {
+        State::Running(Box::new(run))
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            code: String::new(),
+            dry_run: true,
+            output: Arc::default(),
+            cancel: Arc::default(),
+            state: State::Idle,
+        }
+    }
+}
+
+impl Window {
+    fn print(&self, line: impl Into<String>) {
+        self.output.lock().push(line.into());
+    }
+
+    fn run(&mut self, update_state: &luminol_core::UpdateState<'_>) {
+        self.cancel.store(false, Ordering::Relaxed);
+
+        self.print(if self.dry_run {
+            "Running script (dry run)..."
+        } else {
+            "Running script..."
+        });
+
+        let mut remaining = update_state
+            .data
+            .map_infos()
+            .data
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        remaining.sort_unstable_by(|a, b| b.cmp(a));
+        self.state = State::Preparing {
+            snapshot: api::Snapshot::new(update_state),
+            total: remaining.len(),
+            remaining,
+        };
+    }
+
+    /// Starts running the script on a snapshot that has all of the maps copied into it.
+    fn start(&self, snapshot: api::Snapshot) -> State {
+        let output = self.output.clone();
+        let run = match api::ScriptRun::new(
+            &self.code,
+            snapshot,
+            move |line| output.lock().push(line),
+            self.cancel.clone(),
+        ) {
+            Ok(run) => run,
+            Err(error) => {
+                self.print(format!("Error: {error}"));
+                return State::Idle;
+            }
+        };
+
+        State::running(run)
+    }
+
+    /// Does as much of the current run as this frame allows.
+    fn update(&mut self, ctx: &egui::Context, update_state: &mut luminol_core::UpdateState<'_>) {
+        self.state = match std::mem::replace(&mut self.state, State::Idle) {
+            State::Idle => State::Idle,
+
+            State::Preparing { .. } if self.cancel.load(Ordering::Relaxed) => {
+                self.print("Error: cancelled by the user");
+                State::Idle
+            }
+            State::Preparing {
+                mut snapshot,
+                mut remaining,
+                total,
+            } => {
+                let started = web_time::Instant::now();
+                while let Some(id) = remaining.pop() {
+                    snapshot.add_map(update_state, id);
+                    if started.elapsed() >= FRAME_BUDGET {
+                        break;
+                    }
+                }
+                ctx.request_repaint();
+
+                if remaining.is_empty() {
+                    self.start(snapshot)
+                } else {
+                    State::Preparing {
+                        snapshot,
+                        remaining,
+                        total,
+                    }
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            State::Running(promise) => match promise.try_take() {
+                Ok(result) => {
+                    self.finish(result, update_state);
+                    State::Idle
+                }
+                Err(promise) => {
+                    ctx.request_repaint();
+                    State::Running(promise)
+                }
+            },
+            #[cfg(target_arch = "wasm32")]
+            State::Running(mut run) => match run.step(FRAME_BUDGET) {
+                Some(result) => {
+                    self.finish(result, update_state);
+                    State::Idle
+                }
+                None => {
+                    ctx.request_repaint();
+                    State::Running(run)
+                }
+            },
+        };
+    }
+
+    fn finish(
+        &mut self,
+        result: color_eyre::Result<api::RunResult>,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => {
+                self.print(format!("Error: {error}"));
+                return;
+            }
+        };
+
+        if !result.value.is_empty() {
+            self.print(format!("=> {}", result.value));
+        }
+
+        let summary = result.snapshot.summary();
+        if summary.is_empty() {
+            self.print("The script didn't change anything");
+            return;
+        }
+        for line in summary {
+            self.print(line);
+        }
+
+        if self.dry_run {
+            self.print("Dry run, no changes were applied");
+            return;
+        }
+
+        let applied = result.snapshot.apply(update_state);
+        self.print(format!("Applied changes to {} map(s)", applied.count));
+        for id in &applied.skipped {
+            self.print(format!(
+                "Map {id:0>3} is open in a tab and was left unchanged, close it and run the script again to change it"
+            ));
+        }
+        if applied.skipped.is_empty() {
+            luminol_core::info!(
+                update_state.toasts,
+                format!("Batch script changed {} map(s)", applied.count)
+            );
+        } else {
+            luminol_core::warn!(
+                update_state.toasts,
+                format!(
+                    "Batch script changed {} map(s) and skipped {} map(s) that are open in tabs",
+                    applied.count,
+                    applied.skipped.len()
+                )
+            );
+        }
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("Batch Script")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        self.update(ctx, update_state);
+        let is_running = !matches!(self.state, State::Idle);
+        let progress = match &self.state {
+            State::Preparing {
+                remaining, total, ..
+            } => Some((total - remaining.len(), *total)),
+            _ => None,
+        };
+
+        egui::Window::new("Batch Script")
+            .id(self.id())
+            .open(open)
+            .default_size([600., 500.])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if is_running {
+                        if ui.button("Cancel").clicked() {
+                            self.cancel.store(true, Ordering::Relaxed);
+                        }
+                        if let Some((copied, total)) = progress {
+                            ui.add(
+                                egui::ProgressBar::new(copied as f32 / total.max(1) as f32)
+                                    .desired_width(150.)
+                                    .text(format!("Copying maps {copied}/{total}")),
+                            );
+                        } else {
+                            ui.spinner();
+                        }
+                    } else if ui.button("Run").clicked() {
+                        self.run(update_state);
+                    }
+
+                    ui.add_enabled(
+                        !is_running,
+                        egui::Checkbox::new(&mut self.dry_run, "Dry run"),
+                    )
+                    .on_hover_text("Print the changes the script would make without applying them");
+
+                    if ui.button("Clear output").clicked() {
+                        self.output.lock().clear();
+                    }
+                });
+
+                ui.collapsing("API reference", |ui| {
+                    ui.label(egui::RichText::new(API_REFERENCE).monospace());
+                });
+
+                ui.separator();
+
+                egui::TopBottomPanel::bottom("batch_script_output")
+                    .resizable(true)
+                    .default_height(150.)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::both()
+                            .auto_shrink([false; 2])
+                            .stick_to_bottom(true)
+                            .show(ui, |ui| {
+                                for line in self.output.lock().iter() {
+                                    ui.label(egui::RichText::new(line).monospace());
+                                }
+                            });
+                    });
+
+                let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
+                    let mut layout_job = crate::components::syntax_highlighting::highlight(
+                        ui.ctx(),
+                        update_state.global_config.theme,
+                        string,
+                        "rs",
+                    );
+                    layout_job.wrap.max_width = wrap_width;
+                    ui.fonts(|f| f.layout_job(layout_job))
+                };
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        ui.add_enabled(
+                            !is_running,
+                            egui::TextEdit::multiline(&mut self.code)
+                                .code_editor()
+                                .desired_rows(10)
+                                .lock_focus(true)
+                                .desired_width(f32::INFINITY)
+                                .hint_text("for map in maps() { map.replace_tiles(2, 384, 392); }")
+                                .layouter(&mut layouter),
+                        );
+                    });
+            });
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
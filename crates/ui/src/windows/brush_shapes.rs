@@ -0,0 +1,203 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+use luminol_config::project::BrushShape;
+
+const CELL_SIZE: f32 = 24.;
+
+/// Edits the custom brush shapes stored in the project config.
+/// The shape selected here is also the one the custom shape brush draws with.
+#[derive(Default)]
+pub struct Window {
+    /// Whether cells dragged over are being drawn or erased, while the mouse is held down.
+    paint_value: Option<bool>,
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_brush_shapes")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let Some(config) = update_state.project_config.as_mut() else {
+            *open = false;
+            return;
+        };
+        let shapes = &mut config.project.brush_shapes;
+        let selected = &mut update_state.toolbar.brush_shape;
+
+        let mut modified = false;
+
+        egui::Window::new("Brush Shapes")
+            .id(self.id())
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(160.);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(CELL_SIZE * BrushShape::MAX_SIZE as f32)
+                            .show(ui, |ui| {
+                                for (i, shape) in shapes.iter().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        let is_selected = *selected == i;
+                                        let height = ui.spacing().interact_size.y;
+                                        let icon = crate::components::brush_shape_icon(
+                                            ui,
+                                            shape,
+                                            is_selected,
+                                            height,
+                                        );
+                                        let label = ui.selectable_label(is_selected, &shape.name);
+                                        if icon.clicked() || label.clicked() {
+                                            *selected = i;
+                                        }
+                                    });
+                                }
+                            });
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Add").clicked() {
+                                shapes.push(BrushShape::new(
+                                    format!("Shape {}", shapes.len() + 1),
+                                    3,
+                                ));
+                                *selected = shapes.len() - 1;
+                                modified = true;
+                            }
+                            if ui
+                                .add_enabled(*selected < shapes.len(), egui::Button::new("Delete"))
+                                .clicked()
+                            {
+                                shapes.remove(*selected);
+                                *selected = selected.saturating_sub(1);
+                                modified = true;
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
+                    let Some(shape) = shapes.get_mut(*selected) else {
+                        ui.label("Add a shape to start drawing one");
+                        return;
+                    };
+                    // The mask could have the wrong length if the config was edited by hand
+                    if shape.mask.len() != shape.size * shape.size {
+                        shape.resize(shape.size);
+                    }
+
+                    ui.vertical(|ui| {
+                        modified |= ui.text_edit_singleline(&mut shape.name).changed();
+
+                        let mut size = shape.size;
+                        ui.add(egui::Slider::new(&mut size, 1..=BrushShape::MAX_SIZE).text("Size"));
+                        if size != shape.size {
+                            shape.resize(size);
+                            modified = true;
+                        }
+
+                        modified |= self.mask_ui(ui, shape);
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Fill").clicked() {
+                                shape.mask.fill(true);
+                                modified = true;
+                            }
+                            if ui.button("Clear").clicked() {
+                                shape.mask.fill(false);
+                                modified = true;
+                            }
+                        });
+                        ui.weak("Click or drag over cells to toggle them");
+                    });
+                });
+            });
+
+        if modified {
+            update_state.modified.set(true);
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
+
+impl Window {
+    /// Shows the cells of the mask. Returns whether any of them were changed.
+    fn mask_ui(&mut self, ui: &mut egui::Ui, shape: &mut BrushShape) -> bool {
+        let (rect, response) = ui.allocate_exact_size(
+            egui::Vec2::splat(CELL_SIZE * shape.size as f32),
+            egui::Sense::click_and_drag(),
+        );
+
+        let mut modified = false;
+        if response.is_pointer_button_down_on() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let cell = ((pos - rect.min) / CELL_SIZE).floor();
+                let (x, y) = (cell.x as usize, cell.y as usize);
+                if cell.x >= 0. && cell.y >= 0. && x < shape.size && y < shape.size {
+                    // The first cell pressed decides whether the drag draws or erases
+                    let value = *self.paint_value.get_or_insert(!shape.get(x, y));
+                    if shape.get(x, y) != value {
+                        shape.mask[x + y * shape.size] = value;
+                        modified = true;
+                    }
+                }
+            }
+        } else {
+            self.paint_value = None;
+        }
+
+        let visuals = ui.visuals();
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0., visuals.extreme_bg_color);
+        for (y, x) in (0..shape.size).cartesian_product(0..shape.size) {
+            let cell_rect = egui::Rect::from_min_size(
+                rect.min + egui::vec2(x as f32, y as f32) * CELL_SIZE,
+                egui::Vec2::splat(CELL_SIZE),
+            );
+            if shape.get(x, y) {
+                painter.rect_filled(cell_rect.shrink(1.), 0., visuals.selection.bg_fill);
+            }
+            painter.rect_stroke(cell_rect, 0., visuals.widgets.noninteractive.bg_stroke);
+        }
+
+        // Mark the center, which is where the cursor is when stamping the shape
+        let center =
+            rect.min + egui::Vec2::splat((shape.size / 2) as f32 * CELL_SIZE + CELL_SIZE / 2.);
+        painter.circle_filled(center, 3., visuals.strong_text_color());
+
+        modified
+    }
+}
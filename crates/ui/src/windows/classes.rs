@@ -26,6 +26,7 @@ use crate::components::{
     CollapsingView, DatabaseView, EnumComboBox, Field, IdVecSelection, OptionalIdComboBox,
     RankSelection, UiExt,
 };
+use luminol_config::recent_edits::{Database, EditLocation};
 
 #[derive(Default)]
 pub struct Window {
@@ -295,6 +296,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             classes.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Classes,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(classes);
@@ -128,6 +128,8 @@ impl luminol_core::Tab for CommonEventTab {
                 let mut common_events = update_state.data.common_events();
 
                 common_events.data[self.event.id - 1] = self.event.clone();
+                common_events.modified = true;
+                update_state.modified.set(true);
             }
 
             ui.label("Name");
@@ -136,15 +138,21 @@ impl luminol_core::Tab for CommonEventTab {
 
         ui.separator();
 
+        self.command_view.outline_ui(ui, &self.event.list);
+
         egui::ScrollArea::both()
             .auto_shrink([false; 2])
             .show(ui, |ui| {
                 self.command_view.ui(
                     ui,
                     &update_state.project_config.as_ref().unwrap().command_db,
+                    &update_state.data.system(),
                     &mut self.event.list,
                 );
             });
+
+        self.command_view
+            .editor_window(ui.ctx(), update_state, &mut self.event.list);
     }
 
     fn requires_filesystem(&self) -> bool {
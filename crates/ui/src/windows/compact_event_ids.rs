@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::UiExt;
+
+/// Makes the event IDs on every map contiguous. Maps open in a tab are left alone so the change
+/// can go through their undo history instead.
+#[derive(Default)]
+pub struct Window {
+    /// The maps with gaps in their event IDs, and how many events on each would be renumbered.
+    maps: Option<Vec<MapGaps>>,
+}
+
+struct MapGaps {
+    id: usize,
+    name: String,
+    count: usize,
+}
+
+impl Window {
+    fn scan(update_state: &luminol_core::UpdateState<'_>) -> Vec<MapGaps> {
+        let mut map_infos: Vec<_> = (update_state.data.map_infos().data.iter())
+            .map(|(&id, info)| (id, info.name.clone()))
+            .collect();
+        map_infos.sort_unstable_by_key(|(id, _)| *id);
+
+        map_infos
+            .into_iter()
+            .filter_map(|(id, name)| {
+                let map = update_state.data.get_or_load_map(
+                    id,
+                    update_state.filesystem,
+                    update_state.project_config.as_ref().unwrap(),
+                );
+                let count = map.event_id_compaction().len();
+                (count > 0).then_some(MapGaps { id, name, count })
+            })
+            .collect()
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_compact_event_ids")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut compact = false;
+
+        egui::Window::new("Compact Event IDs")
+            .id(self.id())
+            .default_width(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Renumbers the events on each map so their IDs have no gaps, and updates the \
+                    event commands on that map that refer to them.",
+                );
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "Script calls, and common events or troops that refer to map events by ID, \
+                    are not updated.",
+                );
+
+                if ui.button("Scan again").clicked() {
+                    self.maps = None;
+                }
+                let maps = self.maps.get_or_insert_with(|| Self::scan(update_state));
+
+                ui.separator();
+
+                if maps.is_empty() {
+                    ui.label("The event IDs on every map have no gaps");
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        for (i, map) in maps.iter().enumerate() {
+                            ui.with_stripe(i % 2 != 0, |ui| {
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(format!("Map {:0>3}: {}", map.id, map.name));
+                                    if crate::tabs::map::is_open(ui.ctx(), map.id) {
+                                        ui.weak(
+                                            "open in a tab, use Compact event IDs in its toolbar \
+                                        instead so that it can be undone",
+                                        );
+                                    } else {
+                                        ui.weak(format!("{} events to renumber", map.count));
+                                    }
+                                });
+                            });
+                        }
+                    });
+
+                ui.separator();
+
+                compact = ui.button("Compact closed maps").clicked();
+            });
+
+        if compact {
+            let mut count = 0;
+            for gaps in self.maps.take().into_iter().flatten() {
+                if crate::tabs::map::is_open(ctx, gaps.id) {
+                    continue;
+                }
+                let mut map = update_state.data.get_map(gaps.id);
+                let renumbering = map.event_id_compaction();
+                map.renumber_events(&renumbering);
+                map.modified = true;
+                count += 1;
+            }
+            if count > 0 {
+                update_state.modified.set(true);
+            }
+            luminol_core::info!(
+                update_state.toasts,
+                format!("Compacted the event IDs on {count} maps")
+            );
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
@@ -98,6 +98,9 @@ fn converting_to_string(
 
 const FORMAT_WARNING: &str = "Luminol will need to convert your project.\nThis is not 100% safe yet, make backups!\nPress OK to continue.";
 
+/// Magenta, the usual color key of old RPG Maker assets.
+const DEFAULT_TRANSPARENT_COLOR: [u8; 3] = [255, 0, 255];
+
 // Mostly async, opening files is not however.
 // We should probably provide async fns for that
 async fn convert_nil_padded<T>(
@@ -168,6 +171,36 @@ where
     Ok(data)
 }
 
+async fn convert_map_infos(
+    from: FormatHandler,
+    to: FormatHandler,
+    read_buf: &mut Vec<u8>,
+    write_buf: &mut Vec<u8>,
+    host: &luminol_filesystem::host::FileSystem,
+) -> color_eyre::Result<std::collections::HashMap<usize, rpg::MapInfo>> {
+    read_buf.clear();
+    write_buf.clear();
+
+    let mut file = host.open_file(from.path_for("MapInfos"), OpenFlags::Read)?;
+    file.read_to_end(read_buf).await?;
+
+    let data: std::collections::HashMap<usize, rpg::MapInfo> = from.read_data_from(read_buf)?;
+
+    // Map infos are stored in a hash map, so sort them to keep the output deterministic
+    to.write_data_to(&luminol_data::helpers::SortedMap(&data), write_buf)?;
+
+    let mut file = host.open_file(
+        to.path_for("MapInfos"),
+        OpenFlags::Write | OpenFlags::Truncate | OpenFlags::Create,
+    )?;
+    file.write_all(write_buf).await?;
+    file.flush().await?;
+
+    from.remove_file(host, "MapInfos")?;
+
+    Ok(data)
+}
+
 fn convert_project(
     config: &mut luminol_config::project::Config,
     selected_data_format: luminol_config::DataFormat,
@@ -245,8 +278,7 @@ fn convert_project(
         convert_regular::<rpg::System>(from, to, read_buf, write_buf, "System", host).await?;
 
         converting_progress.store(CONVERTING_MAPINFOS, Ordering::Relaxed);
-        let mapinfos: std::collections::HashMap<usize, rpg::MapInfo> =
-            convert_regular(from, to, read_buf, write_buf, "MapInfos", host).await?;
+        let mapinfos = convert_map_infos(from, to, read_buf, write_buf, host).await?;
 
         for (index, map_id) in mapinfos.keys().copied().enumerate() {
             converting_progress.store(CONVERTING_MAPINFOS + index, Ordering::Relaxed);
@@ -279,6 +311,7 @@ impl luminol_core::Window for Window {
         let mut modified = false;
 
         egui::Window::new("Project Config")
+            .id(self.id())
             .open(open)
             .show(ctx, |ui| {
                 ui.label("Editor Settings");
@@ -320,7 +353,14 @@ impl luminol_core::Window for Window {
                     if let luminol_config::DataFormat::Json { pretty }
                     | luminol_config::DataFormat::Ron { pretty } = &mut self.selected_data_format
                     {
-                        ui.checkbox(pretty, "Pretty Print").on_hover_text("This will make the data files human-readable, but significantly larger!");
+                        ui.horizontal(|ui| {
+                            ui.label("Layout");
+                            ui.radio_value(pretty, false, "Compact")
+                                .on_hover_text("Write each data file with as little whitespace as possible.");
+                            ui.radio_value(pretty, true, "Pretty")
+                                .on_hover_text("This will make the data files human-readable, but significantly larger!");
+                        });
+                        ui.weak("Entries are always written in the same order, so saving unchanged data produces identical files.");
                     }
 
                     if self.selected_data_format != config.project.data_format {
@@ -399,6 +439,29 @@ impl luminol_core::Window for Window {
                                     .changed();
                             }
                         });
+
+                    ui.horizontal(|ui| {
+                        let transparent_color = &mut config.project.transparent_color;
+                        let mut enabled = transparent_color.is_some();
+                        if ui
+                            .checkbox(&mut enabled, "Transparent color")
+                            .on_hover_text(
+                                "Treat this color as transparent in images without an alpha channel, like old BMP assets.\nYou may have to reopen editors for changes to take effect",
+                            )
+                            .changed()
+                        {
+                            *transparent_color = enabled.then_some(DEFAULT_TRANSPARENT_COLOR);
+                            modified = true;
+                        }
+
+                        let mut color = transparent_color.unwrap_or(DEFAULT_TRANSPARENT_COLOR);
+                        ui.add_enabled_ui(enabled, |ui| {
+                            if ui.color_edit_button_srgb(&mut color).changed() {
+                                *transparent_color = Some(color);
+                                modified = true;
+                            }
+                        });
+                    });
                 });
 
                 ui.label("Game.ini settings");
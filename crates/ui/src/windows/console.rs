@@ -23,7 +23,20 @@
 // Program grant you additional permission to convey the resulting work.
 
 pub struct Window {
-    term: luminol_term::widget::ProcessTerminal,
+    id: egui::Id,
+    tabs: Vec<Tab>,
+    selected_tab: usize,
+    /// Whether this console shows the logs of background tasks as they're started.
+    shows_task_logs: bool,
+}
+
+enum Tab {
+    Process(luminol_term::widget::ProcessTerminal),
+    /// The read-only log of a background task, kept until the user closes it.
+    TaskLog {
+        term: luminol_term::widget::ChannelTerminal,
+        status: luminol_core::tasks::TaskStatus,
+    },
 }
 
 impl Window {
@@ -31,20 +44,51 @@ impl Window {
         exec: luminol_term::widget::ExecOptions,
         update_state: &luminol_core::UpdateState<'_>,
     ) -> std::io::Result<Self> {
+        // TODO
+        let term = luminol_term::widget::Terminal::process(exec, update_state)?;
         Ok(Self {
-            // TODO
-            term: luminol_term::widget::Terminal::process(exec, update_state)?,
+            id: term.id,
+            tabs: vec![Tab::Process(term)],
+            selected_tab: 0,
+            shows_task_logs: false,
         })
     }
+
+    /// Creates the console that background tasks stream their logs to, with a tab for each task.
+    pub fn task_logs() -> Self {
+        Self {
+            id: egui::Id::new("luminol_task_log_console"),
+            tabs: Vec::new(),
+            selected_tab: 0,
+            shows_task_logs: true,
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self.tabs.first() {
+            Some(Tab::Process(term)) => &term.title,
+            _ => "Task Logs",
+        }
+    }
+}
+
+impl Tab {
+    fn name(&self) -> String {
+        match self {
+            Tab::Process(term) => term.title.clone(),
+            Tab::TaskLog { term, status } if status.is_finished() => term.title.clone(),
+            Tab::TaskLog { term, .. } => format!("{} (running)", term.title),
+        }
+    }
 }
 
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
-        self.term.id
+        self.id
     }
 
     fn requires_filesystem(&self) -> bool {
-        true
+        !self.shows_task_logs
     }
 
     fn show(
@@ -53,11 +97,70 @@ impl luminol_core::Window for Window {
         open: &mut bool,
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
-        egui::Window::new(&self.term.title)
-            .id(self.term.id)
+        if self.shows_task_logs {
+            for log in update_state.tasks.take_new_logs() {
+                let mut term = luminol_term::widget::Terminal::channel(
+                    log.bytes,
+                    &update_state.global_config.terminal,
+                );
+                term.id = egui::Id::new("luminol_task_log").with(std::time::Instant::now());
+                term.title = log.name;
+                self.tabs.push(Tab::TaskLog {
+                    term,
+                    status: log.status,
+                });
+                self.selected_tab = self.tabs.len() - 1;
+            }
+        }
+
+        // Logs are parsed even when their tab isn't shown, so that switching to a long log doesn't
+        // have to parse all of it at once
+        for tab in self.tabs.iter_mut() {
+            if let Tab::TaskLog { term, .. } = tab {
+                term.update();
+            }
+        }
+
+        let title = self.title().to_string();
+        egui::Window::new(title)
+            .id(self.id)
             .open(open)
             .show(ctx, |ui| {
-                if let Err(e) = self.term.ui(update_state, ui) {
+                if self.tabs.len() > 1 || self.shows_task_logs {
+                    let mut closed_tab = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for (index, tab) in self.tabs.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected_tab, index, tab.name());
+                            if matches!(tab, Tab::TaskLog { .. })
+                                && ui
+                                    .small_button("×")
+                                    .on_hover_text("Close this log")
+                                    .clicked()
+                            {
+                                closed_tab = Some(index);
+                            }
+                            ui.separator();
+                        }
+                    });
+                    if let Some(index) = closed_tab {
+                        self.tabs.remove(index);
+                        if self.selected_tab > index {
+                            self.selected_tab -= 1;
+                        }
+                    }
+                    ui.separator();
+                }
+
+                self.selected_tab = self.selected_tab.min(self.tabs.len().saturating_sub(1));
+                let result = match self.tabs.get_mut(self.selected_tab) {
+                    Some(Tab::Process(term)) => term.ui(update_state, ui),
+                    Some(Tab::TaskLog { term, .. }) => term.ui(update_state, ui),
+                    None => {
+                        ui.label("Logs of background tasks are shown here.");
+                        Ok(())
+                    }
+                };
+                if let Err(e) = result {
                     luminol_core::error!(
                         update_state.toasts,
                         e.wrap_err("Error displaying terminal"),
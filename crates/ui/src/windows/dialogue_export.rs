@@ -0,0 +1,720 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use futures_lite::AsyncWriteExt;
+use luminol_config::command_db::CommandDB;
+use luminol_data::rpg::{self, EventCommand};
+
+const SHOW_TEXT: u16 = 101;
+const SHOW_TEXT_LINE: u16 = 401;
+const SHOW_CHOICES: u16 = 102;
+const WHEN_CHOICE: u16 = 402;
+const WHEN_CANCEL: u16 = 403;
+
+/// Exports the dialogue of a map, some of its events, or the whole project as a document that
+/// writers can read through and comment on.
+pub struct Window {
+    scope: Scope,
+    format: Format,
+    map_id: usize,
+    /// The events of `map_id` to export when the scope is [`Scope::Events`].
+    event_ids: BTreeSet<usize>,
+    export_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Map,
+    Events,
+    Project,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            scope: Scope::Map,
+            format: Format::Markdown,
+            map_id: 1,
+            event_ids: BTreeSet::new(),
+            export_promise: None,
+        }
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_dialogue_export")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut export = false;
+
+        egui::Window::new("Export Dialogue Script")
+            .id(self.id())
+            .default_width(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Writes the events' dialogue to a document, with text as quotes, choices as \
+                    lists and every other command as a collapsed one-line summary. Each event, \
+                    page and message has an anchor so review comments can link to it.",
+                );
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.scope, Scope::Map, "Map");
+                    ui.radio_value(&mut self.scope, Scope::Events, "Selected events");
+                    ui.radio_value(&mut self.scope, Scope::Project, "Whole project");
+                });
+
+                if self.scope != Scope::Project {
+                    let mut map_infos: Vec<_> = (update_state.data.map_infos().data.iter())
+                        .map(|(&id, info)| (id, info.name.clone()))
+                        .collect();
+                    map_infos.sort_unstable_by_key(|(id, _)| *id);
+                    if !map_infos.iter().any(|(id, _)| *id == self.map_id) {
+                        if let Some(&(id, _)) = map_infos.first() {
+                            self.map_id = id;
+                            self.event_ids.clear();
+                        }
+                    }
+
+                    let selected_text = map_infos
+                        .iter()
+                        .find(|(id, _)| *id == self.map_id)
+                        .map_or_else(String::new, |(id, name)| format!("{id:0>3}: {name}"));
+                    egui::ComboBox::from_label("Map")
+                        .selected_text(selected_text)
+                        .show_ui(ui, |ui| {
+                            for (id, name) in map_infos {
+                                if ui
+                                    .selectable_value(
+                                        &mut self.map_id,
+                                        id,
+                                        format!("{id:0>3}: {name}"),
+                                    )
+                                    .changed()
+                                {
+                                    self.event_ids.clear();
+                                }
+                            }
+                        });
+                }
+
+                if self.scope == Scope::Events {
+                    let map = update_state.data.get_or_load_map(
+                        self.map_id,
+                        update_state.filesystem,
+                        update_state.project_config.as_ref().unwrap(),
+                    );
+                    egui::ScrollArea::vertical()
+                        .max_height(200.)
+                        .show(ui, |ui| {
+                            for (_, event) in map.events.iter() {
+                                let mut selected = self.event_ids.contains(&event.id);
+                                let text = format!("{:0>3}: {}", event.id, event.name);
+                                if ui.checkbox(&mut selected, text).changed() {
+                                    if selected {
+                                        self.event_ids.insert(event.id);
+                                    } else {
+                                        self.event_ids.remove(&event.id);
+                                    }
+                                }
+                            }
+                        });
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.format, Format::Markdown, "Markdown");
+                    ui.radio_value(&mut self.format, Format::Html, "HTML");
+                });
+
+                if self.export_promise.is_some() {
+                    ui.spinner();
+                } else {
+                    export = ui
+                        .add_enabled(
+                            self.scope != Scope::Events || !self.event_ids.is_empty(),
+                            egui::Button::new("Export..."),
+                        )
+                        .clicked();
+                }
+            });
+
+        if export {
+            let document = self.write_document(update_state);
+            let (filename, filter) = match self.format {
+                Format::Markdown => ("dialogue.md", "Markdown files"),
+                Format::Html => ("dialogue.html", "HTML files"),
+            };
+            self.export_promise = Some(luminol_core::spawn_future(async move {
+                let mut file = luminol_filesystem::host::File::new()?;
+                file.write_all(document.as_bytes()).await?;
+                file.flush().await?;
+                file.save(filename, filter).await?;
+                Ok(())
+            }));
+        }
+
+        if let Some(p) = self.export_promise.take() {
+            match p.try_take() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    if !matches!(
+                        e.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error exporting dialogue")
+                        );
+                    }
+                }
+                Err(p) => self.export_promise = Some(p),
+            }
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
+
+impl Window {
+    fn write_document(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
+        let db = &update_state.project_config.as_ref().unwrap().command_db;
+        let system = update_state.data.system();
+        let mut document = Document::new(self.format, db, &system);
+
+        let mut map_ids = match self.scope {
+            Scope::Map | Scope::Events => vec![self.map_id],
+            Scope::Project => update_state.data.map_infos().data.keys().copied().collect(),
+        };
+        map_ids.sort_unstable();
+
+        for map_id in map_ids {
+            let name = (update_state.data.map_infos().data.get(&map_id))
+                .map_or_else(String::new, |info| info.name.clone());
+            let map = update_state.data.get_or_load_map(
+                map_id,
+                update_state.filesystem,
+                update_state.project_config.as_ref().unwrap(),
+            );
+
+            document.heading(
+                2,
+                &format!("Map {map_id:0>3}: {name}"),
+                &format!("map-{map_id:0>3}"),
+            );
+            for (_, event) in map.events.iter() {
+                if self.scope == Scope::Events && !self.event_ids.contains(&event.id) {
+                    continue;
+                }
+
+                document.heading(
+                    3,
+                    &format!(
+                        "Event {:0>3}: {} ({}, {})",
+                        event.id, event.name, event.x, event.y
+                    ),
+                    &format!("map-{map_id:0>3}-event-{:0>3}", event.id),
+                );
+                for (page, event_page) in event.pages.iter().enumerate() {
+                    let location = Location::Page {
+                        map_id,
+                        event_id: event.id,
+                        page,
+                    };
+                    document.heading(4, &format!("Page {}", page + 1), &location.anchor());
+                    document.commands(&event_page.list, location);
+                }
+            }
+        }
+
+        if self.scope == Scope::Project {
+            document.heading(2, "Common Events", "common-events");
+            for event in update_state.data.common_events().data.iter() {
+                let location = Location::CommonEvent(event.id);
+                document.heading(
+                    3,
+                    &format!("Common Event {:0>3}: {}", event.id, event.name),
+                    &location.anchor(),
+                );
+                document.commands(&event.list, location);
+            }
+        }
+
+        document.finish()
+    }
+}
+
+/// Builds the exported document. Dialogue is written out in full, and every run of other
+/// commands is folded into a `<details>` element, which Markdown viewers also render.
+struct Document<'a> {
+    format: Format,
+    db: &'a CommandDB,
+    system: &'a rpg::System,
+    text: String,
+}
+
+impl<'a> Document<'a> {
+    fn new(format: Format, db: &'a CommandDB, system: &'a rpg::System) -> Self {
+        let text = match format {
+            Format::Markdown => "# Dialogue\n\n".to_string(),
+            Format::Html => "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                <title>Dialogue</title>\n</head>\n<body>\n<h1>Dialogue</h1>\n"
+                .to_string(),
+        };
+        Self {
+            format,
+            db,
+            system,
+            text,
+        }
+    }
+
+    fn heading(&mut self, level: usize, title: &str, anchor: &str) {
+        let title = escape(self.format, title);
+        match self.format {
+            Format::Markdown => {
+                let hashes = "#".repeat(level);
+                let _ = write!(self.text, "<a id=\"{anchor}\"></a>\n\n{hashes} {title}\n\n");
+            }
+            Format::Html => {
+                let _ = writeln!(self.text, "<h{level} id=\"{anchor}\">{title}</h{level}>");
+            }
+        }
+    }
+
+    /// Writes a list of event commands. Messages and choice lists are preceded by a marker
+    /// comment with their address, so edits to them can be imported back.
+    fn commands(&mut self, commands: &[EventCommand], location: Location) {
+        let mut other = vec![];
+        let mut index = 0;
+        while index < commands.len() {
+            let command = &commands[index];
+            match command.code {
+                0 => {}
+                SHOW_TEXT => {
+                    self.other(&mut other);
+                    let lines = message_lines(commands, index).unwrap_or_default();
+                    let marker = Marker::new(MarkerKind::Text, location, index, &lines);
+                    self.quote(&lines, &marker);
+                    index += lines.len() - 1;
+                }
+                SHOW_CHOICES => {
+                    self.other(&mut other);
+                    let choices = choice_strings(commands, index).unwrap_or_default();
+                    let marker = Marker::new(MarkerKind::Choices, location, index, &choices);
+                    self.choices(&choices, &marker);
+                }
+                WHEN_CHOICE => {
+                    self.other(&mut other);
+                    let choice = command.parameters.get(1).and_then(|p| p.as_string());
+                    self.branch(&format!("When \"{}\"", choice.map_or("", String::as_str)));
+                }
+                WHEN_CANCEL => {
+                    self.other(&mut other);
+                    self.branch("When cancelled");
+                }
+                code => {
+                    let summary = match self.db.get(code) {
+                        Some(description) => description.summary(&command.parameters, self.system),
+                        None => format!("Command {code}"),
+                    };
+                    other.push((command.indent, summary));
+
+                    // Skip the commands that continue a multiline command
+                    if let Some(luminol_data::commands::CommandKind::Multi { code, .. }) =
+                        self.db.get(command.code).map(|d| &d.kind)
+                    {
+                        while commands.get(index + 1).is_some_and(|c| c.code == *code) {
+                            index += 1;
+                        }
+                    }
+                }
+            }
+            index += 1;
+        }
+        self.other(&mut other);
+    }
+
+    fn quote(&mut self, lines: &[&str], marker: &Marker) {
+        let anchor = marker.anchor();
+        let _ = writeln!(self.text, "{marker}");
+        match self.format {
+            Format::Markdown => {
+                let _ = writeln!(self.text, "<a id=\"{anchor}\"></a>\n");
+                for line in lines {
+                    let _ = writeln!(self.text, "> {}  ", escape(self.format, line));
+                }
+                self.text.push('\n');
+            }
+            Format::Html => {
+                let lines = lines
+                    .iter()
+                    .map(|line| escape(self.format, line))
+                    .collect::<Vec<_>>();
+                let _ = writeln!(
+                    self.text,
+                    "<blockquote id=\"{anchor}\">{}</blockquote>",
+                    lines.join("<br>")
+                );
+            }
+        }
+    }
+
+    fn choices(&mut self, choices: &[&str], marker: &Marker) {
+        let _ = writeln!(self.text, "{marker}");
+        match self.format {
+            Format::Markdown => {
+                for choice in choices {
+                    let _ = writeln!(self.text, "- {}", escape(self.format, choice));
+                }
+                self.text.push('\n');
+            }
+            Format::Html => {
+                self.text.push_str("<ul>\n");
+                for choice in choices {
+                    let _ = writeln!(self.text, "<li>{}</li>", escape(self.format, choice));
+                }
+                self.text.push_str("</ul>\n");
+            }
+        }
+    }
+
+    /// Marks which choice the dialogue after it belongs to.
+    fn branch(&mut self, text: &str) {
+        let text = escape(self.format, text);
+        match self.format {
+            Format::Markdown => {
+                let _ = write!(self.text, "**{text}**\n\n");
+            }
+            Format::Html => {
+                let _ = writeln!(self.text, "<p><strong>{text}</strong></p>");
+            }
+        }
+    }
+
+    /// Writes the summaries of a run of commands that aren't dialogue as a collapsed list, and
+    /// clears it. Commands keep their indentation relative to each other so branches can still
+    /// be followed.
+    fn other(&mut self, summaries: &mut Vec<(usize, String)>) {
+        if summaries.is_empty() {
+            return;
+        }
+
+        let count = if summaries.len() == 1 {
+            "1 command".to_string()
+        } else {
+            format!("{} commands", summaries.len())
+        };
+        let base_indent = summaries.iter().map(|(i, _)| *i).min().unwrap_or_default();
+        let _ = writeln!(self.text, "<details><summary>{count}</summary>");
+        match self.format {
+            Format::Markdown => {
+                self.text.push('\n');
+                for (indent, summary) in summaries.drain(..) {
+                    let _ = writeln!(
+                        self.text,
+                        "{}- {}",
+                        "  ".repeat(indent - base_indent),
+                        escape(self.format, &summary)
+                    );
+                }
+                self.text.push('\n');
+            }
+            Format::Html => {
+                self.text.push_str("<ul>\n");
+                for (indent, summary) in summaries.drain(..) {
+                    let _ = writeln!(
+                        self.text,
+                        "<li style=\"margin-left: {}em\">{}</li>",
+                        (indent - base_indent) * 2,
+                        escape(self.format, &summary)
+                    );
+                }
+                self.text.push_str("</ul>\n");
+            }
+        }
+        self.text.push_str("</details>\n\n");
+    }
+
+    fn finish(mut self) -> String {
+        if self.format == Format::Html {
+            self.text.push_str("</body>\n</html>\n");
+        }
+        self.text
+    }
+}
+
+/// Escapes the characters that would otherwise be read as markup.
+fn escape(format: Format, text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (format, c) {
+            (_, '&') => escaped.push_str("&amp;"),
+            (_, '<') => escaped.push_str("&lt;"),
+            (_, '>') => escaped.push_str("&gt;"),
+            (Format::Html, '"') => escaped.push_str("&quot;"),
+            (Format::Markdown, '\\' | '`' | '*' | '_' | '[' | ']' | '#' | '|' | '~') => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Where a list of event commands is in the project.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) enum Location {
+    /// A page of a map event. `page` is 0-based.
+    Page {
+        map_id: usize,
+        event_id: usize,
+        page: usize,
+    },
+    CommonEvent(usize),
+}
+
+impl Location {
+    /// The HTML ID of this location's heading.
+    pub(crate) fn anchor(self) -> String {
+        match self {
+            Self::Page {
+                map_id,
+                event_id,
+                page,
+            } => format!("map-{map_id:0>3}-event-{event_id:0>3}-page-{}", page + 1),
+            Self::CommonEvent(id) => format!("common-event-{id:0>3}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Page {
+                map_id,
+                event_id,
+                page,
+            } => write!(
+                f,
+                "Map {map_id:0>3}, event {event_id:0>3}, page {}",
+                page + 1
+            ),
+            Self::CommonEvent(id) => write!(f, "Common event {id:0>3}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum MarkerKind {
+    Text,
+    Choices,
+}
+
+/// The HTML comment written before every message and choice list, like
+/// `<!-- luminol:text map=1 event=2 page=1 command=5 hash=0123456789abcdef -->`.
+///
+/// It holds the address of the command and a hash of its text when it was exported, so the
+/// text can be found again and checked for changes made in the editor since.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Marker {
+    pub(crate) kind: MarkerKind,
+    pub(crate) location: Location,
+    /// The 0-based index of the Show Text or Show Choices command.
+    pub(crate) index: usize,
+    pub(crate) hash: u64,
+}
+
+impl Marker {
+    pub(crate) fn new(kind: MarkerKind, location: Location, index: usize, text: &[&str]) -> Self {
+        Self {
+            kind,
+            location,
+            index,
+            hash: text_hash(text),
+        }
+    }
+
+    fn anchor(&self) -> String {
+        format!("{}-command-{}", self.location.anchor(), self.index + 1)
+    }
+
+    /// Parses a marker from a line of an exported script.
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let line = line
+            .trim()
+            .strip_prefix("<!-- luminol:")?
+            .strip_suffix("-->")?;
+        let mut words = line.split_whitespace();
+        let kind = match words.next()? {
+            "text" => MarkerKind::Text,
+            "choices" => MarkerKind::Choices,
+            _ => return None,
+        };
+
+        let (mut map_id, mut event_id, mut page, mut common_event) = (None, None, None, None);
+        let (mut index, mut hash) = (None, None);
+        for word in words {
+            let (key, value) = word.split_once('=')?;
+            match key {
+                "map" => map_id = value.parse().ok(),
+                "event" => event_id = value.parse().ok(),
+                "page" => page = value.parse::<usize>().ok()?.checked_sub(1),
+                "common" => common_event = value.parse().ok(),
+                "command" => index = value.parse::<usize>().ok()?.checked_sub(1),
+                "hash" => hash = u64::from_str_radix(value, 16).ok(),
+                _ => {}
+            }
+        }
+
+        let location = match (map_id, event_id, page, common_event) {
+            (Some(map_id), Some(event_id), Some(page), None) => Location::Page {
+                map_id,
+                event_id,
+                page,
+            },
+            (None, None, None, Some(id)) => Location::CommonEvent(id),
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            location,
+            index: index?,
+            hash: hash?,
+        })
+    }
+}
+
+impl std::fmt::Display for Marker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            MarkerKind::Text => "text",
+            MarkerKind::Choices => "choices",
+        };
+        write!(f, "<!-- luminol:{kind} ")?;
+        match self.location {
+            Location::Page {
+                map_id,
+                event_id,
+                page,
+            } => write!(f, "map={map_id} event={event_id} page={}", page + 1)?,
+            Location::CommonEvent(id) => write!(f, "common={id}")?,
+        }
+        write!(f, " command={} hash={:016x} -->", self.index + 1, self.hash)
+    }
+}
+
+/// A hash of a message or choice list that stays the same between runs of Luminol (64-bit
+/// FNV-1a), since it's stored in exported scripts.
+pub(crate) fn text_hash(text: &[&str]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for (i, line) in text.iter().enumerate() {
+        let separator = (i > 0).then_some(b'\n');
+        for byte in separator.into_iter().chain(line.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// The lines of the Show Text command at `index`, along with the lines continuing it, or
+/// `None` if that isn't a Show Text command.
+pub(crate) fn message_lines(commands: &[EventCommand], index: usize) -> Option<Vec<&str>> {
+    let first = commands.get(index).filter(|c| c.code == SHOW_TEXT)?;
+    let rest = commands[index + 1..]
+        .iter()
+        .take_while(|c| c.code == SHOW_TEXT_LINE);
+    Some(
+        std::iter::once(first)
+            .chain(rest)
+            .map(|c| {
+                let text = c.parameters.first().and_then(|p| p.as_string());
+                text.map_or("", String::as_str)
+            })
+            .collect(),
+    )
+}
+
+/// The choices of the Show Choices command at `index`, or `None` if that isn't a Show Choices
+/// command.
+pub(crate) fn choice_strings(commands: &[EventCommand], index: usize) -> Option<Vec<&str>> {
+    let command = commands.get(index).filter(|c| c.code == SHOW_CHOICES)?;
+    let choices = command.parameters.first().and_then(|p| p.as_array());
+    Some(
+        choices
+            .into_iter()
+            .flatten()
+            .map(|c| c.as_string().map_or("", String::as_str))
+            .collect(),
+    )
+}
+
+/// Reverses [`escape`] for Markdown.
+pub(crate) fn unescape_markdown(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let entity = [
+            ("&amp;", '&'),
+            ("&lt;", '<'),
+            ("&gt;", '>'),
+            ("&quot;", '"'),
+        ]
+        .into_iter()
+        .find(|(entity, _)| rest.starts_with(entity));
+        if let Some((entity, c)) = entity {
+            unescaped.push(c);
+            rest = &rest[entity.len()..];
+        } else if let Some(escaped) = rest.strip_prefix('\\').and_then(|r| r.chars().next()) {
+            unescaped.push(escaped);
+            rest = &rest[1 + escaped.len_utf8()..];
+        } else {
+            unescaped.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,411 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use std::io::Read;
+
+use luminol_data::rpg::EventCommand;
+use luminol_data::ParameterType;
+
+use super::dialogue_export::{
+    choice_strings, message_lines, text_hash, unescape_markdown, Location, Marker, MarkerKind,
+};
+
+const SHOW_TEXT_LINE: u16 = 401;
+const WHEN_CHOICE: u16 = 402;
+const CHOICES_END: u16 = 404;
+
+/// Imports edited messages and choices from a dialogue script exported as Markdown. The changes
+/// are listed for review before they're applied.
+#[derive(Default)]
+pub struct Window {
+    import_promise: Option<poll_promise::Promise<color_eyre::Result<String>>>,
+    /// The messages and choice lists that differ from the project, after a script is opened.
+    edits: Option<Vec<Edit>>,
+}
+
+struct Edit {
+    marker: Marker,
+    /// The text in the project.
+    old: Vec<String>,
+    /// The text in the script.
+    new: Vec<String>,
+    /// Why this edit can't be applied, if it can't.
+    conflict: Option<&'static str>,
+    apply: bool,
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_dialogue_import")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new("Import Dialogue Edits")
+            .id(self.id())
+            .default_width(600.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Reads an edited copy of a dialogue script exported as Markdown and applies \
+                    the changes made to its messages and choices.",
+                );
+
+                if self.import_promise.is_some() {
+                    ui.spinner();
+                } else if ui.button("Open script...").clicked() {
+                    self.edits = None;
+                    self.import_promise = Some(luminol_core::spawn_future(async move {
+                        let (mut file, _) = luminol_filesystem::host::File::from_file_picker(
+                            "Markdown files",
+                            &["md"],
+                        )
+                        .await?;
+                        let mut script = String::new();
+                        file.read_to_string(&mut script)?;
+                        Ok(script)
+                    }));
+                }
+
+                let Some(edits) = &mut self.edits else {
+                    return;
+                };
+                ui.separator();
+
+                if edits.is_empty() {
+                    ui.label("The script has no changes to import.");
+                    return;
+                }
+
+                let conflicts = edits.iter().filter(|e| e.conflict.is_some()).count();
+                ui.label(format!(
+                    "{} changes, {conflicts} conflicts",
+                    edits.len() - conflicts
+                ));
+
+                egui::ScrollArea::vertical()
+                    .max_height(400.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("luminol_dialogue_import_edits")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label("");
+                                ui.strong("Location");
+                                ui.strong("Current");
+                                ui.strong("Imported");
+                                ui.end_row();
+
+                                for edit in edits.iter_mut() {
+                                    match edit.conflict {
+                                        Some(conflict) => {
+                                            ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                                .on_hover_text(conflict);
+                                        }
+                                        None => {
+                                            ui.checkbox(&mut edit.apply, "");
+                                        }
+                                    }
+                                    ui.vertical(|ui| {
+                                        ui.label(edit.marker.location.to_string());
+                                        ui.weak(format!("Command {}", edit.marker.index + 1));
+                                        if let Some(conflict) = edit.conflict {
+                                            ui.colored_label(ui.visuals().warn_fg_color, conflict);
+                                        }
+                                    });
+                                    ui.label(edit.old.join("\n"));
+                                    ui.label(edit.new.join("\n"));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(conflicts < edits.len(), egui::Button::new("Apply"))
+                        .clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if let Some(p) = self.import_promise.take() {
+            match p.try_take() {
+                Ok(Ok(script)) => self.edits = Some(Self::review(update_state, &script)),
+                Ok(Err(e)) => {
+                    if !matches!(
+                        e.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error opening dialogue script")
+                        );
+                    }
+                }
+                Err(p) => self.import_promise = Some(p),
+            }
+        }
+
+        if apply {
+            let edits = self.edits.take().unwrap_or_default();
+            let (applied, failed) = Self::apply(update_state, edits);
+            if failed > 0 {
+                luminol_core::warn!(
+                    update_state.toasts,
+                    format!(
+                        "Imported {applied} dialogue changes. {failed} were changed in Luminol \
+                        while reviewing and were skipped."
+                    )
+                );
+            } else {
+                luminol_core::info!(
+                    update_state.toasts,
+                    format!("Imported {applied} dialogue changes")
+                );
+            }
+        } else if cancel {
+            self.edits = None;
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
+
+impl Window {
+    /// Compares the messages and choices in a script with the project, returning the ones that
+    /// differ.
+    fn review(update_state: &luminol_core::UpdateState<'_>, script: &str) -> Vec<Edit> {
+        parse(script)
+            .into_iter()
+            .filter_map(|(marker, new)| {
+                let current = with_commands(update_state, marker.location, |commands, _| {
+                    let current = match marker.kind {
+                        MarkerKind::Text => message_lines(commands, marker.index),
+                        MarkerKind::Choices => choice_strings(commands, marker.index),
+                    };
+                    current.map(|lines| lines.into_iter().map(str::to_string).collect())
+                });
+                let (old, conflict) = match current {
+                    Ok(Some(old)) => (old, None),
+                    Ok(None) => (vec![], Some("The command was moved or deleted")),
+                    Err(conflict) => (vec![], Some(conflict)),
+                };
+                if old == new {
+                    return None;
+                }
+
+                let old_refs = old.iter().map(String::as_str).collect::<Vec<_>>();
+                let editor_open = with_commands(update_state, marker.location, |_, open| open);
+                let conflict = conflict.or_else(|| {
+                    if text_hash(&old_refs) != marker.hash {
+                        Some("The text was changed in Luminol after the script was exported")
+                    } else if editor_open == Ok(true) {
+                        Some("The event is open in an event editor")
+                    } else if new.is_empty() && marker.kind == MarkerKind::Text {
+                        Some("Messages can't be deleted by importing")
+                    } else if marker.kind == MarkerKind::Choices && new.len() != old.len() {
+                        Some("Choices can't be added or removed by importing")
+                    } else {
+                        None
+                    }
+                });
+
+                Some(Edit {
+                    marker,
+                    old,
+                    new,
+                    apply: conflict.is_none(),
+                    conflict,
+                })
+            })
+            .collect()
+    }
+
+    /// Applies the chosen edits, returning how many were applied and how many were skipped
+    /// because their text changed since they were reviewed.
+    fn apply(update_state: &luminol_core::UpdateState<'_>, mut edits: Vec<Edit>) -> (usize, usize) {
+        edits.retain(|edit| edit.apply && edit.conflict.is_none());
+        // Messages can change length, so apply the edits from the end of each list so that the
+        // indices of the others stay the same
+        edits.sort_unstable_by(|a, b| {
+            (b.marker.location, b.marker.index).cmp(&(a.marker.location, a.marker.index))
+        });
+
+        let (mut applied, mut failed) = (0, 0);
+        for edit in edits {
+            let result = with_commands(update_state, edit.marker.location, |commands, _| {
+                let current = match edit.marker.kind {
+                    MarkerKind::Text => message_lines(commands, edit.marker.index),
+                    MarkerKind::Choices => choice_strings(commands, edit.marker.index),
+                };
+                if current.map(|lines| text_hash(&lines)) != Some(edit.marker.hash) {
+                    return false;
+                }
+                match edit.marker.kind {
+                    MarkerKind::Text => set_message(commands, edit.marker.index, &edit.new),
+                    MarkerKind::Choices => set_choices(commands, edit.marker.index, &edit.new),
+                }
+                true
+            });
+            if result != Ok(true) {
+                failed += 1;
+                continue;
+            }
+
+            match edit.marker.location {
+                Location::Page { map_id, .. } => update_state.data.get_map(map_id).modified = true,
+                Location::CommonEvent(_) => update_state.data.common_events().modified = true,
+            }
+            update_state.modified.set(true);
+            applied += 1;
+        }
+        (applied, failed)
+    }
+}
+
+/// Finds every marker in an exported Markdown script along with the text after it.
+fn parse(script: &str) -> Vec<(Marker, Vec<String>)> {
+    let mut found = vec![];
+    let mut lines = script.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(marker) = Marker::parse(line) else {
+            continue;
+        };
+
+        // Skip the anchor and blank lines between the marker and the text
+        while lines
+            .next_if(|l| l.trim().is_empty() || l.trim_start().starts_with("<a "))
+            .is_some()
+        {}
+
+        let mut text = vec![];
+        while let Some(line) = lines.peek() {
+            let content = match marker.kind {
+                MarkerKind::Text => line.strip_prefix('>').map(|l| {
+                    let l = l.strip_prefix(' ').unwrap_or(l);
+                    // The two spaces at the end of each line are Markdown line breaks
+                    l.strip_suffix("  ").unwrap_or(l)
+                }),
+                MarkerKind::Choices => line.strip_prefix("- ").or_else(|| line.strip_prefix('-')),
+            };
+            let Some(content) = content else {
+                break;
+            };
+            text.push(unescape_markdown(content));
+            lines.next();
+        }
+        found.push((marker, text));
+    }
+    found
+}
+
+/// Calls `f` with the commands at `location` and whether they are open in an event editor, or
+/// returns why they can't be found.
+fn with_commands<R>(
+    update_state: &luminol_core::UpdateState<'_>,
+    location: Location,
+    f: impl FnOnce(&mut Vec<EventCommand>, bool) -> R,
+) -> Result<R, &'static str> {
+    match location {
+        Location::Page {
+            map_id,
+            event_id,
+            page,
+        } => {
+            if !update_state.data.map_infos().data.contains_key(&map_id) {
+                return Err("The map was deleted");
+            }
+            let mut map = update_state.data.get_or_load_map(
+                map_id,
+                update_state.filesystem,
+                update_state.project_config.as_ref().unwrap(),
+            );
+            let event = map
+                .events
+                .get_mut(event_id)
+                .ok_or("The event was deleted")?;
+            let editor_open = event.extra_data.is_editor_open;
+            let page = event.pages.get_mut(page).ok_or("The page was deleted")?;
+            Ok(f(&mut page.list, editor_open))
+        }
+        Location::CommonEvent(id) => {
+            let mut common_events = update_state.data.common_events();
+            let event = (common_events.data.iter_mut())
+                .find(|e| e.id == id)
+                .ok_or("The common event was deleted")?;
+            Ok(f(&mut event.list, false))
+        }
+    }
+}
+
+/// Replaces the lines of the Show Text command at `index`, adding or removing the commands
+/// that continue it as needed.
+fn set_message(commands: &mut Vec<EventCommand>, index: usize, lines: &[String]) {
+    let old_len = message_lines(commands, index).map_or(1, |lines| lines.len());
+    let indent = commands[index].indent;
+    commands[index].parameters = vec![ParameterType::String(lines[0].clone())];
+    let continuation = lines[1..].iter().map(|line| EventCommand {
+        code: SHOW_TEXT_LINE,
+        indent,
+        parameters: vec![ParameterType::String(line.clone())],
+        guid: rand::random(),
+    });
+    commands.splice(index + 1..index + old_len, continuation);
+}
+
+/// Renames the choices of the Show Choices command at `index`, along with the branches for
+/// them.
+fn set_choices(commands: &mut [EventCommand], index: usize, choices: &[String]) {
+    let indent = commands[index].indent;
+    let array = choices.iter().cloned().map(ParameterType::String).collect();
+    match commands[index].parameters.first_mut() {
+        Some(parameter) => *parameter = ParameterType::Array(array),
+        None => commands[index].parameters.push(ParameterType::Array(array)),
+    }
+
+    for command in commands[index + 1..].iter_mut() {
+        if command.indent < indent || (command.indent == indent && command.code == CHOICES_END) {
+            break;
+        }
+        if command.indent != indent || command.code != WHEN_CHOICE {
+            continue;
+        }
+        let choice = (command.parameters.first())
+            .and_then(ParameterType::as_integer)
+            .and_then(|&i| choices.get(usize::try_from(i).ok()?));
+        if let (Some(choice), Some(parameter)) = (choice, command.parameters.get_mut(1)) {
+            *parameter = ParameterType::String(choice.clone());
+        }
+    }
+}
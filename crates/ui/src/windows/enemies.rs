@@ -26,6 +26,7 @@ use crate::components::{
     CollapsingView, DatabaseView, EnumComboBox, Field, OptionalIdComboBox, RankSelection, UiExt,
 };
 use crate::modals::graphic_picker::hue::Modal as GraphicPicker;
+use luminol_config::recent_edits::{Database, EditLocation};
 use luminol_core::Modal;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
@@ -45,6 +46,10 @@ pub struct Window {
 
     graphic_picker: GraphicPicker,
 
+    /// The battle state the action selection chances are shown for
+    battle_state: luminol_data::rpg::enemy::BattleState,
+    assume_switches_on: bool,
+
     collapsing_view: CollapsingView,
     view: DatabaseView,
 }
@@ -66,60 +71,131 @@ impl Window {
                 "enemy_battler_picker",
             ),
 
+            battle_state: Default::default(),
+            assume_switches_on: true,
+
             collapsing_view: CollapsingView::new(),
             view: DatabaseView::new(),
         }
     }
 
-    fn show_action_header(
-        ui: &mut egui::Ui,
+    fn action_name(
         skills: &luminol_data::rpg::Skills,
         action: &luminol_data::rpg::enemy::Action,
-    ) {
-        let mut conditions = Vec::with_capacity(4);
-        if action.condition_turn_a != 0 || action.condition_turn_b != 1 {
-            conditions.push(if action.condition_turn_b == 0 {
-                format!("Turn {}", action.condition_turn_a,)
-            } else if action.condition_turn_a == 0 {
-                format!("Turn {}x", action.condition_turn_b,)
-            } else if action.condition_turn_b == 1 {
-                format!("Turn {} + x", action.condition_turn_a,)
-            } else {
-                format!(
-                    "Turn {} + {}x",
-                    action.condition_turn_a, action.condition_turn_b,
-                )
-            })
+    ) -> String {
+        match action.kind {
+            luminol_data::rpg::enemy::Kind::Basic => action.basic.to_string(),
+            luminol_data::rpg::enemy::Kind::Skill => skills
+                .data
+                .get(action.skill_id)
+                .map_or_else(|| "".into(), |s| s.name.clone()),
         }
+    }
+
+    /// A summary of when the action can be used, like `turns 3, 6, 9… while HP ≤ 50%`, or `None`
+    /// if it can always be used.
+    fn action_condition_summary(
+        system: &luminol_data::rpg::System,
+        action: &luminol_data::rpg::enemy::Action,
+    ) -> Option<String> {
+        let (a, b) = (action.condition_turn_a, action.condition_turn_b);
+        let turns = if b == 0 {
+            Some(format!("turn {a}"))
+        } else {
+            // turns start at 1, and the action can't be used before turn a
+            let first = if a >= 1 { a } else { b };
+            if b == 1 && first == 1 {
+                None
+            } else if b == 1 {
+                Some(format!("turn {first} onwards"))
+            } else {
+                Some(format!(
+                    "turns {}, {}, {}…",
+                    first,
+                    first + b,
+                    first + 2 * b
+                ))
+            }
+        };
+
+        let mut conditions = Vec::with_capacity(3);
         if action.condition_hp < 100 {
-            conditions.push(format!("{}% HP", action.condition_hp,));
+            conditions.push(format!("HP ≤ {}%", action.condition_hp));
         }
         if action.condition_level > 1 {
-            conditions.push(format!("Level {}", action.condition_level,));
+            conditions.push(format!("party level ≥ {}", action.condition_level));
         }
         if let Some(id) = action.condition_switch_id {
-            conditions.push(format!("Switch {:0>4}", id + 1));
+            conditions.push(format!(
+                "switch {:0>4}: {} is ON",
+                id + 1,
+                system.switches.get(id).map_or("", |s| s.as_str())
+            ));
         }
 
-        ui.label(format!(
-            "{}{}",
-            match action.kind {
-                luminol_data::rpg::enemy::Kind::Basic => {
-                    action.basic.to_string()
-                }
-                luminol_data::rpg::enemy::Kind::Skill => {
-                    skills
-                        .data
-                        .get(action.skill_id)
-                        .map_or_else(|| "".into(), |s| s.name.clone())
-                }
-            },
-            if conditions.is_empty() {
-                String::new()
-            } else {
-                format!(": {}", conditions.join(", "))
+        match (turns, conditions.is_empty()) {
+            (None, true) => None,
+            (Some(turns), true) => Some(turns),
+            (None, false) => Some(format!("while {}", conditions.join(" and "))),
+            (Some(turns), false) => Some(format!("{turns} while {}", conditions.join(" and "))),
+        }
+    }
+
+    fn show_action_header(
+        ui: &mut egui::Ui,
+        system: &luminol_data::rpg::System,
+        skills: &luminol_data::rpg::Skills,
+        action: &luminol_data::rpg::enemy::Action,
+    ) {
+        let name = Self::action_name(skills, action);
+        ui.label(match Self::action_condition_summary(system, action) {
+            Some(summary) => format!("{name}: {summary}"),
+            None => name,
+        });
+    }
+
+    /// Shows how likely each action is to be picked in a battle state picked by the user.
+    fn show_selection_chances(
+        ui: &mut egui::Ui,
+        skills: &luminol_data::rpg::Skills,
+        actions: &[luminol_data::rpg::enemy::Action],
+        state: &mut luminol_data::rpg::enemy::BattleState,
+        assume_switches_on: &mut bool,
+    ) -> egui::Response {
+        ui.vertical(|ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Turn");
+                ui.add(egui::DragValue::new(&mut state.turn).range(1..=i32::MAX));
+                ui.label("HP");
+                ui.add(egui::Slider::new(&mut state.hp_percent, 0..=100).suffix("%"));
+                ui.label("Party level");
+                ui.add(egui::DragValue::new(&mut state.party_level).range(1..=99));
+                ui.checkbox(assume_switches_on, "Switches are ON");
+            });
+
+            let chances = luminol_data::rpg::enemy::selection_chances(actions, |action| {
+                action.is_available(state, |_| *assume_switches_on)
+            });
+            if chances.iter().all(|&chance| chance == 0.) {
+                ui.weak("No action can be picked in this situation");
+                return;
             }
-        ));
+
+            egui::Grid::new("enemy_selection_chances")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (action, chance) in actions.iter().zip(chances) {
+                        ui.label(Self::action_name(skills, action));
+                        ui.add(
+                            egui::ProgressBar::new(chance)
+                                .text(format!("{:.0}%", chance * 100.))
+                                .desired_width(ui.available_width()),
+                        );
+                        ui.end_row();
+                    }
+                });
+        })
+        .response
     }
 
     fn show_action_body(
@@ -588,7 +664,9 @@ impl luminol_core::Window for Window {
                                             enemy.id,
                                             &mut enemy.actions,
                                             |ui, _i, action| {
-                                                Self::show_action_header(ui, &skills, action)
+                                                Self::show_action_header(
+                                                    ui, &system, &skills, action,
+                                                )
                                             },
                                             |ui, i, action| {
                                                 Self::show_action_body(
@@ -604,6 +682,16 @@ impl luminol_core::Window for Window {
                                         .response
                                 }))
                                 .changed();
+
+                            ui.add(Field::new("Selection Chance", |ui: &mut egui::Ui| {
+                                Self::show_selection_chances(
+                                    ui,
+                                    &skills,
+                                    &enemy.actions,
+                                    &mut self.battle_state,
+                                    &mut self.assume_switches_on,
+                                )
+                            }));
                         });
 
                         ui.with_padded_stripe(true, |ui| {
@@ -662,6 +750,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             enemies.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Enemies,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(enemies);
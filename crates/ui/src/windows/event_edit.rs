@@ -27,7 +27,7 @@ use crate::modals::{
     database_modal::{SwitchModal, VariableModal},
     graphic_picker::event::Modal as GraphicPicker,
 };
-use egui::Widget;
+use egui::{InnerResponse, Widget};
 use luminol_core::prelude::*;
 
 /// The event editor window.
@@ -40,8 +40,15 @@ pub struct Window {
     switch_2_modal: SwitchModal,
     variable_modal: VariableModal,
     graphic_modal: GraphicPicker,
+
+    confirmation: luminol_core::Confirmation,
+    /// Index of the page that will be deleted once the user confirms it
+    page_pending_deletion: Option<usize>,
 }
 
+/// Drag and drop payload for reordering pages, holding the index of the dragged page.
+struct PageDragPayload(usize);
+
 impl Window {
     /// Create a new event editor.
     pub fn new(
@@ -68,8 +75,50 @@ impl Window {
             switch_2_modal: SwitchModal::new(id_source.with("switch_2_modal")),
             variable_modal: VariableModal::new(id_source.with("variable_modal")),
             graphic_modal,
+
+            confirmation: luminol_core::Confirmation::new(
+                update_state.ctx,
+                format!("luminol_event_edit_confirmation_{map_id}_{}", event.id),
+            ),
+            page_pending_deletion: None,
         }
     }
+
+    /// Removes the page at `index`, keeping `selected_page` on the same page if it wasn't the one
+    /// removed.
+    fn delete_page(event: &mut rpg::Event, selected_page: &mut usize, index: usize) {
+        event.pages.remove(index);
+        if *selected_page > index || *selected_page >= event.pages.len() {
+            *selected_page = selected_page.saturating_sub(1);
+        }
+    }
+}
+
+/// A short summary of a page's conditions, to tell pages apart in the page list.
+fn condition_summary(condition: &rpg::EventCondition) -> String {
+    let mut parts = Vec::new();
+    if condition.switch1_valid {
+        parts.push(format!("S{:0>4}", condition.switch1_id + 1));
+    }
+    if condition.switch2_valid {
+        parts.push(format!("S{:0>4}", condition.switch2_id + 1));
+    }
+    if condition.variable_valid {
+        parts.push(format!(
+            "V{:0>4} ≥ {}",
+            condition.variable_id + 1,
+            condition.variable_value
+        ));
+    }
+    if condition.self_switch_valid {
+        parts.push(format!("Self {}", condition.self_switch_ch));
+    }
+
+    if parts.is_empty() {
+        "Always".to_string()
+    } else {
+        parts.join(" & ")
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -96,6 +145,8 @@ impl luminol_core::Window for Window {
 
         let mut modified = false;
         let mut graphic_modified = false;
+        // set when pages are added, removed or reordered
+        let mut pages_changed = false;
 
         egui::Window::new(format!("Event '{}' ID {}", event.name, self.event_id))
             .open(open)
@@ -110,39 +161,122 @@ impl luminol_core::Window for Window {
                         ui.label("Name: ");
                         ui.text_edit_singleline(&mut event.name);
                     });
-                    ui.horizontal(|ui| {
+                    ui.horizontal_wrapped(|ui| {
                         ui.label("Page: ");
-                        for i in 0..event.pages.len() {
-                            ui.selectable_value(&mut self.selected_page, i, format!("{}", i + 1));
+
+                        let mut moved_page = None;
+                        for (i, page) in event.pages.iter().enumerate() {
+                            let text = format!("{}: {}", i + 1, condition_summary(&page.condition));
+                            let InnerResponse { inner, response } = ui.dnd_drag_source(
+                                id_source.with("page_tab").with(i),
+                                PageDragPayload(i),
+                                |ui| ui.selectable_label(self.selected_page == i, text),
+                            );
+                            if inner
+                                .on_hover_text(
+                                    "Drag to reorder. Pages are checked from last to first, \
+                                    so the last page whose conditions are met is the active one.",
+                                )
+                                .clicked()
+                            {
+                                self.selected_page = i;
+                            }
+
+                            // show where the dragged page will be dropped
+                            if let Some(payload) = response.dnd_hover_payload::<PageDragPayload>() {
+                                let x = if payload.0 < i {
+                                    response.rect.right()
+                                } else {
+                                    response.rect.left()
+                                };
+                                if payload.0 != i {
+                                    ui.painter().vline(
+                                        x,
+                                        response.rect.y_range(),
+                                        ui.visuals().selection.stroke,
+                                    );
+                                }
+                            }
+                            if let Some(payload) = response.dnd_release_payload::<PageDragPayload>()
+                            {
+                                moved_page = Some((payload.0, i));
+                            }
                         }
 
+                        if let Some((from, to)) = moved_page.filter(|(from, to)| from != to) {
+                            let page = event.pages.remove(from);
+                            event.pages.insert(to, page);
+                            // keep the same page selected
+                            self.selected_page = match self.selected_page {
+                                selected if selected == from => to,
+                                selected if from < selected && selected <= to => selected - 1,
+                                selected if to <= selected && selected < from => selected + 1,
+                                selected => selected,
+                            };
+                            pages_changed = true;
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
                         if ui
                             .button(egui::RichText::new("Add").color(egui::Color32::LIGHT_GREEN))
                             .clicked()
                         {
-                            modified |= true;
                             event.pages.push(rpg::EventPage::default());
                             self.selected_page = event.pages.len() - 1;
+                            pages_changed = true;
+                        }
+
+                        if ui
+                            .button("New from Template")
+                            .on_hover_text(
+                                "Add a page with the same conditions and settings as this one, \
+                                but without any commands",
+                            )
+                            .clicked()
+                        {
+                            let mut page = event.pages[self.selected_page].clone();
+                            page.list.clear();
+                            event.pages.insert(self.selected_page + 1, page);
+                            self.selected_page += 1;
+                            pages_changed = true;
+                        }
+
+                        if ui.button("Duplicate").clicked() {
+                            let page = event.pages[self.selected_page].clone();
+                            event.pages.insert(self.selected_page + 1, page);
+                            self.selected_page += 1;
+                            pages_changed = true;
                         }
 
                         let button = egui::Button::new(
                             egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED),
                         );
-                        if ui.add_enabled(event.pages.len() > 1, button).clicked() {
-                            modified |= true;
-                            event.pages.remove(self.selected_page);
-                            self.selected_page = self.selected_page.saturating_sub(1);
+                        if ui.add_enabled(event.pages.len() > 1, button).clicked()
+                            && !self.confirmation.is_open()
+                        {
+                            if update_state.confirm_destructive(
+                                &mut self.confirmation,
+                                luminol_config::global::DestructiveAction::DeleteEventPage,
+                            ) {
+                                let index = self.selected_page;
+                                Self::delete_page(&mut event, &mut self.selected_page, index);
+                                pages_changed = true;
+                            } else {
+                                self.page_pending_deletion = Some(self.selected_page);
+                            }
                         }
+
                         if ui.button(egui::RichText::new("Clear")).clicked() {
-                            modified |= true;
                             event.pages[self.selected_page] = rpg::EventPage::default();
+                            pages_changed = true;
                         }
                     });
                     ui.add_space(1.0); // pad the bottom of the window
                 });
 
                 let page = &mut event.pages[self.selected_page];
-                if self.selected_page != previous_page {
+                if self.selected_page != previous_page || pages_changed {
                     // reset the modal if we've changed pages
                     self.graphic_modal.reset(update_state, &mut page.graphic);
                 }
@@ -285,7 +419,24 @@ impl luminol_core::Window for Window {
                 });
             });
 
-        if graphic_modified {
+        match self.confirmation.show(update_state.global_config) {
+            Some(luminol_config::global::DestructiveAction::DeleteEventPage) => {
+                if let Some(index) = self.page_pending_deletion.take() {
+                    if event.pages.len() > 1 && index < event.pages.len() {
+                        Self::delete_page(&mut event, &mut self.selected_page, index);
+                        self.graphic_modal
+                            .reset(update_state, &mut event.pages[self.selected_page].graphic);
+                        pages_changed = true;
+                    }
+                }
+            }
+            // the user cancelled, so don't delete the page the next time something is confirmed
+            _ if !self.confirmation.is_open() => self.page_pending_deletion = None,
+            _ => {}
+        }
+
+        // the map view draws the first page, which may be a different one now
+        if graphic_modified || pages_changed {
             event.extra_data.graphic_modified.set(true);
         }
 
@@ -293,8 +444,13 @@ impl luminol_core::Window for Window {
         let mut map = update_state.data.get_map(self.map_id);
         map.events.insert(self.event_id, event);
 
-        if modified {
+        if modified || pages_changed {
             map.modified = true;
+            drop(map);
+            update_state.record_edit(luminol_config::recent_edits::EditLocation::Event {
+                map_id: self.map_id,
+                event_id: self.event_id,
+            });
         }
     }
 
@@ -0,0 +1,140 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::{IconGrid, ICON_DIRECTORY};
+
+/// Browses the icons in `Graphics/Icons`, to copy their names or find where they are used.
+#[derive(Default)]
+pub struct Window {
+    grid: IconGrid,
+    /// The icon usages were last searched for, and where it is used.
+    usages: Option<(camino::Utf8PathBuf, Vec<String>)>,
+}
+
+impl Window {
+    /// Lists the database entries that use the icon named `icon`. RPG Maker XP states don't have
+    /// icons, so only items, skills, weapons and armor can use one.
+    fn find_usages(data: &luminol_core::Data, icon: &camino::Utf8Path) -> Vec<String> {
+        let mut usages = Vec::new();
+        luminol_core::asset_references::for_each_database_reference(data, |reference| {
+            if reference.refers_to(ICON_DIRECTORY, icon) {
+                usages.push(reference.location.to_string());
+            }
+            false
+        });
+        usages
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_icon_browser")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut copy_name = None;
+        let mut find_usages = None;
+        let mut rename = None;
+
+        egui::Window::new("Icons")
+            .id(self.id())
+            .default_size([400., 400.])
+            .open(open)
+            .show(ctx, |ui| {
+                if let Some((icon, usages)) = &self.usages {
+                    egui::TopBottomPanel::bottom(self.id().with("usages")).show_inside(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.strong(format!("Usages of {icon}"));
+                            if ui.small_button("Close").clicked() {
+                                find_usages = Some(None);
+                            }
+                        });
+                        if usages.is_empty() {
+                            ui.weak("Not used by any item, skill, weapon or armor");
+                        }
+                        egui::ScrollArea::vertical()
+                            .max_height(150.)
+                            .show(ui, |ui| {
+                                for usage in usages {
+                                    ui.label(usage);
+                                }
+                            });
+                    });
+                }
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    ui.weak("Click an icon to copy its name. Right click for more options.");
+                    let mut context_menu = |ui: &mut egui::Ui, icon: &camino::Utf8Path| {
+                        if ui.button("Copy name").clicked() {
+                            copy_name = Some(icon.to_path_buf());
+                            ui.close_menu();
+                        }
+                        if ui.button("Find usages").clicked() {
+                            find_usages = Some(Some(icon.to_path_buf()));
+                            ui.close_menu();
+                        }
+                        if ui.button("Rename with references…").clicked() {
+                            rename = Some(icon.to_path_buf());
+                            ui.close_menu();
+                        }
+                    };
+                    let selected = self.usages.as_ref().map(|(icon, _)| icon.as_path());
+                    if let Some(icon) =
+                        self.grid
+                            .ui(ui, update_state, selected, Some(&mut context_menu))
+                    {
+                        copy_name = Some(icon);
+                    }
+                });
+            });
+
+        if let Some(icon) = copy_name {
+            ctx.output_mut(|o| o.copied_text = icon.to_string());
+            luminol_core::info!(
+                update_state.toasts,
+                format!("Copied {icon} to the clipboard")
+            );
+        }
+        if let Some(icon) = find_usages {
+            self.usages = icon.map(|icon| {
+                let usages = Self::find_usages(update_state.data, &icon);
+                (icon, usages)
+            });
+        }
+        if let Some(icon) = rename {
+            update_state
+                .edit_windows
+                .add_window(super::asset_rename::Window::new(ICON_DIRECTORY, Some(icon)));
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
@@ -24,11 +24,12 @@
 
 use crate::{
     components::{
-        DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection,
-        OptionalIdComboBox, UiExt,
+        BattlerData, DamageCalculator, DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection,
+        IdVecSelection, OptionalIdComboBox, UiExt,
     },
-    modals::{graphic_picker::basic::Modal as GraphicPicker, sound_picker::Modal as SoundPicker},
+    modals::{icon_picker::Modal as IconPicker, sound_picker::Modal as SoundPicker},
 };
+use luminol_config::recent_edits::{Database, EditLocation};
 use luminol_core::Modal;
 
 /// Database - Items management window.
@@ -36,33 +37,34 @@ pub struct Window {
     selected_item_name: Option<String>,
 
     menu_se_picker: SoundPicker,
-    graphic_picker: GraphicPicker,
+    icon_picker: IconPicker,
 
     previous_item: Option<usize>,
 
+    calculator: DamageCalculator,
+
     view: DatabaseView,
 }
 
 impl Window {
-    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
-        let items = update_state.data.items();
-        let item = &items.data[0];
+    pub fn new() -> Self {
         Self {
             selected_item_name: None,
             menu_se_picker: SoundPicker::new(luminol_audio::Source::SE, "item_menu_se_picker"),
-            graphic_picker: GraphicPicker::new(
-                update_state,
-                "Graphics/Icons".into(),
-                item.icon_name.as_deref(),
-                egui::vec2(32., 32.),
-                "item_icon_picker",
-            ),
+            icon_picker: IconPicker::new("item_icon_picker"),
             previous_item: None,
+            calculator: DamageCalculator::new(),
             view: DatabaseView::new(),
         }
     }
 }
 
+impl Default for Window {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("item_editor")
@@ -84,6 +86,18 @@ impl luminol_core::Window for Window {
         let common_events = data.common_events();
         let system = data.system();
         let states = data.states();
+        let actors = data.actors();
+        let classes = data.classes();
+        let enemies = data.enemies();
+        let weapons = data.weapons();
+        let armors = data.armors();
+        let battler_data = BattlerData {
+            actors: &actors.data,
+            classes: &classes.data,
+            enemies: &enemies.data,
+            weapons: &weapons.data,
+            armors: &armors.data,
+        };
 
         let mut modified = false;
 
@@ -115,13 +129,12 @@ impl luminol_core::Window for Window {
                                 modified |= ui
                                     .add(Field::new(
                                         "Icon",
-                                        self.graphic_picker
-                                            .button(&mut item.icon_name, update_state),
+                                        self.icon_picker.button(&mut item.icon_name, update_state),
                                     ))
                                     .changed();
                                 if self.previous_item != Some(item.id) {
                                     // avoid desyncs by resetting the modal if the item has changed
-                                    self.graphic_picker.reset(update_state, &mut item.icon_name);
+                                    self.icon_picker.reset(update_state, &mut item.icon_name);
                                 }
 
                                 modified |= ui
@@ -406,6 +419,19 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.with_padded_stripe(true, |ui| {
+                            egui::CollapsingHeader::new("Damage calculator")
+                                .id_source(("item_damage_calculator", item.id))
+                                .show(ui, |ui| {
+                                    self.calculator.item_ui(
+                                        ui,
+                                        "item_damage_calculator",
+                                        &battler_data,
+                                        item,
+                                    );
+                                });
+                        });
+
                         self.previous_item = Some(item.id);
                     },
                 )
@@ -418,6 +444,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             items.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Items,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(items);
@@ -425,6 +455,11 @@ impl luminol_core::Window for Window {
         drop(common_events);
         drop(system);
         drop(states);
+        drop(actors);
+        drop(classes);
+        drop(enemies);
+        drop(weapons);
+        drop(armors);
 
         *update_state.data = data; // restore data
     }
@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 /// The map picker window.
 /// Displays a list of maps in a tree.
@@ -35,12 +35,20 @@ impl Window {
         id: usize,
         children_data: &BTreeMap<usize, BTreeSet<usize>>,
         mapinfos: &mut luminol_data::rpg::MapInfos,
+        modified_maps: &HashSet<usize>,
+        start_map_id: usize,
         open_map_id: &mut Option<usize>,
         ui: &mut egui::Ui,
     ) {
         // We get the map name. It's assumed that there is in fact a map with this ID in mapinfos.
         let map_info = mapinfos.data.get_mut(&id).unwrap();
 
+        // Maps with unsaved changes are shown in the same color as strong (bold) text
+        let mut name_edit = egui::TextEdit::singleline(&mut map_info.name);
+        if modified_maps.contains(&id) {
+            name_edit = name_edit.text_color(ui.visuals().strong_text_color());
+        }
+
         // Does this map have children?
         if children_data.contains_key(&id) {
             // Render a custom collapsing header.
@@ -56,28 +64,48 @@ impl Window {
             header
                 .show_header(ui, |ui| {
                     // Has the user
-                    if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
+                    if ui.add(name_edit).double_clicked() {
                         *open_map_id = Some(id)
                     }
+                    if id == start_map_id {
+                        start_marker(ui);
+                    }
                 })
                 .body(|ui| {
                     for id in children_data.get(&id).unwrap() {
                         // Render children.
-                        Self::render_submap(*id, children_data, mapinfos, open_map_id, ui);
+                        Self::render_submap(
+                            *id,
+                            children_data,
+                            mapinfos,
+                            modified_maps,
+                            start_map_id,
+                            open_map_id,
+                            ui,
+                        );
                     }
                 });
         } else {
             // Just display a label otherwise.
             ui.horizontal(|ui| {
                 ui.add_space(ui.spacing().indent);
-                if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
+                if ui.add(name_edit).double_clicked() {
                     *open_map_id = Some(id)
                 }
+                if id == start_map_id {
+                    start_marker(ui);
+                }
             });
         }
     }
 }
 
+/// Marks the map new games start on.
+fn start_marker(ui: &mut egui::Ui) {
+    ui.colored_label(egui::Color32::from_rgb(0, 200, 83), "🚩")
+        .on_hover_text("New games start on this map");
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("Map Picker")
@@ -91,6 +119,7 @@ impl luminol_core::Window for Window {
     ) {
         let mut window_open = true;
         egui::Window::new("Map Picker")
+            .id(self.id())
             .open(&mut window_open)
             .show(ctx, |ui| {
                 egui::ScrollArea::both()
@@ -104,6 +133,27 @@ impl luminol_core::Window for Window {
                     )
                     .auto_shrink([false; 2])
                     .show(ui, |ui| {
+                        let modified_maps = update_state
+                            .data
+                            .modified_structures()
+                            .into_iter()
+                            .filter_map(|(structure, _)| match structure {
+                                luminol_core::Structure::Map(id) => Some(id),
+                                _ => None,
+                            })
+                            .collect::<HashSet<_>>();
+
+                        if ui
+                            .button("🚩 Go to start position")
+                            .on_hover_text(
+                                "Open the map new games start on, centered on the start tile",
+                            )
+                            .clicked()
+                        {
+                            crate::tabs::map::go_to_start_position(update_state);
+                        }
+                        let start_map_id = update_state.data.system().start_map_id;
+
                         // Aquire the data cache.
                         let mut mapinfos = update_state.data.map_infos();
 
@@ -131,6 +181,8 @@ impl luminol_core::Window for Window {
                                         id,
                                         &children_data,
                                         &mut mapinfos,
+                                        &modified_maps,
+                                        start_map_id,
                                         &mut open_map_id,
                                         ui,
                                     );
@@ -140,13 +192,8 @@ impl luminol_core::Window for Window {
                         drop(mapinfos);
 
                         if let Some(id) = open_map_id {
-                            match crate::tabs::map::Tab::new(id, update_state) {
-                                Ok(tab) => update_state.edit_tabs.add_tab(tab),
-                                Err(e) => luminol_core::error!(
-                                    update_state.toasts,
-                                    e.wrap_err("Error enumerating maps")
-                                ),
-                            }
+                            let tab = crate::tabs::map::Tab::new(id, update_state);
+                            update_state.edit_tabs.add_tab(tab);
                         }
                     })
             });
@@ -38,6 +38,7 @@ impl luminol_core::Window for EguiInspection {
         _update_state: &mut luminol_core::UpdateState<'_>,
     ) {
         egui::Window::new("Egui Inspection")
+            .id(self.id())
             .open(open)
             .show(ctx, |ui| ctx.inspection_ui(ui));
     }
@@ -59,6 +60,7 @@ impl luminol_core::Window for EguiMemory {
         _update_state: &mut luminol_core::UpdateState<'_>,
     ) {
         egui::Window::new("Egui Memory")
+            .id(self.id())
             .open(open)
             .show(ctx, |ui| ctx.memory_ui(ui));
     }
@@ -79,6 +81,7 @@ impl luminol_core::Window for FilesystemDebug {
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
         egui::Window::new("Filesystem Debug")
+            .id(self.id())
             .open(open)
             .show(ctx, |ui| update_state.filesystem.debug_ui(ui));
     }
@@ -89,6 +92,7 @@ pub struct WgpuDebugInfo {
     adapter_features: wgpu::Features,
     adapter_limits: wgpu::Limits,
     downlevel_caps: wgpu::DownlevelCapabilities,
+    compatibility: luminol_graphics::Compatibility,
 }
 
 impl WgpuDebugInfo {
@@ -107,6 +111,7 @@ impl WgpuDebugInfo {
             adapter_features,
             adapter_limits,
             downlevel_caps,
+            compatibility: update_state.graphics.compatibility.clone(),
         }
     }
 }
@@ -123,6 +128,7 @@ impl luminol_core::Window for WgpuDebugInfo {
         _: &mut luminol_core::UpdateState<'_>,
     ) {
         egui::Window::new("WGPU Debug Info")
+            .id(self.id())
             .open(open)
             .scroll([false, true])
             .show(ctx, |ui| {
@@ -133,6 +139,17 @@ impl luminol_core::Window for WgpuDebugInfo {
                     );
                 }
 
+                if self.compatibility.is_active() {
+                    ui.heading("Compatibility mode");
+                    ui.separator();
+
+                    ui.add_space(16.);
+                    for fallback in &self.compatibility.fallbacks {
+                        ui.label(format!("• {fallback}"));
+                    }
+                    ui.add_space(16.);
+                }
+
                 ui.heading("Adapter info");
                 ui.separator();
 
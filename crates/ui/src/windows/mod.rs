@@ -28,23 +28,39 @@ pub mod about;
 pub mod actors;
 /// The animation editor.
 pub mod animations;
+/// The appearance window, for quickly changing the visuals and code theme.
+pub mod appearance;
 /// The archive manager for creating and extracting RGSSAD archives.
 pub mod archive_manager;
 /// The armor editor.
 pub mod armor;
+/// The asset renamer, which updates references to the renamed file.
+pub mod asset_rename;
+/// The batch script runner for editing maps with scripts.
+pub mod batch_script;
+/// The editor for custom brush shapes.
+pub mod brush_shapes;
 /// The class editor.
 pub mod classes;
 /// The common event editor.
 pub mod common_event_edit;
+/// The event ID compaction tool for every map.
+pub mod compact_event_ids;
 /// Config window
 pub mod config_window;
 /// Playtest console
 #[cfg(not(target_arch = "wasm32"))]
 pub mod console;
+/// The dialogue script export.
+pub mod dialogue_export;
+/// The dialogue script import.
+pub mod dialogue_import;
 /// The enemy editor.
 pub mod enemies;
 /// The event editor.
 pub mod event_edit;
+/// The icon browser.
+pub mod icon_browser;
 /// The item editor.
 pub mod items;
 /// The map picker.
@@ -66,5 +82,13 @@ pub mod skills;
 pub mod sound_test;
 /// The state editor.
 pub mod states;
+/// The system editor.
+pub mod system;
+/// The troop editor.
+pub mod troops;
+/// The project validation results.
+pub mod validation;
+/// The read-only viewer for RPG Maker VX Ace maps.
+pub mod vx_ace_map_viewer;
 /// The weapon editor.
 pub mod weapons;
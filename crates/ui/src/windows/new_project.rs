@@ -77,6 +77,7 @@ impl luminol_core::Window for Window {
     ) {
         let mut win_open = true;
         egui::Window::new("New Project")
+            .id(self.id())
             .open(&mut win_open)
             .show(ctx, |ui| {
                 ui.add_enabled_ui(
@@ -222,7 +223,7 @@ impl Window {
         host_fs.create_file(format!("{}.lumproj", config.project.project_name))?;
 
         let mut data_cache = luminol_core::Data::from_defaults();
-        data_cache.save(&host_fs, &config)?;
+        data_cache.save_all(&host_fs, &config)?;
 
         if download_executable {
             Self::download_executable(&config, &host_fs, progress)
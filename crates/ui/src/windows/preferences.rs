@@ -23,14 +23,35 @@
 // Program grant you additional permission to convey the resulting work.
 #[cfg(not(target_arch = "wasm32"))]
 use egui::Widget;
+#[cfg(not(target_arch = "wasm32"))]
+use futures_lite::AsyncReadExt;
+use futures_lite::AsyncWriteExt;
+use luminol_config::global::{Config, Section};
 use strum::IntoEnumIterator;
 
+/// An imported config, along with the sections that differ from the current config and whether
+/// each of them will be applied.
+type ImportedConfig = (Box<Config>, Vec<(Section, bool)>);
+
 #[derive(Default)]
 pub struct Window {
     #[cfg(not(target_arch = "wasm32"))]
     edit_rtp_path_name: String,
     #[cfg(not(target_arch = "wasm32"))]
     edit_rtp_path_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    edit_external_program_extension: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    edit_external_program_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    terminal_theme_promise: Option<poll_promise::Promise<color_eyre::Result<String>>>,
+
+    /// Whether exported configs keep settings that only make sense on this machine
+    export_machine_specific: bool,
+    export_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
+    import_promise: Option<poll_promise::Promise<color_eyre::Result<Config>>>,
+    /// An imported config waiting for the user to look over the changes
+    imported_config: Option<ImportedConfig>,
 
     tab: Tab,
 }
@@ -42,7 +63,7 @@ enum Tab {
     #[cfg_attr(not(target_arch = "wasm32"), default)]
     #[strum(to_string = "Editor Settings")]
     #[cfg(not(target_arch = "wasm32"))]
-    // only setting right now is editing rtp paths, which is not supported on wasm
+    // rtp paths and external programs are not supported on wasm
     EditorSettings,
     #[strum(to_string = "Egui Visuals")]
     #[cfg_attr(target_arch = "wasm32", default)]
@@ -51,17 +72,27 @@ enum Tab {
     PresetVisuals,
     #[strum(to_string = "Code Theme")]
     CodeTheme,
+    Confirmations,
+    Previews,
+    #[strum(to_string = "Overlay Colors")]
+    OverlayColors,
+    #[strum(to_string = "Map Editor")]
+    MapEditor,
+    Saving,
     #[cfg(not(target_arch = "wasm32"))]
     Terminal,
+    #[strum(to_string = "Import/Export")]
+    ImportExport,
 }
 
-const CODE_SAMPLE: &str = luminol_macros::include_asset_str!("assets/ruby/code_sample.rb");
+pub(super) const CODE_SAMPLE: &str =
+    luminol_macros::include_asset_str!("assets/ruby/code_sample.rb");
 
 #[derive(Clone)]
-struct PresetTheme {
-    name: &'static str,
-    visuals: egui::Visuals,
-    description: &'static str,
+pub(super) struct PresetTheme {
+    pub(super) name: &'static str,
+    pub(super) visuals: egui::Visuals,
+    pub(super) description: &'static str,
 }
 
 macro_rules! preset_theme {
@@ -78,55 +109,224 @@ macro_rules! preset_theme {
     };
 }
 
-static PRESET_VISUALS: once_cell::sync::Lazy<[PresetTheme; 7]> = once_cell::sync::Lazy::new(|| {
-    //
-    let catppuccin_frappe = preset_theme!(
-        "Catppuccin Frappe",
-        "themes/catppuccin_frappe.ron",
-        "A less vibrant alternative theme using subdued colors for a muted aesthetic"
-    );
-    let catppuccin_latte = preset_theme!(
+pub(super) static PRESET_VISUALS: once_cell::sync::Lazy<[PresetTheme; 7]> =
+    once_cell::sync::Lazy::new(|| {
+        //
+        let catppuccin_frappe = preset_theme!(
+            "Catppuccin Frappe",
+            "themes/catppuccin_frappe.ron",
+            "A less vibrant alternative theme using subdued colors for a muted aesthetic"
+        );
+        let catppuccin_latte = preset_theme!(
             "Catppuccin Latte",
             "themes/catppuccin_latte.ron",
             "Catppuccin's lightest theme harmoniously inverting the essence of Catppuccin's dark themes"
         );
-    let catppuccin_macchiato = preset_theme!(
-        "Catppuccin Macchiato",
-        "themes/catppuccin_macchiato.ron",
-        "A theme with medium contrast and gentle colors creating a soothing atmosphere"
-    );
-    let catppuccin_mocha = preset_theme!(
-        "Catppuccin Mocha",
-        "themes/catppuccin_mocha.ron",
-        "Catppuccin's darkest variant offering a cozy feeling with color-rich accents"
-    );
-    let luminol = preset_theme!(
-        "Luminol",
-        "themes/luminol.ron",
-        "A high-contrast dark theme based on the Luminol website"
-    );
+        let catppuccin_macchiato = preset_theme!(
+            "Catppuccin Macchiato",
+            "themes/catppuccin_macchiato.ron",
+            "A theme with medium contrast and gentle colors creating a soothing atmosphere"
+        );
+        let catppuccin_mocha = preset_theme!(
+            "Catppuccin Mocha",
+            "themes/catppuccin_mocha.ron",
+            "Catppuccin's darkest variant offering a cozy feeling with color-rich accents"
+        );
+        let luminol = preset_theme!(
+            "Luminol",
+            "themes/luminol.ron",
+            "A high-contrast dark theme based on the Luminol website"
+        );
 
-    let egui_dark = PresetTheme {
-        name: "Egui Dark",
-        visuals: egui::Visuals::dark(),
-        description: "The default theme from Luminol's GUI framework",
-    };
-    let egui_light = PresetTheme {
-        name: "Egui Light",
-        visuals: egui::Visuals::light(),
-        description: "The default light theme from Luminol's GUI framework.",
-    };
+        let egui_dark = PresetTheme {
+            name: "Egui Dark",
+            visuals: egui::Visuals::dark(),
+            description: "The default theme from Luminol's GUI framework",
+        };
+        let egui_light = PresetTheme {
+            name: "Egui Light",
+            visuals: egui::Visuals::light(),
+            description: "The default light theme from Luminol's GUI framework.",
+        };
+
+        [
+            catppuccin_latte,
+            catppuccin_frappe,
+            catppuccin_macchiato,
+            catppuccin_mocha,
+            luminol,
+            egui_dark,
+            egui_light,
+        ]
+    });
+
+impl Window {
+    fn import_export_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        ui.label("Export");
+        ui.separator();
+        ui.checkbox(
+            &mut self.export_machine_specific,
+            "Include settings that only make sense on this machine",
+        )
+        .on_hover_text("Recent projects, RTP paths and external programs");
+        if self.export_promise.is_some() {
+            ui.spinner();
+        } else if ui.button("Export config...").clicked() {
+            let config = if self.export_machine_specific {
+                update_state.global_config.clone()
+            } else {
+                update_state.global_config.without_machine_specific()
+            };
+            self.export_promise = Some(luminol_core::spawn_future(async move {
+                let mut file = luminol_filesystem::host::File::new()?;
+                ron::ser::to_writer_pretty(
+                    std::io::BufWriter::new(&mut file),
+                    &config,
+                    ron::ser::PrettyConfig::new().struct_names(true),
+                )?;
+                file.flush().await?;
+                file.save("luminol_config.ron", "RON files").await?;
+                Ok(())
+            }));
+        }
+        if let Some(p) = self.export_promise.take() {
+            match p.try_take() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    if !matches!(
+                        e.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error exporting config")
+                        );
+                    }
+                }
+                Err(p) => self.export_promise = Some(p),
+            }
+        }
+
+        ui.add_space(6.);
+        ui.label("Import");
+        ui.separator();
+        if self.import_promise.is_some() {
+            ui.spinner();
+        } else if ui.button("Import config...").clicked() {
+            self.imported_config = None;
+            self.import_promise = Some(luminol_core::spawn_future(async move {
+                let (file, _) =
+                    luminol_filesystem::host::File::from_file_picker("RON files", &["ron"]).await?;
+                let config: Config = ron::de::from_reader(std::io::BufReader::new(file))?;
+                Ok(config)
+            }));
+        }
+        if let Some(p) = self.import_promise.take() {
+            match p.try_take() {
+                Ok(Ok(config)) => {
+                    let sections = Section::iter()
+                        .filter(|&section| {
+                            update_state.global_config.section_to_ron(section).ok()
+                                != config.section_to_ron(section).ok()
+                        })
+                        .map(|section| {
+                            // Configs exported without the machine-specific settings have their
+                            // defaults there, which shouldn't replace the settings on this machine
+                            let is_default = config.section_to_ron(section).ok()
+                                == Config::new().section_to_ron(section).ok();
+                            (section, !(section.is_machine_specific() && is_default))
+                        })
+                        .collect();
+                    self.imported_config = Some((Box::new(config), sections));
+                }
+                Ok(Err(e)) => {
+                    if !matches!(
+                        e.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error importing config")
+                        );
+                    }
+                }
+                Err(p) => self.import_promise = Some(p),
+            }
+        }
+
+        let mut finished = false;
+        if let Some((config, sections)) = &mut self.imported_config {
+            if sections.is_empty() {
+                ui.label("The imported config is the same as the current one.");
+                finished = ui.button("OK").clicked();
+            } else {
+                ui.label("These settings will change:");
+                for (section, apply) in sections.iter_mut() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(apply, "");
+                        ui.collapsing(section.to_string(), |ui| {
+                            ui.columns(2, |columns| {
+                                let [current, imported] = columns else {
+                                    unreachable!()
+                                };
+                                current.label("Current");
+                                current.monospace(
+                                    update_state
+                                        .global_config
+                                        .section_to_ron(*section)
+                                        .unwrap_or_default(),
+                                );
+                                imported.label("Imported");
+                                imported
+                                    .monospace(config.section_to_ron(*section).unwrap_or_default());
+                            });
+                        });
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        for &(section, apply) in sections.iter() {
+                            if apply {
+                                update_state
+                                    .global_config
+                                    .copy_section((**config).clone(), section);
+                            }
+                        }
+                        luminol_core::info!(update_state.toasts, "Imported config");
+                        finished = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        finished = true;
+                    }
+                });
+            }
+        }
+        if finished {
+            self.imported_config = None;
+        }
 
-    [
-        catppuccin_latte,
-        catppuccin_frappe,
-        catppuccin_macchiato,
-        catppuccin_mocha,
-        luminol,
-        egui_dark,
-        egui_light,
-    ]
-});
+        ui.add_space(6.);
+        ui.label("Restore defaults");
+        ui.separator();
+        egui::Grid::new("luminol_preferences_restore_defaults")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for section in Section::iter() {
+                    ui.label(section.to_string());
+                    if ui.button("Restore defaults").clicked() {
+                        update_state.global_config.restore_defaults(section);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
 
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
@@ -139,221 +339,494 @@ impl luminol_core::Window for Window {
         open: &mut bool,
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
-        egui::Window::new("Preferences").open(open).show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                for mode in Tab::iter() {
-                    ui.selectable_value(&mut self.tab, mode, mode.to_string());
-                }
-            });
-            ui.separator();
-
-            match self.tab {
-                Tab::EguiVisuals => {
-                    // TODO maybe make a custom visuals editor?
-                    let mut visuals = ctx.style().visuals.clone();
-                    visuals.ui(ui);
-                    ctx.set_visuals(visuals);
-                }
-                Tab::PresetVisuals => ui.columns(2, |cols| {
-                    let [left, right] = cols else { unreachable!() };
-
-                    let mut hover_visual = None;
-                    egui::ScrollArea::vertical().show(left, |ui| {
-                        ui.visuals_mut().button_frame = false;
-                        for PresetTheme {
-                            name,
-                            visuals,
-                            description,
-                        } in PRESET_VISUALS.iter()
-                        {
-                            let response = ui.button(*name).on_hover_text(*description);
-                            if response.hovered() {
-                                hover_visual = Some(visuals.clone());
-                            }
-                            if response.clicked() {
-                                ctx.set_visuals(visuals.clone())
-                            }
-                        }
-                    });
+        egui::Window::new("Preferences")
+            .id(self.id())
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for mode in Tab::iter() {
+                        ui.selectable_value(&mut self.tab, mode, mode.to_string());
+                    }
+                });
+                ui.separator();
 
-                    if let Some(hover_visual) = hover_visual {
-                        *right.visuals_mut() = hover_visual;
-                    };
-
-                    let frame = egui::Frame {
-                        shadow: egui::epaint::Shadow::NONE,
-                        ..egui::Frame::window(right.style())
-                    };
-                    frame.show(right, |ui| {
-                        egui::Grid::new("luminol-preset-theme-gallery")
-                            .num_columns(2)
-                            .spacing([40.0, 4.0])
-                            .striped(true)
-                            .show(ui, gallery_grid_contents)
-                    });
-                }),
-                Tab::CodeTheme => {
-                    ui.horizontal(|ui| {
-                        ui.vertical(|ui| {
-                            for t in luminol_config::SyntectTheme::iter() {
-                                ui.radio_value(
-                                    &mut update_state.global_config.theme.syntect_theme,
-                                    t,
-                                    t.to_string(),
-                                );
+                match self.tab {
+                    Tab::EguiVisuals => {
+                        // TODO maybe make a custom visuals editor?
+                        let mut visuals = ctx.style().visuals.clone();
+                        visuals.ui(ui);
+                        ctx.set_visuals(visuals);
+                    }
+                    Tab::PresetVisuals => ui.columns(2, |cols| {
+                        let [left, right] = cols else { unreachable!() };
+
+                        let mut hover_visual = None;
+                        egui::ScrollArea::vertical().show(left, |ui| {
+                            ui.visuals_mut().button_frame = false;
+                            for PresetTheme {
+                                name,
+                                visuals,
+                                description,
+                            } in PRESET_VISUALS.iter()
+                            {
+                                let response = ui.button(*name).on_hover_text(*description);
+                                if response.hovered() {
+                                    hover_visual = Some(visuals.clone());
+                                }
+                                if response.clicked() {
+                                    ctx.set_visuals(visuals.clone())
+                                }
                             }
                         });
 
-                        ui.vertical(|ui| {
-                            ui.label("Code sample");
-                            ui.label(crate::components::syntax_highlighting::highlight(
-                                ui.ctx(),
-                                update_state.global_config.theme,
-                                CODE_SAMPLE,
-                                "rb",
-                            ));
+                        if let Some(hover_visual) = hover_visual {
+                            *right.visuals_mut() = hover_visual;
+                        };
+
+                        let frame = egui::Frame {
+                            shadow: egui::epaint::Shadow::NONE,
+                            ..egui::Frame::window(right.style())
+                        };
+                        frame.show(right, |ui| {
+                            egui::Grid::new("luminol-preset-theme-gallery")
+                                .num_columns(2)
+                                .spacing([40.0, 4.0])
+                                .striped(true)
+                                .show(ui, gallery_grid_contents)
                         });
-                    });
-                }
-                #[cfg(not(target_arch = "wasm32"))]
-                Tab::EditorSettings => {
-                    ui.label("RTP Paths");
-                    ui.separator();
-
-                    ui.columns(2, |columns| {
-                        let mut new_rtp_paths: indexmap::IndexMap<_, _> = update_state
-                            .global_config
-                            .rtp_paths
-                            .drain(..)
-                            .filter_map(|(mut rtp_name, mut rtp_path)| {
-                                let res = columns[0].horizontal(|ui| {
-                                    let res = ui.button(
-                                        egui::RichText::new("-")
-                                            .monospace()
-                                            .color(egui::Color32::RED),
+                    }),
+                    Tab::CodeTheme => {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                for t in luminol_config::SyntectTheme::iter() {
+                                    ui.radio_value(
+                                        &mut update_state.global_config.theme.syntect_theme,
+                                        t,
+                                        t.to_string(),
                                     );
-                                    ui.text_edit_singleline(&mut rtp_name);
-                                    res.clicked()
+                                }
+                            });
+
+                            ui.vertical(|ui| {
+                                ui.label("Code sample");
+                                ui.label(crate::components::syntax_highlighting::highlight(
+                                    ui.ctx(),
+                                    update_state.global_config.theme,
+                                    CODE_SAMPLE,
+                                    "rb",
+                                ));
+                            });
+                        });
+                    }
+                    Tab::Confirmations => {
+                        let config = &mut *update_state.global_config;
+                        ui.checkbox(
+                            &mut config.confirm_destructive_actions,
+                            "Ask for confirmation before destructive actions",
+                        );
+                        ui.separator();
+
+                        ui.add_enabled_ui(config.confirm_destructive_actions, |ui| {
+                            ui.label("Actions you chose not to be asked about again");
+
+                            if config.skipped_confirmations.is_empty() {
+                                ui.weak("None");
+                            }
+
+                            let mut reset = None;
+                            for &action in config.skipped_confirmations.iter() {
+                                ui.horizontal(|ui| {
+                                    if ui.button("Reset").clicked() {
+                                        reset = Some(action);
+                                    }
+                                    ui.label(action.to_string());
                                 });
-                                columns[1].text_edit_singleline(&mut rtp_path);
-                                (!res.inner).then_some((rtp_name, rtp_path))
-                            })
-                            .collect();
+                            }
+                            if let Some(action) = reset {
+                                config.skipped_confirmations.remove(&action);
+                            }
 
-                        columns[0].horizontal(|ui| {
                             if ui
-                                .button(
-                                    egui::RichText::new("+")
-                                        .monospace()
-                                        .color(egui::Color32::GREEN),
+                                .add_enabled(
+                                    !config.skipped_confirmations.is_empty(),
+                                    egui::Button::new("Reset all"),
                                 )
                                 .clicked()
                             {
-                                new_rtp_paths.insert(
-                                    std::mem::take(&mut self.edit_rtp_path_name),
-                                    std::mem::take(&mut self.edit_rtp_path_path),
-                                );
+                                config.skipped_confirmations.clear();
                             }
-                            ui.text_edit_singleline(&mut self.edit_rtp_path_name);
                         });
-                        columns[1].text_edit_singleline(&mut self.edit_rtp_path_path);
+                    }
+                    Tab::Previews => {
+                        use luminol_config::global::PreviewBackground;
 
-                        update_state.global_config.rtp_paths = new_rtp_paths;
-                    });
-                }
-                #[cfg(not(target_arch = "wasm32"))]
-                Tab::Terminal => {
-                    let config = &mut update_state.global_config.terminal;
-                    ui.horizontal(|ui| {
-                        ui.label("Initial terminal size:");
-                        egui::DragValue::new(&mut config.initial_size.0)
-                            .range(1..=999)
-                            .ui(ui);
-                        ui.label("column(s)");
-                        egui::DragValue::new(&mut config.initial_size.1)
-                            .range(1..=999)
-                            .ui(ui);
-                        ui.label("rows(s)");
-                    });
-                    // ui.horizontal(|ui| {
-                    // ui.label("Font family");
-                    // luminol_components::EnumMenuButton::new(
-                    //     &mut self.font_family,
-                    //     "luminol_term_config_ui_font_family",
-                    // )
-                    // .ui(ui);
-                    // let is_custom = matches!(self.font_family, FontFamily::Custom(_));
-                    // ui.add_enabled_ui(is_custom, |ui| {
-                    //     let mut dummy_text = String::new(); // this doesn't allocate so this is fine, for display purposes
-                    //     let text = match &mut self.font_family {
-                    //         FontFamily::Custom(t) => t,
-                    //         _ => &mut dummy_text,
-                    //     };
-                    //     ui.text_edit_singleline(text);
-                    // });
-                    // ui.label("Font size");
-                    // egui::DragValue::new(&mut self.font_size)
-                    //     .clamp_range(1..=80)
-                    //     .update_while_editing(false)
-                    //     .ui(ui);
-                    // if ui.button("Apply").clicked() {
-                    //     config.font.family = match &self.font_family {
-                    //         FontFamily::Monospace => egui::FontFamily::Monospace,
-                    //         FontFamily::Proportional => egui::FontFamily::Proportional,
-                    //         FontFamily::Custom(name) => {
-                    //             egui::FontFamily::Name(name.as_str().into())
-                    //         } // FIXME doesn't properly handle missing fonts
-                    //     };
-                    //     config.font.size = self.font_size;
-                    // }
-                    // });
-                    crate::components::Field::new(
-                        "Cursor blinking",
-                        crate::components::EnumComboBox::new(
-                            "luminol_term_config_ui_cursor_blinking",
-                            &mut config.cursor_blinking,
-                        )
-                        .max_width(12.)
-                        .wrap_mode(egui::TextWrapMode::Extend),
-                    )
-                    .ui(ui);
-                    ui.add_space(6.);
-                    ui.label("Ui colors");
-                    ui.separator();
-                    ui.columns(2, |cols| {
-                        let [left, right] = cols else {
-                            unreachable!();
-                        };
-                        left.label("Cursor");
-                        let mut arr = color_to_rgb(config.theme.cursor_color);
-                        left.color_edit_button_srgb(&mut arr);
-                        config.theme.cursor_color = color_from_rgb(arr);
-                        right.label("Background");
-                        let mut arr = color_to_rgb(config.theme.background_color);
-                        right.color_edit_button_srgb(&mut arr);
-                        config.theme.background_color = color_from_rgb(arr);
-                    });
-                    ui.add_space(6.);
-                    ui.label("Pallette");
-                    for colors in config.theme.color_pallette.chunks_mut(8) {
+                        let background = &mut update_state.global_config.preview_background;
+                        ui.label("Background behind transparent image previews");
+                        ui.separator();
+
+                        ui.radio_value(background, PreviewBackground::Checkerboard, "Checkerboard");
+                        ui.radio_value(background, PreviewBackground::Map, "Map background")
+                            .on_hover_text("Black, like a map without a panorama in game");
                         ui.horizontal(|ui| {
-                            for color in colors {
-                                let mut arr = color_to_rgb(*color);
-                                ui.color_edit_button_srgb(&mut arr);
-                                *color = color_from_rgb(arr);
+                            let is_custom = matches!(background, PreviewBackground::Custom(_));
+                            let mut color = match background {
+                                PreviewBackground::Custom(color) => *color,
+                                _ => egui::Color32::GRAY,
+                            };
+                            if ui.radio(is_custom, "Custom color").clicked() {
+                                *background = PreviewBackground::Custom(color);
                             }
+                            ui.add_enabled_ui(is_custom, |ui| {
+                                if ui.color_edit_button_srgba(&mut color).changed() {
+                                    *background = PreviewBackground::Custom(color);
+                                }
+                            });
+                        });
+                    }
+                    Tab::OverlayColors => {
+                        use luminol_config::global::OverlayPalette;
+
+                        let palette = &mut update_state.global_config.overlay_palette;
+                        ui.label("Colors of map overlays and selections");
+                        ui.separator();
+
+                        for preset in OverlayPalette::iter() {
+                            ui.radio_value(palette, preset, preset.to_string());
+                        }
+
+                        ui.add_space(8.);
+                        overlay_preview(ui, palette.colors());
+                    }
+                    Tab::MapEditor => {
+                        use luminol_config::global::EventCreation;
+
+                        ui.add(
+                            egui::Slider::new(
+                                &mut update_state.global_config.map_history_size,
+                                1..=500,
+                            )
+                            .text("Undo history size"),
+                        )
+                        .on_hover_text(
+                            "How many changes to each open map can be undone. The oldest changes are forgotten first.",
+                        );
+
+                        ui.separator();
+
+                        let config = &mut *update_state.global_config;
+                        ui.label("Create events on empty tiles with");
+                        for gesture in EventCreation::iter() {
+                            ui.radio_value(
+                                &mut config.event_creation,
+                                gesture,
+                                gesture.to_string(),
+                            );
+                        }
+                        ui.add_enabled(
+                            config.event_creation == EventCreation::DoubleClick,
+                            egui::Slider::new(&mut config.event_double_click_time, 0.2..=1.0)
+                                .text("Double-click time")
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(
+                            "How long the second click can come after the first one. The clicks count as a double-click as long as they're on the same tile, even if the mouse moved in between.",
+                        );
+                        ui.weak("The map's right-click menu can always create events.");
+                    }
+                    Tab::Saving => {
+                        let interval = &mut update_state.global_config.autosave_interval_secs;
+                        let mut autosave = interval.is_some();
+                        if ui
+                            .checkbox(&mut autosave, "Autosave")
+                            .on_hover_text(
+                                "Saves the project automatically a while after it was changed. Autosaving waits until dialogs like the unsaved changes prompt are closed.",
+                            )
+                            .changed()
+                        {
+                            *interval =
+                                autosave.then_some(Config::DEFAULT_AUTOSAVE_INTERVAL_SECS);
+                        }
+                        if let Some(secs) = interval {
+                            ui.add(
+                                egui::Slider::new(secs, 30..=3600)
+                                    .text("Interval")
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text(
+                                "How long after the first unsaved change the project is saved",
+                            );
+                        }
+                    }
+                    Tab::ImportExport => self.import_export_ui(ui, update_state),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    Tab::EditorSettings => {
+                        ui.label("RTP Paths");
+                        ui.separator();
+
+                        ui.columns(2, |columns| {
+                            let mut new_rtp_paths: indexmap::IndexMap<_, _> = update_state
+                                .global_config
+                                .rtp_paths
+                                .drain(..)
+                                .filter_map(|(mut rtp_name, mut rtp_path)| {
+                                    let res = columns[0].horizontal(|ui| {
+                                        let res = ui.button(
+                                            egui::RichText::new("-")
+                                                .monospace()
+                                                .color(egui::Color32::RED),
+                                        );
+                                        ui.text_edit_singleline(&mut rtp_name);
+                                        res.clicked()
+                                    });
+                                    columns[1].text_edit_singleline(&mut rtp_path);
+                                    (!res.inner).then_some((rtp_name, rtp_path))
+                                })
+                                .collect();
+
+                            columns[0].horizontal(|ui| {
+                                if ui
+                                    .button(
+                                        egui::RichText::new("+")
+                                            .monospace()
+                                            .color(egui::Color32::GREEN),
+                                    )
+                                    .clicked()
+                                {
+                                    new_rtp_paths.insert(
+                                        std::mem::take(&mut self.edit_rtp_path_name),
+                                        std::mem::take(&mut self.edit_rtp_path_path),
+                                    );
+                                }
+                                ui.text_edit_singleline(&mut self.edit_rtp_path_name);
+                            });
+                            columns[1].text_edit_singleline(&mut self.edit_rtp_path_path);
+
+                            update_state.global_config.rtp_paths = new_rtp_paths;
+                        });
+
+                        ui.add_space(ui.spacing().item_spacing.y * 4.);
+                        ui.label("External Programs")
+                            .on_hover_text("Programs to open files with from the filesystem view, by file extension (e.g. png)");
+                        ui.separator();
+
+                        ui.columns(2, |columns| {
+                            let mut new_external_programs: indexmap::IndexMap<_, _> = update_state
+                                .global_config
+                                .external_programs
+                                .drain(..)
+                                .filter_map(|(mut extension, mut program)| {
+                                    let res = columns[0].horizontal(|ui| {
+                                        let res = ui.button(
+                                            egui::RichText::new("-")
+                                                .monospace()
+                                                .color(egui::Color32::RED),
+                                        );
+                                        ui.text_edit_singleline(&mut extension);
+                                        res.clicked()
+                                    });
+                                    columns[1].text_edit_singleline(&mut program);
+                                    (!res.inner).then_some((extension, program))
+                                })
+                                .collect();
+
+                            columns[0].horizontal(|ui| {
+                                if ui
+                                    .button(
+                                        egui::RichText::new("+")
+                                            .monospace()
+                                            .color(egui::Color32::GREEN),
+                                    )
+                                    .clicked()
+                                {
+                                    let extension = self
+                                        .edit_external_program_extension
+                                        .trim()
+                                        .trim_start_matches('.')
+                                        .to_lowercase();
+                                    self.edit_external_program_extension.clear();
+                                    new_external_programs.insert(
+                                        extension,
+                                        std::mem::take(&mut self.edit_external_program_path),
+                                    );
+                                }
+                                ui.text_edit_singleline(&mut self.edit_external_program_extension);
+                            });
+                            columns[1].text_edit_singleline(&mut self.edit_external_program_path);
+
+                            update_state.global_config.external_programs = new_external_programs;
                         });
                     }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    Tab::Terminal => {
+                        let config = &mut update_state.global_config.terminal;
+                        ui.horizontal(|ui| {
+                            ui.label("Initial terminal size:");
+                            egui::DragValue::new(&mut config.initial_size.0)
+                                .range(1..=999)
+                                .ui(ui);
+                            ui.label("column(s)");
+                            egui::DragValue::new(&mut config.initial_size.1)
+                                .range(1..=999)
+                                .ui(ui);
+                            ui.label("rows(s)");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Font:");
+                            // proportional fonts would misalign the terminal grid
+                            let families = ui.fonts(|f| f.families());
+                            egui::ComboBox::from_id_source("luminol_term_config_ui_font_family")
+                                .selected_text(config.font.family.to_string())
+                                .show_ui(ui, |ui| {
+                                    for family in families.into_iter().filter(|family| {
+                                        !matches!(family, egui::FontFamily::Proportional)
+                                    }) {
+                                        let text = family.to_string();
+                                        ui.selectable_value(&mut config.font.family, family, text);
+                                    }
+                                });
+                            egui::DragValue::new(&mut config.font.size)
+                                .range(6.0..=72.0)
+                                .speed(0.25)
+                                .suffix(" pt")
+                                .update_while_editing(false)
+                                .ui(ui);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Scrollback:");
+                            egui::DragValue::new(&mut config.scrollback)
+                                .range(0..=100_000)
+                                .update_while_editing(false)
+                                .ui(ui);
+                            ui.label("line(s)");
+                        });
+                        crate::components::Field::new(
+                            "Cursor blinking",
+                            crate::components::EnumComboBox::new(
+                                "luminol_term_config_ui_cursor_blinking",
+                                &mut config.cursor_blinking,
+                            )
+                            .max_width(12.)
+                            .wrap_mode(egui::TextWrapMode::Extend),
+                        )
+                        .ui(ui);
+                        crate::components::Field::new(
+                            "Cursor shape",
+                            crate::components::EnumComboBox::new(
+                                "luminol_term_config_ui_cursor_shape",
+                                &mut config.cursor_shape,
+                            )
+                            .max_width(12.)
+                            .wrap_mode(egui::TextWrapMode::Extend),
+                        )
+                        .ui(ui);
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("luminol_term_config_ui_theme_preset")
+                                .selected_text("Apply preset")
+                                .show_ui(ui, |ui| {
+                                    for preset in luminol_config::terminal::ThemePreset::iter() {
+                                        if ui.button(preset.to_string()).clicked() {
+                                            config.theme.apply_preset(preset);
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
 
-                    if ui
-                        .button(egui::RichText::new("Reset").color(egui::Color32::RED))
-                        .clicked()
-                    {
-                        *config = luminol_config::terminal::Config::default()
+                            if self.terminal_theme_promise.is_some() {
+                                ui.spinner();
+                            } else if ui
+                                .button("Import theme...")
+                                .on_hover_text(
+                                    "Import colors from an X resources (.Xresources) file",
+                                )
+                                .clicked()
+                            {
+                                self.terminal_theme_promise =
+                                    Some(luminol_core::spawn_future(async move {
+                                        let (mut file, _) =
+                                            luminol_filesystem::host::File::from_file_picker(
+                                                "X resources",
+                                                &["Xresources", "xresources", "xrdb", "txt"],
+                                            )
+                                            .await?;
+                                        let mut source = String::new();
+                                        file.read_to_string(&mut source).await?;
+                                        Ok(source)
+                                    }));
+                            }
+                        });
+                        if let Some(p) = self.terminal_theme_promise.take() {
+                            match p.try_take() {
+                                Ok(Ok(source)) => {
+                                    if config.theme.apply_xresources(&source) == 0 {
+                                        luminol_core::error!(
+                                            update_state.toasts,
+                                            color_eyre::eyre::eyre!(
+                                                "No terminal colors were found in the chosen file"
+                                            )
+                                        );
+                                    }
+                                }
+                                Ok(Err(e)) => {
+                                    if !matches!(
+                                        e.root_cause().downcast_ref(),
+                                        Some(luminol_filesystem::Error::CancelledLoading)
+                                    ) {
+                                        luminol_core::error!(
+                                            update_state.toasts,
+                                            e.wrap_err("Error importing terminal theme")
+                                        );
+                                    }
+                                }
+                                Err(p) => self.terminal_theme_promise = Some(p),
+                            }
+                        }
+                        ui.add_space(6.);
+                        ui.label("Ui colors");
+                        ui.separator();
+                        ui.columns(2, |cols| {
+                            let [left, right] = cols else {
+                                unreachable!();
+                            };
+                            left.label("Cursor");
+                            let mut arr = color_to_rgb(config.theme.cursor_color);
+                            left.color_edit_button_srgb(&mut arr);
+                            config.theme.cursor_color = color_from_rgb(arr);
+                            right.label("Background");
+                            let mut arr = color_to_rgb(config.theme.background_color);
+                            right.color_edit_button_srgb(&mut arr);
+                            config.theme.background_color = color_from_rgb(arr);
+                            left.label("Foreground");
+                            let mut arr = color_to_rgb(config.theme.foreground_color);
+                            left.color_edit_button_srgb(&mut arr);
+                            config.theme.foreground_color = color_from_rgb(arr);
+                        });
+                        ui.add_space(6.);
+                        ui.label("Pallette");
+                        for (colors, prefix) in config
+                            .theme
+                            .color_pallette
+                            .chunks_mut(8)
+                            .zip(["", "Bright "])
+                        {
+                            ui.horizontal(|ui| {
+                                for (color, name) in colors.iter_mut().zip(ANSI_COLOR_NAMES) {
+                                    let mut arr = color_to_rgb(*color);
+                                    ui.color_edit_button_srgb(&mut arr)
+                                        .on_hover_text(format!("{prefix}{name}"));
+                                    *color = color_from_rgb(arr);
+                                }
+                            });
+                        }
+
+                        if ui
+                            .button(egui::RichText::new("Reset").color(egui::Color32::RED))
+                            .clicked()
+                        {
+                            *config = luminol_config::terminal::Config::default()
+                        }
                     }
                 }
-            }
-        });
+            });
     }
 }
 
@@ -467,6 +940,83 @@ fn gallery_grid_contents(ui: &mut egui::Ui) {
 }
 
 #[allow(dead_code)]
+/// Shows a sample of each overlay drawn with the given colors.
+fn overlay_preview(ui: &mut egui::Ui, colors: luminol_config::global::OverlayColors) {
+    const SIZE: f32 = 64.;
+    // Roughly the color of a grass tile, so the overlays are seen over something map-like
+    const BACKGROUND: egui::Color32 = egui::Color32::from_rgb(84, 120, 72);
+
+    let swatch = |ui: &mut egui::Ui, label: &str, paint: &dyn Fn(&egui::Painter, egui::Rect)| {
+        ui.vertical(|ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(SIZE), egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 0., BACKGROUND);
+            paint(&painter, rect);
+            ui.label(label);
+        });
+    };
+
+    ui.horizontal_wrapped(|ui| {
+        swatch(ui, "Collision", &|painter, rect| {
+            // A tile that can't be passed from the left or from the top
+            let center = rect.center();
+            for corners in [
+                [rect.left_top(), rect.left_bottom()],
+                [rect.left_top(), rect.right_top()],
+            ] {
+                painter.add(egui::Shape::convex_polygon(
+                    vec![center, corners[0], corners[1]],
+                    colors.collision,
+                    egui::Stroke::NONE,
+                ));
+            }
+        });
+        swatch(ui, "Grid", &|painter, rect| {
+            let center = rect.center();
+            for stroke in [
+                egui::Stroke::new(3., colors.grid_outer),
+                egui::Stroke::new(1., colors.grid_inner),
+            ] {
+                painter.vline(center.x, rect.y_range(), stroke);
+                painter.hline(rect.x_range(), center.y, stroke);
+            }
+        });
+        swatch(ui, "Event", &|painter, rect| {
+            painter.rect_stroke(
+                rect.shrink(12.),
+                5.,
+                egui::Stroke::new(1., colors.event_outline),
+            );
+        });
+        swatch(ui, "Selected event", &|painter, rect| {
+            painter.rect_stroke(
+                rect.shrink(12.),
+                5.,
+                egui::Stroke::new(3., colors.selected_event),
+            );
+        });
+        swatch(ui, "Event being edited", &|painter, rect| {
+            painter.rect_stroke(
+                rect.shrink(12.),
+                5.,
+                egui::Stroke::new(3., colors.editing_event),
+            );
+        });
+        swatch(ui, "Tile selection", &|painter, rect| {
+            painter.rect_stroke(
+                rect.shrink(8.),
+                5.,
+                egui::Stroke::new(1., colors.tile_selection),
+            );
+        });
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const ANSI_COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
 fn color_to_rgb(color: egui::Color32) -> [u8; 3] {
     let [r, g, b, _] = color.to_array();
     [r, g, b]
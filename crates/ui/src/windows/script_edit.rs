@@ -35,6 +35,17 @@ impl Default for Window {
     }
 }
 
+impl Window {
+    /// Makes the script editor open the script at `index` in a tab the next time it's shown.
+    pub fn open_script_later(ctx: &egui::Context, index: usize) {
+        ctx.data_mut(|d| d.insert_temp(pending_script_id(), index));
+    }
+}
+
+fn pending_script_id() -> egui::Id {
+    egui::Id::new("luminol_script_edit_pending_script")
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("Script Edit")
@@ -46,6 +57,14 @@ impl luminol_core::Window for Window {
         open: &mut bool,
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
+        if let Some(index) = ctx.data_mut(|d| d.remove_temp::<usize>(pending_script_id())) {
+            let scripts = update_state.data.scripts();
+            if let Some(script) = scripts.data.get(index) {
+                self.tabs
+                    .add_tab(ScriptTab::new(index, script.script_text.clone()));
+            }
+        }
+
         let name = self
             .tabs
             .focused_name()
@@ -175,6 +194,11 @@ impl luminol_core::Tab for ScriptTab {
                 scripts.data[self.index]
                     .script_text
                     .clone_from(&self.script_text);
+                drop(scripts);
+
+                update_state.record_edit(luminol_config::recent_edits::EditLocation::Script {
+                    index: self.index,
+                });
             }
         });
 
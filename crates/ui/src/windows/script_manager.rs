@@ -282,11 +282,14 @@ impl luminol_core::Window for Window {
 
                     Mode::Create { view, .. } => {
                         let name = host.root_path().to_string();
-                        *view = Some(FileSystemView::new(
-                            "luminol_script_manager_create_view".into(),
-                            host,
-                            name,
-                        ));
+                        *view = Some(
+                            FileSystemView::new(
+                                "luminol_script_manager_create_view".into(),
+                                host.clone(),
+                                name,
+                            )
+                            .with_host(host),
+                        );
                     }
 
                     Mode::Convert { scripts, .. } => {
@@ -302,6 +305,7 @@ impl luminol_core::Window for Window {
 
         let mut window_open = true;
         egui::Window::new("Script Manager")
+            .id(self.id())
             .open(&mut window_open)
             .default_width(500.)
             .show(ctx, |ui| {
@@ -621,11 +625,14 @@ impl Window {
                     match p.try_take() {
                         Ok(Ok(handle)) => {
                             let name = handle.root_path().to_string();
-                            *view = Some(FileSystemView::new(
-                                "luminol_script_manager_create_view".into(),
-                                handle,
-                                name,
-                            ));
+                            *view = Some(
+                                FileSystemView::new(
+                                    "luminol_script_manager_create_view".into(),
+                                    handle.clone(),
+                                    name,
+                                )
+                                .with_host(handle),
+                            );
                         }
                         Ok(Err(e)) => {
                             if !matches!(
@@ -23,9 +23,10 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox,
-    UiExt,
+    BattlerData, DamageCalculator, DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection,
+    IdVecSelection, OptionalIdComboBox, UiExt,
 };
+use luminol_config::recent_edits::{Database, EditLocation};
 use luminol_core::Modal;
 
 use crate::modals::sound_picker::Modal as SoundPicker;
@@ -37,6 +38,8 @@ pub struct Window {
 
     previous_skill: Option<usize>,
 
+    calculator: DamageCalculator,
+
     view: DatabaseView,
 }
 
@@ -46,6 +49,7 @@ impl Default for Window {
             selected_skill_name: None,
             menu_se_picker: SoundPicker::new(luminol_audio::Source::SE, "skill_menu_se_picker"),
             previous_skill: None,
+            calculator: DamageCalculator::new(),
             view: DatabaseView::default(),
         }
     }
@@ -78,6 +82,18 @@ impl luminol_core::Window for Window {
         let common_events = data.common_events();
         let system = data.system();
         let states = data.states();
+        let actors = data.actors();
+        let classes = data.classes();
+        let enemies = data.enemies();
+        let weapons = data.weapons();
+        let armors = data.armors();
+        let battler_data = BattlerData {
+            actors: &actors.data,
+            classes: &classes.data,
+            enemies: &enemies.data,
+            weapons: &weapons.data,
+            armors: &armors.data,
+        };
 
         let mut modified = false;
 
@@ -368,6 +384,19 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.with_padded_stripe(true, |ui| {
+                            egui::CollapsingHeader::new("Damage calculator")
+                                .id_source(("skill_damage_calculator", skill.id))
+                                .show(ui, |ui| {
+                                    self.calculator.skill_ui(
+                                        ui,
+                                        "skill_damage_calculator",
+                                        &battler_data,
+                                        skill,
+                                    );
+                                });
+                        });
+
                         self.previous_skill = Some(skill.id);
                     },
                 )
@@ -380,6 +409,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             skills.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Skills,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(skills);
@@ -387,6 +420,11 @@ impl luminol_core::Window for Window {
         drop(common_events);
         drop(system);
         drop(states);
+        drop(actors);
+        drop(classes);
+        drop(enemies);
+        drop(weapons);
+        drop(armors);
 
         *update_state.data = data; // restore data
     }
@@ -22,6 +22,9 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use color_eyre::eyre::WrapErr;
+use luminol_config::playlists::{Playlist, PlaylistEntry, Playlists};
+use luminol_filesystem::FileSystem;
 use strum::IntoEnumIterator;
 
 /// A tab for a sound (be it BGM, ME, SE, etc)
@@ -31,8 +34,28 @@ use strum::IntoEnumIterator;
 pub struct Window {
     sources: Vec<crate::components::SoundTab>,
     selected_source: luminol_audio::Source,
+    /// Whether the playlists are shown instead of one of the sources
+    show_playlists: bool,
+
+    playlists: Playlists,
+    selected_playlist: usize,
+    new_playlist_name: String,
+    player: Option<Player>,
+}
+
+/// The playlist entry that is playing.
+struct Player {
+    playlist: usize,
+    entry: usize,
+    /// The egui time when the entry started playing
+    started_at: f64,
+    /// How many seconds the entry plays for at its pitch, once that's known
+    duration: poll_promise::Promise<Option<f64>>,
 }
 
+/// Playlists are always played on this source, so that one entry can fade into the next.
+const PLAYLIST_SOURCE: luminol_audio::Source = luminol_audio::Source::BGM;
+
 impl Window {
     pub fn new(filesystem: &impl luminol_filesystem::FileSystem) -> Self {
         Self {
@@ -42,8 +65,406 @@ impl Window {
                 .collect(),
             // By default, bgm is selected.
             selected_source: luminol_audio::Source::BGM,
+            show_playlists: false,
+
+            playlists: filesystem
+                .read_to_string(Playlists::PATH)
+                .ok()
+                .and_then(|s| ron::from_str(&s).ok())
+                .unwrap_or_default(),
+            selected_playlist: 0,
+            new_playlist_name: String::new(),
+            player: None,
         }
     }
+
+    fn save_playlists(&self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let c = "While saving sound test playlists";
+        let pretty_config = ron::ser::PrettyConfig::new().struct_names(true);
+        let result = ron::ser::to_string_pretty(&self.playlists, pretty_config)
+            .wrap_err(c)
+            .and_then(|playlists| {
+                update_state
+                    .filesystem
+                    .write(Playlists::PATH, playlists)
+                    .wrap_err(c)
+            });
+        if let Err(e) = result {
+            luminol_core::error!(update_state.toasts, e);
+        }
+    }
+
+    /// Starts playing an entry of a playlist, fading it in over its crossfade time if `crossfade`
+    /// is true.
+    fn play_entry(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        playlist: usize,
+        entry: usize,
+        crossfade: bool,
+        time: f64,
+    ) {
+        let Some(entry_data) = self
+            .playlists
+            .playlists
+            .get(playlist)
+            .and_then(|p| p.entries.get(entry))
+        else {
+            self.player = None;
+            return;
+        };
+
+        let path = camino::Utf8Path::new("Audio")
+            .join(&entry_data.folder)
+            .join(&entry_data.name);
+        let crossfade = if crossfade {
+            std::time::Duration::from_secs_f32(entry_data.crossfade.max(0.))
+        } else {
+            std::time::Duration::ZERO
+        };
+        if let Err(e) = update_state.audio.play_track(
+            &path,
+            update_state.filesystem,
+            entry_data.volume,
+            entry_data.pitch,
+            PLAYLIST_SOURCE,
+            update_state
+                .project_config
+                .as_ref()
+                .expect("project not loaded")
+                .project
+                .volume_scale,
+            crossfade,
+        ) {
+            luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err("Error playing playlist entry")
+            );
+            self.player = None;
+            return;
+        }
+
+        // The duration is only needed to start the next entry early enough to crossfade into it
+        let speed = entry_data.pitch.max(1) as f64 / 100.;
+        let duration = match update_state
+            .filesystem
+            .open_file(&path, luminol_filesystem::OpenFlags::Read)
+        {
+            Ok(file) => luminol_core::spawn_future(async move {
+                luminol_audio::duration(file)
+                    .ok()
+                    .map(|duration| duration.as_secs_f64() / speed)
+            }),
+            Err(_) => poll_promise::Promise::from_ready(None),
+        };
+        self.player = Some(Player {
+            playlist,
+            entry,
+            started_at: time,
+            duration,
+        });
+    }
+
+    /// Moves on to the next playlist entry once the current one is over, or early enough to
+    /// crossfade into the next one.
+    fn update_player(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let Some(player) = &self.player else {
+            return;
+        };
+        let time = ctx.input(|i| i.time);
+        let (playlist, next) = (player.playlist, player.entry + 1);
+        let next_crossfade = self
+            .playlists
+            .playlists
+            .get(playlist)
+            .and_then(|p| p.entries.get(next))
+            .map(|entry| entry.crossfade as f64);
+
+        let is_playing = update_state.audio.is_playing(PLAYLIST_SOURCE);
+        let crossfade_due = match (player.duration.ready(), next_crossfade) {
+            (Some(Some(duration)), Some(crossfade)) if crossfade > 0. => {
+                time - player.started_at >= duration - crossfade
+            }
+            _ => false,
+        };
+
+        if next_crossfade.is_some() && (!is_playing || crossfade_due) {
+            self.play_entry(update_state, playlist, next, true, time);
+        } else if !is_playing {
+            self.player = None;
+        }
+
+        if self.player.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+
+    fn playlists_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let time = ui.input(|i| i.time);
+        let mut modified = false;
+
+        ui.horizontal(|ui| {
+            let selected_name = self
+                .playlists
+                .playlists
+                .get(self.selected_playlist)
+                .map_or("(none)", |p| p.name.as_str());
+            egui::ComboBox::from_id_source("luminol_sound_test_playlist")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for (i, playlist) in self.playlists.playlists.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected_playlist, i, &playlist.name);
+                    }
+                });
+
+            if ui
+                .add_enabled(
+                    self.selected_playlist < self.playlists.playlists.len(),
+                    egui::Button::new("Delete"),
+                )
+                .clicked()
+            {
+                self.playlists.playlists.remove(self.selected_playlist);
+                if self.player.as_ref().map(|p| p.playlist) == Some(self.selected_playlist) {
+                    update_state.audio.stop(PLAYLIST_SOURCE);
+                }
+                self.player = None;
+                self.selected_playlist = self.selected_playlist.saturating_sub(1);
+                modified = true;
+            }
+
+            ui.separator();
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_playlist_name)
+                    .hint_text("New playlist name")
+                    .desired_width(120.),
+            );
+            if ui.button("New").clicked() {
+                let name = if self.new_playlist_name.trim().is_empty() {
+                    format!("Playlist {}", self.playlists.playlists.len() + 1)
+                } else {
+                    std::mem::take(&mut self.new_playlist_name)
+                };
+                self.playlists.playlists.push(Playlist {
+                    name,
+                    entries: Vec::new(),
+                });
+                self.selected_playlist = self.playlists.playlists.len() - 1;
+                modified = true;
+            }
+        });
+
+        ui.separator();
+
+        let Some(playlist) = self.playlists.playlists.get_mut(self.selected_playlist) else {
+            ui.weak("Create a playlist, then add sounds to it from the other tabs.");
+            return;
+        };
+        let playing_entry = self
+            .player
+            .as_ref()
+            .filter(|p| p.playlist == self.selected_playlist)
+            .map(|p| p.entry);
+
+        // Transport controls
+        let mut play = None;
+        let mut stop = false;
+        ui.horizontal(|ui| {
+            let can_go_back = playing_entry.is_some_and(|e| e > 0);
+            if ui
+                .add_enabled(can_go_back, egui::Button::new("⏮"))
+                .on_hover_text("Previous")
+                .clicked()
+            {
+                play = playing_entry.map(|e| (e - 1, true));
+            }
+            if playing_entry.is_some() {
+                stop = ui.button("⏹").on_hover_text("Stop").clicked();
+            } else if ui
+                .add_enabled(!playlist.entries.is_empty(), egui::Button::new("▶"))
+                .on_hover_text("Play")
+                .clicked()
+            {
+                play = Some((0, false));
+            }
+            let can_skip = playing_entry.is_some_and(|e| e + 1 < playlist.entries.len());
+            if ui
+                .add_enabled(can_skip, egui::Button::new("⏭"))
+                .on_hover_text("Next")
+                .clicked()
+            {
+                play = playing_entry.map(|e| (e + 1, true));
+            }
+
+            match playing_entry.and_then(|e| playlist.entries.get(e).map(|entry| (e, entry))) {
+                Some((e, entry)) => ui.label(format!(
+                    "Now playing: {}. {}/{}",
+                    e + 1,
+                    entry.folder,
+                    entry.name
+                )),
+                None => ui.weak("Not playing"),
+            };
+        });
+
+        ui.separator();
+
+        // Entries
+        let mut remove = None;
+        let mut swap = None;
+        let entry_count = playlist.entries.len();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("luminol_sound_test_playlist_entries")
+                .num_columns(6)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Sound");
+                    ui.label("Volume");
+                    ui.label("Pitch");
+                    ui.label("Crossfade");
+                    ui.label("");
+                    ui.end_row();
+
+                    for (i, entry) in playlist.entries.iter_mut().enumerate() {
+                        let number = egui::RichText::new(format!("{}.", i + 1));
+                        if playing_entry == Some(i) {
+                            ui.label(number.strong().color(ui.visuals().selection.stroke.color));
+                        } else {
+                            ui.label(number);
+                        }
+                        if ui
+                            .selectable_label(
+                                playing_entry == Some(i),
+                                format!("{}/{}", entry.folder, entry.name),
+                            )
+                            .on_hover_text("Play from here")
+                            .clicked()
+                        {
+                            play = Some((i, playing_entry.is_some()));
+                        }
+
+                        let mut edited = |response: egui::Response| {
+                            if response.drag_stopped() || response.lost_focus() {
+                                modified = true;
+                            }
+                        };
+                        edited(ui.add(egui::DragValue::new(&mut entry.volume).range(0..=100)));
+                        edited(ui.add(egui::DragValue::new(&mut entry.pitch).range(50..=150)));
+                        edited(
+                            ui.add(
+                                egui::DragValue::new(&mut entry.crossfade)
+                                    .range(0. ..=30.)
+                                    .speed(0.1)
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text(
+                                "How long this sound fades in for while the one before it fades out",
+                            ),
+                        );
+
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(i > 0, egui::Button::new("⏶")).clicked() {
+                                swap = Some((i - 1, i));
+                            }
+                            if ui
+                                .add_enabled(i + 1 < entry_count, egui::Button::new("⏷"))
+                                .clicked()
+                            {
+                                swap = Some((i, i + 1));
+                            }
+                            if ui.button("🗑").on_hover_text("Remove").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some((a, b)) = swap {
+            playlist.entries.swap(a, b);
+            if let Some(player) = self.player.as_mut().filter(|_| playing_entry.is_some()) {
+                if player.entry == a {
+                    player.entry = b;
+                } else if player.entry == b {
+                    player.entry = a;
+                }
+            }
+            modified = true;
+        }
+        if let Some(i) = remove {
+            playlist.entries.remove(i);
+            if let Some(playing) = playing_entry {
+                if playing == i {
+                    update_state.audio.stop(PLAYLIST_SOURCE);
+                    self.player = None;
+                } else if let Some(player) = self.player.as_mut().filter(|_| playing > i) {
+                    player.entry -= 1;
+                }
+            }
+            modified = true;
+        }
+
+        if stop {
+            update_state.audio.stop(PLAYLIST_SOURCE);
+            self.player = None;
+        }
+        if let Some((entry, crossfade)) = play {
+            self.play_entry(update_state, self.selected_playlist, entry, crossfade, time);
+        }
+        if modified {
+            self.save_playlists(update_state);
+        }
+    }
+
+    /// Adds the sound selected in the current source tab to the selected playlist, creating a
+    /// playlist if there are none.
+    fn add_to_playlist(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        let Some(tab) = self
+            .sources
+            .iter()
+            .find(|t| t.source == self.selected_source)
+        else {
+            return;
+        };
+        let Some(name) = &tab.audio_file.name else {
+            return;
+        };
+        let entry = PlaylistEntry {
+            folder: tab.source.to_string(),
+            name: name.to_string(),
+            volume: tab.audio_file.volume,
+            pitch: tab.audio_file.pitch,
+            crossfade: 0.,
+        };
+
+        if self.playlists.playlists.is_empty() {
+            self.playlists.playlists.push(Playlist {
+                name: "Playlist 1".to_string(),
+                entries: Vec::new(),
+            });
+            self.selected_playlist = 0;
+        }
+        let Some(playlist) = self.playlists.playlists.get_mut(self.selected_playlist) else {
+            return;
+        };
+        luminol_core::info!(
+            update_state.toasts,
+            format!("Added {}/{} to {}", entry.folder, entry.name, playlist.name)
+        );
+        playlist.entries.push(entry);
+        self.save_playlists(update_state);
+    }
 }
 
 impl luminol_core::Window for Window {
@@ -57,31 +478,71 @@ impl luminol_core::Window for Window {
         open: &mut bool,
         update_state: &mut luminol_core::UpdateState<'_>,
     ) {
-        egui::Window::new("Sound Test").open(open).show(ctx, |ui| {
-            egui::TopBottomPanel::top("sound_test_selector").show_inside(ui, |ui| {
-                // Display the tab selector.
-                ui.horizontal_wrapped(|ui| {
-                    for source in &self.sources {
+        self.update_player(ctx, update_state);
+
+        egui::Window::new("Sound Test")
+            .id(self.id())
+            .open(open)
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::top("sound_test_selector").show_inside(ui, |ui| {
+                    // Display the tab selector.
+                    ui.horizontal_wrapped(|ui| {
+                        for source in &self.sources {
+                            if ui
+                                .selectable_label(
+                                    !self.show_playlists && source.source == self.selected_source,
+                                    source.source.to_string(),
+                                )
+                                .clicked()
+                            {
+                                self.selected_source = source.source;
+                                self.show_playlists = false;
+                            }
+                        }
+                        ui.separator();
                         if ui
-                            .selectable_label(
-                                source.source == self.selected_source,
-                                source.source.to_string(),
-                            )
+                            .selectable_label(self.show_playlists, "Playlists")
                             .clicked()
                         {
-                            self.selected_source = source.source;
+                            self.show_playlists = true;
                         }
+                    })
+                });
+
+                if self.show_playlists {
+                    egui::CentralPanel::default().show_inside(ui, |ui| {
+                        self.playlists_ui(ui, update_state);
+                    });
+                    return;
+                }
+
+                egui::TopBottomPanel::bottom("sound_test_playlist_add").show_inside(ui, |ui| {
+                    let has_sound = self
+                        .sources
+                        .iter()
+                        .find(|t| t.source == self.selected_source)
+                        .is_some_and(|t| t.audio_file.name.is_some());
+                    let playlist_name = self
+                        .playlists
+                        .playlists
+                        .get(self.selected_playlist)
+                        .map_or("a new playlist", |p| p.name.as_str());
+                    if ui
+                        .add_enabled(has_sound, egui::Button::new("Add to playlist"))
+                        .on_hover_text(format!("Adds the selected sound to {playlist_name}"))
+                        .clicked()
+                    {
+                        self.add_to_playlist(update_state);
                     }
-                })
-            });
+                });
 
-            // We should be finding something. The unwrap is safe here.
-            self.sources
-                .iter_mut()
-                .find(|t| t.source == self.selected_source)
-                .unwrap()
-                .ui(ui, update_state);
-        });
+                // We should be finding something. The unwrap is safe here.
+                self.sources
+                    .iter_mut()
+                    .find(|t| t.source == self.selected_source)
+                    .unwrap()
+                    .ui(ui, update_state);
+            });
     }
 
     // Technically we don't need the cache, but we do rely on the project being open.
@@ -25,6 +25,7 @@
 use crate::components::{
     EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox, UiExt,
 };
+use luminol_config::recent_edits::{Database, EditLocation};
 
 #[derive(Default)]
 pub struct Window {
@@ -379,6 +380,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             states.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::States,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(states);
@@ -0,0 +1,154 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::{
+    components::{Field, UiExt, WindowskinPreview},
+    modals::backdrop_picker::{Kind as BackdropKind, Modal as BackdropPicker},
+    modals::windowskin_picker::Modal as WindowskinPicker,
+};
+use luminol_core::Modal;
+
+/// Database - System management window.
+pub struct Window {
+    windowskin_picker: WindowskinPicker,
+    windowskin_preview: WindowskinPreview,
+    title_picker: BackdropPicker,
+    gameover_picker: BackdropPicker,
+    transition_picker: BackdropPicker,
+    battleback_picker: BackdropPicker,
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self {
+            windowskin_picker: WindowskinPicker::new("system_windowskin_picker"),
+            windowskin_preview: WindowskinPreview::new(),
+            title_picker: BackdropPicker::new("system_title_picker", BackdropKind::Title),
+            gameover_picker: BackdropPicker::new("system_gameover_picker", BackdropKind::Gameover),
+            transition_picker: BackdropPicker::new(
+                "system_transition_picker",
+                BackdropKind::Transition,
+            ),
+            battleback_picker: BackdropPicker::new(
+                "system_battleback_picker",
+                BackdropKind::Battleback,
+            ),
+        }
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("system_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
+        let mut system = data.system();
+
+        let mut modified = false;
+
+        egui::Window::new("System")
+            .id(self.id())
+            .default_width(400.)
+            .open(open)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.with_padded_stripe(false, |ui| {
+                        modified |= ui
+                            .add(Field::new(
+                                "Windowskin",
+                                self.windowskin_picker
+                                    .button(&mut system.windowskin_name, update_state),
+                            ))
+                            .changed();
+
+                        self.windowskin_preview.ui(
+                            ui,
+                            update_state,
+                            system.windowskin_name.as_deref(),
+                        );
+                    });
+
+                    ui.with_padded_stripe(true, |ui| {
+                        modified |= ui
+                            .add(Field::new(
+                                "Title Graphic",
+                                self.title_picker
+                                    .button(&mut system.title_name, update_state),
+                            ))
+                            .changed();
+                        modified |= ui
+                            .add(Field::new(
+                                "Gameover Graphic",
+                                self.gameover_picker
+                                    .button(&mut system.gameover_name, update_state),
+                            ))
+                            .changed();
+                    });
+
+                    ui.with_padded_stripe(false, |ui| {
+                        modified |= ui
+                            .add(Field::new(
+                                "Battle Transition",
+                                self.transition_picker
+                                    .button(&mut system.battle_transition, update_state),
+                            ))
+                            .changed();
+                        modified |= ui
+                            .add(Field::new(
+                                "Battleback (Battle Test)",
+                                self.battleback_picker
+                                    .button(&mut system.battleback_name, update_state),
+                            ))
+                            .changed();
+                    });
+                });
+            });
+
+        if modified {
+            update_state.modified.set(true);
+            system.modified = true;
+        }
+
+        drop(system);
+
+        *update_state.data = data; // restore data
+    }
+}
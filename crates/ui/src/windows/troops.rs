@@ -0,0 +1,436 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::{
+    CommandView, DatabaseView, EnumComboBox, Field, FieldWithCheckbox, OptionalIdComboBox, UiExt,
+};
+use luminol_config::recent_edits::{Database, EditLocation};
+
+#[derive(Default)]
+pub struct Window {
+    selected_troop_name: Option<String>,
+    selected_page: usize,
+    previous_troop: Option<usize>,
+
+    command_view: CommandView,
+    view: DatabaseView,
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn show_page_tabs(
+        ui: &mut egui::Ui,
+        pages: &mut Vec<luminol_data::rpg::troop::Page>,
+        selected_page: &mut usize,
+    ) -> egui::Response {
+        let mut modified = false;
+
+        let mut response = ui
+            .horizontal_wrapped(|ui| {
+                for i in 0..pages.len() {
+                    ui.selectable_value(selected_page, i, format!("{}", i + 1));
+                }
+
+                if ui
+                    .button(egui::RichText::new("Add").color(egui::Color32::LIGHT_GREEN))
+                    .clicked()
+                {
+                    pages.push(Default::default());
+                    *selected_page = pages.len() - 1;
+                    modified = true;
+                }
+
+                let button = egui::Button::new(
+                    egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED),
+                );
+                if ui.add_enabled(pages.len() > 1, button).clicked() {
+                    pages.remove(*selected_page);
+                    *selected_page = (*selected_page).min(pages.len() - 1);
+                    modified = true;
+                }
+            })
+            .response;
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+
+    fn show_condition(
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        system: &luminol_data::rpg::System,
+        actors: &luminol_data::rpg::Actors,
+        enemies: &luminol_data::rpg::Enemies,
+        troop: (usize, &[luminol_data::rpg::troop::Member]),
+        condition: &mut luminol_data::rpg::troop::Condition,
+    ) -> egui::Response {
+        let (troop_id, members) = troop;
+        let mut modified = false;
+
+        let mut response = egui::Frame::none()
+            .show(ui, |ui| {
+                ui.columns(2, |columns| {
+                    modified |= columns[0]
+                        .add(FieldWithCheckbox::new(
+                            "Turn",
+                            &mut condition.turn_valid,
+                            |ui: &mut egui::Ui| {
+                                let mut modified = false;
+                                let mut response = ui
+                                    .horizontal(|ui| {
+                                        modified |= ui
+                                            .add(
+                                                egui::DragValue::new(&mut condition.turn_a)
+                                                    .range(0..=i32::MAX),
+                                            )
+                                            .changed();
+                                        ui.label("+");
+                                        modified |= ui
+                                            .add(
+                                                egui::DragValue::new(&mut condition.turn_b)
+                                                    .range(0..=i32::MAX),
+                                            )
+                                            .changed();
+                                        ui.label("x");
+                                    })
+                                    .response;
+                                if modified {
+                                    response.mark_changed();
+                                }
+                                response
+                            },
+                        ))
+                        .changed();
+
+                    modified |= columns[1]
+                        .add(FieldWithCheckbox::new(
+                            "Switch is ON",
+                            &mut condition.switch_valid,
+                            OptionalIdComboBox::new(
+                                update_state,
+                                (troop_id, "condition_switch_id"),
+                                &mut condition.switch_id,
+                                0..system.switches.len(),
+                                |id| {
+                                    system.switches.get(id).map_or_else(
+                                        || "".into(),
+                                        |s| format!("{:0>4}: {}", id + 1, s),
+                                    )
+                                },
+                            )
+                            .allow_none(false),
+                        ))
+                        .changed();
+                });
+
+                ui.columns(2, |columns| {
+                    modified |= columns[0]
+                        .add(FieldWithCheckbox::new(
+                            "Enemy HP",
+                            &mut condition.enemy_valid,
+                            |ui: &mut egui::Ui| {
+                                let mut modified = false;
+                                let mut response = ui
+                                    .vertical(|ui| {
+                                        modified |= ui
+                                            .add(OptionalIdComboBox::new(
+                                                update_state,
+                                                (troop_id, "condition_enemy_index"),
+                                                &mut condition.enemy_index,
+                                                0..members.len(),
+                                                |index| {
+                                                    let name = members
+                                                        .get(index)
+                                                        .and_then(|m| enemies.data.get(m.enemy_id))
+                                                        .map_or("", |e| e.name.as_str());
+                                                    format!("{}. {name}", index + 1)
+                                                },
+                                            ))
+                                            .changed();
+                                        modified |= ui
+                                            .add(
+                                                egui::Slider::new(&mut condition.enemy_hp, 0..=100)
+                                                    .prefix("≤ ")
+                                                    .suffix("%"),
+                                            )
+                                            .changed();
+                                    })
+                                    .response;
+                                if modified {
+                                    response.mark_changed();
+                                }
+                                response
+                            },
+                        ))
+                        .changed();
+
+                    modified |= columns[1]
+                        .add(FieldWithCheckbox::new(
+                            "Actor HP",
+                            &mut condition.actor_valid,
+                            |ui: &mut egui::Ui| {
+                                let mut modified = false;
+                                let mut response = ui
+                                    .vertical(|ui| {
+                                        modified |= ui
+                                            .add(
+                                                OptionalIdComboBox::new(
+                                                    update_state,
+                                                    (troop_id, "condition_actor_id"),
+                                                    &mut condition.actor_id,
+                                                    0..actors.data.len(),
+                                                    |id| {
+                                                        actors.data.get(id).map_or_else(
+                                                            || "".into(),
+                                                            |a| {
+                                                                format!(
+                                                                    "{:0>4}: {}",
+                                                                    id + 1,
+                                                                    a.name
+                                                                )
+                                                            },
+                                                        )
+                                                    },
+                                                )
+                                                .allow_none(false),
+                                            )
+                                            .changed();
+                                        modified |= ui
+                                            .add(
+                                                egui::Slider::new(&mut condition.actor_hp, 0..=100)
+                                                    .prefix("≤ ")
+                                                    .suffix("%"),
+                                            )
+                                            .changed();
+                                    })
+                                    .response;
+                                if modified {
+                                    response.mark_changed();
+                                }
+                                response
+                            },
+                        ))
+                        .changed();
+                });
+            })
+            .response;
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("troop_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
+        let mut troops = data.troops();
+        let system = data.system();
+        let actors = data.actors();
+        let enemies = data.enemies();
+
+        let mut modified = false;
+
+        self.selected_troop_name = None;
+
+        let name = if let Some(name) = &self.selected_troop_name {
+            format!("Editing troop {:?}", name)
+        } else {
+            "Troop Editor".into()
+        };
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Troops",
+                    &mut troops.data,
+                    |troop| format!("{:0>4}: {}", troop.id + 1, troop.name),
+                    |ui, troops, id, update_state| {
+                        let troop = &mut troops[id];
+                        self.selected_troop_name = Some(troop.name.clone());
+
+                        if self.previous_troop != Some(troop.id) {
+                            self.selected_page = 0;
+                            self.command_view = CommandView::new((troop.id, 0usize));
+                        }
+                        let previous_page = self.selected_page;
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut troop.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            modified |= ui
+                                .add(Field::new("Pages", |ui: &mut egui::Ui| {
+                                    Self::show_page_tabs(
+                                        ui,
+                                        &mut troop.pages,
+                                        &mut self.selected_page,
+                                    )
+                                }))
+                                .changed();
+                        });
+
+                        if self.selected_page != previous_page {
+                            self.command_view = CommandView::new((troop.id, self.selected_page));
+                        }
+
+                        let Some(page) = troop.pages.get_mut(self.selected_page) else {
+                            self.previous_troop = Some(troop.id);
+                            return;
+                        };
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(Field::new("Conditions", |ui: &mut egui::Ui| {
+                                    Self::show_condition(
+                                        ui,
+                                        update_state,
+                                        &system,
+                                        &actors,
+                                        &enemies,
+                                        (troop.id, troop.members.as_slice()),
+                                        &mut page.condition,
+                                    )
+                                }))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            modified |= ui
+                                .add(Field::new(
+                                    "Span",
+                                    EnumComboBox::new((troop.id, "span"), &mut page.span),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Commands:");
+                                self.command_view.outline_ui(ui, &page.list);
+                            });
+                            ui.group(|ui| {
+                                egui::ScrollArea::both()
+                                    .id_source((troop.id, "commands"))
+                                    .max_height(300.)
+                                    .auto_shrink([false, true])
+                                    .show(ui, |ui| {
+                                        modified |= self.command_view.ui(
+                                            ui,
+                                            &update_state
+                                                .project_config
+                                                .as_ref()
+                                                .unwrap()
+                                                .command_db,
+                                            &system,
+                                            &mut page.list,
+                                        );
+                                    });
+                            });
+                        });
+
+                        self.previous_troop = Some(troop.id);
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        drop(troops);
+        drop(system);
+        drop(actors);
+        drop(enemies);
+
+        *update_state.data = data; // restore data
+
+        // Command editors look things up in the database, so the page's commands are edited as
+        // a copy once the database is back in place
+        let id = self.view.selected_id();
+        let list = self
+            .command_view
+            .is_editing()
+            .then(|| {
+                update_state
+                    .data
+                    .troops()
+                    .data
+                    .get(id)
+                    .and_then(|troop| troop.pages.get(self.selected_page))
+                    .map(|page| page.list.clone())
+            })
+            .flatten();
+        if let Some(mut list) = list {
+            if self
+                .command_view
+                .editor_window(ctx, update_state, &mut list)
+            {
+                let mut troops = update_state.data.troops();
+                troops.data[id].pages[self.selected_page].list = list;
+                modified = true;
+            }
+        }
+
+        if modified {
+            update_state.modified.set(true);
+            update_state.data.troops().modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Troops,
+                id,
+            });
+        }
+    }
+}
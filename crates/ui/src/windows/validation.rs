@@ -0,0 +1,83 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::UiExt;
+
+/// Lists the problems found by the project validation pass.
+#[derive(Default)]
+pub struct Window {
+    issues: Option<Vec<luminol_core::validation::Issue>>,
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_validation_window")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        egui::Window::new("Validate Project")
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                if ui.button("Run again").clicked() {
+                    self.issues = None;
+                }
+                let issues = self
+                    .issues
+                    .get_or_insert_with(|| luminol_core::validation::validate(update_state.data));
+
+                ui.separator();
+
+                if issues.is_empty() {
+                    ui.label("No problems found");
+                    return;
+                }
+
+                ui.label(format!("{} problem(s) found", issues.len()));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, issue) in issues.iter().enumerate() {
+                        ui.with_stripe(i % 2 != 0, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(
+                                    egui::RichText::new(&issue.location)
+                                        .color(ui.visuals().warn_fg_color),
+                                );
+                                ui.label(&issue.message);
+                            });
+                        });
+                    }
+                });
+            });
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
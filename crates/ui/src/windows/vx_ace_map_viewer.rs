@@ -0,0 +1,357 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_filesystem::FileSystem;
+
+/// A read-only viewer for the maps of RPG Maker VX Ace projects, which Luminol can't open for
+/// editing yet.
+pub struct Window {
+    load_promise: Option<poll_promise::Promise<color_eyre::Result<Project>>>,
+    project: Option<Project>,
+    map: Option<ViewedMap>,
+    zoom: f32,
+}
+
+struct Project {
+    root: camino::Utf8PathBuf,
+    /// The project folder, followed by the RTP if it was found.
+    filesystem: luminol_filesystem::list::FileSystem,
+    /// The IDs and names of the maps in the order of the map tree, with how deeply each is nested.
+    maps: Vec<(usize, String, usize)>,
+    tilesets: Vec<luminol_data::rmvx_ace::Tileset>,
+}
+
+struct ViewedMap {
+    id: usize,
+    map: luminol_data::rmvx_ace::Map,
+    texture: egui::TextureHandle,
+    /// Graphics of the map's tileset that couldn't be read.
+    missing_sheets: Vec<String>,
+}
+
+const READ_ONLY_TEXT: &str =
+    "Maps are shown read-only, since editing RPG Maker VX Ace projects isn't supported yet.";
+
+fn handler() -> luminol_core::data_formats::Handler {
+    luminol_core::data_formats::Handler::new(luminol_config::DataFormat::Marshal)
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            load_promise: None,
+            project: None,
+            map: None,
+            zoom: 1.,
+        }
+    }
+}
+
+impl Window {
+    fn open_project(
+        rtp_paths: indexmap::IndexMap<String, String>,
+    ) -> poll_promise::Promise<color_eyre::Result<Project>> {
+        luminol_core::spawn_future(async move {
+            let host = luminol_filesystem::host::FileSystem::from_folder_picker().await?;
+            Project::load(host, &rtp_paths)
+        })
+    }
+
+    fn view_map(&mut self, ctx: &egui::Context, id: usize) -> color_eyre::Result<()> {
+        let project = self.project.as_ref().expect("project not loaded");
+        let map: luminol_data::rmvx_ace::Map = handler().read_data_from(
+            &project
+                .filesystem
+                .read(format!("Data/Map{id:0>3}.rvdata2"))?,
+        )?;
+
+        let (sheets, missing_sheets) = match project.tilesets.get(map.tileset_id) {
+            Some(tileset) => luminol_graphics::vx_ace::load_sheets(&project.filesystem, tileset),
+            None => Default::default(),
+        };
+        let image = luminol_graphics::vx_ace::render_map(&map, &sheets);
+        let texture = ctx.load_texture(
+            format!("luminol_vx_ace_map_{id}"),
+            image,
+            egui::TextureOptions::NEAREST,
+        );
+
+        self.map = Some(ViewedMap {
+            id,
+            map,
+            texture,
+            missing_sheets,
+        });
+        Ok(())
+    }
+}
+
+impl Project {
+    fn load(
+        host: luminol_filesystem::host::FileSystem,
+        rtp_paths: &indexmap::IndexMap<String, String>,
+    ) -> color_eyre::Result<Self> {
+        if !host.exists("Data/MapInfos.rvdata2")? {
+            return Err(color_eyre::eyre::eyre!(
+                "This folder isn't an RPG Maker VX Ace project (Data/MapInfos.rvdata2 is missing)"
+            ));
+        }
+
+        let map_infos: std::collections::HashMap<usize, luminol_data::rpg::MapInfo> = handler()
+            .read_data_from(&host.read("Data/MapInfos.rvdata2")?)
+            .wrap_err("While reading MapInfos")?;
+        let tilesets = handler()
+            .read_nil_padded_from(&host.read("Data/Tilesets.rvdata2")?)
+            .wrap_err("While reading Tilesets")?;
+
+        // sort the maps in the order of the map tree, with children right after their parents
+        let mut children: std::collections::HashMap<usize, Vec<usize>> = Default::default();
+        for (&id, info) in &map_infos {
+            children.entry(info.parent_id).or_default().push(id);
+        }
+        for ids in children.values_mut() {
+            ids.sort_by_key(|id| &map_infos[id]);
+        }
+        let mut maps = Vec::with_capacity(map_infos.len());
+        let mut stack: Vec<_> = children
+            .get(&0)
+            .into_iter()
+            .flatten()
+            .rev()
+            .map(|&id| (id, 0))
+            .collect();
+        while let Some((id, depth)) = stack.pop() {
+            maps.push((id, map_infos[&id].name.clone(), depth));
+            stack.extend(
+                children
+                    .get(&id)
+                    .into_iter()
+                    .flatten()
+                    .rev()
+                    .map(|&id| (id, depth + 1)),
+            );
+        }
+
+        let root = host.root_path().to_path_buf();
+        let mut filesystem = luminol_filesystem::list::FileSystem::new();
+        #[cfg(not(target_arch = "wasm32"))]
+        let rtp = Self::find_rtp(&host, rtp_paths);
+        #[cfg(target_arch = "wasm32")]
+        let _ = rtp_paths;
+        filesystem.push(host);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(rtp) = rtp {
+            filesystem.push(rtp);
+        }
+
+        Ok(Self {
+            root,
+            filesystem,
+            maps,
+            tilesets,
+        })
+    }
+
+    /// Finds the RTP named in `Game.ini`, either in the project's `RTP` folder or at the path set
+    /// for it in the preferences.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn find_rtp(
+        host: &luminol_filesystem::host::FileSystem,
+        rtp_paths: &indexmap::IndexMap<String, String>,
+    ) -> Option<luminol_filesystem::host::FileSystem> {
+        let game_ini = host.read("Game.ini").ok()?;
+        let rtp = String::from_utf8_lossy(&game_ini)
+            .lines()
+            .find_map(|line| Some(line.trim().strip_prefix("RTP=")?.trim().to_string()))
+            .filter(|rtp| !rtp.is_empty())?;
+
+        let in_project = host.root_path().join("RTP").join(&rtp);
+        let path = if in_project.exists() {
+            in_project
+        } else {
+            camino::Utf8PathBuf::from(rtp_paths.get(&rtp)?)
+        };
+        path.exists()
+            .then(|| luminol_filesystem::host::FileSystem::new(path))
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_vx_ace_map_viewer")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut view_map = None;
+
+        egui::Window::new("VX Ace Map Viewer")
+            .id(self.id())
+            .default_size([600., 500.])
+            .open(open)
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::top(self.id().with("top")).show_inside(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if self.load_promise.is_some() {
+                            ui.spinner();
+                        } else if ui.button("Open VX Ace project…").clicked() {
+                            self.load_promise = Some(Self::open_project(
+                                update_state.global_config.rtp_paths.clone(),
+                            ));
+                        }
+                        if let Some(project) = &self.project {
+                            ui.label(project.root.as_str());
+                        }
+                    });
+                    ui.weak(READ_ONLY_TEXT);
+                });
+
+                let Some(project) = &self.project else {
+                    return;
+                };
+
+                egui::SidePanel::left(self.id().with("maps"))
+                    .resizable(true)
+                    .default_width(150.)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            for (id, name, depth) in &project.maps {
+                                ui.horizontal(|ui| {
+                                    ui.add_space(*depth as f32 * 12.);
+                                    let selected =
+                                        self.map.as_ref().is_some_and(|map| map.id == *id);
+                                    if ui
+                                        .selectable_label(selected, format!("{id:0>3}: {name}"))
+                                        .clicked()
+                                    {
+                                        view_map = Some(*id);
+                                    }
+                                });
+                            }
+                        });
+                    });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    let Some(viewed) = &self.map else {
+                        ui.weak("Select a map to view it");
+                        return;
+                    };
+
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{} ({}×{})",
+                            viewed.map.display_name, viewed.map.width, viewed.map.height
+                        ));
+                        ui.separator();
+                        ui.label("Zoom");
+                        ui.add(egui::Slider::new(&mut self.zoom, 0.25..=2.).logarithmic(true));
+                    });
+                    if !viewed.missing_sheets.is_empty() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "Some tileset graphics couldn't be found: {}",
+                                viewed.missing_sheets.join(", ")
+                            ),
+                        );
+                    }
+
+                    egui::ScrollArea::both().show(ui, |ui| {
+                        let size = egui::vec2(
+                            (viewed.map.width * luminol_graphics::vx_ace::TILE_SIZE as usize)
+                                as f32,
+                            (viewed.map.height * luminol_graphics::vx_ace::TILE_SIZE as usize)
+                                as f32,
+                        ) * self.zoom;
+                        let response = ui.add(
+                            egui::Image::new(&viewed.texture)
+                                .fit_to_exact_size(size)
+                                .sense(egui::Sense::hover()),
+                        );
+
+                        let tile_size = luminol_graphics::vx_ace::TILE_SIZE as f32 * self.zoom;
+                        let painter = ui.painter_at(response.rect);
+                        let mut hovered_event = None;
+                        for (_, event) in viewed.map.events.iter() {
+                            let rect = egui::Rect::from_min_size(
+                                response.rect.min
+                                    + egui::vec2(event.x as f32, event.y as f32) * tile_size,
+                                egui::Vec2::splat(tile_size),
+                            );
+                            painter.rect_stroke(
+                                rect.shrink(1.),
+                                2.,
+                                egui::Stroke::new(1., egui::Color32::WHITE),
+                            );
+                            if response.hover_pos().is_some_and(|pos| rect.contains(pos)) {
+                                hovered_event = Some(event);
+                            }
+                        }
+                        if let Some(event) = hovered_event {
+                            response.on_hover_text(format!("{:0>3}: {}", event.id, event.name));
+                        }
+                    });
+                });
+            });
+
+        if let Some(p) = self.load_promise.take() {
+            match p.try_take() {
+                Ok(Ok(project)) => {
+                    luminol_core::info!(
+                        update_state.toasts,
+                        format!("Opened {}. {READ_ONLY_TEXT}", project.root)
+                    );
+                    self.project = Some(project);
+                    self.map = None;
+                }
+                Ok(Err(error)) => {
+                    if !matches!(
+                        error.root_cause().downcast_ref(),
+                        Some(luminol_filesystem::Error::CancelledLoading)
+                    ) {
+                        luminol_core::error!(
+                            update_state.toasts,
+                            error.wrap_err("Error opening the VX Ace project")
+                        );
+                    }
+                }
+                Err(p) => self.load_promise = Some(p),
+            }
+        }
+
+        if let Some(id) = view_map {
+            if let Err(error) = self.view_map(ctx, id) {
+                luminol_core::error!(
+                    update_state.toasts,
+                    error.wrap_err(format!("Error reading map {id:0>3}"))
+                );
+            }
+        }
+    }
+}
@@ -25,6 +25,7 @@
 use crate::components::{
     Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox, UiExt,
 };
+use luminol_config::recent_edits::{Database, EditLocation};
 
 #[derive(Default)]
 pub struct Window {
@@ -262,6 +263,10 @@ impl luminol_core::Window for Window {
         if modified {
             update_state.modified.set(true);
             weapons.modified = true;
+            update_state.record_edit(EditLocation::Database {
+                database: Database::Weapons,
+                id: self.view.selected_id(),
+            });
         }
 
         drop(weapons);